@@ -0,0 +1,115 @@
+//! Benchmarks for the library hot paths at the scale of a large collection.
+//!
+//! Run with `cargo bench`. The database is seeded once with [`SONG_COUNT`]
+//! songs and [`PLAYLIST_COUNT`] playlists, then each query is timed against it.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lofiturtle::domain::entities::{Playlist, Song};
+use lofiturtle::domain::repositories::{PlaylistRepository, PlaylistSongRepository, SongRepository};
+use lofiturtle::domain::value_objects::{Duration, FilePath};
+use lofiturtle::infrastructure::pool::create_in_memory_pool;
+use lofiturtle::infrastructure::repositories::{
+    SqlitePlaylistRepository, SqlitePlaylistSongRepository, SqliteSongRepository,
+};
+use lofiturtle::models;
+use tokio::runtime::Runtime;
+
+const SONG_COUNT: usize = 100_000;
+const PLAYLIST_COUNT: usize = 200;
+const SONGS_PER_PLAYLIST: usize = 50;
+const PAGE_SIZE: usize = 500;
+
+struct Library {
+    songs: SqliteSongRepository,
+    playlists: SqlitePlaylistRepository,
+}
+
+fn seed(rt: &Runtime) -> Library {
+    let pool = create_in_memory_pool().expect("in-memory pool");
+    let songs = SqliteSongRepository::new(pool.clone());
+    let playlists = SqlitePlaylistRepository::new(pool.clone());
+    let playlist_songs = SqlitePlaylistSongRepository::new(pool);
+    songs.initialize_schema().unwrap();
+    playlists.initialize_schema().unwrap();
+    playlist_songs.initialize_schema().unwrap();
+
+    rt.block_on(async {
+        let mut saved = Vec::with_capacity(SONG_COUNT);
+        for i in 0..SONG_COUNT {
+            let song = Song::new(
+                FilePath::new(&format!("/music/artist {}/album {}/track {}.mp3", i % 500, i % 5000, i)).unwrap(),
+                format!("Track {}", i),
+                format!("Artist {}", i % 500),
+                format!("Album {}", i % 5000),
+                Duration::from_seconds(180 + (i % 120) as u64),
+            ).unwrap();
+            songs.save(&song).await.unwrap();
+            saved.push(song);
+        }
+
+        for p in 0..PLAYLIST_COUNT {
+            let playlist = Playlist::new(format!("Playlist {}", p), None).unwrap();
+            playlists.save(&playlist).await.unwrap();
+            for position in 0..SONGS_PER_PLAYLIST {
+                let song = &saved[(p * SONGS_PER_PLAYLIST + position) % SONG_COUNT];
+                playlist_songs.add_song_to_playlist(playlist.id(), song.id(), position).await.unwrap();
+            }
+        }
+    });
+
+    Library { songs, playlists }
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let library = seed(&rt);
+
+    let mut group = c.benchmark_group("library");
+    group.sample_size(10);
+
+    group.bench_function("find_all", |b| {
+        b.iter(|| rt.block_on(library.songs.find_all()).unwrap())
+    });
+
+    group.bench_function("find_range", |b| {
+        b.iter(|| rt.block_on(library.songs.find_range("", black_box(SONG_COUNT / 2), PAGE_SIZE)).unwrap())
+    });
+
+    // Searching the library window counts the matches before reading any
+    group.bench_function("count_matching", |b| {
+        b.iter(|| rt.block_on(library.songs.count(black_box("album 42"))).unwrap())
+    });
+
+    group.bench_function("search", |b| {
+        b.iter(|| rt.block_on(library.songs.search(black_box("artist 42"))).unwrap())
+    });
+
+    group.bench_function("playlists_find_all", |b| {
+        b.iter(|| rt.block_on(library.playlists.find_all()).unwrap())
+    });
+
+    // Views other than the library are filtered in memory on every search
+    // keystroke
+    let songs: Vec<models::Song> = rt.block_on(library.songs.find_all())
+        .unwrap()
+        .iter()
+        .map(|song| models::Song::new(
+            song.file_path().as_str().to_string(),
+            song.title().to_string(),
+            song.artist().to_string(),
+            song.album().to_string(),
+            song.duration().seconds(),
+        ))
+        .collect();
+    group.bench_function("filter", |b| {
+        b.iter(|| {
+            let query = black_box("album 42");
+            songs.iter().enumerate().filter(|(_, song)| song.matches(query)).map(|(i, _)| i).collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries);
+criterion_main!(benches);
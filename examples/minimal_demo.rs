@@ -99,15 +99,11 @@ impl MinimalDemo {
             KeyCode::F(1) => self.show_help = !self.show_help,
             KeyCode::F(2) => self.switch_layout(),
             KeyCode::F(3) => self.switch_theme(),
-            KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                }
+            KeyCode::Up if self.selected > 0 => {
+                self.selected -= 1;
             }
-            KeyCode::Down => {
-                if self.selected < self.playlist.len() - 1 {
-                    self.selected += 1;
-                }
+            KeyCode::Down if self.selected + 1 < self.playlist.len() => {
+                self.selected += 1;
             }
             KeyCode::Enter => {
                 self.set_status(format!("Selected: {}", self.playlist[self.selected]));
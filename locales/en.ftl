@@ -0,0 +1,518 @@
+### English messages, which other translations fall back to
+###
+### Titles are padded with spaces in code, so leave them out here.
+
+## Interface
+
+scan-modal-title = 📀 Scanning Music Library...
+scan-modal-progress = Processing file { $processed } of { $total }...
+scan-modal-walking = Looking for music files...
+scan-modal-cancel = Esc: cancel the scan
+scan-modal-cancelling = Cancelling. Songs read so far are kept...
+scan-rate = { $rate } files/s, { $eta } left
+
+header-library = 🐢 Lofi Turtle Library
+header-playlist = 🐢 Playlist View
+header-smart = 🐢 Smart View
+search-filter = 🔍 Filter: { $query }
+search-hint = Press '/' to search
+
+playlists-title = Playlists
+all-music = All Music
+songs-title = Songs
+smart-recently-added = Recently Added
+smart-most-played = Most Played
+smart-never-played = Never Played
+smart-daily-mix = Daily Mix
+playlist-sort-name = Name
+playlist-sort-created = Newest
+playlist-sort-updated = Last updated
+playlist-sort-song-count = Most songs
+playlist-sorted = Playlists sorted by: { $order }
+
+visuals-title = Visuals
+no-art = No Art
+no-chapters = This song has no chapters
+art-disabled =
+    Art Disabled
+    (Press 'a')
+
+now-playing-title = Now Playing
+nothing-playing = Nothing Playing
+scrobbles-pending =
+    { $count ->
+        [one] { $count } scrobble pending
+       *[other] { $count } scrobbles pending
+    }
+status-scanning = Scanning { $processed }/{ $total }
+status-stop-after = ⏹ Stopping after this song
+status-party = 🎉 Party mode
+
+## Screen reader mode
+
+state-playing = Playing
+state-paused = Paused
+state-stopped = Stopped
+state-shuffle-on = Shuffle on
+state-shuffle-off = Shuffle off
+state-shuffle-smart = Smart shuffle on
+state-repeat-off = Repeat off
+state-repeat-one = Repeat one
+state-repeat-all = Repeat all
+state-volume = Volume { $volume }%
+state-loading = Loading
+state-loved = loved
+now-playing-plain = { $title } by { $artist }
+announce-playing = Playing { $title } by { $artist }
+in-playlists =
+    { $count ->
+        [one] In { $count } playlist
+       *[other] In { $count } playlists
+    }
+
+stats-title = 📊 Stats
+vu-meter-title = VU
+recent-title = 🕘 Recently Played
+recent-empty = Nothing played yet
+recent-none = No such track in the recently played list
+today = Today
+tracks =
+    { $count ->
+        [one] { $count } track
+       *[other] { $count } tracks
+    }
+
+playlist-create-title = Create Playlist
+playlist-edit-title = Edit Playlist
+queue-save-title = Save Queue as Playlist
+party-lock-title = Passphrase to Leave Party Mode Later
+party-confirm-title = Type the Passphrase Again
+party-unlock-title = Passphrase to Leave Party Mode
+bookmark-name-title = Bookmark Name
+input-title = Input
+
+delete-title = Delete File
+delete-to-trash = Move to trash "{ $artist } - { $title }"?
+delete-permanently = Permanently delete "{ $artist } - { $title }"?
+delete-explanation = It will also be removed from the library and all playlists.
+delete-confirm = Are you sure?
+delete-confirm-permanent = This cannot be undone. Are you sure?
+delete-keys = y: Confirm | any other key: Cancel
+delete-failed = Could not delete the song: { $error }
+
+listening-stats-title = 📊 Listening Stats
+weekday-monday = Mon
+weekday-wednesday = Wed
+weekday-friday = Fri
+month-short =
+    { $month ->
+        [1] Jan
+        [2] Feb
+        [3] Mar
+        [4] Apr
+        [5] May
+        [6] Jun
+        [7] Jul
+        [8] Aug
+        [9] Sep
+        [10] Oct
+        [11] Nov
+       *[12] Dec
+    }
+heatmap-less = Less
+heatmap-more = More
+heatmap-summary = { $time } on { $days } days since { $month } { $day }, { $year }
+press-any-key = Press any key to close
+
+history-load-failed = Could not load listening history: { $error }
+copied-path = Copied path: { $text }
+copied-title = Copied: { $text }
+loved-on = Loved { $song }
+loved-off = No longer loved: { $song }
+clipboard-unavailable = Clipboard unavailable
+
+undo-done = Undone: { $edit }
+redo-done = Redone: { $edit }
+undo-nothing = Nothing to undo
+redo-nothing = Nothing to redo
+undo-failed = Could not undo: { $error }
+redo-failed = Could not redo: { $error }
+edit-create = create playlist '{ $name }'
+edit-add-song = add a song to '{ $name }'
+edit-add-songs = add { $count } songs to '{ $name }'
+edit-remove-song = remove a song from '{ $name }'
+edit-delete = delete playlist '{ $name }'
+edit-save-queue = save the queue as '{ $name }'
+
+song-info-title = 🎵 Song Info
+song-info-playlists = In playlists
+song-info-bookmarks = Bookmarks
+song-info-no-bookmarks = No bookmarks yet
+song-info-hint = Enter: jump · d: delete · other keys close
+song-playlists-none = Not in any playlist
+song-info-failed = Could not look up the song: { $error }
+bookmark-added = Bookmarked "{ $name }"
+bookmark-removed = Removed bookmark "{ $name }"
+bookmark-failed = Could not update bookmarks: { $error }
+bookmark-stream = Streams cannot be bookmarked
+trim-saved = Trimmed { $start } off the start and { $end } off the end, from the next play
+trim-cleared = The song plays whole from the next play
+trim-failed = Could not save the trim: { $error }
+trim-stream = Streams cannot be trimmed
+song-renamed = Renamed to "{ $title }"
+rename-failed = Could not rename the song: { $error }
+rename-stream = Streams cannot be renamed
+rename-blank-title = The title cannot be blank
+batch-add-title = Add to Playlist
+batch-add-confirm = { $count ->
+        [one] Add the song shown to "{ $name }"?
+       *[other] Add all { $count } songs shown to "{ $name }"?
+    }
+batch-added = { $skipped ->
+        [0] Added { $count } songs to "{ $name }"
+       *[other] Added { $count } songs to "{ $name }", { $skipped } already in it
+    }
+batch-add-failed = Could not add the songs: { $error }
+batch-add-no-playlist = Select a playlist in the Playlists panel first
+batch-add-loading = Wait for the songs to finish loading
+batch-add-nothing = No songs to add
+batch-add-outside = { $count ->
+        [one] The song not in the library is left out
+       *[other] { $count } songs not in the library are left out
+    }
+loved-failed = Could not save the love: { $error }
+logs-title = 📜 Recent log messages
+logs-empty = Nothing has been logged yet
+logs-hint = ↑/↓ PgUp/PgDn: scroll, any other key: close
+
+setup-title = 🐢 Welcome to Lofi Turtle, step { $step } of { $steps }
+setup-directory-title = Where is your music?
+setup-directory-hint = ↑/↓: move, Enter: open, Backspace: up, Tab: use this folder, Esc: skip
+dir-browser-empty = No folders in here
+setup-theme-title = Pick a theme
+setup-theme-hint = ↑/↓: preview, Enter: use this theme and scan, Esc: back
+setup-scan-title = Reading your music
+setup-scan-done = Found { $count } songs. Enjoy!
+setup-scan-none = No music files were found there. Press U later to scan again.
+setup-scan-unreadable = The folder could not be read.
+
+music-dir-title = 📁 Music directory
+music-dir-hint = ↑/↓: move, Enter: open, Backspace: up, Tab: use this folder, Esc: cancel
+music-dir-changed = Music directory is now { $dir }. Scanning it...
+output-title = 🔊 Audio output
+output-hint = ↑/↓: move, Enter: play through it, Esc: cancel
+output-none = No audio output devices found
+output-changed = Playing through { $device }
+output-failed = Cannot play through { $device }: { $error }
+output-missing = The audio output picked earlier is unavailable, playing through the default: { $error }
+play-failed = Could not play { $song }: { $error }
+audio-stopped = Audio playback stopped working: { $error }
+
+## Help overlay
+
+help-title = ⌨ Keybindings
+hint-play = Play/Pause
+hint-switch = Switch
+hint-search = Search
+hint-help = Help
+hint-quit = Quit
+action-toggle-play = Play or pause
+action-stop = Stop
+action-stop-after-current = Stop when the playing song ends
+action-next-track = Next track
+action-previous-track = Previous track
+action-next-chapter = Next chapter
+action-previous-chapter = Start of the chapter, or the one before
+action-replay-recent = Play a recently played track again
+action-volume-up = Volume up
+action-volume-down = Volume down
+action-toggle-shuffle = Shuffle: off, on or smart
+action-cycle-repeat = Cycle repeat mode
+action-move-up = Move up
+action-move-down = Move down
+action-go-top = Go to the top
+action-go-bottom = Go to the bottom
+action-select = Play the selection
+action-next-panel = Next panel
+action-previous-panel = Previous panel
+action-library = Back to the library
+action-jump-to-playing = Find the playing song in the library
+action-toggle-follow = Keep the playing song selected as playback moves on
+action-search = Search
+action-clear-search = Clear the search
+action-create-playlist = Create a playlist
+action-save-queue = Save the queue as a playlist
+action-shuffle-queue = Shuffle the rest of the queue once, or put it back
+action-remove-from-queue = Take the song out of the queue
+action-remove-queue-above = Take the songs above out of the queue
+action-remove-queue-below = Take the songs below out of the queue
+action-clear-queue-after-current = Clear the queue after the playing song
+action-append-to-queue = Add the selected playlist or the song's album to the queue
+action-enqueue-clipboard = Add the files, folders or stream URLs on the clipboard to the queue
+action-edit-playlist = Rename the playlist
+action-delete-playlist = Delete the playlist
+action-sort-playlists = Change the order of the playlists
+action-add-to-playlist = Add the song to the selected playlist
+action-add-results-to-playlist = Add every song shown to the selected playlist
+action-remove-from-playlist = Remove the song from the playlist
+action-undo = Undo the last playlist edit
+action-redo = Redo the playlist edit
+action-song-playlists = Show the song's playlists and bookmarks
+action-add-bookmark = Bookmark the playing position
+action-trim-start = Skip the playing song's start up to here
+action-trim-end = Cut the playing song's end off from here
+action-clear-trim = Play the playing song whole again
+action-toggle-art = Show or hide album art
+action-toggle-visualizer = Switch between spectrum and oscilloscope
+action-toggle-remaining = Show the song's length or the time left
+action-reveal-file = Show the file in the file manager
+action-copy-path = Copy the file path
+action-copy-title = Copy artist and title
+action-rename-song = Rename the selected song
+action-toggle-love = Love or unlove the song
+action-do-not-disturb = Hold back track notifications, or let them through
+action-delete-file = Delete the song file
+action-show-stats = Listening stats
+action-rescan = Scan the music directory again
+action-change-music-dir = Choose another music directory
+action-choose-output = Choose the audio output
+action-show-logs = Recent log messages
+action-switch-theme = Next theme
+action-party-mode = Party mode: guests can only search and add to the queue
+action-help = This help
+action-quit = Quit
+
+## Background scans
+
+scan-library-offline = Music directory is offline. Using the existing library.
+scan-finished = Scan finished. Added { $count } new songs.
+scan-finished-resumed = Scan finished. Added { $count } new songs after resuming past { $resumed } already added.
+scan-cancelled = Scan cancelled. Added { $count } new songs read before it stopped.
+scan-running = A scan is already running
+scan-failed = Library scan failed: { $error }
+scan-report-title = Scan summary
+scan-report-added = Added: { $count }
+scan-report-updated = Updated: { $count }
+scan-report-skipped = Skipped: { $count }
+scan-report-failed = Failed: { $count }
+scan-report-skipped-heading = Skipped directories:
+scan-report-failed-heading = Failed files:
+scan-report-more = ...and { $count } more
+
+layout-load-failed = ⚠️ Failed to load layout config from { $path }: { $error }. Using defaults.
+layout-not-found = ⚠️ Layout config file { $path } not found. Using defaults.
+log-file-failed = ⚠️ Cannot write the log file { $path }: { $error }. Logging only to the log viewer.
+dir-create-failed = ⚠️ Cannot create directory { $error }
+file-migrated = Copied { $from } to { $to }. The old copy is no longer used.
+migration-failed = ⚠️ Could not copy files from the working directory to their new place: { $error }
+layout-dumped = Layout configuration dumped to { $path }
+
+cli-mode-title = 🎵 LofiTurtle Music Player - CLI Mode
+cli-mode-help = Use --help to see available commands
+cli-mode-tui = Run without --cli-mode for the interactive TUI interface
+
+## Errors
+
+error-directory = ❌ Directory Error: { $message }
+error-database = ❌ Database Error: { $message }
+error-library = ❌ Library Error: { $message }
+error-audio = ❌ Audio Error: { $message }
+error-format = ❌ Format Error: { $message }
+error-config = ❌ Configuration Error: { $message }
+error-terminal = ❌ Terminal Error: { $message }
+interface-crashed = The interface crashed: { $message }
+error-other = ❌ Error: { $message }
+tip-music-dir = 💡 Tip: Use --music-dir to specify a different directory
+tip-reset-database = 💡 Tip: Try deleting the database file to reset
+tip-audio = 💡 Tip: Check if your audio drivers are working
+tip-formats = 💡 Tip: Supported formats: MP3, FLAC, AAC, M4A, OGG, WAV
+tip-help = 💡 Tip: Use --help to see available options
+tip-terminal = 💡 Tip: Try running in a different terminal
+
+## Commands
+
+column-title = Title
+column-artist = Artist
+column-album = Album
+column-duration = Duration
+column-name = Name
+column-songs = Songs
+column-description = Description
+
+list-empty = No songs found matching criteria.
+list-found = Found { $count } songs:
+list-group = { $name } ({ $count } songs, { $duration })
+unknown-genre = Unknown Genre
+search-empty = No songs found matching '{ $query }'.
+search-found = Found { $count } songs matching '{ $query }':
+
+scan-start = Scanning music directory: { $dir }
+scan-offline = Music directory { $dir } is offline. Keeping existing library entries.
+scan-resuming = Resuming an interrupted scan, skipping { $count } files it already added.
+scan-skipped = Skipped { $count } unreadable files or directories.
+scan-force = Force scan enabled. Clearing existing database...
+scan-identifying = Identifying untagged files...
+scan-tag-failed = Failed to tag { $path }: { $error }
+scan-identified = Identified and tagged { $count } files.
+scan-found = Found { $count } songs. Updating database...
+scan-done = Scan completed in { $duration }. Added { $count } new songs.
+scan-cancel-hint = Press Ctrl+C to stop. Songs read so far are kept.
+scan-stopping = Stopping the scan...
+scan-stopped = Scan stopped after { $processed } of { $total } files. Added { $count } new songs, nothing was removed.
+scan-dry-run = Dry run: { $added } songs would be added, { $updated } updated and { $removed } removed. Nothing was changed.
+
+playlist-none = No playlists found.
+playlist-found = Found { $count } playlists:
+playlist-created = Created playlist '{ $name }'
+playlist-deleted = Deleted playlist '{ $name }'
+playlist-not-found = Playlist '{ $name }' not found.
+playlist-no-match = No songs found matching '{ $query }'
+playlist-ambiguous = Found multiple songs matching '{ $query }'. Please be more specific.
+playlist-already-added = '{ $title }' is already in playlist '{ $name }'
+playlist-added = Added '{ $title }' to playlist '{ $name }'
+playlist-removed = Removed '{ $title }' from playlist '{ $name }'
+playlist-not-in = Song matching '{ $query }' not found in playlist '{ $name }'
+playlist-name = Playlist: { $name }
+playlist-description = Description: { $description }
+playlist-song-count = Songs: { $count }
+playlist-empty = (Empty playlist)
+playlist-play-hint = To play a playlist, use the interactive mode or: lofiturtle play --playlist '{ $name }'
+playlist-exists = Playlist '{ $name }' already exists.
+playlist-combined = Created playlist '{ $name }' with { $count } songs
+
+queue-empty = The queue is empty.
+queue-appended = Added { $count } songs of '{ $name }' to the queue
+clipboard-queued = Added { $count } songs from the clipboard to the queue
+clipboard-nothing = No audio file, folder or stream URL on the clipboard
+clipboard-reading =
+    { $count ->
+        [one] Reading the pasted item…
+       *[other] Reading { $count } pasted items…
+    }
+clipboard-unreadable =
+    { $count ->
+        [one] One pasted item could not be read; see the log
+       *[other] { $count } pasted items could not be read; see the log
+    }
+clipboard-folder-failed = Could not read the folder '{ $name }'; see the log
+clipboard-folder-empty = No audio files in '{ $name }'
+queue-library = Play a playlist or view first; the library does not play on by itself
+queue-shuffled = Shuffled the rest of the queue
+queue-unshuffled = Put the queue back in order
+queue-removed = Took { $count } songs out of the queue
+queue-playing-stays = The playing song stays in the queue
+queue-saved = Saved the { $count } songs of the queue as playlist '{ $name }'
+
+stop-after-on = Playback will stop after this song
+stop-after-off = Playback will go on after this song
+stop-after-done = Stopped after the song, as asked
+party-on = Party mode on: only searching and adding to the queue work
+party-off = Party mode off
+party-wrong-passphrase = Wrong passphrase; party mode stays on
+party-blank-passphrase = The passphrase cannot be blank
+party-passphrase-mismatch = The passphrases did not match; party mode stays off
+party-locked = Not available in party mode; search and add to the queue instead
+follow-on = Following playback in the song list
+follow-off = Stopped following playback
+do-not-disturb-on = Do not disturb: track notifications held back
+do-not-disturb-off = Track notifications are back on
+notifications-disabled = Track notifications are off; turn them on with "notifications" in the settings
+
+shuffle-enabled = Shuffle mode enabled
+shuffle-disabled = Shuffle mode disabled
+shuffle-smart-enabled = Smart shuffle enabled: no artist twice in a row, songs heard lately last
+repeat-none = Repeat mode: None
+repeat-single = Repeat mode: Single Song
+repeat-playlist = Repeat mode: Playlist
+
+sync-exported = Exported { $count } playlists to { $file }
+sync-imported = Imported { $file }: { $created } created, { $updated } updated, { $unchanged } already up to date
+sync-stats-imported = Merged play counts and ratings of { $count } songs
+sync-missing-songs = { $count } songs were not found in this library and were skipped
+
+history-exported = Exported { $count } listens to { $file }
+
+export-itunes-done = Exported { $songs } songs and { $playlists } playlists to { $file }
+export-mpd-done = Wrote { $playlists } playlists and the stickers of { $songs } songs to { $dir }
+
+genres-load-failed = ⚠️ Genre rules not applied: { $error }
+genres-no-rules = No genre rules in { $path }
+genres-change = { $song }: { $from } → { $to }
+genres-dry-run = The genre of { $count } songs would change. Run again without --dry-run to change it.
+genres-done = Changed the genre of { $count } songs
+
+art-fetch-start = { $count } albums have no art of their own or downloaded
+art-fetch-album = { $album } - { $artist }
+art-fetch-found = found  { $album } - { $artist }
+art-fetch-missing = none  { $album } - { $artist }
+art-fetch-dry-run = Run again without --dry-run to look up their covers.
+art-fetch-done = Downloaded { $fetched } covers; { $missing } albums have none on the Cover Art Archive
+art-embed-file = cover  { $path }
+art-embed-dry-run = { $count } files would get the cover. Run again without --dry-run to write it.
+art-embed-done = Wrote the cover of { $album } into { $count } files
+
+import-unmatched = skip  { $track } (not in the library)
+import-dry-run = Play counts and ratings of { $count } songs would be imported, { $unmatched } tracks are not in the library. Run again without --dry-run to import them.
+import-done = Imported play counts and ratings of { $count } songs; { $unmatched } tracks are not in the library
+
+lastfm-login-open = Open { $url } in a browser, allow LofiTurtle, then press Enter
+lastfm-login-done = Logged in as { $user }. The session key is kept in { $path } to sync loved tracks
+loved-mark = love  { $song }
+loved-push = push  { $song }
+loved-push-failed = fail  { $song } ({ $error })
+loved-sync-dry-run = { $marked } songs would be marked as loved and { $pushed } loved on Last.fm. Run again without --dry-run to sync them.
+loved-sync-done = Synced with { $user } on Last.fm: { $marked } songs marked as loved, { $pushed } loved on Last.fm
+wrapped-report-written = Report written to { $file }
+
+config-check-ok = { $path }: no problems found
+config-check-missing = { $path }: not found, the defaults are used
+config-check-error = error
+config-check-warning = warning
+config-check-summary = { $errors ->
+        [one] 1 error
+       *[other] { $errors } errors
+    }, { $warnings ->
+        [one] 1 warning
+       *[other] { $warnings } warnings
+    }
+config-check-failed = { $count ->
+        [one] the configuration has 1 error
+       *[other] the configuration has { $count } errors
+    }
+
+theme-unknown = Unknown theme '{ $name }'. Built-in themes: { $themes }
+theme-check-title = Contrast of theme '{ $name }' (named colors as xterm shows them):
+theme-check-pair = { $style }: { $foreground } on { $background }, { $ratio }:1
+theme-check-low = { $style }: { $foreground } on { $background }, { $ratio }:1, below { $minimum }:1 and hard to read
+theme-check-ok = All { $count } color pairs are readable
+theme-check-summary = { $count ->
+        [one] 1 of { $total } color pairs is hard to read
+       *[other] { $count } of { $total } color pairs are hard to read
+    }
+
+devices-host = { $host }
+devices-host-used = { $host } (used)
+devices-host-unavailable = { $host } (unavailable)
+devices-none = no output devices
+
+file-skipped = skip  { $path } ({ $reason })
+file-failed = fail  { $path } ({ $error })
+file-add = add   { $path }
+file-remove = drop  { $path }
+
+tag-nothing = No missing tags to fill in ({ $unmatched } files did not match the pattern).
+tag-preview = { $count } files would be tagged, { $unmatched } did not match the pattern. Run again with --apply to write the tags.
+tag-done = Tagged { $count } files, { $failed } failed.
+
+organize-nothing = Nothing to organize: { $count } files already match the pattern.
+organize-move = move  { $from }
+organize-preview = { $count } files would be moved, { $skipped } skipped. Run again with --apply to move them.
+organize-done = Moved { $moved } files, { $failed } failed, { $skipped } skipped.
+
+prune-dry-run = { $count } songs would be removed from the library. Run again without --dry-run to remove them.
+prune-done = Removed { $count } songs whose files are gone.
+
+identify-start = Identifying untagged files among { $count } songs...
+identify-kept = (kept)
+identify-preview = { $count } files would be tagged.
+identify-done = Tagged { $count } files.
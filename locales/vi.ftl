@@ -0,0 +1,466 @@
+### Vietnamese messages
+###
+### Messages left out here are shown in English.
+
+## Interface
+
+scan-modal-title = 📀 Đang quét thư viện nhạc...
+scan-modal-progress = Đang xử lý tệp { $processed } / { $total }...
+scan-modal-walking = Đang tìm tệp nhạc...
+scan-modal-cancel = Esc: hủy quét
+scan-modal-cancelling = Đang hủy. Các bài đã đọc sẽ được giữ lại...
+scan-rate = { $rate } tệp/giây, còn { $eta }
+
+header-library = 🐢 Thư viện Lofi Turtle
+header-playlist = 🐢 Danh sách phát
+header-smart = 🐢 Danh sách thông minh
+search-filter = 🔍 Lọc: { $query }
+search-hint = Nhấn '/' để tìm kiếm
+
+playlists-title = Danh sách phát
+all-music = Tất cả bài hát
+songs-title = Bài hát
+smart-recently-added = Mới thêm gần đây
+smart-most-played = Nghe nhiều nhất
+smart-never-played = Chưa từng nghe
+smart-daily-mix = Tuyển tập hôm nay
+playlist-sort-name = Tên
+playlist-sort-created = Mới tạo
+playlist-sort-updated = Mới sửa
+playlist-sort-song-count = Nhiều bài nhất
+playlist-sorted = Sắp xếp danh sách phát theo: { $order }
+
+visuals-title = Hình ảnh
+no-art = Không có ảnh bìa
+no-chapters = Bài này không có chương
+art-disabled =
+    Đã tắt ảnh bìa
+    (Nhấn 'a')
+
+now-playing-title = Đang phát
+nothing-playing = Không có gì đang phát
+scrobbles-pending = { $count } lượt scrobble đang chờ
+status-scanning = Đang quét { $processed }/{ $total }
+status-stop-after = ⏹ Sẽ dừng sau bài này
+status-party = 🎉 Chế độ tiệc
+
+## Chế độ trình đọc màn hình
+
+state-playing = Đang phát
+state-paused = Tạm dừng
+state-stopped = Đã dừng
+state-shuffle-on = Trộn bài bật
+state-shuffle-off = Trộn bài tắt
+state-shuffle-smart = Trộn bài thông minh bật
+state-repeat-off = Lặp lại tắt
+state-repeat-one = Lặp một bài
+state-repeat-all = Lặp tất cả
+state-volume = Âm lượng { $volume }%
+state-loading = Đang tải
+state-loved = đã thích
+now-playing-plain = { $title } của { $artist }
+announce-playing = Đang phát { $title } của { $artist }
+in-playlists = Có trong { $count } danh sách phát
+
+stats-title = 📊 Thống kê
+vu-meter-title = VU
+recent-title = 🕘 Vừa nghe
+recent-empty = Chưa nghe bài nào
+recent-none = Không có bài này trong danh sách vừa nghe
+today = Hôm nay
+tracks = { $count } bài
+
+playlist-create-title = Tạo danh sách phát
+playlist-edit-title = Sửa danh sách phát
+queue-save-title = Lưu hàng đợi thành danh sách phát
+party-lock-title = Mật khẩu để tắt chế độ tiệc sau này
+party-confirm-title = Nhập lại mật khẩu
+party-unlock-title = Mật khẩu để tắt chế độ tiệc
+bookmark-name-title = Tên dấu trang
+input-title = Nhập
+
+delete-title = Xóa tệp
+delete-to-trash = Chuyển "{ $artist } - { $title }" vào thùng rác?
+delete-permanently = Xóa vĩnh viễn "{ $artist } - { $title }"?
+delete-explanation = Bài hát cũng sẽ bị gỡ khỏi thư viện và mọi danh sách phát.
+delete-confirm = Bạn có chắc không?
+delete-confirm-permanent = Không thể hoàn tác. Bạn có chắc không?
+delete-keys = y: Xác nhận | phím khác: Hủy
+delete-failed = Không thể xóa bài hát: { $error }
+
+listening-stats-title = 📊 Thống kê nghe nhạc
+weekday-monday = T2
+weekday-wednesday = T4
+weekday-friday = T6
+month-short = Th{ $month }
+heatmap-less = Ít
+heatmap-more = Nhiều
+heatmap-summary = { $time } trong { $days } ngày kể từ { $day }/{ $month_number }/{ $year }
+press-any-key = Nhấn phím bất kỳ để đóng
+
+history-load-failed = Không tải được lịch sử nghe: { $error }
+copied-path = Đã sao chép đường dẫn: { $text }
+copied-title = Đã sao chép: { $text }
+loved-on = Đã thích { $song }
+loved-off = Đã bỏ thích { $song }
+clipboard-unavailable = Không dùng được bộ nhớ tạm
+
+undo-done = Đã hoàn tác: { $edit }
+redo-done = Đã làm lại: { $edit }
+undo-nothing = Không có gì để hoàn tác
+redo-nothing = Không có gì để làm lại
+undo-failed = Không hoàn tác được: { $error }
+redo-failed = Không làm lại được: { $error }
+edit-create = tạo danh sách phát '{ $name }'
+edit-add-song = thêm bài vào '{ $name }'
+edit-add-songs = thêm { $count } bài vào '{ $name }'
+edit-remove-song = gỡ bài khỏi '{ $name }'
+edit-delete = xóa danh sách phát '{ $name }'
+edit-save-queue = lưu hàng đợi thành '{ $name }'
+
+song-info-title = 🎵 Thông tin bài hát
+song-info-playlists = Có trong danh sách phát
+song-info-bookmarks = Dấu trang
+song-info-no-bookmarks = Chưa có dấu trang nào
+song-info-hint = Enter: nhảy tới · d: xóa · phím khác để đóng
+song-playlists-none = Chưa có trong danh sách phát nào
+song-info-failed = Không tra cứu được bài hát: { $error }
+bookmark-added = Đã đánh dấu "{ $name }"
+bookmark-removed = Đã xóa dấu trang "{ $name }"
+bookmark-failed = Không cập nhật được dấu trang: { $error }
+bookmark-stream = Không thể đánh dấu luồng phát
+trim-saved = Bỏ { $start } ở đầu và { $end } ở cuối, từ lần phát sau
+trim-cleared = Bài hát sẽ phát trọn vẹn từ lần phát sau
+trim-failed = Không lưu được đoạn cắt: { $error }
+trim-stream = Không thể cắt luồng phát
+song-renamed = Đã đổi tên thành "{ $title }"
+rename-failed = Không đổi được tên bài hát: { $error }
+rename-stream = Không thể đổi tên luồng phát
+rename-blank-title = Tên bài hát không được để trống
+batch-add-title = Thêm vào danh sách phát
+batch-add-confirm = Thêm { $count } bài đang hiển thị vào "{ $name }"?
+batch-added = { $skipped ->
+        [0] Đã thêm { $count } bài vào "{ $name }"
+       *[other] Đã thêm { $count } bài vào "{ $name }", { $skipped } bài đã có sẵn
+    }
+batch-add-failed = Không thêm được các bài hát: { $error }
+batch-add-no-playlist = Hãy chọn một danh sách phát ở khung Playlists trước
+batch-add-loading = Hãy đợi tải xong danh sách bài hát
+batch-add-nothing = Không có bài nào để thêm
+batch-add-outside = Bỏ qua { $count } bài không có trong thư viện
+loved-failed = Không lưu được lượt thích: { $error }
+logs-title = 📜 Nhật ký gần đây
+logs-empty = Chưa có gì được ghi lại
+logs-hint = ↑/↓ PgUp/PgDn: cuộn, phím khác: đóng
+
+setup-title = 🐢 Chào mừng đến với Lofi Turtle, bước { $step }/{ $steps }
+setup-directory-title = Nhạc của bạn ở đâu?
+setup-directory-hint = ↑/↓: di chuyển, Enter: mở, Backspace: lên trên, Tab: dùng thư mục này, Esc: bỏ qua
+dir-browser-empty = Không có thư mục nào ở đây
+setup-theme-title = Chọn giao diện
+setup-theme-hint = ↑/↓: xem thử, Enter: dùng giao diện này và quét, Esc: quay lại
+setup-scan-title = Đang đọc thư viện nhạc
+setup-scan-done = Đã tìm thấy { $count } bài hát. Chúc bạn nghe nhạc vui!
+setup-scan-none = Không tìm thấy tệp nhạc nào. Nhấn U để quét lại sau.
+setup-scan-unreadable = Không đọc được thư mục này.
+
+music-dir-title = 📁 Thư mục nhạc
+music-dir-hint = ↑/↓: di chuyển, Enter: mở, Backspace: lên trên, Tab: dùng thư mục này, Esc: hủy
+music-dir-changed = Thư mục nhạc giờ là { $dir }. Đang quét...
+output-title = 🔊 Thiết bị phát
+output-hint = ↑/↓: di chuyển, Enter: phát qua thiết bị này, Esc: hủy
+output-none = Không tìm thấy thiết bị phát âm thanh nào
+output-changed = Đang phát qua { $device }
+output-failed = Không thể phát qua { $device }: { $error }
+output-missing = Thiết bị phát đã chọn trước đây không còn, đang phát qua thiết bị mặc định: { $error }
+play-failed = Không thể phát { $song }: { $error }
+audio-stopped = Phát âm thanh đã ngừng hoạt động: { $error }
+
+## Help overlay
+
+help-title = ⌨ Phím tắt
+hint-play = Phát/Tạm dừng
+hint-switch = Chuyển
+hint-search = Tìm
+hint-help = Trợ giúp
+hint-quit = Thoát
+action-toggle-play = Phát hoặc tạm dừng
+action-stop = Dừng
+action-stop-after-current = Dừng khi bài đang phát kết thúc
+action-next-track = Bài tiếp theo
+action-previous-track = Bài trước
+action-next-chapter = Chương tiếp theo
+action-previous-chapter = Về đầu chương, hoặc chương trước
+action-replay-recent = Phát lại bài vừa nghe
+action-volume-up = Tăng âm lượng
+action-volume-down = Giảm âm lượng
+action-toggle-shuffle = Phát ngẫu nhiên: tắt, bật hoặc thông minh
+action-cycle-repeat = Đổi chế độ lặp
+action-move-up = Lên
+action-move-down = Xuống
+action-go-top = Lên đầu danh sách
+action-go-bottom = Xuống cuối danh sách
+action-select = Phát mục đang chọn
+action-next-panel = Bảng tiếp theo
+action-previous-panel = Bảng trước
+action-library = Về thư viện
+action-jump-to-playing = Tìm bài đang phát trong thư viện
+action-toggle-follow = Luôn chọn bài đang phát khi chuyển bài
+action-search = Tìm kiếm
+action-clear-search = Xóa tìm kiếm
+action-create-playlist = Tạo danh sách phát
+action-save-queue = Lưu hàng đợi thành danh sách phát
+action-shuffle-queue = Xáo trộn phần còn lại của hàng đợi một lần, hoặc trả về như cũ
+action-remove-from-queue = Bỏ bài khỏi hàng đợi
+action-remove-queue-above = Bỏ các bài phía trên khỏi hàng đợi
+action-remove-queue-below = Bỏ các bài phía dưới khỏi hàng đợi
+action-clear-queue-after-current = Xóa hàng đợi sau bài đang phát
+action-append-to-queue = Thêm playlist đang chọn hoặc album của bài vào hàng đợi
+action-enqueue-clipboard = Thêm tệp, thư mục hoặc URL luồng trong bộ nhớ tạm vào hàng đợi
+action-edit-playlist = Đổi tên danh sách phát
+action-delete-playlist = Xóa danh sách phát
+action-sort-playlists = Đổi thứ tự danh sách phát
+action-add-to-playlist = Thêm bài vào danh sách phát đang chọn
+action-add-results-to-playlist = Thêm mọi bài đang hiển thị vào danh sách phát đang chọn
+action-remove-from-playlist = Gỡ bài khỏi danh sách phát
+action-undo = Hoàn tác lần sửa danh sách phát gần nhất
+action-redo = Làm lại lần sửa danh sách phát
+action-song-playlists = Xem danh sách phát và dấu trang của bài
+action-add-bookmark = Đánh dấu vị trí đang phát
+action-trim-start = Bỏ phần đầu bài đang phát tới vị trí này
+action-trim-end = Cắt phần cuối bài đang phát từ vị trí này
+action-clear-trim = Phát lại trọn vẹn bài đang phát
+action-toggle-art = Hiện hoặc ẩn ảnh bìa
+action-toggle-visualizer = Chuyển giữa phổ tần và dao động ký
+action-toggle-remaining = Hiện độ dài bài hoặc thời gian còn lại
+action-reveal-file = Mở tệp trong trình quản lý tệp
+action-copy-path = Sao chép đường dẫn tệp
+action-copy-title = Sao chép nghệ sĩ và tên bài
+action-rename-song = Đổi tên bài hát đang chọn
+action-toggle-love = Thích hoặc bỏ thích bài hát
+action-do-not-disturb = Tạm ẩn thông báo chuyển bài, hoặc bật lại
+action-delete-file = Xóa tệp bài hát
+action-show-stats = Thống kê nghe nhạc
+action-rescan = Quét lại thư mục nhạc
+action-change-music-dir = Chọn thư mục nhạc khác
+action-choose-output = Chọn thiết bị phát âm thanh
+action-show-logs = Nhật ký gần đây
+action-switch-theme = Giao diện tiếp theo
+action-party-mode = Chế độ tiệc: khách chỉ có thể tìm kiếm và thêm vào hàng đợi
+action-help = Trợ giúp này
+action-quit = Thoát
+
+## Background scans
+
+scan-library-offline = Thư mục nhạc đang ngoại tuyến. Dùng thư viện hiện có.
+scan-finished = Quét xong. Đã thêm { $count } bài hát mới.
+scan-finished-resumed = Quét xong. Đã thêm { $count } bài hát mới sau khi tiếp tục, bỏ qua { $resumed } tệp đã thêm trước đó.
+scan-cancelled = Đã hủy quét. Đã thêm { $count } bài hát mới đọc được trước khi dừng.
+scan-running = Đang có một lượt quét chạy
+scan-failed = Quét thư viện thất bại: { $error }
+scan-report-title = Tóm tắt lần quét
+scan-report-added = Đã thêm: { $count }
+scan-report-updated = Đã cập nhật: { $count }
+scan-report-skipped = Bỏ qua: { $count }
+scan-report-failed = Lỗi: { $count }
+scan-report-skipped-heading = Thư mục bị bỏ qua:
+scan-report-failed-heading = Tệp bị lỗi:
+scan-report-more = ...và { $count } mục khác
+
+layout-load-failed = ⚠️ Không tải được cấu hình bố cục từ { $path }: { $error }. Dùng mặc định.
+layout-not-found = ⚠️ Không tìm thấy tệp cấu hình bố cục { $path }. Dùng mặc định.
+log-file-failed = ⚠️ Không ghi được tệp nhật ký { $path }: { $error }. Chỉ ghi vào trình xem nhật ký.
+dir-create-failed = ⚠️ Không tạo được thư mục { $error }
+file-migrated = Đã sao chép { $from } sang { $to }. Bản cũ không còn được dùng.
+migration-failed = ⚠️ Không sao chép được tệp từ thư mục hiện tại sang vị trí mới: { $error }
+layout-dumped = Đã ghi cấu hình bố cục vào { $path }
+
+cli-mode-title = 🎵 LofiTurtle - Chế độ dòng lệnh
+cli-mode-help = Dùng --help để xem các lệnh
+cli-mode-tui = Chạy không có --cli-mode để dùng giao diện tương tác
+
+## Errors
+
+error-directory = ❌ Lỗi thư mục: { $message }
+error-database = ❌ Lỗi cơ sở dữ liệu: { $message }
+error-library = ❌ Lỗi thư viện: { $message }
+error-audio = ❌ Lỗi âm thanh: { $message }
+error-format = ❌ Lỗi định dạng: { $message }
+error-config = ❌ Lỗi cấu hình: { $message }
+error-terminal = ❌ Lỗi terminal: { $message }
+interface-crashed = Giao diện bị lỗi và đã dừng: { $message }
+error-other = ❌ Lỗi: { $message }
+tip-music-dir = 💡 Mẹo: Dùng --music-dir để chọn thư mục khác
+tip-reset-database = 💡 Mẹo: Thử xóa tệp cơ sở dữ liệu để đặt lại
+tip-audio = 💡 Mẹo: Kiểm tra trình điều khiển âm thanh
+tip-formats = 💡 Mẹo: Các định dạng được hỗ trợ: MP3, FLAC, AAC, M4A, OGG, WAV
+tip-help = 💡 Mẹo: Dùng --help để xem các tùy chọn
+tip-terminal = 💡 Mẹo: Thử chạy trong một terminal khác
+
+## Commands
+
+column-title = Tên bài
+column-artist = Nghệ sĩ
+column-album = Album
+column-duration = Thời lượng
+column-name = Tên
+column-songs = Số bài
+column-description = Mô tả
+
+list-empty = Không có bài hát nào khớp điều kiện.
+list-found = Tìm thấy { $count } bài hát:
+list-group = { $name } ({ $count } bài, { $duration })
+unknown-genre = Thể loại không rõ
+search-empty = Không có bài hát nào khớp '{ $query }'.
+search-found = Tìm thấy { $count } bài hát khớp '{ $query }':
+
+scan-start = Đang quét thư mục nhạc: { $dir }
+scan-offline = Thư mục nhạc { $dir } đang ngoại tuyến. Giữ nguyên thư viện hiện có.
+scan-resuming = Tiếp tục lần quét bị gián đoạn, bỏ qua { $count } tệp đã thêm trước đó.
+scan-skipped = Đã bỏ qua { $count } tệp hoặc thư mục không đọc được.
+scan-force = Đã bật quét lại toàn bộ. Đang xóa cơ sở dữ liệu hiện có...
+scan-identifying = Đang nhận dạng các tệp chưa có thẻ...
+scan-tag-failed = Không gắn được thẻ cho { $path }: { $error }
+scan-identified = Đã nhận dạng và gắn thẻ { $count } tệp.
+scan-found = Tìm thấy { $count } bài hát. Đang cập nhật cơ sở dữ liệu...
+scan-done = Quét xong trong { $duration }. Đã thêm { $count } bài hát mới.
+scan-cancel-hint = Nhấn Ctrl+C để dừng. Các bài đã đọc sẽ được giữ lại.
+scan-stopping = Đang dừng quét...
+scan-stopped = Đã dừng quét sau { $processed } / { $total } tệp. Đã thêm { $count } bài hát mới, không xóa gì.
+scan-dry-run = Chạy thử: sẽ thêm { $added } bài hát, cập nhật { $updated } và xóa { $removed }. Chưa thay đổi gì.
+
+playlist-none = Chưa có danh sách phát nào.
+playlist-found = Tìm thấy { $count } danh sách phát:
+playlist-created = Đã tạo danh sách phát '{ $name }'
+playlist-deleted = Đã xóa danh sách phát '{ $name }'
+playlist-not-found = Không tìm thấy danh sách phát '{ $name }'.
+playlist-no-match = Không có bài hát nào khớp '{ $query }'
+playlist-ambiguous = Có nhiều bài hát khớp '{ $query }'. Hãy ghi cụ thể hơn.
+playlist-already-added = '{ $title }' đã có trong danh sách phát '{ $name }'
+playlist-added = Đã thêm '{ $title }' vào danh sách phát '{ $name }'
+playlist-removed = Đã gỡ '{ $title }' khỏi danh sách phát '{ $name }'
+playlist-not-in = Không có bài hát nào khớp '{ $query }' trong danh sách phát '{ $name }'
+playlist-name = Danh sách phát: { $name }
+playlist-description = Mô tả: { $description }
+playlist-song-count = Số bài: { $count }
+playlist-empty = (Danh sách trống)
+playlist-play-hint = Để phát danh sách, dùng chế độ tương tác hoặc: lofiturtle play --playlist '{ $name }'
+playlist-exists = Danh sách phát '{ $name }' đã tồn tại.
+playlist-combined = Đã tạo danh sách phát '{ $name }' gồm { $count } bài
+
+queue-empty = Hàng đợi đang trống.
+queue-appended = Đã thêm { $count } bài của '{ $name }' vào hàng đợi
+clipboard-queued = Đã thêm { $count } bài từ bộ nhớ tạm vào hàng đợi
+clipboard-nothing = Không có tệp âm thanh, thư mục hay URL luồng nào trong bộ nhớ tạm
+clipboard-reading = Đang đọc { $count } mục đã dán…
+clipboard-unreadable = Không đọc được { $count } mục đã dán; xem nhật ký
+clipboard-folder-failed = Không đọc được thư mục '{ $name }'; xem nhật ký
+clipboard-folder-empty = Không có tệp âm thanh nào trong '{ $name }'
+queue-library = Hãy phát một playlist hoặc view trước; thư viện không tự phát tiếp
+queue-shuffled = Đã xáo trộn phần còn lại của hàng đợi
+queue-unshuffled = Đã trả hàng đợi về thứ tự cũ
+queue-removed = Đã bỏ { $count } bài khỏi hàng đợi
+queue-playing-stays = Bài đang phát vẫn ở trong hàng đợi
+queue-saved = Đã lưu { $count } bài của hàng đợi thành danh sách phát '{ $name }'
+
+stop-after-on = Sẽ dừng phát sau bài này
+stop-after-off = Sẽ tiếp tục phát sau bài này
+stop-after-done = Đã dừng sau bài hát như đã đặt
+party-on = Đã bật chế độ tiệc: chỉ có thể tìm kiếm và thêm vào hàng đợi
+party-off = Đã tắt chế độ tiệc
+party-wrong-passphrase = Sai mật khẩu; chế độ tiệc vẫn bật
+party-blank-passphrase = Mật khẩu không được để trống
+party-passphrase-mismatch = Hai lần nhập mật khẩu không khớp; chế độ tiệc vẫn tắt
+party-locked = Không dùng được trong chế độ tiệc; hãy tìm kiếm hoặc thêm vào hàng đợi
+follow-on = Danh sách bài hát đi theo bài đang phát
+follow-off = Đã tắt đi theo bài đang phát
+do-not-disturb-on = Không làm phiền: tạm ẩn thông báo chuyển bài
+do-not-disturb-off = Đã bật lại thông báo chuyển bài
+notifications-disabled = Thông báo chuyển bài đang tắt; bật bằng "notifications" trong file cài đặt
+
+shuffle-enabled = Đã bật phát ngẫu nhiên
+shuffle-disabled = Đã tắt phát ngẫu nhiên
+shuffle-smart-enabled = Đã bật phát ngẫu nhiên thông minh: không phát một nghệ sĩ hai lần liền, bài mới nghe gần đây phát sau
+repeat-none = Chế độ lặp: Không lặp
+repeat-single = Chế độ lặp: Một bài
+repeat-playlist = Chế độ lặp: Cả danh sách
+
+sync-exported = Đã xuất { $count } danh sách phát ra { $file }
+sync-imported = Đã nhập { $file }: tạo mới { $created }, cập nhật { $updated }, { $unchanged } đã mới nhất
+sync-stats-imported = Đã gộp lượt nghe và đánh giá của { $count } bài hát
+sync-missing-songs = { $count } bài hát không có trong thư viện này nên đã bị bỏ qua
+
+history-exported = Đã xuất { $count } lượt nghe ra { $file }
+
+export-itunes-done = Đã xuất { $songs } bài hát và { $playlists } danh sách phát ra { $file }
+export-mpd-done = Đã ghi { $playlists } danh sách phát và sticker của { $songs } bài hát vào { $dir }
+
+genres-load-failed = ⚠️ Không áp dụng quy tắc thể loại: { $error }
+genres-no-rules = Không có quy tắc thể loại nào trong { $path }
+genres-change = { $song }: { $from } → { $to }
+genres-dry-run = Thể loại của { $count } bài hát sẽ được đổi. Chạy lại không có --dry-run để đổi.
+genres-done = Đã đổi thể loại của { $count } bài hát
+
+art-fetch-start = { $count } album chưa có ảnh bìa, cả trong file lẫn đã tải về
+art-fetch-album = { $album } - { $artist }
+art-fetch-found = đã tìm thấy  { $album } - { $artist }
+art-fetch-missing = không có  { $album } - { $artist }
+art-fetch-dry-run = Chạy lại không có --dry-run để tìm ảnh bìa cho các album này.
+art-fetch-done = Đã tải { $fetched } ảnh bìa; { $missing } album không có trên Cover Art Archive
+art-embed-file = ảnh bìa  { $path }
+art-embed-dry-run = { $count } file sẽ được ghi ảnh bìa. Chạy lại không có --dry-run để ghi.
+art-embed-done = Đã ghi ảnh bìa của { $album } vào { $count } file
+
+import-unmatched = bỏ qua { $track } (không có trong thư viện)
+import-dry-run = Sẽ nhập số lượt nghe và đánh giá của { $count } bài hát, { $unmatched } bài không có trong thư viện. Chạy lại không có --dry-run để nhập.
+import-done = Đã nhập số lượt nghe và đánh giá của { $count } bài hát; { $unmatched } bài không có trong thư viện
+
+lastfm-login-open = Mở { $url } trong trình duyệt, cho phép LofiTurtle rồi nhấn Enter
+lastfm-login-done = Đã đăng nhập là { $user }. Session key được lưu ở { $path } để đồng bộ bài yêu thích
+loved-mark = thích { $song }
+loved-push = gửi  { $song }
+loved-push-failed = lỗi  { $song } ({ $error })
+loved-sync-dry-run = Sẽ đánh dấu yêu thích { $marked } bài hát và thích { $pushed } bài trên Last.fm. Chạy lại không có --dry-run để đồng bộ.
+loved-sync-done = Đã đồng bộ với { $user } trên Last.fm: đánh dấu yêu thích { $marked } bài hát, thích { $pushed } bài trên Last.fm
+wrapped-report-written = Đã ghi báo cáo vào { $file }
+
+config-check-ok = { $path }: không có vấn đề nào
+config-check-missing = { $path }: không tìm thấy, dùng cấu hình mặc định
+config-check-error = lỗi
+config-check-warning = cảnh báo
+config-check-summary = { $errors } lỗi, { $warnings } cảnh báo
+config-check-failed = cấu hình có { $count } lỗi
+
+theme-unknown = Không có theme '{ $name }'. Các theme có sẵn: { $themes }
+theme-check-title = Độ tương phản của theme '{ $name }' (màu theo tên lấy như xterm hiển thị):
+theme-check-pair = { $style }: { $foreground } trên nền { $background }, { $ratio }:1
+theme-check-low = { $style }: { $foreground } trên nền { $background }, { $ratio }:1, dưới { $minimum }:1 nên khó đọc
+theme-check-ok = Cả { $count } cặp màu đều dễ đọc
+theme-check-summary = { $count } trên { $total } cặp màu khó đọc
+
+devices-host = { $host }
+devices-host-used = { $host } (đang dùng)
+devices-host-unavailable = { $host } (không khả dụng)
+devices-none = không có thiết bị phát
+
+file-skipped = bỏ qua  { $path } ({ $reason })
+file-failed = lỗi  { $path } ({ $error })
+file-add = thêm  { $path }
+file-remove = xóa  { $path }
+
+tag-nothing = Không có thẻ nào cần điền ({ $unmatched } tệp không khớp mẫu).
+tag-preview = Sẽ gắn thẻ { $count } tệp, { $unmatched } tệp không khớp mẫu. Chạy lại với --apply để ghi thẻ.
+tag-done = Đã gắn thẻ { $count } tệp, { $failed } tệp lỗi.
+
+organize-nothing = Không có gì để sắp xếp: { $count } tệp đã khớp mẫu.
+organize-move = chuyển  { $from }
+organize-preview = Sẽ chuyển { $count } tệp, bỏ qua { $skipped }. Chạy lại với --apply để chuyển.
+organize-done = Đã chuyển { $moved } tệp, { $failed } lỗi, { $skipped } bỏ qua.
+
+prune-dry-run = Sẽ xóa { $count } bài hát khỏi thư viện. Chạy lại không có --dry-run để xóa.
+prune-done = Đã xóa { $count } bài hát không còn tệp.
+
+identify-start = Đang nhận dạng các tệp chưa có thẻ trong { $count } bài hát...
+identify-kept = (giữ nguyên)
+identify-preview = Sẽ gắn thẻ { $count } tệp.
+identify-done = Đã gắn thẻ { $count } tệp.
@@ -1,7 +1,9 @@
-/// Data Transfer Objects (DTOs) for the application layer
-/// 
-/// DTOs are used to transfer data between layers without exposing
-/// internal domain structures.
+//! Data Transfer Objects (DTOs) for the application layer
+//!
+//! DTOs are used to transfer data between layers without exposing
+//! internal domain structures.
+
+#![allow(dead_code)]
 
 use crate::domain::entities::{Song, Playlist};
 // Value objects are imported but may not all be used in current DTOs
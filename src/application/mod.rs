@@ -0,0 +1,8 @@
+//! Application layer
+//!
+//! Contains the use cases and services that orchestrate the domain layer
+//! on behalf of the presentation layer.
+
+pub mod dto;
+pub mod services;
+pub mod use_cases;
@@ -0,0 +1,40 @@
+use crate::application::use_cases::*;
+use crate::domain::entities::Bookmark;
+use crate::domain::repositories::BookmarkRepository;
+use crate::domain::value_objects::{Duration, SongId};
+use crate::shared::errors::Result;
+use std::sync::Arc;
+
+/// Application service for bookmarks in songs
+pub struct BookmarkService {
+    add_bookmark_use_case: AddBookmarkUseCase,
+    get_bookmarks_use_case: GetBookmarksUseCase,
+    remove_bookmark_use_case: RemoveBookmarkUseCase,
+}
+
+impl BookmarkService {
+    /// Create new bookmark service with dependency injection
+    pub fn new(bookmark_repository: Arc<dyn BookmarkRepository>) -> Self {
+        Self {
+            add_bookmark_use_case: AddBookmarkUseCase::new(bookmark_repository.clone()),
+            get_bookmarks_use_case: GetBookmarksUseCase::new(bookmark_repository.clone()),
+            remove_bookmark_use_case: RemoveBookmarkUseCase::new(bookmark_repository),
+        }
+    }
+
+    /// Bookmark `position` in a song as `name`
+    pub async fn add_bookmark(&self, song_id: SongId, position: Duration, name: String) -> Result<()> {
+        let request = AddBookmarkRequest { song_id, position, name };
+        self.add_bookmark_use_case.execute(request).await
+    }
+
+    /// Get a song's bookmarks, in order of position
+    pub async fn get_bookmarks(&self, song_id: &SongId) -> Result<Vec<Bookmark>> {
+        self.get_bookmarks_use_case.execute(song_id).await
+    }
+
+    /// Remove a bookmark
+    pub async fn remove_bookmark(&self, bookmark: &Bookmark) -> Result<()> {
+        self.remove_bookmark_use_case.execute(bookmark).await
+    }
+}
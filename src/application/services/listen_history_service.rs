@@ -0,0 +1,92 @@
+use crate::application::use_cases::*;
+use crate::domain::entities::{Listen, ListeningSummary, PendingScrobble};
+use crate::domain::repositories::ListenRepository;
+use crate::shared::errors::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// Application service for listening history and scrobbling
+///
+/// Listens are kept locally whether or not a scrobbling service is set up.
+/// When one is, each listen is also queued so it can be submitted once the
+/// service is reachable.
+pub struct ListenHistoryService {
+    record_listen_use_case: RecordListenUseCase,
+    due_scrobbles_use_case: GetDueScrobblesUseCase,
+    settle_scrobbles_use_case: SettleScrobblesUseCase,
+    count_pending_scrobbles_use_case: CountPendingScrobblesUseCase,
+    get_listens_use_case: GetListensUseCase,
+    recent_listens_use_case: GetRecentListensUseCase,
+    artists_heard_before_use_case: GetArtistsHeardBeforeUseCase,
+    summarize_listens_use_case: SummarizeListensUseCase,
+}
+
+impl ListenHistoryService {
+    /// Create new listen history service with dependency injection
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self {
+            record_listen_use_case: RecordListenUseCase::new(listen_repository.clone()),
+            due_scrobbles_use_case: GetDueScrobblesUseCase::new(listen_repository.clone()),
+            settle_scrobbles_use_case: SettleScrobblesUseCase::new(listen_repository.clone()),
+            count_pending_scrobbles_use_case: CountPendingScrobblesUseCase::new(listen_repository.clone()),
+            get_listens_use_case: GetListensUseCase::new(listen_repository.clone()),
+            recent_listens_use_case: GetRecentListensUseCase::new(listen_repository.clone()),
+            artists_heard_before_use_case: GetArtistsHeardBeforeUseCase::new(listen_repository.clone()),
+            summarize_listens_use_case: SummarizeListensUseCase::new(listen_repository),
+        }
+    }
+
+    /// Add a listen to the history, queueing it for scrobbling if requested
+    pub async fn record_listen(&self, listen: Listen, scrobble: bool) -> Result<i64> {
+        let request = RecordListenRequest { listen, scrobble };
+        let response = self.record_listen_use_case.execute(request).await?;
+        Ok(response.listen_id)
+    }
+
+    /// Get up to `limit` scrobbles that are ready to be submitted
+    pub async fn get_due_scrobbles(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<PendingScrobble>> {
+        let request = GetDueScrobblesRequest { now, limit };
+        let response = self.due_scrobbles_use_case.execute(request).await?;
+        Ok(response.scrobbles)
+    }
+
+    /// Remove successfully submitted scrobbles from the queue
+    pub async fn complete_scrobbles(&self, scrobbles: Vec<PendingScrobble>) -> Result<()> {
+        let request = SettleScrobblesRequest::Submitted { scrobbles };
+        self.settle_scrobbles_use_case.execute(request).await
+    }
+
+    /// Schedule failed scrobbles for another attempt
+    pub async fn retry_scrobbles(&self, scrobbles: Vec<PendingScrobble>, now: DateTime<Utc>) -> Result<()> {
+        let request = SettleScrobblesRequest::Failed { scrobbles, now };
+        self.settle_scrobbles_use_case.execute(request).await
+    }
+
+    /// Count listens waiting to be scrobbled
+    pub async fn count_pending_scrobbles(&self) -> Result<usize> {
+        self.count_pending_scrobbles_use_case.execute().await
+    }
+
+    /// Get the listens that started between `from` and `to`, oldest first
+    pub async fn get_listens(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Listen>> {
+        let request = GetListensRequest { from, to };
+        let response = self.get_listens_use_case.execute(request).await?;
+        Ok(response.listens)
+    }
+
+    /// Get the last `limit` listens, newest first
+    pub async fn recent_listens(&self, limit: usize) -> Result<Vec<Listen>> {
+        self.recent_listens_use_case.execute(limit).await
+    }
+
+    /// Get every artist listened to before `before`
+    pub async fn artists_heard_before(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        self.artists_heard_before_use_case.execute(before).await
+    }
+
+    /// Total up the listens that started between `from` and `to`
+    pub async fn summarize_listens(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<ListeningSummary> {
+        let request = SummarizeListensRequest { from, to };
+        self.summarize_listens_use_case.execute(request).await
+    }
+}
@@ -1,9 +1,12 @@
-/// Application services module
-/// 
-/// Contains high-level application services that orchestrate use cases
-/// and provide facade interfaces for the presentation layer.
+//! Application services module
+//!
+//! Contains high-level application services that orchestrate use cases
+//! and provide facade interfaces for the presentation layer.
 
 pub mod music_library_service;
+pub mod listen_history_service;
+pub mod bookmark_service;
 
-// MusicLibraryService available but not yet integrated with legacy UI
-// pub use music_library_service::MusicLibraryService;
+pub use music_library_service::{MusicLibraryService, SongData};
+pub use listen_history_service::ListenHistoryService;
+pub use bookmark_service::BookmarkService;
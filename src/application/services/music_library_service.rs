@@ -1,8 +1,10 @@
 use crate::application::use_cases::*;
 use crate::domain::repositories::{SongRepository, PlaylistRepository, PlaylistSongRepository};
-use crate::domain::entities::{Song, Playlist};
-use crate::domain::value_objects::{SongId, PlaylistId, FilePath, Duration};
+use crate::domain::entities::{ImportedStats, Song, SongStats, SortKeys, Playlist, PlaylistSetOperation};
+use crate::domain::value_objects::{SongId, PlaylistId, FilePath, Duration, Trim};
 use crate::shared::errors::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Application service for music library operations
@@ -13,14 +15,40 @@ pub struct MusicLibraryService {
     // Use cases
     add_song_use_case: AddSongUseCase,
     search_songs_use_case: SearchSongsUseCase,
+    song_page_use_case: GetSongPageUseCase,
+    #[allow(dead_code)] // Future feature: single-song lookup
     get_song_use_case: GetSongUseCase,
+    get_songs_use_case: GetSongsUseCase,
     remove_song_use_case: RemoveSongUseCase,
+    clear_library_use_case: ClearLibraryUseCase,
+    recently_added_use_case: GetRecentlyAddedSongsUseCase,
+    relocate_song_use_case: RelocateSongUseCase,
+    record_play_use_case: RecordPlayUseCase,
+    set_loved_use_case: SetLovedUseCase,
+    set_trim_use_case: SetTrimUseCase,
+    get_trim_use_case: GetTrimUseCase,
+    loved_songs_use_case: GetLovedSongsUseCase,
+    import_stats_use_case: ImportStatsUseCase,
+    song_stats_use_case: GetSongStatsUseCase,
+    sort_keys_use_case: GetSortKeysUseCase,
+    album_use_case: GetAlbumUseCase,
+    next_album_use_case: GetNextAlbumUseCase,
+    play_stats_use_case: GetPlayStatsSongsUseCase,
+    daily_mix_use_case: GetDailyMixUseCase,
     
     create_playlist_use_case: CreatePlaylistUseCase,
+    list_playlists_use_case: ListPlaylistsUseCase,
+    find_playlist_by_name_use_case: FindPlaylistByNameUseCase,
+    find_playlists_with_song_use_case: FindPlaylistsWithSongUseCase,
     add_song_to_playlist_use_case: AddSongToPlaylistUseCase,
+    add_songs_to_playlist_use_case: AddSongsToPlaylistUseCase,
     remove_song_from_playlist_use_case: RemoveSongFromPlaylistUseCase,
     get_playlist_with_songs_use_case: GetPlaylistWithSongsUseCase,
+    playlist_durations_use_case: GetPlaylistDurationsUseCase,
     delete_playlist_use_case: DeletePlaylistUseCase,
+    reorder_playlist_use_case: ReorderPlaylistUseCase,
+    describe_playlist_use_case: DescribePlaylistUseCase,
+    combine_playlists_use_case: CombinePlaylistsUseCase,
 }
 
 impl MusicLibraryService {
@@ -33,15 +61,43 @@ impl MusicLibraryService {
         Self {
             add_song_use_case: AddSongUseCase::new(song_repository.clone()),
             search_songs_use_case: SearchSongsUseCase::new(song_repository.clone()),
+            song_page_use_case: GetSongPageUseCase::new(song_repository.clone()),
             get_song_use_case: GetSongUseCase::new(song_repository.clone()),
-            remove_song_use_case: RemoveSongUseCase::new(song_repository.clone()),
+            get_songs_use_case: GetSongsUseCase::new(song_repository.clone()),
+            remove_song_use_case: RemoveSongUseCase::new(
+                song_repository.clone(),
+                playlist_repository.clone(),
+                playlist_song_repository.clone(),
+            ),
+            clear_library_use_case: ClearLibraryUseCase::new(song_repository.clone()),
+            recently_added_use_case: GetRecentlyAddedSongsUseCase::new(song_repository.clone()),
+            relocate_song_use_case: RelocateSongUseCase::new(song_repository.clone()),
+            record_play_use_case: RecordPlayUseCase::new(song_repository.clone()),
+            set_loved_use_case: SetLovedUseCase::new(song_repository.clone()),
+            set_trim_use_case: SetTrimUseCase::new(song_repository.clone()),
+            get_trim_use_case: GetTrimUseCase::new(song_repository.clone()),
+            loved_songs_use_case: GetLovedSongsUseCase::new(song_repository.clone()),
+            import_stats_use_case: ImportStatsUseCase::new(song_repository.clone()),
+            song_stats_use_case: GetSongStatsUseCase::new(song_repository.clone()),
+            sort_keys_use_case: GetSortKeysUseCase::new(song_repository.clone()),
+            album_use_case: GetAlbumUseCase::new(song_repository.clone()),
+            next_album_use_case: GetNextAlbumUseCase::new(song_repository.clone()),
+            play_stats_use_case: GetPlayStatsSongsUseCase::new(song_repository.clone()),
+            daily_mix_use_case: GetDailyMixUseCase::new(song_repository.clone()),
             
             create_playlist_use_case: CreatePlaylistUseCase::new(playlist_repository.clone()),
+            list_playlists_use_case: ListPlaylistsUseCase::new(playlist_repository.clone()),
+            find_playlist_by_name_use_case: FindPlaylistByNameUseCase::new(playlist_repository.clone()),
+            find_playlists_with_song_use_case: FindPlaylistsWithSongUseCase::new(playlist_repository.clone()),
             add_song_to_playlist_use_case: AddSongToPlaylistUseCase::new(
                 playlist_repository.clone(),
                 song_repository.clone(),
                 playlist_song_repository.clone(),
             ),
+            add_songs_to_playlist_use_case: AddSongsToPlaylistUseCase::new(
+                playlist_repository.clone(),
+                playlist_song_repository.clone(),
+            ),
             remove_song_from_playlist_use_case: RemoveSongFromPlaylistUseCase::new(
                 playlist_repository.clone(),
                 playlist_song_repository.clone(),
@@ -50,14 +106,22 @@ impl MusicLibraryService {
                 playlist_repository.clone(),
                 playlist_song_repository.clone(),
             ),
+            playlist_durations_use_case: GetPlaylistDurationsUseCase::new(playlist_song_repository.clone()),
             delete_playlist_use_case: DeletePlaylistUseCase::new(
-                playlist_repository,
+                playlist_repository.clone(),
+                playlist_song_repository.clone(),
+            ),
+            reorder_playlist_use_case: ReorderPlaylistUseCase::new(
+                playlist_repository.clone(),
                 playlist_song_repository,
             ),
+            describe_playlist_use_case: DescribePlaylistUseCase::new(playlist_repository.clone()),
+            combine_playlists_use_case: CombinePlaylistsUseCase::new(playlist_repository),
         }
     }
 
     /// Add a new song to the library
+    #[allow(dead_code)] // Future feature: adding single songs
     pub async fn add_song(
         &self,
         file_path: FilePath,
@@ -72,6 +136,7 @@ impl MusicLibraryService {
             artist,
             album,
             duration,
+            genre: None,
         };
 
         let response = self.add_song_use_case.execute(request).await?;
@@ -90,20 +155,157 @@ impl MusicLibraryService {
         self.search_songs(String::new()).await
     }
 
+    /// Get up to `limit` of the songs matching the lowered search `query`,
+    /// or of all songs if it is empty, from position `offset` on in library order
+    pub async fn get_song_page(&self, query: String, offset: usize, limit: usize) -> Result<Vec<Song>> {
+        let request = GetSongPageRequest { query, offset, limit };
+        let response = self.song_page_use_case.execute(request).await?;
+        Ok(response.songs)
+    }
+
+    /// Count the songs matching the lowered search `query`, or all songs if it is empty
+    pub async fn count_songs(&self, query: &str) -> Result<usize> {
+        self.song_page_use_case.count(query).await
+    }
+
+    /// Find the position in library order of the song at `path` among the
+    /// songs matching the lowered search `query`
+    pub async fn get_song_position(&self, query: &str, path: &FilePath) -> Result<Option<usize>> {
+        self.song_page_use_case.position_of(query, path).await
+    }
+
     /// Get song by ID
+    #[allow(dead_code)] // Future feature: single-song lookup
     pub async fn get_song(&self, song_id: SongId) -> Result<Song> {
         let request = GetSongRequest { song_id };
         let response = self.get_song_use_case.execute(request).await?;
         Ok(response.song)
     }
 
-    /// Remove song from library
+    /// Get the songs of the library among `song_ids`
+    pub async fn get_songs(&self, song_ids: &[SongId]) -> Result<Vec<Song>> {
+        self.get_songs_use_case.execute(song_ids).await
+    }
+
+    /// Remove song from the library and all playlists
     pub async fn remove_song(&self, song_id: SongId) -> Result<()> {
         let request = RemoveSongRequest { song_id };
         self.remove_song_use_case.execute(request).await?;
         Ok(())
     }
 
+    /// Get songs added to the library at or after `since`, newest first
+    pub async fn get_recently_added_songs(&self, since: DateTime<Utc>) -> Result<Vec<Song>> {
+        let request = GetRecentlyAddedSongsRequest { since };
+        let response = self.recently_added_use_case.execute(request).await?;
+        Ok(response.songs)
+    }
+
+    /// Point a song at its new file path after the file was moved
+    pub async fn relocate_song(&self, song_id: SongId, new_path: FilePath) -> Result<Song> {
+        let request = RelocateSongRequest { song_id, new_path };
+        let response = self.relocate_song_use_case.execute(request).await?;
+        Ok(response.song)
+    }
+
+    /// Count a play of the song
+    pub async fn record_play(&self, song_id: SongId) -> Result<()> {
+        let request = RecordPlayRequest { song_id };
+        self.record_play_use_case.execute(request).await
+    }
+
+    /// Mark a song as loved, or no longer loved
+    pub async fn set_loved(&self, song_id: SongId, loved: bool) -> Result<()> {
+        let request = SetLovedRequest { song_id, loved };
+        self.set_loved_use_case.execute(request).await
+    }
+
+    /// Cut `trim` off the start and end of a song when it plays
+    pub async fn set_trim(&self, song_id: SongId, trim: Trim) -> Result<()> {
+        let request = SetTrimRequest { song_id, trim };
+        self.set_trim_use_case.execute(request).await
+    }
+
+    /// Get how much is cut off a song when it plays
+    pub async fn get_trim(&self, song_id: &SongId) -> Result<Trim> {
+        self.get_trim_use_case.execute(song_id).await
+    }
+
+    /// Get the loved songs
+    pub async fn get_loved_songs(&self) -> Result<Vec<Song>> {
+        self.loved_songs_use_case.execute().await
+    }
+
+    /// Merge listening stats brought over from another player into a song
+    pub async fn import_stats(&self, song_id: SongId, stats: ImportedStats) -> Result<()> {
+        let request = ImportStatsRequest { song_id, stats };
+        self.import_stats_use_case.execute(request).await
+    }
+
+    /// Get every song with how it has been listened to
+    pub async fn get_song_stats(&self) -> Result<Vec<SongStats>> {
+        self.song_stats_use_case.execute().await
+    }
+
+    /// Get what every song's artist and album sort by
+    pub async fn get_sort_keys(&self) -> Result<HashMap<SongId, SortKeys>> {
+        self.sort_keys_use_case.execute().await
+    }
+
+    /// Get the songs of an album in track order
+    pub async fn get_album(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+        self.album_use_case.execute(artist, album).await
+    }
+
+    /// Get the songs of the album after the song's album, in track order
+    pub async fn get_next_album(&self, id: &SongId) -> Result<Vec<Song>> {
+        self.next_album_use_case.execute(id).await
+    }
+
+    /// Get the `limit` most played songs, most played first
+    pub async fn get_most_played_songs(&self, limit: usize) -> Result<Vec<Song>> {
+        let request = GetPlayStatsSongsRequest::MostPlayed { limit };
+        let response = self.play_stats_use_case.execute(request).await?;
+        Ok(response.songs)
+    }
+
+    /// Get songs that have never been played
+    pub async fn get_never_played_songs(&self) -> Result<Vec<Song>> {
+        let response = self.play_stats_use_case.execute(GetPlayStatsSongsRequest::NeverPlayed).await?;
+        Ok(response.songs)
+    }
+
+    /// Get up to `size` songs for the Daily Mix of `day`
+    pub async fn get_daily_mix(&self, day: NaiveDate, size: usize) -> Result<Vec<Song>> {
+        let response = self.daily_mix_use_case.execute(GetDailyMixRequest { day, size }).await?;
+        Ok(response.songs)
+    }
+
+    /// Remove every song from the library
+    pub async fn clear_library(&self) -> Result<()> {
+        self.clear_library_use_case.execute().await
+    }
+
+    /// Get all playlists, ordered by name
+    pub async fn get_all_playlists(&self) -> Result<Vec<Playlist>> {
+        let response = self.list_playlists_use_case.execute().await?;
+        Ok(response.playlists)
+    }
+
+    /// Find a playlist by its name
+    pub async fn find_playlist_by_name(&self, name: String) -> Result<Option<Playlist>> {
+        let request = FindPlaylistByNameRequest { name };
+        let response = self.find_playlist_by_name_use_case.execute(request).await?;
+        Ok(response.playlist)
+    }
+
+    /// Find the playlists that contain a song, ordered by name
+    pub async fn find_playlists_with_song(&self, song_id: SongId) -> Result<Vec<Playlist>> {
+        let request = FindPlaylistsWithSongRequest { song_id };
+        let response = self.find_playlists_with_song_use_case.execute(request).await?;
+        Ok(response.playlists)
+    }
+
     /// Create a new playlist
     pub async fn create_playlist(&self, name: String, description: Option<String>) -> Result<PlaylistId> {
         let request = CreatePlaylistRequest { name, description };
@@ -118,6 +320,14 @@ impl MusicLibraryService {
         Ok(())
     }
 
+    /// Add songs to the end of a playlist in one go, returning the ones
+    /// that were not in it yet
+    pub async fn add_songs_to_playlist(&self, playlist_id: PlaylistId, song_ids: Vec<SongId>) -> Result<Vec<SongId>> {
+        let request = AddSongsToPlaylistRequest { playlist_id, song_ids };
+        let response = self.add_songs_to_playlist_use_case.execute(request).await?;
+        Ok(response.added)
+    }
+
     /// Remove song from playlist
     pub async fn remove_song_from_playlist(&self, playlist_id: PlaylistId, song_id: SongId) -> Result<()> {
         let request = RemoveSongFromPlaylistRequest { playlist_id, song_id };
@@ -132,6 +342,12 @@ impl MusicLibraryService {
         Ok((response.playlist, response.songs))
     }
 
+    /// Get the total duration of every playlist, missing for empty ones
+    pub async fn get_playlist_durations(&self) -> Result<HashMap<PlaylistId, Duration>> {
+        let response = self.playlist_durations_use_case.execute().await?;
+        Ok(response.durations)
+    }
+
     /// Delete playlist
     pub async fn delete_playlist(&self, playlist_id: PlaylistId) -> Result<()> {
         let request = DeletePlaylistRequest { playlist_id };
@@ -139,6 +355,33 @@ impl MusicLibraryService {
         Ok(())
     }
 
+    /// Put the songs of a playlist in the given order
+    pub async fn reorder_playlist(&self, playlist_id: PlaylistId, song_ids: Vec<SongId>) -> Result<()> {
+        let request = ReorderPlaylistRequest { playlist_id, song_ids };
+        self.reorder_playlist_use_case.execute(request).await?;
+        Ok(())
+    }
+
+    /// Replace the description of a playlist
+    pub async fn describe_playlist(&self, playlist_id: PlaylistId, description: Option<String>) -> Result<()> {
+        let request = DescribePlaylistRequest { playlist_id, description };
+        self.describe_playlist_use_case.execute(request).await
+    }
+
+    /// Combine two playlists into a new one named `into`, returning how
+    /// many songs it got
+    pub async fn combine_playlists(
+        &self,
+        operation: PlaylistSetOperation,
+        first: String,
+        second: String,
+        into: String,
+    ) -> Result<usize> {
+        let request = CombinePlaylistsRequest { operation, first, second, into };
+        let response = self.combine_playlists_use_case.execute(request).await?;
+        Ok(response.song_count)
+    }
+
     /// Batch add multiple songs (useful for library scanning)
     pub async fn batch_add_songs(&self, songs_data: Vec<SongData>) -> Result<BatchAddResult> {
         let mut added_count = 0;
@@ -153,6 +396,7 @@ impl MusicLibraryService {
                 artist: song_data.artist,
                 album: song_data.album,
                 duration: song_data.duration,
+                genre: song_data.genre,
             };
 
             match self.add_song_use_case.execute(request).await {
@@ -188,6 +432,7 @@ pub struct SongData {
     pub artist: String,
     pub album: String,
     pub duration: Duration,
+    pub genre: Option<String>,
 }
 
 /// Result of batch song addition
@@ -209,6 +454,7 @@ pub struct BatchError {
 mod tests {
     use super::*;
     use crate::domain::repositories::{SongRepository, PlaylistRepository, PlaylistSongRepository};
+    use crate::shared::errors::ApplicationError;
     use async_trait::async_trait;
     use std::collections::HashMap;
     use std::sync::Mutex;
@@ -249,6 +495,21 @@ mod tests {
             Ok(songs.values().cloned().collect())
         }
 
+        async fn find_range(&self, query: &str, offset: usize, limit: usize) -> Result<Vec<Song>> {
+            let mut songs = if query.is_empty() { self.find_all().await? } else { self.search(query).await? };
+            songs.sort_by(|a, b| a.id().as_str().cmp(b.id().as_str()));
+            Ok(songs.into_iter().skip(offset).take(limit).collect())
+        }
+
+        async fn find_position(&self, query: &str, path: &FilePath) -> Result<Option<usize>> {
+            let songs = self.find_range(query, 0, usize::MAX).await?;
+            Ok(songs.iter().position(|song| song.file_path() == path))
+        }
+
+        async fn count(&self, query: &str) -> Result<usize> {
+            Ok(self.find_range(query, 0, usize::MAX).await?.len())
+        }
+
         async fn search(&self, query: &str) -> Result<Vec<Song>> {
             let songs = self.songs.lock().unwrap();
             let query_lower = query.to_lowercase();
@@ -279,6 +540,89 @@ mod tests {
                 .collect())
         }
 
+        async fn find_added_since(&self, _since: DateTime<Utc>) -> Result<Vec<Song>> {
+            // The mock does not track insertion times
+            self.find_all().await
+        }
+
+        async fn relocate(&self, id: &SongId, new_path: &FilePath) -> Result<Song> {
+            let mut songs = self.songs.lock().unwrap();
+            let song = songs.remove(id).ok_or_else(|| ApplicationError::Repository("Song not found".to_string()))?;
+            let relocated = Song::new(
+                new_path.clone(),
+                song.title().to_string(),
+                song.artist().to_string(),
+                song.album().to_string(),
+                *song.duration(),
+            ).map_err(ApplicationError::Domain)?;
+            songs.insert(relocated.id().clone(), relocated.clone());
+            Ok(relocated)
+        }
+
+        async fn record_play(&self, _id: &SongId) -> Result<()> {
+            Ok(())
+        }
+
+        async fn set_loved(&self, _id: &SongId, _loved: bool) -> Result<()> {
+            Ok(())
+        }
+
+        async fn set_trim(&self, _id: &SongId, _trim: Trim) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_trim(&self, _id: &SongId) -> Result<Option<Trim>> {
+            Ok(None)
+        }
+
+        async fn find_loved(&self) -> Result<Vec<Song>> {
+            // The mock does not track loved songs
+            Ok(Vec::new())
+        }
+
+        async fn import_stats(&self, _id: &SongId, _stats: &ImportedStats) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_most_played(&self, _limit: usize) -> Result<Vec<Song>> {
+            Ok(Vec::new())
+        }
+
+        async fn find_never_played(&self) -> Result<Vec<Song>> {
+            // The mock does not track plays
+            self.find_all().await
+        }
+
+        async fn find_all_with_stats(&self) -> Result<Vec<SongStats>> {
+            // The mock does not track plays, and every song counts as new
+            let mut songs = self.find_all().await?;
+            songs.sort_by(|a, b| a.id().as_str().cmp(b.id().as_str()));
+            Ok(songs.into_iter()
+                .map(|song| SongStats { song, play_count: 0, last_played_at: None, added_at: Utc::now(), rating: None })
+                .collect())
+        }
+
+        async fn find_sort_keys(&self) -> Result<HashMap<SongId, SortKeys>> {
+            // The mock ignores no articles
+            let songs = self.songs.lock().unwrap();
+            Ok(songs.values()
+                .map(|song| (song.id().clone(), SortKeys { artist: song.artist().to_string(), album: song.album().to_string() }))
+                .collect())
+        }
+
+        async fn find_album(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+            let songs = self.songs.lock().unwrap();
+            Ok(songs.values()
+                .filter(|song| song.artist().eq_ignore_ascii_case(artist) && song.album().eq_ignore_ascii_case(album))
+                .cloned()
+                .collect())
+        }
+
+        async fn find_next_album(&self, _id: &SongId) -> Result<Vec<Song>> {
+            // The mock never goes on to another album
+            Ok(Vec::new())
+        }
+
         async fn clear_all(&self) -> Result<()> {
             let mut songs = self.songs.lock().unwrap();
             songs.clear();
@@ -292,9 +636,11 @@ mod tests {
     #[async_trait]
     impl PlaylistRepository for MockPlaylistRepository {
         async fn save(&self, _playlist: &Playlist) -> Result<()> { Ok(()) }
+        async fn create_with_songs(&self, _playlist: &Playlist) -> Result<()> { Ok(()) }
         async fn find_by_id(&self, _id: &PlaylistId) -> Result<Option<Playlist>> { Ok(None) }
         async fn find_by_name(&self, _name: &str) -> Result<Option<Playlist>> { Ok(None) }
         async fn find_all(&self) -> Result<Vec<Playlist>> { Ok(Vec::new()) }
+        async fn find_by_song(&self, _song_id: &SongId) -> Result<Vec<Playlist>> { Ok(Vec::new()) }
         async fn delete(&self, _id: &PlaylistId) -> Result<()> { Ok(()) }
         async fn exists_by_name(&self, _name: &str) -> Result<bool> { Ok(false) }
     }
@@ -302,8 +648,10 @@ mod tests {
     #[async_trait]
     impl PlaylistSongRepository for MockPlaylistSongRepository {
         async fn add_song_to_playlist(&self, _playlist_id: &PlaylistId, _song_id: &SongId, _position: usize) -> Result<()> { Ok(()) }
+        async fn append_songs(&self, _playlist_id: &PlaylistId, _song_ids: &[SongId]) -> Result<()> { Ok(()) }
         async fn remove_song_from_playlist(&self, _playlist_id: &PlaylistId, _song_id: &SongId) -> Result<()> { Ok(()) }
         async fn get_playlist_songs(&self, _playlist_id: &PlaylistId) -> Result<Vec<Song>> { Ok(Vec::new()) }
+        async fn get_playlist_durations(&self) -> Result<Vec<(PlaylistId, Duration)>> { Ok(Vec::new()) }
         async fn reorder_playlist_songs(&self, _playlist_id: &PlaylistId, _song_ids: &[SongId]) -> Result<()> { Ok(()) }
         async fn clear_playlist(&self, _playlist_id: &PlaylistId) -> Result<()> { Ok(()) }
     }
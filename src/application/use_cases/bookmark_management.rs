@@ -0,0 +1,63 @@
+use crate::domain::entities::Bookmark;
+use crate::domain::repositories::BookmarkRepository;
+use crate::domain::value_objects::{Duration, SongId};
+use crate::shared::errors::Result;
+use std::sync::Arc;
+
+/// Use case for bookmarking a position in a song
+pub struct AddBookmarkUseCase {
+    bookmark_repository: Arc<dyn BookmarkRepository>,
+}
+
+impl AddBookmarkUseCase {
+    pub fn new(bookmark_repository: Arc<dyn BookmarkRepository>) -> Self {
+        Self { bookmark_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: AddBookmarkRequest) -> Result<()> {
+        let bookmark = Bookmark::new(request.song_id, request.position, request.name)?;
+        self.bookmark_repository.save(&bookmark).await
+    }
+}
+
+/// Use case for listing the bookmarks in a song
+pub struct GetBookmarksUseCase {
+    bookmark_repository: Arc<dyn BookmarkRepository>,
+}
+
+impl GetBookmarksUseCase {
+    pub fn new(bookmark_repository: Arc<dyn BookmarkRepository>) -> Self {
+        Self { bookmark_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, song_id: &SongId) -> Result<Vec<Bookmark>> {
+        self.bookmark_repository.find_by_song(song_id).await
+    }
+}
+
+/// Use case for removing a bookmark
+pub struct RemoveBookmarkUseCase {
+    bookmark_repository: Arc<dyn BookmarkRepository>,
+}
+
+impl RemoveBookmarkUseCase {
+    pub fn new(bookmark_repository: Arc<dyn BookmarkRepository>) -> Self {
+        Self { bookmark_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, bookmark: &Bookmark) -> Result<()> {
+        self.bookmark_repository.delete(bookmark.song_id(), *bookmark.position()).await
+    }
+}
+
+// Request/Response DTOs (Data Transfer Objects)
+
+#[derive(Debug, Clone)]
+pub struct AddBookmarkRequest {
+    pub song_id: SongId,
+    pub position: Duration,
+    pub name: String,
+}
@@ -0,0 +1,228 @@
+use crate::domain::entities::{Listen, ListeningSummary, PendingScrobble};
+use crate::domain::repositories::ListenRepository;
+use crate::shared::errors::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// Delay before the first retry of a failed scrobble
+const RETRY_BASE_SECONDS: i64 = 30;
+
+/// Longest delay between retries
+const RETRY_MAX_SECONDS: i64 = 60 * 60;
+
+/// Delay before retrying a scrobble that has failed `attempts` times
+///
+/// Doubles with every failure, starting at 30 seconds and capped at an hour.
+pub fn retry_delay(attempts: u32) -> chrono::Duration {
+    let factor = 1i64 << attempts.saturating_sub(1).min(16);
+    chrono::Duration::seconds((RETRY_BASE_SECONDS * factor).min(RETRY_MAX_SECONDS))
+}
+
+/// Use case for adding a listen to the history
+pub struct RecordListenUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl RecordListenUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: RecordListenRequest) -> Result<RecordListenResponse> {
+        let listen_id = self.listen_repository.save(&request.listen, request.scrobble).await?;
+        Ok(RecordListenResponse { listen_id })
+    }
+}
+
+/// Use case for fetching scrobbles that are ready to be submitted
+pub struct GetDueScrobblesUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl GetDueScrobblesUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetDueScrobblesRequest) -> Result<GetDueScrobblesResponse> {
+        let scrobbles = self.listen_repository.find_due_scrobbles(request.now, request.limit).await?;
+        Ok(GetDueScrobblesResponse { scrobbles })
+    }
+}
+
+/// Use case for settling a submission attempt
+///
+/// Submitted scrobbles leave the queue; failed ones are retried later with
+/// exponential backoff.
+pub struct SettleScrobblesUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl SettleScrobblesUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: SettleScrobblesRequest) -> Result<()> {
+        match request {
+            SettleScrobblesRequest::Submitted { scrobbles } => {
+                let ids: Vec<i64> = scrobbles.iter().map(|scrobble| scrobble.listen_id).collect();
+                self.listen_repository.remove_scrobbles(&ids).await
+            }
+            SettleScrobblesRequest::Failed { scrobbles, now } => {
+                for scrobble in scrobbles {
+                    let attempts = scrobble.attempts + 1;
+                    self.listen_repository
+                        .reschedule_scrobble(scrobble.listen_id, attempts, now + retry_delay(attempts))
+                        .await?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Use case for counting scrobbles that have not been submitted yet
+pub struct CountPendingScrobblesUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl CountPendingScrobblesUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<usize> {
+        self.listen_repository.count_pending_scrobbles().await
+    }
+}
+
+/// Use case for fetching the listens in a period
+pub struct GetListensUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl GetListensUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetListensRequest) -> Result<GetListensResponse> {
+        let listens = self.listen_repository.find_between(request.from, request.to).await?;
+        Ok(GetListensResponse { listens })
+    }
+}
+
+/// Use case for fetching the last few listens
+pub struct GetRecentListensUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl GetRecentListensUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, limit: usize) -> Result<Vec<Listen>> {
+        self.listen_repository.find_recent(limit).await
+    }
+}
+
+/// Use case for listing the artists heard before a point in time
+pub struct GetArtistsHeardBeforeUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl GetArtistsHeardBeforeUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        self.listen_repository.find_artists_before(before).await
+    }
+}
+
+/// Use case for totalling the listens in a period
+pub struct SummarizeListensUseCase {
+    listen_repository: Arc<dyn ListenRepository>,
+}
+
+impl SummarizeListensUseCase {
+    pub fn new(listen_repository: Arc<dyn ListenRepository>) -> Self {
+        Self { listen_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: SummarizeListensRequest) -> Result<ListeningSummary> {
+        self.listen_repository.summarize(request.from, request.to).await
+    }
+}
+
+// Request/Response DTOs (Data Transfer Objects)
+
+#[derive(Debug, Clone)]
+pub struct RecordListenRequest {
+    pub listen: Listen,
+    /// Also queue the listen for submission to a scrobbling service
+    pub scrobble: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordListenResponse {
+    pub listen_id: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDueScrobblesRequest {
+    pub now: DateTime<Utc>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDueScrobblesResponse {
+    pub scrobbles: Vec<PendingScrobble>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SettleScrobblesRequest {
+    Submitted { scrobbles: Vec<PendingScrobble> },
+    Failed { scrobbles: Vec<PendingScrobble>, now: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone)]
+pub struct GetListensRequest {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetListensResponse {
+    pub listens: Vec<Listen>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SummarizeListensRequest {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let delays: Vec<i64> = (1..=5).map(|attempts| retry_delay(attempts).num_seconds()).collect();
+        assert_eq!(delays, [30, 60, 120, 240, 480]);
+        assert_eq!(retry_delay(20).num_seconds(), RETRY_MAX_SECONDS);
+        assert_eq!(retry_delay(u32::MAX).num_seconds(), RETRY_MAX_SECONDS);
+    }
+}
@@ -1,12 +1,17 @@
+//! Use cases module
+//!
+//! Contains all application use cases that represent the business workflows
+//! and application-specific business rules.
+
 #![allow(dead_code)]
-/// Use cases module
-/// 
-/// Contains all application use cases that represent the business workflows
-/// and application-specific business rules.
 
 pub mod song_management;
 pub mod playlist_management;
+pub mod listen_history;
+pub mod bookmark_management;
 
 // Re-export for convenience
 pub use song_management::*;
 pub use playlist_management::*;
+pub use listen_history::*;
+pub use bookmark_management::*;
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
-use crate::domain::entities::Playlist;
+use crate::domain::entities::{Playlist, PlaylistSetOperation};
 use crate::domain::repositories::{PlaylistRepository, SongRepository, PlaylistSongRepository};
-use crate::domain::value_objects::{PlaylistId, SongId};
+use crate::domain::value_objects::{Duration, PlaylistId, SongId};
 use crate::shared::errors::{ApplicationError, Result};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Use case for creating a new playlist
@@ -37,6 +38,57 @@ impl CreatePlaylistUseCase {
     }
 }
 
+/// Use case for listing all playlists
+pub struct ListPlaylistsUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl ListPlaylistsUseCase {
+    pub fn new(playlist_repository: Arc<dyn PlaylistRepository>) -> Self {
+        Self { playlist_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<ListPlaylistsResponse> {
+        let playlists = self.playlist_repository.find_all().await?;
+        Ok(ListPlaylistsResponse { playlists })
+    }
+}
+
+/// Use case for looking up a playlist by its name
+pub struct FindPlaylistByNameUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl FindPlaylistByNameUseCase {
+    pub fn new(playlist_repository: Arc<dyn PlaylistRepository>) -> Self {
+        Self { playlist_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: FindPlaylistByNameRequest) -> Result<FindPlaylistByNameResponse> {
+        let playlist = self.playlist_repository.find_by_name(&request.name).await?;
+        Ok(FindPlaylistByNameResponse { playlist })
+    }
+}
+
+/// Use case for finding the playlists a song is in
+pub struct FindPlaylistsWithSongUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl FindPlaylistsWithSongUseCase {
+    pub fn new(playlist_repository: Arc<dyn PlaylistRepository>) -> Self {
+        Self { playlist_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: FindPlaylistsWithSongRequest) -> Result<FindPlaylistsWithSongResponse> {
+        let playlists = self.playlist_repository.find_by_song(&request.song_id).await?;
+        Ok(FindPlaylistsWithSongResponse { playlists })
+    }
+}
+
 /// Use case for adding songs to a playlist
 pub struct AddSongToPlaylistUseCase {
     playlist_repository: Arc<dyn PlaylistRepository>,
@@ -94,6 +146,53 @@ impl AddSongToPlaylistUseCase {
     }
 }
 
+/// Use case for adding many songs to a playlist at once
+pub struct AddSongsToPlaylistUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+    playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+}
+
+impl AddSongsToPlaylistUseCase {
+    pub fn new(
+        playlist_repository: Arc<dyn PlaylistRepository>,
+        playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+    ) -> Self {
+        Self {
+            playlist_repository,
+            playlist_song_repository,
+        }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: AddSongsToPlaylistRequest) -> Result<AddSongsToPlaylistResponse> {
+        let mut playlist = self.playlist_repository
+            .find_by_id(&request.playlist_id)
+            .await?
+            .ok_or_else(|| ApplicationError::UseCaseFailed(
+                format!("Playlist not found: {}", request.playlist_id.as_str())
+            ))?;
+
+        // Songs already in the playlist are skipped rather than failing the batch
+        let mut added = Vec::new();
+        for song_id in request.song_ids {
+            if !playlist.contains_song(&song_id) {
+                playlist.add_song(song_id.clone()).map_err(ApplicationError::Domain)?;
+                added.push(song_id);
+            }
+        }
+        if added.is_empty() {
+            return Ok(AddSongsToPlaylistResponse { added });
+        }
+
+        self.playlist_song_repository
+            .append_songs(&request.playlist_id, &added)
+            .await?;
+        self.playlist_repository.save(&playlist).await?;
+
+        Ok(AddSongsToPlaylistResponse { added })
+    }
+}
+
 /// Use case for removing songs from a playlist
 pub struct RemoveSongFromPlaylistUseCase {
     playlist_repository: Arc<dyn PlaylistRepository>,
@@ -179,6 +278,25 @@ impl GetPlaylistWithSongsUseCase {
     }
 }
 
+/// Use case for totalling up how long each playlist plays
+pub struct GetPlaylistDurationsUseCase {
+    playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+}
+
+impl GetPlaylistDurationsUseCase {
+    pub fn new(playlist_song_repository: Arc<dyn PlaylistSongRepository>) -> Self {
+        Self { playlist_song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<GetPlaylistDurationsResponse> {
+        let durations = self.playlist_song_repository.get_playlist_durations().await?;
+        Ok(GetPlaylistDurationsResponse {
+            durations: durations.into_iter().collect(),
+        })
+    }
+}
+
 /// Use case for deleting a playlist
 pub struct DeletePlaylistUseCase {
     playlist_repository: Arc<dyn PlaylistRepository>,
@@ -222,6 +340,122 @@ impl DeletePlaylistUseCase {
     }
 }
 
+/// Use case for putting a playlist's songs in a new order
+pub struct ReorderPlaylistUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+    playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+}
+
+impl ReorderPlaylistUseCase {
+    pub fn new(
+        playlist_repository: Arc<dyn PlaylistRepository>,
+        playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+    ) -> Self {
+        Self {
+            playlist_repository,
+            playlist_song_repository,
+        }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: ReorderPlaylistRequest) -> Result<ReorderPlaylistResponse> {
+        let playlist = self.playlist_repository
+            .find_by_id(&request.playlist_id)
+            .await?
+            .ok_or_else(|| ApplicationError::UseCaseFailed(
+                format!("Playlist not found: {}", request.playlist_id.as_str())
+            ))?;
+
+        // The new order must hold exactly the songs already in the playlist
+        let mut current: Vec<&SongId> = playlist.song_ids().iter().collect();
+        let mut requested: Vec<&SongId> = request.song_ids.iter().collect();
+        current.sort_by_key(|id| id.as_str());
+        requested.sort_by_key(|id| id.as_str());
+        if current != requested {
+            return Err(ApplicationError::UseCaseFailed(
+                format!("New order does not match the songs of playlist {}", request.playlist_id.as_str())
+            ));
+        }
+
+        self.playlist_song_repository
+            .reorder_playlist_songs(&request.playlist_id, &request.song_ids)
+            .await?;
+
+        Ok(ReorderPlaylistResponse {
+            playlist_id: request.playlist_id,
+        })
+    }
+}
+
+/// Use case for changing the description of a playlist
+pub struct DescribePlaylistUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl DescribePlaylistUseCase {
+    pub fn new(playlist_repository: Arc<dyn PlaylistRepository>) -> Self {
+        Self { playlist_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: DescribePlaylistRequest) -> Result<()> {
+        let mut playlist = self.playlist_repository
+            .find_by_id(&request.playlist_id)
+            .await?
+            .ok_or_else(|| ApplicationError::UseCaseFailed(
+                format!("Playlist not found: {}", request.playlist_id.as_str())
+            ))?;
+
+        let name = playlist.name().to_string();
+        playlist.update_metadata(name, request.description)?;
+        self.playlist_repository.save(&playlist).await
+    }
+}
+
+/// Use case for merging, intersecting or subtracting two playlists into a new one
+pub struct CombinePlaylistsUseCase {
+    playlist_repository: Arc<dyn PlaylistRepository>,
+}
+
+impl CombinePlaylistsUseCase {
+    pub fn new(playlist_repository: Arc<dyn PlaylistRepository>) -> Self {
+        Self { playlist_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: CombinePlaylistsRequest) -> Result<CombinePlaylistsResponse> {
+        let mut sources = Vec::new();
+        for name in [&request.first, &request.second] {
+            let playlist = self.playlist_repository
+                .find_by_name(name)
+                .await?
+                .ok_or_else(|| ApplicationError::UseCaseFailed(
+                    format!("Playlist not found: {}", name)
+                ))?;
+            sources.push(playlist);
+        }
+
+        if self.playlist_repository.exists_by_name(&request.into).await? {
+            return Err(ApplicationError::ValidationFailed(
+                format!("Playlist '{}' already exists", request.into)
+            ));
+        }
+
+        let mut playlist = Playlist::new(request.into, None)
+            .map_err(ApplicationError::Domain)?;
+        for song_id in request.operation.apply(sources[0].song_ids(), sources[1].song_ids()) {
+            playlist.add_song(song_id).map_err(ApplicationError::Domain)?;
+        }
+
+        self.playlist_repository.create_with_songs(&playlist).await?;
+
+        Ok(CombinePlaylistsResponse {
+            playlist_id: playlist.id().clone(),
+            song_count: playlist.song_count(),
+        })
+    }
+}
+
 // Request/Response DTOs
 
 #[derive(Debug, Clone)]
@@ -235,6 +469,31 @@ pub struct CreatePlaylistResponse {
     pub playlist_id: PlaylistId,
 }
 
+#[derive(Debug, Clone)]
+pub struct ListPlaylistsResponse {
+    pub playlists: Vec<Playlist>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindPlaylistByNameRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindPlaylistByNameResponse {
+    pub playlist: Option<Playlist>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindPlaylistsWithSongRequest {
+    pub song_id: SongId,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindPlaylistsWithSongResponse {
+    pub playlists: Vec<Playlist>,
+}
+
 #[derive(Debug, Clone)]
 pub struct AddSongToPlaylistRequest {
     pub playlist_id: PlaylistId,
@@ -247,6 +506,18 @@ pub struct AddSongToPlaylistResponse {
     pub song_id: SongId,
 }
 
+#[derive(Debug, Clone)]
+pub struct AddSongsToPlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub song_ids: Vec<SongId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AddSongsToPlaylistResponse {
+    /// The songs that were not in the playlist yet, in the order added
+    pub added: Vec<SongId>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RemoveSongFromPlaylistRequest {
     pub playlist_id: PlaylistId,
@@ -270,6 +541,11 @@ pub struct GetPlaylistWithSongsResponse {
     pub songs: Vec<crate::domain::entities::Song>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GetPlaylistDurationsResponse {
+    pub durations: HashMap<PlaylistId, Duration>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DeletePlaylistRequest {
     pub playlist_id: PlaylistId,
@@ -279,3 +555,34 @@ pub struct DeletePlaylistRequest {
 pub struct DeletePlaylistResponse {
     pub playlist_id: PlaylistId,
 }
+
+#[derive(Debug, Clone)]
+pub struct ReorderPlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub song_ids: Vec<SongId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReorderPlaylistResponse {
+    pub playlist_id: PlaylistId,
+}
+
+#[derive(Debug, Clone)]
+pub struct DescribePlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CombinePlaylistsRequest {
+    pub operation: PlaylistSetOperation,
+    pub first: String,
+    pub second: String,
+    pub into: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CombinePlaylistsResponse {
+    pub playlist_id: PlaylistId,
+    pub song_count: usize,
+}
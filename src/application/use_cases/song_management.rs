@@ -1,8 +1,12 @@
 #![allow(dead_code)]
-use crate::domain::entities::Song;
-use crate::domain::repositories::SongRepository;
-use crate::domain::value_objects::{SongId, FilePath, Duration};
+use crate::domain::entities::{ImportedStats, Song, SongStats, SortKeys};
+use crate::domain::repositories::{PlaylistRepository, PlaylistSongRepository, SongRepository};
+use crate::domain::value_objects::{SongId, FilePath, Duration, Trim};
 use crate::shared::errors::{ApplicationError, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Use case for adding a new song to the library
@@ -18,9 +22,15 @@ impl AddSongUseCase {
     /// Execute the use case to add a song
     pub async fn execute(&self, request: AddSongRequest) -> Result<AddSongResponse> {
         // Check if song already exists
-        if self.song_repository.exists_by_path(&request.file_path).await? {
+        if let Some(existing) = self.song_repository.find_by_path(&request.file_path).await? {
+            // Songs scanned before genres were kept pick theirs up on a rescan
+            let existing_id = existing.id().clone();
+            let updated = existing.clone().with_genre(request.genre);
+            if updated.genre().is_some() && updated.genre() != existing.genre() {
+                self.song_repository.save(&updated).await?;
+            }
             return Ok(AddSongResponse {
-                song_id: SongId::from_path(&request.file_path),
+                song_id: existing_id,
                 was_created: false,
             });
         }
@@ -32,7 +42,8 @@ impl AddSongUseCase {
             request.artist,
             request.album,
             request.duration,
-        ).map_err(ApplicationError::Domain)?;
+        ).map_err(ApplicationError::Domain)?
+            .with_genre(request.genre);
 
         // Save to repository
         self.song_repository.save(&song).await?;
@@ -66,6 +77,38 @@ impl SearchSongsUseCase {
     }
 }
 
+/// Use case for reading the library, or its songs matching a search, a
+/// page at a time
+pub struct GetSongPageUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetSongPageUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetSongPageRequest) -> Result<GetSongPageResponse> {
+        let songs = self.song_repository
+            .find_range(&request.query, request.offset, request.limit)
+            .await?;
+
+        Ok(GetSongPageResponse { songs })
+    }
+
+    /// Count the songs the pages for the lowered search `query` hold
+    pub async fn count(&self, query: &str) -> Result<usize> {
+        self.song_repository.count(query).await
+    }
+
+    /// Find the position of the song at `path` among the songs the pages
+    /// for the lowered search `query` hold
+    pub async fn position_of(&self, query: &str, path: &FilePath) -> Result<Option<usize>> {
+        self.song_repository.find_position(query, path).await
+    }
+}
+
 /// Use case for getting song details
 pub struct GetSongUseCase {
     song_repository: Arc<dyn SongRepository>,
@@ -89,14 +132,47 @@ impl GetSongUseCase {
     }
 }
 
+/// Use case for looking up the songs of the library among a set of IDs
+pub struct GetSongsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetSongsUseCase {
+    /// IDs looked up per query, well under SQLite's limit on parameters
+    const CHUNK: usize = 500;
+
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case, leaving out IDs of songs not in the library
+    pub async fn execute(&self, song_ids: &[SongId]) -> Result<Vec<Song>> {
+        let mut songs = Vec::new();
+        for chunk in song_ids.chunks(Self::CHUNK) {
+            songs.extend(self.song_repository.find_by_ids(chunk).await?);
+        }
+        Ok(songs)
+    }
+}
+
 /// Use case for removing a song from the library
 pub struct RemoveSongUseCase {
     song_repository: Arc<dyn SongRepository>,
+    playlist_repository: Arc<dyn PlaylistRepository>,
+    playlist_song_repository: Arc<dyn PlaylistSongRepository>,
 }
 
 impl RemoveSongUseCase {
-    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
-        Self { song_repository }
+    pub fn new(
+        song_repository: Arc<dyn SongRepository>,
+        playlist_repository: Arc<dyn PlaylistRepository>,
+        playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+    ) -> Self {
+        Self {
+            song_repository,
+            playlist_repository,
+            playlist_song_repository,
+        }
     }
 
     /// Execute the use case
@@ -109,6 +185,20 @@ impl RemoveSongUseCase {
                 format!("Song not found: {}", request.song_id.as_str())
             ))?;
 
+        // Remove from every playlist that contains it
+        for mut playlist in self.playlist_repository.find_all().await? {
+            if !playlist.contains_song(&request.song_id) {
+                continue;
+            }
+
+            playlist.remove_song(&request.song_id)
+                .map_err(ApplicationError::Domain)?;
+            self.playlist_repository.save(&playlist).await?;
+            self.playlist_song_repository
+                .remove_song_from_playlist(playlist.id(), &request.song_id)
+                .await?;
+        }
+
         // Remove from repository
         self.song_repository.delete(&request.song_id).await?;
 
@@ -118,6 +208,305 @@ impl RemoveSongUseCase {
     }
 }
 
+/// Use case for listing songs added to the library recently
+pub struct GetRecentlyAddedSongsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetRecentlyAddedSongsUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetRecentlyAddedSongsRequest) -> Result<GetRecentlyAddedSongsResponse> {
+        let songs = self.song_repository.find_added_since(request.since).await?;
+        Ok(GetRecentlyAddedSongsResponse { songs })
+    }
+}
+
+/// Use case for moving a song to a new file path
+pub struct RelocateSongUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl RelocateSongUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: RelocateSongRequest) -> Result<RelocateSongResponse> {
+        // Refuse to merge into another song's row
+        if self.song_repository.find_by_path(&request.new_path).await?.is_some() {
+            return Err(ApplicationError::UseCaseFailed(
+                format!("A song already exists at {}", request.new_path.as_str())
+            ));
+        }
+
+        let song = self.song_repository
+            .relocate(&request.song_id, &request.new_path)
+            .await?;
+
+        Ok(RelocateSongResponse { song })
+    }
+}
+
+/// Use case for counting a song play
+pub struct RecordPlayUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl RecordPlayUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: RecordPlayRequest) -> Result<()> {
+        self.song_repository.record_play(&request.song_id).await
+    }
+}
+
+/// Use case for marking a song as loved or no longer loved
+pub struct SetLovedUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl SetLovedUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: SetLovedRequest) -> Result<()> {
+        self.song_repository.set_loved(&request.song_id, request.loved).await
+    }
+}
+
+/// Use case for trimming the start and end off a song
+pub struct SetTrimUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl SetTrimUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: SetTrimRequest) -> Result<()> {
+        let song = self.song_repository
+            .find_by_id(&request.song_id)
+            .await?
+            .ok_or_else(|| ApplicationError::UseCaseFailed(
+                format!("Song not found: {}", request.song_id.as_str())
+            ))?;
+
+        // Business rule: Some of the song is left to play
+        if !request.trim.leaves_some_of(*song.duration()) {
+            return Err(ApplicationError::ValidationFailed(
+                format!("Trimming {}s and {}s leaves nothing of '{}' to play",
+                    request.trim.start().total_seconds(), request.trim.end().total_seconds(), song.title())
+            ));
+        }
+
+        self.song_repository.set_trim(&request.song_id, request.trim).await
+    }
+}
+
+/// Use case for looking up how much is trimmed off a song
+pub struct GetTrimUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetTrimUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case; songs not in the library play whole
+    pub async fn execute(&self, song_id: &SongId) -> Result<Trim> {
+        Ok(self.song_repository.find_trim(song_id).await?.unwrap_or_default())
+    }
+}
+
+/// Use case for listing the loved songs
+pub struct GetLovedSongsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetLovedSongsUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<Vec<Song>> {
+        self.song_repository.find_loved().await
+    }
+}
+
+/// Use case for merging listening stats brought over from another player
+pub struct ImportStatsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl ImportStatsUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: ImportStatsRequest) -> Result<()> {
+        if request.stats.rating.is_some_and(|stars| !(1..=5).contains(&stars)) {
+            return Err(ApplicationError::ValidationFailed("Rating must be from 1 to 5 stars".to_string()));
+        }
+        self.song_repository.import_stats(&request.song_id, &request.stats).await
+    }
+}
+
+/// Use case for listing every song with how it has been listened to
+pub struct GetSongStatsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetSongStatsUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<Vec<SongStats>> {
+        self.song_repository.find_all_with_stats().await
+    }
+}
+
+/// Use case for looking up what every song's artist and album sort by
+pub struct GetSortKeysUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetSortKeysUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<HashMap<SongId, SortKeys>> {
+        self.song_repository.find_sort_keys().await
+    }
+}
+
+/// Use case for listing the songs of one album
+pub struct GetAlbumUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetAlbumUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+        self.song_repository.find_album(artist, album).await
+    }
+}
+
+/// Use case for finding the album that follows a song's album
+pub struct GetNextAlbumUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetNextAlbumUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, id: &SongId) -> Result<Vec<Song>> {
+        self.song_repository.find_next_album(id).await
+    }
+}
+
+/// Use case for listing songs by how often they were played
+pub struct GetPlayStatsSongsUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetPlayStatsSongsUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetPlayStatsSongsRequest) -> Result<GetPlayStatsSongsResponse> {
+        let songs = match request {
+            GetPlayStatsSongsRequest::MostPlayed { limit } => self.song_repository.find_most_played(limit).await?,
+            GetPlayStatsSongsRequest::NeverPlayed => self.song_repository.find_never_played().await?,
+        };
+
+        Ok(GetPlayStatsSongsResponse { songs })
+    }
+}
+
+/// Use case for picking the songs of a day's Daily Mix
+///
+/// Songs are sampled without replacement, weighted by `SongStats::mix_weight`.
+/// The random draw is seeded with the day, so the mix stays the same for the
+/// whole day unless the library changes.
+pub struct GetDailyMixUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl GetDailyMixUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self, request: GetDailyMixRequest) -> Result<GetDailyMixResponse> {
+        let stats = self.song_repository.find_all_with_stats().await?;
+        let now = request.day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let mut rng = StdRng::seed_from_u64(request.day.num_days_from_ce() as u64);
+
+        // Weighted sampling (Efraimidis-Spirakis): keep the largest u^(1/w),
+        // compared as ln(u) / w to stay clear of underflow
+        let mut keyed: Vec<(f64, SongStats)> = stats.into_iter()
+            .map(|stats| {
+                let weight = stats.mix_weight(now);
+                let roll: f64 = rng.random_range(f64::MIN_POSITIVE..1.0);
+                (roll.ln() / weight, stats)
+            })
+            .collect();
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let songs = keyed.into_iter()
+            .take(request.size)
+            .map(|(_, stats)| stats.song)
+            .collect();
+
+        Ok(GetDailyMixResponse { songs })
+    }
+}
+
+/// Use case for clearing the whole library (used by forced rescans)
+pub struct ClearLibraryUseCase {
+    song_repository: Arc<dyn SongRepository>,
+}
+
+impl ClearLibraryUseCase {
+    pub fn new(song_repository: Arc<dyn SongRepository>) -> Self {
+        Self { song_repository }
+    }
+
+    /// Execute the use case
+    pub async fn execute(&self) -> Result<()> {
+        self.song_repository.clear_all().await
+    }
+}
+
 // Request/Response DTOs (Data Transfer Objects)
 
 #[derive(Debug, Clone)]
@@ -127,6 +516,7 @@ pub struct AddSongRequest {
     pub artist: String,
     pub album: String,
     pub duration: Duration,
+    pub genre: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -145,6 +535,86 @@ pub struct SearchSongsResponse {
     pub songs: Vec<Song>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GetSongPageRequest {
+    /// Lowered search the songs match, or empty for every song
+    pub query: String,
+    /// Position in library order of the first song of the page
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetSongPageResponse {
+    pub songs: Vec<Song>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetRecentlyAddedSongsRequest {
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetRecentlyAddedSongsResponse {
+    pub songs: Vec<Song>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelocateSongRequest {
+    pub song_id: SongId,
+    pub new_path: FilePath,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelocateSongResponse {
+    pub song: Song,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordPlayRequest {
+    pub song_id: SongId,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetLovedRequest {
+    pub song_id: SongId,
+    pub loved: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetTrimRequest {
+    pub song_id: SongId,
+    pub trim: Trim,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportStatsRequest {
+    pub song_id: SongId,
+    pub stats: ImportedStats,
+}
+
+#[derive(Debug, Clone)]
+pub enum GetPlayStatsSongsRequest {
+    MostPlayed { limit: usize },
+    NeverPlayed,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetPlayStatsSongsResponse {
+    pub songs: Vec<Song>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDailyMixRequest {
+    pub day: NaiveDate,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDailyMixResponse {
+    pub songs: Vec<Song>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetSongRequest {
     pub song_id: SongId,
@@ -168,6 +638,7 @@ pub struct RemoveSongResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{DateTime, Utc};
     use crate::domain::repositories::SongRepository;
     use async_trait::async_trait;
     use std::collections::HashMap;
@@ -209,6 +680,21 @@ mod tests {
             Ok(songs.values().cloned().collect())
         }
 
+        async fn find_range(&self, query: &str, offset: usize, limit: usize) -> Result<Vec<Song>> {
+            let mut songs = if query.is_empty() { self.find_all().await? } else { self.search(query).await? };
+            songs.sort_by(|a, b| a.id().as_str().cmp(b.id().as_str()));
+            Ok(songs.into_iter().skip(offset).take(limit).collect())
+        }
+
+        async fn find_position(&self, query: &str, path: &FilePath) -> Result<Option<usize>> {
+            let songs = self.find_range(query, 0, usize::MAX).await?;
+            Ok(songs.iter().position(|song| song.file_path() == path))
+        }
+
+        async fn count(&self, query: &str) -> Result<usize> {
+            Ok(self.find_range(query, 0, usize::MAX).await?.len())
+        }
+
         async fn search(&self, query: &str) -> Result<Vec<Song>> {
             let songs = self.songs.lock().unwrap();
             let query_lower = query.to_lowercase();
@@ -239,6 +725,89 @@ mod tests {
                 .collect())
         }
 
+        async fn find_added_since(&self, _since: DateTime<Utc>) -> Result<Vec<Song>> {
+            // The mock does not track insertion times
+            self.find_all().await
+        }
+
+        async fn relocate(&self, id: &SongId, new_path: &FilePath) -> Result<Song> {
+            let mut songs = self.songs.lock().unwrap();
+            let song = songs.remove(id).ok_or_else(|| ApplicationError::Repository("Song not found".to_string()))?;
+            let relocated = Song::new(
+                new_path.clone(),
+                song.title().to_string(),
+                song.artist().to_string(),
+                song.album().to_string(),
+                *song.duration(),
+            ).map_err(ApplicationError::Domain)?;
+            songs.insert(relocated.id().clone(), relocated.clone());
+            Ok(relocated)
+        }
+
+        async fn record_play(&self, _id: &SongId) -> Result<()> {
+            Ok(())
+        }
+
+        async fn set_loved(&self, _id: &SongId, _loved: bool) -> Result<()> {
+            Ok(())
+        }
+
+        async fn set_trim(&self, _id: &SongId, _trim: Trim) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_trim(&self, _id: &SongId) -> Result<Option<Trim>> {
+            Ok(None)
+        }
+
+        async fn find_loved(&self) -> Result<Vec<Song>> {
+            // The mock does not track loved songs
+            Ok(Vec::new())
+        }
+
+        async fn import_stats(&self, _id: &SongId, _stats: &ImportedStats) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_most_played(&self, _limit: usize) -> Result<Vec<Song>> {
+            Ok(Vec::new())
+        }
+
+        async fn find_never_played(&self) -> Result<Vec<Song>> {
+            // The mock does not track plays
+            self.find_all().await
+        }
+
+        async fn find_all_with_stats(&self) -> Result<Vec<SongStats>> {
+            // The mock does not track plays, and every song counts as new
+            let mut songs = self.find_all().await?;
+            songs.sort_by(|a, b| a.id().as_str().cmp(b.id().as_str()));
+            Ok(songs.into_iter()
+                .map(|song| SongStats { song, play_count: 0, last_played_at: None, added_at: Utc::now(), rating: None })
+                .collect())
+        }
+
+        async fn find_sort_keys(&self) -> Result<HashMap<SongId, SortKeys>> {
+            // The mock ignores no articles
+            let songs = self.songs.lock().unwrap();
+            Ok(songs.values()
+                .map(|song| (song.id().clone(), SortKeys { artist: song.artist().to_string(), album: song.album().to_string() }))
+                .collect())
+        }
+
+        async fn find_album(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+            let songs = self.songs.lock().unwrap();
+            Ok(songs.values()
+                .filter(|song| song.artist().eq_ignore_ascii_case(artist) && song.album().eq_ignore_ascii_case(album))
+                .cloned()
+                .collect())
+        }
+
+        async fn find_next_album(&self, _id: &SongId) -> Result<Vec<Song>> {
+            // The mock never goes on to another album
+            Ok(Vec::new())
+        }
+
         async fn clear_all(&self) -> Result<()> {
             let mut songs = self.songs.lock().unwrap();
             songs.clear();
@@ -249,7 +818,7 @@ mod tests {
     #[tokio::test]
     async fn test_add_song_use_case() {
         let repository = Arc::new(MockSongRepository::new());
-        let use_case = AddSongUseCase::new(repository);
+        let use_case = AddSongUseCase::new(repository.clone());
 
         let request = AddSongRequest {
             file_path: FilePath::new("/test/song.mp3").unwrap(),
@@ -257,10 +826,17 @@ mod tests {
             artist: "Test Artist".to_string(),
             album: "Test Album".to_string(),
             duration: Duration::from_seconds(180),
+            genre: None,
         };
 
-        let response = use_case.execute(request).await.unwrap();
+        let response = use_case.execute(request.clone()).await.unwrap();
         assert!(response.was_created);
+
+        // Adding it again only fills in a genre it was missing
+        let response = use_case.execute(AddSongRequest { genre: Some("Jazz".to_string()), ..request }).await.unwrap();
+        assert!(!response.was_created);
+        let song = repository.find_by_id(&response.song_id).await.unwrap().unwrap();
+        assert_eq!(song.genre(), Some("Jazz"));
     }
 
     #[tokio::test]
@@ -276,6 +852,7 @@ mod tests {
             artist: "Test Artist".to_string(),
             album: "Test Album".to_string(),
             duration: Duration::from_seconds(180),
+            genre: None,
         };
         use_case_add.execute(add_request).await.unwrap();
 
@@ -287,4 +864,42 @@ mod tests {
         assert_eq!(response.songs.len(), 1);
         assert_eq!(response.songs[0].title(), "Test Song");
     }
+
+    #[tokio::test]
+    async fn test_daily_mix_use_case() {
+        let repository = Arc::new(MockSongRepository::new());
+        let add = AddSongUseCase::new(repository.clone());
+        for i in 0..20 {
+            add.execute(AddSongRequest {
+                file_path: FilePath::new(&format!("/test/{}.mp3", i)).unwrap(),
+                title: format!("Song {}", i),
+                artist: "Artist".to_string(),
+                album: "Album".to_string(),
+                duration: Duration::from_seconds(180),
+                genre: None,
+            }).await.unwrap();
+        }
+
+        let use_case = GetDailyMixUseCase::new(repository);
+        let mix = |day: u32, size| {
+            let request = GetDailyMixRequest { day: NaiveDate::from_ymd_opt(2024, 5, day).unwrap(), size };
+            let use_case = &use_case;
+            async move {
+                let songs = use_case.execute(request).await.unwrap().songs;
+                songs.iter().map(|song| song.id().clone()).collect::<Vec<_>>()
+            }
+        };
+
+        let today = mix(1, 5).await;
+        assert_eq!(today.len(), 5);
+        assert_eq!(mix(1, 5).await, today, "the mix holds for the whole day");
+        assert_ne!(mix(2, 5).await, today, "the mix rotates the next day");
+
+        // Every song appears once when the mix is larger than the library
+        let mut everything = mix(1, 50).await;
+        assert_eq!(everything.len(), 20);
+        everything.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        everything.dedup();
+        assert_eq!(everything.len(), 20);
+    }
 }
@@ -1,10 +1,16 @@
+use crate::config::paths::{self, Location};
 use crate::error::{LofiTurtleError, Result};
 use image::{self, GenericImageView};
-use lofty::{prelude::*, probe::Probe};
-use std::collections::HashMap;
+use lofty::config::WriteOptions;
+use lofty::picture::{MimeType, Picture, PictureType};
+use lofty::tag::Tag;
+use lofty::{file::TaggedFile, prelude::*, probe::Probe};
+use lru::LruCache;
 use std::error::Error;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// ASCII characters for different brightness levels (darkest to brightest)
 const ASCII_CHARS: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
@@ -18,8 +24,243 @@ struct AsciiArtCacheKey {
     use_color: bool,
 }
 
-/// Global cache for ASCII art to avoid regeneration
-type AsciiArtCache = Arc<Mutex<HashMap<AsciiArtCacheKey, String>>>;
+/// Default number of rendered pieces of art kept in memory
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 256;
+
+/// Default total size of the rendered art kept in memory, in bytes
+pub const DEFAULT_CACHE_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// Counters describing how well the ASCII art cache is doing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArtCacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl fmt::Display for ArtCacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entries, {} bytes, {} hits, {} misses, {} evictions",
+            self.entries, self.bytes, self.hits, self.misses, self.evictions
+        )
+    }
+}
+
+/// Cache for ASCII art to avoid regeneration
+///
+/// Bounded by entry count and total size, evicting the least recently used
+/// art first, so long sessions over big libraries stay within the caps.
+struct AsciiArtCache {
+    entries: LruCache<AsciiArtCacheKey, String>,
+    max_entries: usize,
+    max_bytes: usize,
+    stats: ArtCacheStats,
+}
+
+impl AsciiArtCache {
+    fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            max_entries,
+            max_bytes,
+            stats: ArtCacheStats::default(),
+        }
+    }
+
+    fn get(&mut self, key: &AsciiArtCacheKey) -> Option<String> {
+        match self.entries.get(key) {
+            Some(art) => {
+                self.stats.hits += 1;
+                Some(art.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: AsciiArtCacheKey, art: String) {
+        // Art that could never fit is not worth evicting everything else for
+        if self.max_entries == 0 || art.len() > self.max_bytes {
+            return;
+        }
+
+        self.stats.bytes += art.len();
+        if let Some(old) = self.entries.put(key, art) {
+            self.stats.bytes -= old.len();
+        }
+
+        while self.entries.len() > self.max_entries || self.stats.bytes > self.max_bytes {
+            let Some((_, evicted)) = self.entries.pop_lru() else { break };
+            self.stats.bytes -= evicted.len();
+            self.stats.evictions += 1;
+        }
+        self.stats.entries = self.entries.len();
+    }
+
+    fn stats(&self) -> ArtCacheStats {
+        self.stats
+    }
+}
+
+/// Covers of albums, kept on disk by album artist and album: downloaded for
+/// albums without art of their own, or taken by a library scan from the art
+/// one of the album's songs has
+///
+/// Only the album art panel shows them, for the song that is playing.
+#[derive(Debug, Clone)]
+pub struct CoverCache {
+    dir: PathBuf,
+}
+
+impl CoverCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Where covers are kept unless another directory is given
+    pub fn default_dir() -> PathBuf {
+        paths::default_path(Location::Cache, "covers")
+    }
+
+    /// The cover of an album, if one was downloaded
+    pub fn get(&self, artist: &str, album: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(artist, album)).ok()
+    }
+
+    pub fn contains(&self, artist: &str, album: &str) -> bool {
+        self.path(artist, album).exists()
+    }
+
+    /// Keep `image` as the cover of an album
+    pub fn store(&self, artist: &str, album: &str, image: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(artist, album), image)?;
+        Ok(())
+    }
+
+    /// Keep the art embedded in the file at `path` as the cover of its album,
+    /// unless the album has one already, so its songs without art of their
+    /// own show it too
+    ///
+    /// Returns whether the album has a cover now.
+    pub fn cache_embedded(&self, path: &Path) -> Result<bool> {
+        let tagged_file = probe(path)?;
+        let Some((artist, album)) = album_of(&tagged_file) else {
+            return Ok(false);
+        };
+        if self.contains(&artist, &album) {
+            return Ok(true);
+        }
+        match embedded_picture(&tagged_file) {
+            Some(picture) => {
+                self.store(&artist, &album, &picture)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Albums differing only in case or surrounding spaces share a cover
+    fn path(&self, artist: &str, album: &str) -> PathBuf {
+        let key = format!("{}\0{}", artist.trim().to_lowercase(), album.trim().to_lowercase());
+        self.dir.join(format!("{:x}", md5::compute(key)))
+    }
+}
+
+/// What the tags of a file say about the cover of its album
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoverTags {
+    /// Album artist, or the artist if there is none
+    pub artist: String,
+    pub album: String,
+    /// MusicBrainz release the file was tagged from
+    pub release_id: Option<String>,
+    /// Whether the file has art of its own
+    pub embedded: bool,
+}
+
+impl CoverTags {
+    pub fn read(path: &Path) -> Result<Self> {
+        let tagged_file = probe(path)?;
+        let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag());
+        let text = |key: ItemKey| tag.and_then(|tag| tag.get_string(&key)).map(str::trim).filter(|text| !text.is_empty());
+        let (artist, album) = album_of(&tagged_file).unwrap_or_default();
+
+        Ok(Self {
+            artist,
+            album,
+            release_id: text(ItemKey::MusicBrainzReleaseId).map(str::to_string),
+            embedded: embedded_picture(&tagged_file).is_some(),
+        })
+    }
+}
+
+/// Make `image` the front cover in the primary tag of a file, replacing the
+/// cover it had
+///
+/// A tag is created if the file has none.
+pub fn embed_cover(path: &Path, image: &[u8]) -> Result<()> {
+    let mime_type = cover_mime_type(image)?;
+    let mut tagged_file = probe(path)?;
+
+    let tag_type = tagged_file.primary_tag_type();
+    if tagged_file.primary_tag().is_none() {
+        tagged_file.insert_tag(Tag::new(tag_type));
+    }
+    let Some(tag) = tagged_file.primary_tag_mut() else {
+        return Err(LofiTurtleError::UnsupportedFormat(format!("Cannot write tags to '{}'", path.display())));
+    };
+
+    tag.remove_picture_type(PictureType::CoverFront);
+    tag.push_picture(Picture::new_unchecked(PictureType::CoverFront, Some(mime_type), None, image.to_vec()));
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| LofiTurtleError::FileSystem(std::io::Error::other(format!("Failed to write tags to '{}': {}", path.display(), e))))
+}
+
+/// Players only read JPEG and PNG covers reliably
+pub fn cover_mime_type(image: &[u8]) -> Result<MimeType> {
+    match image::guess_format(image) {
+        Ok(image::ImageFormat::Jpeg) => Ok(MimeType::Jpeg),
+        Ok(image::ImageFormat::Png) => Ok(MimeType::Png),
+        _ => Err(LofiTurtleError::UnsupportedFormat("Covers must be JPEG or PNG images".to_string())),
+    }
+}
+
+fn probe(path: &Path) -> Result<TaggedFile> {
+    Probe::open(path)
+        .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to probe file: {}", e)))?
+        .read()
+        .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to read file: {}", e)))
+}
+
+fn embedded_picture(tagged_file: &TaggedFile) -> Option<Vec<u8>> {
+    // Try the primary tag first, then the others
+    tagged_file
+        .primary_tag()
+        .into_iter()
+        .chain(tagged_file.tags())
+        .find_map(|tag| tag.pictures().first())
+        .map(|picture| picture.data().to_vec())
+}
+
+/// Album artist, or artist, and album of a file, if it names its album
+fn album_of(tagged_file: &TaggedFile) -> Option<(String, String)> {
+    let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag())?;
+    let album = tag.album()?.trim().to_string();
+    let artist = tag
+        .get_string(&ItemKey::AlbumArtist)
+        .map(str::to_string)
+        .or_else(|| tag.artist().map(|artist| artist.to_string()))?
+        .trim()
+        .to_string();
+    (!album.is_empty() && !artist.is_empty()).then_some((artist, album))
+}
 
 /// Configuration for album art display
 #[derive(Debug, Clone)]
@@ -28,6 +269,12 @@ pub struct AlbumArtConfig {
     pub height: u32,
     pub show_art: bool,
     pub use_color: bool,
+    /// Most pieces of rendered art kept in the cache
+    pub cache_max_entries: usize,
+    /// Most bytes of rendered art kept in the cache
+    pub cache_max_bytes: usize,
+    /// Downloaded covers shown for songs without art of their own
+    pub cover_dir: Option<PathBuf>,
 }
 
 impl Default for AlbumArtConfig {
@@ -37,6 +284,9 @@ impl Default for AlbumArtConfig {
             height: 20,
             show_art: true,
             use_color: false,
+            cache_max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            cache_max_bytes: DEFAULT_CACHE_MAX_BYTES,
+            cover_dir: None,
         }
     }
 }
@@ -56,6 +306,9 @@ pub struct AlbumArtConfigBuilder {
     height: Option<u32>,
     show_art: Option<bool>,
     use_color: Option<bool>,
+    cache_max_entries: Option<usize>,
+    cache_max_bytes: Option<usize>,
+    cover_dir: Option<PathBuf>,
 }
 
 impl AlbumArtConfigBuilder {
@@ -64,12 +317,30 @@ impl AlbumArtConfigBuilder {
         self
     }
 
+    pub fn cache_max_entries(mut self, max_entries: usize) -> Self {
+        self.cache_max_entries = Some(max_entries);
+        self
+    }
+
+    pub fn cache_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.cache_max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn cover_dir(mut self, dir: PathBuf) -> Self {
+        self.cover_dir = Some(dir);
+        self
+    }
+
     pub fn build(self) -> AlbumArtConfig {
         AlbumArtConfig {
             width: self.width.unwrap_or(40),
             height: self.height.unwrap_or(20),
             show_art: self.show_art.unwrap_or(false),
             use_color: self.use_color.unwrap_or(false),
+            cache_max_entries: self.cache_max_entries.unwrap_or(DEFAULT_CACHE_MAX_ENTRIES),
+            cache_max_bytes: self.cache_max_bytes.unwrap_or(DEFAULT_CACHE_MAX_BYTES),
+            cover_dir: self.cover_dir,
         }
     }
 }
@@ -78,40 +349,53 @@ impl AlbumArtConfigBuilder {
 pub struct AlbumArtRenderer {
     config: AlbumArtConfig,
     /// Performance optimization: Cache ASCII art to avoid regeneration
-    ascii_cache: AsciiArtCache,
+    ascii_cache: Mutex<AsciiArtCache>,
+    covers: Option<CoverCache>,
 }
 
 impl AlbumArtRenderer {
     /// Create a new album art renderer with the given configuration
     pub fn new(config: AlbumArtConfig) -> Self {
+        let ascii_cache = AsciiArtCache::new(config.cache_max_entries, config.cache_max_bytes);
+        let covers = config.cover_dir.clone().map(CoverCache::new);
         Self { 
             config,
-            ascii_cache: Arc::new(Mutex::new(HashMap::new())),
+            ascii_cache: Mutex::new(ascii_cache),
+            covers,
         }
     }
 
-    /// Extract album art from an audio file
+    /// Size and hit rate of the ASCII art cache
+    pub fn cache_stats(&self) -> ArtCacheStats {
+        self.ascii_cache.lock().map(|cache| cache.stats()).unwrap_or_default()
+    }
+
+    /// Extract album art from an audio file, or the downloaded cover of its
+    /// album if it has none
     pub fn extract_album_art<P: AsRef<Path>>(&self, file_path: P) -> Result<Option<Vec<u8>>> {
-        let tagged_file = Probe::open(file_path.as_ref())
-            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to probe file: {}", e)))?
-            .read()
-            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to read file: {}", e)))?;
-
-        // Try to get album art from the primary tag
-        if let Some(tag) = tagged_file.primary_tag() {
-            if let Some(picture) = tag.pictures().first() {
-                return Ok(Some(picture.data().to_vec()));
-            }
+        let tagged_file = probe(file_path.as_ref())?;
+        if let Some(picture) = embedded_picture(&tagged_file) {
+            return Ok(Some(picture));
         }
 
-        // Try other tags if primary tag doesn't have album art
-        for tag in tagged_file.tags() {
-            if let Some(picture) = tag.pictures().first() {
-                return Ok(Some(picture.data().to_vec()));
-            }
-        }
+        let cover = self
+            .covers
+            .as_ref()
+            .zip(album_of(&tagged_file))
+            .and_then(|(covers, (artist, album))| covers.get(&artist, &album));
+        Ok(cover)
+    }
 
-        Ok(None)
+    /// Shrink the art of a song file to `width` by `height` pixels, keeping
+    /// the colors, for a thumbnail
+    pub fn thumbnail_from_file<P: AsRef<Path>>(&self, file_path: P, width: u32, height: u32) -> Result<Option<image::RgbImage>> {
+        let Some(image_data) = self.extract_album_art(file_path)? else {
+            return Ok(None);
+        };
+
+        let image = image::load_from_memory(&image_data)
+            .map_err(|e| LofiTurtleError::Configuration(format!("Failed to load image: {}", e)))?;
+        Ok(Some(image.resize_exact(width, height, image::imageops::FilterType::Triangle).to_rgb8()))
     }
 
     /// Convert image data to ASCII art
@@ -199,8 +483,8 @@ impl AlbumArtRenderer {
 
     /// Check if the position should contain part of a music note pattern
     fn is_music_note_pattern(&self, x: u32, y: u32, center_x: u32, center_y: u32) -> bool {
-        let dx = (x as i32 - center_x as i32).abs() as u32;
-        let dy = (y as i32 - center_y as i32).abs() as u32;
+        let dx = (x as i32 - center_x as i32).unsigned_abs();
+        let dy = (y as i32 - center_y as i32).unsigned_abs();
 
         // Simple pattern: musical note in the center
         (dx <= 2 && dy <= 1) || (x == center_x && y < center_y && dy <= 3)
@@ -221,9 +505,9 @@ impl AlbumArtRenderer {
         };
 
         // Try to get from cache
-        if let Ok(cache) = self.ascii_cache.lock() {
+        if let Ok(mut cache) = self.ascii_cache.lock() {
             if let Some(cached_art) = cache.get(&cache_key) {
-                return Ok(cached_art.clone());
+                return Ok(cached_art);
             }
         }
 
@@ -360,3 +644,73 @@ impl Default for AlbumArtRenderer {
 /// Utility functions for album art processing
 pub mod utils {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> AsciiArtCacheKey {
+        AsciiArtCacheKey {
+            file_path: format!("/music/{}.mp3", name),
+            width: 40,
+            height: 20,
+            use_color: false,
+        }
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_entry() {
+        let mut cache = AsciiArtCache::new(2, 1024);
+        cache.insert(key("a"), "aaaa".to_string());
+        cache.insert(key("b"), "bbbb".to_string());
+        assert!(cache.get(&key("a")).is_some());
+
+        cache.insert(key("c"), "cccc".to_string());
+        assert!(cache.get(&key("b")).is_none());
+        assert_eq!(cache.get(&key("a")).as_deref(), Some("aaaa"));
+        assert_eq!(cache.get(&key("c")).as_deref(), Some("cccc"));
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.bytes, 8);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!((stats.hits, stats.misses), (3, 1));
+    }
+
+    #[test]
+    fn test_cover_cache_ignores_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let covers = CoverCache::new(dir.path().join("covers"));
+        assert!(covers.get("Nujabes", "Modal Soul").is_none());
+
+        covers.store("Nujabes", "Modal Soul", b"jpeg").unwrap();
+        assert_eq!(covers.get(" nujabes", "MODAL SOUL ").as_deref(), Some(&b"jpeg"[..]));
+        assert!(!covers.contains("Nujabes", "Metaphorical Music"));
+    }
+
+    #[test]
+    fn test_cover_mime_type() {
+        assert_eq!(cover_mime_type(b"\xFF\xD8\xFF\xE0\0\x10JFIF\0").ok(), Some(MimeType::Jpeg));
+        assert_eq!(cover_mime_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").ok(), Some(MimeType::Png));
+        assert!(cover_mime_type(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn test_cache_stays_within_byte_cap() {
+        let mut cache = AsciiArtCache::new(10, 10);
+        cache.insert(key("a"), "aaaa".to_string());
+        cache.insert(key("b"), "bbbb".to_string());
+        cache.insert(key("c"), "cccc".to_string());
+        assert_eq!(cache.stats().bytes, 8);
+        assert!(cache.get(&key("a")).is_none());
+
+        // Replacing an entry counts only its new size
+        cache.insert(key("c"), "cc".to_string());
+        assert_eq!(cache.stats().bytes, 6);
+
+        // Too big to ever fit, so it is not cached and nothing is evicted
+        cache.insert(key("d"), "d".repeat(11));
+        assert!(cache.get(&key("d")).is_none());
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
@@ -0,0 +1,151 @@
+//! Stereo level metering for the VU meter
+//!
+//! Each frame the latest few milliseconds of output are measured for their
+//! peak and RMS level. Like a hardware meter, the readings rise at once but
+//! fall back at a steady rate, and the highest peak is held for a moment.
+
+use rodio::Sample;
+use std::time::{Duration, Instant};
+
+/// Audio measured for each reading
+pub const WINDOW: Duration = Duration::from_millis(50);
+
+/// Level shown as an empty meter
+pub const FLOOR_DB: f32 = -48.0;
+
+/// How fast readings fall back, in dB per second
+const FALL_DB_PER_SEC: f32 = 24.0;
+
+/// How long the highest peak stays marked
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+
+/// Levels of one channel in dB, from [`FLOOR_DB`] to 0 at full scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelLevel {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+impl Default for ChannelLevel {
+    fn default() -> Self {
+        Self { peak: FLOOR_DB, rms: FLOOR_DB }
+    }
+}
+
+impl ChannelLevel {
+    /// Where `db` falls on the meter, from 0 at the floor to 1 at full scale
+    pub fn fraction(db: f32) -> f32 {
+        ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+    }
+}
+
+/// What the meter shows for one channel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MeterReading {
+    pub level: ChannelLevel,
+    /// Highest recent peak, in dB
+    pub held_peak: f32,
+}
+
+/// Peak and RMS of the left and right channels of `frames`
+pub fn measure(frames: &[[Sample; 2]]) -> [ChannelLevel; 2] {
+    if frames.is_empty() {
+        return [ChannelLevel::default(); 2];
+    }
+
+    [0, 1].map(|side| {
+        let peak = frames.iter().map(|frame| frame[side].abs()).fold(0.0, f32::max);
+        let mean_square = frames.iter().map(|frame| frame[side] * frame[side]).sum::<f32>() / frames.len() as f32;
+        ChannelLevel { peak: to_db(peak), rms: to_db(mean_square.sqrt()) }
+    })
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        FLOOR_DB
+    } else {
+        (20.0 * amplitude.log10()).max(FLOOR_DB)
+    }
+}
+
+/// Stereo meter with falling readings and a held peak
+#[derive(Debug, Default)]
+pub struct VuMeter {
+    readings: [MeterReading; 2],
+    held_at: [Option<Instant>; 2],
+    updated: Option<Instant>,
+}
+
+impl VuMeter {
+    /// Take in levels measured at `now`, returning what the meter shows
+    pub fn update(&mut self, measured: [ChannelLevel; 2], now: Instant) -> [MeterReading; 2] {
+        let elapsed = self.updated.map_or(Duration::ZERO, |updated| now.saturating_duration_since(updated));
+        let fall = FALL_DB_PER_SEC * elapsed.as_secs_f32();
+        self.updated = Some(now);
+
+        for (side, level) in measured.into_iter().enumerate() {
+            let reading = &mut self.readings[side];
+            reading.level.peak = level.peak.max(reading.level.peak - fall).max(FLOOR_DB);
+            reading.level.rms = level.rms.max(reading.level.rms - fall).max(FLOOR_DB);
+
+            let held_at = &mut self.held_at[side];
+            let expired = held_at.is_none_or(|held_at| now.saturating_duration_since(held_at) >= PEAK_HOLD);
+            if level.peak >= reading.held_peak || expired {
+                reading.held_peak = level.peak;
+                *held_at = Some(now);
+            }
+        }
+        self.readings
+    }
+
+    /// Drop back to silence, as when playback stops
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.01
+    }
+
+    #[test]
+    fn test_measure_peak_and_rms() {
+        // A square wave at half scale on the left, silence on the right
+        let frames: Vec<[Sample; 2]> = (0..100).map(|i| [if i % 2 == 0 { 0.5 } else { -0.5 }, 0.0]).collect();
+        let [left, right] = measure(&frames);
+        assert!(close(left.peak, -6.02), "{:?}", left);
+        assert!(close(left.rms, -6.02), "{:?}", left);
+        assert_eq!(right, ChannelLevel::default());
+        assert_eq!(measure(&[]), [ChannelLevel::default(); 2]);
+    }
+
+    #[test]
+    fn test_readings_fall_back_and_hold_peak() {
+        let mut meter = VuMeter::default();
+        let start = Instant::now();
+        let loud = ChannelLevel { peak: -3.0, rms: -10.0 };
+        meter.update([loud; 2], start);
+
+        // Half a second later the meter has fallen 12 dB, the peak still held
+        let [left, _] = meter.update([ChannelLevel::default(); 2], start + Duration::from_millis(500));
+        assert!(close(left.level.rms, -22.0), "{:?}", left);
+        assert!(close(left.level.peak, -15.0), "{:?}", left);
+        assert_eq!(left.held_peak, -3.0);
+
+        // Once the hold runs out the marker follows the current peak
+        let [left, _] = meter.update([ChannelLevel::default(); 2], start + PEAK_HOLD);
+        assert_eq!(left.held_peak, FLOOR_DB);
+    }
+
+    #[test]
+    fn test_fraction_of_meter() {
+        assert_eq!(ChannelLevel::fraction(0.0), 1.0);
+        assert_eq!(ChannelLevel::fraction(FLOOR_DB), 0.0);
+        assert_eq!(ChannelLevel::fraction(FLOOR_DB / 2.0), 0.5);
+        assert_eq!(ChannelLevel::fraction(6.0), 1.0);
+    }
+}
@@ -1,3 +1,11 @@
+pub mod meter;
+pub mod output;
 pub mod player;
+pub mod preamp;
+pub mod spectrum;
+pub mod stream;
+pub mod tap;
+pub mod trim;
 
-pub use player::{AudioPlayer, PlayerCommand, PlayerState, PlaybackStatus};
+pub use player::{AudioOptions, AudioPlayer, CrossfadeOn, PlayerCommand, PlayerError, PlayerState, PlaybackStatus};
+pub use trim::Trim;
@@ -0,0 +1,88 @@
+//! Choosing the audio host and output device
+//!
+//! A host is one of the platform's audio systems, such as ALSA on Linux or
+//! WASAPI on Windows; which ones exist depends on the platform. Names are
+//! matched ignoring case, and the defaults are used for whatever is not given.
+//!
+//! Streams are always opened in shared mode, as cpal has no exclusive mode.
+//! On ALSA, a `hw:` device plays straight to the card without mixing.
+
+use crate::error::{LofiTurtleError, Result};
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::cpal::{self, Device, Host};
+
+/// Names of the hosts available on this platform
+pub fn host_names() -> Vec<&'static str> {
+    cpal::available_hosts().iter().map(|id| id.name()).collect()
+}
+
+/// The host called `name`, or the platform default
+pub fn host(name: Option<&str>) -> Result<Host> {
+    let Some(name) = name else {
+        return Ok(cpal::default_host());
+    };
+
+    let id = cpal::available_hosts()
+        .into_iter()
+        .find(|id| id.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            LofiTurtleError::AudioPlayback(format!(
+                "Unknown audio host '{}', available: {}",
+                name,
+                listed(&host_names())
+            ))
+        })?;
+    cpal::host_from_id(id)
+        .map_err(|e| LofiTurtleError::AudioPlayback(format!("Audio host '{}' is unavailable: {}", name, e)))
+}
+
+/// Names of the output devices of `host`
+pub fn device_names(host: &Host) -> Vec<String> {
+    host.output_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Host and device names of every output device of every available host
+pub fn outputs() -> Vec<(String, String)> {
+    cpal::available_hosts()
+        .into_iter()
+        .filter_map(|id| cpal::host_from_id(id).ok())
+        .flat_map(|host| {
+            let name = host.id().name();
+            device_names(&host).into_iter().map(move |device| (name.to_string(), device))
+        })
+        .collect()
+}
+
+/// Name of the output device `host` plays to unless told otherwise
+pub fn default_device_name(host: &Host) -> Option<String> {
+    host.default_output_device().and_then(|device| device.name().ok())
+}
+
+/// The output device of `host` called `name`, or its default
+pub fn device(host: &Host, name: Option<&str>) -> Result<Device> {
+    let Some(name) = name else {
+        return host.default_output_device().ok_or_else(|| {
+            LofiTurtleError::AudioPlayback(format!("Audio host '{}' has no output device", host.id().name()))
+        });
+    };
+
+    host.output_devices()
+        .map_err(|e| LofiTurtleError::AudioPlayback(e.to_string()))?
+        .find(|device| device.name().is_ok_and(|device_name| device_name.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| {
+            LofiTurtleError::AudioPlayback(format!(
+                "Unknown audio device '{}', available: {}",
+                name,
+                listed(&device_names(host))
+            ))
+        })
+}
+
+fn listed<S: AsRef<str>>(names: &[S]) -> String {
+    match names {
+        [] => "none".to_string(),
+        names => names.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(", "),
+    }
+}
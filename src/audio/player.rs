@@ -1,5 +1,13 @@
+use crate::audio::meter::{self, ChannelLevel};
+use crate::audio::output;
+use crate::audio::preamp::Preamp;
+use crate::audio::stream::{self, HttpStream, Media};
+use crate::audio::tap::{SharedTap, Tapped};
+use crate::audio::trim::{Trim, Trimmed};
 use crate::error::{LofiTurtleError, Result};
-use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink, Source};
+use rodio::cpal::BufferSize;
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Decoder, OutputStream, OutputStreamBuilder, Sample, SampleRate, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -9,12 +17,12 @@ use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub enum PlayerCommand {
-    Play(String),  // Play song at given path
+    Play(String, Trim),  // Play song at given path, trimmed
+    Queue(Option<(String, Trim)>), // Song to play straight after the current one
     Pause,
     Resume,
     Stop,
-    #[allow(dead_code)] // Future feature: seeking
-    Seek(u64),     // Seek to position in seconds
+    Seek(Duration), // Jump to a position in the current song
     #[allow(dead_code)] // Future feature: volume control
     SetVolume(f32), // Set volume (0.0 to 1.0)
     #[allow(dead_code)] // Used in audio thread communication
@@ -24,6 +32,15 @@ pub enum PlayerCommand {
     Quit,
 }
 
+/// Something the audio thread failed at, for the interface to report
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerError {
+    /// The song at `path` could not be played
+    Load { path: String, error: String },
+    /// The audio thread stopped, and nothing plays any more
+    Stopped(String),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PlayerState {
     Stopped,
@@ -38,6 +55,14 @@ pub struct PlaybackStatus {
     pub total_duration: u64,    // Total duration in seconds
     pub current_song: Option<String>, // Path to current song
     pub volume: f32,
+    /// Song queued to follow the current one without a gap
+    pub next_song: Option<String>,
+    /// Whether the current song followed the previous one without a gap,
+    /// rather than being started with `Play`
+    pub gapless: bool,
+    /// How much is trimmed off the current song; `total_duration` is where
+    /// it stops, after its end is cut
+    pub trim: Trim,
 }
 
 impl Default for PlaybackStatus {
@@ -48,31 +73,197 @@ impl Default for PlaybackStatus {
             total_duration: 0,
             current_song: None,
             volume: 0.7,
+            next_song: None,
+            gapless: false,
+            trim: Trim::default(),
+        }
+    }
+}
+
+/// How the audio output is set up
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioOptions {
+    /// How long before the current song ends the queued one is decoded and
+    /// lined up behind it, and how much of it is decoded up front; zero
+    /// starts every song on its own
+    pub prebuffer: Duration,
+    /// Frames in the output device buffer, or the device default. Larger
+    /// buffers ride out a busy CPU at the cost of latency
+    pub buffer_frames: Option<u32>,
+    /// Audio host to play through, e.g. "ALSA" or "WASAPI", or the default
+    pub host: Option<String>,
+    /// Output device of that host, or its default
+    pub device: Option<String>,
+    /// How long one song fades into the next; zero cuts straight over
+    pub crossfade: Duration,
+    /// Which song changes fade
+    pub crossfade_on: CrossfadeOn,
+    /// Decibels every song is raised or lowered by, limited so raised songs
+    /// do not clip
+    pub preamp_db: f32,
+}
+
+/// Song changes that fade into each other when crossfading is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrossfadeOn {
+    /// Songs started by hand, e.g. with Next or from the list
+    Skip,
+    /// Songs that follow one that played to the end
+    End,
+    #[default]
+    Both,
+}
+
+impl CrossfadeOn {
+    fn on_skip(self) -> bool {
+        matches!(self, CrossfadeOn::Skip | CrossfadeOn::Both)
+    }
+
+    fn on_end(self) -> bool {
+        matches!(self, CrossfadeOn::End | CrossfadeOn::Both)
+    }
+}
+
+impl Default for AudioOptions {
+    fn default() -> Self {
+        Self {
+            prebuffer: Duration::from_secs(DEFAULT_PREBUFFER_SECS),
+            buffer_frames: None,
+            host: None,
+            device: None,
+            crossfade: Duration::ZERO,
+            crossfade_on: CrossfadeOn::default(),
+            preamp_db: 0.0,
         }
     }
 }
 
+/// How often the volumes are stepped during a crossfade
+const CROSSFADE_STEP: Duration = Duration::from_millis(20);
+
+/// A song fading out under the one that took over from it
+struct Crossfade {
+    outgoing: Sink,
+    started: Instant,
+}
+
+/// Volumes of the outgoing and incoming songs `elapsed` into a crossfade
+/// lasting `length`, as fractions of the player volume; the two add up in
+/// power, so the loudness holds steady across it
+fn crossfade_gains(elapsed: Duration, length: Duration) -> (f32, f32) {
+    let progress = if length.is_zero() { 1.0 } else { (elapsed.as_secs_f32() / length.as_secs_f32()).min(1.0) };
+    let angle = progress * std::f32::consts::FRAC_PI_2;
+    (angle.cos(), angle.sin())
+}
+
+/// Seconds of the next song decoded ahead unless configured otherwise
+pub const DEFAULT_PREBUFFER_SECS: u64 = 5;
+
+/// Song appended to the sink behind the current one
+struct Queued {
+    path: String,
+    duration: u64,
+    trim: Trim,
+}
+
+/// Whether the song queued next should be lined up now, `position` seconds
+/// into one lasting `total`
+fn due_for_prebuffer(position: u64, total: u64, prebuffer: Duration) -> bool {
+    // Without a known length the end cannot be seen coming
+    !prebuffer.is_zero() && total > 0 && total.saturating_sub(position) <= prebuffer.as_secs()
+}
+
+/// A song whose opening is decoded up front, so it starts the moment the
+/// one before it ends even if decoding is slow
+struct Prebuffered<S> {
+    head: std::vec::IntoIter<Sample>,
+    rest: S,
+}
+
+impl<S: Source> Prebuffered<S> {
+    fn new(mut source: S, length: Duration) -> Self {
+        let channels = source.channels() as usize;
+        let frames = (length.as_secs_f64() * source.sample_rate() as f64) as usize;
+        let head: Vec<Sample> = source.by_ref().take(frames * channels).collect();
+        Self { head: head.into_iter(), rest: source }
+    }
+}
+
+impl<S: Source> Iterator for Prebuffered<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        self.head.next().or_else(|| self.rest.next())
+    }
+}
+
+impl<S: Source> Source for Prebuffered<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        match self.head.len() {
+            0 => self.rest.current_span_len(),
+            len => Some(len),
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.rest.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.rest.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.rest.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> std::result::Result<(), SeekError> {
+        // The decoded opening is of no use anywhere else
+        self.head = Vec::new().into_iter();
+        self.rest.try_seek(position)
+    }
+}
+
 pub struct AudioPlayer {
     command_sender: Sender<PlayerCommand>,
     status: Arc<Mutex<PlaybackStatus>>,
+    tap: SharedTap,
+    errors: Receiver<PlayerError>,
 }
 
 impl AudioPlayer {
-    pub fn new() -> Result<Self> {
+    pub fn new(options: AudioOptions) -> Result<Self> {
         let (command_sender, command_receiver) = mpsc::channel();
         let status = Arc::new(Mutex::new(PlaybackStatus::default()));
         let status_clone = Arc::clone(&status);
+        let tap = SharedTap::default();
+        let tap_clone = Arc::clone(&tap);
+        let (ready_sender, ready_receiver) = mpsc::channel();
+        let (error_sender, errors) = mpsc::channel();
+        let chosen_output = options.host.is_some() || options.device.is_some();
 
         // Spawn the audio thread
         thread::spawn(move || {
-            if let Err(e) = Self::audio_thread(command_receiver, status_clone) {
-                eprintln!("Audio thread error: {}", e);
+            let thread_errors = error_sender.clone();
+            if let Err(e) = Self::audio_thread(command_receiver, status_clone, tap_clone, options, ready_sender, error_sender) {
+                log::error!("Audio thread error: {}", e);
+                let _ = thread_errors.send(PlayerError::Stopped(e.to_string()));
             }
         });
 
+        // Without a working default output the player still runs silently,
+        // but a host or device that was asked for by name has to exist
+        if let Ok(Err(e)) = ready_receiver.recv() {
+            if chosen_output {
+                return Err(e);
+            }
+        }
+
         Ok(Self {
             command_sender,
             status,
+            tap,
+            errors,
         })
     }
 
@@ -87,47 +278,128 @@ impl AudioPlayer {
         self.status.lock().unwrap().clone()
     }
 
+    /// The next failure the audio thread reported, if any
+    pub fn take_error(&self) -> Option<PlayerError> {
+        self.errors.try_recv().ok()
+    }
+
+    /// The latest `count` samples played, mixed down to mono, with their
+    /// sample rate
+    pub fn recent_samples(&self, count: usize) -> (Vec<rodio::Sample>, rodio::SampleRate) {
+        self.tap.lock().unwrap().latest(count)
+    }
+
+    /// Samples played over the last `window`, mixed down to mono
+    pub fn recent_waveform(&self, window: Duration) -> Vec<rodio::Sample> {
+        let tap = self.tap.lock().unwrap();
+        let count = (window.as_secs_f64() * tap.sample_rate() as f64) as usize;
+        tap.latest(count).0
+    }
+
+    /// Left and right levels of the audio played over the last `window`
+    pub fn recent_levels(&self, window: Duration) -> [ChannelLevel; 2] {
+        let tap = self.tap.lock().unwrap();
+        let frames = (window.as_secs_f64() * tap.sample_rate() as f64) as usize;
+        meter::measure(&tap.latest_stereo(frames))
+    }
+
     fn audio_thread(
         command_receiver: Receiver<PlayerCommand>,
         status: Arc<Mutex<PlaybackStatus>>,
+        tap: SharedTap,
+        options: AudioOptions,
+        ready: Sender<Result<()>>,
+        errors: Sender<PlayerError>,
     ) -> Result<()> {
-        let stream_handle = OutputStreamBuilder::open_default_stream()
-            .map_err(|e| LofiTurtleError::AudioPlayback(format!("Failed to create audio output stream: {}", e)))?;
+        let stream_handle = match Self::open_stream(&options) {
+            Ok(stream_handle) => {
+                let _ = ready.send(Ok(()));
+                stream_handle
+            }
+            Err(e) => {
+                let _ = ready.send(Err(LofiTurtleError::AudioPlayback(e.to_string())));
+                return Err(e);
+            }
+        };
 
         let mut sink: Option<Sink> = None;
+        let mut next_song: Option<(String, Trim)> = None;
+        // Trim of the song playing; seeks never go back into its skipped start
+        let mut trim = Trim::default();
+        let mut queued: Option<Queued> = None;
+        // Set when the next song could not be loaded, so it is not retried
+        // every tick; playback then stops and the app starts it as usual
+        let mut prebuffer_failed = false;
         let mut playback_start_time: Option<Instant> = None;
         let mut paused_position: u64 = 0;
+        let mut crossfade: Option<Crossfade> = None;
 
         loop {
             // Handle commands
             while let Ok(command) = command_receiver.try_recv() {
                 match command {
-                    PlayerCommand::Play(path) => {
-                        // Stop current playback
-                        if let Some(s) = sink.take() {
-                            s.stop();
+                    PlayerCommand::Play(path, song_trim) => {
+                        // A song cut short by hand fades out if it was playing
+                        if let Some(fade) = crossfade.take() {
+                            fade.outgoing.stop();
                         }
+                        let fade_out = sink.take().filter(|s| {
+                            let fades = !options.crossfade.is_zero()
+                                && options.crossfade_on.on_skip()
+                                && !s.empty()
+                                && !s.is_paused();
+                            if !fades {
+                                s.stop();
+                            }
+                            fades
+                        });
+
+                        next_song = None;
+                        queued = None;
+                        prebuffer_failed = false;
 
-                        match Self::load_audio_file(&path, &stream_handle) {
+                        let volume = status.lock().unwrap().volume;
+                        let initial_volume = if fade_out.is_some() { 0.0 } else { volume };
+                        match Self::load_audio_file(&path, song_trim, &stream_handle, &tap, initial_volume, options.preamp_db) {
                             Ok((new_sink, duration)) => {
                                 sink = Some(new_sink);
+                                crossfade = fade_out.map(|outgoing| Crossfade { outgoing, started: Instant::now() });
                                 playback_start_time = Some(Instant::now());
-                                paused_position = 0;
+                                trim = song_trim;
+                                paused_position = trim.start.as_secs();
 
                                 let mut status_guard = status.lock().unwrap();
                                 status_guard.state = PlayerState::Playing;
                                 status_guard.current_song = Some(path);
                                 status_guard.total_duration = duration;
-                                status_guard.current_position = 0;
+                                status_guard.current_position = paused_position;
+                                status_guard.trim = trim;
+                                status_guard.next_song = None;
+                                status_guard.gapless = false;
                             }
                             Err(e) => {
-                                eprintln!("Failed to load audio file: {}", e);
+                                log::warn!("Failed to load audio file {}: {}", path, e);
+                                let _ = errors.send(PlayerError::Load { path, error: e.to_string() });
+                                if let Some(outgoing) = fade_out {
+                                    outgoing.stop();
+                                }
                                 let mut status_guard = status.lock().unwrap();
                                 status_guard.state = PlayerState::Stopped;
                             }
                         }
                     }
+                    PlayerCommand::Queue(song) => {
+                        // A song already lined up in the sink cannot be taken
+                        // back out, so it plays regardless
+                        if queued.is_none() {
+                            prebuffer_failed = false;
+                            next_song = song;
+                            status.lock().unwrap().next_song = next_song.as_ref().map(|(path, _)| path.clone());
+                        }
+                    }
                     PlayerCommand::Pause => {
+                        // The fade is cut short rather than frozen half way
+                        Self::finish_crossfade(&mut crossfade, sink.as_ref(), status.lock().unwrap().volume);
                         if let Some(ref s) = sink {
                             s.pause();
                             if let Some(start_time) = playback_start_time {
@@ -152,16 +424,24 @@ impl AudioPlayer {
                         if let Some(s) = sink.take() {
                             s.stop();
                         }
+                        if let Some(fade) = crossfade.take() {
+                            fade.outgoing.stop();
+                        }
                         playback_start_time = None;
                         paused_position = 0;
+                        next_song = None;
+                        queued = None;
 
                         let mut status_guard = status.lock().unwrap();
                         status_guard.state = PlayerState::Stopped;
                         status_guard.current_position = 0;
                         status_guard.current_song = None;
+                        status_guard.next_song = None;
+                        status_guard.gapless = false;
                     }
                     PlayerCommand::SetVolume(volume) => {
-                        if let Some(ref s) = sink {
+                        // A crossfade picks up the new volume on its next step
+                        if let (Some(s), None) = (&sink, &crossfade) {
                             s.set_volume(volume);
                         }
                         let mut status_guard = status.lock().unwrap();
@@ -180,9 +460,35 @@ impl AudioPlayer {
                     PlayerCommand::Quit => {
                         break;
                     }
-                    PlayerCommand::Seek(_) => {
-                        // Seeking is complex with rodio, skip for now
-                        // Could be implemented with custom source
+                    PlayerCommand::Seek(position) => {
+                        let position = position.max(trim.start);
+                        if let Some(ref s) = sink {
+                            match s.try_seek(position) {
+                                Ok(()) => {
+                                    paused_position = position.as_secs();
+                                    if playback_start_time.is_some() {
+                                        playback_start_time = Some(Instant::now());
+                                    }
+
+                                    let mut status_guard = status.lock().unwrap();
+                                    status_guard.current_position = paused_position.min(status_guard.total_duration);
+                                }
+                                Err(e) => log::warn!("Failed to seek: {}", e),
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(fade) = &crossfade {
+                let volume = status.lock().unwrap().volume;
+                if fade.started.elapsed() >= options.crossfade {
+                    Self::finish_crossfade(&mut crossfade, sink.as_ref(), volume);
+                } else {
+                    let (outgoing, incoming) = crossfade_gains(fade.started.elapsed(), options.crossfade);
+                    fade.outgoing.set_volume(volume * outgoing);
+                    if let Some(ref s) = sink {
+                        s.set_volume(volume * incoming);
                     }
                 }
             }
@@ -194,20 +500,87 @@ impl AudioPlayer {
                     sink = None;
                     playback_start_time = None;
                     paused_position = 0;
+                    next_song = None;
+                    queued = None;
 
                     let mut status_guard = status.lock().unwrap();
                     status_guard.state = PlayerState::Stopped;
                     status_guard.current_position = 0;
                     status_guard.current_song = None;
+                    status_guard.next_song = None;
+                    status_guard.gapless = false;
+                } else if queued.is_some() && s.len() == 1 {
+                    // The queued song took over from the one before it
+                    let next = queued.take().unwrap();
+                    next_song = None;
+                    playback_start_time = Some(Instant::now());
+                    trim = next.trim;
+                    paused_position = trim.start.as_secs();
+
+                    let mut status_guard = status.lock().unwrap();
+                    status_guard.current_song = Some(next.path);
+                    status_guard.total_duration = next.duration;
+                    status_guard.current_position = paused_position;
+                    status_guard.trim = trim;
+                    status_guard.next_song = None;
+                    status_guard.gapless = true;
                 } else if let Some(start_time) = playback_start_time {
                     let current_pos = paused_position + start_time.elapsed().as_secs();
-                    let mut status_guard = status.lock().unwrap();
-                    status_guard.current_position = current_pos.min(status_guard.total_duration);
+                    let total_duration = {
+                        let mut status_guard = status.lock().unwrap();
+                        status_guard.current_position = current_pos.min(status_guard.total_duration);
+                        status_guard.total_duration
+                    };
+
+                    let fades_at_end = !options.crossfade.is_zero() && options.crossfade_on.on_end();
+                    if fades_at_end {
+                        // The next song starts in a sink of its own, so the two
+                        // can overlap
+                        // Songs shorter than the fade play out on their own
+                        let due = total_duration > options.crossfade.as_secs()
+                            && total_duration.saturating_sub(current_pos) <= options.crossfade.as_secs();
+                        if let (true, false, None, Some((path, next_trim))) = (due, prebuffer_failed, &crossfade, next_song.clone()) {
+                            match Self::load_audio_file(&path, next_trim, &stream_handle, &tap, 0.0, options.preamp_db) {
+                                Ok((new_sink, duration)) => {
+                                    crossfade = sink.replace(new_sink).map(|outgoing| Crossfade { outgoing, started: Instant::now() });
+                                    next_song = None;
+                                    playback_start_time = Some(Instant::now());
+                                    trim = next_trim;
+                                    paused_position = trim.start.as_secs();
+
+                                    let mut status_guard = status.lock().unwrap();
+                                    status_guard.current_song = Some(path);
+                                    status_guard.total_duration = duration;
+                                    status_guard.current_position = paused_position;
+                                    status_guard.trim = trim;
+                                    status_guard.next_song = None;
+                                    status_guard.gapless = true;
+                                }
+                                Err(e) => {
+                                    log::warn!("Failed to start next song: {}", e);
+                                    prebuffer_failed = true;
+                                }
+                            }
+                        }
+                    } else if queued.is_none() && !prebuffer_failed && due_for_prebuffer(current_pos, total_duration, options.prebuffer) {
+                        if let Some((path, next_trim)) = next_song.clone() {
+                            match Self::decode(&path, next_trim, options.preamp_db) {
+                                Ok((source, duration)) => {
+                                    s.append(Tapped::new(Prebuffered::new(source, options.prebuffer), Arc::clone(&tap)));
+                                    queued = Some(Queued { path, duration, trim: next_trim });
+                                }
+                                Err(e) => {
+                                    log::warn!("Failed to prebuffer next song: {}", e);
+                                    prebuffer_failed = true;
+                                }
+                            }
+                        }
+                    }
                 }
             }
 
-            // Sleep to avoid busy waiting
-            thread::sleep(Duration::from_millis(100));
+            // Sleep to avoid busy waiting, stepping a crossfade more often
+            thread::sleep(if crossfade.is_some() { CROSSFADE_STEP } else { Duration::from_millis(100) });
         }
 
         // This code is unreachable but required for compilation
@@ -215,27 +588,146 @@ impl AudioPlayer {
         Ok(())
     }
 
-    fn load_audio_file(
-        path: &str,
-        stream_handle: &OutputStream,
-    ) -> Result<(Sink, u64)> {
-        let file = File::open(path)
-            .map_err(|e| LofiTurtleError::FileSystem(e))?;
-        
-        let buf_reader = BufReader::new(file);
-        let decoder = Decoder::new(buf_reader)
+    /// Open the configured output device, with the configured buffer size if
+    /// there is one and the device accepts it
+    fn open_stream(options: &AudioOptions) -> Result<OutputStream> {
+        let stream_error = |e: rodio::StreamError| {
+            LofiTurtleError::AudioPlayback(format!("Failed to create audio output stream: {}", e))
+        };
+        if options.host.is_none() && options.device.is_none() && options.buffer_frames.is_none() {
+            // Falls back to any device that works
+            return OutputStreamBuilder::open_default_stream().map_err(stream_error);
+        }
+
+        let host = output::host(options.host.as_deref())?;
+        let device = output::device(&host, options.device.as_deref())?;
+        let builder = OutputStreamBuilder::from_device(device).map_err(stream_error)?;
+        let builder = match options.buffer_frames {
+            Some(frames) => builder.with_buffer_size(BufferSize::Fixed(frames)),
+            None => builder,
+        };
+        builder.open_stream_or_fallback().map_err(stream_error)
+    }
+
+    /// Open a song for decoding, trimmed and through the preamp, with the
+    /// second it ends at, which is 0 for streams
+    fn decode(path: &str, trim: Trim, preamp_db: f32) -> Result<(Preamp<Trimmed<Decoder<Media>>>, u64)> {
+        let builder = if stream::is_stream(path) {
+            let stream = HttpStream::open(path)?;
+            let builder = match stream.mime_type() {
+                Some(mime_type) => Decoder::builder().with_mime_type(mime_type),
+                None => Decoder::builder(),
+            };
+            builder.with_data(Media::Stream(stream)).with_seekable(false)
+        } else {
+            let file = File::open(path)
+                .map_err(LofiTurtleError::FileSystem)?;
+            let len = file.metadata().map_err(LofiTurtleError::FileSystem)?.len();
+
+            // Given the file's length, so that it knows the song's and can seek
+            Decoder::builder()
+                .with_data(Media::File(BufReader::new(file)))
+                .with_byte_len(len)
+                .with_seekable(true)
+        };
+        let decoder = builder.build()
             .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to decode audio file '{}': {}", path, e)))?;
 
+        let trimmed = Trimmed::new(decoder, trim);
         // Get duration before consuming the decoder
-        let total_duration = decoder.total_duration()
+        let total_duration = trimmed.total_duration()
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
+        Ok((Preamp::new(trimmed, preamp_db), total_duration))
+    }
+
+    fn load_audio_file(
+        path: &str,
+        trim: Trim,
+        stream_handle: &OutputStream,
+        tap: &SharedTap,
+        volume: f32,
+        preamp_db: f32,
+    ) -> Result<(Sink, u64)> {
+        let (decoder, total_duration) = Self::decode(path, trim, preamp_db)?;
+
         let sink = Sink::connect_new(stream_handle.mixer());
 
-        sink.append(decoder);
-        sink.set_volume(0.7); // Default volume
+        // Set before the song is added, so a fade in starts from silence
+        sink.set_volume(volume);
+        sink.append(Tapped::new(decoder, Arc::clone(tap)));
 
         Ok((sink, total_duration))
     }
+
+    /// End a crossfade: the outgoing song stops and the incoming one plays
+    /// at full volume
+    fn finish_crossfade(crossfade: &mut Option<Crossfade>, sink: Option<&Sink>, volume: f32) {
+        if let Some(fade) = crossfade.take() {
+            fade.outgoing.stop();
+            if let Some(s) = sink {
+                s.set_volume(volume);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    #[test]
+    fn test_prebuffer_due_near_the_end() {
+        let prebuffer = Duration::from_secs(5);
+        assert!(!due_for_prebuffer(100, 200, prebuffer));
+        assert!(due_for_prebuffer(195, 200, prebuffer));
+        assert!(due_for_prebuffer(200, 200, prebuffer));
+
+        // Disabled, or the length is unknown
+        assert!(!due_for_prebuffer(199, 200, Duration::ZERO));
+        assert!(!due_for_prebuffer(0, 0, prebuffer));
+    }
+
+    #[test]
+    fn test_crossfade_hands_over_at_constant_power() {
+        let length = Duration::from_secs(4);
+        assert_eq!(crossfade_gains(Duration::ZERO, length), (1.0, 0.0));
+        let (outgoing, incoming) = crossfade_gains(Duration::from_secs(2), length);
+        assert!((outgoing - incoming).abs() < 1e-6);
+        assert!((outgoing * outgoing + incoming * incoming - 1.0).abs() < 1e-6);
+        let (outgoing, incoming) = crossfade_gains(Duration::from_secs(9), length);
+        assert!(outgoing.abs() < 1e-6 && incoming == 1.0);
+    }
+
+    #[test]
+    fn test_crossfade_modes() {
+        assert!(CrossfadeOn::Skip.on_skip() && !CrossfadeOn::Skip.on_end());
+        assert!(CrossfadeOn::End.on_end() && !CrossfadeOn::End.on_skip());
+        assert!(CrossfadeOn::Both.on_skip() && CrossfadeOn::Both.on_end());
+    }
+
+    #[test]
+    fn test_prebuffered_plays_every_sample_in_order() {
+        let samples: Vec<Sample> = (0..40).map(|i| i as Sample).collect();
+        // Two channels at 10 Hz, so half a second is five frames
+        let source = Prebuffered::new(SamplesBuffer::new(2, 10, samples.clone()), Duration::from_millis(500));
+
+        assert_eq!(source.head.len(), 10);
+        assert_eq!(source.current_span_len(), Some(10));
+        assert_eq!(source.channels(), 2);
+        assert_eq!(source.sample_rate(), 10);
+        assert_eq!(source.collect::<Vec<_>>(), samples);
+    }
+
+    #[test]
+    fn test_prebuffered_seek_drops_the_head() {
+        let samples: Vec<Sample> = (0..40).map(|i| i as Sample).collect();
+        let mut source = Prebuffered::new(SamplesBuffer::new(2, 10, samples), Duration::from_millis(500));
+
+        // One second in is frame 10, past the decoded opening
+        source.try_seek(Duration::from_secs(1)).unwrap();
+        assert_eq!(source.next(), Some(20.0));
+    }
 }
@@ -0,0 +1,136 @@
+//! Preamp with a limiter
+//!
+//! The preamp raises or lowers every song by the same number of decibels,
+//! for DACs and headphones too quiet at full volume. Raised songs would clip
+//! on their loudest peaks, so a limiter turns the gain down just far enough
+//! the moment a peak would go over, and brings it back up over the next
+//! fraction of a second.
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::time::Duration;
+
+/// Largest boost or cut, in decibels
+pub const MAX_PREAMP_DB: f32 = 15.0;
+
+/// Highest level the limiter lets through, a little under full scale
+const CEILING: Sample = 0.98;
+
+/// How long the limiter takes to give back most of the gain it took
+const RELEASE: Duration = Duration::from_millis(200);
+
+/// Parse a preamp setting in decibels, for the command line
+pub fn parse_preamp(value: &str) -> Result<f32, String> {
+    let db: f32 = value.trim_end_matches("dB").trim().parse().map_err(|_| format!("'{}' is not a number of decibels", value))?;
+    if !db.is_finite() {
+        return Err(format!("'{}' is not a number of decibels", value));
+    }
+    if db.abs() > MAX_PREAMP_DB {
+        return Err(format!("the preamp goes from -{0} to +{0} dB", MAX_PREAMP_DB));
+    }
+    Ok(db)
+}
+
+/// A song raised or lowered by the preamp
+pub struct Preamp<S> {
+    source: S,
+    gain: Sample,
+    /// Gain the limiter is taking off, 1 when it is idle
+    reduction: Sample,
+    /// How much of the reduction is left after each sample
+    release: Sample,
+}
+
+impl<S: Source> Preamp<S> {
+    pub fn new(source: S, db: f32) -> Self {
+        let samples_per_second = source.sample_rate() as f32 * source.channels().max(1) as f32;
+        Self {
+            gain: 10f32.powf(db / 20.0),
+            reduction: 1.0,
+            release: (-1.0 / (RELEASE.as_secs_f32() * samples_per_second)).exp(),
+            source,
+        }
+    }
+}
+
+impl<S: Source> Iterator for Preamp<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.source.next()? * self.gain;
+        // Cutting can never clip, and songs left alone stay bit for bit
+        if self.gain <= 1.0 {
+            return Some(sample);
+        }
+
+        if (sample * self.reduction).abs() > CEILING {
+            self.reduction = CEILING / sample.abs();
+        }
+        let limited = sample * self.reduction;
+        self.reduction = 1.0 - (1.0 - self.reduction) * self.release;
+        Some(limited)
+    }
+}
+
+impl<S: Source> Source for Preamp<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.source.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.reduction = 1.0;
+        self.source.try_seek(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    #[test]
+    fn test_boost_never_clips() {
+        let samples = vec![0.1, -0.2, 0.9, -1.0, 0.5, 0.1];
+        let boosted: Vec<Sample> = Preamp::new(SamplesBuffer::new(1, 8000, samples), 12.0).collect();
+
+        assert!(boosted.iter().all(|sample| sample.abs() <= CEILING + 1e-6));
+        // Quiet samples before the first peak get the whole boost
+        assert!((boosted[0] - 0.1 * 10f32.powf(0.6)).abs() < 1e-5);
+        assert!((boosted[2] - CEILING).abs() < 1e-6);
+        // The limiter lets go gradually, so a quiet sample just after a
+        // peak is still turned down
+        assert!(boosted[5] < 0.1 * 10f32.powf(0.6));
+    }
+
+    #[test]
+    fn test_cut_and_flat_leave_peaks_alone() {
+        let samples = vec![1.0, -1.0, 0.5];
+        let flat: Vec<Sample> = Preamp::new(SamplesBuffer::new(1, 8000, samples.clone()), 0.0).collect();
+        assert_eq!(flat, samples);
+
+        let cut: Vec<Sample> = Preamp::new(SamplesBuffer::new(1, 8000, samples), -6.0).collect();
+        assert!((cut[0] - 0.501).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_parse_preamp() {
+        assert_eq!(parse_preamp("6"), Ok(6.0));
+        assert_eq!(parse_preamp("-3.5dB"), Ok(-3.5));
+        assert!(parse_preamp("16").is_err());
+        assert!(parse_preamp("loud").is_err());
+        assert!(parse_preamp("NaN").is_err());
+        assert!(parse_preamp("inf").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Spectrum analysis for the visualizer
+//!
+//! The latest samples are windowed and run through an FFT, and the bins are
+//! gathered into bars spaced logarithmically between two frequencies, so
+//! each octave gets the same room the way it is heard.
+
+use rodio::{Sample, SampleRate};
+use std::f32::consts::PI;
+
+/// Level shown as an empty bar; anything quieter is cut off
+const DB_FLOOR: f32 = -60.0;
+
+/// Allowed FFT sizes, both powers of two
+pub const MIN_FFT_SIZE: usize = 64;
+pub const MAX_FFT_SIZE: usize = crate::audio::tap::TAP_CAPACITY;
+
+/// Most bars the spectrum can be split into
+pub const MAX_BARS: usize = 256;
+
+/// How the spectrum is analysed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumParams {
+    /// Samples per analysis, a power of two; larger sizes resolve low notes
+    /// better but react more slowly
+    pub fft_size: usize,
+    /// Number of bars
+    pub bars: usize,
+    /// Share of the previous level kept each frame, from 0 (none) to just
+    /// under 1 (barely moves)
+    pub smoothing: f32,
+    /// Frequency range covered, in Hz
+    pub min_freq: f32,
+    pub max_freq: f32,
+}
+
+impl SpectrumParams {
+    /// The same parameters with out-of-range values brought into range
+    pub fn clamped(self) -> Self {
+        let fft_size = self.fft_size.clamp(MIN_FFT_SIZE, MAX_FFT_SIZE).next_power_of_two().min(MAX_FFT_SIZE);
+        let min_freq = if self.min_freq.is_finite() { self.min_freq.max(1.0) } else { 1.0 };
+        let max_freq = if self.max_freq.is_finite() { self.max_freq.max(min_freq * 2.0) } else { min_freq * 2.0 };
+        Self {
+            fft_size,
+            bars: self.bars.clamp(1, MAX_BARS),
+            smoothing: if self.smoothing.is_nan() { 0.0 } else { self.smoothing.clamp(0.0, 0.99) },
+            min_freq,
+            max_freq,
+        }
+    }
+}
+
+/// Spectrum analyser that smooths its bars from one frame to the next
+#[derive(Debug, Default)]
+pub struct Analyzer {
+    levels: Vec<f32>,
+}
+
+impl Analyzer {
+    /// Analyse `samples`, the latest `params.fft_size` of them, and return
+    /// the bar levels from 0 to 1, lowest frequency first
+    pub fn update(&mut self, samples: &[Sample], sample_rate: SampleRate, params: &SpectrumParams) -> &[f32] {
+        let params = params.clamped();
+        let bars = bar_levels(samples, sample_rate, &params);
+
+        if self.levels.len() != bars.len() {
+            self.levels = bars;
+        } else {
+            for (level, bar) in self.levels.iter_mut().zip(bars) {
+                *level = *level * params.smoothing + bar * (1.0 - params.smoothing);
+            }
+        }
+        &self.levels
+    }
+}
+
+/// Bar levels of one frame, without smoothing
+fn bar_levels(samples: &[Sample], sample_rate: SampleRate, params: &SpectrumParams) -> Vec<f32> {
+    let n = params.fft_size;
+    if sample_rate == 0 || samples.len() < n {
+        return vec![0.0; params.bars];
+    }
+
+    // Hann window, which keeps a loud bin from smearing over its neighbours
+    let samples = &samples[samples.len() - n..];
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| sample * 0.5 * (1.0 - (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+
+    // A full-scale sine comes out at 0 dB: halved by the window, and split
+    // between the positive and negative frequencies
+    let amplitudes: Vec<f32> = (0..n / 2).map(|k| 4.0 * re[k].hypot(im[k]) / n as f32).collect();
+
+    let nyquist = sample_rate as f32 / 2.0;
+    let max_freq = params.max_freq.min(nyquist);
+    let min_freq = params.min_freq.min(max_freq / 2.0);
+    let ratio = max_freq / min_freq;
+    let bin_of = |freq: f32| freq * n as f32 / sample_rate as f32;
+
+    (0..params.bars)
+        .map(|bar| {
+            let low = min_freq * ratio.powf(bar as f32 / params.bars as f32);
+            let high = min_freq * ratio.powf((bar + 1) as f32 / params.bars as f32);
+            let first = (bin_of(low).round() as usize).min(amplitudes.len() - 1);
+            let last = (bin_of(high).round() as usize).clamp(first + 1, amplitudes.len());
+            let amplitude = amplitudes[first..last].iter().copied().fold(0.0, f32::max);
+            level(amplitude)
+        })
+        .collect()
+}
+
+/// Height of a bar for an amplitude, from 0 at the floor to 1 at full scale
+fn level(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return 0.0;
+    }
+    ((20.0 * amplitude.log10() - DB_FLOOR) / -DB_FLOOR).clamp(0.0, 1.0)
+}
+
+/// In-place radix-2 FFT; the length must be a power of two
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let (re_b, im_b) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - re_b;
+                im[b] = im[a] - im_b;
+                re[a] += re_b;
+                im[a] += im_b;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, sample_rate: SampleRate, count: usize) -> Vec<Sample> {
+        (0..count).map(|i| (2.0 * PI * freq * i as f32 / sample_rate as f32).sin()).collect()
+    }
+
+    fn params() -> SpectrumParams {
+        SpectrumParams { fft_size: 2048, bars: 16, smoothing: 0.5, min_freq: 50.0, max_freq: 16000.0 }
+    }
+
+    #[test]
+    fn test_fft_finds_the_frequency() {
+        let mut re = sine(4.0, 64, 64);
+        let mut im = vec![0.0; 64];
+        fft(&mut re, &mut im);
+
+        let magnitudes: Vec<f32> = (0..32).map(|k| re[k].hypot(im[k])).collect();
+        let peak = (0..32).max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b])).unwrap();
+        assert_eq!(peak, 4);
+        assert!((magnitudes[4] - 32.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sine_lights_its_bar() {
+        let mut analyzer = Analyzer::default();
+        let levels = analyzer.update(&sine(1000.0, 44100, 2048), 44100, &params()).to_vec();
+
+        // 1 kHz falls in the bar whose range holds it
+        let ratio: f32 = 16000.0 / 50.0;
+        let expected = (16.0 * (1000.0f32 / 50.0).ln() / ratio.ln()) as usize;
+        let loudest = (0..levels.len()).max_by(|&a, &b| levels[a].total_cmp(&levels[b])).unwrap();
+        assert_eq!(loudest, expected);
+        assert!(levels[loudest] > 0.9, "{:?}", levels);
+        assert!(levels[0] < 0.3, "{:?}", levels);
+    }
+
+    #[test]
+    fn test_levels_are_smoothed() {
+        let mut analyzer = Analyzer::default();
+        let loud = sine(1000.0, 44100, 2048);
+        let peak = analyzer.update(&loud, 44100, &params()).iter().copied().fold(0.0, f32::max);
+
+        let silent = vec![0.0; 2048];
+        let after = analyzer.update(&silent, 44100, &params()).iter().copied().fold(0.0, f32::max);
+        assert!((after - peak * 0.5).abs() < 1e-4);
+
+        // Too few samples reads as silence
+        assert_eq!(Analyzer::default().update(&[0.5; 10], 44100, &params()), &[0.0; 16]);
+    }
+
+    #[test]
+    fn test_params_clamped() {
+        let params = SpectrumParams { fft_size: 1000, bars: 0, smoothing: 1.5, min_freq: -5.0, max_freq: 0.0 }.clamped();
+        assert_eq!(params.fft_size, 1024);
+        assert_eq!(params.bars, 1);
+        assert_eq!(params.smoothing, 0.99);
+        assert_eq!(params.min_freq, 1.0);
+        assert_eq!(params.max_freq, 2.0);
+        assert_eq!(SpectrumParams { fft_size: 1 << 20, ..params }.clamped().fft_size, MAX_FFT_SIZE);
+    }
+}
@@ -0,0 +1,159 @@
+//! Internet radio and other streams played over HTTP
+//!
+//! A stream cannot seek, but decoders find its format by reading the start
+//! and seeking back, so the first bytes read are kept to seek back into.
+
+use crate::error::{LofiTurtleError, Result};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Start of a stream kept for seeking back
+const HEAD_BYTES: usize = 256 * 1024;
+
+/// Streams are endless, so only connecting has a time limit
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether `path` is the URL of a stream rather than a file
+pub fn is_stream(path: &str) -> bool {
+    let scheme = path.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+    matches!(scheme.as_deref(), Some("http" | "https"))
+}
+
+/// A stream read over HTTP
+pub struct HttpStream {
+    // Behind a mutex only because decoders must be Sync
+    reader: Mutex<Box<dyn Read + Send>>,
+    mime_type: Option<String>,
+    head: Vec<u8>,
+    /// Bytes read from the server
+    fetched: u64,
+    position: u64,
+}
+
+impl HttpStream {
+    pub fn open(url: &str) -> Result<Self> {
+        let agent: ureq::Agent = ureq::Agent::config_builder().timeout_connect(Some(CONNECT_TIMEOUT)).build().into();
+        let response = agent
+            .get(url)
+            .call()
+            .map_err(|e| LofiTurtleError::AudioPlayback(format!("Failed to open stream '{}': {}", url, e)))?;
+
+        let mime_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_string());
+        Ok(Self::new(Box::new(response.into_body().into_reader()), mime_type))
+    }
+
+    fn new(reader: Box<dyn Read + Send>, mime_type: Option<String>) -> Self {
+        Self { reader: Mutex::new(reader), mime_type, head: Vec::new(), fetched: 0, position: 0 }
+    }
+
+    /// Content type the server gave the stream
+    pub fn mime_type(&self) -> Option<&str> {
+        self.mime_type.as_deref()
+    }
+
+    /// Whether every byte read so far is kept
+    fn head_complete(&self) -> bool {
+        self.fetched == self.head.len() as u64
+    }
+}
+
+impl Read for HttpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position < self.fetched {
+            let head = &self.head[self.position as usize..];
+            let count = head.len().min(buf.len());
+            buf[..count].copy_from_slice(&head[..count]);
+            self.position += count as u64;
+            return Ok(count);
+        }
+
+        let reader = self.reader.get_mut().map_err(|_| io::Error::other("stream reader poisoned"))?;
+        let count = reader.read(buf)?;
+        if self.head_complete() && self.head.len() + count <= HEAD_BYTES {
+            self.head.extend_from_slice(&buf[..count]);
+        }
+        self.fetched += count as u64;
+        self.position = self.fetched;
+        Ok(count)
+    }
+}
+
+impl Seek for HttpStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+        match target {
+            Some(target) if target == self.position || (target <= self.fetched && self.head_complete()) => {
+                self.position = target;
+                Ok(target)
+            }
+            _ => Err(io::Error::new(io::ErrorKind::Unsupported, "streams cannot seek")),
+        }
+    }
+}
+
+/// What a song is decoded from: a file, or a stream
+pub enum Media {
+    File(BufReader<File>),
+    Stream(HttpStream),
+}
+
+impl Read for Media {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Media::File(file) => file.read(buf),
+            Media::Stream(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Seek for Media {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Media::File(file) => file.seek(pos),
+            Media::Stream(stream) => stream.seek(pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stream() {
+        assert!(is_stream("http://radio.example.com:8000/lofi"));
+        assert!(is_stream("HTTPS://example.com/lofi.mp3"));
+        assert!(!is_stream("/music/http://.mp3"));
+        assert!(!is_stream("file:///music/a.mp3"));
+        assert!(!is_stream("/music/a.mp3"));
+    }
+
+    #[test]
+    fn test_stream_seeks_back_into_its_head() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut stream = HttpStream::new(Box::new(io::Cursor::new(data)), None);
+
+        let mut probe = [0; 16];
+        stream.read_exact(&mut probe).unwrap();
+        assert_eq!(stream.seek(SeekFrom::Start(4)).unwrap(), 4);
+        assert_eq!(stream.stream_position().unwrap(), 4);
+
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, (4..=255).collect::<Vec<u8>>());
+
+        assert!(stream.seek(SeekFrom::End(0)).is_err());
+        assert!(stream.seek(SeekFrom::Start(300)).is_err());
+    }
+}
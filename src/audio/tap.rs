@@ -0,0 +1,202 @@
+//! Copies of the samples being played, for visualizers
+//!
+//! Songs are wrapped in [`Tapped`] before they reach the sink, which mixes
+//! each frame down to mono for analysis, keeps its first two channels for
+//! level meters, and hands them to a shared [`SampleTap`] in batches. The
+//! audio callback never waits for the lock: a batch is dropped when the UI
+//! happens to be reading, which a visualizer does not notice.
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Samples kept, enough for the largest spectrum analysis
+pub const TAP_CAPACITY: usize = 16384;
+
+/// Stereo frames kept, enough for a level meter's window
+pub const STEREO_CAPACITY: usize = 8192;
+
+/// Frames collected before they are handed to the tap
+const BATCH_FRAMES: usize = 256;
+
+/// The most recent samples played, mixed down to mono and in stereo
+#[derive(Debug, Default)]
+pub struct SampleTap {
+    samples: VecDeque<Sample>,
+    /// Left and right of each frame; mono songs have both the same
+    stereo: VecDeque<[Sample; 2]>,
+    sample_rate: SampleRate,
+}
+
+/// Tap shared between the audio thread and the UI
+pub type SharedTap = Arc<Mutex<SampleTap>>;
+
+impl SampleTap {
+    /// The latest `count` samples, oldest first, padded with silence at the
+    /// start if fewer have been played, with their sample rate
+    pub fn latest(&self, count: usize) -> (Vec<Sample>, SampleRate) {
+        let available = self.samples.len().min(count);
+        let mut samples = vec![0.0; count - available];
+        samples.extend(self.samples.range(self.samples.len() - available..));
+        (samples, self.sample_rate)
+    }
+
+    /// The latest `count` stereo frames, oldest first; fewer if fewer have
+    /// been played
+    pub fn latest_stereo(&self, count: usize) -> Vec<[Sample; 2]> {
+        let available = self.stereo.len().min(count);
+        self.stereo.range(self.stereo.len() - available..).copied().collect()
+    }
+
+    /// Sample rate of the song played last
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    fn push(&mut self, samples: &[Sample], stereo: &[[Sample; 2]], sample_rate: SampleRate) {
+        self.sample_rate = sample_rate;
+        keep_latest(&mut self.samples, samples, TAP_CAPACITY);
+        keep_latest(&mut self.stereo, stereo, STEREO_CAPACITY);
+    }
+}
+
+/// Append `items` to `queue`, dropping the oldest beyond `capacity`
+fn keep_latest<T: Copy>(queue: &mut VecDeque<T>, items: &[T], capacity: usize) {
+    let overflow = (queue.len() + items.len()).saturating_sub(capacity);
+    queue.drain(..overflow.min(queue.len()));
+    queue.extend(&items[items.len().saturating_sub(capacity)..]);
+}
+
+/// A song whose samples are copied to a tap as they are played
+pub struct Tapped<S> {
+    source: S,
+    tap: SharedTap,
+    batch: Vec<Sample>,
+    stereo_batch: Vec<[Sample; 2]>,
+    frame_sum: Sample,
+    frame: [Sample; 2],
+    channel: ChannelCount,
+}
+
+impl<S: Source> Tapped<S> {
+    pub fn new(source: S, tap: SharedTap) -> Self {
+        Self {
+            source,
+            tap,
+            batch: Vec::with_capacity(BATCH_FRAMES),
+            stereo_batch: Vec::with_capacity(BATCH_FRAMES),
+            frame_sum: 0.0,
+            frame: [0.0; 2],
+            channel: 0,
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Ok(mut tap) = self.tap.try_lock() {
+            tap.push(&self.batch, &self.stereo_batch, self.source.sample_rate());
+        }
+        self.batch.clear();
+        self.stereo_batch.clear();
+    }
+}
+
+impl<S: Source> Iterator for Tapped<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let Some(sample) = self.source.next() else {
+            if !self.batch.is_empty() {
+                self.flush();
+            }
+            return None;
+        };
+
+        self.frame_sum += sample;
+        if let Some(side) = self.frame.get_mut(self.channel as usize) {
+            *side = sample;
+        }
+        self.channel += 1;
+        let channels = self.source.channels().max(1);
+        if self.channel >= channels {
+            self.batch.push(self.frame_sum / channels as Sample);
+            let right = if channels == 1 { self.frame[0] } else { self.frame[1] };
+            self.stereo_batch.push([self.frame[0], right]);
+            self.frame_sum = 0.0;
+            self.channel = 0;
+            if self.batch.len() == BATCH_FRAMES {
+                self.flush();
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Tapped<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.source.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.source.try_seek(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    #[test]
+    fn test_tap_keeps_mono_copy_of_latest_samples() {
+        let tap = SharedTap::default();
+        // Stereo frames whose channels average to the frame number
+        let samples: Vec<Sample> = (0..BATCH_FRAMES * 3).flat_map(|i| [i as Sample - 1.0, i as Sample + 1.0]).collect();
+        let played: Vec<Sample> = Tapped::new(SamplesBuffer::new(2, 8000, samples.clone()), Arc::clone(&tap)).collect();
+        assert_eq!(played, samples);
+
+        let (latest, sample_rate) = tap.lock().unwrap().latest(4);
+        assert_eq!(sample_rate, 8000);
+        let last = (BATCH_FRAMES * 3) as Sample;
+        assert_eq!(latest, vec![last - 4.0, last - 3.0, last - 2.0, last - 1.0]);
+
+        // Padded with silence before the first sample
+        let (latest, _) = tap.lock().unwrap().latest(BATCH_FRAMES * 3 + 2);
+        assert_eq!(&latest[..3], &[0.0, 0.0, 0.0]);
+
+        let stereo = tap.lock().unwrap().latest_stereo(2);
+        assert_eq!(stereo, vec![[last - 3.0, last - 1.0], [last - 2.0, last]]);
+    }
+
+    #[test]
+    fn test_mono_tap_has_both_sides() {
+        let tap = SharedTap::default();
+        let _: Vec<Sample> = Tapped::new(SamplesBuffer::new(1, 8000, vec![0.5; 10]), Arc::clone(&tap)).collect();
+        let stereo = tap.lock().unwrap().latest_stereo(100);
+        assert_eq!(stereo, vec![[0.5, 0.5]; 10]);
+    }
+
+    #[test]
+    fn test_tap_is_bounded() {
+        let mut tap = SampleTap::default();
+        tap.push(&vec![1.0; TAP_CAPACITY], &vec![[1.0; 2]; STEREO_CAPACITY], 44100);
+        tap.push(&[2.0; 10], &[[2.0; 2]; 10], 44100);
+        assert_eq!(tap.samples.len(), TAP_CAPACITY);
+        assert_eq!(tap.samples.back(), Some(&2.0));
+        assert_eq!(tap.stereo.len(), STEREO_CAPACITY);
+        assert_eq!(tap.stereo.back(), Some(&[2.0; 2]));
+    }
+}
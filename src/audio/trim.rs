@@ -0,0 +1,132 @@
+//! Trimmed songs
+//!
+//! A song can skip the first seconds of its file, like a long intro, and
+//! stop before the last ones, like silence after the final note. Positions
+//! still count from the start of the file, so a song with five seconds
+//! trimmed off starts at 0:05, and seeks and bookmarks mean the same spot
+//! trimmed or not.
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::time::Duration;
+
+/// How much of a song is skipped at its start and cut off its end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Trim {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl Trim {
+    /// Where a song lasting `total` stops, if its end is cut and its length
+    /// is known
+    fn stop_at(&self, total: Option<Duration>) -> Option<Duration> {
+        total.filter(|_| !self.end.is_zero()).map(|total| total.saturating_sub(self.end))
+    }
+}
+
+/// A song played with its trim
+pub struct Trimmed<S> {
+    source: S,
+    /// Where the song stops, if its end is cut
+    stop_at: Option<Duration>,
+    /// Samples left until then
+    remaining: Option<u64>,
+}
+
+impl<S: Source> Trimmed<S> {
+    pub fn new(mut source: S, trim: Trim) -> Self {
+        let stop_at = trim.stop_at(source.total_duration());
+        let mut start = trim.start;
+        if !start.is_zero() {
+            if let Err(e) = source.try_seek(start) {
+                log::warn!("Failed to skip the trimmed start: {}", e);
+                start = Duration::ZERO;
+            }
+        }
+
+        let mut trimmed = Self { source, stop_at, remaining: None };
+        trimmed.count_from(start);
+        trimmed
+    }
+
+    /// Count the samples left from `position` to where the song stops
+    fn count_from(&mut self, position: Duration) {
+        let samples_per_second = self.source.sample_rate() as f64 * self.source.channels() as f64;
+        self.remaining = self
+            .stop_at
+            .map(|stop_at| (stop_at.saturating_sub(position).as_secs_f64() * samples_per_second).round() as u64);
+    }
+}
+
+impl<S: Source> Iterator for Trimmed<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(remaining) = self.remaining.as_mut() {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        self.source.next()
+    }
+}
+
+impl<S: Source> Source for Trimmed<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        let remaining = self.remaining.map(|remaining| remaining as usize);
+        match (self.source.current_span_len(), remaining) {
+            (Some(span), Some(remaining)) => Some(span.min(remaining)),
+            (span, remaining) => span.or(remaining),
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.stop_at.or(self.source.total_duration())
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.source.try_seek(position)?;
+        self.count_from(position);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    /// Five seconds of one channel at 10 Hz, each sample its own index
+    fn five_seconds() -> SamplesBuffer {
+        SamplesBuffer::new(1, 10, (0..50).map(|i| i as Sample).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_trim_skips_start_and_cuts_end() {
+        let trim = Trim { start: Duration::from_secs(1), end: Duration::from_secs(2) };
+        let trimmed = Trimmed::new(five_seconds(), trim);
+        assert_eq!(trimmed.total_duration(), Some(Duration::from_secs(3)));
+        assert_eq!(trimmed.collect::<Vec<_>>(), (10..30).map(|i| i as Sample).collect::<Vec<_>>());
+
+        let whole: Vec<Sample> = Trimmed::new(five_seconds(), Trim::default()).collect();
+        assert_eq!(whole.len(), 50);
+    }
+
+    #[test]
+    fn test_seek_counts_from_the_new_position() {
+        let trim = Trim { start: Duration::ZERO, end: Duration::from_secs(1) };
+        let mut trimmed = Trimmed::new(five_seconds(), trim);
+        trimmed.try_seek(Duration::from_secs(3)).unwrap();
+        assert_eq!(trimmed.collect::<Vec<_>>(), (30..40).map(|i| i as Sample).collect::<Vec<_>>());
+    }
+}
@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::config::paths::{self, Location};
+use crate::services::wrapped::WrappedPeriod;
 use std::path::PathBuf;
 
 /// Repeat mode argument for CLI
@@ -9,6 +12,70 @@ pub enum RepeatModeArg {
     Playlist,
 }
 
+/// Log level argument for CLI
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LogLevelArg {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevelArg> for log::LevelFilter {
+    fn from(level: LogLevelArg) -> Self {
+        match level {
+            LogLevelArg::Off => log::LevelFilter::Off,
+            LogLevelArg::Error => log::LevelFilter::Error,
+            LogLevelArg::Warn => log::LevelFilter::Warn,
+            LogLevelArg::Info => log::LevelFilter::Info,
+            LogLevelArg::Debug => log::LevelFilter::Debug,
+            LogLevelArg::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// Auto-DJ strategy argument for CLI
+#[derive(Debug, Clone, ValueEnum)]
+pub enum AutoDjArg {
+    SameArtist,
+    SameAlbum,
+    SameGenre,
+    LeastRecentlyPlayed,
+    Random,
+}
+
+/// Song changes to crossfade, as a CLI argument
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CrossfadeOnArg {
+    /// Songs started by hand, e.g. Next or picking one from the list
+    Skip,
+    /// Songs following one that played to the end
+    End,
+    /// Every song change
+    Both,
+}
+
+/// Song field `search --regex` looks in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SearchField {
+    Title,
+    Artist,
+    Album,
+    Path,
+}
+
+/// How `list --group-by` nests the songs it prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// Artist, then album
+    Artist,
+    Album,
+    /// Genre, then artist and album
+    Genre,
+}
+
 /// LofiTurtle - A terminal-based music player
 #[derive(Parser, Debug)]
 #[command(name = "lofiturtle")]
@@ -19,18 +86,43 @@ pub struct Cli {
     #[arg(short, long, value_name = "DIR")]
     pub music_dir: Option<PathBuf>,
 
-    /// Database file path
-    #[arg(short, long, value_name = "FILE", default_value = "music_library.db")]
-    pub database: PathBuf,
+    /// Database file path [default: ~/.local/share/lofiturtle/music_library.db]
+    #[arg(short, long, value_name = "FILE")]
+    pub database: Option<PathBuf>,
 
     /// Enable verbose logging
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Write log records at this level and above to the log file
+    /// (defaults to debug with --verbose, otherwise RUST_LOG or off)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub log_level: Option<LogLevelArg>,
+
+    /// Log file, rotated by size [default: ~/.local/state/lofiturtle/lofiturtle.log]
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
     /// Disable library scanning on startup
     #[arg(long)]
     pub no_scan: bool,
 
+    /// Follow symbolic links when scanning the music directory; they are
+    /// skipped otherwise
+    #[arg(long)]
+    pub follow_symlinks: bool,
+
+    /// Treat directories that take longer than this many seconds to list,
+    /// and files whose tags take that long to read, as unreachable (useful
+    /// for NFS/SMB mounts)
+    #[arg(long, value_name = "SECS")]
+    pub io_timeout: Option<u64>,
+
+    /// Read at most this many files per second when scanning, so a rescan
+    /// while music plays does not starve playback on slow disks
+    #[arg(long, value_name = "FILES", value_parser = clap::value_parser!(u32).range(1..))]
+    pub scan_rate: Option<u32>,
+
     /// Show album art in terminal (enabled by default)
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub show_art: bool,
@@ -39,6 +131,56 @@ pub struct Cli {
     #[arg(long, conflicts_with = "show_art")]
     pub no_art: bool,
 
+    /// Plain text for terminal screen readers: no album art or decorative
+    /// symbols, with changes announced on the status line
+    #[arg(long)]
+    pub screen_reader: bool,
+
+    /// Most pieces of rendered album art kept in memory
+    #[arg(long, value_name = "COUNT", default_value_t = crate::art::DEFAULT_CACHE_MAX_ENTRIES)]
+    pub art_cache_entries: usize,
+
+    /// Most megabytes of rendered album art kept in memory
+    #[arg(long, value_name = "MB", default_value_t = crate::art::DEFAULT_CACHE_MAX_BYTES / (1024 * 1024))]
+    pub art_cache_mb: usize,
+
+
+    /// Seconds of the next song decoded ahead so it starts without a gap;
+    /// 0 starts every song on its own. Remembered for next time [default: 5]
+    #[arg(long, value_name = "SECS")]
+    pub prebuffer: Option<u64>,
+
+    /// Frames in the audio device buffer; raise it if playback crackles on a
+    /// slow machine, at the cost of latency. Remembered for next time; 0
+    /// goes back to the device's [default: the device's]
+    #[arg(long, value_name = "FRAMES")]
+    pub audio_buffer: Option<u32>,
+
+    /// Seconds over which one song fades into the next; 0 cuts straight over
+    #[arg(long, value_name = "SECS", default_value_t = 0)]
+    pub crossfade: u64,
+
+    /// Which song changes fade into each other with --crossfade; with `skip`,
+    /// songs that play to the end still follow without a gap
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "both")]
+    pub crossfade_on: CrossfadeOnArg,
+
+    /// Decibels every song is raised (up to 15) or lowered (down to -15) by,
+    /// for quiet DACs; a limiter keeps raised songs from clipping. It is
+    /// remembered for later runs [default: the last one given, else 0]
+    #[arg(long, value_name = "DB", allow_negative_numbers = true,
+          value_parser = crate::audio::preamp::parse_preamp)]
+    pub preamp: Option<f32>,
+
+    /// Audio host to play through, e.g. ALSA, CoreAudio or WASAPI (see the
+    /// devices command) [default: the platform's]
+    #[arg(long, value_name = "HOST")]
+    pub audio_host: Option<String>,
+
+    /// Output device of the audio host (see the devices command)
+    /// [default: the host's]
+    #[arg(long, value_name = "NAME")]
+    pub audio_device: Option<String>,
 
     /// Enable shuffle mode
     #[arg(long)]
@@ -48,17 +190,88 @@ pub struct Cli {
     #[arg(long, value_enum)]
     pub repeat: Option<RepeatModeArg>,
 
+    /// Keep playing picked tracks when the queue runs out and repeat is off
+    #[arg(long, value_enum, value_name = "STRATEGY")]
+    pub auto_dj: Option<AutoDjArg>,
+
+    /// When an album played on its own runs out and repeat is off, go on to
+    /// the artist's next album, or the next one alphabetically. Remembered
+    /// for next time; `--continue-albums=false` turns it off
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    pub continue_albums: Option<bool>,
+
+    /// Number of days the "Recently Added" view looks back
+    #[arg(long, value_name = "DAYS", default_value_t = 30)]
+    pub recent_days: u32,
+
     /// Use CLI mode instead of TUI interface
     #[arg(long)]
     pub cli_mode: bool,
 
-    /// Layout configuration file path
-    #[arg(long, value_name = "FILE", default_value = "layout.toml")]
-    pub layout_config: PathBuf,
+    /// Layout configuration file path [default: ~/.config/lofiturtle/layout.toml]
+    #[arg(long, value_name = "FILE")]
+    pub layout_config: Option<PathBuf>,
+
+    /// Keymap configuration file path [default: ~/.config/lofiturtle/keymap.toml]
+    #[arg(long, value_name = "FILE")]
+    pub keymap_config: Option<PathBuf>,
+
+    /// Genre normalization rules [default: ~/.config/lofiturtle/genres.toml]
+    #[arg(long, value_name = "FILE")]
+    pub genres_config: Option<PathBuf>,
+
+    /// Append player events as JSON lines to this file for external integrations
+    #[arg(long, value_name = "FILE")]
+    pub event_log: Option<PathBuf>,
+
+    /// Delete song files permanently instead of moving them to the trash
+    #[arg(long)]
+    pub no_trash: bool,
+
+    /// Do not register with the OS media session (media keys, now-playing display)
+    #[arg(long)]
+    pub no_media_keys: bool,
+
+    /// AcoustID API key for identifying untagged files (defaults to the
+    /// ACOUSTID_API_KEY environment variable)
+    #[arg(long, value_name = "KEY")]
+    pub acoustid_key: Option<String>,
+
+    /// ListenBrainz user token for scrobbling listens (defaults to the
+    /// LISTENBRAINZ_TOKEN environment variable)
+    #[arg(long, value_name = "TOKEN")]
+    pub listenbrainz_token: Option<String>,
+
+    /// Last.fm API key for syncing loved tracks (defaults to the
+    /// LASTFM_API_KEY environment variable)
+    #[arg(long, value_name = "KEY")]
+    pub lastfm_api_key: Option<String>,
+
+    /// Shared secret of the Last.fm API key (defaults to the
+    /// LASTFM_API_SECRET environment variable)
+    #[arg(long, value_name = "SECRET")]
+    pub lastfm_secret: Option<String>,
 
-    /// Keymap configuration file path
-    #[arg(long, value_name = "FILE", default_value = "keymap.toml")]
-    pub keymap_config: PathBuf,
+    /// Directory of Rhai scripts to run on player events
+    /// [default: ~/.config/lofiturtle/scripts]
+    #[arg(long, value_name = "DIR")]
+    pub scripts_dir: Option<PathBuf>,
+
+    /// Language of the interface, e.g. "vi" (defaults to the saved setting,
+    /// then the system locale)
+    #[arg(long, value_name = "LANG", global = true)]
+    pub locale: Option<String>,
+
+    /// Keep the database, settings, layout, keymap, scripts and log in one
+    /// directory: DIR, or the executable's without it. Relative paths given
+    /// for those files are taken from there
+    #[arg(long, value_name = "DIR", num_args = 0..=1, require_equals = true)]
+    pub portable: Option<Option<PathBuf>>,
+
+    /// Keep a separate library, settings, layout and log under this name,
+    /// e.g. "lofi" or "classical"
+    #[arg(long, value_name = "NAME", value_parser = paths::parse_profile, global = true)]
+    pub profile: Option<String>,
 
     /// Dump complete layout configuration to file
     #[arg(long, value_name = "FILE")]
@@ -85,6 +298,21 @@ pub enum Commands {
         /// Force rescan of all files
         #[arg(short, long)]
         force: bool,
+        /// Look up untagged files on AcoustID and write the tags found
+        #[arg(long)]
+        identify: bool,
+        /// Print what was added, updated, skipped and failed, with reasons
+        #[arg(long)]
+        report: bool,
+        /// Only print what would be added and removed, leaving the library alone
+        #[arg(long, conflicts_with = "identify")]
+        dry_run: bool,
+    },
+    /// Remove songs whose files no longer exist from the library
+    Prune {
+        /// Only print what would be removed
+        #[arg(long)]
+        dry_run: bool,
     },
     /// List all songs in the database
     List {
@@ -94,17 +322,137 @@ pub enum Commands {
         /// Filter by album
         #[arg(short = 'A', long)]
         album: Option<String>,
+        /// Print the songs as a tree of groups, with totals for each
+        #[arg(long, value_enum, value_name = "GROUP")]
+        group_by: Option<GroupBy>,
     },
     /// Search for songs
     Search {
         /// Search query
-        query: String,
+        #[arg(required_unless_present = "regex", conflicts_with = "regex")]
+        query: Option<String>,
+        /// Match a regular expression instead, e.g. '(?i)^the ' or '\.flac$'
+        #[arg(long, value_name = "PATTERN")]
+        regex: Option<String>,
+        /// Fields the regular expression is matched against, comma separated
+        /// [default: title,artist,album,path]
+        // clap drops `requires` for an argument that conflicts with one
+        // given, so a query rules --field out itself
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "FIELDS", requires = "regex", conflicts_with = "query")]
+        field: Vec<SearchField>,
     },
     /// Manage playlists
     Playlist {
         #[command(subcommand)]
         action: PlaylistAction,
     },
+    /// Work with the queue of the running player
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+    /// Export or import library changes to sync between machines
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+    /// Rename and move music files according to their tags
+    ///
+    /// Only prints the planned moves unless --apply is given.
+    Organize {
+        /// Target layout relative to the music directory. Placeholders:
+        /// {artist}, {album}, {title}, {track}, {year}
+        #[arg(short, long, default_value = "{artist}/{album}/{track} - {title}")]
+        pattern: String,
+        /// Move the files instead of only showing the plan
+        #[arg(long)]
+        apply: bool,
+        /// Only show the plan, which is the default without --apply
+        #[arg(long, conflicts_with = "apply")]
+        dry_run: bool,
+    },
+    /// Fill in missing tags by parsing file names
+    ///
+    /// Existing tags are kept. Only prints the changes unless --apply is given.
+    Tag {
+        /// Layout of the file names. Placeholders: {artist}, {album},
+        /// {title}, {track}, {year}. Use / to match parent folders
+        #[arg(short, long, default_value = "{track} - {artist} - {title}")]
+        pattern: String,
+        /// Directory to tag (defaults to the music directory)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Write the tags instead of only showing them
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Identify untagged library files by audio fingerprint
+    ///
+    /// Requires chromaprint's fpcalc and an AcoustID API key. Title, artist
+    /// and album are written to files that are missing them.
+    Identify {
+        /// Only show what would be tagged
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Work with the local listening history
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Bring play counts, last plays and ratings over from another player
+    ///
+    /// Counts already higher here and ratings already given are kept, so
+    /// importing twice changes nothing.
+    Import {
+        #[command(subcommand)]
+        action: ImportAction,
+    },
+    /// Write the library with its playlists, play counts and ratings for
+    /// another player
+    Export {
+        /// Format to write
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// File to write for itunes-xml; directory for the playlists and
+        /// sticker.sql for mpd
+        output: PathBuf,
+    },
+    /// Apply the genre normalization rules of genres.toml
+    Genres {
+        #[command(subcommand)]
+        action: GenresAction,
+    },
+    /// Manage album art
+    Art {
+        #[command(subcommand)]
+        action: ArtAction,
+    },
+    /// Sync the songs marked as loved with Last.fm
+    Loved {
+        #[command(subcommand)]
+        action: LovedAction,
+    },
+    /// Summarize your listening over the past week, month or year
+    Wrapped {
+        #[command(flatten)]
+        period: WrappedPeriodArgs,
+        /// Also write the summary to a Markdown file
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
+    },
+    /// Inspect the configuration files
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Inspect the built-in themes and the layout's theme
+    Theme {
+        #[command(subcommand)]
+        action: ThemeAction,
+    },
+    /// List the audio hosts and their output devices, marking the one used
+    Devices,
     /// Toggle shuffle mode
     Shuffle {
         /// Enable or disable shuffle
@@ -163,6 +511,218 @@ pub enum PlaylistAction {
         /// Playlist name
         name: String,
     },
+    /// Make a new playlist of the songs in either of two playlists
+    Merge {
+        /// First playlist, whose order comes first
+        first: String,
+        /// Second playlist
+        second: String,
+        /// Name of the new playlist
+        #[arg(long, value_name = "NAME")]
+        into: String,
+    },
+    /// Make a new playlist of the songs two playlists share
+    Intersect {
+        /// First playlist, whose order is kept
+        first: String,
+        /// Second playlist
+        second: String,
+        /// Name of the new playlist
+        #[arg(long, value_name = "NAME")]
+        into: String,
+    },
+    /// Make a new playlist of the songs in one playlist but not another
+    Subtract {
+        /// Playlist to take songs from
+        first: String,
+        /// Playlist whose songs are left out
+        second: String,
+        /// Name of the new playlist
+        #[arg(long, value_name = "NAME")]
+        into: String,
+    },
+}
+
+/// Queue actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum QueueAction {
+    /// Save the queue as a new playlist, in queue order
+    Save {
+        /// Playlist name
+        name: String,
+    },
+}
+
+/// Library sync actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum SyncAction {
+    /// Write playlists, play counts and ratings to a sync file
+    Export {
+        /// Sync file to write
+        file: PathBuf,
+        /// Only export playlists changed and songs played after this time (RFC 3339)
+        #[arg(long, value_name = "TIME")]
+        since: Option<DateTime<Utc>>,
+    },
+    /// Merge a sync file into the library
+    Import {
+        /// Sync file to read
+        file: PathBuf,
+    },
+}
+
+/// Listening history actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum HistoryAction {
+    /// Write the full listening history for backup or import elsewhere
+    Export {
+        /// File to write (defaults to standard output)
+        file: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Csv)]
+        format: HistoryFormat,
+    },
+}
+
+/// Players to import play counts and ratings from
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportAction {
+    /// Import from the Library.xml iTunes or Music exports
+    Itunes {
+        /// Library.xml file (File > Library > Export Library)
+        file: PathBuf,
+        /// Only print what would be imported
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import the playCount, lastPlayed and rating stickers of MPD
+    Mpd {
+        /// MPD's sticker database, e.g. ~/.mpd/sticker.sql
+        stickers: PathBuf,
+        /// MPD's music_directory, which sticker paths are relative to
+        /// (defaults to the music directory)
+        #[arg(long, value_name = "DIR")]
+        mpd_music_dir: Option<PathBuf>,
+        /// Only print what would be imported
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Genre actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum GenresAction {
+    /// Store the genres of songs already in the library as the rules say,
+    /// as a scan does for new ones
+    Normalize {
+        /// Only print what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Album art actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum ArtAction {
+    /// Download covers from the Cover Art Archive for albums without art
+    ///
+    /// Albums are looked up by the MusicBrainz release ID in their tags,
+    /// or searched for on MusicBrainz by album artist and album. Covers are
+    /// kept in the cache directory and shown in the album art panel while a
+    /// song of the album plays; the player has no album view to show them in.
+    Fetch {
+        /// Only list the albums that would be looked up
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a cover into the tags of every file of an album
+    ///
+    /// The cover is the given image, or the one `art fetch` downloaded for
+    /// the album. It then becomes the album's cover in the cache too.
+    Embed {
+        /// Album to write the cover into, ignoring case
+        album: String,
+        /// Only the album by this album artist, ignoring case; needed when
+        /// several artists have an album with this name
+        #[arg(long)]
+        artist: Option<String>,
+        /// JPEG or PNG image to use instead of the downloaded cover
+        #[arg(long, value_name = "FILE")]
+        image: Option<PathBuf>,
+        /// Only list the files that would be changed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Loved track actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum LovedAction {
+    /// Allow LofiTurtle to love tracks on your Last.fm account, keeping
+    /// the session key in the config directory
+    Login,
+    /// Mark library songs loved on Last.fm as loved, and love on Last.fm
+    /// the songs loved here that are not yet
+    ///
+    /// Songs are matched by artist and title, ignoring case. Nothing is
+    /// unloved on either side.
+    Sync {
+        /// Only print what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Configuration actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Report every problem in the settings, layout and keymap files
+    ///
+    /// The layout and keymap checked are those given with --layout-config
+    /// and --keymap-config. Exits with an error if any file has errors.
+    Check,
+}
+
+/// Theme actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum ThemeAction {
+    /// Measure the contrast of the theme's text, borders and bars against
+    /// their background, warning about pairs that are hard to read
+    ///
+    /// NAME is a built-in theme, like high_contrast, or the theme of the
+    /// layout given with --layout-config.
+    Check { name: String },
+}
+
+/// Formats the library can be exported in
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// A Library.xml as iTunes and Music write it
+    ItunesXml,
+    /// M3U playlists and a sticker database for MPD
+    Mpd,
+}
+
+/// File format of history exports
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+/// Period covered by `wrapped`, the past week unless another is chosen
+#[derive(Args, Debug, Clone, Copy)]
+#[group(multiple = false)]
+pub struct WrappedPeriodArgs {
+    /// Summarize the past week
+    #[arg(long)]
+    pub week: bool,
+    /// Summarize the past month
+    #[arg(long)]
+    pub month: bool,
+    /// Summarize the past year
+    #[arg(long)]
+    pub year: bool,
 }
 
 /// Shuffle mode for CLI
@@ -171,9 +731,50 @@ pub enum ShuffleMode {
     On,
     Off,
     Toggle,
+    /// Shuffle without the same artist twice in a row, playing songs heard
+    /// lately last
+    Smart,
+}
+
+impl WrappedPeriodArgs {
+    /// The chosen period
+    pub fn period(&self) -> WrappedPeriod {
+        if self.year {
+            WrappedPeriod::Year
+        } else if self.month {
+            WrappedPeriod::Month
+        } else {
+            WrappedPeriod::Week
+        }
+    }
 }
 
 impl Cli {
+    /// Database file to use
+    pub fn database_path(&self) -> PathBuf {
+        paths::resolve(self.database.as_deref(), Location::Data, "music_library.db")
+    }
+
+    /// Layout file to load
+    pub fn layout_path(&self) -> PathBuf {
+        paths::resolve(self.layout_config.as_deref(), Location::Config, "layout.toml")
+    }
+
+    /// Keymap file to load
+    pub fn keymap_path(&self) -> PathBuf {
+        paths::resolve(self.keymap_config.as_deref(), Location::Config, "keymap.toml")
+    }
+
+    /// Genre normalization rules to load
+    pub fn genres_path(&self) -> PathBuf {
+        paths::resolve(self.genres_config.as_deref(), Location::Config, "genres.toml")
+    }
+
+    /// Directory of scripts to load
+    pub fn scripts_path(&self) -> PathBuf {
+        paths::resolve(self.scripts_dir.as_deref(), Location::Config, "scripts")
+    }
+
     /// Get the music directory, using platform-specific defaults if not specified
     pub fn get_music_dir(&self) -> PathBuf {
         if let Some(ref dir) = self.music_dir {
@@ -185,8 +786,85 @@ impl Cli {
             return dir.clone();
         }
 
-        // Use platform-specific default directories
-        Self::default_music_dir()
+        // Then the one saved in the settings, or the platform's default
+        crate::config::PersistentSettings::load().music_dir.unwrap_or_else(Self::default_music_dir)
+    }
+
+    /// Audio host and output device to play through: those on the command
+    /// line, or else the ones picked in the player
+    pub fn audio_output(&self) -> (Option<String>, Option<String>) {
+        if self.audio_host.is_some() || self.audio_device.is_some() {
+            return (self.audio_host.clone(), self.audio_device.clone());
+        }
+        let settings = crate::config::PersistentSettings::load();
+        (settings.audio_host, settings.audio_device)
+    }
+
+    /// Preamp to play with in decibels: the one on the command line, which
+    /// is remembered for next time, or else the last one given
+    pub fn preamp_db(&self) -> f32 {
+        let settings = crate::config::PersistentSettings::load();
+        let Some(db) = self.preamp else {
+            // A hand-edited setting out of range is ignored
+            return settings.preamp_db.filter(|db| db.abs() <= crate::audio::preamp::MAX_PREAMP_DB).unwrap_or(0.0);
+        };
+        if settings.preamp_db != Some(db) {
+            let settings = crate::config::PersistentSettings { preamp_db: Some(db), ..settings };
+            if let Err(e) = settings.save() {
+                log::warn!("Failed to save the preamp: {}", e);
+            }
+        }
+        db
+    }
+
+    /// Seconds of the next song to decode ahead: the ones on the command
+    /// line, which are remembered for next time, or else the last given
+    pub fn prebuffer_secs(&self) -> u64 {
+        let settings = crate::config::PersistentSettings::load();
+        let Some(secs) = self.prebuffer else {
+            return settings.prebuffer_secs.unwrap_or(crate::audio::player::DEFAULT_PREBUFFER_SECS);
+        };
+        if settings.prebuffer_secs != Some(secs) {
+            let settings = crate::config::PersistentSettings { prebuffer_secs: Some(secs), ..settings };
+            if let Err(e) = settings.save() {
+                log::warn!("Failed to save the prebuffer: {}", e);
+            }
+        }
+        secs
+    }
+
+    /// Frames in the audio device buffer, `None` for the device's own: as
+    /// given on the command line, which is remembered for next time, or else
+    /// as last given
+    pub fn audio_buffer_frames(&self) -> Option<u32> {
+        let settings = crate::config::PersistentSettings::load();
+        let Some(frames) = self.audio_buffer else {
+            return settings.audio_buffer_frames;
+        };
+        let frames = Some(frames).filter(|&frames| frames > 0);
+        if settings.audio_buffer_frames != frames {
+            let settings = crate::config::PersistentSettings { audio_buffer_frames: frames, ..settings };
+            if let Err(e) = settings.save() {
+                log::warn!("Failed to save the audio buffer size: {}", e);
+            }
+        }
+        frames
+    }
+
+    /// Whether to go on to the next album: as given on the command line,
+    /// which is remembered for next time, or else as last given
+    pub fn continue_albums(&self) -> bool {
+        let settings = crate::config::PersistentSettings::load();
+        let Some(enabled) = self.continue_albums else {
+            return settings.continue_albums;
+        };
+        if settings.continue_albums != enabled {
+            let settings = crate::config::PersistentSettings { continue_albums: enabled, ..settings };
+            if let Err(e) = settings.save() {
+                log::warn!("Failed to save whether to continue albums: {}", e);
+            }
+        }
+        enabled
     }
 
     /// Get platform-specific default music directory
@@ -200,6 +878,13 @@ impl Cli {
         }
     }
 
+    /// Whether the player is starting without a music directory ever being
+    /// chosen, so it should offer to pick one
+    pub fn needs_setup(&self) -> bool {
+        let player = matches!(self.command, None | Some(Commands::Play { music_dir: None })) && !self.cli_mode;
+        player && self.music_dir.is_none() && crate::config::PersistentSettings::load().music_dir.is_none()
+    }
+
     /// Check if the specified music directory exists
     pub fn validate_music_dir(&self) -> crate::error::Result<PathBuf> {
         let music_dir = self.get_music_dir();
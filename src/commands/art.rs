@@ -0,0 +1,182 @@
+use crate::art::{self, CoverCache, CoverTags};
+use crate::cli::ArtAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::{LofiTurtleError, Result};
+use crate::services::cover_art::CoverArtClient;
+use crate::services::LibraryService;
+use crate::t;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct ArtCommand {
+    action: ArtAction,
+}
+
+impl ArtCommand {
+    pub fn new(action: ArtAction) -> Self {
+        Self { action }
+    }
+}
+
+/// An album without art, with the release ID any of its files has
+struct Album {
+    artist: String,
+    album: String,
+    release_id: Option<String>,
+}
+
+impl ArtCommand {
+    fn fetch(&self, config: &Config, dry_run: bool) -> Result<()> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let covers = CoverCache::new(CoverCache::default_dir());
+
+        // Albums are told apart ignoring case, as the cover cache does
+        let mut albums: BTreeMap<(String, String), Album> = BTreeMap::new();
+        for song in library.get_all_songs()? {
+            let tags = match CoverTags::read(Path::new(&song.path)) {
+                Ok(tags) => tags,
+                Err(e) => {
+                    log::warn!("Skipping {}: {}", song.path, e);
+                    continue;
+                }
+            };
+            if tags.embedded || tags.album.is_empty() || covers.contains(&tags.artist, &tags.album) {
+                continue;
+            }
+
+            let key = (tags.artist.to_lowercase(), tags.album.to_lowercase());
+            let album = albums.entry(key).or_insert_with(|| Album {
+                artist: tags.artist,
+                album: tags.album,
+                release_id: None,
+            });
+            album.release_id = album.release_id.take().or(tags.release_id);
+        }
+
+        println!("{}", t!("art-fetch-start", count = albums.len()));
+        if dry_run {
+            for album in albums.values() {
+                println!("{}", t!("art-fetch-album", album = album.album.as_str(), artist = album.artist.as_str()));
+            }
+            println!("{}", t!("art-fetch-dry-run"));
+            return Ok(());
+        }
+
+        // A failed request stops the run, since the network is usually at fault
+        let mut client = CoverArtClient::new();
+        let mut fetched = 0;
+        for album in albums.values() {
+            let release_id = match &album.release_id {
+                Some(id) => Some(id.clone()),
+                None => client.find_release(&album.artist, &album.album)?,
+            };
+            let cover = match release_id {
+                Some(id) => client.front_cover(&id)?,
+                None => None,
+            };
+
+            let name = (album.album.as_str(), album.artist.as_str());
+            match cover {
+                Some(cover) => {
+                    covers.store(&album.artist, &album.album, &cover)?;
+                    fetched += 1;
+                    println!("{}", t!("art-fetch-found", album = name.0, artist = name.1));
+                }
+                None => println!("{}", t!("art-fetch-missing", album = name.0, artist = name.1)),
+            }
+        }
+
+        println!("{}", t!("art-fetch-done", fetched = fetched, missing = albums.len() - fetched));
+        Ok(())
+    }
+
+    fn embed(&self, config: &Config, album: &str, artist: Option<&str>, image: Option<&Path>, dry_run: bool) -> Result<()> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let covers = CoverCache::new(CoverCache::default_dir());
+        let fold = |text: &str| text.trim().to_lowercase();
+
+        let mut files = Vec::new();
+        let mut album_tags: Option<CoverTags> = None;
+        // Artists found under the album name, told apart ignoring case
+        let mut artists: BTreeMap<String, String> = BTreeMap::new();
+        for song in library.get_all_songs()? {
+            if fold(&song.album) != fold(album) {
+                continue;
+            }
+            let tags = match CoverTags::read(Path::new(&song.path)) {
+                Ok(tags) => tags,
+                Err(e) => {
+                    log::warn!("Skipping {}: {}", song.path, e);
+                    continue;
+                }
+            };
+            if artist.is_some_and(|artist| fold(artist) != fold(&tags.artist)) {
+                continue;
+            }
+            files.push(PathBuf::from(song.path));
+            artists.entry(fold(&tags.artist)).or_insert_with(|| tags.artist.clone());
+            album_tags.get_or_insert(tags);
+        }
+
+        let Some(tags) = album_tags else {
+            return Err(LofiTurtleError::InvalidCommand(format!("No album '{}' in the library", album)));
+        };
+        // Same-named albums by different artists must not share one cover
+        if artists.len() > 1 {
+            let artists: Vec<_> = artists.into_values().collect();
+            return Err(LofiTurtleError::InvalidCommand(format!(
+                "More than one artist has an album '{}': {}; pass --artist to pick one",
+                album,
+                artists.join(", ")
+            )));
+        }
+        let image = match image {
+            Some(path) => fs::read(path)?,
+            None => covers.get(&tags.artist, &tags.album).ok_or_else(|| {
+                LofiTurtleError::CoverArt(format!(
+                    "No cover was downloaded for {} - {}; run `art fetch` or pass --image",
+                    tags.album, tags.artist
+                ))
+            })?,
+        };
+        art::cover_mime_type(&image)?;
+
+        let mut embedded = 0;
+        for path in &files {
+            println!("{}", t!("art-embed-file", path = path.display().to_string()));
+            if dry_run {
+                continue;
+            }
+            match art::embed_cover(path, &image) {
+                Ok(()) => embedded += 1,
+                Err(e) => println!("{}", t!("file-failed", path = path.display().to_string(), error = e.to_string())),
+            }
+        }
+
+        if dry_run {
+            println!("{}", t!("art-embed-dry-run", count = files.len()));
+            return Ok(());
+        }
+        // Files that could not be written still show the cover
+        covers.store(&tags.artist, &tags.album, &image)?;
+        println!("{}", t!("art-embed-done", count = embedded, album = tags.album.as_str()));
+        Ok(())
+    }
+}
+
+impl Command for ArtCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        match &self.action {
+            ArtAction::Fetch { dry_run } => self.fetch(config, *dry_run),
+            ArtAction::Embed { album, artist, image, dry_run } => {
+                self.embed(config, album, artist.as_deref(), image.as_deref(), *dry_run)
+            }
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Manage album art"
+    }
+}
@@ -0,0 +1,75 @@
+use crate::cli::ConfigAction;
+use crate::commands::Command;
+use crate::config::check::{self, Problem, Severity};
+use crate::config::{Config, PersistentSettings};
+use crate::error::{LofiTurtleError, Result};
+use crate::t;
+use std::path::{Path, PathBuf};
+
+pub struct ConfigCommand {
+    action: ConfigAction,
+    layout_path: PathBuf,
+    keymap_path: PathBuf,
+}
+
+impl ConfigCommand {
+    pub fn new(action: ConfigAction, layout_path: PathBuf, keymap_path: PathBuf) -> Self {
+        Self { action, layout_path, keymap_path }
+    }
+
+    /// Check the file at `path` if there is one, printing what was found
+    fn check_file(&self, path: &Path, check: fn(&Path, &str) -> Vec<Problem>) -> Vec<Problem> {
+        let problems = match std::fs::read_to_string(path) {
+            Ok(content) => check(path, &content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("{}", t!("config-check-missing", path = path.display().to_string()));
+                return Vec::new();
+            }
+            Err(e) => vec![Problem {
+                severity: Severity::Error,
+                file: path.to_path_buf(),
+                line: None,
+                field: None,
+                message: e.to_string(),
+            }],
+        };
+
+        if problems.is_empty() {
+            println!("{}", t!("config-check-ok", path = path.display().to_string()));
+        }
+        for problem in &problems {
+            let label = match problem.severity {
+                Severity::Error => t!("config-check-error"),
+                Severity::Warning => t!("config-check-warning"),
+            };
+            println!("{}: {}", label, problem);
+        }
+        problems
+    }
+}
+
+impl Command for ConfigCommand {
+    fn execute(&self, _config: &Config) -> Result<()> {
+        match &self.action {
+            ConfigAction::Check => {
+                let mut problems = self.check_file(&PersistentSettings::settings_path(), check::check_settings);
+                problems.extend(self.check_file(&self.layout_path, check::check_layout));
+                problems.extend(self.check_file(&self.keymap_path, check::check_keymap));
+
+                let errors = problems.iter().filter(|problem| problem.severity == Severity::Error).count();
+                let warnings = problems.len() - errors;
+                println!("{}", t!("config-check-summary", errors = errors, warnings = warnings));
+
+                if errors > 0 {
+                    return Err(LofiTurtleError::Configuration(t!("config-check-failed", count = errors)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Inspect the configuration files"
+    }
+}
@@ -0,0 +1,59 @@
+use crate::audio::output;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::t;
+use rodio::cpal;
+
+pub struct DevicesCommand {
+    host: Option<String>,
+    device: Option<String>,
+}
+
+impl DevicesCommand {
+    pub fn new(host: Option<String>, device: Option<String>) -> Self {
+        Self { host, device }
+    }
+}
+
+impl Command for DevicesCommand {
+    fn execute(&self, _config: &Config) -> Result<()> {
+        // Fails here if the configured host does not exist
+        let used_host = output::host(self.host.as_deref())?.id();
+
+        for id in cpal::available_hosts() {
+            let Ok(host) = cpal::host_from_id(id) else {
+                println!("{}", t!("devices-host-unavailable", host = id.name()));
+                continue;
+            };
+
+            let used_device = if id == used_host {
+                println!("{}", t!("devices-host-used", host = id.name()));
+                self.device.clone().or_else(|| output::default_device_name(&host))
+            } else {
+                println!("{}", t!("devices-host", host = id.name()));
+                None
+            };
+
+            let devices = output::device_names(&host);
+            if devices.is_empty() {
+                println!("    {}", t!("devices-none"));
+            }
+            for name in devices {
+                let used = used_device.as_ref().is_some_and(|used| used.eq_ignore_ascii_case(&name));
+                println!("  {} {}", if used { '*' } else { ' ' }, name);
+            }
+
+            if id == used_host {
+                // Fails if the configured device does not exist
+                output::device(&host, self.device.as_deref())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "List the audio hosts and their output devices"
+    }
+}
@@ -0,0 +1,53 @@
+use crate::cli::ExportFormat;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::{library_export, LibraryService};
+use crate::t;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+pub struct ExportCommand {
+    format: ExportFormat,
+    output: PathBuf,
+}
+
+impl ExportCommand {
+    pub fn new(format: ExportFormat, output: PathBuf) -> Self {
+        Self { format, output }
+    }
+}
+
+impl Command for ExportCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let songs = library.get_song_stats()?;
+        let playlists = library.get_all_playlists()?;
+        let output = self.output.display().to_string();
+
+        match self.format {
+            ExportFormat::ItunesXml => {
+                let file = BufWriter::new(File::create(&self.output)?);
+                library_export::write_itunes_xml(&songs, &playlists, &config.music_dir, file)?;
+                println!(
+                    "{}",
+                    t!("export-itunes-done", songs = songs.len(), playlists = playlists.len(), file = output)
+                );
+            }
+            ExportFormat::Mpd => {
+                let export = library_export::write_mpd(&songs, &playlists, &config.music_dir, &self.output)?;
+                println!(
+                    "{}",
+                    t!("export-mpd-done", playlists = export.playlists, songs = export.songs, dir = output)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Write the library for another player"
+    }
+}
@@ -0,0 +1,61 @@
+use crate::cli::GenresAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::LibraryService;
+use crate::t;
+use std::path::PathBuf;
+
+pub struct GenresCommand {
+    action: GenresAction,
+    rules_path: PathBuf,
+}
+
+impl GenresCommand {
+    pub fn new(action: GenresAction, rules_path: PathBuf) -> Self {
+        Self { action, rules_path }
+    }
+}
+
+impl Command for GenresCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        match &self.action {
+            GenresAction::Normalize { dry_run } => {
+                let rules = &config.scan_options.genre_rules;
+                if rules.is_empty() {
+                    println!("{}", t!("genres-no-rules", path = self.rules_path.display().to_string()));
+                    return Ok(());
+                }
+
+                let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+                let mut changed = Vec::new();
+                for mut song in library.get_all_songs()? {
+                    let Some(genre) = &song.genre else { continue };
+                    let normalized = rules.normalize(genre);
+                    if normalized == *genre {
+                        continue;
+                    }
+                    println!(
+                        "{}",
+                        t!("genres-change", song = song.display_name(), from = genre.as_str(), to = normalized.as_str())
+                    );
+                    song.genre = Some(normalized);
+                    changed.push(song);
+                }
+
+                if *dry_run {
+                    println!("{}", t!("genres-dry-run", count = changed.len()));
+                    return Ok(());
+                }
+                library.add_songs(&changed)?;
+                println!("{}", t!("genres-done", count = changed.len()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Apply the genre normalization rules"
+    }
+}
@@ -0,0 +1,52 @@
+use crate::cli::{HistoryAction, HistoryFormat};
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::history_export;
+use crate::services::LibraryService;
+use crate::t;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+pub struct HistoryCommand {
+    action: HistoryAction,
+}
+
+impl HistoryCommand {
+    pub fn new(action: HistoryAction) -> Self {
+        Self { action }
+    }
+}
+
+impl Command for HistoryCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+
+        match &self.action {
+            HistoryAction::Export { file, format } => {
+                let listens = library.get_all_listens()?;
+                let output: Box<dyn Write> = match file {
+                    Some(file) => Box::new(BufWriter::new(File::create(file)?)),
+                    None => Box::new(io::stdout().lock()),
+                };
+
+                match format {
+                    HistoryFormat::Csv => history_export::write_csv(&listens, output)?,
+                    HistoryFormat::Json => history_export::write_json(&listens, output)?,
+                }
+
+                // Standard output carries the export itself, so only report
+                // when writing to a file
+                if let Some(file) = file {
+                    println!("{}", t!("history-exported", count = listens.len(), file = file.display().to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Work with the local listening history"
+    }
+}
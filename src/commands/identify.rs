@@ -0,0 +1,61 @@
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::library::MusicScanner;
+use crate::services::acoustid::{self, AcoustIdClient};
+use crate::services::LibraryService;
+use crate::t;
+use std::path::PathBuf;
+
+pub struct IdentifyCommand {
+    dry_run: bool,
+}
+
+impl IdentifyCommand {
+    pub fn new(dry_run: bool) -> Self {
+        Self { dry_run }
+    }
+}
+
+impl Command for IdentifyCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let mut client = AcoustIdClient::from_config(config)?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+
+        let paths: Vec<PathBuf> = library.get_all_songs()?.into_iter().map(|song| PathBuf::from(song.path)).collect();
+        println!("{}", t!("identify-start", count = paths.len()));
+
+        let identified = acoustid::identify_files(&mut client, &paths)?;
+        let kept = t!("identify-kept");
+        for (path, tags) in &identified {
+            println!(
+                "{}\n    {} - {}",
+                path.display(),
+                tags.artist.as_deref().unwrap_or(&kept),
+                tags.title.as_deref().unwrap_or(&kept)
+            );
+        }
+
+        if self.dry_run {
+            println!("\n{}", t!("identify-preview", count = identified.len()));
+            return Ok(());
+        }
+
+        let scanner = MusicScanner::with_options(config.scan_options.clone());
+        let mut updated = Vec::new();
+        for (path, tags) in &identified {
+            match tags.write(path).and_then(|_| scanner.extract_metadata(path)) {
+                Ok(song) => updated.push(song),
+                Err(e) => println!("{}", t!("file-failed", path = path.display().to_string(), error = e.to_string())),
+            }
+        }
+        library.add_songs(&updated)?;
+
+        println!("{}", t!("identify-done", count = updated.len()));
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Identify untagged files by audio fingerprint"
+    }
+}
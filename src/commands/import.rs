@@ -0,0 +1,50 @@
+use crate::cli::ImportAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::{stats_import, LibraryService};
+use crate::t;
+
+pub struct ImportCommand {
+    action: ImportAction,
+}
+
+impl ImportCommand {
+    pub fn new(action: ImportAction) -> Self {
+        Self { action }
+    }
+}
+
+impl Command for ImportCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let (tracks, dry_run) = match &self.action {
+            ImportAction::Itunes { file, dry_run } => (stats_import::read_itunes(file)?, *dry_run),
+            ImportAction::Mpd { stickers, mpd_music_dir, dry_run } => {
+                let music_dir = mpd_music_dir.as_ref().unwrap_or(&config.music_dir);
+                (stats_import::read_mpd_stickers(stickers, music_dir)?, *dry_run)
+            }
+        };
+
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let plan = stats_import::plan(&library.get_all_songs()?, tracks);
+
+        for track in &plan.unmatched {
+            println!("{}", t!("import-unmatched", track = track.name()));
+        }
+        if dry_run {
+            println!("{}", t!("import-dry-run", count = plan.matched.len(), unmatched = plan.unmatched.len()));
+            return Ok(());
+        }
+
+        for (song, stats) in plan.matched.iter().cloned() {
+            library.import_stats(&song.id, stats)?;
+        }
+        println!("{}", t!("import-done", count = plan.matched.len(), unmatched = plan.unmatched.len()));
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Bring play counts and ratings over from another player"
+    }
+}
@@ -1,23 +1,71 @@
+use crate::cli::GroupBy;
 use crate::commands::Command;
 use crate::config::Config;
+use crate::domain::entities::SortKeys;
 use crate::error::Result;
-use crate::library::Database;
+use crate::models::Song;
+use crate::services::LibraryService;
+use crate::shared::natural_sort::natural_cmp;
+use crate::t;
+use std::collections::{BTreeMap, HashMap};
+
+/// Indentation of each level of a grouped listing
+const INDENT: &str = "  ";
+
+/// Field a level of a grouped listing puts songs together by
+#[derive(Debug, Clone, Copy)]
+enum Level {
+    Genre,
+    Artist,
+    Album,
+}
+
+impl Level {
+    /// Levels of the tree for `group_by`, outermost first
+    fn nesting(group_by: GroupBy) -> &'static [Level] {
+        match group_by {
+            GroupBy::Artist => &[Level::Artist, Level::Album],
+            GroupBy::Album => &[Level::Album],
+            GroupBy::Genre => &[Level::Genre, Level::Artist, Level::Album],
+        }
+    }
+
+    fn name(self, song: &Song) -> String {
+        match self {
+            Level::Genre => song.genre.clone().unwrap_or_else(|| t!("unknown-genre")),
+            Level::Artist => song.artist.clone(),
+            Level::Album => song.album.clone(),
+        }
+    }
+
+    /// What the group of `song` sorts by: artists and albums use the sort
+    /// keys the library stored, which can leave out their leading article
+    fn sort_key<'a>(self, song: &'a Song, keys: &'a HashMap<String, SortKeys>, name: &'a str) -> &'a str {
+        match (self, keys.get(&song.id)) {
+            (Level::Artist, Some(keys)) => &keys.artist,
+            (Level::Album, Some(keys)) => &keys.album,
+            _ => name,
+        }
+    }
+}
 
 pub struct ListCommand {
     artist: Option<String>,
     album: Option<String>,
+    group_by: Option<GroupBy>,
 }
 
 impl ListCommand {
-    pub fn new(artist: Option<String>, album: Option<String>) -> Self {
-        Self { artist, album }
+    pub fn new(artist: Option<String>, album: Option<String>, group_by: Option<GroupBy>) -> Self {
+        Self { artist, album, group_by }
     }
 }
 
 impl Command for ListCommand {
     fn execute(&self, config: &Config) -> Result<()> {
-        let database = Database::new(&config.database_path)?;
-        let songs = database.get_all_songs()?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let songs = library.get_all_songs()?;
+        let keys = library.get_sort_keys()?;
 
         let filtered_songs: Vec<_> = songs.into_iter().filter(|song| {
             if let Some(ref artist) = self.artist {
@@ -34,10 +82,22 @@ impl Command for ListCommand {
         }).collect();
 
         if filtered_songs.is_empty() {
-            println!("No songs found matching criteria.");
+            println!("{}", t!("list-empty"));
+        } else if let Some(group_by) = self.group_by {
+            println!("{}", t!("list-found", count = filtered_songs.len()));
+            let songs: Vec<&Song> = filtered_songs.iter().collect();
+            for line in group_lines(&songs, &keys, Level::nesting(group_by), 0) {
+                println!("{}", line);
+            }
         } else {
-            println!("Found {} songs:", filtered_songs.len());
-            println!("{:<50} | {:<30} | {:<30} | {:<10}", "Title", "Artist", "Album", "Duration");
+            println!("{}", t!("list-found", count = filtered_songs.len()));
+            println!(
+                "{:<50} | {:<30} | {:<30} | {:<10}",
+                t!("column-title"),
+                t!("column-artist"),
+                t!("column-album"),
+                t!("column-duration")
+            );
             println!("{:-<50}-+-{:-<30}-+-{:-<30}-+-{:-<10}", "", "", "", "");
 
             for song in filtered_songs {
@@ -58,6 +118,57 @@ impl Command for ListCommand {
     }
 }
 
+/// Lines of a tree of `songs` grouped by each of `levels` in turn, names
+/// compared without case and groups in natural order of their sort `keys`,
+/// each group headed by its song count and total duration; the songs
+/// themselves are listed naturally by path, the order of tracks on disk
+fn group_lines(songs: &[&Song], keys: &HashMap<String, SortKeys>, levels: &[Level], depth: usize) -> Vec<String> {
+    let indent = INDENT.repeat(depth);
+    let Some((&level, inner)) = levels.split_first() else {
+        let mut songs = songs.to_vec();
+        songs.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+        return songs
+            .iter()
+            .map(|song| format!("{}{}  {}", indent, song.duration_formatted(), song.title))
+            .collect();
+    };
+
+    let mut groups: BTreeMap<String, (String, String, Vec<&Song>)> = BTreeMap::new();
+    for &song in songs {
+        let name = level.name(song);
+        groups
+            .entry(name.to_lowercase())
+            .or_insert_with(|| (level.sort_key(song, keys, &name).to_string(), name.clone(), Vec::new()))
+            .2
+            .push(song);
+    }
+
+    let mut groups: Vec<(String, String, Vec<&Song>)> = groups.into_values().collect();
+    groups.sort_by(|a, b| natural_cmp(&a.0, &b.0).then_with(|| natural_cmp(&a.1, &b.1)));
+
+    let mut lines = Vec::new();
+    for (_, name, songs) in groups {
+        let total = format_total(songs.iter().map(|song| song.duration).sum());
+        lines.push(format!(
+            "{}{}",
+            indent,
+            t!("list-group", name = name.as_str(), count = songs.len(), duration = total.as_str())
+        ));
+        lines.extend(group_lines(&songs, keys, inner, depth + 1));
+    }
+    lines
+}
+
+/// A total duration, with hours once it runs past one
+fn format_total(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
 fn truncate(s: &str, max_width: usize) -> String {
     if s.len() > max_width {
         format!("{}...", &s[0..max_width-3])
@@ -65,3 +176,57 @@ fn truncate(s: &str, max_width: usize) -> String {
         s.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str, title: &str, artist: &str, album: &str) -> Song {
+        Song::new(path.to_string(), title.to_string(), artist.to_string(), album.to_string(), 90)
+    }
+
+    fn group(indent: &str, name: &str, count: usize, duration: &str) -> String {
+        format!("{}{}", indent, t!("list-group", name = name, count = count, duration = duration))
+    }
+
+    #[test]
+    fn test_group_lines_nest_and_sort() {
+        let songs = [
+            song("/music/band/10.mp3", "Ten", "The Band", "Live"),
+            song("/music/mono/1.mp3", "One", "Mono", "Solo"),
+            song("/music/band/2.mp3", "Two", "the band", "live"),
+        ];
+        let songs: Vec<&Song> = songs.iter().collect();
+
+        // Names differing only in case are one group, named as first seen,
+        // and tracks follow their paths naturally
+        let band = [
+            group("", "The Band", 2, "03:00"),
+            group(INDENT, "Live", 2, "03:00"),
+            format!("{}01:30  Two", INDENT.repeat(2)),
+            format!("{}01:30  Ten", INDENT.repeat(2)),
+        ];
+        let mono = [
+            group("", "Mono", 1, "01:30"),
+            group(INDENT, "Solo", 1, "01:30"),
+            format!("{}01:30  One", INDENT.repeat(2)),
+        ];
+        let levels = Level::nesting(GroupBy::Artist);
+        assert_eq!(group_lines(&songs, &HashMap::new(), levels, 0), [&mono[..], &band[..]].concat());
+
+        // Stored sort keys without the article put the band first
+        let keys: HashMap<String, SortKeys> = songs
+            .iter()
+            .filter(|song| song.artist.to_lowercase() == "the band")
+            .map(|song| (song.id.clone(), SortKeys { artist: "Band".to_string(), album: "Live".to_string() }))
+            .collect();
+        assert_eq!(group_lines(&songs, &keys, levels, 0), [&band[..], &mono[..]].concat());
+    }
+
+    #[test]
+    fn test_format_total() {
+        assert_eq!(format_total(0), "00:00");
+        assert_eq!(format_total(59 * 60 + 59), "59:59");
+        assert_eq!(format_total(3600 + 61), "1:01:01");
+    }
+}
@@ -0,0 +1,79 @@
+use crate::cli::LovedAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::scrobbling::{LastFmClient, LastFmSession};
+use crate::services::{loved_sync, LibraryService};
+use crate::t;
+use std::collections::HashSet;
+use std::io;
+
+pub struct LovedCommand {
+    action: LovedAction,
+}
+
+impl LovedCommand {
+    pub fn new(action: LovedAction) -> Self {
+        Self { action }
+    }
+}
+
+impl Command for LovedCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let client = LastFmClient::from_config(config)?;
+
+        match &self.action {
+            LovedAction::Login => {
+                let token = client.request_token()?;
+                println!("{}", t!("lastfm-login-open", url = client.auth_url(&token)));
+                io::stdin().read_line(&mut String::new())?;
+
+                let session = client.session(&token)?;
+                let path = LastFmSession::path();
+                session.save(&path)?;
+                println!("{}", t!("lastfm-login-done", user = session.user.as_str(), path = path.display().to_string()));
+            }
+            LovedAction::Sync { dry_run } => {
+                let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+                let user = client.user()?;
+                let remote = client.loved_tracks(&user)?;
+                let songs = library.get_all_songs()?;
+                let loved: HashSet<String> =
+                    library.get_loved_songs()?.into_iter().map(|song| song.id).collect();
+                let sync = loved_sync::plan(&songs, &loved, &remote);
+
+                for song in &sync.to_mark {
+                    println!("{}", t!("loved-mark", song = song.display_name()));
+                    if !dry_run {
+                        library.set_loved(&song.id, true)?;
+                    }
+                }
+
+                let mut pushed = 0;
+                for song in &sync.to_love {
+                    println!("{}", t!("loved-push", song = song.display_name()));
+                    if *dry_run {
+                        continue;
+                    }
+                    // A track Last.fm refuses should not stop the others
+                    match client.set_loved(&song.artist, &song.title, true) {
+                        Ok(()) => pushed += 1,
+                        Err(e) => println!("{}", t!("loved-push-failed", song = song.display_name(), error = e.to_string())),
+                    }
+                }
+
+                if *dry_run {
+                    println!("{}", t!("loved-sync-dry-run", marked = sync.to_mark.len(), pushed = sync.to_love.len()));
+                } else {
+                    println!("{}", t!("loved-sync-done", user = user, marked = sync.to_mark.len(), pushed = pushed));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Sync the songs marked as loved with Last.fm"
+    }
+}
@@ -6,12 +6,44 @@ pub mod scan;
 pub mod list;
 pub mod search;
 pub mod playlist;
+pub mod queue;
+pub mod sync;
+pub mod organize;
+pub mod tag;
+pub mod identify;
+pub mod export;
+pub mod history;
+pub mod import;
+pub mod loved;
+pub mod wrapped;
+pub mod art;
+pub mod genres;
+pub mod config;
+pub mod theme;
+pub mod prune;
+pub mod devices;
 
 pub use play::PlayCommand;
 pub use scan::ScanCommand;
 pub use list::ListCommand;
 pub use search::SearchCommand;
 pub use playlist::{PlaylistCommand, ShuffleCommand, RepeatCommand};
+pub use queue::QueueCommand;
+pub use sync::SyncCommand;
+pub use organize::OrganizeCommand;
+pub use tag::TagCommand;
+pub use identify::IdentifyCommand;
+pub use export::ExportCommand;
+pub use history::HistoryCommand;
+pub use import::ImportCommand;
+pub use loved::LovedCommand;
+pub use wrapped::WrappedCommand;
+pub use art::ArtCommand;
+pub use genres::GenresCommand;
+pub use config::ConfigCommand;
+pub use theme::ThemeCommand;
+pub use prune::PruneCommand;
+pub use devices::DevicesCommand;
 
 /// Command trait for implementing the Command pattern
 /// Each CLI operation implements this trait for consistent execution
@@ -35,15 +67,50 @@ pub struct CommandFactory;
 
 impl CommandFactory {
     /// Create a command from CLI arguments
-    pub fn create_command(cli_command: &crate::cli::Commands) -> Box<dyn Command> {
+    pub fn create_command(cli_command: &crate::cli::Commands, cli: &crate::cli::Cli) -> Box<dyn Command> {
         match cli_command {
             crate::cli::Commands::Play { .. } => Box::new(PlayCommand::new()),
-            crate::cli::Commands::Scan { force, .. } => Box::new(ScanCommand::new(*force)),
-            crate::cli::Commands::List { artist, album } => {
-                Box::new(ListCommand::new(artist.clone(), album.clone()))
+            crate::cli::Commands::Scan { force, identify, report, dry_run, .. } => {
+                Box::new(ScanCommand::new(*force, *identify, *report, *dry_run))
+            }
+            crate::cli::Commands::Prune { dry_run } => Box::new(PruneCommand::new(*dry_run)),
+            crate::cli::Commands::List { artist, album, group_by } => {
+                Box::new(ListCommand::new(artist.clone(), album.clone(), *group_by))
+            }
+            crate::cli::Commands::Search { query, regex, field } => {
+                Box::new(SearchCommand::new(query.clone(), regex.clone(), field.clone()))
             }
-            crate::cli::Commands::Search { query } => Box::new(SearchCommand::new(query.clone())),
             crate::cli::Commands::Playlist { action } => Box::new(PlaylistCommand::new(action.clone())),
+            crate::cli::Commands::Queue { action } => Box::new(QueueCommand::new(action.clone())),
+            crate::cli::Commands::Sync { action } => Box::new(SyncCommand::new(action.clone())),
+            crate::cli::Commands::Organize { pattern, apply, .. } => {
+                Box::new(OrganizeCommand::new(pattern.clone(), *apply))
+            }
+            crate::cli::Commands::Identify { dry_run } => Box::new(IdentifyCommand::new(*dry_run)),
+            crate::cli::Commands::Tag { pattern, dir, apply } => {
+                Box::new(TagCommand::new(pattern.clone(), dir.clone(), *apply))
+            }
+            crate::cli::Commands::History { action } => Box::new(HistoryCommand::new(action.clone())),
+            crate::cli::Commands::Export { format, output } => Box::new(ExportCommand::new(*format, output.clone())),
+            crate::cli::Commands::Genres { action } => Box::new(GenresCommand::new(action.clone(), cli.genres_path())),
+            crate::cli::Commands::Art { action } => Box::new(ArtCommand::new(action.clone())),
+            crate::cli::Commands::Import { action } => Box::new(ImportCommand::new(action.clone())),
+            crate::cli::Commands::Loved { action } => Box::new(LovedCommand::new(action.clone())),
+            crate::cli::Commands::Wrapped { period, report } => {
+                Box::new(WrappedCommand::new(period.period(), report.clone()))
+            }
+            crate::cli::Commands::Devices => {
+                let (host, device) = cli.audio_output();
+                Box::new(DevicesCommand::new(host, device))
+            }
+            crate::cli::Commands::Config { action } => Box::new(ConfigCommand::new(
+                action.clone(),
+                cli.layout_path(),
+                cli.keymap_path(),
+            )),
+            crate::cli::Commands::Theme { action } => {
+                Box::new(ThemeCommand::new(action.clone(), cli.layout_path()))
+            }
             crate::cli::Commands::Shuffle { mode } => {
                 // Handle Option<ShuffleMode>
                 let mode = mode.clone().unwrap_or(crate::cli::ShuffleMode::Toggle);
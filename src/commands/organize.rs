@@ -0,0 +1,92 @@
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::library::organizer;
+use crate::library::tag_pattern::TagPattern;
+use crate::services::LibraryService;
+use crate::t;
+
+pub struct OrganizeCommand {
+    pattern: String,
+    apply: bool,
+}
+
+impl OrganizeCommand {
+    pub fn new(pattern: String, apply: bool) -> Self {
+        Self { pattern, apply }
+    }
+}
+
+impl Command for OrganizeCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let pattern = TagPattern::parse(&self.pattern)?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let root = &config.music_dir;
+
+        let plan = organizer::plan(root, &library.get_all_songs()?, &pattern);
+
+        for skipped in &plan.skipped {
+            println!(
+                "{}",
+                t!("file-skipped", path = skipped.path.display().to_string(), reason = skipped.reason.to_string())
+            );
+        }
+
+        if plan.moves.is_empty() {
+            println!("{}", t!("organize-nothing", count = plan.unchanged));
+            return Ok(());
+        }
+
+        if !self.apply {
+            for planned in &plan.moves {
+                println!(
+                    "{}\n   -> {}",
+                    t!("organize-move", from = planned.from.display().to_string()),
+                    planned.to.display()
+                );
+            }
+            println!(
+                "\n{}",
+                t!("organize-preview", count = plan.moves.len(), skipped = plan.skipped.len())
+            );
+            return Ok(());
+        }
+
+        let mut moved = 0;
+        let mut failed = 0;
+        for planned in &plan.moves {
+            if let Err(e) = organizer::move_file(&planned.from, &planned.to) {
+                println!("{}", t!("file-failed", path = planned.from.display().to_string(), error = e.to_string()));
+                failed += 1;
+                continue;
+            }
+
+            // Keep the file and the database in step: undo the move if the
+            // library could not be updated
+            let new_path = planned.to.to_string_lossy();
+            if let Err(e) = library.relocate_song(&planned.song_id, &new_path) {
+                println!("{}", t!("file-failed", path = planned.from.display().to_string(), error = e.to_string()));
+                if let Err(e) = organizer::move_file(&planned.to, &planned.from) {
+                    log::error!("Failed to move {} back: {}", planned.to.display(), e);
+                }
+                failed += 1;
+                continue;
+            }
+
+            if let Some(parent) = planned.from.parent() {
+                organizer::remove_empty_dirs(parent, root);
+            }
+            moved += 1;
+        }
+
+        println!(
+            "{}",
+            t!("organize-done", moved = moved, failed = failed, skipped = plan.skipped.len())
+        );
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Rename and move music files according to their tags"
+    }
+}
@@ -12,6 +12,12 @@ impl PlayCommand {
     }
 }
 
+impl Default for PlayCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Command for PlayCommand {
     fn execute(&self, config: &Config) -> Result<()> {
         // Use default layout config for backward compatibility
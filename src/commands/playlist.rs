@@ -1,9 +1,11 @@
 use crate::commands::Command;
 use crate::config::Config;
+use crate::domain::entities::PlaylistSetOperation;
 use crate::error::Result;
-use crate::library::Database;
-use crate::models::{PlaylistBuilder, RepeatMode};
+use crate::services::LibraryService;
+use crate::models::RepeatMode;
 use crate::cli::{PlaylistAction, ShuffleMode, RepeatModeArg};
+use crate::t;
 
 pub struct PlaylistCommand {
     action: PlaylistAction,
@@ -17,116 +19,132 @@ impl PlaylistCommand {
 
 impl Command for PlaylistCommand {
     fn execute(&self, config: &Config) -> Result<()> {
-        let database = Database::new(&config.database_path)?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
 
         match &self.action {
             PlaylistAction::List => {
-                let playlists = database.get_all_playlists()?;
+                let playlists = library.get_all_playlists()?;
                 if playlists.is_empty() {
-                    println!("No playlists found.");
+                    println!("{}", t!("playlist-none"));
                 } else {
-                    println!("Found {} playlists:", playlists.len());
-                    println!("{:<30} | {:<10} | {:<30}", "Name", "Songs", "Description");
-                    println!("{:-<30}-+-{:-<10}-+-{:-<30}", "", "", "");
+                    println!("{}", t!("playlist-found", count = playlists.len()));
+                    println!(
+                        "{:<30} | {:<10} | {:<10} | {:<30}",
+                        t!("column-name"), t!("column-songs"), t!("column-duration"), t!("column-description")
+                    );
+                    println!("{:-<30}-+-{:-<10}-+-{:-<10}-+-{:-<30}", "", "", "", "");
 
                     for playlist in playlists {
-                        println!("{:<30} | {:<10} | {:<30}",
+                        println!("{:<30} | {:<10} | {:<10} | {:<30}",
                             playlist.name,
                             playlist.song_count(),
+                            playlist.duration_formatted(),
                             playlist.description.clone().unwrap_or_default()
                         );
                     }
                 }
             }
             PlaylistAction::Create { name, description } => {
-                let playlist = PlaylistBuilder::new()
-                    .name(name)
-                    .description(description.clone().unwrap_or_default())
-                    .build()
-                    .map_err(|e| crate::error::LofiTurtleError::Configuration(e))?;
-
-                database.create_playlist(&playlist)?;
-                println!("Created playlist '{}'", name);
+                library.create_playlist(name, description.clone())?;
+                println!("{}", t!("playlist-created", name = name.as_str()));
             }
             PlaylistAction::Delete { name } => {
-                if let Some(playlist) = database.get_playlist_by_name(name)? {
-                    database.delete_playlist(&playlist.id)?;
-                    println!("Deleted playlist '{}'", name);
+                if let Some(playlist) = library.get_playlist_by_name(name)? {
+                    library.delete_playlist(&playlist.id)?;
+                    println!("{}", t!("playlist-deleted", name = name.as_str()));
                 } else {
-                    println!("Playlist '{}' not found.", name);
+                    println!("{}", t!("playlist-not-found", name = name.as_str()));
                 }
             }
             PlaylistAction::Add { playlist: playlist_name, songs } => {
-                if let Some(playlist) = database.get_playlist_by_name(playlist_name)? {
+                if let Some(playlist) = library.get_playlist_by_name(playlist_name)? {
                     for song_query in songs {
                         // Find song by path or title
-                        let found_songs = database.search_songs(song_query)?;
+                        let found_songs = library.search_songs(song_query)?;
                         if found_songs.is_empty() {
-                            println!("No songs found matching '{}'", song_query);
+                            println!("{}", t!("playlist-no-match", query = song_query.as_str()));
                         } else if found_songs.len() > 1 {
-                            println!("Found multiple songs matching '{}'. Please be more specific.", song_query);
+                            println!("{}", t!("playlist-ambiguous", query = song_query.as_str()));
                             for (i, s) in found_songs.iter().enumerate().take(5) {
                                 println!("{}. {} - {}", i + 1, s.title, s.artist);
                             }
                         } else {
                             let song_to_add = &found_songs[0];
                             // Add to end of playlist
-                            let current_songs = database.get_playlist_songs(&playlist.id)?;
-                            database.add_song_to_playlist(&playlist.id, &song_to_add.id, current_songs.len())?;
-                            println!("Added '{}' to playlist '{}'", song_to_add.title, playlist_name);
+                            if playlist.song_ids.contains(&song_to_add.id) {
+                                println!(
+                                    "{}",
+                                    t!("playlist-already-added", title = song_to_add.title.as_str(), name = playlist_name.as_str())
+                                );
+                            } else {
+                                library.add_song_to_playlist(&playlist.id, &song_to_add.id)?;
+                                println!(
+                                    "{}",
+                                    t!("playlist-added", title = song_to_add.title.as_str(), name = playlist_name.as_str())
+                                );
+                            }
                         }
                     }
                 } else {
-                    println!("Playlist '{}' not found.", playlist_name);
+                    println!("{}", t!("playlist-not-found", name = playlist_name.as_str()));
                 }
             }
             PlaylistAction::Remove { playlist: playlist_name, songs } => {
-                if let Some(playlist) = database.get_playlist_by_name(playlist_name)? {
+                if let Some(playlist) = library.get_playlist_by_name(playlist_name)? {
                     for song_query in songs {
                         // Find song in playlist
-                        let playlist_songs = database.get_playlist_songs(&playlist.id)?;
+                        let playlist_songs = library.get_playlist_songs(&playlist.id)?;
                         let song_to_remove = playlist_songs.iter().find(|s|
                             s.title.to_lowercase().contains(&song_query.to_lowercase()) ||
                             s.path.contains(song_query)
                         );
 
                         if let Some(s) = song_to_remove {
-                            database.remove_song_from_playlist(&playlist.id, &s.id)?;
-                            println!("Removed '{}' from playlist '{}'", s.title, playlist_name);
+                            library.remove_song_from_playlist(&playlist.id, &s.id)?;
+                            println!("{}", t!("playlist-removed", title = s.title.as_str(), name = playlist_name.as_str()));
                         } else {
-                            println!("Song matching '{}' not found in playlist '{}'", song_query, playlist_name);
+                            println!("{}", t!("playlist-not-in", query = song_query.as_str(), name = playlist_name.as_str()));
                         }
                     }
                 } else {
-                    println!("Playlist '{}' not found.", playlist_name);
+                    println!("{}", t!("playlist-not-found", name = playlist_name.as_str()));
                 }
             }
             PlaylistAction::Show { name } => {
-                if let Some(playlist) = database.get_playlist_by_name(name)? {
-                    println!("Playlist: {}", playlist.name);
+                if let Some(playlist) = library.get_playlist_by_name(name)? {
+                    println!("{}", t!("playlist-name", name = playlist.name.as_str()));
                     if let Some(desc) = &playlist.description {
-                        println!("Description: {}", desc);
+                        println!("{}", t!("playlist-description", description = desc.as_str()));
                     }
-                    println!("Songs: {}", playlist.song_count());
+                    println!("{}", t!("playlist-song-count", count = playlist.song_count()));
                     println!();
 
-                    let songs = database.get_playlist_songs(&playlist.id)?;
+                    let songs = library.get_playlist_songs(&playlist.id)?;
                     if songs.is_empty() {
-                        println!("(Empty playlist)");
+                        println!("{}", t!("playlist-empty"));
                     } else {
                         for (i, song) in songs.iter().enumerate() {
                             println!("{}. {} - {} ({})", i + 1, song.title, song.artist, song.duration_formatted());
                         }
                     }
                 } else {
-                    println!("Playlist '{}' not found.", name);
+                    println!("{}", t!("playlist-not-found", name = name.as_str()));
                 }
             }
             PlaylistAction::Play { name } => {
                 // This is a special case that delegates to PlayCommand
                 // In a real CLI, we might want to handle this differently
                 // For now, we'll just print instructions
-                println!("To play a playlist, use the interactive mode or: lofiturtle play --playlist '{}'", name);
+                println!("{}", t!("playlist-play-hint", name = name.as_str()));
+            }
+            PlaylistAction::Merge { first, second, into } => {
+                combine_playlists(&library, PlaylistSetOperation::Merge, first, second, into)?;
+            }
+            PlaylistAction::Intersect { first, second, into } => {
+                combine_playlists(&library, PlaylistSetOperation::Intersect, first, second, into)?;
+            }
+            PlaylistAction::Subtract { first, second, into } => {
+                combine_playlists(&library, PlaylistSetOperation::Subtract, first, second, into)?;
             }
         }
 
@@ -138,6 +156,31 @@ impl Command for PlaylistCommand {
     }
 }
 
+/// Fill the new playlist `into` with the songs of `first` and `second`
+/// combined by `operation`
+fn combine_playlists(
+    library: &LibraryService,
+    operation: PlaylistSetOperation,
+    first: &str,
+    second: &str,
+    into: &str,
+) -> Result<()> {
+    for name in [first, second] {
+        if library.get_playlist_by_name(name)?.is_none() {
+            println!("{}", t!("playlist-not-found", name = name));
+            return Ok(());
+        }
+    }
+    if library.get_playlist_by_name(into)?.is_some() {
+        println!("{}", t!("playlist-exists", name = into));
+        return Ok(());
+    }
+
+    let count = library.combine_playlists(operation, first, second, into)?;
+    println!("{}", t!("playlist-combined", name = into, count = count));
+    Ok(())
+}
+
 pub struct ShuffleCommand {
     mode: ShuffleMode,
 }
@@ -156,15 +199,21 @@ impl Command for ShuffleCommand {
         match self.mode {
             ShuffleMode::On => {
                 settings.shuffle = true;
-                println!("Shuffle mode enabled");
+                settings.smart_shuffle = false;
+                println!("{}", t!("shuffle-enabled"));
             }
             ShuffleMode::Off => {
                 settings.shuffle = false;
-                println!("Shuffle mode disabled");
+                println!("{}", t!("shuffle-disabled"));
+            }
+            ShuffleMode::Smart => {
+                settings.shuffle = true;
+                settings.smart_shuffle = true;
+                println!("{}", t!("shuffle-smart-enabled"));
             }
             ShuffleMode::Toggle => {
                 settings.shuffle = !settings.shuffle;
-                println!("Shuffle mode {}", if settings.shuffle { "enabled" } else { "disabled" });
+                println!("{}", if settings.shuffle { t!("shuffle-enabled") } else { t!("shuffle-disabled") });
             }
         }
 
@@ -195,15 +244,15 @@ impl Command for RepeatCommand {
         match self.mode {
             RepeatModeArg::None => {
                 settings.repeat_mode = RepeatMode::None;
-                println!("Repeat mode: None");
+                println!("{}", t!("repeat-none"));
             }
             RepeatModeArg::Single => {
                 settings.repeat_mode = RepeatMode::Single;
-                println!("Repeat mode: Single Song");
+                println!("{}", t!("repeat-single"));
             }
             RepeatModeArg::Playlist => {
                 settings.repeat_mode = RepeatMode::Playlist;
-                println!("Repeat mode: Playlist");
+                println!("{}", t!("repeat-playlist"));
             }
         }
 
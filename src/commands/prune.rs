@@ -0,0 +1,59 @@
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::LibraryService;
+use crate::t;
+use std::path::Path;
+
+pub struct PruneCommand {
+    dry_run: bool,
+}
+
+impl PruneCommand {
+    pub fn new(dry_run: bool) -> Self {
+        Self { dry_run }
+    }
+}
+
+impl Command for PruneCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        // Every file looks gone while the music directory is unmounted, so
+        // leave the library alone rather than empty it
+        if !config.music_dir.is_dir() {
+            println!("{}", t!("scan-offline", dir = config.music_dir.display().to_string()));
+            return Ok(());
+        }
+
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let mut missing: Vec<_> = library
+            .get_all_songs()?
+            .into_iter()
+            .filter(|song| !Path::new(&song.path).exists())
+            .collect();
+        missing.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut removed = 0;
+        for song in &missing {
+            println!("{}", t!("file-remove", path = song.path.clone()));
+            if self.dry_run {
+                continue;
+            }
+            match library.remove_song(&song.id) {
+                Ok(()) => removed += 1,
+                Err(e) => println!("{}", t!("file-failed", path = song.path.clone(), error = e.to_string())),
+            }
+        }
+
+        if self.dry_run {
+            println!("{}", t!("prune-dry-run", count = missing.len()));
+        } else {
+            println!("{}", t!("prune-done", count = removed));
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove songs whose files no longer exist from the library"
+    }
+}
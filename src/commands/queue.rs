@@ -0,0 +1,53 @@
+use crate::cli::QueueAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::models::PlayQueue;
+use crate::services::LibraryService;
+use crate::t;
+use std::collections::HashSet;
+
+pub struct QueueCommand {
+    action: QueueAction,
+}
+
+impl QueueCommand {
+    pub fn new(action: QueueAction) -> Self {
+        Self { action }
+    }
+}
+
+impl Command for QueueCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        match &self.action {
+            QueueAction::Save { name } => {
+                // The queue as the player last saved it
+                let song_ids = PlayQueue::load().song_ids();
+                if song_ids.is_empty() {
+                    println!("{}", t!("queue-empty"));
+                    return Ok(());
+                }
+
+                let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+                if library.get_playlist_by_name(name)?.is_some() {
+                    println!("{}", t!("playlist-exists", name = name.as_str()));
+                    return Ok(());
+                }
+
+                // Songs removed from the library since the queue was saved,
+                // streams and files from outside it are left out
+                let in_library: HashSet<String> = library.get_songs(&song_ids)?.into_iter().map(|song| song.id).collect();
+                let song_ids: Vec<String> = song_ids.into_iter().filter(|id| in_library.contains(id)).collect();
+
+                let playlist_id = library.create_playlist(name, None)?;
+                let saved = library.add_songs_to_playlist(&playlist_id, &song_ids)?;
+                println!("{}", t!("queue-saved", name = name.as_str(), count = saved.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the player's queue as a playlist"
+    }
+}
@@ -1,41 +1,187 @@
 use crate::commands::Command;
 use crate::config::Config;
 use crate::error::Result;
-use crate::library::{Database, MusicScanner};
-use std::time::Instant;
+use crate::library::{CancelToken, MusicScanner, ScanCheckpoint, ScanProgress};
+use crate::models::Song;
+use crate::services::acoustid::{self, AcoustIdClient};
+use crate::services::scan_job::{self, ScanOutcome};
+use crate::services::LibraryService;
+use crate::t;
+use std::collections::HashSet;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
 
 pub struct ScanCommand {
     force: bool,
+    identify: bool,
+    report: bool,
+    dry_run: bool,
 }
 
 impl ScanCommand {
-    pub fn new(force: bool) -> Self {
-        Self { force }
+    pub fn new(force: bool, identify: bool, report: bool, dry_run: bool) -> Self {
+        Self { force, identify, report, dry_run }
+    }
+
+    fn print_report(&self, outcome: &ScanOutcome) {
+        if self.report {
+            println!();
+            for line in outcome.report_lines(None) {
+                println!("{}", line);
+            }
+        }
+    }
+
+    /// Print the songs the scan would add, and with --force remove, then
+    /// what it would have done in all
+    fn print_dry_run(&self, library: &LibraryService, songs: &[Song], mut outcome: ScanOutcome) -> Result<()> {
+        let known: HashSet<String> = library.get_all_songs()?.into_iter().map(|song| song.path).collect();
+
+        let mut added: Vec<&str> = songs.iter().map(|song| song.path.as_str()).filter(|path| !known.contains(*path)).collect();
+        added.sort();
+        for path in &added {
+            println!("{}", t!("file-add", path = path.to_string()));
+        }
+        outcome.added = added.len();
+        outcome.updated = songs.len() - added.len();
+
+        // Only a forced scan that gets through every file clears the library
+        let mut removed = Vec::new();
+        if self.force && !outcome.cancelled {
+            let scanned: HashSet<&str> = songs.iter().map(|song| song.path.as_str()).collect();
+            removed.extend(known.iter().filter(|path| !scanned.contains(path.as_str())));
+            removed.sort();
+        }
+        for path in &removed {
+            println!("{}", t!("file-remove", path = path.to_string()));
+        }
+
+        println!(
+            "{}",
+            t!("scan-dry-run", added = outcome.added, updated = outcome.updated, removed = removed.len())
+        );
+        self.print_report(&outcome);
+        Ok(())
     }
 }
 
 impl Command for ScanCommand {
     fn execute(&self, config: &Config) -> Result<()> {
-        println!("Scanning music directory: {}", config.music_dir.display());
+        println!("{}", t!("scan-start", dir = config.music_dir.display().to_string()));
         let start = Instant::now();
 
-        let mut database = Database::new(&config.database_path)?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
 
-        if self.force {
-            println!("Force scan enabled. Clearing existing database...");
-            database.clear_all_songs()?;
+        // Fail before scanning if identification cannot work
+        let mut client = if self.identify { Some(AcoustIdClient::from_config(config)?) } else { None };
+
+        let cancel = CancelToken::new();
+        cancel_on_ctrl_c(cancel.clone());
+        println!("{}", t!("scan-cancel-hint"));
+
+        // Pick up after a scan that was interrupted, skipping what it added.
+        // A dry run leaves the journal alone, and a forced scan reads every
+        // file again since it rebuilds the library.
+        let checkpoint_path = ScanCheckpoint::path_for(&config.database_path);
+        let mut checkpoint = if self.dry_run {
+            None
+        } else {
+            if self.force {
+                ScanCheckpoint::discard(&checkpoint_path)?;
+            }
+            Some(ScanCheckpoint::open(&checkpoint_path, &config.music_dir)?)
+        };
+        let done = checkpoint.as_mut().map(ScanCheckpoint::take_done).unwrap_or_default();
+
+        let scanner = MusicScanner::with_options(config.scan_options.clone()).skipping(done);
+        let mut last = ScanProgress::default();
+        let mut printer = ProgressLine::new();
+        let mut report = scanner.scan_roots_with(&[&config.music_dir], &cancel, |progress| {
+            printer.update(progress);
+            last = progress.clone();
+        });
+        printer.finish();
+
+        if report.is_offline(&config.music_dir) {
+            println!("{}", t!("scan-offline", dir = config.music_dir.display().to_string()));
+            return Ok(());
         }
 
-        let scanner = MusicScanner::new();
-        let songs = scanner.scan_directory(&config.music_dir)?;
+        if report.resumed > 0 {
+            println!("{}", t!("scan-resuming", count = report.resumed));
+        }
+        if !report.skipped.is_empty() {
+            println!("{}", t!("scan-skipped", count = report.skipped.len()));
+        }
+        let mut outcome = ScanOutcome {
+            cancelled: report.cancelled,
+            resumed: report.resumed,
+            skipped: std::mem::take(&mut report.skipped),
+            failed: std::mem::take(&mut report.failed),
+            ..ScanOutcome::default()
+        };
 
-        println!("Found {} songs. Updating database...", songs.len());
+        if self.dry_run {
+            return self.print_dry_run(&library, &report.songs, outcome);
+        }
+
+        // A stopped scan only adds what it read: it never clears the library
+        // and skips identification, which could take a long time again
+        if report.cancelled {
+            outcome.record(library.store_songs(&report.songs)?);
+            // The next scan goes on from here
+            if let Some(checkpoint) = checkpoint.as_mut() {
+                checkpoint.record(&report.songs)?;
+            }
+            println!("{}", t!("scan-stopped", processed = last.processed, total = last.total, count = outcome.added));
+            self.print_report(&outcome);
+            return Ok(());
+        }
+
+        if let Some(client) = client.as_mut() {
+            let paths: Vec<PathBuf> = report.songs.iter().map(|song| PathBuf::from(&song.path)).collect();
+            println!("{}", t!("scan-identifying"));
+
+            let mut tagged = 0;
+            for (path, tags) in acoustid::identify_files(client, &paths)? {
+                let refreshed = tags.write(&path).and_then(|_| scanner.extract_metadata(&path));
+                match refreshed {
+                    Ok(song) => {
+                        if let Some(scanned) = report.songs.iter_mut().find(|s| s.path == song.path) {
+                            *scanned = song;
+                        }
+                        tagged += 1;
+                    }
+                    Err(e) => println!("{}", t!("scan-tag-failed", path = path.display().to_string(), error = e.to_string())),
+                }
+            }
+            println!("{}", t!("scan-identified", count = tagged));
+        }
 
-        // Use bulk insert for better performance
-        let count = database.insert_songs_bulk(&songs)?;
+        println!("{}", t!("scan-found", count = report.songs.len()));
+
+        // Only clear once the scan and identification have succeeded, so an
+        // unreachable share or AcoustID error cannot wipe the library
+        if self.force {
+            println!("{}", t!("scan-force"));
+            library.clear_songs()?;
+        }
+
+        outcome.record(library.store_songs(&report.songs)?);
+
+        // Every file was read, so the next scan starts from scratch
+        if let Some(checkpoint) = checkpoint {
+            checkpoint.finish()?;
+        }
 
         let duration = start.elapsed();
-        println!("Scan completed in {:.2?}. Added/Updated {} songs.", duration, count);
+        println!("{}", t!("scan-done", duration = format!("{:.2?}", duration), count = outcome.added));
+        self.print_report(&outcome);
 
         Ok(())
     }
@@ -44,3 +190,66 @@ impl Command for ScanCommand {
         "Scan music directory and update the library database"
     }
 }
+
+/// Cancel the scan on the first Ctrl+C, and quit on the second
+fn cancel_on_ctrl_c(cancel: CancelToken) {
+    thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread().enable_all().build() else {
+            return;
+        };
+        runtime.block_on(async {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            eprintln!("\n{}", t!("scan-stopping"));
+            cancel.cancel();
+
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        });
+    });
+}
+
+/// Scan progress redrawn in place on a terminal's stderr
+struct ProgressLine {
+    enabled: bool,
+    last_drawn: Option<Instant>,
+}
+
+impl ProgressLine {
+    fn new() -> Self {
+        Self {
+            enabled: std::io::stderr().is_terminal(),
+            last_drawn: None,
+        }
+    }
+
+    fn update(&mut self, progress: &ScanProgress) {
+        if !self.enabled || self.last_drawn.is_some_and(|drawn| drawn.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        self.last_drawn = Some(Instant::now());
+
+        let mut line = t!("scan-modal-progress", processed = progress.processed, total = progress.total);
+        if let Some(rate) = scan_job::rate_label(progress) {
+            line.push_str("  ");
+            line.push_str(&rate);
+        }
+        if let Some(name) = progress.current_file.as_deref().and_then(|path| path.file_name()) {
+            line.push_str("  ");
+            line.push_str(&name.to_string_lossy());
+        }
+
+        // Clear the rest of the previous line, which may have been longer
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K{}", line);
+        let _ = stderr.flush();
+    }
+
+    fn finish(&self) {
+        if self.enabled && self.last_drawn.is_some() {
+            eprintln!();
+        }
+    }
+}
@@ -1,28 +1,60 @@
+use crate::cli::SearchField;
 use crate::commands::Command;
 use crate::config::Config;
-use crate::error::Result;
-use crate::library::Database;
+use crate::error::{LofiTurtleError, Result};
+use crate::models::Song;
+use crate::services::LibraryService;
+use crate::t;
+use regex::Regex;
+
+/// Fields a regular expression is matched against when none are given
+const ALL_FIELDS: [SearchField; 4] = [SearchField::Title, SearchField::Artist, SearchField::Album, SearchField::Path];
 
 pub struct SearchCommand {
-    query: String,
+    query: Option<String>,
+    regex: Option<String>,
+    fields: Vec<SearchField>,
 }
 
 impl SearchCommand {
-    pub fn new(query: String) -> Self {
-        Self { query }
+    pub fn new(query: Option<String>, regex: Option<String>, fields: Vec<SearchField>) -> Self {
+        Self { query, regex, fields }
     }
 }
 
 impl Command for SearchCommand {
     fn execute(&self, config: &Config) -> Result<()> {
-        let database = Database::new(&config.database_path)?;
-        let songs = database.search_songs(&self.query)?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let (query, songs) = match (&self.regex, &self.query) {
+            (Some(pattern), _) => {
+                // Compiled once for the whole library
+                let regex = Regex::new(pattern)
+                    .map_err(|e| LofiTurtleError::InvalidCommand(format!("Invalid regular expression: {}", e)))?;
+                // Without --field it looks everywhere; a default value would
+                // make clap ask for --regex even when --field is left out
+                let fields = if self.fields.is_empty() { &ALL_FIELDS[..] } else { &self.fields };
+                let songs = library
+                    .get_all_songs()?
+                    .into_iter()
+                    .filter(|song| fields.iter().any(|&field| regex.is_match(field_text(song, field))))
+                    .collect();
+                (pattern.as_str(), songs)
+            }
+            (None, Some(query)) => (query.as_str(), library.search_songs(query)?),
+            (None, None) => return Ok(()),
+        };
 
         if songs.is_empty() {
-            println!("No songs found matching '{}'.", self.query);
+            println!("{}", t!("search-empty", query = query));
         } else {
-            println!("Found {} songs matching '{}':", songs.len(), self.query);
-            println!("{:<50} | {:<30} | {:<30} | {:<10}", "Title", "Artist", "Album", "Duration");
+            println!("{}", t!("search-found", count = songs.len(), query = query));
+            println!(
+                "{:<50} | {:<30} | {:<30} | {:<10}",
+                t!("column-title"),
+                t!("column-artist"),
+                t!("column-album"),
+                t!("column-duration")
+            );
             println!("{:-<50}-+-{:-<30}-+-{:-<30}-+-{:-<10}", "", "", "", "");
 
             for song in songs {
@@ -43,6 +75,16 @@ impl Command for SearchCommand {
     }
 }
 
+/// Text of `field` of a song
+fn field_text(song: &Song, field: SearchField) -> &str {
+    match field {
+        SearchField::Title => &song.title,
+        SearchField::Artist => &song.artist,
+        SearchField::Album => &song.album,
+        SearchField::Path => &song.path,
+    }
+}
+
 fn truncate(s: &str, max_width: usize) -> String {
     if s.len() > max_width {
         format!("{}...", &s[0..max_width-3])
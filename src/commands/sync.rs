@@ -0,0 +1,55 @@
+use crate::cli::SyncAction;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::{LibraryService, SyncFile, SyncService};
+use crate::t;
+
+pub struct SyncCommand {
+    action: SyncAction,
+}
+
+impl SyncCommand {
+    pub fn new(action: SyncAction) -> Self {
+        Self { action }
+    }
+}
+
+impl Command for SyncCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let sync = SyncService::new(LibraryService::new(&config.database_path, &config.sort_articles)?);
+
+        match &self.action {
+            SyncAction::Export { file, since } => {
+                let export = sync.export(*since)?;
+                export.save(file)?;
+                println!("{}", t!("sync-exported", count = export.playlists.len(), file = file.display().to_string()));
+            }
+            SyncAction::Import { file } => {
+                let summary = sync.import(&SyncFile::load(file)?)?;
+                println!(
+                    "{}",
+                    t!(
+                        "sync-imported",
+                        file = file.display().to_string(),
+                        created = summary.created,
+                        updated = summary.updated,
+                        unchanged = summary.unchanged,
+                    )
+                );
+                if summary.stats_imported > 0 {
+                    println!("{}", t!("sync-stats-imported", count = summary.stats_imported));
+                }
+                if summary.missing_songs > 0 {
+                    println!("{}", t!("sync-missing-songs", count = summary.missing_songs));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Export or import library changes to keep machines in sync"
+    }
+}
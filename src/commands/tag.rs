@@ -0,0 +1,109 @@
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::library::tag_pattern::TagPattern;
+use crate::library::tags::TrackTags;
+use crate::library::{tagger, MusicScanner};
+use crate::services::LibraryService;
+use crate::t;
+use std::path::PathBuf;
+
+pub struct TagCommand {
+    pattern: String,
+    dir: Option<PathBuf>,
+    apply: bool,
+}
+
+impl TagCommand {
+    pub fn new(pattern: String, dir: Option<PathBuf>, apply: bool) -> Self {
+        Self { pattern, dir, apply }
+    }
+}
+
+/// One line per field that would be written
+fn describe(tags: &TrackTags) -> Vec<String> {
+    let mut fields = Vec::new();
+    if let Some(artist) = &tags.artist {
+        fields.push(format!("artist: {}", artist));
+    }
+    if let Some(album) = &tags.album {
+        fields.push(format!("album:  {}", album));
+    }
+    if let Some(title) = &tags.title {
+        fields.push(format!("title:  {}", title));
+    }
+    if let Some(track) = tags.track {
+        fields.push(format!("track:  {}", track));
+    }
+    if let Some(year) = tags.year {
+        fields.push(format!("year:   {}", year));
+    }
+    fields
+}
+
+impl Command for TagCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let pattern = TagPattern::parse(&self.pattern)?;
+        let dir = self.dir.as_ref().unwrap_or(&config.music_dir);
+
+        let scanner = MusicScanner::with_options(config.scan_options.clone());
+        let files: Vec<PathBuf> = scanner
+            .scan_directory(dir)?
+            .into_iter()
+            .map(|song| PathBuf::from(song.path))
+            .collect();
+
+        let plan = tagger::plan(&files, &pattern);
+
+        for (path, reason) in &plan.failed {
+            println!("{}", t!("file-skipped", path = path.display().to_string(), reason = reason.to_string()));
+        }
+
+        if plan.changes.is_empty() {
+            println!("{}", t!("tag-nothing", unmatched = plan.unmatched.len()));
+            return Ok(());
+        }
+
+        if !self.apply {
+            for change in &plan.changes {
+                println!("{}", change.path.display());
+                for field in describe(&change.tags) {
+                    println!("    {}", field);
+                }
+            }
+            println!(
+                "\n{}",
+                t!("tag-preview", count = plan.changes.len(), unmatched = plan.unmatched.len())
+            );
+            return Ok(());
+        }
+
+        let mut tagged = Vec::new();
+        for change in &plan.changes {
+            match change.tags.write(&change.path) {
+                Ok(()) => tagged.push(change.path.clone()),
+                Err(e) => println!("{}", t!("file-failed", path = change.path.display().to_string(), error = e.to_string())),
+            }
+        }
+
+        // Refresh library entries so the new tags show up without a rescan
+        let in_library: Vec<_> = tagged
+            .iter()
+            .filter(|path| path.starts_with(&config.music_dir))
+            .filter_map(|path| scanner.extract_metadata(path).ok())
+            .collect();
+        if !in_library.is_empty() {
+            LibraryService::new(&config.database_path, &config.sort_articles)?.add_songs(&in_library)?;
+        }
+
+        println!(
+            "{}",
+            t!("tag-done", count = tagged.len(), failed = plan.changes.len() - tagged.len())
+        );
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Fill in missing tags from file names"
+    }
+}
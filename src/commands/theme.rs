@@ -0,0 +1,95 @@
+use crate::cli::ThemeAction;
+use crate::commands::Command;
+use crate::config::{Config, LayoutConfig};
+use crate::error::{LofiTurtleError, Result};
+use crate::t;
+use crate::ui::contrast;
+use crate::ui::layout::ThemeConfig;
+use crate::ui::Themes;
+use std::path::PathBuf;
+
+pub struct ThemeCommand {
+    action: ThemeAction,
+    layout_path: PathBuf,
+}
+
+impl ThemeCommand {
+    pub fn new(action: ThemeAction, layout_path: PathBuf) -> Self {
+        Self { action, layout_path }
+    }
+
+    /// The built-in theme called `name`, or the layout's theme if it has
+    /// that name
+    fn find_theme(&self, name: &str) -> Option<ThemeConfig> {
+        Themes::get_by_name(name).or_else(|| {
+            LayoutConfig::load_from_file(&self.layout_path)
+                .ok()
+                .map(|layout| layout.theme)
+                .filter(|theme| theme.name.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+impl Command for ThemeCommand {
+    fn execute(&self, _config: &Config) -> Result<()> {
+        match &self.action {
+            ThemeAction::Check { name } => {
+                let theme = self.find_theme(name).ok_or_else(|| {
+                    let themes: Vec<String> = Themes::all().into_iter().map(|theme| theme.name).collect();
+                    LofiTurtleError::Configuration(t!(
+                        "theme-unknown",
+                        name = name.as_str(),
+                        themes = themes.join(", ")
+                    ))
+                })?;
+
+                println!("{}", t!("theme-check-title", name = theme.name.as_str()));
+                let pairs = contrast::check(&theme);
+                for pair in &pairs {
+                    let ratio = format!("{:.2}", pair.ratio);
+                    let foreground = pair.foreground.to_string();
+                    let background = pair.background.to_string();
+                    if pair.is_readable() {
+                        println!(
+                            "  {}",
+                            t!(
+                                "theme-check-pair",
+                                style = pair.name,
+                                foreground = foreground,
+                                background = background,
+                                ratio = ratio
+                            )
+                        );
+                    } else {
+                        let minimum = format!("{:.1}", pair.minimum);
+                        println!(
+                            "{}: {}",
+                            t!("config-check-warning"),
+                            t!(
+                                "theme-check-low",
+                                style = pair.name,
+                                foreground = foreground,
+                                background = background,
+                                ratio = ratio,
+                                minimum = minimum
+                            )
+                        );
+                    }
+                }
+
+                let low = pairs.iter().filter(|pair| !pair.is_readable()).count();
+                if low == 0 {
+                    println!("{}", t!("theme-check-ok", count = pairs.len()));
+                } else {
+                    println!("{}", t!("theme-check-summary", count = low, total = pairs.len()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Check the contrast of a theme"
+    }
+}
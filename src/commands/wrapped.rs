@@ -0,0 +1,49 @@
+use crate::commands::Command;
+use crate::config::Config;
+use crate::error::Result;
+use crate::services::library_service::start_of_day;
+use crate::services::wrapped::{Wrapped, WrappedPeriod};
+use crate::services::LibraryService;
+use crate::t;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct WrappedCommand {
+    period: WrappedPeriod,
+    report: Option<PathBuf>,
+}
+
+impl WrappedCommand {
+    pub fn new(period: WrappedPeriod, report: Option<PathBuf>) -> Self {
+        Self { period, report }
+    }
+}
+
+impl Command for WrappedCommand {
+    fn execute(&self, config: &Config) -> Result<()> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+
+        let now = chrono::Local::now();
+        let today = now.date_naive();
+        let first_day = self.period.first_day(today);
+        let from = start_of_day(first_day);
+
+        let listens = library.get_listens(from, now.to_utc())?;
+        let known_artists: HashSet<String> = library.artists_heard_before(from)?.into_iter().collect();
+        let wrapped = Wrapped::new(self.period, first_day, today, &listens, &known_artists, &chrono::Local);
+
+        print!("{}", wrapped.to_text());
+
+        if let Some(report) = &self.report {
+            fs::write(report, wrapped.to_markdown())?;
+            println!("\n{}", t!("wrapped-report-written", file = report.display().to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Summarize listening over the past week, month or year"
+    }
+}
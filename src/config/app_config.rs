@@ -4,9 +4,14 @@
 //! previously in config.rs, now properly organized within the config module.
 
 use crate::error::{LofiTurtleError, Result};
-use crate::models::RepeatMode;
-use crate::art::AlbumArtConfig;
-use std::path::PathBuf;
+use crate::models::{AutoDjStrategy, PlaylistSort, RepeatMode};
+use crate::art::{AlbumArtConfig, CoverCache};
+use crate::audio::{AudioOptions, CrossfadeOn};
+use crate::library::{GenreRules, ScanOptions};
+use crate::scrobbling::LastFmSession;
+use crate::config::paths::{self, Location};
+use crate::shared::sort_key;
+use std::path::{Path, PathBuf};
 use std::fs;
 use serde::{Deserialize, Serialize};
 
@@ -15,13 +20,77 @@ use serde::{Deserialize, Serialize};
 pub struct PersistentSettings {
     pub volume: f32,
     pub shuffle: bool,
+    /// Shuffle with smart shuffle, which spaces out artists and saves songs
+    /// heard lately for later
+    #[serde(default)]
+    pub smart_shuffle: bool,
     pub repeat_mode: RepeatMode,
+    /// Language of the interface, e.g. "vi"; the system locale if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Order of the Playlists panel
+    #[serde(default)]
+    pub playlist_sort: PlaylistSort,
+    /// Music directory used when none is given on the command line, so each
+    /// profile can have its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub music_dir: Option<PathBuf>,
+    /// Keep the playing song selected in the song list as playback moves on
+    #[serde(default)]
+    pub follow_playback: bool,
+    /// Name of the built-in theme picked in the player, used over the
+    /// layout's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Sort artists and albums without their leading article
+    #[serde(default)]
+    pub ignore_articles: bool,
+    /// Articles left out when `ignore_articles` is on; "The", "A" and "An"
+    /// if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub articles: Option<Vec<String>>,
+    /// Show a desktop notification when the track changes
+    #[serde(default)]
+    pub notifications: bool,
+    /// Milliseconds a notification stays up; as long as the desktop keeps
+    /// them if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_timeout_ms: Option<u64>,
+    /// Hold notifications back for now, without turning them off
+    #[serde(default)]
+    pub do_not_disturb: bool,
+    /// Show the time left in the song after the position, rather than the
+    /// song's length
+    #[serde(default)]
+    pub show_remaining: bool,
+    /// Audio host picked in the player, used unless one is given on the
+    /// command line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_host: Option<String>,
+    /// Output device of `audio_host` picked in the player
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_device: Option<String>,
+    /// Preamp last given with `--preamp`, in decibels, used when the option
+    /// is left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preamp_db: Option<f32>,
+    /// Go on to the next album when one played on its own ends, as last
+    /// given with `--continue-albums`
+    #[serde(default)]
+    pub continue_albums: bool,
+    /// Seconds of the next song decoded ahead, as last given with
+    /// `--prebuffer`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prebuffer_secs: Option<u64>,
+    /// Audio device buffer in frames, as last given with `--audio-buffer`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_buffer_frames: Option<u32>,
 }
 
 impl PersistentSettings {
     /// Get the path to the settings file
-    fn settings_path() -> PathBuf {
-        PathBuf::from("lofiturtle_settings.json")
+    pub fn settings_path() -> PathBuf {
+        paths::default_path(Location::Config, "lofiturtle_settings.json")
     }
 
     /// Load persistent settings from file
@@ -43,6 +112,17 @@ impl PersistentSettings {
         }
     }
 
+    /// Articles artists and albums sort without, none unless
+    /// `ignore_articles` is on
+    pub fn sort_articles(&self) -> Vec<String> {
+        if !self.ignore_articles {
+            return Vec::new();
+        }
+        self.articles
+            .clone()
+            .unwrap_or_else(|| sort_key::DEFAULT_ARTICLES.iter().map(|article| article.to_string()).collect())
+    }
+
     /// Save persistent settings to file
     pub fn save(&self) -> Result<()> {
         let content = serde_json::to_string_pretty(self)
@@ -66,7 +146,25 @@ impl Default for PersistentSettings {
         Self {
             volume: 0.7,
             shuffle: false,
+            smart_shuffle: false,
             repeat_mode: RepeatMode::None,
+            locale: None,
+            playlist_sort: PlaylistSort::default(),
+            music_dir: None,
+            follow_playback: false,
+            theme: None,
+            ignore_articles: false,
+            articles: None,
+            notifications: false,
+            notification_timeout_ms: None,
+            do_not_disturb: false,
+            show_remaining: false,
+            audio_host: None,
+            audio_device: None,
+            preamp_db: None,
+            continue_albums: false,
+            prebuffer_secs: None,
+            audio_buffer_frames: None,
         }
     }
 }
@@ -75,32 +173,70 @@ impl Default for PersistentSettings {
 #[derive(Debug, Clone)]
 pub struct Config {
     pub music_dir: PathBuf,
+    /// No music directory has been chosen yet, so the player offers to
+    /// pick one and `music_dir` may not exist
+    pub setup: bool,
     pub database_path: PathBuf,
     pub verbose: bool,
     pub no_scan: bool,
     pub tick_rate_ms: u64,
     pub default_volume: f32,
     pub show_art: bool,
+    /// Plain text output for terminal screen readers
+    pub screen_reader: bool,
     pub shuffle: bool,
     pub repeat_mode: RepeatMode,
+    pub auto_dj: Option<AutoDjStrategy>,
+    pub continue_albums: bool,
+    pub recently_added_days: u32,
+    /// Articles artists and albums sort without
+    pub sort_articles: Vec<String>,
     pub album_art_config: AlbumArtConfig,
     pub cli_mode: bool,
+    pub event_log: Option<PathBuf>,
+    pub scripts_dir: PathBuf,
+    pub scan_options: ScanOptions,
+    pub audio_options: AudioOptions,
+    pub media_keys: bool,
+    pub delete_to_trash: bool,
+    pub acoustid_key: Option<String>,
+    pub listenbrainz_token: Option<String>,
+    pub lastfm_api_key: Option<String>,
+    pub lastfm_secret: Option<String>,
+    pub lastfm_session_key: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             music_dir: crate::cli::Cli::default_music_dir(),
-            database_path: PathBuf::from("music_library.db"),
+            setup: false,
+            database_path: paths::default_path(Location::Data, "music_library.db"),
             verbose: false,
             no_scan: false,
             tick_rate_ms: 250,
             default_volume: 0.7,
             show_art: true,
+            screen_reader: false,
             shuffle: false,
             repeat_mode: RepeatMode::None,
+            auto_dj: None,
+            continue_albums: false,
+            recently_added_days: 30,
+            sort_articles: Vec::new(),
             album_art_config: AlbumArtConfig::default(),
             cli_mode: false,
+            event_log: None,
+            scripts_dir: paths::default_path(Location::Config, "scripts"),
+            scan_options: ScanOptions::default(),
+            audio_options: AudioOptions::default(),
+            media_keys: true,
+            delete_to_trash: true,
+            acoustid_key: None,
+            listenbrainz_token: None,
+            lastfm_api_key: None,
+            lastfm_secret: None,
+            lastfm_session_key: None,
         }
     }
 }
@@ -109,16 +245,33 @@ impl Default for Config {
 #[derive(Debug, Default)]
 pub struct ConfigBuilder {
     music_dir: Option<PathBuf>,
+    setup: Option<bool>,
     database_path: Option<PathBuf>,
     verbose: Option<bool>,
     no_scan: Option<bool>,
     tick_rate_ms: Option<u64>,
     default_volume: Option<f32>,
     show_art: Option<bool>,
+    screen_reader: Option<bool>,
     shuffle: Option<bool>,
     repeat_mode: Option<RepeatMode>,
+    auto_dj: Option<AutoDjStrategy>,
+    continue_albums: Option<bool>,
+    recently_added_days: Option<u32>,
+    sort_articles: Option<Vec<String>>,
     album_art_config: Option<AlbumArtConfig>,
     cli_mode: Option<bool>,
+    event_log: Option<PathBuf>,
+    scripts_dir: Option<PathBuf>,
+    scan_options: Option<ScanOptions>,
+    audio_options: Option<AudioOptions>,
+    media_keys: Option<bool>,
+    delete_to_trash: Option<bool>,
+    acoustid_key: Option<String>,
+    listenbrainz_token: Option<String>,
+    lastfm_api_key: Option<String>,
+    lastfm_secret: Option<String>,
+    lastfm_session_key: Option<String>,
 }
 
 impl ConfigBuilder {
@@ -133,6 +286,13 @@ impl ConfigBuilder {
         self
     }
 
+    /// Let the player start without a chosen music directory and offer to
+    /// pick one
+    pub fn setup(mut self, setup: bool) -> Self {
+        self.setup = Some(setup);
+        self
+    }
+
     /// Set the database path
     pub fn database_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
         self.database_path = Some(path.into());
@@ -161,7 +321,7 @@ impl ConfigBuilder {
     /// Set the default volume (0.0 to 1.0)
     #[allow(dead_code)] // Future feature: configurable volume
     pub fn default_volume(mut self, volume: f32) -> Self {
-        if volume < 0.0 || volume > 1.0 {
+        if !(0.0..=1.0).contains(&volume) {
             log::warn!("Volume should be between 0.0 and 1.0, got {}", volume);
         }
         self.default_volume = Some(volume.clamp(0.0, 1.0));
@@ -174,6 +334,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable or disable the plain text output for screen readers
+    pub fn screen_reader(mut self, enabled: bool) -> Self {
+        self.screen_reader = Some(enabled);
+        self
+    }
+
     /// Enable or disable shuffle mode
     pub fn shuffle(mut self, shuffle: bool) -> Self {
         self.shuffle = Some(shuffle);
@@ -186,6 +352,30 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set the auto-DJ strategy used when the queue runs out
+    pub fn auto_dj(mut self, strategy: Option<AutoDjStrategy>) -> Self {
+        self.auto_dj = strategy;
+        self
+    }
+
+    /// Go on to the next album when the queue runs out at the end of one
+    pub fn continue_albums(mut self, enabled: bool) -> Self {
+        self.continue_albums = Some(enabled);
+        self
+    }
+
+    /// Set how many days the "Recently Added" view looks back
+    pub fn recently_added_days(mut self, days: u32) -> Self {
+        self.recently_added_days = Some(days);
+        self
+    }
+
+    /// Set the articles artists and albums sort without
+    pub fn sort_articles(mut self, articles: Vec<String>) -> Self {
+        self.sort_articles = Some(articles);
+        self
+    }
+
     /// Set the album art configuration
     pub fn album_art_config(mut self, config: AlbumArtConfig) -> Self {
         self.album_art_config = Some(config);
@@ -198,21 +388,79 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set the file player events are published to
+    pub fn event_log<P: Into<PathBuf>>(mut self, path: Option<P>) -> Self {
+        self.event_log = path.map(Into::into);
+        self
+    }
+
+    /// Set the directory user scripts are loaded from
+    pub fn scripts_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.scripts_dir = Some(path.into());
+        self
+    }
+
+    /// Set the filesystem options used when scanning the library
+    pub fn scan_options(mut self, options: ScanOptions) -> Self {
+        self.scan_options = Some(options);
+        self
+    }
+
+    /// Set the audio output options
+    pub fn audio_options(mut self, options: AudioOptions) -> Self {
+        self.audio_options = Some(options);
+        self
+    }
+
+    /// Set whether the player registers with the OS media session
+    pub fn media_keys(mut self, enabled: bool) -> Self {
+        self.media_keys = Some(enabled);
+        self
+    }
+
+    /// Set whether deleted song files go to the trash
+    pub fn delete_to_trash(mut self, enabled: bool) -> Self {
+        self.delete_to_trash = Some(enabled);
+        self
+    }
+
+    /// Set the AcoustID API key used to identify untagged files
+    pub fn acoustid_key(mut self, key: Option<String>) -> Self {
+        self.acoustid_key = key;
+        self
+    }
+
+    /// Set the ListenBrainz user token listens are scrobbled with
+    pub fn listenbrainz_token(mut self, token: Option<String>) -> Self {
+        self.listenbrainz_token = token;
+        self
+    }
+
+    /// Set the Last.fm API account and the session key loved tracks are
+    /// synced with
+    pub fn lastfm(mut self, api_key: Option<String>, secret: Option<String>, session_key: Option<String>) -> Self {
+        self.lastfm_api_key = api_key;
+        self.lastfm_secret = secret;
+        self.lastfm_session_key = session_key;
+        self
+    }
+
     /// Build the configuration, validating all settings
     pub fn build(self) -> Result<Config> {
         let default_config = Config::default();
         
         let music_dir = self.music_dir.unwrap_or(default_config.music_dir);
         let database_path = self.database_path.unwrap_or(default_config.database_path);
+        let setup = self.setup.unwrap_or(default_config.setup);
         
-        // Validate music directory exists
-        if !music_dir.exists() {
+        // Validate music directory exists, unless one is still to be picked
+        if !setup && !music_dir.exists() {
             return Err(LofiTurtleError::DirectoryNotFound(
                 format!("Music directory '{}' does not exist", music_dir.display())
             ));
         }
 
-        if !music_dir.is_dir() {
+        if !setup && !music_dir.is_dir() {
             return Err(LofiTurtleError::Configuration(
                 format!("'{}' is not a directory", music_dir.display())
             ));
@@ -228,16 +476,33 @@ impl ConfigBuilder {
 
         Ok(Config {
             music_dir,
+            setup,
             database_path,
             verbose: self.verbose.unwrap_or(default_config.verbose),
             no_scan: self.no_scan.unwrap_or(default_config.no_scan),
             tick_rate_ms,
             default_volume: self.default_volume.unwrap_or(default_config.default_volume),
             show_art: self.show_art.unwrap_or(default_config.show_art),
+            screen_reader: self.screen_reader.unwrap_or(default_config.screen_reader),
             shuffle: self.shuffle.unwrap_or(default_config.shuffle),
             repeat_mode: self.repeat_mode.unwrap_or(default_config.repeat_mode),
+            auto_dj: self.auto_dj.or(default_config.auto_dj),
+            continue_albums: self.continue_albums.unwrap_or(default_config.continue_albums),
+            recently_added_days: self.recently_added_days.unwrap_or(default_config.recently_added_days),
+            sort_articles: self.sort_articles.unwrap_or(default_config.sort_articles),
             album_art_config: self.album_art_config.unwrap_or(default_config.album_art_config),
             cli_mode: self.cli_mode.unwrap_or(default_config.cli_mode),
+            event_log: self.event_log.or(default_config.event_log),
+            scripts_dir: self.scripts_dir.unwrap_or(default_config.scripts_dir),
+            scan_options: self.scan_options.unwrap_or(default_config.scan_options),
+            audio_options: self.audio_options.unwrap_or(default_config.audio_options),
+            media_keys: self.media_keys.unwrap_or(default_config.media_keys),
+            delete_to_trash: self.delete_to_trash.unwrap_or(default_config.delete_to_trash),
+            acoustid_key: self.acoustid_key.or(default_config.acoustid_key),
+            listenbrainz_token: self.listenbrainz_token.or(default_config.listenbrainz_token),
+            lastfm_api_key: self.lastfm_api_key.or(default_config.lastfm_api_key),
+            lastfm_secret: self.lastfm_secret.or(default_config.lastfm_secret),
+            lastfm_session_key: self.lastfm_session_key.or(default_config.lastfm_session_key),
         })
     }
 }
@@ -250,7 +515,10 @@ impl Config {
 
     /// Create configuration from CLI arguments
     pub fn from_cli(cli: &crate::cli::Cli) -> Result<Self> {
-        let music_dir = cli.validate_music_dir()?;
+        // A missing default directory is no error when the player is about
+        // to ask for one
+        let setup = cli.needs_setup();
+        let music_dir = if setup { cli.get_music_dir() } else { cli.validate_music_dir()? };
         
         // Convert CLI repeat mode to internal repeat mode
         let repeat_mode = match &cli.repeat {
@@ -260,24 +528,88 @@ impl Config {
             None => RepeatMode::None,
         };
         
-        // Determine show_art: default true, but can be disabled with --no-art
-        let show_art = !cli.no_art; // Default true unless --no-art is specified
+        let auto_dj = cli.auto_dj.as_ref().map(|strategy| match strategy {
+            crate::cli::AutoDjArg::SameArtist => AutoDjStrategy::SameArtist,
+            crate::cli::AutoDjArg::SameAlbum => AutoDjStrategy::SameAlbum,
+            crate::cli::AutoDjArg::SameGenre => AutoDjStrategy::SameGenre,
+            crate::cli::AutoDjArg::LeastRecentlyPlayed => AutoDjStrategy::LeastRecentlyPlayed,
+            crate::cli::AutoDjArg::Random => AutoDjStrategy::Random,
+        });
+        
+        // Determine show_art: default true, but can be disabled with --no-art,
+        // and screen readers have no use for it
+        let show_art = !cli.no_art && !cli.screen_reader;
         
         // Create album art configuration
         let album_art_config = AlbumArtConfig::builder()
             .show_art(show_art)
+            .cache_max_entries(cli.art_cache_entries)
+            .cache_max_bytes(cli.art_cache_mb.saturating_mul(1024 * 1024))
+            .cover_dir(CoverCache::default_dir())
             .build();
         
+        let scan_options = ScanOptions {
+            follow_symlinks: cli.follow_symlinks,
+            io_timeout: cli.io_timeout.map(std::time::Duration::from_secs),
+            max_files_per_sec: cli.scan_rate,
+            genre_rules: load_genre_rules(&cli.genres_path()),
+            covers: Some(CoverCache::new(CoverCache::default_dir())),
+            ..ScanOptions::default()
+        };
+
+        let (host, device) = cli.audio_output();
+        let audio_options = AudioOptions {
+            prebuffer: std::time::Duration::from_secs(cli.prebuffer_secs()),
+            buffer_frames: cli.audio_buffer_frames(),
+            host,
+            device,
+            crossfade: std::time::Duration::from_secs(cli.crossfade),
+            crossfade_on: match cli.crossfade_on {
+                crate::cli::CrossfadeOnArg::Skip => CrossfadeOn::Skip,
+                crate::cli::CrossfadeOnArg::End => CrossfadeOn::End,
+                crate::cli::CrossfadeOnArg::Both => CrossfadeOn::Both,
+            },
+            preamp_db: cli.preamp_db(),
+        };
+
         Self::builder()
             .music_dir(music_dir)
-            .database_path(&cli.database)
+            .setup(setup)
+            .database_path(cli.database_path())
             .verbose(cli.verbose)
             .no_scan(cli.no_scan)
             .show_art(show_art)
+            .screen_reader(cli.screen_reader)
             .shuffle(cli.shuffle)
             .repeat_mode(repeat_mode)
+            .auto_dj(auto_dj)
+            .continue_albums(cli.continue_albums())
+            .recently_added_days(cli.recent_days)
+            .sort_articles(PersistentSettings::load().sort_articles())
             .album_art_config(album_art_config)
             .cli_mode(cli.cli_mode)
+            .event_log(cli.event_log.as_ref())
+            .scripts_dir(cli.scripts_path())
+            .scan_options(scan_options)
+            .audio_options(audio_options)
+            .media_keys(!cli.no_media_keys)
+            .delete_to_trash(!cli.no_trash)
+            .acoustid_key(cli.acoustid_key.clone().or_else(|| std::env::var("ACOUSTID_API_KEY").ok()))
+            .listenbrainz_token(cli.listenbrainz_token.clone().or_else(|| std::env::var("LISTENBRAINZ_TOKEN").ok()))
+            .lastfm(
+                cli.lastfm_api_key.clone().or_else(|| std::env::var("LASTFM_API_KEY").ok()),
+                cli.lastfm_secret.clone().or_else(|| std::env::var("LASTFM_API_SECRET").ok()),
+                std::env::var("LASTFM_SESSION_KEY").ok().or_else(|| LastFmSession::load_key(&LastFmSession::path())),
+            )
             .build()
     }
 }
+
+/// Genre rules of the file at `path`, none if it is missing or broken
+fn load_genre_rules(path: &Path) -> GenreRules {
+    GenreRules::load(path).unwrap_or_else(|e| {
+        log::warn!("Failed to load genre rules: {}", e);
+        eprintln!("{}", crate::t!("genres-load-failed", error = e.to_string()));
+        GenreRules::default()
+    })
+}
@@ -0,0 +1,530 @@
+//! Checks of the configuration files, for `lofiturtle config check`
+//!
+//! Each file is read the way startup reads it, but instead of falling back to
+//! defaults on the first problem, every problem found is reported with the
+//! file, line and field it comes from. Keys the program would ignore, which
+//! are usually typos, are reported as warnings.
+
+use crate::config::{LayoutConfig, PersistentSettings};
+use crate::ui::keymap;
+use crate::ui::layout::{ThemeConfig, WidgetConfig, WidgetStyle};
+use crate::ui::theme::ColorPalette;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item};
+
+/// Keys read under another name, as (accepted, canonical)
+const KEY_ALIASES: &[(&str, &str)] = &[("widget_type", "type")];
+
+/// Message for keys the program does not read
+const UNKNOWN_KEY: &str = "Unknown key, ignored";
+
+/// One step of the way to a field
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a field is in a configuration file, e.g. `widgets[2].size`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldPath(Vec<Segment>);
+
+impl FieldPath {
+    /// Path to a top-level key
+    pub fn key(key: &str) -> Self {
+        Self::default().then_key(key)
+    }
+
+    /// Path to `key` within this field
+    pub fn then_key(mut self, key: &str) -> Self {
+        self.0.push(Segment::Key(key.to_string()));
+        self
+    }
+
+    /// Path to the entry at `index` of this array
+    pub fn then_index(mut self, index: usize) -> Self {
+        self.0.push(Segment::Index(index));
+        self
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i > 0 => write!(f, ".{}", key)?,
+                Segment::Key(key) => write!(f, "{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How bad a problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The file cannot be used as written
+    Error,
+    /// The file works, but part of it is ignored
+    Warning,
+}
+
+/// A problem found in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub field: Option<FieldPath>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    /// `file:line: field: message`, leaving out what is not known
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, ": {}", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Check a layout file, with its theme and key bindings
+pub fn check_layout(file: &Path, content: &str) -> Vec<Problem> {
+    let mut report = Report::new(file, content);
+    let Some(input) = report.parse_toml() else {
+        return report.finish();
+    };
+
+    match toml::from_str::<LayoutConfig>(content) {
+        Ok(config) => {
+            if let Ok(known) = toml::Value::try_from(&config) {
+                report.unknown_keys(FieldPath::default(), &input, &known);
+            }
+            for (field, message) in config.problems() {
+                report.error(field, message);
+            }
+            for (key, action) in &config.keybindings {
+                check_binding(&mut report, FieldPath::key("keybindings").then_key(key), key, action);
+            }
+            check_theme(&mut report, &config.theme);
+            for (i, widget) in config.widgets.iter().enumerate() {
+                check_widget_style(&mut report, FieldPath::key("widgets").then_index(i).then_key("style"), &widget.style, &config.theme);
+            }
+        }
+        Err(e) => layout_section_errors(&mut report, &input, &e),
+    }
+
+    report.finish()
+}
+
+/// Check a keymap file, read as its `[keybindings]` table or, without one,
+/// as a table of keys to actions
+pub fn check_keymap(file: &Path, content: &str) -> Vec<Problem> {
+    let mut report = Report::new(file, content);
+    let Some(input) = report.parse_toml() else {
+        return report.finish();
+    };
+
+    let (base, bindings) = match input.get("keybindings").filter(|bindings| bindings.is_table()) {
+        Some(bindings) => {
+            let others = input.as_table().into_iter().flatten().filter(|(key, _)| *key != "keybindings");
+            for (key, _) in others {
+                report.warning(FieldPath::key(key), UNKNOWN_KEY.to_string());
+            }
+            (FieldPath::key("keybindings"), bindings)
+        }
+        None => (FieldPath::default(), &input),
+    };
+
+    for (key, action) in bindings.as_table().into_iter().flatten() {
+        let field = base.clone().then_key(key);
+        match action.as_str() {
+            Some(_) if key.is_empty() => report.error(field, "Keybinding key cannot be empty".to_string()),
+            Some("") => report.error(field, format!("Keybinding action for key '{}' cannot be empty", key)),
+            Some(action) => check_binding(&mut report, field, key, action),
+            None => report.warning(field, format!("Expected an action name, found {}; ignored", action)),
+        }
+    }
+
+    report.finish()
+}
+
+/// Check the saved settings, which are JSON
+pub fn check_settings(file: &Path, content: &str) -> Vec<Problem> {
+    let mut report = Report::new(file, content);
+
+    let settings = match serde_json::from_str::<PersistentSettings>(content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            // The position is reported as the line instead
+            let message = e.to_string();
+            let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+            report.push(Severity::Error, Some(e.line()), None, message.to_string());
+            return report.finish();
+        }
+    };
+
+    let input = serde_json::from_str::<serde_json::Value>(content).unwrap_or_default();
+    let known = serde_json::to_value(&settings).unwrap_or_default();
+    for key in input.as_object().into_iter().flat_map(|object| object.keys()) {
+        if known.get(key).is_none() {
+            let quoted = format!("\"{}\"", key);
+            let line = content.find(&quoted).map(|offset| line_at(content, offset));
+            report.push(Severity::Warning, line, Some(FieldPath::key(key)), UNKNOWN_KEY.to_string());
+        }
+    }
+
+    if let Some(locale) = &settings.locale {
+        if !crate::i18n::available_languages().any(|language| language == locale) {
+            let line = content.find("\"locale\"").map(|offset| line_at(content, offset));
+            let message = format!("No translation for '{}'; the system language is used", locale);
+            report.push(Severity::Warning, line, Some(FieldPath::key("locale")), message);
+        }
+    }
+
+    report.finish()
+}
+
+/// When the layout does not parse, parse each of its sections on its own, so
+/// every broken one is reported rather than only the first
+fn layout_section_errors(report: &mut Report, input: &toml::Value, error: &toml::de::Error) {
+    let found = report.problems.len();
+
+    for (key, value) in input.as_table().into_iter().flatten() {
+        if let (true, Some(widgets)) = (key == "widgets", value.as_array()) {
+            for (i, widget) in widgets.iter().enumerate() {
+                if let Err(e) = widget.clone().try_into::<WidgetConfig>() {
+                    report.section_error(FieldPath::key(key).then_index(i), &e, error);
+                }
+            }
+            continue;
+        }
+
+        let section = toml::Value::Table([(key.clone(), value.clone())].into_iter().collect());
+        if let Err(e) = section.try_into::<LayoutConfig>() {
+            report.section_error(FieldPath::key(key), &e, error);
+        }
+    }
+
+    if report.problems.len() == found {
+        report.parse_error(error.span(), error.message());
+    }
+}
+
+/// Check a key binding names a key and an action that exist
+fn check_binding(report: &mut Report, field: FieldPath, key: &str, action: &str) {
+    // Empty keys and actions are reported by the layout's own validation
+    if !key.is_empty() && !keymap::is_key(key) {
+        report.error(field.clone(), format!("Unknown key '{}'", key));
+    }
+    if !action.is_empty() && !keymap::is_action(action) {
+        report.error(field, format!("Unknown action '{}'", action));
+    }
+}
+
+/// Check the theme's colors parse and its styles use colors that exist
+fn check_theme(report: &mut Report, theme: &ThemeConfig) {
+    let base = FieldPath::key("theme");
+    for (name, color) in theme.colors.iter().flatten() {
+        if ColorPalette::parse_color(color).is_none() {
+            report.error(base.clone().then_key("colors").then_key(name), format!("Invalid color '{}'", color));
+        }
+    }
+
+    for (name, style) in theme.styles.iter().flatten() {
+        let field = base.clone().then_key("styles").then_key(name);
+        for (key, color) in [("fg", &style.fg), ("bg", &style.bg)] {
+            check_style_color(report, field.clone().then_key(key), color, theme);
+        }
+    }
+}
+
+/// Check a widget's style overrides use colors that exist
+fn check_widget_style(report: &mut Report, field: FieldPath, style: &WidgetStyle, theme: &ThemeConfig) {
+    let colors = [
+        ("fg_color", &style.fg_color),
+        ("bg_color", &style.bg_color),
+        ("border_color", &style.border_color),
+        ("highlight_color", &style.highlight_color),
+        ("selected_color", &style.selected_color),
+    ];
+    for (key, color) in colors {
+        check_style_color(report, field.clone().then_key(key), color, theme);
+    }
+}
+
+/// Style colors name a theme color or give a color directly
+fn check_style_color(report: &mut Report, field: FieldPath, color: &Option<String>, theme: &ThemeConfig) {
+    let Some(color) = color else { return };
+    let named = theme.colors.as_ref().is_some_and(|colors| colors.contains_key(color));
+    if !named && ColorPalette::parse_color(color).is_none() {
+        report.error(field, format!("'{}' is neither a theme color nor a valid color", color));
+    }
+}
+
+/// Problems of one file, pointing fields at their line where it can be found
+struct Report<'a> {
+    file: &'a Path,
+    content: &'a str,
+    document: Option<ImDocument<&'a str>>,
+    problems: Vec<Problem>,
+}
+
+impl<'a> Report<'a> {
+    fn new(file: &'a Path, content: &'a str) -> Self {
+        Self { file, content, document: None, problems: Vec::new() }
+    }
+
+    fn push(&mut self, severity: Severity, line: Option<usize>, field: Option<FieldPath>, message: String) {
+        self.problems.push(Problem { severity, file: self.file.to_path_buf(), line, field, message });
+    }
+
+    fn error(&mut self, field: FieldPath, message: String) {
+        let line = self.line_of(&field);
+        self.push(Severity::Error, line, Some(field), message);
+    }
+
+    fn warning(&mut self, field: FieldPath, message: String) {
+        let line = self.line_of(&field);
+        self.push(Severity::Warning, line, Some(field), message);
+    }
+
+    /// Report an error parsing the whole file, at the place it points to
+    fn parse_error(&mut self, span: Option<Range<usize>>, message: &str) {
+        let line = span.map(|span| line_at(self.content, span.start));
+        self.push(Severity::Error, line, None, message.trim().to_string());
+    }
+
+    /// Parse the file as TOML, keeping the document to find lines in, or
+    /// report why it does not parse
+    fn parse_toml(&mut self) -> Option<toml::Value> {
+        match ImDocument::parse(self.content) {
+            Ok(document) => self.document = Some(document),
+            Err(e) => {
+                self.parse_error(e.span(), e.message());
+                return None;
+            }
+        }
+        toml::from_str(self.content).ok()
+    }
+
+    /// Report an error found parsing one section of a file on its own
+    ///
+    /// Where it is the error parsing the whole file hit, that error knows the
+    /// exact line; otherwise the line of the section's key is used.
+    fn section_error(&mut self, field: FieldPath, error: &toml::de::Error, whole: &toml::de::Error) {
+        let line = match whole.span() {
+            Some(span) if error.message() == whole.message() => Some(line_at(self.content, span.start)),
+            _ => self.line_of(&field),
+        };
+        let message = error.to_string().lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ");
+        self.push(Severity::Error, line, Some(field), message);
+    }
+
+    /// Warn about keys in `input` that are missing from `known`, the same
+    /// value as the program read it, so everything it ignores is pointed out
+    fn unknown_keys(&mut self, field: FieldPath, input: &toml::Value, known: &toml::Value) {
+        match (input, known) {
+            (toml::Value::Table(input), toml::Value::Table(known)) => {
+                for (key, value) in input {
+                    let canonical = KEY_ALIASES
+                        .iter()
+                        .find(|(alias, _)| alias == key)
+                        .map_or(key.as_str(), |(_, canonical)| canonical);
+                    match known.get(canonical) {
+                        Some(known) => self.unknown_keys(field.clone().then_key(key), value, known),
+                        None => self.warning(field.clone().then_key(key), UNKNOWN_KEY.to_string()),
+                    }
+                }
+            }
+            (toml::Value::Array(input), toml::Value::Array(known)) => {
+                for (i, (value, known)) in input.iter().zip(known).enumerate() {
+                    self.unknown_keys(field.clone().then_index(i), value, known);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Line of the key naming `field`, or of the nearest enclosing one found
+    fn line_of(&self, field: &FieldPath) -> Option<usize> {
+        let document = self.document.as_ref()?;
+        let mut node = Some(Node::Item(document.as_item()));
+        let mut span = None;
+
+        for segment in &field.0 {
+            let Some(current) = node else { break };
+            node = match segment {
+                Segment::Key(key) => current.table().and_then(|table| table.get_key_value(key)).map(|(key, item)| {
+                    span = key.span().or(span.take());
+                    Node::Item(item)
+                }),
+                Segment::Index(index) => current.entry(*index).inspect(|entry| {
+                    span = entry.span().or(span.take());
+                }),
+            };
+        }
+
+        span.map(|span| line_at(self.content, span.start))
+    }
+
+    /// The problems in the order of the file
+    fn finish(mut self) -> Vec<Problem> {
+        self.problems.sort_by_key(|problem| problem.line);
+        self.problems
+    }
+}
+
+/// Part of a parsed TOML document, while walking down to a field
+#[derive(Clone, Copy)]
+enum Node<'d> {
+    Item(&'d Item),
+    Table(&'d toml_edit::Table),
+    Value(&'d toml_edit::Value),
+}
+
+impl<'d> Node<'d> {
+    fn table(self) -> Option<&'d dyn toml_edit::TableLike> {
+        match self {
+            Node::Item(item) => item.as_table_like(),
+            Node::Table(table) => Some(table),
+            Node::Value(value) => value.as_inline_table().map(|table| table as &dyn toml_edit::TableLike),
+        }
+    }
+
+    fn entry(self, index: usize) -> Option<Node<'d>> {
+        match self {
+            Node::Item(Item::ArrayOfTables(tables)) => tables.get(index).map(Node::Table),
+            Node::Item(Item::Value(value)) | Node::Value(value) => value.as_array()?.get(index).map(Node::Value),
+            _ => None,
+        }
+    }
+
+    fn span(self) -> Option<Range<usize>> {
+        match self {
+            Node::Item(item) => item.span(),
+            Node::Table(table) => table.span(),
+            Node::Value(value) => value.span(),
+        }
+    }
+}
+
+/// One-based line of the byte at `offset`
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(problems: &[Problem]) -> Vec<(Severity, Option<usize>, String)> {
+        problems
+            .iter()
+            .map(|problem| (problem.severity, problem.line, problem.field.as_ref().map(ToString::to_string).unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_reports_every_broken_section() {
+        let content = r#"version = "1.0"
+
+[settings.responsive]
+small_width = "wide"
+
+[[widgets]]
+name = "songs"
+type = "songlist"
+position = "left"
+size = { percentage = 50 }
+"#;
+        let problems = check_layout(Path::new("layout.toml"), content);
+        assert_eq!(
+            lines(&problems),
+            vec![
+                (Severity::Error, Some(4), "settings".to_string()),
+                (Severity::Error, Some(6), "widgets[0]".to_string()),
+            ]
+        );
+        assert!(problems[0].message.contains("settings.responsive.small_width"));
+        assert!(problems[1].message.contains("songlist"));
+    }
+
+    #[test]
+    fn test_layout_reports_values_and_unknown_keys() {
+        let content = r##"version = "1.0"
+nmae = "typo"
+
+[theme]
+name = "night"
+[theme.colors]
+accent = "#zzzzzz"
+[theme.styles.playing]
+fg = "accent"
+bg = "nope"
+
+[[widgets]]
+name = "songs"
+widget_type = "sidebar"
+position = "left"
+size = { percentage = 50 }
+
+[keybindings]
+"ctrl+Q" = "quit"
+x = "fly"
+"##;
+        let problems = check_layout(Path::new("layout.toml"), content);
+        assert_eq!(
+            lines(&problems),
+            vec![
+                (Severity::Warning, Some(2), "nmae".to_string()),
+                (Severity::Error, Some(7), "theme.colors.accent".to_string()),
+                (Severity::Error, Some(10), "theme.styles.playing.bg".to_string()),
+                (Severity::Error, Some(19), "keybindings.ctrl+Q".to_string()),
+                (Severity::Error, Some(20), "keybindings.x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_points_at_its_line() {
+        let problems = check_layout(Path::new("layout.toml"), "version = \"1.0\"\nname = \n");
+        assert_eq!(lines(&problems), vec![(Severity::Error, Some(2), String::new())]);
+    }
+
+    #[test]
+    fn test_keymap_checks_bindings_and_ignored_keys() {
+        let content = "[keybindings]\nq = \"stop_playback\"\nz = 3\n\"g g\" = \"go_top\"\n\n[extra]\na = 1\n";
+        let problems = check_keymap(Path::new("keymap.toml"), content);
+        assert_eq!(
+            lines(&problems),
+            vec![
+                (Severity::Error, Some(2), "keybindings.q".to_string()),
+                (Severity::Warning, Some(3), "keybindings.z".to_string()),
+                (Severity::Warning, Some(6), "extra".to_string()),
+            ]
+        );
+
+        // Without a [keybindings] table the whole file is the bindings
+        assert!(check_keymap(Path::new("keymap.toml"), "q = \"quit\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_settings_report_lines() {
+        let content = "{\n  \"volume\": 0.5,\n  \"shuffle\": false,\n  \"repeat_mode\": \"None\",\n  \"colour\": 1\n}";
+        let problems = check_settings(Path::new("settings.json"), content);
+        assert_eq!(lines(&problems), vec![(Severity::Warning, Some(5), "colour".to_string())]);
+
+        let problems = check_settings(Path::new("settings.json"), &content.replace("false", "\"no\""));
+        assert_eq!(lines(&problems), vec![(Severity::Error, Some(3), String::new())]);
+        assert!(!problems[0].message.contains("at line"));
+    }
+}
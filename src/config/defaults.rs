@@ -5,13 +5,15 @@
 
 use std::collections::HashMap;
 use crate::config::layout_config::LayoutConfig;
-use crate::ui::layout::{WidgetConfig, WidgetType, Position, SizeConstraint, WidgetStyle, LayoutSettings, ResponsiveBreakpoints};
+use crate::ui::progress::ProgressBarStyle;
+use crate::ui::visualizer::VisualizerConfig;
+use crate::ui::layout::{WidgetConfig, WidgetType, Position, SizeConstraint, WidgetStyle, BorderStyle, LayoutSettings, ResponsiveBreakpoints};
 
 /// Default layout configuration as a TOML string
 /// This is used when no user configuration file is found
 #[allow(dead_code)]
 pub fn get_default_layout_toml() -> String {
-    format!(r##"version = "1.0"
+    r##"version = "1.0"
 name = "Lofi Night"
 description = "A chill, dark theme with rounded borders and vibrant accents."
 
@@ -32,7 +34,7 @@ success = "#50fa7b"      # Dracula Green
 name = "sidebar"
 type = "sidebar"
 position = "left"
-size = {{ percentage = 25 }}
+size = { percentage = 25 }
 visible = true
 border = true
 title = "Library"
@@ -50,7 +52,7 @@ title = "Current Playlist"
 name = "now_playing"
 type = "now_playing"
 position = "right"
-size = {{ percentage = 30 }}
+size = { percentage = 30 }
 visible = true
 border = true
 title = "Now Playing"
@@ -59,7 +61,7 @@ title = "Now Playing"
 name = "album_art"
 type = "album_art"
 position = "right"
-size = {{ percentage = 25 }}
+size = { percentage = 25 }
 visible = true
 border = true
 title = "Visuals"
@@ -68,7 +70,7 @@ title = "Visuals"
 name = "progress"
 type = "progress_bar"
 position = "bottom"
-size = {{ length = 3 }}
+size = { length = 3 }
 visible = true
 border = false
 
@@ -76,7 +78,7 @@ border = false
 name = "status"
 type = "status_bar"
 position = "bottom"
-size = {{ length = 1 }}
+size = { length = 1 }
 visible = true
 border = false
 
@@ -101,11 +103,17 @@ esc = "quit"
 [settings]
 auto_save = true
 debounce_ms = 300
+progress_bar = "gradient"
+chord_timeout_ms = 1000
+
+[settings.visualizer]
+mode = "spectrum"
+preset = "bars"
 
 [settings.responsive]
 small_width = 80
 medium_width = 120
-large_width = 160"##)
+large_width = 160"##.to_string()
 }
 
 /// Create default layout configuration
@@ -158,6 +166,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Percentage(25),
                 visible: true,
                 border: true,
+                border_style: BorderStyle::Rounded,
                 title: Some("Library".to_string()),
                 style: WidgetStyle::default(),
             },
@@ -168,6 +177,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Fill,
                 visible: true,
                 border: true,
+                border_style: BorderStyle::Rounded,
                 title: Some("Current Playlist".to_string()),
                 style: WidgetStyle::default(),
             },
@@ -178,6 +188,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Percentage(30),
                 visible: true,
                 border: true,
+                border_style: BorderStyle::Rounded,
                 title: Some("Now Playing".to_string()),
                 style: WidgetStyle::default(),
             },
@@ -188,6 +199,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Percentage(25),
                 visible: true,
                 border: true,
+                border_style: BorderStyle::Rounded,
                 title: Some("Visuals".to_string()),
                 style: WidgetStyle::default(),
             },
@@ -198,6 +210,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Length(3),
                 visible: true,
                 border: false,
+                border_style: BorderStyle::Rounded,
                 title: None,
                 style: WidgetStyle::default(),
             },
@@ -208,6 +221,7 @@ pub fn create_default_layout() -> LayoutConfig {
                 size: SizeConstraint::Length(1),
                 visible: true,
                 border: false,
+                border_style: BorderStyle::Rounded,
                 title: None,
                 style: WidgetStyle::default(),
             },
@@ -221,6 +235,10 @@ pub fn create_default_layout() -> LayoutConfig {
                 medium_width: 120,
                 large_width: 160,
             },
+            progress_bar: ProgressBarStyle::Gradient,
+            status_bar: None,
+            chord_timeout_ms: 1000,
+            visualizer: VisualizerConfig::default(),
         },
     }
 }
@@ -259,6 +277,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Percentage(25),
             visible: true,
             border: true,
+            border_style: BorderStyle::Rounded,
             title: Some("Library".to_string()),
             style: WidgetStyle::default(),
         },
@@ -269,6 +288,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Fill,
             visible: true,
             border: true,
+            border_style: BorderStyle::Rounded,
             title: Some("Current Playlist".to_string()),
             style: WidgetStyle::default(),
         },
@@ -279,6 +299,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Percentage(30),
             visible: true,
             border: true,
+            border_style: BorderStyle::Rounded,
             title: Some("Now Playing".to_string()),
             style: WidgetStyle::default(),
         },
@@ -289,6 +310,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Percentage(25),
             visible: true,
             border: true,
+            border_style: BorderStyle::Rounded,
             title: Some("Visuals".to_string()),
             style: WidgetStyle::default(),
         },
@@ -299,6 +321,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Length(3),
             visible: true,
             border: false,
+            border_style: BorderStyle::Rounded,
             title: None,
             style: WidgetStyle::default(),
         },
@@ -309,6 +332,7 @@ pub fn get_default_widgets() -> Vec<WidgetConfig> {
             size: SizeConstraint::Length(1),
             visible: true,
             border: false,
+            border_style: BorderStyle::Rounded,
             title: None,
             style: WidgetStyle::default(),
         },
@@ -326,5 +350,9 @@ pub fn get_default_settings() -> LayoutSettings {
             medium_width: 120,
             large_width: 160,
         },
+        progress_bar: ProgressBarStyle::Gradient,
+        status_bar: None,
+        chord_timeout_ms: 1000,
+        visualizer: VisualizerConfig::default(),
     }
 }
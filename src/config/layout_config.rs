@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use crate::config::check::FieldPath;
 use crate::error::{LofiTurtleError, Result};
 use crate::ui::layout::{WidgetConfig, LayoutSettings};
 use crate::ui::layout::ThemeConfig;
@@ -95,47 +96,51 @@ impl LayoutConfig {
     /// Validate the layout configuration
     #[allow(dead_code)]
     pub fn validate(&self) -> Result<()> {
+        match self.problems().into_iter().next() {
+            Some((_, message)) => Err(LofiTurtleError::Configuration(message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Everything that makes the layout invalid, each with the field it is in
+    pub fn problems(&self) -> Vec<(FieldPath, String)> {
+        let mut problems = Vec::new();
+
         // Check version compatibility
         if self.version.is_empty() {
-            return Err(LofiTurtleError::Configuration(
-                "Layout version cannot be empty".to_string()
-            ));
+            problems.push((FieldPath::key("version"), "Layout version cannot be empty".to_string()));
         }
-        
+
         // Check that we have at least one visible widget
         if !self.widgets.iter().any(|w| w.visible) {
-            return Err(LofiTurtleError::Configuration(
-                "At least one widget must be visible".to_string()
-            ));
+            problems.push((FieldPath::key("widgets"), "At least one widget must be visible".to_string()));
         }
-        
+
         // Check for duplicate widget names
         let mut widget_names = std::collections::HashSet::new();
-        for widget in &self.widgets {
+        for (i, widget) in self.widgets.iter().enumerate() {
             if !widget_names.insert(&widget.name) {
-                return Err(LofiTurtleError::Configuration(
-                    format!("Duplicate widget name: {}", widget.name)
-                ));
+                problems.push((FieldPath::key("widgets").then_index(i).then_key("name"), format!("Duplicate widget name: {}", widget.name)));
             }
         }
-        
+
         // Validate keybindings (check for empty keys or actions)
         for (key, action) in &self.keybindings {
             if key.is_empty() {
-                return Err(LofiTurtleError::Configuration(
-                    "Keybinding key cannot be empty".to_string()
-                ));
-            }
-            if action.is_empty() {
-                return Err(LofiTurtleError::Configuration(
-                    format!("Keybinding action for key '{}' cannot be empty", key)
+                problems.push((FieldPath::key("keybindings"), "Keybinding key cannot be empty".to_string()));
+            } else if action.is_empty() {
+                problems.push((
+                    FieldPath::key("keybindings").then_key(key),
+                    format!("Keybinding action for key '{}' cannot be empty", key),
                 ));
             }
         }
-        
-        Ok(())
+
+        problems.extend(self.settings.visualizer.problems(&FieldPath::key("settings").then_key("visualizer")));
+
+        problems
     }
-    
+
     /// Get widget by name
     #[allow(dead_code)]
     pub fn get_widget(&self, name: &str) -> Option<&WidgetConfig> {
@@ -151,7 +156,7 @@ impl LayoutConfig {
     /// Check if a widget exists and is visible
     #[allow(dead_code)]
     pub fn is_widget_visible(&self, name: &str) -> bool {
-        self.get_widget(name).map_or(false, |w| w.visible)
+        self.get_widget(name).is_some_and(|w| w.visible)
     }
     
     /// Get all visible widgets
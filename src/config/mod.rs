@@ -6,6 +6,8 @@
 pub mod layout_config;
 pub mod defaults;
 pub mod app_config;
+pub mod check;
+pub mod paths;
 
 pub use layout_config::LayoutConfig;
 pub use app_config::{Config, PersistentSettings};
@@ -0,0 +1,394 @@
+//! Where the program keeps its files
+//!
+//! Following the XDG base directory spec, the settings, layout, keymap and
+//! scripts live under `$XDG_CONFIG_HOME/lofiturtle`, the database under
+//! `$XDG_DATA_HOME/lofiturtle`, the log under `$XDG_STATE_HOME/lofiturtle`
+//! and downloaded cover art under `$XDG_CACHE_HOME/lofiturtle`, with the
+//! spec's defaults under the home directory. macOS keeps them in
+//! `~/Library`, and Windows in `%APPDATA%` and `%LOCALAPPDATA%`, unless the
+//! XDG variables are set.
+//!
+//! In portable mode all of them live in one directory instead, next to the
+//! executable unless another is given, so the player can run from a USB stick
+//! without leaving anything behind on the machine. Portable mode is turned on
+//! with `--portable`, or by a [`PORTABLE_MARKER`] file next to the
+//! executable. The marker may name the directory to use, relative to the
+//! executable; an empty one means the executable's own.
+//!
+//! With `--profile <name>`, each of these directories gets a
+//! `profiles/<name>` subdirectory holding a separate set of files, so several
+//! libraries can be kept apart. Without one, the files are those of the
+//! default profile, directly in the directories above.
+//!
+//! Older versions kept everything in the working directory. The first run
+//! that finds those files copies them to where the default profile keeps them.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File next to the executable that turns portable mode on
+pub const PORTABLE_MARKER: &str = "lofiturtle.portable";
+
+/// Directory of the program's own within each base directory
+const APP_DIR: &str = "lofiturtle";
+
+/// Directory within the program's that holds the profiles
+const PROFILES_DIR: &str = "profiles";
+
+/// Files older versions kept in the working directory, and where they go
+const LEGACY_FILES: &[(&str, Location)] = &[
+    ("music_library.db", Location::Data),
+    ("music_library.scan-checkpoint", Location::Data),
+    ("lofiturtle_settings.json", Location::Config),
+    ("layout.toml", Location::Config),
+    ("keymap.toml", Location::Config),
+    ("scripts", Location::Config),
+];
+
+/// Files only an older version's working directory holds, one of which must
+/// be there before anything is copied from it
+const LEGACY_SIGNS: &[&str] = &["music_library.db", "lofiturtle_settings.json"];
+
+/// Files SQLite keeps next to a database in write-ahead logging mode, which
+/// can hold changes not yet written to the database itself
+const SQLITE_COMPANIONS: &[&str] = &["-wal", "-shm"];
+
+/// Left in the data directory once files were migrated, so it happens once
+const MIGRATED_MARKER: &str = "migrated-from-working-dir";
+
+static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// What a file is for, which decides the directory it goes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Written by the user: settings, layout, keymap and scripts
+    Config,
+    /// Kept by the program and worth backing up: the library database
+    Data,
+    /// Kept by the program but disposable: the log
+    State,
+    /// Downloaded, and downloaded again if lost: cover art
+    Cache,
+}
+
+/// A file copied from the working directory to where it belongs now
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Pick the portable directory and the profile, once at startup and before
+/// any file is read, from the `--portable` flag, `Some(None)` for a bare
+/// flag, and the `--profile` option
+///
+/// Creates the configuration and data directories, returning the error if
+/// they cannot be.
+pub fn init(flag: Option<Option<PathBuf>>, profile: Option<String>) -> io::Result<()> {
+    let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let _ = PORTABLE_DIR.set(portable_dir_from(flag, exe_dir.as_deref()));
+    let _ = PROFILE.set(profile);
+
+    for location in [Location::Config, Location::Data] {
+        let dir = dir_for(location);
+        fs::create_dir_all(&dir).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e)))?;
+    }
+    Ok(())
+}
+
+/// Check a profile name can be used as a directory name
+pub fn parse_profile(name: &str) -> Result<String, String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err("profile names may only contain letters, digits, '-', '_' and '.', and cannot start with '.'".to_string())
+    }
+}
+
+/// The profile files are kept for, if not the default one
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().and_then(Option::as_deref)
+}
+
+/// The directory everything is kept in, in portable mode
+pub fn portable_dir() -> Option<&'static Path> {
+    PORTABLE_DIR.get().and_then(Option::as_deref)
+}
+
+/// Where the file `name` goes unless another path is given
+pub fn default_path(location: Location, name: &str) -> PathBuf {
+    dir_for(location).join(name)
+}
+
+/// The directory files for `location` go in, for the profile in use
+///
+/// Falls back to the working directory if there is no home directory to
+/// put them under.
+fn dir_for(location: Location) -> PathBuf {
+    let dir = match portable_dir() {
+        Some(dir) => Some(dir.to_path_buf()),
+        None => app_dir(location, |var| std::env::var_os(var)),
+    };
+    match (dir, profile()) {
+        (Some(dir), Some(profile)) => dir.join(PROFILES_DIR).join(profile),
+        (Some(dir), None) => dir,
+        (None, _) => PathBuf::new(),
+    }
+}
+
+/// The path to open for a file given as `path`, or [`default_path`] if none
+/// was: relative paths are taken from the portable directory in portable
+/// mode, and from the working directory otherwise
+pub fn resolve(path: Option<&Path>, location: Location, default_name: &str) -> PathBuf {
+    match (path, portable_dir()) {
+        (Some(path), Some(dir)) if path.is_relative() => dir.join(path),
+        (Some(path), _) => path.to_path_buf(),
+        (None, _) => default_path(location, default_name),
+    }
+}
+
+/// Copy the files an older version left in the working directory to where
+/// the default profile keeps them, the first time any are found
+///
+/// Only a working directory holding the old library or settings counts as
+/// one an older version ran in, so a `layout.toml` or `scripts` folder of
+/// another program is never taken for ours.
+///
+/// Files already at their new place are left alone, and the old ones are
+/// kept in case another version still reads them.
+pub fn migrate_legacy_files() -> io::Result<Vec<Migration>> {
+    if portable_dir().is_some() || profile().is_some() {
+        return Ok(Vec::new());
+    }
+    let (Ok(working_dir), Some(data_dir)) = (std::env::current_dir(), app_dir(Location::Data, |var| std::env::var_os(var))) else {
+        return Ok(Vec::new());
+    };
+    migrate_from(&working_dir, &data_dir.join(MIGRATED_MARKER), |location, name| {
+        app_dir(location, |var| std::env::var_os(var)).map(|dir| dir.join(name))
+    })
+}
+
+/// Copy the legacy files in `dir` to the paths `target` gives them, unless
+/// the `marker` file says this was done before
+fn migrate_from(dir: &Path, marker: &Path, target: impl Fn(Location, &str) -> Option<PathBuf>) -> io::Result<Vec<Migration>> {
+    if marker.exists() || !LEGACY_SIGNS.iter().any(|name| dir.join(name).exists()) {
+        return Ok(Vec::new());
+    }
+
+    let mut migrated = Vec::new();
+    for &(name, location) in LEGACY_FILES {
+        let from = dir.join(name);
+        let Some(to) = target(location, name) else { continue };
+        if !from.exists() || to.exists() || from == to {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        copy_all(&from, &to)?;
+        let companions = if name.ends_with(".db") { copy_companions(&from, &to)? } else { Vec::new() };
+        migrated.push(Migration { from, to });
+        migrated.extend(companions);
+    }
+
+    if !migrated.is_empty() {
+        if let Some(parent) = marker.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listing: String = migrated.iter().map(|migration| format!("{}\n", migration.from.display())).collect();
+        fs::write(marker, listing)?;
+    }
+    Ok(migrated)
+}
+
+/// Copy the write-ahead log of the database at `from` along with it, so
+/// no change is lost, and drop any stale one at `to`
+fn copy_companions(from: &Path, to: &Path) -> io::Result<Vec<Migration>> {
+    let with_suffix = |path: &Path, suffix: &str| {
+        let mut path = path.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    };
+
+    let mut copied = Vec::new();
+    for suffix in SQLITE_COMPANIONS {
+        let (from, to) = (with_suffix(from, suffix), with_suffix(to, suffix));
+        if from.exists() {
+            fs::copy(&from, &to)?;
+            copied.push(Migration { from, to });
+        } else if to.exists() {
+            fs::remove_file(&to)?;
+        }
+    }
+    Ok(copied)
+}
+
+/// Copy a file, or a directory with everything in it
+fn copy_all(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_all(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// The program's directory for `location`, looking environment variables up
+/// with `var`
+fn app_dir(location: Location, var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let absolute = |name: &str| var(name).map(PathBuf::from).filter(|dir| dir.is_absolute());
+    let (xdg_var, xdg_default) = match location {
+        Location::Config => ("XDG_CONFIG_HOME", ".config"),
+        Location::Data => ("XDG_DATA_HOME", ".local/share"),
+        Location::State => ("XDG_STATE_HOME", ".local/state"),
+        Location::Cache => ("XDG_CACHE_HOME", ".cache"),
+    };
+
+    let base = if let Some(dir) = absolute(xdg_var) {
+        dir
+    } else if cfg!(windows) {
+        absolute(if location == Location::Config { "APPDATA" } else { "LOCALAPPDATA" })?
+    } else if cfg!(target_os = "macos") {
+        let library = absolute("HOME")?.join("Library");
+        match location {
+            Location::State => library.join("Logs"),
+            Location::Cache => library.join("Caches"),
+            _ => library.join("Application Support"),
+        }
+    } else {
+        absolute("HOME")?.join(xdg_default)
+    };
+    Some(base.join(APP_DIR))
+}
+
+/// The directory given with the flag, the executable's for a bare flag, or
+/// the one a marker next to the executable names
+fn portable_dir_from(flag: Option<Option<PathBuf>>, exe_dir: Option<&Path>) -> Option<PathBuf> {
+    match flag {
+        Some(Some(dir)) => Some(dir),
+        Some(None) => exe_dir.map(Path::to_path_buf),
+        None => {
+            let exe_dir = exe_dir?;
+            let marker = fs::read_to_string(exe_dir.join(PORTABLE_MARKER)).ok()?;
+            match marker.trim() {
+                "" => Some(exe_dir.to_path_buf()),
+                named => Some(exe_dir.join(named)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_dir_from_flag_or_marker() {
+        let exe_dir = tempfile::tempdir().unwrap();
+        let exe_dir = exe_dir.path();
+
+        assert_eq!(portable_dir_from(None, Some(exe_dir)), None);
+        assert_eq!(portable_dir_from(Some(None), Some(exe_dir)), Some(exe_dir.to_path_buf()));
+        assert_eq!(
+            portable_dir_from(Some(Some(PathBuf::from("/media/usb/lofi"))), Some(exe_dir)),
+            Some(PathBuf::from("/media/usb/lofi"))
+        );
+
+        fs::write(exe_dir.join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(portable_dir_from(None, Some(exe_dir)), Some(exe_dir.to_path_buf()));
+        fs::write(exe_dir.join(PORTABLE_MARKER), "data\n").unwrap();
+        assert_eq!(portable_dir_from(None, Some(exe_dir)), Some(exe_dir.join("data")));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_app_dir_follows_xdg() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| OsString::from(value))
+        };
+
+        let home = env(&[("HOME", "/home/turtle")]);
+        assert_eq!(app_dir(Location::Config, home), Some(PathBuf::from("/home/turtle/.config/lofiturtle")));
+        assert_eq!(app_dir(Location::Data, home), Some(PathBuf::from("/home/turtle/.local/share/lofiturtle")));
+        assert_eq!(app_dir(Location::State, home), Some(PathBuf::from("/home/turtle/.local/state/lofiturtle")));
+        assert_eq!(app_dir(Location::Cache, home), Some(PathBuf::from("/home/turtle/.cache/lofiturtle")));
+
+        // Relative XDG directories are ignored, as the spec says
+        let xdg = env(&[("HOME", "/home/turtle"), ("XDG_DATA_HOME", "/data"), ("XDG_CONFIG_HOME", "conf")]);
+        assert_eq!(app_dir(Location::Data, xdg), Some(PathBuf::from("/data/lofiturtle")));
+        assert_eq!(app_dir(Location::Config, xdg), Some(PathBuf::from("/home/turtle/.config/lofiturtle")));
+
+        assert_eq!(app_dir(Location::Config, env(&[])), None);
+    }
+
+    #[test]
+    fn test_profile_names() {
+        assert_eq!(parse_profile("lofi"), Ok("lofi".to_string()));
+        assert!(parse_profile("classical-2.0_live").is_ok());
+        for name in ["", ".", "..", ".hidden", "a/b", "a\\b", "with space"] {
+            assert!(parse_profile(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_migration_copies_once() {
+        let (old, new) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        fs::write(old.path().join("music_library.db"), "library").unwrap();
+        fs::write(old.path().join("music_library.db-wal"), "changes").unwrap();
+        fs::write(old.path().join("layout.toml"), "old layout").unwrap();
+        fs::create_dir(old.path().join("scripts")).unwrap();
+        fs::write(old.path().join("scripts").join("notify.rhai"), "script").unwrap();
+
+        // A layout already in place is kept
+        let target = |location: Location, name: &str| {
+            let dir = if location == Location::Data { "data" } else { "config" };
+            Some(new.path().join(dir).join(name))
+        };
+        fs::create_dir(new.path().join("config")).unwrap();
+        fs::write(new.path().join("config").join("layout.toml"), "new layout").unwrap();
+
+        let marker = new.path().join("data").join(MIGRATED_MARKER);
+        let migrated = migrate_from(old.path(), &marker, target).unwrap();
+        let names: Vec<_> = migrated.iter().map(|migration| migration.from.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, ["music_library.db", "music_library.db-wal", "scripts"]);
+
+        // The database keeps the changes still in its log
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(new.path().join("data").join("music_library.db")), "library");
+        assert_eq!(read(new.path().join("data").join("music_library.db-wal")), "changes");
+        assert_eq!(read(new.path().join("config").join("layout.toml")), "new layout");
+        assert_eq!(read(new.path().join("config").join("scripts").join("notify.rhai")), "script");
+        assert!(old.path().join("music_library.db").exists());
+
+        // Once done, it is not done again
+        fs::remove_file(new.path().join("data").join("music_library.db")).unwrap();
+        assert!(migrate_from(old.path(), &marker, target).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migration_needs_old_library_or_settings() {
+        let (old, new) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        fs::write(old.path().join("layout.toml"), "someone else's layout").unwrap();
+        fs::create_dir(old.path().join("scripts")).unwrap();
+
+        let target = |_: Location, name: &str| Some(new.path().join(name));
+        let marker = new.path().join(MIGRATED_MARKER);
+        assert!(migrate_from(old.path(), &marker, target).unwrap().is_empty());
+        assert!(!new.path().join("layout.toml").exists());
+
+        fs::write(old.path().join("lofiturtle_settings.json"), "{}").unwrap();
+        let migrated = migrate_from(old.path(), &marker, target).unwrap();
+        let names: Vec<_> = migrated.iter().map(|migration| migration.from.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, ["lofiturtle_settings.json", "layout.toml", "scripts"]);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::domain::value_objects::{Duration, SongId};
+use crate::shared::errors::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+
+/// A named position in a song, for finding a spot in a long mix again
+///
+/// A song has at most one bookmark at each position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    song_id: SongId,
+    position: Duration,
+    name: String,
+}
+
+impl Bookmark {
+    /// Create a bookmark with validation
+    pub fn new(song_id: SongId, position: Duration, name: String) -> DomainResult<Self> {
+        // Business rule: Bookmark name cannot be empty
+        if name.trim().is_empty() {
+            return Err(DomainError::InvalidBookmarkName("Name cannot be empty".to_string()));
+        }
+
+        // Business rule: Bookmark name cannot be too long
+        if name.len() > 100 {
+            return Err(DomainError::InvalidBookmarkName("Name cannot exceed 100 characters".to_string()));
+        }
+
+        Ok(Self { song_id, position, name: name.trim().to_string() })
+    }
+
+    /// Get the song the bookmark is in
+    pub fn song_id(&self) -> &SongId {
+        &self.song_id
+    }
+
+    /// Get how far into the song the bookmark is
+    pub fn position(&self) -> &Duration {
+        &self.position
+    }
+
+    /// Get name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmark_name_validation() {
+        let song_id = SongId::from_string("song".to_string());
+        let bookmark = Bookmark::new(song_id.clone(), Duration::from_seconds(754), "  Drop  ".to_string()).unwrap();
+        assert_eq!(bookmark.name(), "Drop");
+        assert_eq!(bookmark.position().total_seconds(), 754);
+
+        assert!(Bookmark::new(song_id.clone(), Duration::from_seconds(0), " ".to_string()).is_err());
+        assert!(Bookmark::new(song_id, Duration::from_seconds(0), "x".repeat(101)).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::domain::value_objects::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tracks shorter than this never count as a listen
+const MIN_TRACK_SECONDS: u64 = 30;
+
+/// Playing this long always counts as a listen, however long the track
+const MAX_REQUIRED_SECONDS: u64 = 240;
+
+/// A track the user listened to, kept as listening history
+///
+/// Listens copy the track's metadata instead of referencing the song, so the
+/// history survives songs being retagged, moved or deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Listen {
+    track_path: String,
+    title: String,
+    artist: String,
+    album: String,
+    duration: Duration,
+    played: Duration,
+    listened_at: DateTime<Utc>,
+}
+
+impl Listen {
+    /// Create a listen that started at `listened_at`
+    pub fn new(
+        track_path: String,
+        title: String,
+        artist: String,
+        album: String,
+        duration: Duration,
+        played: Duration,
+        listened_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            track_path,
+            title,
+            artist,
+            album,
+            duration,
+            played,
+            listened_at,
+        }
+    }
+
+    /// Whether playing a track for `played` out of `duration` counts as a listen
+    ///
+    /// Follows the common scrobbling rule: the track is longer than 30 seconds
+    /// and was played for half its length or four minutes, whichever is less.
+    pub fn qualifies(duration: Duration, played: Duration) -> bool {
+        let required = (duration.total_seconds() / 2).min(MAX_REQUIRED_SECONDS);
+        duration.total_seconds() > MIN_TRACK_SECONDS && played.total_seconds() >= required
+    }
+
+    /// Get the path of the file that was played
+    pub fn track_path(&self) -> &str {
+        &self.track_path
+    }
+
+    /// Get title
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Get artist
+    pub fn artist(&self) -> &str {
+        &self.artist
+    }
+
+    /// Get album
+    pub fn album(&self) -> &str {
+        &self.album
+    }
+
+    /// Get the track's length
+    pub fn duration(&self) -> &Duration {
+        &self.duration
+    }
+
+    /// Get how long the track was actually played
+    pub fn played(&self) -> &Duration {
+        &self.played
+    }
+
+    /// Get when playback started
+    pub fn listened_at(&self) -> DateTime<Utc> {
+        self.listened_at
+    }
+}
+
+/// A listen waiting to be submitted to a scrobbling service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingScrobble {
+    pub listen_id: i64,
+    pub listen: Listen,
+    /// Failed submission attempts so far
+    pub attempts: u32,
+}
+
+/// Totals over the listens in a period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListeningSummary {
+    pub listens: usize,
+    /// Time spent actually playing, not the tracks' full length
+    pub played: Duration,
+}
+
+impl Default for ListeningSummary {
+    fn default() -> Self {
+        Self {
+            listens: 0,
+            played: Duration::from_seconds(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qualifies() {
+        let seconds = Duration::from_seconds;
+
+        assert!(Listen::qualifies(seconds(200), seconds(100)));
+        assert!(!Listen::qualifies(seconds(200), seconds(99)));
+        // Long tracks only need four minutes
+        assert!(Listen::qualifies(seconds(3600), seconds(240)));
+        // Very short tracks never count
+        assert!(!Listen::qualifies(seconds(30), seconds(30)));
+    }
+}
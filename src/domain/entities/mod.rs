@@ -1,10 +1,14 @@
-/// Domain entities module
-/// 
-/// Contains the core business entities that represent the main concepts
-/// in the music player domain.
+//! Domain entities module
+//!
+//! Contains the core business entities that represent the main concepts
+//! in the music player domain.
 
 pub mod song;
 pub mod playlist;
+pub mod listen;
+pub mod bookmark;
 
-pub use song::Song;
-pub use playlist::Playlist;
+pub use song::{ImportedStats, Song, SongStats, SortKeys};
+pub use playlist::{Playlist, PlaylistSetOperation};
+pub use listen::{Listen, ListeningSummary, PendingScrobble};
+pub use bookmark::Bookmark;
@@ -2,6 +2,7 @@ use crate::domain::value_objects::{PlaylistId, SongId};
 use crate::shared::errors::{DomainError, DomainResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Playlist entity with business rules
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -262,6 +263,40 @@ impl Default for PlaylistBuilder {
     }
 }
 
+/// Ways of combining the songs of two playlists into a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistSetOperation {
+    /// Songs in either playlist
+    Merge,
+    /// Songs in both playlists
+    Intersect,
+    /// Songs in the first playlist but not the second
+    Subtract,
+}
+
+impl PlaylistSetOperation {
+    /// Combine the songs of two playlists, keeping the order of the first
+    /// and, when merging, following it with the songs only the second has
+    pub fn apply(self, first: &[SongId], second: &[SongId]) -> Vec<SongId> {
+        let in_second: HashSet<&SongId> = second.iter().collect();
+        match self {
+            PlaylistSetOperation::Merge => {
+                let in_first: HashSet<&SongId> = first.iter().collect();
+                first.iter()
+                    .chain(second.iter().filter(|id| !in_first.contains(id)))
+                    .cloned()
+                    .collect()
+            }
+            PlaylistSetOperation::Intersect => {
+                first.iter().filter(|id| in_second.contains(id)).cloned().collect()
+            }
+            PlaylistSetOperation::Subtract => {
+                first.iter().filter(|id| !in_second.contains(id)).cloned().collect()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,4 +358,18 @@ mod tests {
         assert_eq!(playlist.name(), "Built Playlist");
         assert!(playlist.contains_song(&song_id));
     }
+
+    #[test]
+    fn test_playlist_set_operations() {
+        let ids = |paths: &[&str]| -> Vec<SongId> {
+            paths.iter().map(|path| SongId::from_path(&FilePath::new(path).unwrap())).collect()
+        };
+        let first = ids(&["/a.mp3", "/b.mp3", "/c.mp3"]);
+        let second = ids(&["/d.mp3", "/c.mp3", "/a.mp3"]);
+
+        assert_eq!(PlaylistSetOperation::Merge.apply(&first, &second), ids(&["/a.mp3", "/b.mp3", "/c.mp3", "/d.mp3"]));
+        assert_eq!(PlaylistSetOperation::Intersect.apply(&first, &second), ids(&["/a.mp3", "/c.mp3"]));
+        assert_eq!(PlaylistSetOperation::Subtract.apply(&first, &second), ids(&["/b.mp3"]));
+        assert!(PlaylistSetOperation::Subtract.apply(&first, &first).is_empty());
+    }
 }
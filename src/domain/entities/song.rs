@@ -1,7 +1,11 @@
 use crate::domain::value_objects::{SongId, Duration, FilePath};
 use crate::shared::errors::DomainError;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Songs added this many days ago or less are favoured in mixes
+const FRESH_DAYS: i64 = 30;
+
 /// Core Song entity representing a music track in the domain
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Song {
@@ -11,6 +15,8 @@ pub struct Song {
     artist: String,
     album: String,
     duration: Duration,
+    #[serde(default)]
+    genre: Option<String>,
 }
 
 impl Song {
@@ -37,9 +43,16 @@ impl Song {
             artist: artist.trim().to_string(),
             album: album.trim().to_string(),
             duration,
+            genre: None,
         })
     }
 
+    /// Set the genre; a blank one counts as none
+    pub fn with_genre(mut self, genre: Option<String>) -> Self {
+        self.genre = genre.map(|genre| genre.trim().to_string()).filter(|genre| !genre.is_empty());
+        self
+    }
+
     /// Get song ID
     pub fn id(&self) -> &SongId {
         &self.id
@@ -78,6 +91,11 @@ impl Song {
         &self.duration
     }
 
+    /// Get genre, if the song's tags name one
+    pub fn genre(&self) -> Option<&str> {
+        self.genre.as_deref()
+    }
+
     /// Get display name for UI (business rule: format as "Title - Artist")
     #[allow(dead_code)]
     pub fn display_name(&self) -> String {
@@ -110,6 +128,54 @@ impl Song {
     }
 }
 
+/// What a song's artist and album sort by, without the leading articles
+/// the settings ignore
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKeys {
+    pub artist: String,
+    pub album: String,
+}
+
+/// A song with how it has been listened to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongStats {
+    pub song: Song,
+    pub play_count: u32,
+    pub last_played_at: Option<DateTime<Utc>>,
+    pub added_at: DateTime<Utc>,
+    /// Stars from one to five, if the song was rated
+    pub rating: Option<u8>,
+}
+
+impl SongStats {
+    /// How strongly a mix made at `now` should favour this song
+    ///
+    /// Songs played often or rated highly count as favourites and songs
+    /// added lately get a boost, while songs heard in the last few days rest
+    /// so that mixes do not keep repeating them.
+    pub fn mix_weight(&self, now: DateTime<Utc>) -> f64 {
+        // Three stars is taken as no opinion
+        let rated = self.rating.map_or(1.0, |stars| f64::from(stars) / 3.0);
+        let favourite = (1.0 + f64::from(self.play_count).ln_1p()) * rated;
+        let fresh = if now - self.added_at <= chrono::Duration::days(FRESH_DAYS) { 1.5 } else { 1.0 };
+        let rested = match self.last_played_at.map(|played| now - played) {
+            Some(since) if since < chrono::Duration::days(1) => 0.1,
+            Some(since) if since < chrono::Duration::days(7) => 0.5,
+            _ => 1.0,
+        };
+        favourite * fresh * rested
+    }
+}
+
+/// How a song was listened to in another player, brought over on import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportedStats {
+    pub play_count: u32,
+    pub last_played_at: Option<DateTime<Utc>>,
+    /// Stars from one to five
+    pub rating: Option<u8>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +232,36 @@ mod tests {
         assert_eq!(song.artist(), "Unknown Artist");
         assert_eq!(song.display_name(), "Test Song");
     }
+
+    #[test]
+    fn test_mix_weight() {
+        let now = Utc::now();
+        let song = Song::new(
+            FilePath::new("/music/song.mp3").unwrap(),
+            "Test Song".to_string(),
+            "Test Artist".to_string(),
+            "Test Album".to_string(),
+            Duration::from_seconds(180),
+        ).unwrap();
+        let stats = |play_count, last_played_days: Option<i64>, added_days| SongStats {
+            song: song.clone(),
+            play_count,
+            last_played_at: last_played_days.map(|days| now - chrono::Duration::days(days)),
+            added_at: now - chrono::Duration::days(added_days),
+            rating: None,
+        };
+
+        let plain = stats(0, None, 365).mix_weight(now);
+        assert_eq!(plain, 1.0);
+        // Favourites and new songs come up more often
+        assert!(stats(20, Some(30), 365).mix_weight(now) > plain);
+        assert!(stats(0, None, 3).mix_weight(now) > plain);
+        // and so do songs rated above three stars
+        let rated = |rating| SongStats { rating: Some(rating), ..stats(0, None, 365) }.mix_weight(now);
+        assert!(rated(5) > plain && rated(3) == plain && rated(1) < plain);
+        // Songs heard lately rest, the more so the more recent
+        let yesterday = stats(20, Some(0), 365).mix_weight(now);
+        let last_week = stats(20, Some(3), 365).mix_weight(now);
+        assert!(yesterday < last_week && last_week < stats(20, Some(30), 365).mix_weight(now));
+    }
 }
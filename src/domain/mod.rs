@@ -0,0 +1,8 @@
+//! Domain layer
+//!
+//! Contains the core entities, value objects and repository interfaces.
+//! This layer has no knowledge of storage or presentation concerns.
+
+pub mod entities;
+pub mod repositories;
+pub mod value_objects;
@@ -1,7 +1,9 @@
-use crate::domain::entities::{Song, Playlist};
-use crate::domain::value_objects::{SongId, PlaylistId, FilePath};
+use crate::domain::entities::{ImportedStats, Song, SongStats, SortKeys, Playlist, Listen, ListeningSummary, PendingScrobble, Bookmark};
+use crate::domain::value_objects::{SongId, PlaylistId, FilePath, Duration, Trim};
 use crate::shared::errors::ApplicationError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 /// Repository interface for Song entities (Dependency Inversion Principle)
 #[async_trait]
@@ -18,18 +20,87 @@ pub trait SongRepository: Send + Sync {
     /// Get all songs
     async fn find_all(&self) -> Result<Vec<Song>, ApplicationError>;
     
+    /// Get up to `limit` of the songs matching the lowered search `query`,
+    /// or of all songs if it is empty, in the order of `find_all` and
+    /// skipping the first `offset`
+    async fn find_range(&self, query: &str, offset: usize, limit: usize) -> Result<Vec<Song>, ApplicationError>;
+    
+    /// Get the position of the song at `path` among the songs `find_range`
+    /// reads for `query`, if it is one of them
+    async fn find_position(&self, query: &str, path: &FilePath) -> Result<Option<usize>, ApplicationError>;
+    
+    /// Count the songs `find_range` reads for `query`
+    async fn count(&self, query: &str) -> Result<usize, ApplicationError>;
+    
     /// Search songs by query (title, artist, album)
     async fn search(&self, query: &str) -> Result<Vec<Song>, ApplicationError>;
     
     /// Check if song exists by path
+    #[allow(dead_code)]
     async fn exists_by_path(&self, path: &FilePath) -> Result<bool, ApplicationError>;
     
     /// Delete song by ID
     async fn delete(&self, id: &SongId) -> Result<(), ApplicationError>;
     
-    /// Get songs by IDs (for playlist loading)
+    /// Get the songs with any of the IDs, leaving out IDs not found
     async fn find_by_ids(&self, ids: &[SongId]) -> Result<Vec<Song>, ApplicationError>;
     
+    /// Get songs added to the library at or after `since`, newest first
+    async fn find_added_since(&self, since: DateTime<Utc>) -> Result<Vec<Song>, ApplicationError>;
+    
+    /// Point a song at a new file path, keeping its play history and
+    /// playlist entries
+    ///
+    /// Song IDs are derived from the path, so this also changes the ID. The
+    /// relocated song is returned.
+    async fn relocate(&self, id: &SongId, new_path: &FilePath) -> Result<Song, ApplicationError>;
+    
+    /// Count a play of the song and remember when it happened
+    async fn record_play(&self, id: &SongId) -> Result<(), ApplicationError>;
+    
+    /// Mark the song as loved, or no longer loved
+    async fn set_loved(&self, id: &SongId, loved: bool) -> Result<(), ApplicationError>;
+    
+    /// Get the loved songs
+    async fn find_loved(&self) -> Result<Vec<Song>, ApplicationError>;
+    
+    /// Set how much is cut off the start and end of the song when it plays
+    async fn set_trim(&self, id: &SongId, trim: Trim) -> Result<(), ApplicationError>;
+    
+    /// Get how much is cut off the song when it plays, or `None` if it is
+    /// not in the library
+    async fn find_trim(&self, id: &SongId) -> Result<Option<Trim>, ApplicationError>;
+    
+    /// Merge listening stats brought over from another player, keeping the
+    /// higher play count and the later last play, and the song's own rating
+    /// if it has one
+    async fn import_stats(&self, id: &SongId, stats: &ImportedStats) -> Result<(), ApplicationError>;
+    
+    /// Get the `limit` most played songs, most played first
+    async fn find_most_played(&self, limit: usize) -> Result<Vec<Song>, ApplicationError>;
+    
+    /// Get songs that have never been played
+    async fn find_never_played(&self) -> Result<Vec<Song>, ApplicationError>;
+    
+    /// Get every song with its play statistics, ordered by ID
+    async fn find_all_with_stats(&self) -> Result<Vec<SongStats>, ApplicationError>;
+
+    /// Get the stored artist and album sort keys of every song by ID
+    async fn find_sort_keys(&self) -> Result<HashMap<SongId, SortKeys>, ApplicationError>;
+
+    /// Get the songs of an album by its artist and title, ignoring case, in
+    /// the natural order of their paths, which most albums number
+    async fn find_album(&self, artist: &str, album: &str) -> Result<Vec<Song>, ApplicationError>;
+
+    /// Get the songs of the album after the one the song belongs to, in
+    /// track order
+    ///
+    /// Albums follow each other by their stored artist and album sort keys,
+    /// so an artist's next album comes first and the last album of the
+    /// library wraps round to the first. Empty if the song is not in the
+    /// library or its album is the only one.
+    async fn find_next_album(&self, id: &SongId) -> Result<Vec<Song>, ApplicationError>;
+
     /// Clear all songs (for force rescan)
     async fn clear_all(&self) -> Result<(), ApplicationError>;
 }
@@ -39,6 +110,9 @@ pub trait SongRepository: Send + Sync {
 pub trait PlaylistRepository: Send + Sync {
     /// Save a playlist to the repository
     async fn save(&self, playlist: &Playlist) -> Result<(), ApplicationError>;
+
+    /// Add a new playlist together with its songs, all or nothing
+    async fn create_with_songs(&self, playlist: &Playlist) -> Result<(), ApplicationError>;
     
     /// Find playlist by ID
     async fn find_by_id(&self, id: &PlaylistId) -> Result<Option<Playlist>, ApplicationError>;
@@ -48,6 +122,9 @@ pub trait PlaylistRepository: Send + Sync {
     
     /// Get all playlists
     async fn find_all(&self) -> Result<Vec<Playlist>, ApplicationError>;
+
+    /// Get the playlists that contain a song, ordered by name
+    async fn find_by_song(&self, song_id: &SongId) -> Result<Vec<Playlist>, ApplicationError>;
     
     /// Delete playlist by ID
     async fn delete(&self, id: &PlaylistId) -> Result<(), ApplicationError>;
@@ -66,6 +143,9 @@ pub trait PlaylistSongRepository: Send + Sync {
         song_id: &SongId, 
         position: usize
     ) -> Result<(), ApplicationError>;
+
+    /// Append songs to the end of a playlist in order, all or nothing
+    async fn append_songs(&self, playlist_id: &PlaylistId, song_ids: &[SongId]) -> Result<(), ApplicationError>;
     
     /// Remove song from playlist
     async fn remove_song_from_playlist(
@@ -77,6 +157,9 @@ pub trait PlaylistSongRepository: Send + Sync {
     /// Get songs for a playlist in order
     async fn get_playlist_songs(&self, playlist_id: &PlaylistId) -> Result<Vec<Song>, ApplicationError>;
     
+    /// Total duration of the songs in each playlist that has any
+    async fn get_playlist_durations(&self) -> Result<Vec<(PlaylistId, Duration)>, ApplicationError>;
+
     /// Reorder songs in playlist
     async fn reorder_playlist_songs(
         &self, 
@@ -88,7 +171,58 @@ pub trait PlaylistSongRepository: Send + Sync {
     async fn clear_playlist(&self, playlist_id: &PlaylistId) -> Result<(), ApplicationError>;
 }
 
+/// Repository interface for listening history and the scrobble queue
+#[async_trait]
+pub trait ListenRepository: Send + Sync {
+    /// Save a listen, also queueing it for scrobbling when `scrobble` is set.
+    /// Returns the new listen's ID.
+    async fn save(&self, listen: &Listen, scrobble: bool) -> Result<i64, ApplicationError>;
+    
+    /// Get queued scrobbles whose next attempt is due at `now`, oldest first
+    async fn find_due_scrobbles(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<PendingScrobble>, ApplicationError>;
+    
+    /// Remove submitted listens from the scrobble queue
+    async fn remove_scrobbles(&self, listen_ids: &[i64]) -> Result<(), ApplicationError>;
+    
+    /// Record a failed submission and when to try again
+    async fn reschedule_scrobble(
+        &self,
+        listen_id: i64,
+        attempts: u32,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), ApplicationError>;
+    
+    /// Count listens waiting to be scrobbled
+    async fn count_pending_scrobbles(&self) -> Result<usize, ApplicationError>;
+    
+    /// Get the listens that started in `[from, to)`, oldest first
+    async fn find_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Listen>, ApplicationError>;
+    
+    /// Get the last `limit` listens, newest first
+    async fn find_recent(&self, limit: usize) -> Result<Vec<Listen>, ApplicationError>;
+    
+    /// Get every distinct artist with a listen that started before `before`
+    async fn find_artists_before(&self, before: DateTime<Utc>) -> Result<Vec<String>, ApplicationError>;
+    
+    /// Total up the listens that started in `[from, to)`
+    async fn summarize(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<ListeningSummary, ApplicationError>;
+}
+
+/// Repository interface for bookmarks in songs
+#[async_trait]
+pub trait BookmarkRepository: Send + Sync {
+    /// Save a bookmark, renaming the one already at its position if any
+    async fn save(&self, bookmark: &Bookmark) -> Result<(), ApplicationError>;
+    
+    /// Get a song's bookmarks, in order of position
+    async fn find_by_song(&self, song_id: &SongId) -> Result<Vec<Bookmark>, ApplicationError>;
+    
+    /// Delete the bookmark at `position` in a song
+    async fn delete(&self, song_id: &SongId, position: Duration) -> Result<(), ApplicationError>;
+}
+
 /// Unit of Work pattern for transactional operations
+#[allow(dead_code)] // Future feature: multi-repository transactions
 #[async_trait]
 pub trait UnitOfWork: Send + Sync {
     /// Begin transaction
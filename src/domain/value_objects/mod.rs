@@ -74,7 +74,7 @@ impl FilePath {
 }
 
 /// Duration value object with business rules
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct Duration(u64); // seconds
 
 impl Duration {
@@ -116,7 +116,6 @@ impl Duration {
     }
 
     /// Format as H:MM:SS for long durations
-    #[allow(dead_code)]
     pub fn format_h_mm_ss(&self) -> String {
         let hours = self.0 / 3600;
         let minutes = (self.0 % 3600) / 60;
@@ -130,6 +129,35 @@ impl Duration {
     }
 }
 
+/// How much is cut off the start and the end of a song when it plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Trim {
+    start: Duration,
+    end: Duration,
+}
+
+impl Trim {
+    pub fn new(start: Duration, end: Duration) -> Self {
+        Self { start, end }
+    }
+
+    /// Get how much of the start is skipped
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// Get how much of the end is cut
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+
+    /// Whether anything of a song lasting `duration` is left to play; songs
+    /// of unknown length always have something left
+    pub fn leaves_some_of(&self, duration: Duration) -> bool {
+        duration.total_seconds() == 0 || self.start.total_seconds() + self.end.total_seconds() < duration.total_seconds()
+    }
+}
+
 /// Volume value object with validation (0.0 to 1.0)
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Volume(f32);
@@ -12,7 +12,6 @@ pub enum LofiTurtleError {
     #[error("File system error: {0}")]
     FileSystem(#[from] std::io::Error),
 
-    #[allow(dead_code)] // Future feature: music library operations
     #[error("Music library error: {0}")]
     MusicLibrary(String),
 
@@ -28,13 +27,27 @@ pub enum LofiTurtleError {
     #[error("Music directory not found: {0}")]
     DirectoryNotFound(String),
 
-    #[allow(dead_code)] // Future feature: command validation
     #[error("Invalid command: {0}")]
     InvalidCommand(String),
 
     #[error("Channel communication error: {0}")]
     ChannelError(String),
 
+    #[error("Media controls error: {0}")]
+    MediaControls(String),
+
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("Track identification error: {0}")]
+    Identification(String),
+
+    #[error("Scrobbling error: {0}")]
+    Scrobbling(String),
+
+    #[error("Cover art error: {0}")]
+    CoverArt(String),
+
 }
 
 /// Result type alias for convenience
@@ -46,6 +59,12 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for LofiTurtleError {
     }
 }
 
+impl From<crate::shared::errors::ApplicationError> for LofiTurtleError {
+    fn from(err: crate::shared::errors::ApplicationError) -> Self {
+        LofiTurtleError::MusicLibrary(err.to_string())
+    }
+}
+
 impl From<std::sync::mpsc::SendError<crate::audio::PlayerCommand>> for LofiTurtleError {
     fn from(err: std::sync::mpsc::SendError<crate::audio::PlayerCommand>) -> Self {
         LofiTurtleError::ChannelError(format!("Failed to send player command: {}", err))
@@ -0,0 +1,203 @@
+//! Translations of user-facing text
+//!
+//! Messages are kept in [Fluent](https://projectfluent.org) files under
+//! `locales/`, one per language, and compiled into the binary. Look them up
+//! with the [`t!`](crate::t) macro:
+//!
+//! ```ignore
+//! println!("{}", t!("playlist-created", name = "Chill"));
+//! ```
+//!
+//! Messages missing from a translation fall back to English, so a
+//! translation can be completed over time.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::FluentResource;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Language messages fall back to
+const DEFAULT_LANGUAGE: &str = "en";
+
+/// Bundled translations as (language, Fluent source)
+const TRANSLATIONS: [(&str, &str); 2] = [
+    ("en", include_str!("../../locales/en.ftl")),
+    ("vi", include_str!("../../locales/vi.ftl")),
+];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Translate the message `id`
+///
+/// Use as `t!("message-id")`, or with arguments as
+/// `t!("message-id", name = value, count = 3)`.
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::i18n::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+
+pub use fluent_bundle::FluentArgs;
+
+struct Localizer {
+    /// Bundle of the selected language, unless it is the default
+    selected: Option<FluentBundle<FluentResource>>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Localizer {
+    fn new(language: &str) -> Self {
+        let selected = (language != DEFAULT_LANGUAGE).then(|| bundle(language));
+        Self {
+            selected,
+            fallback: bundle(DEFAULT_LANGUAGE),
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        self.selected
+            .iter()
+            .chain(std::iter::once(&self.fallback))
+            .find_map(|bundle| format(bundle, id, args))
+    }
+}
+
+/// Select the language from `requested`, e.g. "vi" or "vi_VN.UTF-8"
+///
+/// Without a request, or for a language without a translation, the system
+/// locale is used, then English. Only the first call has an effect.
+pub fn init(requested: Option<&str>) {
+    let language = requested
+        .and_then(supported_language)
+        .or_else(|| system_locale().as_deref().and_then(supported_language))
+        .unwrap_or(DEFAULT_LANGUAGE);
+
+    if let Some(requested) = requested.filter(|requested| supported_language(requested).is_none()) {
+        log::warn!("No translation for locale '{}', using '{}'", requested, language);
+    }
+
+    let _ = LOCALIZER.set(Localizer::new(language));
+}
+
+/// Languages with a bundled translation
+pub fn available_languages() -> impl Iterator<Item = &'static str> {
+    TRANSLATIONS.iter().map(|(language, _)| *language)
+}
+
+#[doc(hidden)]
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let localizer = LOCALIZER.get_or_init(|| {
+        let language = system_locale()
+            .as_deref()
+            .and_then(supported_language)
+            .unwrap_or(DEFAULT_LANGUAGE);
+        Localizer::new(language)
+    });
+
+    localizer.format(id, args).unwrap_or_else(|| {
+        log::warn!("Missing translation for '{}'", id);
+        id.to_string()
+    })
+}
+
+/// The bundled language matching a locale such as "vi-VN" or "vi_VN.UTF-8"
+fn supported_language(locale: &str) -> Option<&'static str> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let language: LanguageIdentifier = tag.parse().ok()?;
+    available_languages().find(|available| *available == language.language.as_str())
+}
+
+/// Locale of the environment, following the POSIX precedence
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let source = TRANSLATIONS
+        .iter()
+        .find(|(available, _)| *available == language)
+        .map(|(_, source)| *source)
+        .unwrap_or_default();
+    let langid: LanguageIdentifier = language.parse().unwrap_or_default();
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Isolation marks show up as stray characters in many terminals
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+        log::warn!("Errors in the '{}' translation: {:?}", language, errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::warn!("Duplicate messages in the '{}' translation: {:?}", language, errors);
+    }
+    bundle
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        log::warn!("Errors formatting '{}': {:?}", id, errors);
+    }
+    Some(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_language() {
+        assert_eq!(supported_language("vi"), Some("vi"));
+        assert_eq!(supported_language("vi_VN.UTF-8"), Some("vi"));
+        assert_eq!(supported_language("en-GB"), Some("en"));
+        assert_eq!(supported_language("fr_FR"), None);
+        assert_eq!(supported_language("C"), None);
+    }
+
+    #[test]
+    fn test_format_with_arguments_and_plurals() {
+        let localizer = Localizer::new("en");
+        let mut args = FluentArgs::new();
+        args.set("count", 1);
+        assert_eq!(localizer.format("tracks", Some(&args)).unwrap(), "1 track");
+        args.set("count", 3);
+        assert_eq!(localizer.format("tracks", Some(&args)).unwrap(), "3 tracks");
+    }
+
+    #[test]
+    fn test_translation_falls_back_to_english() {
+        let localizer = Localizer::new("vi");
+        assert_eq!(localizer.format("all-music", None).unwrap(), "Tất cả bài hát");
+        assert!(localizer.format("no-such-message", None).is_none());
+
+        let untranslated = Localizer::new("fr");
+        assert_eq!(untranslated.format("all-music", None).unwrap(), "All Music");
+
+        // Translations parse and only contain messages English has
+        let english = bundle(DEFAULT_LANGUAGE);
+        for (language, source) in TRANSLATIONS {
+            assert!(FluentResource::try_new(source.to_string()).is_ok(), "{} does not parse", language);
+
+            let ids = source
+                .lines()
+                .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+                .filter_map(|line| line.split_once(" ="))
+                .map(|(id, _)| id);
+            for id in ids {
+                assert!(english.has_message(id), "{} has unknown message {}", language, id);
+            }
+        }
+    }
+}
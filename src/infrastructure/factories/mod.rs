@@ -1,9 +1,8 @@
-/// Factory pattern implementations for dependency injection
-/// 
-/// Contains factories for creating and configuring various components
-/// with proper dependency injection.
+//! Factory pattern implementations for dependency injection
+//!
+//! Contains factories for creating and configuring various components
+//! with proper dependency injection.
 
 pub mod repository_factory;
 
-// RepositoryFactory available but not yet integrated with legacy UI
-// pub use repository_factory::RepositoryFactory;
+pub use repository_factory::RepositoryFactory;
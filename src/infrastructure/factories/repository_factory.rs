@@ -1,52 +1,62 @@
-use crate::domain::repositories::{SongRepository, PlaylistRepository, PlaylistSongRepository};
+use crate::domain::repositories::{
+    SongRepository, PlaylistRepository, PlaylistSongRepository, ListenRepository, BookmarkRepository,
+};
 use crate::infrastructure::repositories::{
-    SqliteSongRepository, SqlitePlaylistRepository, SqlitePlaylistSongRepository
+    checkout, SqliteSongRepository, SqlitePlaylistRepository, SqlitePlaylistSongRepository,
+    SqliteListenRepository, SqliteBookmarkRepository,
 };
+use crate::infrastructure::pool::{self, DbPool};
 use crate::shared::errors::{ApplicationError, Result};
 use rusqlite::Connection;
-use std::sync::{Arc, Mutex};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Current database schema version, stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 11;
 
 /// Factory for creating repository instances with dependency injection
 /// 
 /// This factory implements the Factory pattern and provides a centralized
 /// way to create and configure repository instances.
 pub struct RepositoryFactory {
-    connection: Arc<Mutex<Connection>>,
+    pool: DbPool,
+    /// Articles artists and albums sort without
+    sort_articles: Vec<String>,
 }
 
 impl RepositoryFactory {
-    /// Create new repository factory with database connection
-    pub fn new(database_path: &str) -> Result<Self> {
-        let connection = Connection::open(database_path)
+    /// Create new repository factory backed by a pooled database connection,
+    /// sorting artists and albums without the leading `sort_articles`
+    pub fn new<P: AsRef<Path>>(database_path: P, sort_articles: &[String]) -> Result<Self> {
+        let pool = pool::create_pool(database_path)
             .map_err(|e| ApplicationError::Repository(
                 format!("Failed to open database: {}", e)
             ))?;
 
-        let factory = Self {
-            connection: Arc::new(Mutex::new(connection)),
-        };
+        Self::with_pool(pool, sort_articles)
+    }
+
+    /// Create repository factory on top of an existing connection pool
+    pub fn with_pool(pool: DbPool, sort_articles: &[String]) -> Result<Self> {
+        let factory = Self { pool, sort_articles: sort_articles.to_vec() };
 
-        // Initialize all schemas
+        // Initialize all schemas and bring older databases up to date
         factory.initialize_schemas()?;
+        factory.migrate()?;
+        factory.create_song_repository().refresh_sort_keys()?;
 
         Ok(factory)
     }
 
     /// Create new in-memory repository factory for testing
+    #[allow(dead_code)]
     pub fn new_in_memory() -> Result<Self> {
-        let connection = Connection::open_in_memory()
+        let pool = pool::create_in_memory_pool()
             .map_err(|e| ApplicationError::Repository(
                 format!("Failed to create in-memory database: {}", e)
             ))?;
 
-        let factory = Self {
-            connection: Arc::new(Mutex::new(connection)),
-        };
-
-        // Initialize all schemas
-        factory.initialize_schemas()?;
-
-        Ok(factory)
+        Self::with_pool(pool, &[])
     }
 
     /// Initialize all database schemas
@@ -60,22 +70,38 @@ impl RepositoryFactory {
         let playlist_song_repo = self.create_playlist_song_repository();
         playlist_song_repo.initialize_schema()?;
 
+        let listen_repo = self.create_listen_repository();
+        listen_repo.initialize_schema()?;
+
+        let bookmark_repo = self.create_bookmark_repository();
+        bookmark_repo.initialize_schema()?;
+
         Ok(())
     }
 
     /// Create song repository instance
     pub fn create_song_repository(&self) -> SqliteSongRepository {
-        SqliteSongRepository::new(self.connection.clone())
+        SqliteSongRepository::new(self.pool.clone()).with_sort_articles(&self.sort_articles)
     }
 
     /// Create playlist repository instance
     pub fn create_playlist_repository(&self) -> SqlitePlaylistRepository {
-        SqlitePlaylistRepository::new(self.connection.clone())
+        SqlitePlaylistRepository::new(self.pool.clone())
     }
 
     /// Create playlist-song repository instance
     pub fn create_playlist_song_repository(&self) -> SqlitePlaylistSongRepository {
-        SqlitePlaylistSongRepository::new(self.connection.clone())
+        SqlitePlaylistSongRepository::new(self.pool.clone())
+    }
+
+    /// Create listen repository instance
+    pub fn create_listen_repository(&self) -> SqliteListenRepository {
+        SqliteListenRepository::new(self.pool.clone())
+    }
+
+    /// Create bookmark repository instance
+    pub fn create_bookmark_repository(&self) -> SqliteBookmarkRepository {
+        SqliteBookmarkRepository::new(self.pool.clone())
     }
 
     /// Create song repository as trait object for dependency injection
@@ -93,23 +119,36 @@ impl RepositoryFactory {
         Arc::new(self.create_playlist_song_repository())
     }
 
+    /// Create listen repository as trait object for dependency injection
+    pub fn create_listen_repository_arc(&self) -> Arc<dyn ListenRepository> {
+        Arc::new(self.create_listen_repository())
+    }
+
+    /// Create bookmark repository as trait object for dependency injection
+    pub fn create_bookmark_repository_arc(&self) -> Arc<dyn BookmarkRepository> {
+        Arc::new(self.create_bookmark_repository())
+    }
+
     /// Create all repositories as a bundle for convenience
     pub fn create_all_repositories(&self) -> RepositoryBundle {
         RepositoryBundle {
             song_repository: self.create_song_repository_arc(),
             playlist_repository: self.create_playlist_repository_arc(),
             playlist_song_repository: self.create_playlist_song_repository_arc(),
+            listen_repository: self.create_listen_repository_arc(),
+            bookmark_repository: self.create_bookmark_repository_arc(),
         }
     }
 
-    /// Get database connection for advanced operations
-    pub fn get_connection(&self) -> Arc<Mutex<Connection>> {
-        self.connection.clone()
+    /// Get the connection pool for advanced operations
+    #[allow(dead_code)]
+    pub fn get_pool(&self) -> DbPool {
+        self.pool.clone()
     }
 
     /// Execute database migrations if needed
     pub fn migrate(&self) -> Result<()> {
-        let conn = self.connection.lock().unwrap();
+        let conn = checkout(&self.pool)?;
         
         // Check current schema version
         let version: i32 = conn.query_row(
@@ -118,26 +157,143 @@ impl RepositoryFactory {
             |row| row.get(0)
         ).unwrap_or(0);
 
-        match version {
-            0 => {
-                // Initial schema - already created by initialize_schemas
-                conn.execute("PRAGMA user_version = 1", [])
-                    .map_err(|e| ApplicationError::Repository(
-                        format!("Failed to set schema version: {}", e)
-                    ))?;
-            }
-            // Add future migrations here
-            _ => {
-                // Schema is up to date
-            }
+        if version < 1 {
+            // Databases created by the legacy data layer lack the
+            // bookkeeping columns the repositories write to
+            Self::add_timestamp_column_if_missing(&conn, "songs", "created_at")?;
+            Self::add_timestamp_column_if_missing(&conn, "songs", "updated_at")?;
+            Self::add_timestamp_column_if_missing(&conn, "playlist_songs", "added_at")?;
+        }
+
+        if version < 2 {
+            // Play tracking for the "Most Played" and "Never Played" views
+            Self::add_column_if_missing(&conn, "songs", "play_count", "INTEGER NOT NULL DEFAULT 0")?;
+            Self::add_column_if_missing(&conn, "songs", "last_played_at", "DATETIME")?;
+        }
+
+        if version < 3 {
+            // Genres read from tags, for grouping the library
+            Self::add_column_if_missing(&conn, "songs", "genre", "TEXT")?;
+        }
+
+        if version < 4 {
+            // Loved songs, kept in step with Last.fm
+            Self::add_column_if_missing(&conn, "songs", "loved", "INTEGER NOT NULL DEFAULT 0")?;
+        }
+
+        if version < 5 {
+            // Star ratings, brought over from other players
+            Self::add_column_if_missing(&conn, "songs", "rating", "INTEGER")?;
+        }
+
+        if version < 6 {
+            // The library is sorted naturally now, through NATURAL_SORT
+            conn.execute("DROP INDEX IF EXISTS idx_songs_order", [])
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to drop index idx_songs_order: {}", e)
+                ))?;
+        }
+
+        if version < 7 {
+            // Artists and albums sort by keys that can leave out their
+            // leading article, filled in by refresh_sort_keys
+            Self::add_column_if_missing(&conn, "songs", "artist_sort", "TEXT")?;
+            Self::add_column_if_missing(&conn, "songs", "album_sort", "TEXT")?;
+        }
+
+        if version < 8 {
+            // Seconds cut off the start and end of songs when they play
+            Self::add_column_if_missing(&conn, "songs", "trim_start", "INTEGER NOT NULL DEFAULT 0")?;
+            Self::add_column_if_missing(&conn, "songs", "trim_end", "INTEGER NOT NULL DEFAULT 0")?;
+        }
+
+        if version < 9 {
+            // An index on the app's own collation cannot be read by the
+            // sqlite3 shell or other tools, which lack the collation
+            conn.execute("DROP INDEX IF EXISTS idx_songs_natural_order", [])
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to drop index idx_songs_natural_order: {}", e)
+                ))?;
+        }
+
+        if version < 10 {
+            // The sort key index used NATURAL_SORT too
+            conn.execute("DROP INDEX IF EXISTS idx_songs_sort_keys", [])
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to drop index idx_songs_sort_keys: {}", e)
+                ))?;
+        }
+
+        if version < 11 {
+            // Natural-sort keys as plain bytes, filled in by
+            // refresh_sort_keys, so the library order has indexes any tool
+            // can read
+            Self::add_column_if_missing(&conn, "songs", "title_key", "BLOB")?;
+            Self::add_column_if_missing(&conn, "songs", "artist_key", "BLOB")?;
+            Self::add_column_if_missing(&conn, "songs", "album_key", "BLOB")?;
+            conn.execute_batch(
+                "CREATE INDEX IF NOT EXISTS idx_songs_title_order ON songs(title_key, artist_key, id);
+                 CREATE INDEX IF NOT EXISTS idx_songs_artist_order ON songs(artist_key, album_key, title_key);"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to create sort key indexes: {}", e)
+            ))?;
+        }
+
+        // Add future migrations here
+
+        if version < SCHEMA_VERSION {
+            conn.execute(&format!("PRAGMA user_version = {}", SCHEMA_VERSION), [])
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to set schema version: {}", e)
+                ))?;
         }
 
         Ok(())
     }
 
+    /// Add a timestamp column to an existing table, backfilling current rows.
+    ///
+    /// SQLite cannot add a column with a non-constant default, so the column
+    /// is added as nullable and filled in afterwards.
+    fn add_timestamp_column_if_missing(conn: &Connection, table: &str, column: &str) -> Result<()> {
+        if Self::add_column_if_missing(conn, table, column, "DATETIME")? {
+            conn.execute(
+                &format!("UPDATE {table} SET {column} = CURRENT_TIMESTAMP WHERE {column} IS NULL"),
+                [],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to backfill column {}.{}: {}", table, column, e)
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Add a column to an existing table, returning whether it was missing
+    fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool> {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+            [table, column],
+            |row| row.get(0),
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to inspect table {}: {}", table, e)
+        ))?;
+
+        if exists {
+            return Ok(false);
+        }
+
+        conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), [])
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to add column {}.{}: {}", table, column, e)
+            ))?;
+
+        Ok(true)
+    }
+
     /// Perform database maintenance operations
+    #[allow(dead_code)] // Future feature: database maintenance command
     pub fn maintain(&self) -> Result<()> {
-        let conn = self.connection.lock().unwrap();
+        let conn = checkout(&self.pool)?;
         
         // Analyze tables for query optimization
         conn.execute("ANALYZE", [])
@@ -160,10 +316,13 @@ pub struct RepositoryBundle {
     pub song_repository: Arc<dyn SongRepository>,
     pub playlist_repository: Arc<dyn PlaylistRepository>,
     pub playlist_song_repository: Arc<dyn PlaylistSongRepository>,
+    pub listen_repository: Arc<dyn ListenRepository>,
+    pub bookmark_repository: Arc<dyn BookmarkRepository>,
 }
 
 impl RepositoryBundle {
     /// Create repositories from factory
+    #[allow(dead_code)]
     pub fn from_factory(factory: &RepositoryFactory) -> Self {
         factory.create_all_repositories()
     }
@@ -206,6 +365,106 @@ mod tests {
         factory.migrate().unwrap();
     }
 
+    #[test]
+    fn test_migrate_legacy_schema() {
+        let pool = pool::create_in_memory_pool().unwrap();
+        pool.get().unwrap().execute_batch(
+            "CREATE TABLE songs (
+                id TEXT PRIMARY KEY,
+                path TEXT NOT NULL UNIQUE,
+                title TEXT NOT NULL,
+                artist TEXT NOT NULL,
+                album TEXT NOT NULL,
+                duration INTEGER NOT NULL
+            );
+            INSERT INTO songs VALUES ('1', '/old/song.mp3', 'Old', 'The Artist', 'Album', 60);
+            CREATE TABLE playlist_songs (
+                playlist_id TEXT NOT NULL,
+                song_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (playlist_id, song_id)
+            );"
+        ).unwrap();
+
+        let factory = RepositoryFactory::with_pool(pool.clone(), &["The".to_string()]).unwrap();
+        let conn = pool.get().unwrap();
+        let missing: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM songs WHERE created_at IS NULL OR updated_at IS NULL",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(missing, 0);
+
+        let (play_count, artist_sort, version): (i64, String, i32) = conn.query_row(
+            "SELECT play_count, artist_sort, (SELECT user_version FROM pragma_user_version) FROM songs",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap();
+        assert_eq!(play_count, 0);
+        assert_eq!(artist_sort, "Artist");
+        assert_eq!(version, SCHEMA_VERSION);
+
+        // Tools without the app's collation can still read the database
+        let natural_indexes: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND sql LIKE '%NATURAL_SORT%'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(natural_indexes, 0);
+
+        // Legacy rows get their sort keys, and pages walk the index in order
+        let unkeyed: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM songs WHERE title_key IS NULL OR artist_key IS NULL OR album_key IS NULL",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(unkeyed, 0);
+        let plan: String = conn.query_row(
+            "EXPLAIN QUERY PLAN SELECT id FROM songs ORDER BY title_key, artist_key, id LIMIT 10",
+            [],
+            |row| row.get(3),
+        ).unwrap();
+        assert!(plan.contains("idx_songs_title_order"), "{}", plan);
+        drop(conn);
+
+        // Legacy rows remain readable through the repositories
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let songs = runtime.block_on(factory.create_song_repository().find_all()).unwrap();
+        assert_eq!(songs.len(), 1);
+    }
+
+    #[test]
+    fn test_sort_keys_refresh_when_articles_change() {
+        let pool = pool::create_in_memory_pool().unwrap();
+        let english = ["The".to_string()];
+        let factory = RepositoryFactory::with_pool(pool.clone(), &english).unwrap();
+        let song = Song::new(
+            FilePath::new("/music/roots.mp3").unwrap(),
+            "Song".to_string(),
+            "The Roots".to_string(),
+            "The Album".to_string(),
+            Duration::from_seconds(60),
+        ).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(factory.create_song_repository().save(&song)).unwrap();
+
+        let artist_sort = || -> String {
+            pool.get().unwrap().query_row("SELECT artist_sort FROM songs", [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(artist_sort(), "Roots");
+
+        // Opening with the same articles leaves the stored keys alone
+        pool.get().unwrap().execute("UPDATE songs SET artist_sort = 'Untouched'", []).unwrap();
+        RepositoryFactory::with_pool(pool.clone(), &english).unwrap();
+        assert_eq!(artist_sort(), "Untouched");
+
+        // Other articles store the keys afresh
+        RepositoryFactory::with_pool(pool.clone(), &[]).unwrap();
+        assert_eq!(artist_sort(), "The Roots");
+        let keys = runtime.block_on(factory.create_song_repository().find_sort_keys()).unwrap();
+        assert_eq!(keys[song.id()].album, "The Album");
+    }
+
     #[test]
     fn test_factory_maintenance() {
         let factory = RepositoryFactory::new_in_memory().unwrap();
@@ -0,0 +1,8 @@
+//! Infrastructure layer
+//!
+//! Contains the SQLite-backed implementations of the domain repository
+//! interfaces along with connection pooling and factories.
+
+pub mod factories;
+pub mod pool;
+pub mod repositories;
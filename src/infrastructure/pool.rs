@@ -0,0 +1,126 @@
+use crate::shared::natural_sort::natural_cmp;
+use crate::shared::search::search_text;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::Duration;
+
+/// Shared pool of SQLite connections
+pub type DbPool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A connection checked out of a [`DbPool`]
+pub type PooledConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+/// Maximum number of connections kept open against a database file
+const MAX_POOL_SIZE: u32 = 8;
+
+/// How long a connection waits on a locked database before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Prepared statements each connection keeps for reuse, enough to hold every
+/// fixed statement the repositories run
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Collation sorting names the way [`natural_cmp`] does, for `COLLATE NATURAL_SORT`
+pub const NATURAL_COLLATION: &str = "NATURAL_SORT";
+
+/// Function telling whether a song matches a lowered search query, for
+/// `MATCHES_SEARCH(title, artist, album, query)`
+pub const SEARCH_FUNCTION: &str = "MATCHES_SEARCH";
+
+/// Create a connection pool for the database at `db_path`.
+///
+/// Every connection runs in WAL mode, so readers (UI queries) proceed while a
+/// writer (a library scan) holds the write lock.
+pub fn create_pool<P: AsRef<Path>>(db_path: P) -> Result<DbPool, r2d2::Error> {
+    let manager = SqliteConnectionManager::file(db_path.as_ref()).with_init(configure_connection);
+
+    r2d2::Pool::builder()
+        .max_size(MAX_POOL_SIZE)
+        .build(manager)
+}
+
+/// Create a pool backed by a single in-memory database.
+///
+/// Each in-memory connection is its own database, so the pool is capped at one
+/// connection to keep every caller looking at the same data.
+#[allow(dead_code)] // Used by tests and in-memory factories
+pub fn create_in_memory_pool() -> Result<DbPool, r2d2::Error> {
+    let manager = SqliteConnectionManager::memory().with_init(|conn| {
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        register_functions(conn)
+    });
+
+    r2d2::Pool::builder()
+        .max_size(1)
+        .build(manager)
+}
+
+/// Per-connection pragmas applied when the pool opens a new connection
+fn configure_connection(conn: &mut Connection) -> rusqlite::Result<()> {
+    // Enable WAL mode for better concurrency and performance
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
+    // Set synchronous to NORMAL for better write performance while maintaining safety
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+    // Wait for a competing writer instead of failing immediately with SQLITE_BUSY
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+
+    register_functions(conn)
+}
+
+/// Collations and functions of the app that queries may use
+fn register_functions(conn: &mut Connection) -> rusqlite::Result<()> {
+    // Queries sorting by names that have no stored natural key use it
+    conn.create_collation(NATURAL_COLLATION, natural_cmp)?;
+
+    // Searching in the database finds just the songs a search of songs
+    // held in memory would
+    conn.create_scalar_function(
+        SEARCH_FUNCTION,
+        4,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let text = search_text(&ctx.get::<String>(0)?, &ctx.get::<String>(1)?, &ctx.get::<String>(2)?);
+            Ok(text.contains(ctx.get::<String>(3)?.as_str()))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_pool_uses_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = create_pool(dir.path().join("pool.db")).unwrap();
+
+        let conn = pool.get().unwrap();
+        let mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+    }
+
+    #[test]
+    fn test_concurrent_readers() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = create_pool(dir.path().join("pool.db")).unwrap();
+        pool.get()
+            .unwrap()
+            .execute("CREATE TABLE t (x INTEGER)", [])
+            .unwrap();
+
+        // Two connections can be checked out at the same time
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        let count: i64 = first.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+        let count2: i64 = second.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, count2);
+    }
+}
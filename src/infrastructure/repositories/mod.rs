@@ -1,11 +1,73 @@
-/// Infrastructure repositories module
-/// 
-/// Contains concrete implementations of repository interfaces defined in the domain layer.
+//! Infrastructure repositories module
+//!
+//! Contains concrete implementations of repository interfaces defined in the domain layer.
 
 pub mod sqlite_song_repository;
 pub mod sqlite_playlist_repository;
 pub mod sqlite_playlist_song_repository;
+pub mod sqlite_listen_repository;
+pub mod sqlite_bookmark_repository;
 
 pub use sqlite_song_repository::SqliteSongRepository;
 pub use sqlite_playlist_repository::SqlitePlaylistRepository;
 pub use sqlite_playlist_song_repository::SqlitePlaylistSongRepository;
+pub use sqlite_listen_repository::SqliteListenRepository;
+pub use sqlite_bookmark_repository::SqliteBookmarkRepository;
+
+use crate::infrastructure::pool::{DbPool, PooledConnection};
+use crate::shared::errors::{ApplicationError, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{Connection, Params, Result as SqliteResult};
+
+/// Run a statement through the connection's statement cache
+///
+/// Repositories run the same few statements over and over, e.g. one upsert per
+/// file during a scan, so each is only compiled once per pooled connection.
+pub(crate) fn execute_cached<P: Params>(conn: &Connection, sql: &str, params: P) -> SqliteResult<usize> {
+    conn.prepare_cached(sql)?.execute(params)
+}
+
+/// Timestamp format used by SQLite's CURRENT_TIMESTAMP, always in UTC
+pub(crate) const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub(crate) fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.format(TIMESTAMP_FORMAT).to_string()
+}
+
+pub(crate) fn parse_timestamp(value: &str, column: usize) -> SqliteResult<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT)
+        .map(|time| time.and_utc())
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+/// Check a connection out of the shared pool for a single repository operation
+pub(crate) fn checkout(pool: &DbPool) -> Result<PooledConnection> {
+    pool.get().map_err(|e| ApplicationError::Repository(
+        format!("Failed to get database connection: {}", e)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::pool::create_in_memory_pool;
+
+    #[test]
+    fn test_execute_cached_reuses_statements() {
+        let pool = create_in_memory_pool().unwrap();
+        let conn = checkout(&pool).unwrap();
+        conn.execute("CREATE TABLE t (x INTEGER)", []).unwrap();
+
+        for x in 0..3 {
+            assert_eq!(execute_cached(&conn, "INSERT INTO t (x) VALUES (?1)", [x]).unwrap(), 1);
+        }
+
+        // A cached statement still runs after the schema it was prepared
+        // against changes
+        conn.execute("ALTER TABLE t ADD COLUMN y INTEGER", []).unwrap();
+        execute_cached(&conn, "INSERT INTO t (x) VALUES (?1)", [3]).unwrap();
+
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 4);
+    }
+}
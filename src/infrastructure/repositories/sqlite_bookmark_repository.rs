@@ -0,0 +1,179 @@
+use crate::domain::entities::Bookmark;
+use crate::domain::repositories::BookmarkRepository;
+use crate::domain::value_objects::{Duration, SongId};
+use crate::shared::errors::{ApplicationError, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Result as SqliteResult};
+use crate::infrastructure::pool::DbPool;
+use crate::infrastructure::repositories::{checkout, execute_cached};
+use tokio::task;
+
+/// SQLite implementation of BookmarkRepository
+pub struct SqliteBookmarkRepository {
+    pool: DbPool,
+}
+
+impl SqliteBookmarkRepository {
+    /// Create new SQLite bookmark repository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Initialize database schema
+    pub fn initialize_schema(&self) -> Result<()> {
+        let conn = checkout(&self.pool)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bookmarks (
+                song_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (song_id, position),
+                FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+            )",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create bookmarks table: {}", e)
+        ))?;
+
+        Ok(())
+    }
+
+    /// Convert a `song_id, position, name` row to a Bookmark
+    fn row_to_bookmark(row: &rusqlite::Row) -> SqliteResult<Bookmark> {
+        let position: i64 = row.get(1)?;
+        let name: String = row.get(2)?;
+
+        Bookmark::new(
+            SongId::from_string(row.get(0)?),
+            Duration::from_seconds(position as u64),
+            name,
+        ).map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))
+    }
+}
+
+#[async_trait]
+impl BookmarkRepository for SqliteBookmarkRepository {
+    async fn save(&self, bookmark: &Bookmark) -> Result<()> {
+        let bookmark = bookmark.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            execute_cached(
+                &conn,
+                "INSERT INTO bookmarks (song_id, position, name) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(song_id, position) DO UPDATE SET name = excluded.name",
+                params![
+                    bookmark.song_id().as_str(),
+                    bookmark.position().total_seconds() as i64,
+                    bookmark.name(),
+                ],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to save bookmark: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_by_song(&self, song_id: &SongId) -> Result<Vec<Bookmark>> {
+        let song_id = song_id.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT song_id, position, name FROM bookmarks WHERE song_id = ?1 ORDER BY position"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let bookmarks = stmt.query_map([song_id.as_str()], Self::row_to_bookmark)
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to query bookmarks: {}", e)
+                ))?
+                .collect::<SqliteResult<Vec<_>>>()
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to read bookmark: {}", e)
+                ))?;
+
+            Ok(bookmarks)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn delete(&self, song_id: &SongId, position: Duration) -> Result<()> {
+        let song_id = song_id.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            execute_cached(
+                &conn,
+                "DELETE FROM bookmarks WHERE song_id = ?1 AND position = ?2",
+                params![song_id.as_str(), position.total_seconds() as i64],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to delete bookmark: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::pool::create_in_memory_pool;
+    use crate::infrastructure::repositories::SqliteSongRepository;
+
+    fn create_test_repository() -> SqliteBookmarkRepository {
+        let pool = create_in_memory_pool().unwrap();
+        // Bookmarks reference the songs table
+        SqliteSongRepository::new(pool.clone()).initialize_schema().unwrap();
+
+        let repo = SqliteBookmarkRepository::new(pool);
+        repo.initialize_schema().unwrap();
+        repo
+    }
+
+    fn bookmark(song: &str, seconds: u64, name: &str) -> Bookmark {
+        Bookmark::new(SongId::from_string(song.to_string()), Duration::from_seconds(seconds), name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_by_song() {
+        let repo = create_test_repository();
+        let mix = SongId::from_string("mix".to_string());
+        let conn = checkout(&repo.pool).unwrap();
+        for id in ["mix", "other"] {
+            conn.execute(
+                "INSERT INTO songs (id, path, title, artist, album, duration) VALUES (?1, ?1, 'T', 'A', 'B', 3600)",
+                [id],
+            ).unwrap();
+        }
+        drop(conn);
+
+        repo.save(&bookmark("mix", 1800, "Second half")).await.unwrap();
+        repo.save(&bookmark("mix", 95, "Intro ends")).await.unwrap();
+        repo.save(&bookmark("other", 10, "Elsewhere")).await.unwrap();
+        // Saving at the same position renames the bookmark there
+        repo.save(&bookmark("mix", 1800, "Peak")).await.unwrap();
+
+        let names = |bookmarks: Vec<Bookmark>| bookmarks.iter().map(|b| b.name().to_string()).collect::<Vec<_>>();
+        assert_eq!(names(repo.find_by_song(&mix).await.unwrap()), ["Intro ends", "Peak"]);
+
+        repo.delete(&mix, Duration::from_seconds(95)).await.unwrap();
+        assert_eq!(names(repo.find_by_song(&mix).await.unwrap()), ["Peak"]);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::domain::entities::{Listen, ListeningSummary, PendingScrobble};
+use crate::domain::repositories::ListenRepository;
+use crate::domain::value_objects::Duration;
+use crate::shared::errors::{ApplicationError, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, params_from_iter, Result as SqliteResult};
+use crate::infrastructure::pool::DbPool;
+use crate::infrastructure::repositories::{checkout, execute_cached, format_timestamp, parse_timestamp};
+use tokio::task;
+
+/// SQLite implementation of ListenRepository
+pub struct SqliteListenRepository {
+    pool: DbPool,
+}
+
+impl SqliteListenRepository {
+    /// Create new SQLite listen repository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Initialize database schema
+    pub fn initialize_schema(&self) -> Result<()> {
+        let conn = checkout(&self.pool)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS listens (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                track_path TEXT NOT NULL,
+                title TEXT NOT NULL,
+                artist TEXT NOT NULL,
+                album TEXT NOT NULL,
+                duration INTEGER NOT NULL,
+                played INTEGER NOT NULL,
+                listened_at DATETIME NOT NULL
+            )",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create listens table: {}", e)
+        ))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_listens_listened_at ON listens(listened_at)",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create listened_at index: {}", e)
+        ))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scrobble_queue (
+                listen_id INTEGER PRIMARY KEY,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at DATETIME NOT NULL,
+                FOREIGN KEY (listen_id) REFERENCES listens(id) ON DELETE CASCADE
+            )",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create scrobble_queue table: {}", e)
+        ))?;
+
+        Ok(())
+    }
+
+    /// Convert a row of `listens` columns, starting at `offset`, to a Listen
+    fn row_to_listen(row: &rusqlite::Row, offset: usize) -> SqliteResult<Listen> {
+        let duration: i64 = row.get(offset + 4)?;
+        let played: i64 = row.get(offset + 5)?;
+        let listened_at: String = row.get(offset + 6)?;
+
+        Ok(Listen::new(
+            row.get(offset)?,     // track_path
+            row.get(offset + 1)?, // title
+            row.get(offset + 2)?, // artist
+            row.get(offset + 3)?, // album
+            Duration::from_seconds(duration as u64),
+            Duration::from_seconds(played as u64),
+            parse_timestamp(&listened_at, offset + 6)?,
+        ))
+    }
+}
+
+#[async_trait]
+impl ListenRepository for SqliteListenRepository {
+    async fn save(&self, listen: &Listen, scrobble: bool) -> Result<i64> {
+        let listen = listen.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            // Start transaction so a listen is never queued twice or lost
+            let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
+                format!("Failed to start transaction: {}", e)
+            ))?;
+
+            execute_cached(
+                &tx,
+                "INSERT INTO listens (track_path, title, artist, album, duration, played, listened_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    listen.track_path(),
+                    listen.title(),
+                    listen.artist(),
+                    listen.album(),
+                    listen.duration().total_seconds() as i64,
+                    listen.played().total_seconds() as i64,
+                    format_timestamp(listen.listened_at()),
+                ],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to save listen: {}", e)
+            ))?;
+            let listen_id = tx.last_insert_rowid();
+
+            if scrobble {
+                execute_cached(
+                    &tx,
+                    "INSERT INTO scrobble_queue (listen_id, next_attempt_at) VALUES (?1, CURRENT_TIMESTAMP)",
+                    [listen_id],
+                ).map_err(|e| ApplicationError::Repository(
+                    format!("Failed to queue scrobble: {}", e)
+                ))?;
+            }
+
+            // Commit transaction
+            tx.commit().map_err(|e| ApplicationError::Repository(
+                format!("Failed to commit transaction: {}", e)
+            ))?;
+
+            Ok(listen_id)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_due_scrobbles(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<PendingScrobble>> {
+        let pool = self.pool.clone();
+        let now = format_timestamp(now);
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT q.listen_id, q.attempts,
+                        l.track_path, l.title, l.artist, l.album, l.duration, l.played, l.listened_at
+                 FROM scrobble_queue q
+                 JOIN listens l ON l.id = q.listen_id
+                 WHERE q.next_attempt_at <= ?1
+                 ORDER BY l.listened_at, l.id
+                 LIMIT ?2"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let scrobbles = stmt.query_map(params![now, limit as i64], |row| {
+                Ok(PendingScrobble {
+                    listen_id: row.get(0)?,
+                    attempts: row.get(1)?,
+                    listen: Self::row_to_listen(row, 2)?,
+                })
+            }).map_err(|e| ApplicationError::Repository(
+                format!("Failed to query due scrobbles: {}", e)
+            ))?;
+
+            scrobbles.collect::<SqliteResult<Vec<_>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read scrobble: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn remove_scrobbles(&self, listen_ids: &[i64]) -> Result<()> {
+        if listen_ids.is_empty() {
+            return Ok(());
+        }
+
+        let listen_ids = listen_ids.to_vec();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let placeholders = vec!["?"; listen_ids.len()].join(", ");
+            conn.execute(
+                &format!("DELETE FROM scrobble_queue WHERE listen_id IN ({})", placeholders),
+                params_from_iter(listen_ids.iter()),
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to remove scrobbles: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn reschedule_scrobble(
+        &self,
+        listen_id: i64,
+        attempts: u32,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let pool = self.pool.clone();
+        let next_attempt_at = format_timestamp(next_attempt_at);
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            execute_cached(
+                &conn,
+                "UPDATE scrobble_queue SET attempts = ?1, next_attempt_at = ?2 WHERE listen_id = ?3",
+                params![attempts, next_attempt_at, listen_id],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to reschedule scrobble: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn count_pending_scrobbles(&self) -> Result<usize> {
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let count: i64 = conn.query_row("SELECT COUNT(*) FROM scrobble_queue", [], |row| row.get(0))
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to count pending scrobbles: {}", e)
+                ))?;
+
+            Ok(count as usize)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Listen>> {
+        let pool = self.pool.clone();
+        let from = format_timestamp(from);
+        let to = format_timestamp(to);
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT track_path, title, artist, album, duration, played, listened_at
+                 FROM listens
+                 WHERE listened_at >= ?1 AND listened_at < ?2
+                 ORDER BY listened_at, id"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let listens = stmt.query_map(params![from, to], |row| Self::row_to_listen(row, 0))
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to query listens: {}", e)
+                ))?;
+
+            listens.collect::<SqliteResult<Vec<_>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read listen: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_recent(&self, limit: usize) -> Result<Vec<Listen>> {
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT track_path, title, artist, album, duration, played, listened_at
+                 FROM listens
+                 ORDER BY listened_at DESC, id DESC
+                 LIMIT ?1"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let listens = stmt.query_map([limit as i64], |row| Self::row_to_listen(row, 0))
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to query listens: {}", e)
+                ))?;
+
+            listens.collect::<SqliteResult<Vec<_>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read listen: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_artists_before(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        let pool = self.pool.clone();
+        let before = format_timestamp(before);
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT DISTINCT artist FROM listens WHERE listened_at < ?1 ORDER BY artist"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let artists = stmt.query_map([before], |row| row.get(0))
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to query artists: {}", e)
+                ))?;
+
+            artists.collect::<SqliteResult<Vec<String>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read artist: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn summarize(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<ListeningSummary> {
+        let pool = self.pool.clone();
+        let from = format_timestamp(from);
+        let to = format_timestamp(to);
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let (listens, played): (i64, i64) = conn.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(played), 0) FROM listens
+                 WHERE listened_at >= ?1 AND listened_at < ?2",
+                params![from, to],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to summarize listens: {}", e)
+            ))?;
+
+            Ok(ListeningSummary {
+                listens: listens as usize,
+                played: Duration::from_seconds(played as u64),
+            })
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::pool::create_in_memory_pool;
+
+    fn create_test_repository() -> SqliteListenRepository {
+        let repo = SqliteListenRepository::new(create_in_memory_pool().unwrap());
+        repo.initialize_schema().unwrap();
+        repo
+    }
+
+    fn listen(title: &str, minutes_ago: i64) -> Listen {
+        Listen::new(
+            format!("/music/{}.mp3", title),
+            title.to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(200),
+            Duration::from_seconds(150),
+            Utc::now() - chrono::Duration::minutes(minutes_ago),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_scrobble_queue() {
+        let repo = create_test_repository();
+
+        let first = repo.save(&listen("first", 10), true).await.unwrap();
+        let second = repo.save(&listen("second", 5), true).await.unwrap();
+        repo.save(&listen("unqueued", 1), false).await.unwrap();
+        assert_eq!(repo.count_pending_scrobbles().await.unwrap(), 2);
+
+        let due = repo.find_due_scrobbles(Utc::now(), 10).await.unwrap();
+        let ids: Vec<i64> = due.iter().map(|scrobble| scrobble.listen_id).collect();
+        assert_eq!(ids, [first, second]);
+        assert_eq!(due[0].listen.title(), "first");
+        assert_eq!(due[0].listen.played().total_seconds(), 150);
+
+        // A rescheduled scrobble is not due until its next attempt
+        let later = Utc::now() + chrono::Duration::minutes(5);
+        repo.reschedule_scrobble(first, 1, later).await.unwrap();
+        let due = repo.find_due_scrobbles(Utc::now(), 10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].listen_id, second);
+
+        let due = repo.find_due_scrobbles(later, 10).await.unwrap();
+        assert_eq!(due[0].attempts, 1);
+
+        repo.remove_scrobbles(&[first, second]).await.unwrap();
+        assert_eq!(repo.count_pending_scrobbles().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_summarize() {
+        let repo = create_test_repository();
+
+        repo.save(&listen("yesterday", 24 * 60), false).await.unwrap();
+        repo.save(&listen("first", 30), false).await.unwrap();
+        repo.save(&listen("second", 10), true).await.unwrap();
+
+        let now = Utc::now();
+        let summary = repo.summarize(now - chrono::Duration::hours(1), now).await.unwrap();
+        assert_eq!(summary.listens, 2);
+        assert_eq!(summary.played.total_seconds(), 300);
+
+        let empty = repo.summarize(now, now + chrono::Duration::hours(1)).await.unwrap();
+        assert_eq!(empty, ListeningSummary::default());
+
+        let listens = repo.find_between(now - chrono::Duration::hours(1), now).await.unwrap();
+        let titles: Vec<&str> = listens.iter().map(Listen::title).collect();
+        assert_eq!(titles, ["first", "second"]);
+
+        let recent = repo.find_recent(2).await.unwrap();
+        let titles: Vec<&str> = recent.iter().map(Listen::title).collect();
+        assert_eq!(titles, ["second", "first"]);
+
+        let artists = repo.find_artists_before(now - chrono::Duration::hours(1)).await.unwrap();
+        assert_eq!(artists, ["Artist"]);
+        assert!(repo.find_artists_before(now - chrono::Duration::days(2)).await.unwrap().is_empty());
+    }
+}
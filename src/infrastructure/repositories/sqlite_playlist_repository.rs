@@ -1,27 +1,29 @@
 use crate::domain::entities::Playlist;
 use crate::domain::repositories::PlaylistRepository;
-use crate::domain::value_objects::PlaylistId;
+use crate::domain::value_objects::{PlaylistId, SongId};
 use crate::shared::errors::{ApplicationError, Result};
 use async_trait::async_trait;
 use rusqlite::{params, Connection, Result as SqliteResult};
-use std::sync::{Arc, Mutex};
+use crate::infrastructure::pool::DbPool;
+use crate::infrastructure::repositories::{checkout, execute_cached};
 use tokio::task;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 /// SQLite implementation of PlaylistRepository
 pub struct SqlitePlaylistRepository {
-    connection: Arc<Mutex<Connection>>,
+    pool: DbPool,
 }
 
 impl SqlitePlaylistRepository {
     /// Create new SQLite playlist repository
-    pub fn new(connection: Arc<Mutex<Connection>>) -> Self {
-        Self { connection }
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
     }
 
     /// Initialize database schema
     pub fn initialize_schema(&self) -> Result<()> {
-        let conn = self.connection.lock().unwrap();
+        let conn = checkout(&self.pool)?;
         
         conn.execute(
             "CREATE TABLE IF NOT EXISTS playlists (
@@ -73,27 +75,91 @@ impl SqlitePlaylistRepository {
             playlist_id,
             name,
             description,
-            Vec::new(), // Empty song list - will be populated separately
+            Vec::new(), // Empty song list - populated by load_song_ids
             created_at,
             updated_at,
         ).map_err(|_| rusqlite::Error::InvalidColumnType(0, "playlist_creation".to_string(), rusqlite::types::Type::Text))?;
         
         Ok(playlist)
     }
+
+    /// Load the ordered song IDs of a playlist and attach them to the entity
+    fn load_song_ids(conn: &Connection, playlist: Playlist) -> Result<Playlist> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT song_id FROM playlist_songs WHERE playlist_id = ?1 ORDER BY position"
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to prepare statement: {}", e)
+        ))?;
+
+        let song_ids = stmt.query_map([playlist.id().as_str()], |row| row.get::<_, String>(0))
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to query playlist songs: {}", e)
+            ))?
+            .map(|id| id.map(SongId::from_string))
+            .collect::<SqliteResult<Vec<_>>>()
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to parse playlist song row: {}", e)
+            ))?;
+
+        Self::with_song_ids(playlist, song_ids)
+    }
+
+    /// Load the ordered song IDs of every playlist in a single query
+    fn load_all_song_ids(conn: &Connection) -> Result<HashMap<String, Vec<SongId>>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT playlist_id, song_id FROM playlist_songs ORDER BY playlist_id, position"
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to prepare statement: {}", e)
+        ))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to query playlist songs: {}", e)
+            ))?;
+
+        let mut song_ids: HashMap<String, Vec<SongId>> = HashMap::new();
+        for row in rows {
+            let (playlist_id, song_id) = row.map_err(|e| ApplicationError::Repository(
+                format!("Failed to parse playlist song row: {}", e)
+            ))?;
+            song_ids.entry(playlist_id).or_default().push(SongId::from_string(song_id));
+        }
+
+        Ok(song_ids)
+    }
+
+    /// Attach loaded song IDs to a playlist read without them
+    fn with_song_ids(playlist: Playlist, song_ids: Vec<SongId>) -> Result<Playlist> {
+        Playlist::from_existing(
+            playlist.id().clone(),
+            playlist.name().to_string(),
+            playlist.description().map(|d| d.to_string()),
+            song_ids,
+            playlist.created_at(),
+            playlist.updated_at(),
+        ).map_err(ApplicationError::Domain)
+    }
 }
 
 #[async_trait]
 impl PlaylistRepository for SqlitePlaylistRepository {
     async fn save(&self, playlist: &Playlist) -> Result<()> {
         let playlist = playlist.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            conn.execute(
-                "INSERT OR REPLACE INTO playlists (id, name, description, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            // Upsert rather than REPLACE: REPLACE deletes the old row, which
+            // would cascade to the playlist's songs
+            execute_cached(
+                &conn,
+                "INSERT INTO playlists (id, name, description, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name,
+                    description = excluded.description,
+                    updated_at = excluded.updated_at",
                 params![
                     playlist.id().as_str(),
                     playlist.name(),
@@ -111,14 +177,62 @@ impl PlaylistRepository for SqlitePlaylistRepository {
         ))?
     }
 
+    async fn create_with_songs(&self, playlist: &Playlist) -> Result<()> {
+        let playlist = playlist.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            // A failure part way leaves no half-filled playlist behind
+            let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
+                format!("Failed to start transaction: {}", e)
+            ))?;
+
+            execute_cached(
+                &tx,
+                "INSERT INTO playlists (id, name, description, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    playlist.id().as_str(),
+                    playlist.name(),
+                    playlist.description(),
+                    playlist.created_at().to_rfc3339(),
+                    playlist.updated_at().to_rfc3339()
+                ],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to create playlist: {}", e)
+            ))?;
+
+            for (position, song_id) in playlist.song_ids().iter().enumerate() {
+                execute_cached(
+                    &tx,
+                    "INSERT INTO playlist_songs (playlist_id, song_id, position, added_at)
+                     VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+                    params![playlist.id().as_str(), song_id.as_str(), position as i64],
+                ).map_err(|e| ApplicationError::Repository(
+                    format!("Failed to add song to playlist: {}", e)
+                ))?;
+            }
+
+            tx.commit().map_err(|e| ApplicationError::Repository(
+                format!("Failed to commit transaction: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
     async fn find_by_id(&self, id: &PlaylistId) -> Result<Option<Playlist>> {
         let id = id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare_cached(
                 "SELECT id, name, description, created_at, updated_at FROM playlists WHERE id = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
@@ -127,7 +241,7 @@ impl PlaylistRepository for SqlitePlaylistRepository {
             let playlist_result = stmt.query_row([id.as_str()], Self::row_to_playlist);
             
             match playlist_result {
-                Ok(playlist) => Ok(Some(playlist)),
+                Ok(playlist) => Ok(Some(Self::load_song_ids(&conn, playlist)?)),
                 Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
                 Err(e) => Err(ApplicationError::Repository(
                     format!("Failed to find playlist by id: {}", e)
@@ -140,12 +254,12 @@ impl PlaylistRepository for SqlitePlaylistRepository {
 
     async fn find_by_name(&self, name: &str) -> Result<Option<Playlist>> {
         let name = name.to_string();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare_cached(
                 "SELECT id, name, description, created_at, updated_at FROM playlists WHERE name = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
@@ -154,7 +268,7 @@ impl PlaylistRepository for SqlitePlaylistRepository {
             let playlist_result = stmt.query_row([&name], Self::row_to_playlist);
             
             match playlist_result {
-                Ok(playlist) => Ok(Some(playlist)),
+                Ok(playlist) => Ok(Some(Self::load_song_ids(&conn, playlist)?)),
                 Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
                 Err(e) => Err(ApplicationError::Repository(
                     format!("Failed to find playlist by name: {}", e)
@@ -166,13 +280,13 @@ impl PlaylistRepository for SqlitePlaylistRepository {
     }
 
     async fn find_all(&self) -> Result<Vec<Playlist>> {
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
-                "SELECT id, name, description, created_at, updated_at FROM playlists ORDER BY name"
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, name, description, created_at, updated_at FROM playlists ORDER BY name COLLATE NATURAL_SORT"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
             ))?;
@@ -182,12 +296,50 @@ impl PlaylistRepository for SqlitePlaylistRepository {
                     format!("Failed to query playlists: {}", e)
                 ))?;
 
+            // One query for the songs of every playlist rather than one per playlist
+            let mut song_ids = Self::load_all_song_ids(&conn)?;
+
             let mut playlists = Vec::new();
             for playlist_result in playlist_iter {
                 let playlist = playlist_result.map_err(|e| ApplicationError::Repository(
                     format!("Failed to parse playlist row: {}", e)
                 ))?;
-                playlists.push(playlist);
+                let ids = song_ids.remove(playlist.id().as_str()).unwrap_or_default();
+                playlists.push(Self::with_song_ids(playlist, ids)?);
+            }
+
+            Ok(playlists)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_by_song(&self, song_id: &SongId) -> Result<Vec<Playlist>> {
+        let song_id = song_id.clone();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, name, description, created_at, updated_at FROM playlists
+                 WHERE id IN (SELECT playlist_id FROM playlist_songs WHERE song_id = ?1)
+                 ORDER BY name COLLATE NATURAL_SORT"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let playlist_iter = stmt.query_map([song_id.as_str()], Self::row_to_playlist)
+                .map_err(|e| ApplicationError::Repository(
+                    format!("Failed to query playlists: {}", e)
+                ))?;
+
+            let mut playlists = Vec::new();
+            for playlist_result in playlist_iter {
+                let playlist = playlist_result.map_err(|e| ApplicationError::Repository(
+                    format!("Failed to parse playlist row: {}", e)
+                ))?;
+                playlists.push(Self::load_song_ids(&conn, playlist)?);
             }
 
             Ok(playlists)
@@ -198,12 +350,13 @@ impl PlaylistRepository for SqlitePlaylistRepository {
 
     async fn delete(&self, id: &PlaylistId) -> Result<()> {
         let id = id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            conn.execute(
+            execute_cached(
+                &conn,
                 "DELETE FROM playlists WHERE id = ?1",
                 [id.as_str()],
             ).map_err(|e| ApplicationError::Repository(
@@ -218,12 +371,12 @@ impl PlaylistRepository for SqlitePlaylistRepository {
 
     async fn exists_by_name(&self, name: &str) -> Result<bool> {
         let name = name.to_string();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare_cached(
                 "SELECT COUNT(*) FROM playlists WHERE name = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
@@ -245,14 +398,27 @@ impl PlaylistRepository for SqlitePlaylistRepository {
 mod tests {
     use super::*;
     use crate::domain::repositories::PlaylistRepository;
-    use rusqlite::Connection;
+    use crate::infrastructure::pool::create_in_memory_pool;
+    use crate::infrastructure::repositories::{SqlitePlaylistSongRepository, SqliteSongRepository};
 
     async fn create_test_repository() -> SqlitePlaylistRepository {
+        create_test_repositories().await.0
+    }
+
+    /// Playlist repository along with the song repositories it reads from
+    async fn create_test_repositories() -> (SqlitePlaylistRepository, SqliteSongRepository, SqlitePlaylistSongRepository) {
         // Use in-memory database for tests to avoid permission issues
-        let conn = Connection::open(":memory:").unwrap();
-        let repo = SqlitePlaylistRepository::new(Arc::new(Mutex::new(conn)));
+        let pool = create_in_memory_pool().unwrap();
+        let repo = SqlitePlaylistRepository::new(pool.clone());
         repo.initialize_schema().unwrap();
-        repo
+
+        // Playlists are loaded together with their song IDs, and the
+        // playlist_songs foreign keys need the songs table to exist
+        let song_repo = SqliteSongRepository::new(pool.clone());
+        song_repo.initialize_schema().unwrap();
+        let playlist_song_repo = SqlitePlaylistSongRepository::new(pool);
+        playlist_song_repo.initialize_schema().unwrap();
+        (repo, song_repo, playlist_song_repo)
     }
 
     #[tokio::test]
@@ -314,4 +480,77 @@ mod tests {
         repo.delete(playlist.id()).await.unwrap();
         assert!(!repo.exists_by_name("Test Playlist").await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_find_playlists_by_song() {
+        use crate::domain::entities::Song;
+        use crate::domain::repositories::{PlaylistSongRepository, SongRepository};
+        use crate::domain::value_objects::{Duration, FilePath};
+
+        let (repo, song_repo, playlist_song_repo) = create_test_repositories().await;
+
+        let song = |path: &str| Song::new(
+            FilePath::new(path).unwrap(),
+            "Title".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(180),
+        ).unwrap();
+        let shared = song("/music/shared.mp3");
+        let other = song("/music/other.mp3");
+        song_repo.save(&shared).await.unwrap();
+        song_repo.save(&other).await.unwrap();
+
+        for (name, songs) in [("Rainy", vec![&shared]), ("Focus", vec![&other, &shared]), ("Empty", vec![])] {
+            let playlist = Playlist::new(name.to_string(), None).unwrap();
+            repo.save(&playlist).await.unwrap();
+            for (position, song) in songs.into_iter().enumerate() {
+                playlist_song_repo.add_song_to_playlist(playlist.id(), song.id(), position).await.unwrap();
+            }
+        }
+
+        let found = repo.find_by_song(shared.id()).await.unwrap();
+        assert_eq!(found.iter().map(|p| p.name()).collect::<Vec<_>>(), ["Focus", "Rainy"]);
+        assert_eq!(found[0].song_ids(), [other.id().clone(), shared.id().clone()]);
+
+        let found = repo.find_by_song(other.id()).await.unwrap();
+        assert_eq!(found.iter().map(|p| p.name()).collect::<Vec<_>>(), ["Focus"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_with_songs_is_all_or_nothing() {
+        use crate::domain::entities::Song;
+        use crate::domain::repositories::SongRepository;
+        use crate::domain::value_objects::{Duration, FilePath};
+
+        let (repo, song_repo, _) = create_test_repositories().await;
+        let song = Song::new(
+            FilePath::new("/music/rain.mp3").unwrap(),
+            "Rain".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(180),
+        ).unwrap();
+        song_repo.save(&song).await.unwrap();
+
+        let mut playlist = Playlist::new("Rainy".to_string(), None).unwrap();
+        playlist.add_song(song.id().clone()).unwrap();
+        repo.create_with_songs(&playlist).await.unwrap();
+        let found = repo.find_by_name("Rainy").await.unwrap().unwrap();
+        assert_eq!(found.song_ids(), [song.id().clone()]);
+
+        // A song listed twice fails the second insert, and the playlist
+        // goes with it
+        let now = Utc::now();
+        let broken = Playlist::from_existing(
+            PlaylistId::new(),
+            "Broken".to_string(),
+            None,
+            vec![song.id().clone(), song.id().clone()],
+            now,
+            now,
+        ).unwrap();
+        assert!(repo.create_with_songs(&broken).await.is_err());
+        assert!(!repo.exists_by_name("Broken").await.unwrap());
+    }
 }
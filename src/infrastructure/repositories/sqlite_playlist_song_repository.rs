@@ -3,24 +3,25 @@ use crate::domain::repositories::PlaylistSongRepository;
 use crate::domain::value_objects::{PlaylistId, SongId, FilePath, Duration};
 use crate::shared::errors::{ApplicationError, Result};
 use async_trait::async_trait;
-use rusqlite::{params, Connection, Result as SqliteResult, OptionalExtension};
-use std::sync::{Arc, Mutex};
+use rusqlite::{params, Result as SqliteResult, OptionalExtension};
+use crate::infrastructure::pool::DbPool;
+use crate::infrastructure::repositories::{checkout, execute_cached};
 use tokio::task;
 
 /// SQLite implementation of PlaylistSongRepository
 pub struct SqlitePlaylistSongRepository {
-    connection: Arc<Mutex<Connection>>,
+    pool: DbPool,
 }
 
 impl SqlitePlaylistSongRepository {
     /// Create new SQLite playlist-song repository
-    pub fn new(connection: Arc<Mutex<Connection>>) -> Self {
-        Self { connection }
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
     }
 
     /// Initialize database schema
     pub fn initialize_schema(&self) -> Result<()> {
-        let conn = self.connection.lock().unwrap();
+        let conn = checkout(&self.pool)?;
         
         conn.execute(
             "CREATE TABLE IF NOT EXISTS playlist_songs (
@@ -52,6 +53,14 @@ impl SqlitePlaylistSongRepository {
             format!("Failed to create position index: {}", e)
         ))?;
 
+        // Looking up the playlists a song is in
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_playlist_songs_song_id ON playlist_songs(song_id)",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create song_id index: {}", e)
+        ))?;
+
         Ok(())
     }
 
@@ -63,6 +72,7 @@ impl SqlitePlaylistSongRepository {
         
         let duration_secs: i64 = row.get(5)?;
         let duration = Duration::from_seconds(duration_secs as u64);
+        let genre: Option<String> = row.get(6)?;
 
         Song::new(
             file_path,
@@ -70,7 +80,8 @@ impl SqlitePlaylistSongRepository {
             row.get(3)?, // artist
             row.get(4)?, // album
             duration,
-        ).map_err(|_| rusqlite::Error::InvalidColumnType(0, "song_creation".to_string(), rusqlite::types::Type::Text))
+        ).map(|song| song.with_genre(genre))
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "song_creation".to_string(), rusqlite::types::Type::Text))
     }
 }
 
@@ -84,10 +95,10 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
     ) -> Result<()> {
         let playlist_id = playlist_id.clone();
         let song_id = song_id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
             // Start transaction
             let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
@@ -95,7 +106,8 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
             ))?;
 
             // Shift existing songs at or after this position
-            tx.execute(
+            execute_cached(
+                &tx,
                 "UPDATE playlist_songs SET position = position + 1 
                  WHERE playlist_id = ?1 AND position >= ?2",
                 params![playlist_id.as_str(), position as i64],
@@ -104,7 +116,8 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
             ))?;
 
             // Insert the new song
-            tx.execute(
+            execute_cached(
+                &tx,
                 "INSERT OR REPLACE INTO playlist_songs (playlist_id, song_id, position, added_at)
                  VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
                 params![
@@ -127,6 +140,48 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
         ))?
     }
 
+    async fn append_songs(&self, playlist_id: &PlaylistId, song_ids: &[SongId]) -> Result<()> {
+        let playlist_id = playlist_id.clone();
+        let song_ids: Vec<String> = song_ids.iter().map(|id| id.as_str().to_string()).collect();
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            // A failure part way leaves the playlist as it was
+            let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
+                format!("Failed to start transaction: {}", e)
+            ))?;
+
+            let end: i64 = tx.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM playlist_songs WHERE playlist_id = ?1",
+                [playlist_id.as_str()],
+                |row| row.get(0),
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to get playlist length: {}", e)
+            ))?;
+
+            for (offset, song_id) in song_ids.iter().enumerate() {
+                execute_cached(
+                    &tx,
+                    "INSERT INTO playlist_songs (playlist_id, song_id, position, added_at)
+                     VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+                    params![playlist_id.as_str(), song_id, end + offset as i64],
+                ).map_err(|e| ApplicationError::Repository(
+                    format!("Failed to add song to playlist: {}", e)
+                ))?;
+            }
+
+            tx.commit().map_err(|e| ApplicationError::Repository(
+                format!("Failed to commit transaction: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
     async fn remove_song_from_playlist(
         &self,
         playlist_id: &PlaylistId,
@@ -134,10 +189,10 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
     ) -> Result<()> {
         let playlist_id = playlist_id.clone();
         let song_id = song_id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
             // Start transaction
             let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
@@ -155,7 +210,8 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
 
             if let Some(pos) = position {
                 // Remove the song
-                tx.execute(
+                execute_cached(
+                    &tx,
                     "DELETE FROM playlist_songs WHERE playlist_id = ?1 AND song_id = ?2",
                     params![playlist_id.as_str(), song_id.as_str()],
                 ).map_err(|e| ApplicationError::Repository(
@@ -163,7 +219,8 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
                 ))?;
 
                 // Shift remaining songs down
-                tx.execute(
+                execute_cached(
+                    &tx,
                     "UPDATE playlist_songs SET position = position - 1 
                      WHERE playlist_id = ?1 AND position > ?2",
                     params![playlist_id.as_str(), pos],
@@ -185,13 +242,13 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
 
     async fn get_playlist_songs(&self, playlist_id: &PlaylistId) -> Result<Vec<Song>> {
         let playlist_id = playlist_id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
-                "SELECT s.id, s.path, s.title, s.artist, s.album, s.duration
+            let mut stmt = conn.prepare_cached(
+                "SELECT s.id, s.path, s.title, s.artist, s.album, s.duration, s.genre
                  FROM playlist_songs ps
                  JOIN songs s ON ps.song_id = s.id
                  WHERE ps.playlist_id = ?1
@@ -219,6 +276,39 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
         ))?
     }
 
+    async fn get_playlist_durations(&self) -> Result<Vec<(PlaylistId, Duration)>> {
+        let pool = self.pool.clone();
+
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+
+            let mut stmt = conn.prepare_cached(
+                "SELECT ps.playlist_id, SUM(s.duration)
+                 FROM playlist_songs ps
+                 JOIN songs s ON ps.song_id = s.id
+                 GROUP BY ps.playlist_id"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let durations = stmt.query_map([], |row| {
+                let playlist_id = PlaylistId::from_string(row.get(0)?);
+                let seconds: i64 = row.get(1)?;
+                Ok((playlist_id, Duration::from_seconds(seconds as u64)))
+            }).map_err(|e| ApplicationError::Repository(
+                format!("Failed to query playlist durations: {}", e)
+            ))?
+            .collect::<SqliteResult<Vec<_>>>()
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to parse playlist duration row: {}", e)
+            ))?;
+
+            Ok(durations)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
     async fn reorder_playlist_songs(
         &self,
         playlist_id: &PlaylistId,
@@ -226,10 +316,10 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
     ) -> Result<()> {
         let playlist_id = playlist_id.clone();
         let song_ids: Vec<String> = song_ids.iter().map(|id| id.as_str().to_string()).collect();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
             // Start transaction
             let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
@@ -238,7 +328,8 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
 
             // Update positions for each song
             for (position, song_id) in song_ids.iter().enumerate() {
-                tx.execute(
+                execute_cached(
+                    &tx,
                     "UPDATE playlist_songs SET position = ?1 
                      WHERE playlist_id = ?2 AND song_id = ?3",
                     params![position as i64, playlist_id.as_str(), song_id],
@@ -260,12 +351,13 @@ impl PlaylistSongRepository for SqlitePlaylistSongRepository {
 
     async fn clear_playlist(&self, playlist_id: &PlaylistId) -> Result<()> {
         let playlist_id = playlist_id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            conn.execute(
+            execute_cached(
+                &conn,
                 "DELETE FROM playlist_songs WHERE playlist_id = ?1",
                 [playlist_id.as_str()],
             ).map_err(|e| ApplicationError::Repository(
@@ -285,15 +377,15 @@ mod tests {
     use crate::infrastructure::repositories::{SqliteSongRepository, SqlitePlaylistRepository};
     use crate::domain::entities::{Song, Playlist};
     use crate::domain::repositories::{SongRepository, PlaylistRepository, PlaylistSongRepository};
-    use rusqlite::Connection;
+    use crate::infrastructure::pool::create_in_memory_pool;
 
     async fn create_test_setup() -> (SqlitePlaylistSongRepository, SqliteSongRepository, SqlitePlaylistRepository) {
         // Use in-memory database for tests to avoid permission issues
-        let conn = Arc::new(Mutex::new(Connection::open(":memory:").unwrap()));
+        let pool = create_in_memory_pool().unwrap();
         
-        let playlist_song_repo = SqlitePlaylistSongRepository::new(conn.clone());
-        let song_repo = SqliteSongRepository::new(conn.clone());
-        let playlist_repo = SqlitePlaylistRepository::new(conn.clone());
+        let playlist_song_repo = SqlitePlaylistSongRepository::new(pool.clone());
+        let song_repo = SqliteSongRepository::new(pool.clone());
+        let playlist_repo = SqlitePlaylistRepository::new(pool);
         
         playlist_song_repo.initialize_schema().unwrap();
         song_repo.initialize_schema().unwrap();
@@ -345,6 +437,12 @@ mod tests {
         assert_eq!(songs.len(), 2);
         assert_eq!(songs[0].title(), "Song 1");
         assert_eq!(songs[1].title(), "Song 2");
+
+        // Empty playlists have no total
+        let empty = Playlist::new("Empty".to_string(), None).unwrap();
+        playlist_repo.save(&empty).await.unwrap();
+        let durations = playlist_song_repo.get_playlist_durations().await.unwrap();
+        assert_eq!(durations, [(playlist.id().clone(), Duration::from_seconds(380))]);
     }
 
     #[tokio::test]
@@ -378,4 +476,70 @@ mod tests {
         let songs = playlist_song_repo.get_playlist_songs(playlist.id()).await.unwrap();
         assert_eq!(songs.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_append_songs() {
+        let (playlist_song_repo, song_repo, playlist_repo) = create_test_setup().await;
+
+        let playlist = Playlist::new("Test Playlist".to_string(), None).unwrap();
+        playlist_repo.save(&playlist).await.unwrap();
+
+        let mut songs = Vec::new();
+        for name in ["a", "b", "c"] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                name.to_string(),
+                "Artist".to_string(),
+                "Album".to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            song_repo.save(&song).await.unwrap();
+            songs.push(song);
+        }
+        playlist_song_repo.add_song_to_playlist(playlist.id(), songs[0].id(), 0).await.unwrap();
+
+        let appended = [songs[1].id().clone(), songs[2].id().clone()];
+        playlist_song_repo.append_songs(playlist.id(), &appended).await.unwrap();
+        let titles = |songs: Vec<Song>| songs.iter().map(|song| song.title().to_string()).collect::<Vec<_>>();
+        assert_eq!(titles(playlist_song_repo.get_playlist_songs(playlist.id()).await.unwrap()), ["a", "b", "c"]);
+
+        // A song that cannot be added takes the rest of the batch with it
+        let missing = SongId::from_string("missing".to_string());
+        let fourth = Song::new(
+            FilePath::new("/test/d.mp3").unwrap(),
+            "d".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(120),
+        ).unwrap();
+        song_repo.save(&fourth).await.unwrap();
+        assert!(playlist_song_repo.append_songs(playlist.id(), &[fourth.id().clone(), missing]).await.is_err());
+        assert_eq!(titles(playlist_song_repo.get_playlist_songs(playlist.id()).await.unwrap()), ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_playlist_loads_song_ids() {
+        let (playlist_song_repo, song_repo, playlist_repo) = create_test_setup().await;
+
+        let playlist = Playlist::new("Test Playlist".to_string(), None).unwrap();
+        playlist_repo.save(&playlist).await.unwrap();
+
+        let song = Song::new(
+            FilePath::new("/test/song.mp3").unwrap(),
+            "Test Song".to_string(),
+            "Test Artist".to_string(),
+            "Test Album".to_string(),
+            Duration::from_seconds(180),
+        ).unwrap();
+        song_repo.save(&song).await.unwrap();
+        playlist_song_repo.add_song_to_playlist(playlist.id(), song.id(), 0).await.unwrap();
+
+        let loaded = playlist_repo.find_by_name("Test Playlist").await.unwrap().unwrap();
+        assert_eq!(loaded.song_ids(), &[song.id().clone()]);
+
+        // Re-saving the playlist must not drop its songs
+        playlist_repo.save(&loaded).await.unwrap();
+        let songs = playlist_song_repo.get_playlist_songs(playlist.id()).await.unwrap();
+        assert_eq!(songs.len(), 1);
+    }
 }
@@ -1,26 +1,40 @@
-use crate::domain::entities::Song;
+use crate::domain::entities::{ImportedStats, Song, SongStats, SortKeys};
 use crate::domain::repositories::SongRepository;
-use crate::domain::value_objects::{SongId, FilePath, Duration};
+use crate::domain::value_objects::{SongId, FilePath, Duration, Trim};
 use crate::shared::errors::{ApplicationError, Result};
+use crate::shared::natural_sort::natural_key;
+use crate::shared::sort_key::strip_article;
 use async_trait::async_trait;
-use rusqlite::{params, Connection, Result as SqliteResult};
-use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Params, Result as SqliteResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::infrastructure::pool::DbPool;
+use crate::infrastructure::repositories::{checkout, execute_cached, format_timestamp, parse_timestamp};
 use tokio::task;
 
 /// SQLite implementation of SongRepository
 pub struct SqliteSongRepository {
-    connection: Arc<Mutex<Connection>>,
+    pool: DbPool,
+    /// Articles the artist and album sort keys leave out
+    sort_articles: Arc<[String]>,
 }
 
 impl SqliteSongRepository {
-    /// Create new SQLite song repository
-    pub fn new(connection: Arc<Mutex<Connection>>) -> Self {
-        Self { connection }
+    /// Create new SQLite song repository, sorting names as they are
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool, sort_articles: Arc::from([]) }
+    }
+
+    /// Sort artists and albums without the leading `articles`
+    pub fn with_sort_articles(mut self, articles: &[String]) -> Self {
+        self.sort_articles = Arc::from(articles);
+        self
     }
 
     /// Initialize database schema
     pub fn initialize_schema(&self) -> Result<()> {
-        let conn = self.connection.lock().unwrap();
+        let conn = checkout(&self.pool)?;
         
         conn.execute(
             "CREATE TABLE IF NOT EXISTS songs (
@@ -30,8 +44,20 @@ impl SqliteSongRepository {
                 artist TEXT NOT NULL,
                 album TEXT NOT NULL,
                 duration INTEGER NOT NULL,
+                genre TEXT,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                play_count INTEGER NOT NULL DEFAULT 0,
+                last_played_at DATETIME,
+                loved INTEGER NOT NULL DEFAULT 0,
+                rating INTEGER,
+                artist_sort TEXT,
+                album_sort TEXT,
+                trim_start INTEGER NOT NULL DEFAULT 0,
+                trim_end INTEGER NOT NULL DEFAULT 0,
+                title_key BLOB,
+                artist_key BLOB,
+                album_key BLOB
             )",
             [],
         ).map_err(|e| ApplicationError::Repository(
@@ -53,9 +79,116 @@ impl SqliteSongRepository {
             format!("Failed to create artist index: {}", e)
         ))?;
 
+        // `path` needs no index of its own: its UNIQUE constraint already provides one
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_songs_album ON songs(album)",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create album index: {}", e)
+        ))?;
+
+        // Settings the stored rows depend on, such as the articles their sort
+        // keys leave out
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS library_meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to create library_meta table: {}", e)
+        ))?;
+
         Ok(())
     }
 
+    /// Bring the sort keys in line with the articles ignored now, if the
+    /// settings changed them since the keys were stored, and fill in the keys
+    /// of rows stored before they existed
+    pub fn refresh_sort_keys(&self) -> Result<()> {
+        let mut conn = checkout(&self.pool)?;
+        let transaction = conn.transaction().map_err(|e| ApplicationError::Repository(
+            format!("Failed to begin transaction: {}", e)
+        ))?;
+
+        let articles = self.sort_articles.join("\n");
+        let stored: Option<String> = transaction.query_row(
+            "SELECT value FROM library_meta WHERE key = 'sort_articles'",
+            [],
+            |row| row.get(0),
+        ).optional().map_err(|e| ApplicationError::Repository(
+            format!("Failed to read sort articles: {}", e)
+        ))?;
+        // Every row is saved with all of its keys, so one missing title key
+        // is enough to tell
+        let missing: bool = transaction.query_row(
+            "SELECT EXISTS (SELECT 1 FROM songs WHERE title_key IS NULL)",
+            [],
+            |row| row.get(0),
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to check sort keys: {}", e)
+        ))?;
+        if stored.as_deref() == Some(articles.as_str()) && !missing {
+            return Ok(());
+        }
+
+        {
+            let mut select = transaction.prepare(
+                "SELECT id, title, artist, album, artist_sort, album_sort, title_key IS NULL FROM songs"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+            let mut update = transaction.prepare(
+                "UPDATE songs SET artist_sort = ?2, album_sort = ?3, title_key = ?4, artist_key = ?5, album_key = ?6
+                 WHERE id = ?1"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let mut rows = select.query([]).map_err(|e| ApplicationError::Repository(
+                format!("Failed to query sort keys: {}", e)
+            ))?;
+            while let Some(row) = rows.next().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read sort keys: {}", e)
+            ))? {
+                let read = |index| row.get::<_, Option<String>>(index).map_err(|e| ApplicationError::Repository(
+                    format!("Failed to read sort keys: {}", e)
+                ));
+                let (id, title) = (read(0)?, read(1)?.unwrap_or_default());
+                let (artist, album) = (read(2)?.unwrap_or_default(), read(3)?.unwrap_or_default());
+                let artist_sort = strip_article(&artist, &self.sort_articles);
+                let album_sort = strip_article(&album, &self.sort_articles);
+                let title_key_missing: bool = row.get(6).map_err(|e| ApplicationError::Repository(
+                    format!("Failed to read sort keys: {}", e)
+                ))?;
+                if title_key_missing || read(4)?.as_deref() != Some(artist_sort) || read(5)?.as_deref() != Some(album_sort) {
+                    update.execute(params![
+                        id,
+                        artist_sort,
+                        album_sort,
+                        natural_key(&title),
+                        natural_key(artist_sort),
+                        natural_key(album_sort)
+                    ]).map_err(|e| ApplicationError::Repository(
+                        format!("Failed to update sort keys: {}", e)
+                    ))?;
+                }
+            }
+        }
+
+        transaction.execute(
+            "INSERT INTO library_meta (key, value) VALUES ('sort_articles', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [&articles],
+        ).map_err(|e| ApplicationError::Repository(
+            format!("Failed to store sort articles: {}", e)
+        ))?;
+
+        transaction.commit().map_err(|e| ApplicationError::Repository(
+            format!("Failed to commit sort keys: {}", e)
+        ))
+    }
+
     /// Convert database row to Song entity
     fn row_to_song(row: &rusqlite::Row) -> SqliteResult<Song> {
         let path_str: String = row.get(1)?;
@@ -64,6 +197,7 @@ impl SqliteSongRepository {
         
         let duration_secs: i64 = row.get(5)?;
         let duration = Duration::from_seconds(duration_secs as u64);
+        let genre: Option<String> = row.get(6)?;
 
         Song::new(
             file_path,
@@ -71,7 +205,42 @@ impl SqliteSongRepository {
             row.get(3)?, // artist
             row.get(4)?, // album
             duration,
-        ).map_err(|_| rusqlite::Error::InvalidColumnType(0, "song_creation".to_string(), rusqlite::types::Type::Text))
+        ).map(|song| song.with_genre(genre))
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "song_creation".to_string(), rusqlite::types::Type::Text))
+    }
+
+    /// Run a query selecting song rows and collect the results
+    fn query_songs<P: Params>(conn: &Connection, sql: &str, params: P, description: &str) -> Result<Vec<Song>> {
+        let mut stmt = conn.prepare_cached(sql).map_err(|e| ApplicationError::Repository(
+            format!("Failed to prepare statement: {}", e)
+        ))?;
+
+        let song_iter = stmt.query_map(params, Self::row_to_song)
+            .map_err(|e| ApplicationError::Repository(
+                format!("Failed to query {}: {}", description, e)
+            ))?;
+
+        let mut songs = Vec::new();
+        for song_result in song_iter {
+            let song = song_result.map_err(|e| ApplicationError::Repository(
+                format!("Failed to parse song row: {}", e)
+            ))?;
+            songs.push(song);
+        }
+
+        Ok(songs)
+    }
+
+    /// Songs of an album, ignoring case, in the natural order of their paths
+    fn query_album(conn: &Connection, artist: &str, album: &str) -> Result<Vec<Song>> {
+        Self::query_songs(
+            conn,
+            "SELECT id, path, title, artist, album, duration, genre FROM songs
+             WHERE artist = ?1 COLLATE NOCASE AND album = ?2 COLLATE NOCASE
+             ORDER BY path COLLATE NATURAL_SORT",
+            params![artist, album],
+            "album songs",
+        )
     }
 }
 
@@ -79,21 +248,47 @@ impl SqliteSongRepository {
 impl SongRepository for SqliteSongRepository {
     async fn save(&self, song: &Song) -> Result<()> {
         let song = song.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
+        let articles = self.sort_articles.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
+            let artist_sort = strip_article(song.artist(), &articles);
+            let album_sort = strip_article(song.album(), &articles);
             
-            conn.execute(
-                "INSERT OR REPLACE INTO songs (id, path, title, artist, album, duration, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)",
+            // Upsert rather than REPLACE so an existing row keeps its created_at,
+            // which drives the "Recently added" view
+            execute_cached(
+                &conn,
+                "INSERT INTO songs (id, path, title, artist, album, duration, genre, artist_sort, album_sort,
+                                    title_key, artist_key, album_key, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, CURRENT_TIMESTAMP)
+                 ON CONFLICT(id) DO UPDATE SET
+                    path = excluded.path,
+                    title = excluded.title,
+                    artist = excluded.artist,
+                    album = excluded.album,
+                    duration = excluded.duration,
+                    genre = excluded.genre,
+                    artist_sort = excluded.artist_sort,
+                    album_sort = excluded.album_sort,
+                    title_key = excluded.title_key,
+                    artist_key = excluded.artist_key,
+                    album_key = excluded.album_key,
+                    updated_at = excluded.updated_at",
                 params![
                     song.id().as_str(),
                     song.file_path().as_str(),
                     song.title(),
                     song.artist(),
                     song.album(),
-                    song.duration().total_seconds() as i64
+                    song.duration().total_seconds() as i64,
+                    song.genre(),
+                    artist_sort,
+                    album_sort,
+                    natural_key(song.title()),
+                    natural_key(artist_sort),
+                    natural_key(album_sort)
                 ],
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to save song: {}", e)
@@ -107,13 +302,13 @@ impl SongRepository for SqliteSongRepository {
 
     async fn find_by_id(&self, id: &SongId) -> Result<Option<Song>> {
         let id = id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
-                "SELECT id, path, title, artist, album, duration FROM songs WHERE id = ?1"
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, path, title, artist, album, duration, genre FROM songs WHERE id = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
             ))?;
@@ -134,13 +329,13 @@ impl SongRepository for SqliteSongRepository {
 
     async fn find_by_path(&self, path: &FilePath) -> Result<Option<Song>> {
         let path = path.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
-                "SELECT id, path, title, artist, album, duration FROM songs WHERE path = ?1"
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, path, title, artist, album, duration, genre FROM songs WHERE path = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
             ))?;
@@ -160,13 +355,13 @@ impl SongRepository for SqliteSongRepository {
     }
 
     async fn find_all(&self) -> Result<Vec<Song>> {
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
-                "SELECT id, path, title, artist, album, duration FROM songs ORDER BY title, artist"
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, path, title, artist, album, duration, genre FROM songs ORDER BY title_key, artist_key, id"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
             ))?;
@@ -190,18 +385,104 @@ impl SongRepository for SqliteSongRepository {
         ))?
     }
 
+    async fn find_range(&self, query: &str, offset: usize, limit: usize) -> Result<Vec<Song>> {
+        let query = query.to_string();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            // The stored keys compare bytewise in natural order, so
+            // idx_songs_title_order serves the order, and skipping to any
+            // range of a large library only walks the index
+            if query.is_empty() {
+                Self::query_songs(
+                    &conn,
+                    "SELECT id, path, title, artist, album, duration, genre FROM songs
+                     ORDER BY title_key, artist_key, id
+                     LIMIT ?1 OFFSET ?2",
+                    params![limit as i64, offset as i64],
+                    "song range",
+                )
+            } else {
+                Self::query_songs(
+                    &conn,
+                    "SELECT id, path, title, artist, album, duration, genre FROM songs
+                     WHERE MATCHES_SEARCH(title, artist, album, ?1)
+                     ORDER BY title_key, artist_key, id
+                     LIMIT ?2 OFFSET ?3",
+                    params![query, limit as i64, offset as i64],
+                    "song range",
+                )
+            }
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_position(&self, query: &str, path: &FilePath) -> Result<Option<usize>> {
+        let query = query.to_string();
+        let path = path.as_str().to_string();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            let position: Option<i64> = conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM songs AS other
+                         WHERE (other.title_key, other.artist_key, other.id) < (song.title_key, song.artist_key, song.id)
+                           AND (?1 = '' OR MATCHES_SEARCH(other.title, other.artist, other.album, ?1)))
+                 FROM songs AS song
+                 WHERE song.path = ?2 AND (?1 = '' OR MATCHES_SEARCH(song.title, song.artist, song.album, ?1))",
+                params![query, path],
+                |row| row.get(0),
+            ).optional().map_err(|e| ApplicationError::Repository(
+                format!("Failed to find song position: {}", e)
+            ))?;
+
+            Ok(position.map(|position| position as usize))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn count(&self, query: &str) -> Result<usize> {
+        let query = query.to_string();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            let count: i64 = if query.is_empty() {
+                conn.query_row("SELECT COUNT(*) FROM songs", [], |row| row.get(0))
+            } else {
+                conn.query_row(
+                    "SELECT COUNT(*) FROM songs WHERE MATCHES_SEARCH(title, artist, album, ?1)",
+                    [&query],
+                    |row| row.get(0),
+                )
+            }.map_err(|e| ApplicationError::Repository(
+                format!("Failed to count songs: {}", e)
+            ))?;
+
+            Ok(count as usize)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
     async fn search(&self, query: &str) -> Result<Vec<Song>> {
         let query = query.to_string();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             let search_pattern = format!("%{}%", query.to_lowercase());
             
-            let mut stmt = conn.prepare(
-                "SELECT id, path, title, artist, album, duration FROM songs 
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, path, title, artist, album, duration, genre FROM songs 
                  WHERE LOWER(title) LIKE ?1 OR LOWER(artist) LIKE ?1 OR LOWER(album) LIKE ?1
-                 ORDER BY title, artist"
+                 ORDER BY title_key, artist_key"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare search statement: {}", e)
             ))?;
@@ -227,12 +508,12 @@ impl SongRepository for SqliteSongRepository {
 
     async fn exists_by_path(&self, path: &FilePath) -> Result<bool> {
         let path = path.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare_cached(
                 "SELECT COUNT(*) FROM songs WHERE path = ?1"
             ).map_err(|e| ApplicationError::Repository(
                 format!("Failed to prepare statement: {}", e)
@@ -251,12 +532,13 @@ impl SongRepository for SqliteSongRepository {
 
     async fn delete(&self, id: &SongId) -> Result<()> {
         let id = id.clone();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            conn.execute(
+            execute_cached(
+                &conn,
                 "DELETE FROM songs WHERE id = ?1",
                 [id.as_str()],
             ).map_err(|e| ApplicationError::Repository(
@@ -271,10 +553,10 @@ impl SongRepository for SqliteSongRepository {
 
     async fn find_by_ids(&self, ids: &[SongId]) -> Result<Vec<Song>> {
         let ids: Vec<String> = ids.iter().map(|id| id.as_str().to_string()).collect();
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
             if ids.is_empty() {
                 return Ok(Vec::new());
@@ -283,7 +565,7 @@ impl SongRepository for SqliteSongRepository {
             // Create placeholders for IN clause
             let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
             let query = format!(
-                "SELECT id, path, title, artist, album, duration FROM songs WHERE id IN ({}) ORDER BY title, artist",
+                "SELECT id, path, title, artist, album, duration, genre FROM songs WHERE id IN ({}) ORDER BY title_key, artist_key",
                 placeholders
             );
 
@@ -313,13 +595,410 @@ impl SongRepository for SqliteSongRepository {
         ))?
     }
 
+    async fn find_added_since(&self, since: DateTime<Utc>) -> Result<Vec<Song>> {
+        let pool = self.pool.clone();
+        // Match SQLite's CURRENT_TIMESTAMP format so the comparison is textual
+        let since = format_timestamp(since);
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            Self::query_songs(
+                &conn,
+                "SELECT id, path, title, artist, album, duration, genre FROM songs
+                 WHERE created_at >= ?1
+                 ORDER BY created_at DESC, title_key, artist_key",
+                [&since],
+                "recently added songs",
+            )
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn relocate(&self, id: &SongId, new_path: &FilePath) -> Result<Song> {
+        let old_id = id.clone();
+        let new_path = new_path.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            let new_id = SongId::from_path(&new_path);
+
+            // Start transaction so the song, its playlist entries and its
+            // bookmarks move together
+            let tx = conn.unchecked_transaction().map_err(|e| ApplicationError::Repository(
+                format!("Failed to start transaction: {}", e)
+            ))?;
+
+            // Playlist entries and bookmarks reference the old ID until they
+            // are updated below
+            tx.execute_batch("PRAGMA defer_foreign_keys = ON").map_err(|e| ApplicationError::Repository(
+                format!("Failed to defer foreign keys: {}", e)
+            ))?;
+
+            let updated = execute_cached(
+                &tx,
+                "UPDATE songs SET id = ?1, path = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+                params![new_id.as_str(), new_path.as_str(), old_id.as_str()],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to relocate song: {}", e)
+            ))?;
+
+            if updated == 0 {
+                return Err(ApplicationError::Repository(
+                    format!("Song not found: {}", old_id.as_str())
+                ));
+            }
+
+            execute_cached(
+                &tx,
+                "UPDATE playlist_songs SET song_id = ?1 WHERE song_id = ?2",
+                params![new_id.as_str(), old_id.as_str()],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to update playlist entries: {}", e)
+            ))?;
+
+            execute_cached(
+                &tx,
+                "UPDATE bookmarks SET song_id = ?1 WHERE song_id = ?2",
+                params![new_id.as_str(), old_id.as_str()],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to update bookmarks: {}", e)
+            ))?;
+
+            let song = tx.query_row(
+                "SELECT id, path, title, artist, album, duration, genre FROM songs WHERE id = ?1",
+                [new_id.as_str()],
+                Self::row_to_song,
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to load relocated song: {}", e)
+            ))?;
+
+            // Commit transaction
+            tx.commit().map_err(|e| ApplicationError::Repository(
+                format!("Failed to commit transaction: {}", e)
+            ))?;
+
+            Ok(song)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn record_play(&self, id: &SongId) -> Result<()> {
+        let id = id.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            execute_cached(
+                &conn,
+                "UPDATE songs SET play_count = play_count + 1, last_played_at = CURRENT_TIMESTAMP
+                 WHERE id = ?1",
+                [id.as_str()],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to record play: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn set_loved(&self, id: &SongId, loved: bool) -> Result<()> {
+        let id = id.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            execute_cached(
+                &conn,
+                "UPDATE songs SET loved = ?2 WHERE id = ?1",
+                rusqlite::params![id.as_str(), loved],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to mark song as loved: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn set_trim(&self, id: &SongId, trim: Trim) -> Result<()> {
+        let id = id.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            execute_cached(
+                &conn,
+                "UPDATE songs SET trim_start = ?2, trim_end = ?3 WHERE id = ?1",
+                params![id.as_str(), trim.start().total_seconds() as i64, trim.end().total_seconds() as i64],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to trim song: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_trim(&self, id: &SongId) -> Result<Option<Trim>> {
+        let id = id.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            let mut stmt = conn.prepare_cached(
+                "SELECT trim_start, trim_end FROM songs WHERE id = ?1"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let trim = stmt.query_row([id.as_str()], |row| {
+                let start: i64 = row.get(0)?;
+                let end: i64 = row.get(1)?;
+                Ok(Trim::new(Duration::from_seconds(start as u64), Duration::from_seconds(end as u64)))
+            });
+            match trim {
+                Ok(trim) => Ok(Some(trim)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(ApplicationError::Repository(
+                    format!("Failed to look up trim: {}", e)
+                )),
+            }
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_loved(&self) -> Result<Vec<Song>> {
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            Self::query_songs(
+                &conn,
+                "SELECT id, path, title, artist, album, duration, genre FROM songs
+                 WHERE loved = 1
+                 ORDER BY artist_key, album_key, title_key",
+                [],
+                "loved songs",
+            )
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn import_stats(&self, id: &SongId, stats: &ImportedStats) -> Result<()> {
+        let id = id.clone();
+        let stats = stats.clone();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            // Timestamps share one format, so they compare as text
+            execute_cached(
+                &conn,
+                "UPDATE songs SET
+                    play_count = MAX(play_count, ?2),
+                    last_played_at = CASE WHEN last_played_at IS NULL OR last_played_at < ?3
+                        THEN COALESCE(?3, last_played_at) ELSE last_played_at END,
+                    rating = COALESCE(rating, ?4)
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id.as_str(),
+                    stats.play_count,
+                    stats.last_played_at.map(format_timestamp),
+                    stats.rating,
+                ],
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to import song stats: {}", e)
+            ))?;
+
+            Ok(())
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_most_played(&self, limit: usize) -> Result<Vec<Song>> {
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            Self::query_songs(
+                &conn,
+                "SELECT id, path, title, artist, album, duration, genre FROM songs
+                 WHERE play_count > 0
+                 ORDER BY play_count DESC, last_played_at DESC
+                 LIMIT ?1",
+                [limit as i64],
+                "most played songs",
+            )
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_never_played(&self) -> Result<Vec<Song>> {
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            Self::query_songs(
+                &conn,
+                "SELECT id, path, title, artist, album, duration, genre FROM songs
+                 WHERE play_count = 0
+                 ORDER BY artist_key, album_key, title_key",
+                [],
+                "never played songs",
+            )
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_all_with_stats(&self) -> Result<Vec<SongStats>> {
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, path, title, artist, album, duration, genre, play_count, last_played_at, created_at, rating
+                 FROM songs ORDER BY id"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let rows = stmt.query_map([], |row| {
+                let last_played_at: Option<String> = row.get(8)?;
+                let created_at: String = row.get(9)?;
+                Ok(SongStats {
+                    song: Self::row_to_song(row)?,
+                    play_count: row.get(7)?,
+                    last_played_at: last_played_at.map(|value| parse_timestamp(&value, 8)).transpose()?,
+                    added_at: parse_timestamp(&created_at, 9)?,
+                    rating: row.get(10)?,
+                })
+            }).map_err(|e| ApplicationError::Repository(
+                format!("Failed to query song stats: {}", e)
+            ))?;
+
+            rows.collect::<SqliteResult<Vec<_>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to parse song row: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_sort_keys(&self) -> Result<HashMap<SongId, SortKeys>> {
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            // Rows stored before sort keys existed and not yet refreshed sort
+            // by their names
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, COALESCE(artist_sort, artist), COALESCE(album_sort, album) FROM songs"
+            ).map_err(|e| ApplicationError::Repository(
+                format!("Failed to prepare statement: {}", e)
+            ))?;
+
+            let rows = stmt.query_map([], |row| {
+                Ok((SongId::from_string(row.get(0)?), SortKeys { artist: row.get(1)?, album: row.get(2)? }))
+            }).map_err(|e| ApplicationError::Repository(
+                format!("Failed to query sort keys: {}", e)
+            ))?;
+
+            rows.collect::<SqliteResult<HashMap<_, _>>>().map_err(|e| ApplicationError::Repository(
+                format!("Failed to read sort keys: {}", e)
+            ))
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_album(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+        let (artist, album) = (artist.to_string(), album.to_string());
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            Self::query_album(&conn, &artist, &album)
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
+    async fn find_next_album(&self, id: &SongId) -> Result<Vec<Song>> {
+        let id = id.as_str().to_string();
+        let pool = self.pool.clone();
+        
+        task::spawn_blocking(move || {
+            let conn = checkout(&pool)?;
+            
+            let current: Option<(String, String, String, String)> = conn.query_row(
+                "SELECT COALESCE(artist_sort, artist), artist, COALESCE(album_sort, album), album FROM songs WHERE id = ?1",
+                [&id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            ).optional().map_err(|e| ApplicationError::Repository(
+                format!("Failed to find song album: {}", e)
+            ))?;
+            let Some((artist_sort, artist, album_sort, album)) = current else {
+                return Ok(Vec::new());
+            };
+
+            // Albums after the current one sort first, then the library
+            // wraps round to the ones before it. Names are lowered so that
+            // albums told apart only by case, which are one album, sort
+            // together.
+            let next: Option<(String, String)> = conn.query_row(
+                "SELECT artist, album FROM songs
+                 WHERE NOT (artist = ?2 COLLATE NOCASE AND album = ?4 COLLATE NOCASE)
+                 ORDER BY (LOWER(COALESCE(artist_sort, artist)) COLLATE NATURAL_SORT, LOWER(artist) COLLATE NATURAL_SORT,
+                           LOWER(COALESCE(album_sort, album)) COLLATE NATURAL_SORT, LOWER(album) COLLATE NATURAL_SORT)
+                          <= (LOWER(?1), LOWER(?2), LOWER(?3), LOWER(?4)),
+                          LOWER(COALESCE(artist_sort, artist)) COLLATE NATURAL_SORT, LOWER(artist) COLLATE NATURAL_SORT,
+                          LOWER(COALESCE(album_sort, album)) COLLATE NATURAL_SORT, LOWER(album) COLLATE NATURAL_SORT
+                 LIMIT 1",
+                params![artist_sort, artist, album_sort, album],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional().map_err(|e| ApplicationError::Repository(
+                format!("Failed to find next album: {}", e)
+            ))?;
+
+            match next {
+                Some((artist, album)) => Self::query_album(&conn, &artist, &album),
+                None => Ok(Vec::new()),
+            }
+        }).await.map_err(|e| ApplicationError::Repository(
+            format!("Task execution failed: {}", e)
+        ))?
+    }
+
     async fn clear_all(&self) -> Result<()> {
-        let connection = self.connection.clone();
+        let pool = self.pool.clone();
         
         task::spawn_blocking(move || {
-            let conn = connection.lock().unwrap();
+            let conn = checkout(&pool)?;
             
-            conn.execute("DELETE FROM songs", [])
+            execute_cached(&conn, "DELETE FROM songs", [])
                 .map_err(|e| ApplicationError::Repository(
                     format!("Failed to clear all songs: {}", e)
                 ))?;
@@ -334,12 +1013,11 @@ impl SongRepository for SqliteSongRepository {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rusqlite::Connection;
+    use crate::infrastructure::pool::create_in_memory_pool;
 
     async fn create_test_repository() -> SqliteSongRepository {
         // Use in-memory database for tests to avoid permission issues
-        let conn = Connection::open(":memory:").unwrap();
-        let repo = SqliteSongRepository::new(Arc::new(Mutex::new(conn)));
+        let repo = SqliteSongRepository::new(create_in_memory_pool().unwrap());
         repo.initialize_schema().unwrap();
         repo
     }
@@ -356,7 +1034,7 @@ mod tests {
             "Test Artist".to_string(),
             "Test Album".to_string(),
             duration,
-        ).unwrap();
+        ).unwrap().with_genre(Some("Lo-fi".to_string()));
 
         // Save song
         repo.save(&song).await.unwrap();
@@ -364,7 +1042,9 @@ mod tests {
         // Find by ID
         let found_song = repo.find_by_id(song.id()).await.unwrap();
         assert!(found_song.is_some());
-        assert_eq!(found_song.unwrap().title(), "Test Song");
+        let found_song = found_song.unwrap();
+        assert_eq!(found_song.title(), "Test Song");
+        assert_eq!(found_song.genre(), Some("Lo-fi"));
 
         // Find by path
         let found_by_path = repo.find_by_path(&file_path).await.unwrap();
@@ -397,4 +1077,322 @@ mod tests {
         let results = repo.search("Artist").await.unwrap();
         assert_eq!(results.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_find_range() {
+        let repo = create_test_repository().await;
+
+        // Same titles and artists, so only the ID tells some songs apart
+        for (name, title) in [("a", "Alpha"), ("b", "Beta"), ("c", "Beta"), ("d", "Gamma"), ("e", "Beta")] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                title.to_string(),
+                "Artist".to_string(),
+                "Album".to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            repo.save(&song).await.unwrap();
+        }
+
+        let mut paged = Vec::new();
+        for offset in (0..6).step_by(2) {
+            let page = repo.find_range("", offset, 2).await.unwrap();
+            assert!(page.len() <= 2);
+            paged.extend(page);
+        }
+
+        let all = repo.find_all().await.unwrap();
+        let ids = |songs: &[Song]| songs.iter().map(|song| song.id().clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&paged), ids(&all));
+        assert_eq!(repo.count("").await.unwrap(), 5);
+        for (position, song) in all.iter().enumerate() {
+            assert_eq!(repo.find_position("", song.file_path()).await.unwrap(), Some(position));
+        }
+
+        // A search reads, counts and places its matches alone
+        let betas = repo.find_range("beta", 1, 10).await.unwrap();
+        assert_eq!(ids(&betas), ids(&all[2..4]));
+        assert_eq!(repo.count("beta").await.unwrap(), 3);
+        assert_eq!(repo.find_position("beta", all[2].file_path()).await.unwrap(), Some(1));
+        assert_eq!(repo.find_position("beta", all[0].file_path()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_songs_sort_naturally() {
+        let repo = create_test_repository().await;
+        for (name, title) in [("a", "Track 10"), ("b", "track 2"), ("c", "Track 1"), ("d", "Interlude")] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                title.to_string(),
+                "Artist".to_string(),
+                "Album".to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            repo.save(&song).await.unwrap();
+        }
+
+        let titles = |songs: Vec<Song>| songs.iter().map(|song| song.title().to_string()).collect::<Vec<_>>();
+        assert_eq!(titles(repo.find_all().await.unwrap()), ["Interlude", "Track 1", "track 2", "Track 10"]);
+
+        // Ranges follow the same order
+        assert_eq!(titles(repo.find_range("", 2, 10).await.unwrap()), ["track 2", "Track 10"]);
+    }
+
+    #[tokio::test]
+    async fn test_next_album() {
+        let repo = create_test_repository().await.with_sort_articles(&["The".to_string()]);
+        let mut songs = Vec::new();
+        for (name, artist, album) in [
+            ("a10", "alpha", "first"),
+            ("a1", "Alpha", "First"),
+            ("a2", "Alpha", "First"),
+            ("a3", "Alpha", "Second"),
+            ("b1", "The Beta", "First"),
+            ("b2", "The Beta", "Other"),
+            ("c1", "Charlie", "Only"),
+        ] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                name.to_string(),
+                artist.to_string(),
+                album.to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            repo.save(&song).await.unwrap();
+            songs.push(song);
+        }
+
+        let titles = |songs: Vec<Song>| songs.iter().map(|song| song.title().to_string()).collect::<Vec<_>>();
+        // Albums ignore case and their tracks sort naturally
+        assert_eq!(titles(repo.find_album("ALPHA", "First").await.unwrap()), ["a1", "a2", "a10"]);
+        // The artist's next album, then the next artist without its article,
+        // then round again
+        assert_eq!(titles(repo.find_next_album(songs[2].id()).await.unwrap()), ["a3"]);
+        assert_eq!(titles(repo.find_next_album(songs[3].id()).await.unwrap()), ["b1"]);
+        assert_eq!(titles(repo.find_next_album(songs[5].id()).await.unwrap()), ["c1"]);
+        assert_eq!(titles(repo.find_next_album(songs[6].id()).await.unwrap()), ["a1", "a2", "a10"]);
+
+        // The only album has none after it
+        for song in &songs[1..] {
+            repo.delete(song.id()).await.unwrap();
+        }
+        assert!(repo.find_next_album(songs[0].id()).await.unwrap().is_empty());
+        assert!(repo.find_next_album(songs[1].id()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_added_since() {
+        let repo = create_test_repository().await;
+
+        let old = Song::new(
+            FilePath::new("/test/old.mp3").unwrap(),
+            "Old Song".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(120),
+        ).unwrap();
+        let new = Song::new(
+            FilePath::new("/test/new.mp3").unwrap(),
+            "New Song".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(120),
+        ).unwrap();
+        repo.save(&old).await.unwrap();
+        repo.save(&new).await.unwrap();
+
+        checkout(&repo.pool).unwrap().execute(
+            "UPDATE songs SET created_at = '2000-01-01 00:00:00' WHERE id = ?1",
+            [old.id().as_str()],
+        ).unwrap();
+
+        // Saving an existing song again must not reset when it was added
+        repo.save(&old).await.unwrap();
+
+        let since = Utc::now() - chrono::Duration::days(7);
+        let recent = repo.find_added_since(since).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].title(), "New Song");
+    }
+
+    #[tokio::test]
+    async fn test_play_counts() {
+        let repo = create_test_repository().await;
+
+        let mut songs = Vec::new();
+        for name in ["a", "b", "c"] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                name.to_string(),
+                "Artist".to_string(),
+                "Album".to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            repo.save(&song).await.unwrap();
+            songs.push(song);
+        }
+
+        repo.record_play(songs[1].id()).await.unwrap();
+        repo.record_play(songs[1].id()).await.unwrap();
+        repo.record_play(songs[0].id()).await.unwrap();
+
+        // Rescanning a song must keep its play count
+        repo.save(&songs[1]).await.unwrap();
+
+        let most_played = repo.find_most_played(10).await.unwrap();
+        let titles: Vec<&str> = most_played.iter().map(|song| song.title()).collect();
+        assert_eq!(titles, ["b", "a"]);
+        assert_eq!(repo.find_most_played(1).await.unwrap().len(), 1);
+
+        let never_played = repo.find_never_played().await.unwrap();
+        assert_eq!(never_played.len(), 1);
+        assert_eq!(never_played[0].title(), "c");
+
+        let stats = repo.find_all_with_stats().await.unwrap();
+        assert_eq!(stats.len(), 3);
+        let b = stats.iter().find(|stats| stats.song.title() == "b").unwrap();
+        assert_eq!(b.play_count, 2);
+        assert!(b.last_played_at.is_some());
+        let c = stats.iter().find(|stats| stats.song.title() == "c").unwrap();
+        assert_eq!(c.play_count, 0);
+        assert_eq!(c.last_played_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_import_stats_merges() {
+        let repo = create_test_repository().await;
+        let song = Song::new(
+            FilePath::new("/test/a.mp3").unwrap(),
+            "a".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(120),
+        ).unwrap();
+        repo.save(&song).await.unwrap();
+        repo.record_play(song.id()).await.unwrap();
+
+        let long_ago = chrono::DateTime::parse_from_rfc3339("2015-06-01T12:00:00Z").unwrap().to_utc();
+        let imported = ImportedStats { play_count: 40, last_played_at: Some(long_ago), rating: Some(4) };
+        repo.import_stats(song.id(), &imported).await.unwrap();
+        // Importing again changes nothing, and a second rating does not
+        // replace the first
+        repo.import_stats(song.id(), &ImportedStats { rating: Some(2), ..imported }).await.unwrap();
+
+        let stats = repo.find_all_with_stats().await.unwrap();
+        assert_eq!(stats[0].play_count, 40);
+        assert_eq!(stats[0].rating, Some(4));
+        // The play just recorded is later than the imported one
+        assert!(stats[0].last_played_at.unwrap() > long_ago);
+    }
+
+    #[tokio::test]
+    async fn test_loved_songs() {
+        let repo = create_test_repository().await;
+
+        let mut songs = Vec::new();
+        for name in ["a", "b"] {
+            let song = Song::new(
+                FilePath::new(&format!("/test/{}.mp3", name)).unwrap(),
+                name.to_string(),
+                "Artist".to_string(),
+                "Album".to_string(),
+                Duration::from_seconds(120),
+            ).unwrap();
+            repo.save(&song).await.unwrap();
+            songs.push(song);
+        }
+
+        repo.set_loved(songs[0].id(), true).await.unwrap();
+        repo.set_loved(songs[1].id(), true).await.unwrap();
+        repo.set_loved(songs[1].id(), false).await.unwrap();
+
+        // Rescanning a song must keep it loved
+        repo.save(&songs[0]).await.unwrap();
+
+        let loved = repo.find_loved().await.unwrap();
+        let titles: Vec<&str> = loved.iter().map(|song| song.title()).collect();
+        assert_eq!(titles, ["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_song_trim() {
+        let repo = create_test_repository().await;
+        let song = Song::new(
+            FilePath::new("/test/intro.mp3").unwrap(),
+            "Intro".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(240),
+        ).unwrap();
+        repo.save(&song).await.unwrap();
+
+        assert_eq!(repo.find_trim(song.id()).await.unwrap(), Some(Trim::default()));
+        let trim = Trim::new(Duration::from_seconds(5), Duration::from_seconds(20));
+        repo.set_trim(song.id(), trim).await.unwrap();
+
+        // Rescanning a song must keep its trim
+        repo.save(&song).await.unwrap();
+        assert_eq!(repo.find_trim(song.id()).await.unwrap(), Some(trim));
+
+        let missing = SongId::from_string("missing".to_string());
+        assert_eq!(repo.find_trim(&missing).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_relocate_song() {
+        let repo = create_test_repository().await;
+        crate::infrastructure::repositories::SqlitePlaylistRepository::new(repo.pool.clone())
+            .initialize_schema()
+            .unwrap();
+        crate::infrastructure::repositories::SqlitePlaylistSongRepository::new(repo.pool.clone())
+            .initialize_schema()
+            .unwrap();
+        crate::infrastructure::repositories::SqliteBookmarkRepository::new(repo.pool.clone())
+            .initialize_schema()
+            .unwrap();
+
+        let song = Song::new(
+            FilePath::new("/test/old.mp3").unwrap(),
+            "Song".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(120),
+        ).unwrap();
+        repo.save(&song).await.unwrap();
+        let conn = checkout(&repo.pool).unwrap();
+        conn.execute("INSERT INTO playlists (id, name) VALUES ('list', 'List')", []).unwrap();
+        conn.execute(
+            "INSERT INTO playlist_songs (playlist_id, song_id, position) VALUES ('list', ?1, 0)",
+            [song.id().as_str()],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO bookmarks (song_id, position, name) VALUES (?1, 60, 'Chorus')",
+            [song.id().as_str()],
+        ).unwrap();
+        drop(conn);
+
+        let new_path = FilePath::new("/test/Artist/new.mp3").unwrap();
+        let moved = repo.relocate(song.id(), &new_path).await.unwrap();
+        assert_eq!(moved.file_path(), &new_path);
+        assert_eq!(moved.id(), &SongId::from_path(&new_path));
+        assert!(repo.find_by_id(song.id()).await.unwrap().is_none());
+
+        // Playlist entries follow the song to its new ID
+        let entry: String = checkout(&repo.pool).unwrap().query_row(
+            "SELECT song_id FROM playlist_songs WHERE playlist_id = 'list'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(entry, moved.id().as_str());
+
+        // So do bookmarks
+        let bookmarked: String = checkout(&repo.pool).unwrap().query_row(
+            "SELECT song_id FROM bookmarks WHERE name = 'Chorus'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(bookmarked, moved.id().as_str());
+
+        assert!(repo.relocate(song.id(), &new_path).await.is_err());
+    }
 }
@@ -7,11 +7,23 @@ pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod error;
+pub mod i18n;
 pub mod library;
+pub mod logging;
+pub mod media_controls;
 pub mod models;
+pub mod notifications;
+pub mod plugins;
+pub mod scrobbling;
 pub mod services;
 pub mod ui;
 
+// Clean architecture layers
+pub mod application;
+pub mod domain;
+pub mod infrastructure;
+pub mod shared;
+
 // Re-export commonly used types for convenience
 pub use error::{LofiTurtleError, Result};
 pub use ui::{ThemeManager, Themes};
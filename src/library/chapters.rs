@@ -0,0 +1,212 @@
+//! Chapters of long files, such as whole albums or mixes
+//!
+//! Chapters come from a CUE sheet next to the file, named after it
+//! (`mix.cue` or `mix.flac.cue`), or else from `CHAPTER001`/`CHAPTER001NAME`
+//! tags, the convention for chapters in Vorbis comments.
+
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::ItemKey;
+use std::path::Path;
+use std::time::Duration;
+
+/// Frames of a CUE sheet time per second
+const CUE_FRAMES_PER_SECOND: u64 = 75;
+
+/// A chapter of a song
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start: Duration,
+    pub title: Option<String>,
+}
+
+/// Chapters of the file at `path`, in order; empty if it has none
+pub fn read(path: &Path) -> Vec<Chapter> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".cue");
+    for cue in [path.with_extension("cue"), sidecar.into()] {
+        if let Ok(text) = std::fs::read_to_string(&cue) {
+            let chapters = parse_cue(&text, &file_name);
+            if !chapters.is_empty() {
+                return chapters;
+            }
+        }
+    }
+
+    let Ok(tagged_file) = Probe::open(path).and_then(|probe| probe.read()) else {
+        return Vec::new();
+    };
+    let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) else {
+        return Vec::new();
+    };
+    from_tags(tag.items().filter_map(|item| match (item.key(), item.value().text()) {
+        (ItemKey::Unknown(key), Some(value)) => Some((key.as_str(), value)),
+        _ => None,
+    }))
+}
+
+/// Chapters of `file_name` in a CUE sheet, from the `INDEX 01` of each
+/// track; a sheet with a single `FILE` is taken to be about the file
+/// whatever it names
+pub fn parse_cue(text: &str, file_name: &str) -> Vec<Chapter> {
+    let mut files = 0;
+    let mut file = None;
+    // File, title and start of each track
+    let mut tracks: Vec<(Option<String>, Option<String>, Option<Duration>)> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command.to_ascii_uppercase().as_str() {
+            "FILE" => {
+                files += 1;
+                file = Some(quoted(rest));
+            }
+            "TRACK" => tracks.push((file.clone(), None, None)),
+            "TITLE" => {
+                if let Some(track) = tracks.last_mut() {
+                    track.1 = Some(quoted(rest)).filter(|title| !title.is_empty());
+                }
+            }
+            "INDEX" => {
+                let mut parts = rest.split_whitespace();
+                if let (Some(track), Some("01"), Some(time)) = (tracks.last_mut(), parts.next(), parts.next()) {
+                    track.2 = cue_time(time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut chapters: Vec<Chapter> = tracks
+        .into_iter()
+        .filter(|(file, _, _)| {
+            files == 1 || file.as_deref().is_some_and(|file| same_file(file, file_name))
+        })
+        .filter_map(|(_, title, start)| Some(Chapter { start: start?, title }))
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Chapters in `CHAPTERnnn` tags holding `HH:MM:SS.mmm` starts, titled by
+/// the matching `CHAPTERnnnNAME`
+pub fn from_tags<'a>(items: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<Chapter> {
+    let items: Vec<(String, &str)> = items.map(|(key, value)| (key.to_ascii_uppercase(), value)).collect();
+    let mut chapters: Vec<Chapter> = items
+        .iter()
+        .filter(|(key, _)| key.strip_prefix("CHAPTER").is_some_and(|number| number.chars().all(|c| c.is_ascii_digit())))
+        .filter_map(|(key, value)| {
+            let name = format!("{}NAME", key);
+            let title = items.iter().find(|(key, _)| *key == name).map(|(_, title)| title.trim().to_string());
+            Some(Chapter { start: tag_time(value)?, title: title.filter(|title| !title.is_empty()) })
+        })
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Index of the chapter playing `position` seconds in, if any has started
+pub fn current(chapters: &[Chapter], position: u64) -> Option<usize> {
+    chapters.iter().rposition(|chapter| chapter.start.as_secs() <= position)
+}
+
+/// Text of a CUE argument, without its quotes and any file type after it
+fn quoted(text: &str) -> String {
+    match text.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or_default().to_string(),
+        None => text.split_whitespace().next().unwrap_or_default().to_string(),
+    }
+}
+
+/// Whether a CUE sheet's `FILE` names the file called `file_name`
+fn same_file(file: &str, file_name: &str) -> bool {
+    let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+    name.eq_ignore_ascii_case(file_name)
+}
+
+/// A CUE time, `MM:SS:FF` in frames of 1/75 s
+fn cue_time(text: &str) -> Option<Duration> {
+    let mut parts = text.split(':').map(|part| part.parse::<u64>().ok());
+    let (minutes, seconds, frames) = (parts.next()??, parts.next()??, parts.next()??);
+    let millis = (minutes * 60 + seconds) * 1000 + frames * 1000 / CUE_FRAMES_PER_SECOND;
+    Some(Duration::from_millis(millis))
+}
+
+/// A chapter tag time, `HH:MM:SS` with optional fractions of a second
+fn tag_time(text: &str) -> Option<Duration> {
+    let mut parts = text.trim().split(':');
+    let (hours, minutes, seconds) = (parts.next()?, parts.next()?, parts.next()?);
+    let seconds: f64 = seconds.parse().ok()?;
+    let whole = hours.parse::<u64>().ok()? * 3600 + minutes.parse::<u64>().ok()? * 60;
+    (seconds >= 0.0 && parts.next().is_none()).then(|| Duration::from_secs(whole) + Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUE: &str = r#"
+PERFORMER "Various"
+TITLE "Night Mix"
+FILE "night mix.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Intro"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Rain"
+    INDEX 00 03:58:00
+    INDEX 01 04:00:30
+"#;
+
+    #[test]
+    fn test_cue_chapters() {
+        let chapters = parse_cue(CUE, "night mix.flac");
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter { start: Duration::ZERO, title: Some("Intro".to_string()) },
+                Chapter { start: Duration::from_millis(240_400), title: Some("Rain".to_string()) },
+            ]
+        );
+        // A single file is this one whatever the sheet calls it
+        assert_eq!(parse_cue(CUE, "renamed.flac").len(), 2);
+    }
+
+    #[test]
+    fn test_cue_with_several_files_keeps_this_one() {
+        let cue = "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n\
+                   FILE \"B.WAV\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\nTRACK 03 AUDIO\nINDEX 01 01:00:00";
+        assert_eq!(parse_cue(cue, "b.wav").len(), 2);
+        assert!(parse_cue(cue, "c.wav").is_empty());
+    }
+
+    #[test]
+    fn test_tag_chapters() {
+        let tags = [
+            ("CHAPTER002", "00:10:00.500"),
+            ("CHAPTER002NAME", "Second"),
+            ("CHAPTER001", "00:00:00.000"),
+            ("COMMENT", "00:01:00"),
+            ("CHAPTER003", "soon"),
+        ];
+        let chapters = from_tags(tags.into_iter());
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter { start: Duration::ZERO, title: None },
+                Chapter { start: Duration::from_millis(600_500), title: Some("Second".to_string()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_current_chapter() {
+        let chapters = parse_cue(CUE, "night mix.flac");
+        assert_eq!(current(&chapters, 0), Some(0));
+        assert_eq!(current(&chapters, 239), Some(0));
+        assert_eq!(current(&chapters, 240), Some(1));
+        assert_eq!(current(&[], 10), None);
+    }
+}
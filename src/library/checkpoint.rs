@@ -0,0 +1,136 @@
+//! Checkpoints that let an interrupted scan resume
+//!
+//! While a scan adds songs to the library, the paths of every batch that was
+//! committed are appended to a journal file next to the database. If the scan
+//! is cancelled, or the app quits or crashes, the journal stays behind and the
+//! next scan of the same root skips those files. A scan that finishes removes
+//! the journal, so the one after it reads everything again.
+
+use crate::models::Song;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// First line of a journal, naming the root it belongs to
+const ROOT_PREFIX: &str = "# root: ";
+
+/// Journal of the files an unfinished scan has already added
+pub struct ScanCheckpoint {
+    path: PathBuf,
+    file: File,
+    done: HashSet<PathBuf>,
+}
+
+impl ScanCheckpoint {
+    /// Journal kept for the library in the database at `database_path`
+    pub fn path_for(database_path: &Path) -> PathBuf {
+        database_path.with_extension("scan-checkpoint")
+    }
+
+    /// Open the journal at `path`, picking up where an unfinished scan of
+    /// `root` left off, or starting afresh for any other root
+    pub fn open(path: &Path, root: &Path) -> io::Result<Self> {
+        let header = format!("{}{}", ROOT_PREFIX, root.display());
+        let done = match File::open(path) {
+            Ok(file) => {
+                let mut lines = BufReader::new(file).lines();
+                match lines.next().transpose()? {
+                    Some(first) if first == header => lines
+                        .map(|line| line.map(PathBuf::from))
+                        .collect::<io::Result<_>>()?,
+                    _ => HashSet::new(),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e),
+        };
+
+        let file = if done.is_empty() {
+            let mut file = File::create(path)?;
+            writeln!(file, "{}", header)?;
+            file
+        } else {
+            OpenOptions::new().append(true).open(path)?
+        };
+
+        Ok(Self { path: path.to_path_buf(), file, done })
+    }
+
+    /// Files the unfinished scan already added, to be skipped this time
+    pub fn take_done(&mut self) -> HashSet<PathBuf> {
+        std::mem::take(&mut self.done)
+    }
+
+    /// Note a batch of songs that was committed to the library
+    pub fn record(&mut self, songs: &[Song]) -> io::Result<()> {
+        for song in songs {
+            writeln!(self.file, "{}", song.path)?;
+        }
+        self.file.flush()
+    }
+
+    /// The scan got through every file, so the next one starts from scratch
+    pub fn finish(self) -> io::Result<()> {
+        drop(self.file);
+        Self::discard(&self.path)
+    }
+
+    /// Forget the journal at `path`, if there is one, after the library was
+    /// rebuilt some other way
+    pub fn discard(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str) -> Song {
+        Song::new(path.to_string(), "Title".to_string(), "Artist".to_string(), "Album".to_string(), 60)
+    }
+
+    #[test]
+    fn test_resumes_same_root_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = ScanCheckpoint::path_for(&dir.path().join("library.db"));
+        let root = Path::new("/music");
+
+        let mut checkpoint = ScanCheckpoint::open(&path, root).unwrap();
+        assert!(checkpoint.take_done().is_empty());
+        checkpoint.record(&[song("/music/a.mp3"), song("/music/b.mp3")]).unwrap();
+        drop(checkpoint);
+
+        // Interrupted: the next scan of the root skips what was added
+        let mut resumed = ScanCheckpoint::open(&path, root).unwrap();
+        resumed.record(&[song("/music/c.mp3")]).unwrap();
+        drop(resumed);
+        let mut resumed = ScanCheckpoint::open(&path, root).unwrap();
+        let done = resumed.take_done();
+        assert_eq!(done.len(), 3);
+        assert!(done.contains(Path::new("/music/c.mp3")));
+
+        // A scan of another root does not use it
+        let mut other = ScanCheckpoint::open(&path, Path::new("/elsewhere")).unwrap();
+        assert!(other.take_done().is_empty());
+    }
+
+    #[test]
+    fn test_finished_scan_removes_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = ScanCheckpoint::path_for(&dir.path().join("library.db"));
+
+        let mut checkpoint = ScanCheckpoint::open(&path, Path::new("/music")).unwrap();
+        checkpoint.record(&[song("/music/a.mp3")]).unwrap();
+        checkpoint.finish().unwrap();
+        assert!(!path.exists());
+        ScanCheckpoint::discard(&path).unwrap();
+
+        let mut next = ScanCheckpoint::open(&path, Path::new("/music")).unwrap();
+        assert!(next.take_done().is_empty());
+    }
+}
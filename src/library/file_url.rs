@@ -0,0 +1,57 @@
+//! `file://` URLs, which iTunes and Music use for the locations of tracks
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use std::path::{Path, PathBuf};
+
+/// Characters escaped in the path of a URL
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// URL of the file at `path`, written as iTunes does on macOS
+/// (`file:///Users/...`) and Windows (`file://localhost/C:/Users/...`)
+pub fn from_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let host = if path.starts_with('/') { "" } else { "localhost/" };
+    format!("file://{}{}", host, utf8_percent_encode(&path, PATH))
+}
+
+/// Path of a `file://` URL, in either of the forms [`from_path`] writes
+pub fn to_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    // A drive letter follows the slash that starts the path
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => &path[1..],
+        _ => &path[..],
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_urls_round_trip() {
+        let path = Path::new("/Users/me/Music/Café #1 [live].mp3");
+        let url = from_path(path);
+        assert_eq!(url, "file:///Users/me/Music/Caf%C3%A9%20%231%20%5Blive%5D.mp3");
+        assert_eq!(to_path(&url).as_deref(), Some(path));
+
+        assert_eq!(from_path(Path::new("C:\\Music\\a b.mp3")), "file://localhost/C:/Music/a%20b.mp3");
+        assert_eq!(to_path("file://localhost/C:/Music/a%20b.mp3"), Some(PathBuf::from("C:/Music/a b.mp3")));
+        assert_eq!(to_path("http://example.com/a.mp3"), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Genre normalization rules
+//!
+//! Tags spell the same genre many ways ("Hip-Hop/Rap", "hiphop", "lo-fi"),
+//! which splits one genre into several. `genres.toml` maps each genre to the
+//! spellings that should become it:
+//!
+//! ```toml
+//! "Hip Hop" = ["Hip-Hop/Rap", "Rap/Hip-Hop"]
+//! Lofi = ["lo-fi hip hop", "chillhop"]
+//! ```
+//!
+//! Spellings match ignoring case, spaces and punctuation, so "lofi", "Lo-Fi"
+//! and "LO FI" all become "Lofi" without being listed. Genres no rule
+//! matches are kept as tagged.
+
+use crate::error::{LofiTurtleError, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Genres by the folded spellings that become them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenreRules {
+    genres: HashMap<String, String>,
+}
+
+impl GenreRules {
+    /// Read rules from a `genres.toml`, none if there is no such file
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|message| LofiTurtleError::Configuration(format!("{}: {}", path.display(), message))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Parse rules from a table of genres to a spelling or list of them
+    fn parse(content: &str) -> std::result::Result<Self, String> {
+        let table: toml::Table = content.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let mut rules = Self::default();
+        for (genre, spellings) in table {
+            let spellings = match spellings {
+                toml::Value::String(spelling) => vec![spelling],
+                toml::Value::Array(spellings) => spellings
+                    .into_iter()
+                    .map(|spelling| match spelling {
+                        toml::Value::String(spelling) => Ok(spelling),
+                        _ => Err(format!("'{}' must list spellings as strings", genre)),
+                    })
+                    .collect::<std::result::Result<_, _>>()?,
+                _ => return Err(format!("'{}' must be a spelling or a list of them", genre)),
+            };
+
+            // A genre's own name is one of its spellings
+            for spelling in spellings.iter().chain([&genre]) {
+                rules.genres.insert(fold(spelling), genre.clone());
+            }
+        }
+        Ok(rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.genres.is_empty()
+    }
+
+    /// The genre a tagged `genre` becomes
+    pub fn normalize(&self, genre: &str) -> String {
+        self.genres.get(&fold(genre)).cloned().unwrap_or_else(|| genre.trim().to_string())
+    }
+}
+
+/// Spelling without case, spaces or punctuation
+fn fold(spelling: &str) -> String {
+    spelling.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_matches_any_spelling() {
+        let rules = GenreRules::parse(
+            r#"
+            "Hip Hop" = ["Hip-Hop/Rap", "Rap/Hip-Hop"]
+            Lofi = "chillhop"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.normalize("hip-hop/rap"), "Hip Hop");
+        assert_eq!(rules.normalize("HIPHOP"), "Hip Hop");
+        assert_eq!(rules.normalize("lo-fi"), "Lofi");
+        assert_eq!(rules.normalize(" Chillhop "), "Lofi");
+        assert_eq!(rules.normalize(" Jazz "), "Jazz");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_values() {
+        assert!(GenreRules::parse("Lofi = 1").is_err());
+        assert!(GenreRules::parse("Lofi = [\"lo-fi\", 2]").is_err());
+        assert!(GenreRules::parse("Lofi = ").is_err());
+        assert!(GenreRules::parse("").unwrap().is_empty());
+    }
+}
@@ -1,5 +1,13 @@
-pub mod database;
+pub mod chapters;
+pub mod file_url;
+pub mod genres;
+pub mod checkpoint;
+pub mod organizer;
 pub mod scanner;
+pub mod tag_pattern;
+pub mod tagger;
+pub mod tags;
 
-pub use database::Database;
-pub use scanner::MusicScanner;
+pub use checkpoint::ScanCheckpoint;
+pub use genres::GenreRules;
+pub use scanner::{CancelToken, MusicScanner, ScanIssue, ScanOptions, ScanProgress, ScanReport};
@@ -0,0 +1,130 @@
+use super::tag_pattern::TagPattern;
+use super::tags::TrackTags;
+use crate::models::Song;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A file that would move to a new location
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMove {
+    pub song_id: String,
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// A file that cannot be moved
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Moves needed to bring a library in line with a pattern
+#[derive(Debug, Default)]
+pub struct OrganizePlan {
+    pub moves: Vec<PlannedMove>,
+    pub skipped: Vec<SkippedFile>,
+    /// Files that are already where the pattern puts them
+    pub unchanged: usize,
+}
+
+/// Work out where every song under `root` belongs
+///
+/// Files whose target is already taken, either on disk or by another song in
+/// the plan, are skipped rather than overwritten.
+pub fn plan(root: &Path, songs: &[Song], pattern: &TagPattern) -> OrganizePlan {
+    let mut plan = OrganizePlan::default();
+    let mut claimed = HashSet::new();
+
+    for song in songs {
+        let from = PathBuf::from(&song.path);
+        if !from.starts_with(root) {
+            continue;
+        }
+
+        let tags = match TrackTags::read(&from) {
+            Ok(tags) => tags,
+            Err(e) => {
+                plan.skipped.push(SkippedFile { path: from, reason: e.to_string() });
+                continue;
+            }
+        };
+
+        let to = root.join(pattern.render(&tags, &from));
+        if to == from {
+            claimed.insert(to);
+            plan.unchanged += 1;
+            continue;
+        }
+
+        if to.exists() || !claimed.insert(to.clone()) {
+            plan.skipped.push(SkippedFile {
+                path: from,
+                reason: format!("{} already exists", to.display()),
+            });
+            continue;
+        }
+
+        plan.moves.push(PlannedMove {
+            song_id: song.id.clone(),
+            from,
+            to,
+        });
+    }
+
+    plan
+}
+
+/// Move a file, creating the target's parent directories
+///
+/// Falls back to copy and delete when the target is on another filesystem.
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    fs::copy(from, to)?;
+    if let Err(e) = fs::remove_file(from) {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Remove `dir` and its parents up to `root` while they are empty
+pub fn remove_empty_dirs(dir: &Path, root: &Path) {
+    let mut current = Some(dir);
+    while let Some(dir) = current {
+        if dir == root || !dir.starts_with(root) || fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_file_creates_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("old/song.mp3");
+        let to = dir.path().join("Artist/Album/song.mp3");
+        fs::create_dir_all(from.parent().unwrap()).unwrap();
+        fs::write(&from, "audio").unwrap();
+
+        move_file(&from, &to).unwrap();
+        remove_empty_dirs(from.parent().unwrap(), dir.path());
+
+        assert_eq!(fs::read_to_string(&to).unwrap(), "audio");
+        assert!(!dir.path().join("old").exists());
+        assert!(dir.path().exists());
+    }
+}
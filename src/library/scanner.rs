@@ -1,47 +1,519 @@
+use crate::art::CoverCache;
 use crate::models::Song;
 use crate::error::{LofiTurtleError, Result};
+use crate::library::GenreRules;
 use lofty::prelude::*;
 use lofty::probe::Probe;
-use std::fs;
-use std::path::Path;
+use std::collections::HashSet;
+use std::fs::{self, FileType};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub struct MusicScanner;
+/// Files waiting for a worker to read their tags
+const PATH_QUEUE_SIZE: usize = 64;
+
+/// Songs read but not yet taken by the writer
+const SONG_QUEUE_SIZE: usize = 256;
+
+/// Songs handed to the writer at a time
+const WRITE_BATCH_SIZE: usize = 500;
+
+/// Songs waiting for the art stage; it skips songs rather than hold the
+/// writer up when this is full
+const ART_QUEUE_SIZE: usize = 64;
+
+/// Most threads reading tags when the options leave it to the scanner
+const MAX_DEFAULT_WORKERS: usize = 4;
+
+/// Options controlling how the scanner walks the filesystem
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Descend into symlinked directories and index symlinked files. Off by
+    /// default, so links leading out of the library are only followed when
+    /// asked for
+    pub follow_symlinks: bool,
+    /// Give up on a directory listing or a file's tags that take longer
+    /// than this, which keeps a stalled NFS/SMB mount from hanging the whole
+    /// scan. Each read then runs on a helper thread of its own
+    pub io_timeout: Option<Duration>,
+    /// Read at most this many files a second, leaving the disk free enough
+    /// for playback while a scan runs in the background
+    pub max_files_per_sec: Option<u32>,
+    /// Threads reading tags, or 0 to pick from the number of CPUs
+    pub workers: usize,
+    /// Spellings of genres to store as another
+    pub genre_rules: GenreRules,
+    /// Where to keep the cover of each album whose songs have art, or
+    /// nowhere
+    pub covers: Option<CoverCache>,
+}
+
+/// A path the scan could not use, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanIssue {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl ScanIssue {
+    fn new(path: &Path, reason: impl ToString) -> Self {
+        Self { path: path.to_path_buf(), reason: reason.to_string() }
+    }
+}
+
+/// Result of scanning one or more library roots
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub songs: Vec<Song>,
+    /// Roots that could not be read at all, e.g. an unmounted network share
+    pub offline_roots: Vec<PathBuf>,
+    /// Directories and entries skipped because of IO errors
+    pub skipped: Vec<ScanIssue>,
+    /// Audio files whose tags could not be read
+    pub failed: Vec<ScanIssue>,
+    /// The scan was cancelled, so `songs` holds only the files read before then
+    pub cancelled: bool,
+    /// Files found but left out because an earlier scan already read them
+    pub resumed: usize,
+}
+
+impl ScanReport {
+    /// Check if the given root was unreachable during the scan
+    pub fn is_offline<P: AsRef<Path>>(&self, root: P) -> bool {
+        self.offline_roots.iter().any(|r| r == root.as_ref())
+    }
+}
+
+/// Flag a running scan checks between files, so another thread can stop it
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the scan to stop after the file it is reading
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a scan has got through the audio files it found
+#[derive(Debug, Clone)]
+pub struct ScanProgress {
+    /// Files read so far
+    pub processed: usize,
+    /// Audio files found, known once the directories have been walked
+    pub total: usize,
+    /// File read most recently
+    pub current_file: Option<PathBuf>,
+    pub started: Instant,
+}
+
+impl Default for ScanProgress {
+    fn default() -> Self {
+        Self {
+            processed: 0,
+            total: 0,
+            current_file: None,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl ScanProgress {
+    /// Files read per second since the scan started
+    pub fn rate(&self) -> f64 {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            self.processed as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// Time left at the current rate, once there is a rate to go by
+    pub fn eta(&self) -> Option<Duration> {
+        let rate = self.rate();
+        if self.processed == 0 || rate <= 0.0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.processed) as f64;
+        Some(Duration::from_secs_f64(remaining / rate))
+    }
+}
+
+pub struct MusicScanner {
+    options: ScanOptions,
+    /// Files to leave out, e.g. those an interrupted scan already added
+    skip: HashSet<PathBuf>,
+}
+
+impl Default for MusicScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl MusicScanner {
     pub fn new() -> Self {
-        Self
+        Self::with_options(ScanOptions::default())
+    }
+
+    /// Create a scanner with custom filesystem options
+    pub fn with_options(options: ScanOptions) -> Self {
+        Self { options, skip: HashSet::new() }
+    }
+
+    /// Leave `paths` out of the scan, to resume one that was interrupted
+    pub fn skipping(mut self, paths: HashSet<PathBuf>) -> Self {
+        self.skip = paths;
+        self
     }
 
     /// Scan directory and return a list of songs
     /// This version collects all songs into a vector
     pub fn scan_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<Song>> {
+        let mut report = ScanReport::default();
+        let mut files = Vec::new();
+        let cancel = CancelToken::new();
+        self.scan_recursive(dir_path.as_ref(), &mut files, &mut report, &mut HashSet::new(), &cancel)
+            .map_err(LofiTurtleError::FileSystem)?;
+
         let mut songs = Vec::new();
-        self.scan_recursive(dir_path.as_ref(), &mut songs)?;
+        self.read_files(files, &mut report, &cancel, |_| {}, |batch| {
+            songs.extend_from_slice(batch);
+            Ok(())
+        })?;
         Ok(songs)
     }
 
-    fn scan_recursive(&self, dir: &Path, songs: &mut Vec<Song>) -> Result<()> {
-        let entries = fs::read_dir(dir).map_err(|e| LofiTurtleError::FileSystem(e))?;
+    /// Scan several library roots, marking unreachable ones as offline
+    /// instead of failing the whole scan
+    #[allow(dead_code)] // Kept for library users; the app scans through scan_roots_with
+    pub fn scan_roots<P: AsRef<Path>>(&self, roots: &[P]) -> ScanReport {
+        self.scan_roots_with(roots, &CancelToken::new(), |_| {})
+    }
 
-        for entry in entries {
-            let entry = entry.map_err(LofiTurtleError::FileSystem)?;
-            let path = entry.path();
+    /// Scan several library roots until done or cancelled through `cancel`,
+    /// collecting the songs into the report
+    pub fn scan_roots_with<P, F>(&self, roots: &[P], cancel: &CancelToken, on_progress: F) -> ScanReport
+    where
+        P: AsRef<Path>,
+        F: FnMut(&ScanProgress),
+    {
+        let mut songs = Vec::new();
+        let result = self.scan_roots_into(roots, cancel, on_progress, |batch| {
+            songs.extend_from_slice(batch);
+            Ok(())
+        });
 
-            if path.is_dir() {
-                if let Err(e) = self.scan_recursive(&path, songs) {
-                    log::warn!("Failed to scan directory {}: {}", path.display(), e);
+        // Collecting into memory cannot fail
+        let mut report = result.unwrap_or_default();
+        report.songs = songs;
+        report
+    }
+
+    /// Read the tags of the given audio files until done or cancelled
+    /// through `cancel`, collecting the songs into the report
+    pub fn scan_files_with<F>(&self, files: Vec<PathBuf>, cancel: &CancelToken, on_progress: F) -> ScanReport
+    where
+        F: FnMut(&ScanProgress),
+    {
+        let mut report = ScanReport::default();
+        let mut songs = Vec::new();
+        // Collecting into memory cannot fail
+        let _ = self.read_files(files, &mut report, cancel, on_progress, |batch| {
+            songs.extend_from_slice(batch);
+            Ok(())
+        });
+        report.songs = songs;
+        report
+    }
+
+    /// Scan several library roots, handing the songs to `write` in batches
+    /// as they are read instead of keeping them all
+    ///
+    /// The roots are walked first to find the audio files. Then a producer
+    /// hands the files out, workers read their tags on threads of their own
+    /// and `write` runs on the calling thread, with bounded queues between
+    /// these stages, so reading and writing overlap without a slow writer
+    /// letting songs pile up in memory. With a cover cache in the options,
+    /// an art stage beside the writer caches the embedded cover of each
+    /// album from the songs the writer passes on. `on_progress` is called on
+    /// the calling thread after every file.
+    ///
+    /// If `write` fails, the scan stops and the error is returned.
+    pub fn scan_roots_into<P, F, W>(&self, roots: &[P], cancel: &CancelToken, on_progress: F, write: W) -> Result<ScanReport>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&ScanProgress),
+        W: FnMut(&[Song]) -> Result<()>,
+    {
+        let mut report = ScanReport::default();
+        let mut files = Vec::new();
+        let mut visited = HashSet::new();
+
+        for root in roots {
+            let root = root.as_ref();
+            if let Err(e) = self.scan_recursive(root, &mut files, &mut report, &mut visited, cancel) {
+                log::warn!("Library root {} is offline: {}", root.display(), e);
+                report.offline_roots.push(root.to_path_buf());
+            }
+        }
+
+        let found = files.len();
+        files.retain(|path| !self.skip.contains(path));
+        report.resumed = found - files.len();
+
+        self.read_files(files, &mut report, cancel, on_progress, write)?;
+        Ok(report)
+    }
+
+    /// Read the tags of `files` through the worker pipeline, stopping early
+    /// if cancelled
+    fn read_files<F, W>(&self, files: Vec<PathBuf>, report: &mut ScanReport, cancel: &CancelToken, mut on_progress: F, mut write: W) -> Result<()>
+    where
+        F: FnMut(&ScanProgress),
+        W: FnMut(&[Song]) -> Result<()>,
+    {
+        let mut progress = ScanProgress { total: files.len(), ..ScanProgress::default() };
+        on_progress(&progress);
+
+        // Set when writing fails, so the other stages wind down too
+        let halt = CancelToken::new();
+        let stopped = || cancel.is_cancelled() || halt.is_cancelled();
+        let mut write_error = None;
+
+        let (path_sender, path_receiver) = mpsc::sync_channel::<PathBuf>(PATH_QUEUE_SIZE);
+        let (song_sender, song_receiver) = mpsc::sync_channel::<(PathBuf, Result<Song>)>(SONG_QUEUE_SIZE);
+        let path_receiver = Mutex::new(path_receiver);
+        // Helper threads reading with a timeout may outlive the scan
+        let detached = self.options.io_timeout.map(|timeout| (Arc::new(Self::with_options(self.options.clone())), timeout));
+        let detached = &detached;
+
+        // Art stage: on a thread outside the scope, since with a timeout set
+        // a stalled mount could hold it up and the scan must not wait for it
+        let (art_sender, art_stage) = match &self.options.covers {
+            Some(covers) => {
+                let (art_sender, art_receiver) = mpsc::sync_channel::<(PathBuf, String)>(ART_QUEUE_SIZE);
+                let (covers, cancel) = (covers.clone(), cancel.clone());
+                let stage = thread::Builder::new()
+                    .name("scan-art".to_string())
+                    .spawn(move || cache_covers(&covers, art_receiver, &cancel));
+                match stage {
+                    Ok(stage) => (Some(art_sender), Some(stage)),
+                    Err(e) => {
+                        log::warn!("Scanning without caching covers: {}", e);
+                        (None, None)
+                    }
+                }
+            }
+            None => (None, None),
+        };
+
+        thread::scope(|scope| {
+            // Producer: hands out files no faster than the rate limit allows
+            scope.spawn(move || {
+                let started = Instant::now();
+                for (sent, path) in files.into_iter().enumerate() {
+                    if let Some(delay) = throttle_delay(self.options.max_files_per_sec, sent, started.elapsed()) {
+                        thread::sleep(delay);
+                    }
+                    if stopped() || path_sender.send(path).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            // Workers: read tags, skipping what is left once stopped
+            for _ in 0..self.worker_count() {
+                let song_sender = song_sender.clone();
+                let path_receiver = &path_receiver;
+                scope.spawn(move || loop {
+                    let next = match path_receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok(path) = next else { break };
+                    if stopped() {
+                        continue;
+                    }
+                    let song = match detached {
+                        Some((scanner, timeout)) => read_detached(scanner, &path, *timeout),
+                        None => self.extract_metadata(&path),
+                    };
+                    if song_sender.send((path, song)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(song_sender);
+
+            // Writer: batches the songs on this thread
+            let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+            for (path, song) in song_receiver {
+                match song {
+                    Ok(song) => {
+                        // A full queue skips the song; the album's others
+                        // still get their turn
+                        if let (Some(art), false) = (&art_sender, song.album.is_empty()) {
+                            let album = format!("{}\0{}", song.artist.to_lowercase(), song.album.to_lowercase());
+                            let _ = art.try_send((path.clone(), album));
+                        }
+                        batch.push(song);
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to extract metadata from {}: {}", path.display(), e);
+                        report.failed.push(ScanIssue::new(&path, e));
+                    }
+                }
+                progress.processed += 1;
+                progress.current_file = Some(path);
+                on_progress(&progress);
+
+                if batch.len() >= WRITE_BATCH_SIZE && write_error.is_none() {
+                    if let Err(e) = write(&batch) {
+                        write_error = Some(e);
+                        halt.cancel();
+                    }
+                    batch.clear();
+                }
+            }
+
+            // Songs already read are kept even when the scan was cancelled
+            if !batch.is_empty() && write_error.is_none() {
+                if let Err(e) = write(&batch) {
+                    write_error = Some(e);
+                }
+            }
+        });
+
+        // The art stage finishes the songs it was handed once the queue closes
+        drop(art_sender);
+        if let (Some(stage), None) = (art_stage, self.options.io_timeout) {
+            let _ = stage.join();
+        }
+
+        report.cancelled = cancel.is_cancelled();
+        progress.current_file = None;
+        on_progress(&progress);
+
+        match write_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn worker_count(&self) -> usize {
+        if self.options.workers > 0 {
+            return self.options.workers;
+        }
+        thread::available_parallelism()
+            .map(|count| count.get().min(MAX_DEFAULT_WORKERS))
+            .unwrap_or(1)
+    }
+
+    /// Walk `dir` collecting audio files, returning an error only if `dir`
+    /// itself cannot be listed
+    fn scan_recursive(
+        &self,
+        dir: &Path,
+        files: &mut Vec<PathBuf>,
+        report: &mut ScanReport,
+        visited: &mut HashSet<PathBuf>,
+        cancel: &CancelToken,
+    ) -> io::Result<()> {
+        if cancel.is_cancelled() {
+            return Ok(());
+        }
+
+        // Symlinked directories can form cycles, so remember where we have been
+        if self.options.follow_symlinks {
+            let canonical = fs::canonicalize(dir)?;
+            if !visited.insert(canonical) {
+                return Ok(());
+            }
+        }
+
+        for (path, file_type) in self.list_dir(dir, report)? {
+            let (is_dir, is_file) = if file_type.is_symlink() {
+                if !self.options.follow_symlinks {
+                    continue;
+                }
+                match fs::metadata(&path) {
+                    Ok(metadata) => (metadata.is_dir(), metadata.is_file()),
+                    Err(e) => {
+                        log::warn!("Skipping broken symlink {}: {}", path.display(), e);
+                        report.skipped.push(ScanIssue::new(&path, e));
+                        continue;
+                    }
                 }
-            } else if self.is_audio_file(&path) {
-                match self.extract_metadata(&path) {
-                    Ok(song) => songs.push(song),
-                    Err(e) => log::warn!("Failed to extract metadata from {}: {}", path.display(), e),
+            } else {
+                (file_type.is_dir(), file_type.is_file())
+            };
+
+            if is_dir {
+                if let Err(e) = self.scan_recursive(&path, files, report, visited, cancel) {
+                    log::warn!("Failed to scan directory {}: {}", path.display(), e);
+                    report.skipped.push(ScanIssue::new(&path, e));
                 }
+            } else if is_file && self.is_audio_file(&path) {
+                files.push(path);
             }
         }
 
         Ok(())
     }
 
+    /// List a directory, skipping unreadable entries and honouring the IO timeout
+    fn list_dir(&self, dir: &Path, report: &mut ScanReport) -> io::Result<Vec<(PathBuf, FileType)>> {
+        let entries = match self.options.io_timeout {
+            None => read_entries(dir)?,
+            Some(timeout) => {
+                // A hung mount blocks inside the kernel, so the listing runs on
+                // a helper thread that is abandoned if it does not answer in time
+                let (sender, receiver) = mpsc::channel();
+                let dir_owned = dir.to_path_buf();
+                thread::spawn(move || {
+                    let _ = sender.send(read_entries(&dir_owned));
+                });
+
+                match receiver.recv_timeout(timeout) {
+                    Ok(result) => result?,
+                    Err(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("listing {} timed out after {:?}", dir.display(), timeout),
+                        ))
+                    }
+                }
+            }
+        };
+
+        let mut listed = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match entry {
+                Ok(entry) => listed.push(entry),
+                Err(e) => {
+                    log::warn!("Skipping unreadable entry in {}: {}", dir.display(), e);
+                    report.skipped.push(ScanIssue::new(dir, e));
+                }
+            }
+        }
+
+        Ok(listed)
+    }
+
     fn is_audio_file(&self, path: &Path) -> bool {
         if let Some(extension) = path.extension() {
             let ext = extension.to_string_lossy().to_lowercase();
@@ -62,6 +534,11 @@ impl MusicScanner {
 
         let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag());
 
+        let genre = tag
+            .and_then(|tag| tag.genre())
+            .map(|genre| self.options.genre_rules.normalize(&genre))
+            .filter(|genre| !genre.is_empty());
+
         let (title, artist, album) = if let Some(tag) = tag {
             let title = tag.title()
                 .map(|t| t.to_string())
@@ -90,7 +567,7 @@ impl MusicScanner {
             artist,
             album,
             duration,
-        ))
+        ).with_genre(genre))
     }
 
     fn extract_title_from_filename(&self, path: &Path) -> String {
@@ -100,3 +577,261 @@ impl MusicScanner {
             .to_string()
     }
 }
+
+/// How long to wait before reading the next file to stay within `max_per_sec`,
+/// given how many files were read in the time elapsed so far
+fn throttle_delay(max_per_sec: Option<u32>, processed: usize, elapsed: Duration) -> Option<Duration> {
+    let max_per_sec = max_per_sec.filter(|&rate| rate > 0)?;
+    let due = Duration::from_secs_f64(processed as f64 / max_per_sec as f64);
+    due.checked_sub(elapsed).filter(|delay| !delay.is_zero())
+}
+
+/// Read the tags of `path` on a helper thread, giving up after `timeout`
+///
+/// A hung mount blocks inside the kernel, so the helper is abandoned rather
+/// than waited for.
+fn read_detached(scanner: &Arc<MusicScanner>, path: &Path, timeout: Duration) -> Result<Song> {
+    let (sender, receiver) = mpsc::channel();
+    let scanner = Arc::clone(scanner);
+    let path_owned = path.to_path_buf();
+    thread::spawn(move || {
+        let _ = sender.send(scanner.extract_metadata(&path_owned));
+    });
+
+    receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        Err(LofiTurtleError::FileSystem(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("reading {} timed out after {:?}", path.display(), timeout),
+        )))
+    })
+}
+
+/// Keep the embedded cover of each album of `songs`, given by path and album,
+/// in `covers`, trying the album's songs in turn until one has art
+fn cache_covers(covers: &CoverCache, songs: mpsc::Receiver<(PathBuf, String)>, cancel: &CancelToken) {
+    let mut cached = HashSet::new();
+    for (path, album) in songs {
+        if cancel.is_cancelled() {
+            break;
+        }
+        if cached.contains(&album) {
+            continue;
+        }
+        match covers.cache_embedded(&path) {
+            Ok(true) => {
+                cached.insert(album);
+            }
+            Ok(false) => {}
+            Err(e) => log::debug!("Not caching the cover of {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Read all entries of a directory along with their (non-followed) file types
+fn read_entries(dir: &Path) -> io::Result<Vec<io::Result<(PathBuf, FileType)>>> {
+    Ok(fs::read_dir(dir)?
+        .map(|entry| entry.and_then(|e| Ok((e.path(), e.file_type()?))))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::tags::TrackTags;
+
+    /// Write a short silent 8-bit mono WAV file
+    fn write_wav(path: &Path) {
+        let data_len: u32 = 8000;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+        bytes.extend_from_slice(&8000u32.to_le_bytes()); // sample rate
+        bytes.extend_from_slice(&8000u32.to_le_bytes()); // byte rate
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // block align
+        bytes.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 128);
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_scan_directory_finds_nested_songs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("album")).unwrap();
+        write_wav(&dir.path().join("one.wav"));
+        write_wav(&dir.path().join("album").join("two.wav"));
+        fs::write(dir.path().join("cover.jpg"), b"not audio").unwrap();
+
+        let songs = MusicScanner::new().scan_directory(dir.path()).unwrap();
+        let mut titles: Vec<_> = songs.iter().map(|s| s.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, ["one", "two"]);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        write_wav(&dir.path().join("good.wav"));
+        fs::write(dir.path().join("broken.wav"), b"not audio").unwrap();
+
+        let report = MusicScanner::new().scan_roots(&[dir.path()]);
+        assert_eq!(report.songs.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].path, dir.path().join("broken.wav"));
+        assert!(report.skipped.is_empty());
+
+        // Files given one by one are reported the same way
+        let files = vec![dir.path().join("good.wav"), dir.path().join("broken.wav")];
+        let report = MusicScanner::new().scan_files_with(files, &CancelToken::new(), |_| {});
+        assert_eq!(report.songs.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].path, dir.path().join("broken.wav"));
+    }
+
+    #[test]
+    fn test_scan_caches_album_covers() {
+        let dir = tempfile::tempdir().unwrap();
+        let tags = TrackTags { artist: Some("Nujabes".to_string()), album: Some("Modal Soul".to_string()), ..TrackTags::default() };
+        for name in ["with art", "without art"] {
+            let path = dir.path().join(format!("{}.wav", name));
+            write_wav(&path);
+            tags.write(&path).unwrap();
+        }
+        let cover = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        crate::art::embed_cover(&dir.path().join("with art.wav"), cover).unwrap();
+
+        let covers = CoverCache::new(dir.path().join("covers"));
+        let scanner = MusicScanner::with_options(ScanOptions { covers: Some(covers.clone()), ..ScanOptions::default() });
+        let report = scanner.scan_roots(&[dir.path()]);
+        assert_eq!(report.songs.len(), 2);
+        assert_eq!(covers.get("Nujabes", "Modal Soul").as_deref(), Some(&cover[..]));
+    }
+
+    #[test]
+    fn test_unreachable_root_is_marked_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        write_wav(&dir.path().join("song.wav"));
+        let missing = dir.path().join("unmounted");
+
+        let scanner = MusicScanner::with_options(ScanOptions {
+            io_timeout: Some(Duration::from_secs(5)),
+            ..ScanOptions::default()
+        });
+        let report = scanner.scan_roots(&[missing.as_path(), dir.path()]);
+
+        assert_eq!(report.songs.len(), 1);
+        assert!(report.is_offline(&missing));
+        assert!(!report.is_offline(dir.path()));
+        assert!(scanner.scan_directory(&missing).is_err());
+    }
+
+    #[test]
+    fn test_cancelled_scan_keeps_files_read_so_far() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c"] {
+            write_wav(&dir.path().join(format!("{}.wav", name)));
+        }
+
+        let cancel = CancelToken::new();
+        let mut reports = Vec::new();
+        let report = MusicScanner::new().scan_roots_with(&[dir.path()], &cancel, |progress| {
+            reports.push((progress.processed, progress.total, progress.current_file.is_some()));
+            if progress.processed == 1 {
+                cancel.cancel();
+            }
+        });
+
+        // Workers may have read ahead before seeing the cancel, but whatever
+        // was read is kept and the progress accounts for it
+        assert!(report.cancelled);
+        assert!(!report.songs.is_empty());
+        assert_eq!(reports.first(), Some(&(0, 3, false)));
+        assert_eq!(reports.last(), Some(&(report.songs.len(), 3, false)));
+    }
+
+    #[test]
+    fn test_songs_are_written_as_they_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c"] {
+            write_wav(&dir.path().join(format!("{}.wav", name)));
+        }
+        let scanner = MusicScanner::with_options(ScanOptions { workers: 2, ..ScanOptions::default() });
+
+        let mut written = Vec::new();
+        let report = scanner.scan_roots_into(&[dir.path()], &CancelToken::new(), |_| {}, |batch| {
+            written.extend(batch.iter().map(|song| song.title.clone()));
+            Ok(())
+        }).unwrap();
+        written.sort();
+        assert_eq!(written, ["a", "b", "c"]);
+        assert!(report.songs.is_empty());
+        assert!(!report.cancelled);
+
+        // A resumed scan only reads the files the last one did not get to
+        let resumed = MusicScanner::new().skipping(HashSet::from([dir.path().join("b.wav")]));
+        let report = resumed.scan_roots_with(&[dir.path()], &CancelToken::new(), |_| {});
+        assert_eq!(report.songs.len(), 2);
+        assert_eq!(report.resumed, 1);
+
+        let failed = scanner.scan_roots_into(&[dir.path()], &CancelToken::new(), |_| {}, |_| {
+            Err(LofiTurtleError::Configuration("disk full".to_string()))
+        });
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn test_throttle_delay() {
+        // Unthrottled, or ahead of the rate by no more than it allows
+        assert_eq!(throttle_delay(None, 100, Duration::ZERO), None);
+        assert_eq!(throttle_delay(Some(0), 100, Duration::ZERO), None);
+        assert_eq!(throttle_delay(Some(10), 0, Duration::ZERO), None);
+        assert_eq!(throttle_delay(Some(10), 5, Duration::from_secs(1)), None);
+
+        // Ten files at 10 a second are due after a second
+        assert_eq!(throttle_delay(Some(10), 10, Duration::from_millis(400)), Some(Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn test_scan_progress_eta() {
+        let progress = ScanProgress {
+            processed: 10,
+            total: 30,
+            current_file: None,
+            started: Instant::now() - Duration::from_secs(5),
+        };
+        assert!((progress.rate() - 2.0).abs() < 0.1);
+        let eta = progress.eta().unwrap().as_secs_f64();
+        assert!((eta - 10.0).abs() < 0.5);
+
+        assert!(ScanProgress::default().eta().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_followed_only_when_enabled() {
+        let music = tempfile::tempdir().unwrap();
+        let elsewhere = tempfile::tempdir().unwrap();
+        write_wav(&elsewhere.path().join("linked.wav"));
+        std::os::unix::fs::symlink(elsewhere.path(), music.path().join("share")).unwrap();
+        // A link back to the root must not make the scan loop forever
+        std::os::unix::fs::symlink(music.path(), music.path().join("loop")).unwrap();
+
+        // Following links is opt-in
+        assert!(!ScanOptions::default().follow_symlinks);
+        let default_scan = MusicScanner::new().scan_directory(music.path()).unwrap();
+        assert!(default_scan.is_empty());
+
+        let scanner = MusicScanner::with_options(ScanOptions {
+            follow_symlinks: true,
+            ..ScanOptions::default()
+        });
+        let songs = scanner.scan_directory(music.path()).unwrap();
+        assert_eq!(songs.len(), 1);
+        assert_eq!(songs[0].title, "linked");
+    }
+}
@@ -0,0 +1,277 @@
+use super::tags::TrackTags;
+use crate::error::{LofiTurtleError, Result};
+use std::path::{Path, PathBuf};
+
+/// Fields that can be used in a pattern
+const PLACEHOLDERS: [&str; 5] = ["artist", "album", "title", "track", "year"];
+
+/// Part of a parsed pattern
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder(String),
+}
+
+/// A file layout such as `{artist}/{album}/{track} - {title}`
+///
+/// `/` separates directories. The file extension is never part of the
+/// pattern: it is kept when rendering and ignored when matching.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagPattern {
+    components: Vec<Vec<Segment>>,
+}
+
+impl TagPattern {
+    /// Parse a pattern, rejecting unknown placeholders
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let mut components = Vec::new();
+
+        for component in pattern.split(['/', '\\']) {
+            if component.is_empty() {
+                continue;
+            }
+
+            let mut segments = Vec::new();
+            let mut rest = component;
+            while let Some(start) = rest.find('{') {
+                let end = rest[start..].find('}').map(|end| start + end).ok_or_else(|| {
+                    LofiTurtleError::Configuration(format!("Unclosed placeholder in pattern '{}'", pattern))
+                })?;
+
+                let name = &rest[start + 1..end];
+                if !PLACEHOLDERS.contains(&name) {
+                    return Err(LofiTurtleError::Configuration(format!(
+                        "Unknown placeholder {{{}}}, expected one of: {}",
+                        name,
+                        PLACEHOLDERS.join(", ")
+                    )));
+                }
+
+                if start > 0 {
+                    segments.push(Segment::Text(rest[..start].to_string()));
+                }
+                segments.push(Segment::Placeholder(name.to_string()));
+                rest = &rest[end + 1..];
+            }
+            if !rest.is_empty() {
+                segments.push(Segment::Text(rest.to_string()));
+            }
+
+            components.push(segments);
+        }
+
+        if components.is_empty() {
+            return Err(LofiTurtleError::Configuration("Pattern is empty".to_string()));
+        }
+
+        Ok(Self { components })
+    }
+
+    /// Build the new path for the file at `path`, relative to the library root
+    pub fn render(&self, tags: &TrackTags, path: &Path) -> PathBuf {
+        let mut relative = PathBuf::new();
+
+        for segments in &self.components {
+            let component: String = segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Text(text) => sanitize(text),
+                    Segment::Placeholder(name) => sanitize(&tag_value(tags, name, path)),
+                })
+                .collect();
+            relative.push(finish_component(&component));
+        }
+
+        if let Some(extension) = path.extension() {
+            let mut file_name = relative.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+            file_name.push(".");
+            file_name.push(extension);
+            relative.set_file_name(file_name);
+        }
+
+        relative
+    }
+
+    /// Read tag values out of a file's path
+    ///
+    /// The pattern is matched against the file name without its extension
+    /// and, for patterns with directories, the folders above it. Returns
+    /// `None` when the path does not fit the pattern.
+    pub fn match_path(&self, path: &Path) -> Option<TrackTags> {
+        let mut names = vec![path.file_stem()?.to_str()?];
+        for ancestor in path.ancestors().skip(1).take(self.components.len() - 1) {
+            names.push(ancestor.file_name()?.to_str()?);
+        }
+        if names.len() < self.components.len() {
+            return None;
+        }
+        names.reverse();
+
+        let mut captures = Vec::new();
+        for (segments, name) in self.components.iter().zip(&names) {
+            if !match_segments(segments, name, &mut captures) {
+                return None;
+            }
+        }
+
+        let mut tags = TrackTags::default();
+        for (placeholder, value) in captures {
+            match placeholder {
+                "artist" => tags.artist = Some(value),
+                "album" => tags.album = Some(value),
+                "title" => tags.title = Some(value),
+                "track" => tags.track = value.parse().ok(),
+                "year" => tags.year = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(tags)
+    }
+}
+
+/// Value substituted for `placeholder`, with defaults for missing tags
+fn tag_value(tags: &TrackTags, placeholder: &str, path: &Path) -> String {
+    match placeholder {
+        "artist" => tags.artist.clone().unwrap_or_else(|| "Unknown Artist".to_string()),
+        "album" => tags.album.clone().unwrap_or_else(|| "Unknown Album".to_string()),
+        "title" => tags.title.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Unknown Title".to_string())
+        }),
+        "track" => format!("{:02}", tags.track.unwrap_or(0)),
+        "year" => tags.year.map(|year| year.to_string()).unwrap_or_else(|| "Unknown Year".to_string()),
+        _ => String::new(),
+    }
+}
+
+/// Match `text` against `segments`, collecting placeholder values
+///
+/// Placeholders take as little text as possible, except the last one which
+/// takes the rest, so "{artist} - {title}" splits at the first " - ".
+/// Numeric placeholders only match digits.
+fn match_segments<'a>(segments: &'a [Segment], text: &str, captures: &mut Vec<(&'a str, String)>) -> bool {
+    let Some((first, rest)) = segments.split_first() else {
+        return text.is_empty();
+    };
+
+    match first {
+        Segment::Text(literal) => match text.strip_prefix(literal.as_str()) {
+            Some(remaining) => match_segments(rest, remaining, captures),
+            None => false,
+        },
+        Segment::Placeholder(name) => {
+            let ends: Vec<usize> = if rest.is_empty() {
+                vec![text.len()]
+            } else {
+                text.char_indices().skip(1).map(|(i, _)| i).chain([text.len()]).collect()
+            };
+
+            for end in ends {
+                let value = text[..end].trim();
+                let numeric = matches!(name.as_str(), "track" | "year");
+                if value.is_empty() || (numeric && !value.chars().all(|c| c.is_ascii_digit())) {
+                    continue;
+                }
+
+                captures.push((name.as_str(), value.to_string()));
+                if match_segments(rest, &text[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
+
+/// Replace characters that are not allowed in file names
+pub fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Trim a path component and make sure it is a usable name
+///
+/// Trailing dots are removed because Windows drops them, which also rules
+/// out `.` and `..` components.
+fn finish_component(component: &str) -> String {
+    let trimmed = component.trim().trim_end_matches('.').trim_end();
+    if trimmed.is_empty() {
+        "_".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags() -> TrackTags {
+        TrackTags {
+            artist: Some("AC/DC".to_string()),
+            album: Some("Back in Black".to_string()),
+            title: Some("Hells Bells?".to_string()),
+            track: Some(1),
+            year: None,
+        }
+    }
+
+    #[test]
+    fn test_render_pattern() {
+        let pattern = TagPattern::parse("{artist}/{album}/{track} - {title}").unwrap();
+        let path = pattern.render(&tags(), Path::new("/music/old name.flac"));
+        assert_eq!(path, PathBuf::from("AC_DC/Back in Black/01 - Hells Bells_.flac"));
+    }
+
+    #[test]
+    fn test_render_missing_tags() {
+        let pattern = TagPattern::parse("{artist}/{year}/{title}").unwrap();
+        let path = pattern.render(&TrackTags::default(), Path::new("/music/old name.mp3"));
+        assert_eq!(path, PathBuf::from("Unknown Artist/Unknown Year/old name.mp3"));
+
+        // Values can never escape the library root
+        let tags = TrackTags { artist: Some("..".to_string()), ..Default::default() };
+        let pattern = TagPattern::parse("{artist}/x").unwrap();
+        assert_eq!(pattern.render(&tags, Path::new("a.mp3")), PathBuf::from("_/x.mp3"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_patterns() {
+        assert!(TagPattern::parse("{artist}/{genre}").is_err());
+        assert!(TagPattern::parse("{artist").is_err());
+        assert!(TagPattern::parse("//").is_err());
+    }
+
+    #[test]
+    fn test_match_file_name() {
+        let pattern = TagPattern::parse("{track} - {artist} - {title}").unwrap();
+
+        let tags = pattern.match_path(Path::new("/music/01 - Nujabes - Aruarian Dance - Live.mp3")).unwrap();
+        assert_eq!(tags.track, Some(1));
+        assert_eq!(tags.artist.as_deref(), Some("Nujabes"));
+        assert_eq!(tags.title.as_deref(), Some("Aruarian Dance - Live"));
+
+        assert!(pattern.match_path(Path::new("/music/Nujabes - Aruarian Dance.mp3")).is_none());
+        assert!(pattern.match_path(Path::new("/music/A1 - Nujabes - Aruarian Dance.mp3")).is_none());
+    }
+
+    #[test]
+    fn test_match_directories() {
+        let pattern = TagPattern::parse("{artist}/{album}/{track}. {title}").unwrap();
+        let tags = pattern.match_path(Path::new("/music/Nujabes/Modal Soul/03. Feather.flac")).unwrap();
+        assert_eq!(tags.artist.as_deref(), Some("Nujabes"));
+        assert_eq!(tags.album.as_deref(), Some("Modal Soul"));
+        assert_eq!(tags.track, Some(3));
+        assert_eq!(tags.title.as_deref(), Some("Feather"));
+
+        assert!(pattern.match_path(Path::new("03. Feather.flac")).is_none());
+    }
+}
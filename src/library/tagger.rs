@@ -0,0 +1,107 @@
+use super::tag_pattern::TagPattern;
+use super::tags::TrackTags;
+use std::path::PathBuf;
+
+/// Tags to add to a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagChange {
+    pub path: PathBuf,
+    /// Only the fields the file is missing
+    pub tags: TrackTags,
+}
+
+/// Tag changes needed to fill in missing tags from file names
+#[derive(Debug, Default)]
+pub struct TagPlan {
+    pub changes: Vec<TagChange>,
+    /// Files whose name does not fit the pattern
+    pub unmatched: Vec<PathBuf>,
+    /// Files that could not be read
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Work out which tags to add to each file
+///
+/// Existing tags are never overwritten; files that already have every field
+/// the pattern provides are left out of the plan.
+pub fn plan(files: &[PathBuf], pattern: &TagPattern) -> TagPlan {
+    let mut plan = TagPlan::default();
+
+    for path in files {
+        let Some(parsed) = pattern.match_path(path) else {
+            plan.unmatched.push(path.clone());
+            continue;
+        };
+
+        let existing = match TrackTags::read(path) {
+            Ok(tags) => tags,
+            Err(e) => {
+                plan.failed.push((path.clone(), e.to_string()));
+                continue;
+            }
+        };
+
+        let tags = parsed.missing_from(&existing);
+        if !tags.is_empty() {
+            plan.changes.push(TagChange { path: path.clone(), tags });
+        }
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    /// Write a short silent 8-bit mono WAV file without tags
+    fn write_wav(path: &Path) {
+        let data_len: u32 = 800;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+        bytes.extend_from_slice(&8000u32.to_le_bytes()); // sample rate
+        bytes.extend_from_slice(&8000u32.to_le_bytes()); // byte rate
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // block align
+        bytes.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 128);
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_plan_and_write_missing_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let tagged = dir.path().join("01 - Nujabes - Feather.wav");
+        let other = dir.path().join("cover art.wav");
+        write_wav(&tagged);
+        write_wav(&other);
+
+        let pattern = TagPattern::parse("{track} - {artist} - {title}").unwrap();
+        let files = vec![tagged.clone(), other.clone()];
+
+        let plan = plan(&files, &pattern);
+        assert_eq!(plan.unmatched, [other]);
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].tags.artist.as_deref(), Some("Nujabes"));
+
+        plan.changes[0].tags.write(&tagged).unwrap();
+        let written = TrackTags::read(&tagged).unwrap();
+        assert_eq!(written.artist.as_deref(), Some("Nujabes"));
+        assert_eq!(written.title.as_deref(), Some("Feather"));
+
+        // Written fields are no longer missing
+        let replanned = super::plan(&files, &pattern);
+        assert!(replanned
+            .changes
+            .iter()
+            .all(|change| change.tags.artist.is_none() && change.tags.title.is_none()));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::{LofiTurtleError, Result};
+use lofty::config::WriteOptions;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::Tag;
+use std::path::Path;
+
+/// The tag fields the library cares about
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackTags {
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub title: Option<String>,
+    pub track: Option<u32>,
+    pub year: Option<u32>,
+}
+
+impl TrackTags {
+    /// Read the tags of an audio file
+    pub fn read(path: &Path) -> Result<Self> {
+        let tagged_file = Probe::open(path)
+            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to open audio file '{}': {}", path.display(), e)))?
+            .read()
+            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to read audio file '{}': {}", path.display(), e)))?;
+
+        let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) else {
+            return Ok(Self::default());
+        };
+
+        // Treat blank text fields as missing
+        let text = |value: Option<std::borrow::Cow<'_, str>>| {
+            value.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+        };
+
+        Ok(Self {
+            artist: text(tag.artist()),
+            album: text(tag.album()),
+            title: text(tag.title()),
+            track: tag.track(),
+            year: tag.year(),
+        })
+    }
+
+    /// Fields of `self` that are missing from `existing`
+    pub fn missing_from(&self, existing: &TrackTags) -> TrackTags {
+        TrackTags {
+            artist: self.artist.clone().filter(|_| existing.artist.is_none()),
+            album: self.album.clone().filter(|_| existing.album.is_none()),
+            title: self.title.clone().filter(|_| existing.title.is_none()),
+            track: self.track.filter(|_| existing.track.is_none()),
+            year: self.year.filter(|_| existing.year.is_none()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == TrackTags::default()
+    }
+
+    /// Write the fields that are set to the file's primary tag
+    ///
+    /// A tag is created if the file has none. Fields that are `None` are left
+    /// untouched.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut tagged_file = Probe::open(path)
+            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to open audio file '{}': {}", path.display(), e)))?
+            .read()
+            .map_err(|e| LofiTurtleError::UnsupportedFormat(format!("Failed to read audio file '{}': {}", path.display(), e)))?;
+
+        let tag_type = tagged_file.primary_tag_type();
+        if tagged_file.primary_tag().is_none() {
+            tagged_file.insert_tag(Tag::new(tag_type));
+        }
+        let Some(tag) = tagged_file.primary_tag_mut() else {
+            return Err(LofiTurtleError::UnsupportedFormat(format!("Cannot write tags to '{}'", path.display())));
+        };
+
+        if let Some(artist) = &self.artist {
+            tag.set_artist(artist.clone());
+        }
+        if let Some(album) = &self.album {
+            tag.set_album(album.clone());
+        }
+        if let Some(title) = &self.title {
+            tag.set_title(title.clone());
+        }
+        if let Some(track) = self.track {
+            tag.set_track(track);
+        }
+        if let Some(year) = self.year {
+            tag.set_year(year);
+        }
+
+        tag.save_to_path(path, WriteOptions::default())
+            .map_err(|e| LofiTurtleError::FileSystem(std::io::Error::other(format!("Failed to write tags to '{}': {}", path.display(), e))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_from_keeps_existing_values() {
+        let parsed = TrackTags {
+            artist: Some("Artist".to_string()),
+            title: Some("Title".to_string()),
+            track: Some(3),
+            ..Default::default()
+        };
+        let existing = TrackTags {
+            title: Some("Real Title".to_string()),
+            ..Default::default()
+        };
+
+        let missing = parsed.missing_from(&existing);
+        assert_eq!(missing.artist.as_deref(), Some("Artist"));
+        assert_eq!(missing.title, None);
+        assert_eq!(missing.track, Some(3));
+        assert!(parsed.missing_from(&parsed).is_empty());
+    }
+}
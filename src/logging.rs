@@ -0,0 +1,226 @@
+//! Logging setup
+//!
+//! Writing to stderr would corrupt the TUI, so records go to a log file
+//! instead, and only when a level is asked for with `--log-level`, `--verbose`
+//! or `RUST_LOG`. The file rotates once it grows past [`MAX_FILE_BYTES`].
+//!
+//! Independently of that, the most recent records at info level and above are
+//! kept in memory, so the log viewer in the TUI can show scan warnings and
+//! audio errors without a restart.
+
+use crate::config::paths::{self, Location};
+use chrono::{DateTime, Local, SecondsFormat};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Records kept for the log viewer, the oldest dropped first
+const RECENT_CAPACITY: usize = 500;
+
+/// Least severe level kept for the log viewer, whatever the file logs
+const RECENT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Size at which the log file is rotated
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated log files kept, `.1` being the newest
+const ROTATED_FILES: usize = 3;
+
+static RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// A log record kept for the log viewer
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub message: String,
+}
+
+/// Log file that starts afresh once it grows too large, keeping the last few
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path: path.to_path_buf(), file, size, max_bytes, keep })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Shift `.1` to `.2` and so on, dropping the oldest, and start a new file
+    fn rotate(&mut self) -> io::Result<()> {
+        for n in (1..self.keep).rev() {
+            match fs::rename(rotated_path(&self.path, n), rotated_path(&self.path, n + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        if self.keep > 0 {
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// Logger writing to the log file and keeping the recent records
+struct AppLogger {
+    level: LevelFilter,
+    file: Option<Mutex<RotatingFile>>,
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= RECENT_LEVEL || (self.file.is_some() && metadata.level() <= self.level)
+    }
+
+    fn log(&self, record: &Record) {
+        let time = Local::now();
+        if let Some(file) = self.file.as_ref().filter(|_| record.level() <= self.level) {
+            let line = format!(
+                "{} {:<5} {}: {}",
+                time.to_rfc3339_opts(SecondsFormat::Millis, false),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            if let Ok(mut file) = file.lock() {
+                // Nowhere left to report a failure to log
+                let _ = file.write_line(&line);
+            }
+        }
+        if record.level() <= RECENT_LEVEL {
+            keep(LogEntry {
+                time,
+                level: record.level(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// Install the logger, once at startup, writing records at `level` and above
+/// to the file at `path`
+///
+/// The in-memory records for the log viewer are kept even if the file cannot
+/// be opened, in which case the error is returned.
+pub fn init(level: LevelFilter, path: &Path) -> io::Result<()> {
+    let (file, result) = match level {
+        LevelFilter::Off => (None, Ok(())),
+        _ => match RotatingFile::open(path, MAX_FILE_BYTES, ROTATED_FILES) {
+            Ok(file) => (Some(Mutex::new(file)), Ok(())),
+            Err(e) => (None, Err(e)),
+        },
+    };
+
+    let max_level = if file.is_some() { level.max(RECENT_LEVEL) } else { RECENT_LEVEL };
+    // The logger lives for the rest of the program
+    let logger = Box::leak(Box::new(AppLogger { level, file }));
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+    result
+}
+
+/// Level asked for by `RUST_LOG`, if it names a single level
+pub fn env_level() -> Option<LevelFilter> {
+    std::env::var("RUST_LOG").ok()?.trim().parse().ok()
+}
+
+/// Where the log file goes unless another is given
+pub fn default_log_path() -> PathBuf {
+    paths::default_path(Location::State, "lofiturtle.log")
+}
+
+/// The records kept for the log viewer, oldest first
+pub fn recent() -> Vec<LogEntry> {
+    RECENT.lock().map(|recent| recent.iter().cloned().collect()).unwrap_or_default()
+}
+
+fn keep(entry: LogEntry) {
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_keeps_newest_records() {
+        for i in 0..=RECENT_CAPACITY {
+            keep(LogEntry {
+                time: Local::now(),
+                level: Level::Warn,
+                message: i.to_string(),
+            });
+        }
+
+        let recent = recent();
+        assert_eq!(recent.len(), RECENT_CAPACITY);
+        assert_eq!(recent[0].message, "1");
+        assert_eq!(recent[RECENT_CAPACITY - 1].message, RECENT_CAPACITY.to_string());
+    }
+
+    #[test]
+    fn test_log_file_rotates_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("lofiturtle.log");
+
+        // Each line is 10 bytes with its newline, so two fit before rotating
+        let mut file = RotatingFile::open(&path, 25, 2).unwrap();
+        for i in 0..7 {
+            file.write_line(&format!("line {:04}", i)).unwrap();
+        }
+
+        let read = |path: &Path| fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "line 0006\n");
+        assert_eq!(read(&rotated_path(&path, 1)), "line 0004\nline 0005\n");
+        assert_eq!(read(&rotated_path(&path, 2)), "line 0002\nline 0003\n");
+        assert!(!rotated_path(&path, 3).exists());
+
+        // Reopening carries on from the size already written
+        let mut file = RotatingFile::open(&path, 25, 2).unwrap();
+        file.write_line("line 0007").unwrap();
+        file.write_line("line 0008").unwrap();
+        assert_eq!(read(&path), "line 0008\n");
+    }
+}
@@ -1,12 +1,22 @@
+mod application;
 mod art;
 mod audio;
 mod cli;
 mod commands;
 mod config;
+mod domain;
 mod error;
+mod i18n;
+mod infrastructure;
 mod library;
+mod logging;
+mod media_controls;
 mod models;
+mod notifications;
+mod plugins;
+mod scrobbling;
 mod services;
+mod shared;
 mod ui;
 
 use clap::Parser;
@@ -18,12 +28,48 @@ use toml::Value;
 
 /// Main entry point for the LofiTurtle music player
 fn main() {
-    // Initialize logging
-    env_logger::init();
-
     // Parse command line arguments
     let cli = Cli::parse();
 
+    // Portable mode and the profile move every file, so they are settled
+    // before any is read, and files an older version left in the working
+    // directory are moved in
+    let dirs = config::paths::init(cli.portable.clone(), cli.profile.clone());
+    let migrated = config::paths::migrate_legacy_files();
+
+    // Pick the interface language before anything is printed
+    let settings = config::PersistentSettings::load();
+    let locale = cli.locale.clone().or_else(|| settings.locale.clone());
+    i18n::init(locale.as_deref());
+
+    if let Err(e) = dirs {
+        eprintln!("{}", t!("dir-create-failed", error = e.to_string()));
+    }
+    // Log to a file, never to the terminal the TUI draws on
+    let log_level = cli.log_level.map(Into::into)
+        .or(cli.verbose.then_some(log::LevelFilter::Debug))
+        .or_else(logging::env_level)
+        .unwrap_or(log::LevelFilter::Off);
+    let log_path = cli.log_file.clone().unwrap_or_else(logging::default_log_path);
+    if let Err(e) = logging::init(log_level, &log_path) {
+        eprintln!("{}", t!("log-file-failed", path = log_path.display().to_string(), error = e.to_string()));
+    }
+    match migrated {
+        Ok(migrated) => {
+            for migration in migrated {
+                log::info!("Copied {} to {}", migration.from.display(), migration.to.display());
+                eprintln!(
+                    "{}",
+                    t!("file-migrated", from = migration.from.display().to_string(), to = migration.to.display().to_string())
+                );
+            }
+        }
+        Err(e) => {
+            log::error!("Failed to copy files from the working directory: {}", e);
+            eprintln!("{}", t!("migration-failed", error = e.to_string()));
+        }
+    }
+
     // Run application with proper error handling
     if let Err(error) = run_application(cli) {
         display_error(&error);
@@ -33,14 +79,18 @@ fn main() {
 
 /// Run the application with proper error handling
 fn run_application(cli: Cli) -> Result<()> {
+    // Checking the configuration reports its problems in full, so it runs
+    // before anything is loaded with fallbacks or the music directory is
+    // needed, and listing audio devices or checking a theme needs neither
+    if let Some(command @ (cli::Commands::Config { .. } | cli::Commands::Theme { .. } | cli::Commands::Devices)) = &cli.command {
+        return CommandFactory::create_command(command, &cli).execute(&Config::default());
+    }
+
     // Create configuration from CLI arguments
     let config = Config::from_cli(&cli)?;
 
-    // Load layout configuration
-    // Check if layout_config is specified or default exists
-    // If user didn't specify --layout-config, clap uses default "layout.toml"
-    // We should check if that file exists, otherwise use default
-    let layout_path = &cli.layout_config;
+    // Load layout configuration, or the defaults if there is none
+    let layout_path = &cli.layout_path();
 
     let mut layout_config = if layout_path.exists() {
         log::info!("Loading layout config from {}", layout_path.display());
@@ -48,37 +98,35 @@ fn run_application(cli: Cli) -> Result<()> {
             Ok(config) => config,
             Err(e) => {
                 log::warn!("Failed to load layout config from {}: {}. Using defaults.", layout_path.display(), e);
-                eprintln!("⚠️ Failed to load layout config from {}: {}. Using defaults.", layout_path.display(), e);
+                eprintln!(
+                    "{}",
+                    t!("layout-load-failed", path = layout_path.display().to_string(), error = e.to_string())
+                );
                 LayoutConfig::default()
             }
         }
     } else {
-        // Only log if it's not the default path, or if user explicitly asked for a file that doesn't exist
-        // But since clap provides a default, we can't easily distinguish "user provided" vs "default"
-        // unless we check if the path is "layout.toml" and it's missing.
-        // For now, just use default silently if default file is missing.
-        if layout_path.to_string_lossy() != "layout.toml" {
+        // Only a layout asked for with --layout-config is missed
+        if cli.layout_config.is_some() {
              log::warn!("Layout config file {} not found. Using defaults.", layout_path.display());
-             eprintln!("⚠️ Layout config file {} not found. Using defaults.", layout_path.display());
+             eprintln!("{}", t!("layout-not-found", path = layout_path.display().to_string()));
         }
         LayoutConfig::default()
     };
 
     // Load keymap configuration if it exists
-    if cli.keymap_config.exists() {
-        log::info!("Loading keymap config from {}", cli.keymap_config.display());
-        match std::fs::read_to_string(&cli.keymap_config) {
+    let keymap_path = cli.keymap_path();
+    if keymap_path.exists() {
+        log::info!("Loading keymap config from {}", keymap_path.display());
+        match std::fs::read_to_string(&keymap_path) {
             Ok(content) => {
                 match content.parse::<Value>() {
                     Ok(value) => {
                         // Try to find [keybindings] section, or use the whole file if it's a table
-                        let keybindings = if let Some(table) = value.get("keybindings").and_then(|v| v.as_table()) {
-                            Some(table)
-                        } else if let Some(table) = value.as_table() {
-                            Some(table)
-                        } else {
-                            None
-                        };
+                        let keybindings = value
+                            .get("keybindings")
+                            .and_then(|v| v.as_table())
+                            .or_else(|| value.as_table());
 
                         if let Some(table) = keybindings {
                             for (key, action_val) in table {
@@ -99,7 +147,7 @@ fn run_application(cli: Cli) -> Result<()> {
     if let Some(dump_path) = &cli.dump_layout {
         log::info!("Dumping layout config to {}", dump_path.display());
         layout_config.save_to_file(dump_path)?;
-        println!("Layout configuration dumped to {}", dump_path.display());
+        println!("{}", t!("layout-dumped", path = dump_path.display().to_string()));
         return Ok(());
     }
 
@@ -107,7 +155,7 @@ fn run_application(cli: Cli) -> Result<()> {
     match &cli.command {
         Some(command) => {
             // Execute the specified command
-            let cmd = CommandFactory::create_command(command);
+            let cmd = CommandFactory::create_command(command, &cli);
             // Pass layout config if the command supports it (PlayCommand does)
             cmd.execute_with_layout(&config, &layout_config)
         }
@@ -115,9 +163,9 @@ fn run_application(cli: Cli) -> Result<()> {
             // Default behavior: check if CLI mode is requested
             if config.cli_mode {
                 // Run in CLI mode - show help or basic info
-                println!("🎵 LofiTurtle Music Player - CLI Mode");
-                println!("Use --help to see available commands");
-                println!("Run without --cli-mode for the interactive TUI interface");
+                println!("{}", t!("cli-mode-title"));
+                println!("{}", t!("cli-mode-help"));
+                println!("{}", t!("cli-mode-tui"));
                 Ok(())
             } else {
                 // Default behavior: start the interactive music player (TUI mode)
@@ -132,31 +180,35 @@ fn run_application(cli: Cli) -> Result<()> {
 fn display_error(error: &LofiTurtleError) {
     match error {
         LofiTurtleError::DirectoryNotFound(msg) => {
-            eprintln!("❌ Directory Error: {}", msg);
-            eprintln!("💡 Tip: Use --music-dir to specify a different directory");
+            eprintln!("{}", t!("error-directory", message = msg.to_string()));
+            eprintln!("{}", t!("tip-music-dir"));
         }
         LofiTurtleError::Database(err) => {
-            eprintln!("❌ Database Error: {}", err);
-            eprintln!("💡 Tip: Try deleting the database file to reset");
+            eprintln!("{}", t!("error-database", message = err.to_string()));
+            eprintln!("{}", t!("tip-reset-database"));
+        }
+        LofiTurtleError::MusicLibrary(msg) => {
+            eprintln!("{}", t!("error-library", message = msg.to_string()));
+            eprintln!("{}", t!("tip-reset-database"));
         }
         LofiTurtleError::AudioPlayback(msg) => {
-            eprintln!("❌ Audio Error: {}", msg);
-            eprintln!("💡 Tip: Check if your audio drivers are working");
+            eprintln!("{}", t!("error-audio", message = msg.to_string()));
+            eprintln!("{}", t!("tip-audio"));
         }
         LofiTurtleError::UnsupportedFormat(msg) => {
-            eprintln!("❌ Format Error: {}", msg);
-            eprintln!("💡 Tip: Supported formats: MP3, FLAC, AAC, M4A, OGG, WAV");
+            eprintln!("{}", t!("error-format", message = msg.to_string()));
+            eprintln!("{}", t!("tip-formats"));
         }
         LofiTurtleError::Configuration(msg) => {
-            eprintln!("❌ Configuration Error: {}", msg);
-            eprintln!("💡 Tip: Use --help to see available options");
+            eprintln!("{}", t!("error-config", message = msg.to_string()));
+            eprintln!("{}", t!("tip-help"));
         }
         LofiTurtleError::Terminal(msg) => {
-            eprintln!("❌ Terminal Error: {}", msg);
-            eprintln!("💡 Tip: Try running in a different terminal");
+            eprintln!("{}", t!("error-terminal", message = msg.to_string()));
+            eprintln!("{}", t!("tip-terminal"));
         }
         _ => {
-            eprintln!("❌ Error: {}", error);
+            eprintln!("{}", t!("error-other", message = error.to_string()));
         }
     }
 }
@@ -0,0 +1,196 @@
+//! Integration with the OS media session
+//!
+//! Keyboard media keys reach the player through the desktop's media session
+//! API, so they work even when the terminal is not focused, and the desktop's
+//! now-playing display shows the current track. That is MPRIS on the D-Bus
+//! session bus on Linux, the System Media Transport Controls on Windows and
+//! the Now Playing center on macOS.
+
+#[cfg(target_os = "linux")]
+mod mpris;
+#[cfg(target_os = "macos")]
+mod now_playing_center;
+#[cfg(target_os = "windows")]
+mod smtc;
+
+#[cfg(target_os = "linux")]
+use mpris as session;
+#[cfg(target_os = "macos")]
+use now_playing_center as session;
+#[cfg(target_os = "windows")]
+use smtc as session;
+
+use crate::error::{LofiTurtleError, Result};
+use crate::plugins::{PlayerEvent, Plugin, TrackInfo};
+use std::sync::mpsc::{self, Receiver, TryIter};
+use tokio::sync::mpsc::{self as async_mpsc, UnboundedSender};
+
+/// Playback requests sent by media keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    PlayPause,
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Previous,
+}
+
+/// Transport state shown by the OS media session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NowPlayingStatus {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+/// What the OS media session shows about the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct NowPlaying {
+    pub status: NowPlayingStatus,
+    pub track: Option<TrackInfo>,
+    pub volume: f32,
+}
+
+impl Default for NowPlaying {
+    fn default() -> Self {
+        Self {
+            status: NowPlayingStatus::Stopped,
+            track: None,
+            volume: 1.0,
+        }
+    }
+}
+
+impl NowPlaying {
+    /// Update from a player event, returning whether anything changed
+    pub fn apply(&mut self, event: &PlayerEvent) -> bool {
+        let previous = self.clone();
+
+        match event {
+            PlayerEvent::TrackChanged { track } => {
+                self.track = Some(track.clone());
+                self.status = NowPlayingStatus::Playing;
+            }
+            PlayerEvent::PlaybackPaused => self.status = NowPlayingStatus::Paused,
+            PlayerEvent::PlaybackResumed => self.status = NowPlayingStatus::Playing,
+            PlayerEvent::PlaybackStopped => {
+                self.track = None;
+                self.status = NowPlayingStatus::Stopped;
+            }
+            PlayerEvent::VolumeChanged { volume } => self.volume = *volume,
+            PlayerEvent::AppStarted | PlayerEvent::AppQuit => {}
+        }
+
+        *self != previous
+    }
+}
+
+/// Connection to the OS media session
+pub struct MediaControls {
+    keys: Receiver<MediaKey>,
+    updates: UnboundedSender<NowPlaying>,
+}
+
+impl MediaControls {
+    /// Register the player with the OS media session
+    ///
+    /// Returns `None` when the platform is unsupported or the media session
+    /// could not be registered, e.g. when no D-Bus session bus is running.
+    pub fn start() -> Option<Self> {
+        let (key_sender, keys) = mpsc::channel();
+        let (updates, update_receiver) = async_mpsc::unbounded_channel();
+
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        {
+            match session::spawn(key_sender, update_receiver) {
+                Ok(()) => Some(Self { keys, updates }),
+                Err(e) => {
+                    log::warn!("Media controls unavailable: {}", e);
+                    None
+                }
+            }
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+        {
+            drop((key_sender, keys, updates, update_receiver));
+            log::info!("Media controls are not supported on this platform");
+            None
+        }
+    }
+
+    /// Media keys pressed since the last call
+    pub fn pending(&self) -> TryIter<'_, MediaKey> {
+        #[cfg(target_os = "macos")]
+        now_playing_center::run_main_loop();
+
+        self.keys.try_iter()
+    }
+
+    /// Plugin that keeps the session's now-playing display up to date
+    pub fn now_playing_plugin(&self) -> Box<dyn Plugin> {
+        Box::new(NowPlayingPlugin {
+            now_playing: NowPlaying::default(),
+            updates: self.updates.clone(),
+        })
+    }
+}
+
+/// Publishes player events to the media session
+struct NowPlayingPlugin {
+    now_playing: NowPlaying,
+    updates: UnboundedSender<NowPlaying>,
+}
+
+impl Plugin for NowPlayingPlugin {
+    fn name(&self) -> &str {
+        "media-session"
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+        if self.now_playing.apply(event) {
+            self.updates
+                .send(self.now_playing.clone())
+                .map_err(|_| LofiTurtleError::MediaControls("Media session has stopped".to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> TrackInfo {
+        TrackInfo {
+            path: "/music/song.mp3".to_string(),
+            title: "Title".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            duration: 200,
+        }
+    }
+
+    #[test]
+    fn test_now_playing_follows_events() {
+        let mut now_playing = NowPlaying::default();
+
+        assert!(!now_playing.apply(&PlayerEvent::AppStarted));
+        assert!(now_playing.apply(&PlayerEvent::TrackChanged { track: track() }));
+        assert_eq!(now_playing.status, NowPlayingStatus::Playing);
+        assert_eq!(now_playing.track, Some(track()));
+
+        assert!(now_playing.apply(&PlayerEvent::PlaybackPaused));
+        assert!(!now_playing.apply(&PlayerEvent::PlaybackPaused));
+        assert_eq!(now_playing.status, NowPlayingStatus::Paused);
+
+        assert!(now_playing.apply(&PlayerEvent::VolumeChanged { volume: 0.5 }));
+        assert_eq!(now_playing.volume, 0.5);
+
+        assert!(now_playing.apply(&PlayerEvent::PlaybackStopped));
+        assert_eq!(now_playing.status, NowPlayingStatus::Stopped);
+        assert_eq!(now_playing.track, None);
+    }
+}
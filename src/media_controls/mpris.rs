@@ -0,0 +1,286 @@
+//! MPRIS media session for Linux desktops
+//!
+//! Desktop environments forward media keys to the MPRIS players on the
+//! session bus, so registering as one is enough to receive them. The same
+//! session feeds the desktop's now-playing widgets.
+
+use super::{MediaKey, NowPlaying, NowPlayingStatus};
+use crate::audio::stream;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::file_url;
+use crate::plugins::TrackInfo;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+use zbus::object_server::InterfaceRef;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+use zbus::{connection, interface, Connection};
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.lofiturtle";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+
+/// How long to wait for the session bus before giving up
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The `org.mpris.MediaPlayer2` root interface
+struct RootInterface;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl RootInterface {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        "LofiTurtle".to_string()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The `org.mpris.MediaPlayer2.Player` interface, forwarding key presses
+/// and exposing the current track
+struct PlayerInterface {
+    keys: Sender<MediaKey>,
+    now_playing: NowPlaying,
+}
+
+impl PlayerInterface {
+    fn forward(&self, key: MediaKey) {
+        if self.keys.send(key).is_err() {
+            log::debug!("Dropped media key {:?}: player is shutting down", key);
+        }
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl PlayerInterface {
+    fn next(&self) {
+        self.forward(MediaKey::Next);
+    }
+
+    fn previous(&self) {
+        self.forward(MediaKey::Previous);
+    }
+
+    fn pause(&self) {
+        self.forward(MediaKey::Pause);
+    }
+
+    fn play_pause(&self) {
+        self.forward(MediaKey::PlayPause);
+    }
+
+    fn stop(&self) {
+        self.forward(MediaKey::Stop);
+    }
+
+    fn play(&self) {
+        self.forward(MediaKey::Play);
+    }
+
+    fn seek(&self, _offset: i64) {}
+
+    fn set_position(&self, _track_id: ObjectPath<'_>, _position: i64) {}
+
+    fn open_uri(&self, _uri: &str) {}
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        match self.now_playing.status {
+            NowPlayingStatus::Playing => "Playing",
+            NowPlayingStatus::Paused => "Paused",
+            NowPlayingStatus::Stopped => "Stopped",
+        }
+        .to_string()
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        self.now_playing.track.as_ref().map(track_metadata).unwrap_or_default()
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        self.now_playing.volume as f64
+    }
+
+    #[zbus(property)]
+    fn position(&self) -> i64 {
+        0
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// Build the MPRIS metadata map for a track
+fn track_metadata(track: &TrackInfo) -> HashMap<String, OwnedValue> {
+    // A hex digest is always a valid object path element
+    let track_id = format!("/org/lofiturtle/track/{:x}", md5::compute(&track.path));
+    let entries = [
+        ("mpris:trackid", Value::from(ObjectPath::from_string_unchecked(track_id))),
+        ("mpris:length", Value::from(track.duration as i64 * 1_000_000)),
+        ("xesam:title", Value::from(track.title.as_str())),
+        ("xesam:artist", Value::from(vec![track.artist.as_str()])),
+        ("xesam:album", Value::from(track.album.as_str())),
+        ("xesam:url", Value::from(track_url(&track.path))),
+    ];
+
+    entries
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), OwnedValue::try_from(value).ok()?)))
+        .collect()
+}
+
+/// URL of a track for `xesam:url`: streams as they are, files as an escaped
+/// `file://` URL
+fn track_url(path: &str) -> String {
+    if stream::is_stream(path) {
+        return path.to_string();
+    }
+    file_url::from_path(Path::new(path))
+}
+
+/// Apply now-playing updates and announce the changed properties
+async fn publish_updates(connection: &Connection, mut updates: UnboundedReceiver<NowPlaying>) -> zbus::Result<()> {
+    let player: InterfaceRef<PlayerInterface> = connection.object_server().interface(OBJECT_PATH).await?;
+
+    while let Some(now_playing) = updates.recv().await {
+        let mut interface = player.get_mut().await;
+        interface.now_playing = now_playing;
+
+        let emitter = player.signal_emitter();
+        interface.playback_status_changed(emitter).await?;
+        interface.metadata_changed(emitter).await?;
+        interface.volume_changed(emitter).await?;
+    }
+
+    Ok(())
+}
+
+/// Register the MPRIS session on a background thread
+///
+/// Blocks until the bus name is acquired so registration errors can be
+/// reported to the caller. The session closes once the update sender is
+/// dropped.
+pub fn spawn(keys: Sender<MediaKey>, updates: UnboundedReceiver<NowPlaying>) -> Result<()> {
+    let (ready_sender, ready_receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .name("mpris".to_string())
+        .spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = ready_sender.send(Err(e.to_string()));
+                    return;
+                }
+            };
+
+            runtime.block_on(async move {
+                let connection = connection::Builder::session()
+                    .and_then(|builder| builder.name(BUS_NAME))
+                    .and_then(|builder| builder.serve_at(OBJECT_PATH, RootInterface))
+                    .and_then(|builder| {
+                        let player = PlayerInterface { keys, now_playing: NowPlaying::default() };
+                        builder.serve_at(OBJECT_PATH, player)
+                    });
+
+                let connection = match connection {
+                    Ok(builder) => builder.build().await,
+                    Err(e) => Err(e),
+                };
+
+                match connection {
+                    Ok(connection) => {
+                        let _ = ready_sender.send(Ok(()));
+                        if let Err(e) = publish_updates(&connection, updates).await {
+                            log::warn!("MPRIS session stopped: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e.to_string()));
+                    }
+                }
+            });
+        })?;
+
+    match ready_receiver.recv_timeout(STARTUP_TIMEOUT) {
+        Ok(Ok(())) => {
+            log::info!("Registered media session as {}", BUS_NAME);
+            Ok(())
+        }
+        Ok(Err(e)) => Err(LofiTurtleError::MediaControls(format!("Failed to register MPRIS session: {}", e))),
+        Err(_) => Err(LofiTurtleError::MediaControls("Timed out connecting to the session bus".to_string())),
+    }
+}
@@ -0,0 +1,144 @@
+//! Now Playing for macOS
+//!
+//! Media keys and the Control Center's Now Playing widget go through the
+//! MediaPlayer framework: `MPRemoteCommandCenter` forwards the keys and
+//! `MPNowPlayingInfoCenter` shows the current track. Remote commands are
+//! delivered on the main run loop, which a terminal program never runs on
+//! its own, so [`run_main_loop`] gives it a turn each time keys are polled.
+
+use super::{MediaKey, NowPlaying, NowPlayingStatus};
+use crate::error::{LofiTurtleError, Result};
+use crate::plugins::TrackInfo;
+use block2::RcBlock;
+use objc2::rc::{autoreleasepool, Retained};
+use objc2::runtime::AnyObject;
+use objc2::{class, msg_send};
+use objc2_foundation::{NSDictionary, NSNumber, NSString};
+use std::ffi::c_void;
+use std::sync::mpsc::Sender;
+use std::thread;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+#[link(name = "MediaPlayer", kind = "framework")]
+extern "C" {
+    static MPMediaItemPropertyTitle: &'static NSString;
+    static MPMediaItemPropertyArtist: &'static NSString;
+    static MPMediaItemPropertyAlbumTitle: &'static NSString;
+    static MPMediaItemPropertyPlaybackDuration: &'static NSString;
+    static MPNowPlayingInfoPropertyPlaybackRate: &'static NSString;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    static kCFRunLoopDefaultMode: *const c_void;
+    fn CFRunLoopRunInMode(mode: *const c_void, seconds: f64, return_after_source_handled: u8) -> i32;
+}
+
+/// `MPRemoteCommandHandlerStatusSuccess`
+const HANDLER_SUCCESS: isize = 0;
+
+/// `MPNowPlayingPlaybackState` values
+const STATE_PLAYING: usize = 1;
+const STATE_PAUSED: usize = 2;
+const STATE_STOPPED: usize = 3;
+
+/// Forward a remote command, an `MPRemoteCommand`, to the player as `key`
+fn forward(command: Option<Retained<AnyObject>>, keys: &Sender<MediaKey>, key: MediaKey) -> Result<()> {
+    let command = command.ok_or_else(|| LofiTurtleError::MediaControls(format!("No remote command for {:?}", key)))?;
+    let keys = keys.clone();
+    let handler = RcBlock::new(move |_event: *mut AnyObject| -> isize {
+        if keys.send(key).is_err() {
+            log::debug!("Dropped media key {:?}: player is shutting down", key);
+        }
+        HANDLER_SUCCESS
+    });
+    // The command center keeps the handler for the life of the process
+    let _target: Option<Retained<AnyObject>> = unsafe { msg_send![&*command, addTargetWithHandler: &*handler] };
+    Ok(())
+}
+
+/// Forward the remote commands for the media keys
+fn register(keys: &Sender<MediaKey>) -> Result<()> {
+    let center: Option<Retained<AnyObject>> = unsafe { msg_send![class!(MPRemoteCommandCenter), sharedCommandCenter] };
+    let center = center.ok_or_else(|| LofiTurtleError::MediaControls("No remote command center".to_string()))?;
+
+    unsafe {
+        forward(msg_send![&*center, togglePlayPauseCommand], keys, MediaKey::PlayPause)?;
+        forward(msg_send![&*center, playCommand], keys, MediaKey::Play)?;
+        forward(msg_send![&*center, pauseCommand], keys, MediaKey::Pause)?;
+        forward(msg_send![&*center, stopCommand], keys, MediaKey::Stop)?;
+        forward(msg_send![&*center, nextTrackCommand], keys, MediaKey::Next)?;
+        forward(msg_send![&*center, previousTrackCommand], keys, MediaKey::Previous)?;
+    }
+    Ok(())
+}
+
+/// Build the `nowPlayingInfo` dictionary for a track
+fn track_info(track: &TrackInfo, status: NowPlayingStatus) -> Retained<NSDictionary<NSString, AnyObject>> {
+    let title = NSString::from_str(&track.title);
+    let artist = NSString::from_str(&track.artist);
+    let album = NSString::from_str(&track.album);
+    let duration = NSNumber::new_f64(track.duration as f64);
+    let rate = NSNumber::new_f64(if status == NowPlayingStatus::Playing { 1.0 } else { 0.0 });
+
+    let keys = unsafe {
+        [
+            MPMediaItemPropertyTitle,
+            MPMediaItemPropertyArtist,
+            MPMediaItemPropertyAlbumTitle,
+            MPMediaItemPropertyPlaybackDuration,
+            MPNowPlayingInfoPropertyPlaybackRate,
+        ]
+    };
+    let values: [&AnyObject; 5] = [&title, &artist, &album, &duration, &rate];
+    NSDictionary::from_slices(&keys, &values)
+}
+
+/// Show the playback state and the current track in the Now Playing center
+fn show(now_playing: &NowPlaying) {
+    let center: Option<Retained<AnyObject>> = unsafe { msg_send![class!(MPNowPlayingInfoCenter), defaultCenter] };
+    let Some(center) = center else {
+        log::warn!("No Now Playing center to update");
+        return;
+    };
+
+    let info = now_playing.track.as_ref().map(|track| track_info(track, now_playing.status));
+    let state = match now_playing.status {
+        NowPlayingStatus::Playing => STATE_PLAYING,
+        NowPlayingStatus::Paused => STATE_PAUSED,
+        NowPlayingStatus::Stopped => STATE_STOPPED,
+    };
+    unsafe {
+        let _: () = msg_send![&*center, setNowPlayingInfo: info.as_deref()];
+        let _: () = msg_send![&*center, setPlaybackState: state];
+    }
+}
+
+/// Give the main run loop a turn to deliver pending remote commands
+///
+/// Must be called from the main thread.
+pub fn run_main_loop() {
+    unsafe {
+        CFRunLoopRunInMode(kCFRunLoopDefaultMode, 0.0, 1);
+    }
+}
+
+/// Register with the Now Playing center
+///
+/// Must be called from the main thread, which receives the remote commands.
+/// Updates are applied on a background thread, and stop once the update
+/// sender is dropped.
+pub fn spawn(keys: Sender<MediaKey>, mut updates: UnboundedReceiver<NowPlaying>) -> Result<()> {
+    register(&keys)?;
+
+    thread::Builder::new()
+        .name("now-playing".to_string())
+        .spawn(move || {
+            while let Some(now_playing) = updates.blocking_recv() {
+                autoreleasepool(|_| show(&now_playing));
+            }
+        })?;
+
+    log::info!("Registered with the Now Playing center");
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! System Media Transport Controls for Windows
+//!
+//! SMTC is normally tied to an app window, which a terminal program does not
+//! own. A `MediaPlayer` that never plays anything carries its own controls,
+//! so the session is borrowed from one with its command manager turned off,
+//! leaving the buttons to the player alone.
+
+use super::{MediaKey, NowPlaying, NowPlayingStatus};
+use crate::error::{LofiTurtleError, Result};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+use windows::core::HSTRING;
+use windows::Foundation::TypedEventHandler;
+use windows::Media::Playback::MediaPlayer;
+use windows::Media::{
+    MediaPlaybackStatus, MediaPlaybackType, SystemMediaTransportControls, SystemMediaTransportControlsButton,
+    SystemMediaTransportControlsButtonPressedEventArgs,
+};
+use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED};
+
+/// How long to wait for the controls to register before giving up
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The media key a transport control button stands for
+fn media_key(button: SystemMediaTransportControlsButton) -> Option<MediaKey> {
+    match button {
+        SystemMediaTransportControlsButton::Play => Some(MediaKey::Play),
+        SystemMediaTransportControlsButton::Pause => Some(MediaKey::Pause),
+        SystemMediaTransportControlsButton::Stop => Some(MediaKey::Stop),
+        SystemMediaTransportControlsButton::Next => Some(MediaKey::Next),
+        SystemMediaTransportControlsButton::Previous => Some(MediaKey::Previous),
+        _ => None,
+    }
+}
+
+/// Create the media player owning the controls and forward their buttons
+///
+/// The player must be kept alive for as long as the controls are used.
+fn register(keys: Sender<MediaKey>) -> windows::core::Result<(MediaPlayer, SystemMediaTransportControls)> {
+    let player = MediaPlayer::new()?;
+    player.CommandManager()?.SetIsEnabled(false)?;
+
+    let controls = player.SystemMediaTransportControls()?;
+    controls.SetIsEnabled(true)?;
+    controls.SetIsPlayEnabled(true)?;
+    controls.SetIsPauseEnabled(true)?;
+    controls.SetIsStopEnabled(true)?;
+    controls.SetIsNextEnabled(true)?;
+    controls.SetIsPreviousEnabled(true)?;
+
+    let handler = TypedEventHandler::<SystemMediaTransportControls, SystemMediaTransportControlsButtonPressedEventArgs>::new(
+        move |_, args| {
+            if let Some(key) = media_key(args.ok()?.Button()?) {
+                if keys.send(key).is_err() {
+                    log::debug!("Dropped media key {:?}: player is shutting down", key);
+                }
+            }
+            Ok(())
+        },
+    );
+    controls.ButtonPressed(&handler)?;
+
+    Ok((player, controls))
+}
+
+/// Show the playback state and the current track in the controls
+fn show(controls: &SystemMediaTransportControls, now_playing: &NowPlaying) -> windows::core::Result<()> {
+    controls.SetPlaybackStatus(match now_playing.status {
+        NowPlayingStatus::Playing => MediaPlaybackStatus::Playing,
+        NowPlayingStatus::Paused => MediaPlaybackStatus::Paused,
+        NowPlayingStatus::Stopped => MediaPlaybackStatus::Stopped,
+    })?;
+
+    let display = controls.DisplayUpdater()?;
+    match &now_playing.track {
+        Some(track) => {
+            display.SetType(MediaPlaybackType::Music)?;
+            let properties = display.MusicProperties()?;
+            properties.SetTitle(&HSTRING::from(track.title.as_str()))?;
+            properties.SetArtist(&HSTRING::from(track.artist.as_str()))?;
+            properties.SetAlbumTitle(&HSTRING::from(track.album.as_str()))?;
+        }
+        None => display.ClearAll()?,
+    }
+    display.Update()
+}
+
+/// Register the transport controls on a background thread
+///
+/// Blocks until the controls are registered so errors can be reported to
+/// the caller. The session closes once the update sender is dropped.
+pub fn spawn(keys: Sender<MediaKey>, mut updates: UnboundedReceiver<NowPlaying>) -> Result<()> {
+    let (ready_sender, ready_receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .name("smtc".to_string())
+        .spawn(move || {
+            // WinRT objects can only be created once the thread has joined
+            // an apartment
+            if let Err(e) = unsafe { RoInitialize(RO_INIT_MULTITHREADED) } {
+                let _ = ready_sender.send(Err(e.to_string()));
+                return;
+            }
+
+            let (_player, controls) = match register(keys) {
+                Ok(registered) => registered,
+                Err(e) => {
+                    let _ = ready_sender.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let _ = ready_sender.send(Ok(()));
+
+            while let Some(now_playing) = updates.blocking_recv() {
+                if let Err(e) = show(&controls, &now_playing) {
+                    log::warn!("Failed to update the media transport controls: {}", e);
+                }
+            }
+        })?;
+
+    match ready_receiver.recv_timeout(STARTUP_TIMEOUT) {
+        Ok(Ok(())) => {
+            log::info!("Registered with the system media transport controls");
+            Ok(())
+        }
+        Ok(Err(e)) => Err(LofiTurtleError::MediaControls(format!("Failed to register media transport controls: {}", e))),
+        Err(_) => Err(LofiTurtleError::MediaControls("Timed out registering media transport controls".to_string())),
+    }
+}
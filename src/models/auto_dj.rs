@@ -0,0 +1,149 @@
+use crate::models::Song;
+use chrono::{DateTime, Utc};
+use rand::{rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How the auto-DJ picks tracks when the queue runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutoDjStrategy {
+    /// Prefer other songs by the artist that just played
+    SameArtist,
+    /// Prefer other songs from the album that just played
+    SameAlbum,
+    /// Prefer other songs of the genre that just played
+    SameGenre,
+    /// Pick the songs heard longest ago, never played ones first
+    LeastRecentlyPlayed,
+    /// Pick random songs from the whole library
+    Random,
+}
+
+impl AutoDjStrategy {
+    /// Check if a candidate matches the strategy for the last played song
+    fn matches(&self, last: &Song, candidate: &Song) -> bool {
+        match self {
+            AutoDjStrategy::SameArtist => candidate.artist.eq_ignore_ascii_case(&last.artist),
+            AutoDjStrategy::SameAlbum => {
+                candidate.album.eq_ignore_ascii_case(&last.album)
+                    && candidate.artist.eq_ignore_ascii_case(&last.artist)
+            }
+            AutoDjStrategy::SameGenre => match (&last.genre, &candidate.genre) {
+                (Some(last), Some(genre)) => genre.eq_ignore_ascii_case(last),
+                _ => false,
+            },
+            AutoDjStrategy::LeastRecentlyPlayed | AutoDjStrategy::Random => true,
+        }
+    }
+
+    /// Pick up to `count` songs from `library` to follow `last`
+    ///
+    /// Songs already in `queue` are never picked. If too few songs match the
+    /// strategy, the rest are filled with random songs so playback keeps going.
+    /// `last_played` holds when each song was last heard, by ID; only
+    /// `LeastRecentlyPlayed` looks at it.
+    pub fn pick_tracks<'a>(
+        &self,
+        last: &Song,
+        library: &[Song],
+        queue: impl IntoIterator<Item = &'a Song>,
+        last_played: &HashMap<String, DateTime<Utc>>,
+        count: usize,
+    ) -> Vec<Song> {
+        let queued: HashSet<&str> = queue.into_iter().map(|song| song.id.as_str()).collect();
+        let (mut matching, mut others): (Vec<&Song>, Vec<&Song>) = library
+            .iter()
+            .filter(|song| !queued.contains(song.id.as_str()) && song.id != last.id)
+            .partition(|song| self.matches(last, song));
+
+        let mut rng = rng();
+        matching.shuffle(&mut rng);
+        others.shuffle(&mut rng);
+        if *self == AutoDjStrategy::LeastRecentlyPlayed {
+            // The sort is stable, so songs heard at the same time stay shuffled
+            matching.sort_by_key(|song| last_played.get(&song.id));
+        }
+
+        matching
+            .into_iter()
+            .chain(others)
+            .take(count)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str, artist: &str, album: &str) -> Song {
+        Song::new(path.to_string(), path.to_string(), artist.to_string(), album.to_string(), 180)
+    }
+
+    fn library() -> Vec<Song> {
+        vec![
+            song("/a1.mp3", "Alpha", "First"),
+            song("/a2.mp3", "Alpha", "First"),
+            song("/a3.mp3", "Alpha", "Second"),
+            song("/b1.mp3", "Beta", "First"),
+            song("/b2.mp3", "Beta", "Other"),
+        ]
+    }
+
+    fn paths(songs: &[Song]) -> Vec<&str> {
+        let mut paths: Vec<&str> = songs.iter().map(|s| s.path.as_str()).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_same_artist_prefers_artist() {
+        let library = library();
+        let picked = AutoDjStrategy::SameArtist.pick_tracks(&library[0], &library, &library[..1], &HashMap::new(), 2);
+        assert_eq!(paths(&picked), ["/a2.mp3", "/a3.mp3"]);
+    }
+
+    #[test]
+    fn test_same_album_requires_same_artist() {
+        let library = library();
+        let picked = AutoDjStrategy::SameAlbum.pick_tracks(&library[0], &library, &library[..1], &HashMap::new(), 1);
+        assert_eq!(paths(&picked), ["/a2.mp3"]);
+    }
+
+    #[test]
+    fn test_same_genre_prefers_genre() {
+        let genre = |song: Song, genre: &str| song.with_genre(Some(genre.to_string()));
+        let library = vec![
+            genre(song("/a1.mp3", "Alpha", "First"), "Jazz"),
+            genre(song("/a2.mp3", "Alpha", "First"), "Rock"),
+            genre(song("/b1.mp3", "Beta", "First"), "jazz"),
+            song("/b2.mp3", "Beta", "Other"),
+        ];
+        let picked = AutoDjStrategy::SameGenre.pick_tracks(&library[0], &library, &library[..1], &HashMap::new(), 1);
+        assert_eq!(paths(&picked), ["/b1.mp3"]);
+    }
+
+    #[test]
+    fn test_least_recently_played_picks_oldest_first() {
+        let library = library();
+        let now = Utc::now();
+        let last_played = HashMap::from([
+            (library[1].id.clone(), now),
+            (library[2].id.clone(), now - chrono::Duration::days(30)),
+            (library[3].id.clone(), now - chrono::Duration::days(1)),
+        ]);
+        let picked = AutoDjStrategy::LeastRecentlyPlayed.pick_tracks(&library[0], &library, &library[..1], &last_played, 3);
+        // Never played, then a month ago, then yesterday
+        let picked: Vec<&str> = picked.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(picked, ["/b2.mp3", "/a3.mp3", "/b1.mp3"]);
+    }
+
+    #[test]
+    fn test_falls_back_to_other_songs_and_skips_queue() {
+        let library = library();
+        let queue = &library[..3];
+        let picked = AutoDjStrategy::SameArtist.pick_tracks(&library[2], &library, queue, &HashMap::new(), 5);
+        assert_eq!(paths(&picked), ["/b1.mp3", "/b2.mp3"]);
+    }
+}
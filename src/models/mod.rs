@@ -1,7 +1,12 @@
 pub mod song;
 pub mod playlist;
 pub mod playback;
+pub mod auto_dj;
+pub mod queue;
+pub mod smart_shuffle;
 
 pub use song::Song;
-pub use playlist::{Playlist, PlaylistBuilder};
+pub use playlist::{Playlist, PlaylistSort};
 pub use playback::{RepeatMode, PlaybackState};
+pub use auto_dj::AutoDjStrategy;
+pub use queue::PlayQueue;
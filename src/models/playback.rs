@@ -1,11 +1,14 @@
+use crate::models::AutoDjStrategy;
+use crate::models::smart_shuffle::{self, ShuffleTrack};
 use serde::{Deserialize, Serialize};
-use rand::{seq::SliceRandom, rng};
+use rand::{seq::SliceRandom, rng, Rng};
 use std::collections::VecDeque;
 
 /// Playback modes for the music player
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum RepeatMode {
     /// No repeat - play through the playlist once
+    #[default]
     None,
     /// Repeat the current song
     Single,
@@ -13,40 +16,50 @@ pub enum RepeatMode {
     Playlist,
 }
 
-impl Default for RepeatMode {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 /// Playback state for the music player
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlaybackState {
     pub shuffle: bool,
+    /// Shuffle with smart shuffle rather than at random
+    #[serde(default)]
+    pub smart_shuffle: bool,
     pub repeat_mode: RepeatMode,
     pub current_song_index: usize,
     pub is_playing: bool,
     pub is_paused: bool,
     pub volume: f32,
+    /// Keep playing picked tracks when the queue runs out (None disables auto-DJ)
+    #[serde(default)]
+    pub auto_dj: Option<AutoDjStrategy>,
+    /// Go on to the next album when the queue runs out at the end of one
+    #[serde(default)]
+    pub continue_albums: bool,
     /// Shuffle queue for fair randomization - stores indices of songs to play
     #[serde(skip)]
     pub shuffle_queue: VecDeque<usize>,
     /// Original playlist order for when shuffle is disabled
     #[serde(skip)]
     pub original_order: Vec<usize>,
+    /// Artist and last play of each song in the list, for smart shuffle
+    #[serde(skip)]
+    pub shuffle_tracks: Vec<ShuffleTrack>,
 }
 
 impl Default for PlaybackState {
     fn default() -> Self {
         Self {
             shuffle: false,
+            smart_shuffle: false,
             repeat_mode: RepeatMode::default(),
             current_song_index: 0,
             is_playing: false,
             is_paused: false,
             volume: 0.7, // 70% volume by default
+            auto_dj: None,
+            continue_albums: false,
             shuffle_queue: VecDeque::new(),
             original_order: Vec::new(),
+            shuffle_tracks: Vec::new(),
         }
     }
 }
@@ -95,9 +108,13 @@ impl PlaybackState {
         };
     }
 
-    /// Toggle shuffle mode with fair randomization
-    pub fn toggle_shuffle(&mut self, playlist_size: usize) {
-        self.shuffle = !self.shuffle;
+    /// Step through the shuffle modes: off, shuffle, smart shuffle
+    pub fn cycle_shuffle(&mut self, playlist_size: usize) {
+        (self.shuffle, self.smart_shuffle) = match (self.shuffle, self.smart_shuffle) {
+            (false, _) => (true, false),
+            (true, false) => (true, true),
+            (true, true) => (false, false),
+        };
         
         if self.shuffle {
             self.enable_shuffle(playlist_size);
@@ -127,10 +144,29 @@ impl PlaybackState {
         if let Some(pos) = indices.iter().position(|&x| x == self.current_song_index) {
             indices.remove(pos);
         }
+
+        // Smart shuffle draws its own order from what it knows of the songs
+        if self.smart_shuffle && self.shuffle_tracks.len() == playlist_size {
+            let playing = self.shuffle_tracks.get(self.current_song_index).map(|track| track.artist.as_str());
+            indices = smart_shuffle::smart_order(&indices, &self.shuffle_tracks, playing, &mut rng());
+        }
         
         self.shuffle_queue = indices.into();
     }
 
+    /// Take songs appended at `added` into the shuffle, each at a random
+    /// point among the songs still to play
+    pub fn extend_shuffle(&mut self, added: std::ops::Range<usize>) {
+        if !self.shuffle {
+            return;
+        }
+        let mut rng = rng();
+        for index in added {
+            let at = rng.random_range(0..=self.shuffle_queue.len());
+            self.shuffle_queue.insert(at, index);
+        }
+    }
+
     /// Disable shuffle mode and restore original order
     pub fn disable_shuffle(&mut self) {
         self.shuffle_queue.clear();
@@ -230,6 +266,20 @@ mod tests {
         assert_eq!(state.volume, 0.7);
     }
 
+    #[test]
+    fn test_extend_shuffle_adds_songs_to_play() {
+        let mut state = PlaybackState::default();
+        state.extend_shuffle(3..5);
+        assert!(state.shuffle_queue.is_empty());
+
+        state.shuffle = true;
+        state.enable_shuffle(3);
+        state.extend_shuffle(3..5);
+        let mut queued: Vec<usize> = state.shuffle_queue.iter().copied().collect();
+        queued.sort();
+        assert_eq!(queued, vec![1, 2, 3, 4]);
+    }
+
     #[test]
     fn test_cycle_repeat_mode() {
         let mut state = PlaybackState::default();
@@ -269,8 +319,10 @@ mod tests {
 
     #[test]
     fn test_next_song_repeat_playlist() {
-        let mut state = PlaybackState::default();
-        state.repeat_mode = RepeatMode::Playlist;
+        let mut state = PlaybackState {
+            repeat_mode: RepeatMode::Playlist,
+            ..PlaybackState::default()
+        };
         let playlist_size = 3;
 
         state.current_song_index = 2; // Last song
@@ -282,8 +334,10 @@ mod tests {
 
     #[test]
     fn test_next_song_repeat_single() {
-        let mut state = PlaybackState::default();
-        state.repeat_mode = RepeatMode::Single;
+        let mut state = PlaybackState {
+            repeat_mode: RepeatMode::Single,
+            ..PlaybackState::default()
+        };
         let playlist_size = 3;
 
         state.current_song_index = 1;
@@ -299,7 +353,7 @@ mod tests {
         let playlist_size = 5;
 
         // Enable shuffle
-        state.toggle_shuffle(playlist_size);
+        state.cycle_shuffle(playlist_size);
         assert!(state.shuffle);
         assert_eq!(state.shuffle_queue.len(), 4); // Should exclude current song (index 0)
 
@@ -320,11 +374,13 @@ mod tests {
 
     #[test]
     fn test_shuffle_repeat_playlist() {
-        let mut state = PlaybackState::default();
-        state.repeat_mode = RepeatMode::Playlist;
+        let mut state = PlaybackState {
+            repeat_mode: RepeatMode::Playlist,
+            ..PlaybackState::default()
+        };
         let playlist_size = 3;
 
-        state.toggle_shuffle(playlist_size);
+        state.cycle_shuffle(playlist_size);
 
         // Consume all songs
         let first = state.next_song_index(playlist_size).unwrap();
@@ -340,4 +396,28 @@ mod tests {
         // Queue should have been regenerated (size - 1 because current is removed)
         assert_eq!(state.shuffle_queue.len(), 1);
     }
+
+    #[test]
+    fn test_cycle_shuffle_modes() {
+        let mut state = PlaybackState {
+            shuffle_tracks: ["A", "A", "B", "B"]
+                .into_iter()
+                .map(|artist| ShuffleTrack { artist: artist.to_string(), days_since_played: None })
+                .collect(),
+            ..PlaybackState::default()
+        };
+
+        state.cycle_shuffle(4);
+        assert!(state.shuffle && !state.smart_shuffle);
+
+        state.cycle_shuffle(4);
+        assert!(state.shuffle && state.smart_shuffle);
+        // Song 0 by A is playing, so B comes first and the artists alternate
+        let artists: Vec<&str> = state.shuffle_queue.iter().map(|&index| state.shuffle_tracks[index].artist.as_str()).collect();
+        assert_eq!(artists, ["B", "A", "B"]);
+
+        state.cycle_shuffle(4);
+        assert!(!state.shuffle && !state.smart_shuffle);
+        assert!(state.shuffle_queue.is_empty());
+    }
 }
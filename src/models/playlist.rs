@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::shared::natural_sort::natural_cmp;
 
 /// Represents a playlist containing multiple songs
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,28 +11,29 @@ pub struct Playlist {
     pub song_ids: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Total length of the songs, in seconds
+    #[serde(default)]
+    pub total_duration: u64,
 }
 
 impl Playlist {
-    /// Create a new playlist with the given name
-    pub fn new(name: String, description: Option<String>) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4().to_string(),
-            name,
-            description,
-            song_ids: Vec::new(),
-            created_at: now,
-            updated_at: now,
-        }
-    }
-
-
     /// Get the number of songs in the playlist
     pub fn song_count(&self) -> usize {
         self.song_ids.len()
     }
 
+    /// Total duration as "mm:ss", or "h:mm:ss" from an hour up
+    pub fn duration_formatted(&self) -> String {
+        let hours = self.total_duration / 3600;
+        let minutes = self.total_duration % 3600 / 60;
+        let seconds = self.total_duration % 60;
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            format!("{:02}:{:02}", minutes, seconds)
+        }
+    }
+
 
     /// Get a display name for the playlist
     #[allow(dead_code)]
@@ -41,44 +42,91 @@ impl Playlist {
     }
 }
 
-/// Builder pattern for creating playlists with validation
-#[derive(Default)]
-pub struct PlaylistBuilder {
-    name: Option<String>,
-    description: Option<String>,
-    song_ids: Vec<String>,
+/// Order of the playlists in the Playlists panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlaylistSort {
+    /// Alphabetical by name
+    #[default]
+    Name,
+    /// Newest first
+    Created,
+    /// Most recently changed first
+    Updated,
+    /// Most songs first
+    SongCount,
 }
 
-impl PlaylistBuilder {
-    /// Create a new playlist builder
-    pub fn new() -> Self {
-        Self::default()
+impl PlaylistSort {
+    /// The order after this one when cycling through them
+    pub fn next(self) -> Self {
+        match self {
+            PlaylistSort::Name => PlaylistSort::Created,
+            PlaylistSort::Created => PlaylistSort::Updated,
+            PlaylistSort::Updated => PlaylistSort::SongCount,
+            PlaylistSort::SongCount => PlaylistSort::Name,
+        }
     }
 
-    /// Set the playlist name
-    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
-        self.name = Some(name.into());
-        self
+    /// Put `playlists` in this order, by name where they tie
+    pub fn sort(self, playlists: &mut [Playlist]) {
+        playlists.sort_by(|a, b| {
+            let order = match self {
+                PlaylistSort::Name => std::cmp::Ordering::Equal,
+                PlaylistSort::Created => b.created_at.cmp(&a.created_at),
+                PlaylistSort::Updated => b.updated_at.cmp(&a.updated_at),
+                PlaylistSort::SongCount => b.song_count().cmp(&a.song_count()),
+            };
+            order.then_with(|| natural_cmp(&a.name, &b.name))
+        });
     }
+}
 
-    /// Set the playlist description
-    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
-        self.description = Some(description.into());
-        self
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn playlist(name: &str, day: u32, updated_day: u32, songs: usize) -> Playlist {
+        Playlist {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            song_ids: (0..songs).map(|i| i.to_string()).collect(),
+            created_at: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2024, 2, updated_day, 0, 0, 0).unwrap(),
+            total_duration: 0,
+        }
     }
 
+    #[test]
+    fn test_playlist_sort() {
+        let mut playlists = vec![
+            playlist("Focus", 1, 9, 3),
+            playlist("Chill", 5, 2, 3),
+            playlist("Rainy", 3, 4, 10),
+        ];
+        let names = |playlists: &[Playlist]| playlists.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
 
-    /// Build the playlist
-    pub fn build(self) -> Result<Playlist, String> {
-        let name = self.name.ok_or("Playlist name is required")?;
-        
-        if name.trim().is_empty() {
-            return Err("Playlist name cannot be empty".to_string());
-        }
+        PlaylistSort::Name.sort(&mut playlists);
+        assert_eq!(names(&playlists), ["Chill", "Focus", "Rainy"]);
+        PlaylistSort::Created.sort(&mut playlists);
+        assert_eq!(names(&playlists), ["Chill", "Rainy", "Focus"]);
+        PlaylistSort::Updated.sort(&mut playlists);
+        assert_eq!(names(&playlists), ["Focus", "Rainy", "Chill"]);
+        // Ties keep alphabetical order
+        PlaylistSort::SongCount.sort(&mut playlists);
+        assert_eq!(names(&playlists), ["Rainy", "Chill", "Focus"]);
+
+        assert_eq!(PlaylistSort::SongCount.next(), PlaylistSort::Name);
+    }
 
-        let mut playlist = Playlist::new(name, self.description);
-        playlist.song_ids = self.song_ids;
-        
-        Ok(playlist)
+    #[test]
+    fn test_duration_formatted() {
+        let mut playlist = playlist("Focus", 1, 1, 0);
+        assert_eq!(playlist.duration_formatted(), "00:00");
+        playlist.total_duration = 42 * 60 + 5;
+        assert_eq!(playlist.duration_formatted(), "42:05");
+        playlist.total_duration = 3600 + 5 * 60 + 30;
+        assert_eq!(playlist.duration_formatted(), "1:05:30");
     }
 }
@@ -0,0 +1,119 @@
+//! The play queue: saving it for commands run alongside the player, and
+//! shuffling it once
+//!
+//! The queue is the list playback moves through: the songs of the playlist
+//! or view being played, or just the playing song in the library, which
+//! does not play on by itself. The player writes it to the state directory
+//! whenever the song changes, so `lofiturtle queue save` can read it.
+
+use crate::config::paths::{self, Location};
+use crate::error::{LofiTurtleError, Result};
+use crate::models::Song;
+use rand::{rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+
+/// Songs of the queue in order, with the one playing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayQueue {
+    pub songs: Vec<Song>,
+    /// Index in `songs` of the playing song
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<usize>,
+}
+
+impl PlayQueue {
+    /// Where the player keeps the queue
+    pub fn path() -> std::path::PathBuf {
+        paths::default_path(Location::State, "queue.json")
+    }
+
+    /// The queue the player saved last, empty if there is none
+    pub fn load() -> Self {
+        match fs::read_to_string(Self::path()) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Failed to parse the saved queue: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string(self)
+            .map_err(|e| LofiTurtleError::Configuration(format!("Failed to serialize the queue: {}", e)))?;
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(LofiTurtleError::FileSystem)?;
+        }
+        fs::write(path, content).map_err(LofiTurtleError::FileSystem)
+    }
+
+    /// IDs of the songs in queue order, each once, as a playlist holds them
+    pub fn song_ids(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.songs
+            .iter()
+            .filter(|song| seen.insert(song.id.as_str()))
+            .map(|song| song.id.clone())
+            .collect()
+    }
+}
+
+/// Shuffle the songs of `order` after position `from`, leaving those up to
+/// it where they are
+pub fn shuffle_after<T>(order: &mut [T], from: usize) {
+    if from < order.len() {
+        order[from..].shuffle(&mut rng());
+    }
+}
+
+/// The `original` order brought back, followed by songs in `current` that
+/// were added since, in the order they are in now
+pub fn restore_order(current: &[usize], original: &[usize]) -> Vec<usize> {
+    let known: HashSet<usize> = original.iter().copied().collect();
+    original.iter().chain(current.iter().filter(|song| !known.contains(song))).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str) -> Song {
+        Song::new(path.to_string(), path.to_string(), "Artist".to_string(), "Album".to_string(), 180)
+    }
+
+    #[test]
+    fn test_song_ids_keep_order_without_repeats() {
+        let queue = PlayQueue { songs: vec![song("b.mp3"), song("a.mp3"), song("b.mp3")], current: Some(2) };
+        assert_eq!(queue.song_ids(), vec![song("b.mp3").id, song("a.mp3").id]);
+    }
+
+    #[test]
+    fn test_shuffle_after_keeps_the_played_part() {
+        let mut order: Vec<usize> = (0..50).collect();
+        shuffle_after(&mut order, 10);
+        assert_eq!(&order[..10], &(0..10).collect::<Vec<_>>()[..]);
+        let mut rest = order[10..].to_vec();
+        rest.sort();
+        assert_eq!(rest, (10..50).collect::<Vec<_>>());
+
+        // Nothing left to shuffle
+        shuffle_after(&mut order, 50);
+    }
+
+    #[test]
+    fn test_restore_order_keeps_songs_added_since() {
+        assert_eq!(restore_order(&[2, 0, 5, 1, 4], &[0, 1, 2]), vec![0, 1, 2, 5, 4]);
+        assert_eq!(restore_order(&[1, 0], &[0, 1]), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_queue_round_trips() {
+        let queue = PlayQueue { songs: vec![song("a.mp3")], current: Some(0) };
+        let read: PlayQueue = serde_json::from_str(&serde_json::to_string(&queue).unwrap()).unwrap();
+        assert_eq!(read.songs[0].path, "a.mp3");
+        assert_eq!(read.current, Some(0));
+    }
+}
@@ -0,0 +1,151 @@
+//! Smart shuffle
+//!
+//! A shuffle that saves songs heard lately for later and keeps one artist
+//! from playing twice in a row while another artist is left. Songs are
+//! drawn at random, less often the more recently they were played, and a
+//! song that would follow one by the same artist waits for the next song
+//! by someone else to go first.
+
+use rand::Rng;
+use std::collections::{BTreeSet, HashMap};
+
+/// Days after its last play for a song to be drawn as often as one never
+/// played
+pub const RECOVERY_DAYS: f64 = 14.0;
+
+/// Chance of a song played just now being drawn, next to a fresh one
+const MIN_WEIGHT: f64 = 0.05;
+
+/// What smart shuffle knows of a song in the list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShuffleTrack {
+    pub artist: String,
+    /// Days since the song was last played, if it was within
+    /// `RECOVERY_DAYS`
+    pub days_since_played: Option<f64>,
+}
+
+impl ShuffleTrack {
+    fn weight(&self) -> f64 {
+        self.days_since_played.map_or(1.0, |days| (days / RECOVERY_DAYS).clamp(MIN_WEIGHT, 1.0))
+    }
+}
+
+/// Put `indices` into `tracks` in smart shuffle order, the first of them
+/// by someone other than `after`, the artist playing now
+pub fn smart_order(indices: &[usize], tracks: &[ShuffleTrack], after: Option<&str>, rng: &mut impl Rng) -> Vec<usize> {
+    // Weighted draw without replacement: each song gets a key of u^(1/w),
+    // and the highest keys go first
+    let mut keyed: Vec<(f64, usize)> = indices
+        .iter()
+        .map(|&index| (rng.random::<f64>().powf(1.0 / tracks[index].weight()), index))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let order: Vec<usize> = keyed.into_iter().map(|(_, index)| index).collect();
+    separate_artists(&order, tracks, after)
+}
+
+/// `order` with each song as early as it can be without following a song
+/// by the same artist, wherever the songs left allow
+fn separate_artists(order: &[usize], tracks: &[ShuffleTrack], after: Option<&str>) -> Vec<usize> {
+    let artists: Vec<Option<String>> = order.iter().map(|&index| artist_key(&tracks[index].artist)).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for artist in artists.iter().flatten() {
+        *counts.entry(artist).or_default() += 1;
+    }
+    let mut by_count: BTreeSet<(usize, &str)> = counts.iter().map(|(&artist, &count)| (count, artist)).collect();
+
+    let mut taken = vec![false; order.len()];
+    let mut front = 0;
+    let mut previous = after.and_then(artist_key);
+    let mut result = Vec::with_capacity(order.len());
+    for left in (1..=order.len()).rev() {
+        while taken[front] {
+            front += 1;
+        }
+
+        // An artist with more than half the songs left has to come now, or
+        // two of their songs would meet later
+        let forced = by_count
+            .last()
+            .filter(|&&(count, artist)| count * 2 > left && previous.as_deref() != Some(artist))
+            .map(|&(_, artist)| artist);
+        let pick = (front..order.len())
+            .filter(|&position| !taken[position])
+            .find(|&position| match (forced, artists[position].as_deref()) {
+                (Some(forced), artist) => artist == Some(forced),
+                (None, None) => true,
+                (None, artist) => artist != previous.as_deref(),
+            })
+            .unwrap_or(front);
+
+        taken[pick] = true;
+        if let Some(artist) = artists[pick].as_deref() {
+            let count = counts.get_mut(artist).expect("every artist is counted");
+            by_count.remove(&(*count, artist));
+            *count -= 1;
+            if *count > 0 {
+                by_count.insert((*count, artist));
+            }
+        }
+        previous = artists[pick].clone();
+        result.push(order[pick]);
+    }
+    result
+}
+
+/// The artist a song is by, ignoring case, if it is known
+fn artist_key(artist: &str) -> Option<String> {
+    Some(artist.to_lowercase()).filter(|artist| !artist.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(artist: &str, days_since_played: Option<f64>) -> ShuffleTrack {
+        ShuffleTrack { artist: artist.to_string(), days_since_played }
+    }
+
+    #[test]
+    fn test_same_artist_never_twice_in_a_row() {
+        let tracks: Vec<ShuffleTrack> = ["Nujabes", "nujabes", "Nujabes", "J Dilla", "Uyama Hiroto", "J Dilla"]
+            .into_iter()
+            .map(|artist| track(artist, None))
+            .collect();
+        let indices: Vec<usize> = (0..tracks.len()).collect();
+
+        for _ in 0..50 {
+            let order = smart_order(&indices, &tracks, Some("Nujabes"), &mut rand::rng());
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, indices);
+
+            let artists: Vec<&str> = order.iter().map(|&index| tracks[index].artist.as_str()).collect();
+            assert_ne!(artist_key(artists[0]), artist_key("Nujabes"), "{:?}", artists);
+            assert!(artists.windows(2).all(|pair| artist_key(pair[0]) != artist_key(pair[1])), "{:?}", artists);
+        }
+    }
+
+    #[test]
+    fn test_one_artist_left_plays_on() {
+        let tracks = vec![track("Nujabes", None), track("Nujabes", None), track("Nujabes", None), track("", None)];
+        let order = smart_order(&[0, 1, 2, 3], &tracks, None, &mut rand::rng());
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3]);
+        // The unknown artist splits two of the songs, and the third has to
+        // follow one of them
+        assert_eq!(tracks[order[1]].artist, "");
+    }
+
+    #[test]
+    fn test_recent_songs_come_later() {
+        let tracks = vec![track("A", Some(0.0)), track("B", None)];
+        let mut rng = rand::rng();
+        let later = (0..200).filter(|_| smart_order(&[0, 1], &tracks, None, &mut rng)[0] == 1).count();
+        // The song just played goes first about one time in twenty
+        assert!(later > 170, "{}", later);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::shared::search::search_text;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 
@@ -9,6 +10,8 @@ pub struct Song {
     pub artist: String,    // Artist name
     pub album: String,     // Album name
     pub duration: u64,     // Duration in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>, // Genre from the tags, if any
     
     // Performance optimization: Cache frequently accessed strings
     #[serde(skip)]
@@ -35,12 +38,18 @@ impl Song {
             artist,
             album,
             duration,
+            genre: None,
             display_name_cache: OnceLock::new(),
             duration_formatted_cache: OnceLock::new(),
             search_string_cache: OnceLock::new(),
         }
     }
 
+    pub fn with_genre(mut self, genre: Option<String>) -> Self {
+        self.genre = genre;
+        self
+    }
+
     /// Performance optimized: Cache duration string to avoid repeated formatting
     pub fn duration_formatted(&self) -> &str {
         self.duration_formatted_cache.get_or_init(|| {
@@ -64,9 +73,7 @@ impl Song {
     /// Check if the song matches the given query (case-insensitive)
     /// Uses a cached lowercased search string to avoid repeated allocations
     pub fn matches(&self, query_lower: &str) -> bool {
-        let search_string = self.search_string_cache.get_or_init(|| {
-            format!("{} {} {}", self.title, self.artist, self.album).to_lowercase()
-        });
+        let search_string = self.search_string_cache.get_or_init(|| search_text(&self.title, &self.artist, &self.album));
         search_string.contains(query_lower)
     }
 }
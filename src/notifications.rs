@@ -0,0 +1,163 @@
+//! Desktop notifications of track changes
+//!
+//! Each new track pops up a notification with its title, artist and album
+//! and a thumbnail of its cover. Notifications are sent from a background
+//! thread, since the desktop can be slow to answer, and a burst of skips
+//! only notifies the track skipped to. Do not disturb holds them back
+//! without turning them off.
+
+use crate::art::AlbumArtRenderer;
+use crate::config::paths::{self, Location};
+use crate::error::Result;
+use crate::plugins::{PlayerEvent, Plugin, TrackInfo};
+use notify_rust::{Notification, Timeout};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Width and height of the cover shown in a notification
+const THUMBNAIL_SIZE: u32 = 128;
+
+/// Sends a notification whenever the track changes
+pub struct Notifier {
+    tracks: Sender<TrackInfo>,
+    do_not_disturb: Arc<AtomicBool>,
+}
+
+impl Notifier {
+    /// Start the notification thread; notifications stay up for `timeout`,
+    /// or as long as the desktop keeps them if it is `None`
+    pub fn start(art: AlbumArtRenderer, timeout: Option<Duration>, do_not_disturb: bool) -> Self {
+        let (tracks, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("notifications".to_string())
+            .spawn(move || notify_tracks(receiver, &art, timeout));
+        if let Err(e) = spawned {
+            log::warn!("Notifications unavailable: {}", e);
+        }
+
+        Self { tracks, do_not_disturb: Arc::new(AtomicBool::new(do_not_disturb)) }
+    }
+
+    pub fn set_do_not_disturb(&self, on: bool) {
+        self.do_not_disturb.store(on, Ordering::Relaxed);
+    }
+
+    /// Plugin that passes track changes on to the notification thread
+    pub fn plugin(&self) -> Box<dyn Plugin> {
+        Box::new(NotificationPlugin { tracks: self.tracks.clone(), do_not_disturb: Arc::clone(&self.do_not_disturb) })
+    }
+}
+
+struct NotificationPlugin {
+    tracks: Sender<TrackInfo>,
+    do_not_disturb: Arc<AtomicBool>,
+}
+
+impl Plugin for NotificationPlugin {
+    fn name(&self) -> &str {
+        "notifications"
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+        if let PlayerEvent::TrackChanged { track } = event {
+            if !self.do_not_disturb.load(Ordering::Relaxed) {
+                // The thread is gone only if it could not start, which was logged
+                let _ = self.tracks.send(track.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn notify_tracks(tracks: Receiver<TrackInfo>, art: &AlbumArtRenderer, timeout: Option<Duration>) {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut shown: Option<u32> = None;
+
+    while let Ok(track) = tracks.recv() {
+        // Only the latest of the tracks skipped through is worth showing
+        let track = tracks.try_iter().last().unwrap_or(track);
+
+        let mut notification = Notification::new();
+        notification.appname("LofiTurtle").summary(&track.title).body(&body(&track));
+        if let Some(cover) = thumbnail(art, &track) {
+            notification.image_path(&cover.to_string_lossy());
+        }
+        if let Some(timeout) = timeout {
+            notification.timeout(Timeout::Milliseconds(timeout.as_millis().try_into().unwrap_or(u32::MAX)));
+        }
+
+        // The notification of the track before is replaced rather than
+        // stacked up under the new one
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if let Some(id) = shown {
+            notification.id(id);
+        }
+
+        match notification.show() {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Ok(handle) => shown = Some(handle.id()),
+            #[cfg(not(all(unix, not(target_os = "macos"))))]
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to show notification: {}", e),
+        }
+    }
+}
+
+/// Second line of a notification: the artist and album
+fn body(track: &TrackInfo) -> String {
+    [track.artist.as_str(), track.album.as_str()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" — ")
+}
+
+/// The cover of `track` as a small PNG for the notification to show, if
+/// it has one
+fn thumbnail(art: &AlbumArtRenderer, track: &TrackInfo) -> Option<PathBuf> {
+    let cover = art.extract_album_art(&track.path).ok()??;
+    let image = image::load_from_memory(&cover)
+        .inspect_err(|e| log::debug!("Unreadable cover in {}: {}", track.path, e))
+        .ok()?;
+
+    let path = paths::default_path(Location::Cache, "notification-cover.png");
+    let saved = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(image::ImageError::IoError)
+        .and_then(|()| image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).save(&path));
+    match saved {
+        Ok(()) => Some(path),
+        Err(e) => {
+            log::warn!("Failed to save the notification cover: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_skips_missing_tags() {
+        let mut track = TrackInfo {
+            path: "/music/feather.mp3".to_string(),
+            title: "Feather".to_string(),
+            artist: "Nujabes".to_string(),
+            album: "Modal Soul".to_string(),
+            duration: 175,
+        };
+        assert_eq!(body(&track), "Nujabes — Modal Soul");
+
+        track.album.clear();
+        assert_eq!(body(&track), "Nujabes");
+        track.artist.clear();
+        assert_eq!(body(&track), "");
+    }
+}
@@ -0,0 +1,89 @@
+//! Built-in plugin that publishes player events as JSON lines
+//!
+//! Each event is appended to the configured file as a single JSON object, so
+//! external tools (scrobblers, lighting controllers, status bars) can follow
+//! the player with `tail -f`.
+
+use super::{PlayerEvent, Plugin};
+use crate::error::{LofiTurtleError, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Plugin that appends every event to a JSON lines file
+pub struct EventLogPlugin {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl EventLogPlugin {
+    /// Create a plugin writing to `path`
+    ///
+    /// The file is opened lazily, so nothing is created until the player
+    /// actually emits an event.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            file: None,
+        }
+    }
+
+    fn file(&mut self) -> Result<&mut File> {
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.file = Some(file);
+        }
+
+        Ok(self.file.as_mut().expect("event log file was just opened"))
+    }
+}
+
+impl Plugin for EventLogPlugin {
+    fn name(&self) -> &str {
+        "event-log"
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+        let line = serde_json::to_string(event)
+            .map_err(|e| LofiTurtleError::Configuration(format!("Failed to serialize event: {}", e)))?;
+
+        let result = writeln!(self.file()?, "{}", line);
+        if result.is_err() {
+            // Reopen on the next event, e.g. after the file was rotated away
+            self.file = None;
+        }
+
+        Ok(result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_written_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+
+        let mut plugin = EventLogPlugin::new(&path);
+        assert!(!path.exists());
+
+        plugin.on_event(&PlayerEvent::AppStarted).unwrap();
+        plugin.on_event(&PlayerEvent::VolumeChanged { volume: 0.25 }).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "app_started");
+        assert_eq!(lines[1]["event"], "volume_changed");
+        assert_eq!(lines[1]["volume"], 0.25);
+    }
+}
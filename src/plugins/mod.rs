@@ -0,0 +1,206 @@
+//! Plugin and extension hooks
+//!
+//! Plugins observe player events without having to fork the player. Native
+//! plugins implement the [`Plugin`] trait and are registered with the
+//! [`PluginManager`] at startup; external programs can follow the JSON event
+//! stream written by the built-in [`EventLogPlugin`], and user scripts in the
+//! scripts directory are run by the [`ScriptPlugin`].
+
+pub mod event_log;
+pub mod scripting;
+
+pub use event_log::EventLogPlugin;
+pub use scripting::ScriptPlugin;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models::Song;
+use serde::Serialize;
+
+/// Track metadata attached to player events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrackInfo {
+    pub path: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: u64,
+}
+
+impl From<&Song> for TrackInfo {
+    fn from(song: &Song) -> Self {
+        Self {
+            path: song.path.clone(),
+            title: song.title.clone(),
+            artist: song.artist.clone(),
+            album: song.album.clone(),
+            duration: song.duration,
+        }
+    }
+}
+
+/// Events emitted by the player to registered plugins
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PlayerEvent {
+    /// The player finished starting up
+    AppStarted,
+    /// A new track started playing
+    TrackChanged { track: TrackInfo },
+    /// Playback was paused
+    PlaybackPaused,
+    /// Playback resumed after a pause
+    PlaybackResumed,
+    /// Playback stopped
+    PlaybackStopped,
+    /// The output volume changed (0.0 to 1.0)
+    VolumeChanged { volume: f32 },
+    /// The player is shutting down
+    AppQuit,
+}
+
+/// Extension point for third-party integrations
+///
+/// Plugins are called synchronously from the UI thread, so long-running work
+/// such as network requests should be handed off to a background thread.
+pub trait Plugin: Send {
+    /// Name used when reporting plugin errors
+    fn name(&self) -> &str;
+
+    /// Handle a player event
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()>;
+}
+
+/// Owns the registered plugins and dispatches events to them
+#[derive(Default)]
+pub struct PluginManager {
+    plugins: Vec<Box<dyn Plugin>>,
+}
+
+impl PluginManager {
+    /// Create an empty plugin manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a plugin manager with the built-in plugins enabled by `config`
+    pub fn from_config(config: &Config) -> Self {
+        let mut manager = Self::new();
+
+        if let Some(path) = &config.event_log {
+            manager.register(Box::new(EventLogPlugin::new(path)));
+        }
+
+        if config.scripts_dir.is_dir() {
+            match ScriptPlugin::load_dir(&config.scripts_dir) {
+                Ok(plugin) if plugin.script_count() > 0 => manager.register(Box::new(plugin)),
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to load scripts from {}: {}", config.scripts_dir.display(), e),
+            }
+        }
+
+        manager
+    }
+
+    /// Register a plugin to receive events
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) {
+        log::info!("Registered plugin '{}'", plugin.name());
+        self.plugins.push(plugin);
+    }
+
+    /// Number of registered plugins
+    #[allow(dead_code)] // Future feature: plugin status display
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Check if no plugins are registered
+    #[allow(dead_code)] // Future feature: plugin status display
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Send an event to every plugin
+    ///
+    /// A failing plugin is logged and skipped so it cannot take down playback
+    /// or prevent other plugins from seeing the event.
+    pub fn dispatch(&mut self, event: &PlayerEvent) {
+        for plugin in &mut self.plugins {
+            if let Err(e) = plugin.on_event(event) {
+                log::warn!("Plugin '{}' failed to handle {:?}: {}", plugin.name(), event, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::LofiTurtleError;
+    use std::sync::{Arc, Mutex};
+
+    struct RecordingPlugin {
+        events: Arc<Mutex<Vec<PlayerEvent>>>,
+    }
+
+    impl Plugin for RecordingPlugin {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    struct FailingPlugin;
+
+    impl Plugin for FailingPlugin {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn on_event(&mut self, _event: &PlayerEvent) -> Result<()> {
+            Err(LofiTurtleError::Configuration("broken plugin".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_dispatch_reaches_all_plugins() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut manager = PluginManager::new();
+        manager.register(Box::new(FailingPlugin));
+        manager.register(Box::new(RecordingPlugin { events: Arc::clone(&events) }));
+        assert_eq!(manager.len(), 2);
+
+        // A failing plugin must not stop later plugins from seeing events
+        manager.dispatch(&PlayerEvent::AppStarted);
+        manager.dispatch(&PlayerEvent::VolumeChanged { volume: 0.5 });
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![PlayerEvent::AppStarted, PlayerEvent::VolumeChanged { volume: 0.5 }]
+        );
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let song = Song::new(
+            "/music/song.mp3".to_string(),
+            "Title".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            200,
+        );
+        let event = PlayerEvent::TrackChanged { track: TrackInfo::from(&song) };
+
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "track_changed");
+        assert_eq!(json["track"]["title"], "Title");
+        assert_eq!(json["track"]["duration"], 200);
+
+        let json = serde_json::to_value(PlayerEvent::PlaybackPaused).unwrap();
+        assert_eq!(json["event"], "playback_paused");
+    }
+}
@@ -0,0 +1,280 @@
+//! Rhai scripting hooks
+//!
+//! Every `*.rhai` file in the scripts directory is loaded at startup. A script
+//! reacts to player events by defining any of these functions:
+//!
+//! ```rhai
+//! fn on_app_start() {}
+//! fn on_track_change(song) {}   // song is a map: title, artist, album, path, duration
+//! fn on_pause() {}
+//! fn on_resume() {}
+//! fn on_stop() {}
+//! fn on_volume_change(volume) {}
+//! fn on_app_quit() {}
+//! ```
+//!
+//! Scripts only get a small API: `current_song()`, `notify(message)` for a
+//! desktop notification and `run(program, args)` to spawn a command.
+
+use super::{PlayerEvent, Plugin, TrackInfo};
+use crate::error::{LofiTurtleError, Result};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Upper bound on operations per hook call, so a runaway script cannot
+/// freeze the UI thread
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// A compiled user script
+struct Script {
+    name: String,
+    ast: AST,
+}
+
+/// Plugin that runs user scripts on player events
+pub struct ScriptPlugin {
+    engine: Engine,
+    scripts: Vec<Script>,
+    current_song: Arc<Mutex<Option<TrackInfo>>>,
+}
+
+impl ScriptPlugin {
+    /// Load every script in `dir`
+    ///
+    /// Scripts that fail to compile are logged and skipped so one broken
+    /// script does not disable the others.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir.as_ref())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        let current_song = Arc::new(Mutex::new(None));
+        let engine = Self::create_engine(Arc::clone(&current_song));
+
+        let mut scripts = Vec::new();
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            match engine.compile_file(path) {
+                Ok(ast) => {
+                    log::info!("Loaded script '{}'", name);
+                    scripts.push(Script { name, ast });
+                }
+                Err(e) => log::warn!("Failed to load script '{}': {}", name, e),
+            }
+        }
+
+        Ok(Self {
+            engine,
+            scripts,
+            current_song,
+        })
+    }
+
+    /// Number of successfully loaded scripts
+    pub fn script_count(&self) -> usize {
+        self.scripts.len()
+    }
+
+    /// Create the sandboxed engine with the script API registered
+    fn create_engine(current_song: Arc<Mutex<Option<TrackInfo>>>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        // The terminal belongs to the TUI, so script output goes to the log
+        engine.on_print(|text| log::info!("[script] {}", text));
+        engine.on_debug(|text, _, pos| log::debug!("[script] {:?} {}", pos, text));
+
+        engine.register_fn("current_song", move || -> Dynamic {
+            match current_song.lock().ok().and_then(|song| song.clone()) {
+                Some(track) => track_to_map(&track).into(),
+                None => Dynamic::UNIT,
+            }
+        });
+        engine.register_fn("notify", send_notification);
+        engine.register_fn("run", |program: &str, args: Array| run_command(program, args));
+        engine.register_fn("run", |program: &str| run_command(program, Array::new()));
+
+        engine
+    }
+
+    /// Call `hook` in every script that defines it with a matching arity
+    fn call_hook(&self, hook: &str, args: Vec<Dynamic>) -> Result<()> {
+        let mut failures = Vec::new();
+
+        for script in &self.scripts {
+            let defined = script
+                .ast
+                .iter_functions()
+                .any(|f| f.name == hook && f.params.len() == args.len());
+            if !defined {
+                continue;
+            }
+
+            let mut scope = Scope::new();
+            if let Err(e) = self.engine.call_fn::<Dynamic>(&mut scope, &script.ast, hook, args.clone()) {
+                failures.push(format!("{}: {}", script.name, e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(LofiTurtleError::Configuration(format!(
+                "Script hook {} failed: {}",
+                hook,
+                failures.join("; ")
+            )))
+        }
+    }
+}
+
+impl Plugin for ScriptPlugin {
+    fn name(&self) -> &str {
+        "scripts"
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+        match event {
+            PlayerEvent::TrackChanged { track } => {
+                if let Ok(mut current) = self.current_song.lock() {
+                    *current = Some(track.clone());
+                }
+            }
+            PlayerEvent::PlaybackStopped => {
+                if let Ok(mut current) = self.current_song.lock() {
+                    *current = None;
+                }
+            }
+            _ => {}
+        }
+
+        match event {
+            PlayerEvent::AppStarted => self.call_hook("on_app_start", vec![]),
+            PlayerEvent::TrackChanged { track } => {
+                self.call_hook("on_track_change", vec![track_to_map(track).into()])
+            }
+            PlayerEvent::PlaybackPaused => self.call_hook("on_pause", vec![]),
+            PlayerEvent::PlaybackResumed => self.call_hook("on_resume", vec![]),
+            PlayerEvent::PlaybackStopped => self.call_hook("on_stop", vec![]),
+            PlayerEvent::VolumeChanged { volume } => {
+                self.call_hook("on_volume_change", vec![Dynamic::from_float(*volume as f64)])
+            }
+            PlayerEvent::AppQuit => self.call_hook("on_app_quit", vec![]),
+        }
+    }
+}
+
+/// Convert track metadata into a script object map
+fn track_to_map(track: &TrackInfo) -> Map {
+    let mut map = Map::new();
+    map.insert("title".into(), track.title.clone().into());
+    map.insert("artist".into(), track.artist.clone().into());
+    map.insert("album".into(), track.album.clone().into());
+    map.insert("path".into(), track.path.clone().into());
+    map.insert("duration".into(), Dynamic::from_int(track.duration as i64));
+    map
+}
+
+/// Show a desktop notification using the platform's notifier
+fn send_notification(message: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        let escaped = message.replace('\\', "\\\\").replace('"', "\\\"");
+        command.args(["-e", &format!("display notification \"{}\" with title \"LofiTurtle\"", escaped)]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["LofiTurtle", message]);
+        command
+    };
+
+    if let Err(e) = command.stdout(Stdio::null()).stderr(Stdio::null()).spawn() {
+        log::warn!("Failed to send notification: {}", e);
+    }
+}
+
+/// Spawn a command without waiting for it, keeping it off the terminal
+fn run_command(program: &str, args: Array) {
+    let args: Vec<String> = args.into_iter().map(|arg| arg.to_string()).collect();
+
+    let result = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    if let Err(e) = result {
+        log::warn!("Script failed to run '{}': {}", program, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> TrackInfo {
+        TrackInfo {
+            path: "/music/song.mp3".to_string(),
+            title: "Title".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            duration: 200,
+        }
+    }
+
+    #[test]
+    fn test_load_dir_skips_invalid_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("good.rhai"), "fn on_app_start() {}").unwrap();
+        std::fs::write(dir.path().join("broken.rhai"), "fn on_app_start( {").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a script").unwrap();
+
+        let plugin = ScriptPlugin::load_dir(dir.path()).unwrap();
+        assert_eq!(plugin.script_count(), 1);
+    }
+
+    #[test]
+    fn test_hooks_receive_song() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("check.rhai"),
+            r#"
+            fn on_track_change(song) {
+                if song.title != "Title" || song.duration != 200 { throw "bad song argument"; }
+                if current_song().artist != "Artist" { throw "bad current song"; }
+            }
+            fn on_stop() {
+                if current_song() != () { throw "song not cleared"; }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let mut plugin = ScriptPlugin::load_dir(dir.path()).unwrap();
+        plugin.on_event(&PlayerEvent::TrackChanged { track: track() }).unwrap();
+        plugin.on_event(&PlayerEvent::PlaybackStopped).unwrap();
+
+        // Events without a matching hook are ignored
+        plugin.on_event(&PlayerEvent::AppStarted).unwrap();
+    }
+
+    #[test]
+    fn test_failing_and_runaway_scripts_report_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("throw.rhai"), r#"fn on_pause() { throw "boom"; }"#).unwrap();
+        std::fs::write(dir.path().join("loop.rhai"), "fn on_resume() { loop {} }").unwrap();
+
+        let mut plugin = ScriptPlugin::load_dir(dir.path()).unwrap();
+        assert!(plugin.on_event(&PlayerEvent::PlaybackPaused).is_err());
+        assert!(plugin.on_event(&PlayerEvent::PlaybackResumed).is_err());
+    }
+}
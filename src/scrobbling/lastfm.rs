@@ -0,0 +1,332 @@
+//! Loving tracks on Last.fm
+//!
+//! Calls that change anything are signed with the API secret and made for
+//! the user of a session key, which `lofiturtle loved login` obtains once
+//! the user has allowed LofiTurtle on the Last.fm website and keeps in the
+//! config directory, readable only by the user.
+
+use crate::config::paths::{self, Location};
+use crate::config::Config;
+use crate::error::{LofiTurtleError, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+const AUTH_URL: &str = "https://www.last.fm/api/auth/";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Loved tracks fetched per request
+const PAGE_SIZE: usize = 200;
+
+/// A track loved on Last.fm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LovedTrack {
+    pub artist: String,
+    pub title: String,
+}
+
+/// A Last.fm session, standing for the user who allowed LofiTurtle
+#[derive(Debug, Clone)]
+pub struct LastFmSession {
+    pub user: String,
+    pub key: String,
+}
+
+impl LastFmSession {
+    /// File the session key is kept in
+    pub fn path() -> PathBuf {
+        paths::default_path(Location::Config, "lastfm_session")
+    }
+
+    /// Keep the key in `path` for later runs, where no other user can read
+    /// it: the key acts for the Last.fm account without a password
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // The mode only applies to a new file
+            if path.exists() {
+                fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let mut file = options.open(path)?;
+        writeln!(file, "{}", self.key)?;
+        Ok(())
+    }
+
+    /// The session key kept in `path`, if `loved login` has saved one
+    pub fn load_key(path: &Path) -> Option<String> {
+        let key = fs::read_to_string(path).ok()?;
+        let key = key.trim();
+        (!key.is_empty()).then(|| key.to_string())
+    }
+}
+
+/// Client for the Last.fm web services API
+#[derive(Clone)]
+pub struct LastFmClient {
+    api_key: String,
+    secret: String,
+    session_key: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl LastFmClient {
+    /// Create a client from the configured API account and session key
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match (&config.lastfm_api_key, &config.lastfm_secret) {
+            (Some(api_key), Some(secret)) => {
+                Ok(Self::new(api_key.clone(), secret.clone(), config.lastfm_session_key.clone()))
+            }
+            _ => Err(LofiTurtleError::Configuration(
+                "A Last.fm API key and secret are required; pass --lastfm-api-key and --lastfm-secret \
+                 or set LASTFM_API_KEY and LASTFM_API_SECRET"
+                    .to_string(),
+            )),
+        }
+    }
+
+    pub fn new(api_key: String, secret: String, session_key: Option<String>) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        Self { api_key, secret, session_key, agent }
+    }
+
+    /// Whether the client can act for a user
+    pub fn has_session(&self) -> bool {
+        self.session_key.is_some()
+    }
+
+    /// Ask for a token the user then allows on the page at [`Self::auth_url`]
+    pub fn request_token(&self) -> Result<String> {
+        let response = self.get("auth.getToken", Vec::new(), true)?;
+        string_at(&response, &["token"])
+    }
+
+    /// Page where the user allows LofiTurtle to use `token`
+    pub fn auth_url(&self, token: &str) -> String {
+        format!("{}?api_key={}&token={}", AUTH_URL, self.api_key, token)
+    }
+
+    /// Trade an allowed token for a session
+    pub fn session(&self, token: &str) -> Result<LastFmSession> {
+        let response = self.get("auth.getSession", vec![("token", token.to_string())], true)?;
+        Ok(LastFmSession {
+            user: string_at(&response, &["session", "name"])?,
+            key: string_at(&response, &["session", "key"])?,
+        })
+    }
+
+    /// Name of the user of the session
+    pub fn user(&self) -> Result<String> {
+        let params = vec![("sk", self.require_session()?.to_string())];
+        let response = self.get("user.getInfo", params, true)?;
+        string_at(&response, &["user", "name"])
+    }
+
+    /// Love the track, or unlove it
+    pub fn set_loved(&self, artist: &str, title: &str, loved: bool) -> Result<()> {
+        let method = if loved { "track.love" } else { "track.unlove" };
+        let params = vec![
+            ("artist", artist.to_string()),
+            ("track", title.to_string()),
+            ("sk", self.require_session()?.to_string()),
+        ];
+        let mut params = self.signed(method, params);
+        params.push(("format", "json".to_string()));
+        let body = self
+            .agent
+            .post(API_URL)
+            .send_form(params.iter().map(|(name, value)| (*name, value.as_str())))
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(request_error)?;
+        parse_response(&body).map(|_| ())
+    }
+
+    /// Every track `user` has loved
+    pub fn loved_tracks(&self, user: &str) -> Result<Vec<LovedTrack>> {
+        let mut tracks = Vec::new();
+        let mut page = 1;
+        loop {
+            let params = vec![
+                ("user", user.to_string()),
+                ("limit", PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+            ];
+            let response = self.get("user.getLovedTracks", params, false)?;
+            let (found, pages) = parse_loved_tracks(&response);
+            tracks.extend(found);
+            if page >= pages {
+                return Ok(tracks);
+            }
+            page += 1;
+        }
+    }
+
+    fn require_session(&self) -> Result<&str> {
+        self.session_key.as_deref().ok_or_else(|| {
+            LofiTurtleError::Configuration(
+                "A Last.fm session key is required; run `lofiturtle loved login` once"
+                    .to_string(),
+            )
+        })
+    }
+
+    fn get(&self, method: &'static str, mut params: Vec<(&'static str, String)>, sign: bool) -> Result<Value> {
+        if sign {
+            params = self.signed(method, params);
+        } else {
+            params.extend([("method", method.to_string()), ("api_key", self.api_key.clone())]);
+        }
+        params.push(("format", "json".to_string()));
+
+        let body = self
+            .agent
+            .get(API_URL)
+            .query_pairs(params.iter().map(|(name, value)| (*name, value.as_str())))
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(request_error)?;
+        parse_response(&body)
+    }
+
+    /// `params` with the method, the API key and their signature added
+    fn signed(&self, method: &'static str, mut params: Vec<(&'static str, String)>) -> Vec<(&'static str, String)> {
+        params.extend([("method", method.to_string()), ("api_key", self.api_key.clone())]);
+        let signature = signature(&params, &self.secret);
+        params.push(("api_sig", signature));
+        params
+    }
+}
+
+/// The `api_sig` of a call: the MD5 of its parameters, sorted by name and
+/// written name then value, followed by the secret
+fn signature(params: &[(&str, String)], secret: &str) -> String {
+    let mut sorted: Vec<&(&str, String)> = params.iter().filter(|(name, _)| *name != "format").collect();
+    sorted.sort_by_key(|(name, _)| *name);
+    let mut text: String = sorted.iter().map(|(name, value)| format!("{}{}", name, value)).collect();
+    text.push_str(secret);
+    format!("{:x}", md5::compute(text.as_bytes()))
+}
+
+fn request_error(e: ureq::Error) -> LofiTurtleError {
+    LofiTurtleError::Scrobbling(format!("Last.fm request failed: {}", e))
+}
+
+/// The JSON of a response, or the error Last.fm answered with
+fn parse_response(body: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| LofiTurtleError::Scrobbling(format!("Unexpected Last.fm response: {}", e)))?;
+    if let Some(code) = value.get("error") {
+        let message = value["message"].as_str().unwrap_or_default();
+        return Err(LofiTurtleError::Scrobbling(format!("Last.fm error {}: {}", code, message)));
+    }
+    Ok(value)
+}
+
+fn string_at(value: &Value, path: &[&str]) -> Result<String> {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| LofiTurtleError::Scrobbling(format!("Last.fm response has no {}", path.join("."))))
+}
+
+/// Tracks of a page of loved tracks, with the number of pages there are
+fn parse_loved_tracks(response: &Value) -> (Vec<LovedTrack>, usize) {
+    let loved = &response["lovedtracks"];
+    let pages = loved["@attr"]["totalPages"].as_str().and_then(|pages| pages.parse().ok()).unwrap_or(1);
+
+    // A page of one track holds it on its own rather than in a list
+    let tracks = match &loved["track"] {
+        Value::Array(tracks) => tracks.iter().collect(),
+        Value::Object(_) => vec![&loved["track"]],
+        _ => Vec::new(),
+    };
+    let tracks = tracks
+        .into_iter()
+        .filter_map(|track| {
+            Some(LovedTrack {
+                artist: track["artist"]["name"].as_str()?.to_string(),
+                title: track["name"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+
+    (tracks, pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_session_key_is_saved_privately() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join("lastfm_session");
+        assert_eq!(LastFmSession::load_key(&path), None);
+
+        let session = LastFmSession { user: "me".to_string(), key: "d580d57f32848f5dcf574d1ce18d78b2".to_string() };
+        session.save(&path).unwrap();
+        assert_eq!(LastFmSession::load_key(&path).as_deref(), Some("d580d57f32848f5dcf574d1ce18d78b2"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_signature_sorts_params_and_skips_format() {
+        let params = vec![
+            ("track", "Feather".to_string()),
+            ("artist", "Nujabes".to_string()),
+            ("format", "json".to_string()),
+        ];
+        let expected = format!("{:x}", md5::compute("artistNujabestrackFeathersecret"));
+        assert_eq!(signature(&params, "secret"), expected);
+    }
+
+    #[test]
+    fn test_parse_loved_tracks() {
+        let response = json!({"lovedtracks": {
+            "track": [
+                {"name": "Feather", "artist": {"name": "Nujabes"}},
+                {"name": "No artist"}
+            ],
+            "@attr": {"page": "1", "totalPages": "3"}
+        }});
+        let (tracks, pages) = parse_loved_tracks(&response);
+        assert_eq!(tracks, vec![LovedTrack { artist: "Nujabes".to_string(), title: "Feather".to_string() }]);
+        assert_eq!(pages, 3);
+
+        let single = json!({"lovedtracks": {"track": {"name": "Aruarian Dance", "artist": {"name": "Nujabes"}}}});
+        assert_eq!(parse_loved_tracks(&single), (vec![LovedTrack {
+            artist: "Nujabes".to_string(),
+            title: "Aruarian Dance".to_string()
+        }], 1));
+    }
+
+    #[test]
+    fn test_error_responses_are_errors() {
+        assert!(parse_response(r#"{"error": 9, "message": "Invalid session key"}"#).is_err());
+        assert!(parse_response(r#"{"lovedtracks": {}}"#).is_ok());
+    }
+}
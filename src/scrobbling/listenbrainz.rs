@@ -0,0 +1,98 @@
+//! Submitting listens to ListenBrainz
+
+use crate::domain::entities::Listen;
+use crate::error::{LofiTurtleError, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const SUBMIT_URL: &str = "https://api.listenbrainz.org/1/submit-listens";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Client for the ListenBrainz submission API
+pub struct ListenBrainzClient {
+    token: String,
+    agent: ureq::Agent,
+}
+
+impl ListenBrainzClient {
+    /// Create a client that submits with the user token `token`
+    pub fn new(token: String) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+
+        Self { token, agent }
+    }
+
+    /// Submit listens in a single request
+    pub fn submit(&self, listens: &[Listen]) -> Result<()> {
+        let body = submission(listens).to_string();
+
+        self.agent
+            .post(SUBMIT_URL)
+            .header("Authorization", format!("Token {}", self.token))
+            .header("Content-Type", "application/json")
+            .send(body.as_str())
+            .map_err(|e| LofiTurtleError::Scrobbling(format!("ListenBrainz submission failed: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Build the JSON body for a submission
+fn submission(listens: &[Listen]) -> Value {
+    let payload: Vec<Value> = listens
+        .iter()
+        .map(|listen| {
+            json!({
+                "listened_at": listen.listened_at().timestamp(),
+                "track_metadata": {
+                    "artist_name": listen.artist(),
+                    "track_name": listen.title(),
+                    "release_name": listen.album(),
+                    "additional_info": {
+                        "duration_ms": listen.duration().total_seconds() * 1000,
+                        "media_player": "LofiTurtle",
+                        "submission_client": "LofiTurtle",
+                        "submission_client_version": env!("CARGO_PKG_VERSION"),
+                    },
+                },
+            })
+        })
+        .collect();
+
+    // "single" is meant for a listen that just finished; queued listens
+    // submitted together count as an import
+    let listen_type = if listens.len() == 1 { "single" } else { "import" };
+    json!({ "listen_type": listen_type, "payload": payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::Duration as TrackDuration;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_submission_body() {
+        let listen = Listen::new(
+            "/music/feather.mp3".to_string(),
+            "Feather".to_string(),
+            "Nujabes".to_string(),
+            "Modal Soul".to_string(),
+            TrackDuration::from_seconds(175),
+            TrackDuration::from_seconds(175),
+            Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+        );
+
+        let body = submission(std::slice::from_ref(&listen));
+        assert_eq!(body["listen_type"], "single");
+        assert_eq!(body["payload"][0]["listened_at"], 1_700_000_000);
+        assert_eq!(body["payload"][0]["track_metadata"]["track_name"], "Feather");
+        assert_eq!(body["payload"][0]["track_metadata"]["additional_info"]["duration_ms"], 175_000);
+
+        assert_eq!(submission(&[listen.clone(), listen])["listen_type"], "import");
+    }
+}
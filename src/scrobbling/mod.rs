@@ -0,0 +1,317 @@
+//! Listening history and scrobbling
+//!
+//! A plugin watches player events and turns every track that was played long
+//! enough into a listen. Listens are handed to a background worker that
+//! stores them in the library database and, when a ListenBrainz token is
+//! configured, submits them. Submissions that fail, e.g. while offline, stay
+//! queued in the database and are retried with exponential backoff, so no
+//! listen is lost across restarts.
+
+mod lastfm;
+mod listenbrainz;
+
+pub use lastfm::{LastFmClient, LastFmSession, LovedTrack};
+pub use listenbrainz::ListenBrainzClient;
+
+use crate::config::Config;
+use crate::domain::entities::{Listen, ListeningSummary};
+use crate::domain::value_objects::Duration as TrackDuration;
+use crate::error::{LofiTurtleError, Result};
+use crate::plugins::{PlayerEvent, Plugin, TrackInfo};
+use crate::services::LibraryService;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Number of listens kept at hand for the recently played widget
+pub const RECENT_LISTENS: usize = 5;
+
+/// How often the worker checks for scrobbles whose retry is due
+const FLUSH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Most listens submitted in one request
+const BATCH_SIZE: usize = 50;
+
+/// The track being played and how long it has played so far
+struct CurrentTrack {
+    track: TrackInfo,
+    started_at: DateTime<Utc>,
+    played: Duration,
+    /// When playback last started or resumed, `None` while paused
+    resumed_at: Option<Instant>,
+}
+
+impl CurrentTrack {
+    fn played_until(&self, now: Instant) -> Duration {
+        self.played + self.resumed_at.map(|resumed| now - resumed).unwrap_or_default()
+    }
+}
+
+/// Works out which plays count as listens
+#[derive(Default)]
+pub struct ListenTracker {
+    current: Option<CurrentTrack>,
+}
+
+impl ListenTracker {
+    /// Update for a player event, returning a listen when a track that was
+    /// played long enough has ended
+    pub fn handle(&mut self, event: &PlayerEvent, now: Instant) -> Option<Listen> {
+        match event {
+            PlayerEvent::TrackChanged { track } => {
+                let finished = self.finish(now);
+                self.current = Some(CurrentTrack {
+                    track: track.clone(),
+                    started_at: Utc::now(),
+                    played: Duration::ZERO,
+                    resumed_at: Some(now),
+                });
+                finished
+            }
+            PlayerEvent::PlaybackPaused => {
+                if let Some(current) = &mut self.current {
+                    current.played = current.played_until(now);
+                    current.resumed_at = None;
+                }
+                None
+            }
+            PlayerEvent::PlaybackResumed => {
+                if let Some(current) = &mut self.current {
+                    current.resumed_at.get_or_insert(now);
+                }
+                None
+            }
+            PlayerEvent::PlaybackStopped | PlayerEvent::AppQuit => self.finish(now),
+            PlayerEvent::AppStarted | PlayerEvent::VolumeChanged { .. } => None,
+        }
+    }
+
+    fn finish(&mut self, now: Instant) -> Option<Listen> {
+        let current = self.current.take()?;
+        let duration = TrackDuration::from_seconds(current.track.duration);
+        let played = TrackDuration::from_seconds(current.played_until(now).as_secs());
+
+        if !Listen::qualifies(duration, played) {
+            return None;
+        }
+
+        let track = current.track;
+        Some(Listen::new(track.path, track.title, track.artist, track.album, duration, played, current.started_at))
+    }
+}
+
+/// Plugin feeding completed listens to the scrobbler
+struct ListenPlugin {
+    tracker: ListenTracker,
+    listens: Sender<Listen>,
+}
+
+impl Plugin for ListenPlugin {
+    fn name(&self) -> &str {
+        "listen-history"
+    }
+
+    fn on_event(&mut self, event: &PlayerEvent) -> Result<()> {
+        if let Some(listen) = self.tracker.handle(event, Instant::now()) {
+            self.listens
+                .send(listen)
+                .map_err(|_| LofiTurtleError::Scrobbling("Scrobbler has stopped".to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Handle to the background worker that records and submits listens
+///
+/// Dropping the handle waits for the worker to store any listens it was
+/// sent, so the last track played before quitting is not lost.
+pub struct Scrobbler {
+    listens: Option<Sender<Listen>>,
+    pending: Arc<AtomicUsize>,
+    today: Arc<Mutex<ListeningSummary>>,
+    recent: Arc<Mutex<Vec<Listen>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Scrobbler {
+    /// Start the worker, submitting to ListenBrainz if a token is configured
+    pub fn start(config: &Config) -> Result<Self> {
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
+        let client = config.listenbrainz_token.clone().map(ListenBrainzClient::new);
+
+        let pending = Arc::new(AtomicUsize::new(library.count_pending_scrobbles()?));
+        let today = Arc::new(Mutex::new(library.listening_today()?));
+        let recent = Arc::new(Mutex::new(library.recent_listens(RECENT_LISTENS)?));
+        let (listens, receiver) = mpsc::channel();
+
+        let worker_pending = pending.clone();
+        let worker_today = today.clone();
+        let worker_recent = recent.clone();
+        let worker = thread::Builder::new()
+            .name("scrobbler".to_string())
+            .spawn(move || run(library, client, receiver, worker_pending, worker_today, worker_recent))?;
+
+        Ok(Self {
+            listens: Some(listens),
+            pending,
+            today,
+            recent,
+            worker: Some(worker),
+        })
+    }
+
+    /// Plugin that reports finished tracks to this scrobbler
+    pub fn listen_plugin(&self) -> Option<Box<dyn Plugin>> {
+        let listens = self.listens.clone()?;
+        Some(Box::new(ListenPlugin {
+            tracker: ListenTracker::default(),
+            listens,
+        }))
+    }
+
+    /// Number of listens waiting to be submitted
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Listens recorded since midnight, as of the worker's last update
+    pub fn today(&self) -> ListeningSummary {
+        self.today.lock().map(|today| *today).unwrap_or_default()
+    }
+
+    /// The last `RECENT_LISTENS` listens, newest first, as of the worker's
+    /// last update
+    pub fn recent(&self) -> Vec<Listen> {
+        self.recent.lock().map(|recent| recent.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for Scrobbler {
+    fn drop(&mut self) {
+        // The worker exits once every sender, including the plugin's, is gone
+        self.listens.take();
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                log::warn!("Scrobbler worker panicked");
+            }
+        }
+    }
+}
+
+/// Worker loop: store incoming listens and keep the queue flushed
+fn run(
+    library: LibraryService,
+    client: Option<ListenBrainzClient>,
+    listens: Receiver<Listen>,
+    pending: Arc<AtomicUsize>,
+    today: Arc<Mutex<ListeningSummary>>,
+    recent: Arc<Mutex<Vec<Listen>>>,
+) {
+    loop {
+        let shutting_down = match listens.recv_timeout(FLUSH_INTERVAL) {
+            Ok(listen) => {
+                if let Err(e) = library.record_listen(listen, client.is_some()) {
+                    log::warn!("Failed to record listen: {}", e);
+                }
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        // Leave the queue for the next start rather than delaying shutdown
+        // on a slow network
+        if let (Some(client), false) = (&client, shutting_down) {
+            if let Err(e) = flush(&library, client) {
+                log::warn!("Failed to update scrobble queue: {}", e);
+            }
+        }
+
+        match library.count_pending_scrobbles() {
+            Ok(count) => pending.store(count, Ordering::Relaxed),
+            Err(e) => log::warn!("Failed to count pending scrobbles: {}", e),
+        }
+
+        // Refreshed on every pass so the totals also reset at midnight
+        match library.listening_today() {
+            Ok(summary) => {
+                if let Ok(mut today) = today.lock() {
+                    *today = summary;
+                }
+            }
+            Err(e) => log::warn!("Failed to summarize today's listening: {}", e),
+        }
+
+        match library.recent_listens(RECENT_LISTENS) {
+            Ok(listens) => {
+                if let Ok(mut recent) = recent.lock() {
+                    *recent = listens;
+                }
+            }
+            Err(e) => log::warn!("Failed to look up recent listens: {}", e),
+        }
+
+        if shutting_down {
+            break;
+        }
+    }
+}
+
+/// Submit due scrobbles until the queue is empty or a submission fails
+fn flush(library: &LibraryService, client: &ListenBrainzClient) -> Result<()> {
+    loop {
+        let scrobbles = library.get_due_scrobbles(BATCH_SIZE)?;
+        if scrobbles.is_empty() {
+            return Ok(());
+        }
+
+        let batch: Vec<Listen> = scrobbles.iter().map(|scrobble| scrobble.listen.clone()).collect();
+        match client.submit(&batch) {
+            Ok(()) => {
+                log::info!("Scrobbled {} listens", batch.len());
+                library.complete_scrobbles(scrobbles)?;
+            }
+            Err(e) => {
+                log::debug!("Will retry {} scrobbles: {}", batch.len(), e);
+                return library.retry_scrobbles(scrobbles);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(duration: u64) -> TrackInfo {
+        TrackInfo {
+            path: "/music/song.mp3".to_string(),
+            title: "Title".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            duration,
+        }
+    }
+
+    #[test]
+    fn test_tracker_counts_played_time() {
+        let mut tracker = ListenTracker::default();
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert!(tracker.handle(&PlayerEvent::TrackChanged { track: track(200) }, at(0)).is_none());
+        tracker.handle(&PlayerEvent::PlaybackPaused, at(60));
+        // Time spent paused does not count
+        tracker.handle(&PlayerEvent::PlaybackResumed, at(600));
+        let listen = tracker.handle(&PlayerEvent::TrackChanged { track: track(200) }, at(640)).unwrap();
+        assert_eq!(listen.played().total_seconds(), 100);
+        assert_eq!(listen.title(), "Title");
+
+        // Skipped after a few seconds
+        assert!(tracker.handle(&PlayerEvent::PlaybackStopped, at(650)).is_none());
+        assert!(tracker.handle(&PlayerEvent::AppQuit, at(700)).is_none());
+    }
+}
@@ -0,0 +1,286 @@
+//! Track identification through AcoustID
+//!
+//! Files are fingerprinted with chromaprint's `fpcalc` tool and looked up on
+//! AcoustID, which links fingerprints to MusicBrainz recordings.
+
+use crate::config::Config;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::tags::TrackTags;
+use serde::Deserialize;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const LOOKUP_URL: &str = "https://api.acoustid.org/v2/lookup";
+
+/// AcoustID allows three requests per second per client
+const REQUEST_INTERVAL: Duration = Duration::from_millis(334);
+
+/// Matches below this score are too unreliable to tag files with
+const MIN_SCORE: f64 = 0.8;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whether a file lacks the tags identification can provide
+pub fn needs_identification(tags: &TrackTags) -> bool {
+    tags.title.is_none() || tags.artist.is_none()
+}
+
+/// Chromaprint fingerprint of an audio file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Fingerprint {
+    pub duration: f64,
+    pub fingerprint: String,
+}
+
+impl Fingerprint {
+    /// Fingerprint `path` with `fpcalc`
+    pub fn calculate(path: &Path) -> Result<Self> {
+        let output = Command::new("fpcalc").arg("-json").arg(path).output().map_err(fpcalc_error)?;
+
+        if !output.status.success() {
+            return Err(LofiTurtleError::Identification(format!(
+                "fpcalc failed on '{}': {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| LofiTurtleError::Identification(format!("Unexpected fpcalc output: {}", e)))
+    }
+}
+
+fn fpcalc_error(e: std::io::Error) -> LofiTurtleError {
+    if e.kind() == ErrorKind::NotFound {
+        LofiTurtleError::Identification(
+            "fpcalc not found; install chromaprint to identify tracks".to_string(),
+        )
+    } else {
+        LofiTurtleError::Identification(format!("Failed to run fpcalc: {}", e))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupResponse {
+    status: String,
+    #[serde(default)]
+    results: Vec<LookupResult>,
+    error: Option<LookupError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupResult {
+    score: f64,
+    #[serde(default)]
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    title: Option<String>,
+    #[serde(default)]
+    artists: Vec<Artist>,
+    #[serde(default)]
+    releasegroups: Vec<ReleaseGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Artist {
+    name: String,
+    joinphrase: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseGroup {
+    title: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+/// Tags from the best match of a lookup response, if it is good enough
+fn parse_lookup(body: &str) -> Result<Option<TrackTags>> {
+    let response: LookupResponse = serde_json::from_str(body)
+        .map_err(|e| LofiTurtleError::Identification(format!("Unexpected AcoustID response: {}", e)))?;
+
+    if response.status != "ok" {
+        let message = response.error.map(|error| error.message).unwrap_or(response.status);
+        return Err(LofiTurtleError::Identification(format!("AcoustID lookup failed: {}", message)));
+    }
+
+    let best = response
+        .results
+        .iter()
+        .filter(|result| result.score >= MIN_SCORE)
+        .max_by(|a, b| a.score.total_cmp(&b.score));
+
+    let Some(recording) = best.and_then(|result| result.recordings.iter().find(|recording| recording.title.is_some())) else {
+        return Ok(None);
+    };
+
+    let artist: String = recording
+        .artists
+        .iter()
+        .map(|artist| format!("{}{}", artist.name, artist.joinphrase.as_deref().unwrap_or("")))
+        .collect();
+
+    // Prefer the album a track appeared on over singles and compilations
+    let album = recording
+        .releasegroups
+        .iter()
+        .find(|group| group.kind.as_deref() == Some("Album"))
+        .or_else(|| recording.releasegroups.first())
+        .map(|group| group.title.clone());
+
+    Ok(Some(TrackTags {
+        title: recording.title.clone(),
+        artist: Some(artist).filter(|artist| !artist.is_empty()),
+        album,
+        ..TrackTags::default()
+    }))
+}
+
+/// Client for the AcoustID lookup API
+pub struct AcoustIdClient {
+    api_key: String,
+    agent: ureq::Agent,
+    last_request: Option<Instant>,
+}
+
+impl AcoustIdClient {
+    /// Create a client from the configured API key
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let api_key = config.acoustid_key.clone().ok_or_else(|| {
+            LofiTurtleError::Configuration(
+                "An AcoustID API key is required; pass --acoustid-key or set ACOUSTID_API_KEY".to_string(),
+            )
+        })?;
+        Self::new(api_key)
+    }
+
+    /// Create a client, checking that `fpcalc` is installed
+    pub fn new(api_key: String) -> Result<Self> {
+        Command::new("fpcalc").arg("-version").output().map_err(fpcalc_error)?;
+
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        Ok(Self { api_key, agent, last_request: None })
+    }
+
+    /// Look up a fingerprint, waiting as needed to respect the rate limit
+    pub fn lookup(&mut self, fingerprint: &Fingerprint) -> Result<Option<TrackTags>> {
+        if let Some(elapsed) = self.last_request.map(|last| last.elapsed()) {
+            if elapsed < REQUEST_INTERVAL {
+                thread::sleep(REQUEST_INTERVAL - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+
+        let duration = (fingerprint.duration.round() as u64).to_string();
+        let body = self
+            .agent
+            .post(LOOKUP_URL)
+            .send_form([
+                ("client", self.api_key.as_str()),
+                ("meta", "recordings releasegroups"),
+                ("duration", duration.as_str()),
+                ("fingerprint", fingerprint.fingerprint.as_str()),
+            ])
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| LofiTurtleError::Identification(format!("AcoustID request failed: {}", e)))?;
+
+        parse_lookup(&body)
+    }
+}
+
+/// Identify untagged files, returning the tags each one is missing
+///
+/// Files that cannot be read or fingerprinted are skipped. A failed lookup
+/// stops the run, since it usually means the key or the network is at fault.
+/// Nothing is written to the files.
+pub fn identify_files(client: &mut AcoustIdClient, paths: &[PathBuf]) -> Result<Vec<(PathBuf, TrackTags)>> {
+    let mut identified = Vec::new();
+
+    for path in paths {
+        let existing = match TrackTags::read(path) {
+            Ok(tags) if needs_identification(&tags) => tags,
+            Ok(_) => continue,
+            Err(e) => {
+                log::warn!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let fingerprint = match Fingerprint::calculate(path) {
+            Ok(fingerprint) => fingerprint,
+            Err(e) => {
+                log::warn!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        if let Some(found) = client.lookup(&fingerprint)? {
+            let missing = found.missing_from(&existing);
+            if !missing.is_empty() {
+                identified.push((path.clone(), missing));
+            }
+        }
+    }
+
+    Ok(identified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lookup_picks_best_match() {
+        let body = r#"{
+            "status": "ok",
+            "results": [
+                {"id": "a", "score": 0.85, "recordings": [{"title": "Other"}]},
+                {"id": "b", "score": 0.97, "recordings": [{
+                    "title": "Luv(sic.) pt3",
+                    "artists": [
+                        {"name": "Nujabes", "joinphrase": " feat. "},
+                        {"name": "Shing02"}
+                    ],
+                    "releasegroups": [
+                        {"title": "Luv(sic.) pt3", "type": "Single"},
+                        {"title": "Modal Soul", "type": "Album"}
+                    ]
+                }]}
+            ]
+        }"#;
+
+        let tags = parse_lookup(body).unwrap().unwrap();
+        assert_eq!(tags.title.as_deref(), Some("Luv(sic.) pt3"));
+        assert_eq!(tags.artist.as_deref(), Some("Nujabes feat. Shing02"));
+        assert_eq!(tags.album.as_deref(), Some("Modal Soul"));
+    }
+
+    #[test]
+    fn test_parse_lookup_without_confident_match() {
+        let weak = r#"{"status": "ok", "results": [{"id": "a", "score": 0.4, "recordings": [{"title": "Guess"}]}]}"#;
+        assert_eq!(parse_lookup(weak).unwrap(), None);
+
+        let unlinked = r#"{"status": "ok", "results": [{"id": "a", "score": 0.99}]}"#;
+        assert_eq!(parse_lookup(unlinked).unwrap(), None);
+
+        let error = r#"{"status": "error", "error": {"code": 4, "message": "invalid API key"}}"#;
+        let message = parse_lookup(error).unwrap_err().to_string();
+        assert!(message.contains("invalid API key"));
+    }
+}
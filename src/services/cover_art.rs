@@ -0,0 +1,160 @@
+//! Album covers from the Cover Art Archive
+//!
+//! The Cover Art Archive holds the covers of MusicBrainz releases. Albums are
+//! found by the release ID Picard and other taggers write into files, or by
+//! searching MusicBrainz for the album artist and album when the files have
+//! none. Downloaded covers go to the [`CoverCache`] the album art panel reads.
+//!
+//! [`CoverCache`]: crate::art::CoverCache
+
+use crate::error::{LofiTurtleError, Result};
+use serde::Deserialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SEARCH_URL: &str = "https://musicbrainz.org/ws/2/release/";
+const COVER_URL: &str = "https://coverartarchive.org/release";
+
+/// MusicBrainz asks clients to identify themselves
+const USER_AGENT: &str = concat!("LofiTurtle/", env!("CARGO_PKG_VERSION"), " ( https://github.com/uy-td-dev/LoFi-Turtle )");
+
+/// MusicBrainz allows one request per second per client
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Search results below this score are likely other albums
+const MIN_SCORE: u32 = 90;
+
+/// Largest cover downloaded
+const MAX_COVER_BYTES: u64 = 10 * 1024 * 1024;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    releases: Vec<Release>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    id: String,
+    #[serde(default)]
+    score: u32,
+}
+
+/// ID of the best release of a search response, if it is good enough
+fn parse_search(body: &str) -> Result<Option<String>> {
+    let response: SearchResponse = serde_json::from_str(body)
+        .map_err(|e| LofiTurtleError::CoverArt(format!("Unexpected MusicBrainz response: {}", e)))?;
+
+    Ok(response
+        .releases
+        .into_iter()
+        .filter(|release| release.score >= MIN_SCORE)
+        .max_by_key(|release| release.score)
+        .map(|release| release.id))
+}
+
+/// Quote a search term for MusicBrainz's Lucene query syntax
+fn quote(term: &str) -> String {
+    format!("\"{}\"", term.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Client for MusicBrainz release search and the Cover Art Archive
+pub struct CoverArtClient {
+    agent: ureq::Agent,
+    last_request: Option<Instant>,
+}
+
+impl CoverArtClient {
+    pub fn new() -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .user_agent(USER_AGENT)
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        Self { agent, last_request: None }
+    }
+
+    /// Find the MusicBrainz release of an album
+    pub fn find_release(&mut self, artist: &str, album: &str) -> Result<Option<String>> {
+        self.wait();
+        let query = format!("release:{} AND artist:{}", quote(album), quote(artist));
+        let body = self
+            .agent
+            .get(SEARCH_URL)
+            .query_pairs([("query", query.as_str()), ("fmt", "json"), ("limit", "5")])
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| LofiTurtleError::CoverArt(format!("MusicBrainz request failed: {}", e)))?;
+
+        parse_search(&body)
+    }
+
+    /// Download the front cover of a release, if it has one
+    pub fn front_cover(&mut self, release_id: &str) -> Result<Option<Vec<u8>>> {
+        self.wait();
+        // 500 pixels is plenty for the panel and far smaller than the original
+        let url = format!("{}/{}/front-500", COVER_URL, release_id);
+        let mut response = self
+            .agent
+            .get(&url)
+            .call()
+            .map_err(|e| LofiTurtleError::CoverArt(format!("Cover Art Archive request failed: {}", e)))?;
+
+        match response.status().as_u16() {
+            200 => {}
+            404 => return Ok(None),
+            status => return Err(LofiTurtleError::CoverArt(format!("Cover Art Archive answered {}", status))),
+        }
+
+        let cover = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_COVER_BYTES)
+            .read_to_vec()
+            .map_err(|e| LofiTurtleError::CoverArt(format!("Cover Art Archive request failed: {}", e)))?;
+        Ok(Some(cover))
+    }
+
+    fn wait(&mut self) {
+        if let Some(elapsed) = self.last_request.map(|last| last.elapsed()) {
+            if elapsed < REQUEST_INTERVAL {
+                thread::sleep(REQUEST_INTERVAL - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+impl Default for CoverArtClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_takes_best_match() {
+        let body = r#"{"releases": [
+            {"id": "low", "score": 60, "title": "Modal Soul (Remixes)"},
+            {"id": "best", "score": 100, "title": "Modal Soul"},
+            {"id": "other", "score": 95, "title": "Modal Soul"}
+        ]}"#;
+        assert_eq!(parse_search(body).unwrap().as_deref(), Some("best"));
+
+        let weak = r#"{"releases": [{"id": "low", "score": 60}]}"#;
+        assert_eq!(parse_search(weak).unwrap(), None);
+        assert!(parse_search("not json").is_err());
+    }
+
+    #[test]
+    fn test_quote_escapes_lucene() {
+        assert_eq!(quote(r#"Say "Hi" \o/"#), r#""Say \"Hi\" \\o/""#);
+    }
+}
@@ -0,0 +1,149 @@
+//! Hand-offs to the desktop environment
+
+use crate::audio::stream;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::file_url;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Show `file` in the system file manager
+///
+/// On macOS and Windows the file is selected in its folder. Linux has no
+/// portable way to select a file, so the containing folder is opened instead.
+pub fn reveal_in_file_manager(file: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(file);
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", file.display()));
+        command
+    } else {
+        let folder = file.parent().ok_or_else(|| {
+            LofiTurtleError::FileSystem(std::io::Error::other(format!("{} has no parent folder", file.display())))
+        })?;
+        let mut command = Command::new("xdg-open");
+        command.arg(folder);
+        command
+    };
+
+    // Keep the launcher's output off the TUI
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Reap the launcher in the background so it does not linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// System clipboard, opened on first use
+///
+/// On X11 the copied text is served by this process, so the clipboard must be
+/// kept alive for as long as the text should stay pasteable.
+#[derive(Default)]
+pub struct Clipboard {
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Replace the clipboard contents with `text`
+    pub fn set_text(&mut self, text: &str) -> Result<()> {
+        self.open()?
+            .set_text(text)
+            .map_err(|e| LofiTurtleError::Clipboard(e.to_string()))
+    }
+
+    /// The text on the clipboard
+    pub fn get_text(&mut self) -> Result<String> {
+        self.open()?
+            .get_text()
+            .map_err(|e| LofiTurtleError::Clipboard(e.to_string()))
+    }
+
+    fn open(&mut self) -> Result<&mut arboard::Clipboard> {
+        let clipboard = match self.inner.take() {
+            Some(clipboard) => clipboard,
+            None => arboard::Clipboard::new().map_err(|e| LofiTurtleError::Clipboard(e.to_string()))?,
+        };
+        Ok(self.inner.insert(clipboard))
+    }
+}
+
+/// Something pasted that can be queued
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pasted {
+    /// A file or a folder of them
+    Path(PathBuf),
+    /// The URL of a stream
+    Stream(String),
+}
+
+/// The paths and stream URLs in pasted `text`, one a line
+///
+/// File managers copy files as paths or `file://` URLs, and shells quote
+/// paths with spaces, so both forms are understood. Whether the paths
+/// exist is left to the caller.
+pub fn parse_pasted(text: &str) -> Vec<Pasted> {
+    text.lines()
+        .map(|line| {
+            let line = line.trim();
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|&quote| line.strip_prefix(quote).and_then(|line| line.strip_suffix(quote)));
+            unquoted.unwrap_or(line)
+        })
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if stream::is_stream(line) {
+                Pasted::Stream(line.to_string())
+            } else if let Some(path) = file_url::to_path(line) {
+                Pasted::Path(path)
+            } else {
+                Pasted::Path(PathBuf::from(line))
+            }
+        })
+        .collect()
+}
+
+/// Delete `file`, moving it to the system trash when `to_trash` is set
+pub fn delete_file(file: &Path, to_trash: bool) -> Result<()> {
+    if to_trash {
+        trash::delete(file).map_err(|e| {
+            LofiTurtleError::FileSystem(std::io::Error::other(format!("Failed to move {} to trash: {}", file.display(), e)))
+        })
+    } else {
+        Ok(std::fs::remove_file(file)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pasted() {
+        let text = "/music/a.mp3\n  'My Music/b c.flac'  \n\nfile:///music/Caf%C3%A9\nhttps://radio.example.com/lofi\n";
+        assert_eq!(parse_pasted(text), [
+            Pasted::Path(PathBuf::from("/music/a.mp3")),
+            Pasted::Path(PathBuf::from("My Music/b c.flac")),
+            Pasted::Path(PathBuf::from("/music/Café")),
+            Pasted::Stream("https://radio.example.com/lofi".to_string()),
+        ]);
+        assert!(parse_pasted("  \n").is_empty());
+    }
+
+    #[test]
+    fn test_delete_file_permanently() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("song.mp3");
+        std::fs::write(&file, "audio").unwrap();
+
+        delete_file(&file, false).unwrap();
+        assert!(!file.exists());
+        assert!(delete_file(&file, false).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! Listening history export for backups and other services
+
+use crate::domain::entities::Listen;
+use crate::error::{LofiTurtleError, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::io::Write;
+
+/// Column order of CSV exports, matching the JSON field names
+const CSV_HEADER: [&str; 7] = ["listened_at", "artist", "title", "album", "duration", "played", "track_path"];
+
+/// A listen as written to an export, with durations in seconds
+#[derive(Debug, Serialize)]
+struct ExportedListen<'a> {
+    listened_at: DateTime<Utc>,
+    artist: &'a str,
+    title: &'a str,
+    album: &'a str,
+    duration: u64,
+    played: u64,
+    track_path: &'a str,
+}
+
+impl<'a> From<&'a Listen> for ExportedListen<'a> {
+    fn from(listen: &'a Listen) -> Self {
+        Self {
+            listened_at: listen.listened_at(),
+            artist: listen.artist(),
+            title: listen.title(),
+            album: listen.album(),
+            duration: listen.duration().total_seconds(),
+            played: listen.played().total_seconds(),
+            track_path: listen.track_path(),
+        }
+    }
+}
+
+/// Write listens as a JSON array
+pub fn write_json<W: Write>(listens: &[Listen], mut writer: W) -> Result<()> {
+    let exported: Vec<ExportedListen> = listens.iter().map(ExportedListen::from).collect();
+    serde_json::to_writer_pretty(&mut writer, &exported)
+        .map_err(|e| LofiTurtleError::MusicLibrary(format!("Failed to write listening history: {}", e)))?;
+
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write listens as CSV with a header row
+///
+/// Timestamps are RFC 3339 in UTC and durations are whole seconds.
+pub fn write_csv<W: Write>(listens: &[Listen], mut writer: W) -> Result<()> {
+    writeln!(writer, "{}", CSV_HEADER.join(","))?;
+
+    for listen in listens {
+        let listen = ExportedListen::from(listen);
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            listen.listened_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            csv_field(listen.artist),
+            csv_field(listen.title),
+            csv_field(listen.album),
+            listen.duration,
+            listen.played,
+            csv_field(listen.track_path),
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::Duration;
+
+    fn listens() -> Vec<Listen> {
+        vec![
+            Listen::new(
+                "/music/Nujabes/Feather.mp3".to_string(),
+                "Feather".to_string(),
+                "Nujabes".to_string(),
+                "Modal Soul".to_string(),
+                Duration::from_seconds(175),
+                Duration::from_seconds(175),
+                "2026-03-10T08:15:00Z".parse().unwrap(),
+            ),
+            Listen::new(
+                "/music/Misc/Track.mp3".to_string(),
+                "Say \"Hi\", Again".to_string(),
+                "Various".to_string(),
+                "".to_string(),
+                Duration::from_seconds(200),
+                Duration::from_seconds(120),
+                "2026-03-10T09:00:00Z".parse().unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut output = Vec::new();
+        write_csv(&listens(), &mut output).unwrap();
+
+        let csv = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "listened_at,artist,title,album,duration,played,track_path");
+        assert_eq!(lines[1], "2026-03-10T08:15:00Z,Nujabes,Feather,Modal Soul,175,175,/music/Nujabes/Feather.mp3");
+        assert_eq!(lines[2], "2026-03-10T09:00:00Z,Various,\"Say \"\"Hi\"\", Again\",,200,120,/music/Misc/Track.mp3");
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut output = Vec::new();
+        write_json(&listens(), &mut output).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json[0]["artist"], "Nujabes");
+        assert_eq!(json[0]["listened_at"], "2026-03-10T08:15:00Z");
+        assert_eq!(json[1]["played"], 120);
+        assert_eq!(json.as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,306 @@
+//! Writing the library in the formats of other players, with playlists,
+//! play counts and ratings
+//!
+//! iTunes and Music, and the players that read their `Library.xml`, get the
+//! whole library in one file. MPD finds songs by scanning the music
+//! directory itself, so it gets its playlists as M3U files and the play
+//! counts and ratings as stickers, the way [`stats_import`] reads them back.
+//!
+//! [`stats_import`]: crate::services::stats_import
+
+use crate::domain::entities::SongStats;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::{file_url, tag_pattern};
+use crate::models::Playlist;
+use chrono::{DateTime, Utc};
+use plist::{Date, Dictionary, Value};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// What an MPD export wrote
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MpdExport {
+    pub playlists: usize,
+    /// Songs given stickers
+    pub songs: usize,
+}
+
+/// Write the library as an iTunes `Library.xml`
+pub fn write_itunes_xml(songs: &[SongStats], playlists: &[Playlist], music_dir: &Path, out: impl Write) -> Result<()> {
+    // Tracks are numbered in order, and playlists refer to them by number
+    let track_ids: HashMap<&str, i64> =
+        songs.iter().zip(1..).map(|(stats, track_id)| (stats.song.id().as_str(), track_id)).collect();
+
+    let tracks: Dictionary = songs
+        .iter()
+        .zip(1..)
+        .map(|(stats, track_id)| (track_id.to_string(), itunes_track(stats, track_id)))
+        .collect();
+
+    let playlists = playlists
+        .iter()
+        .zip(1..)
+        .map(|(playlist, playlist_id)| {
+            let items = playlist
+                .song_ids
+                .iter()
+                .filter_map(|id| track_ids.get(id.as_str()))
+                .map(|&track_id| Value::Dictionary(Dictionary::from_iter([("Track ID", track_id)])))
+                .collect();
+            let mut entries = Dictionary::new();
+            entries.insert("Name".to_string(), playlist.name.clone().into());
+            entries.insert("Playlist ID".to_string(), playlist_id.into());
+            entries.insert("Playlist Persistent ID".to_string(), persistent_id(&playlist.id).into());
+            if let Some(description) = &playlist.description {
+                entries.insert("Description".to_string(), description.clone().into());
+            }
+            entries.insert("All Items".to_string(), true.into());
+            entries.insert("Playlist Items".to_string(), Value::Array(items));
+            Value::Dictionary(entries)
+        })
+        .collect();
+
+    let mut library = Dictionary::new();
+    library.insert("Major Version".to_string(), 1.into());
+    library.insert("Minor Version".to_string(), 1.into());
+    library.insert("Date".to_string(), itunes_date(Utc::now()));
+    library.insert("Application Version".to_string(), format!("LofiTurtle {}", env!("CARGO_PKG_VERSION")).into());
+    library.insert("Music Folder".to_string(), file_url::from_path(music_dir).into());
+    library.insert("Tracks".to_string(), Value::Dictionary(tracks));
+    library.insert("Playlists".to_string(), Value::Array(playlists));
+    Value::Dictionary(library)
+        .to_writer_xml(out)
+        .map_err(|e| LofiTurtleError::MusicLibrary(format!("Failed to write the library: {}", e)))
+}
+
+fn itunes_track(stats: &SongStats, track_id: i64) -> Value {
+    let song = &stats.song;
+    let mut entries = Dictionary::new();
+    entries.insert("Track ID".to_string(), track_id.into());
+    entries.insert("Persistent ID".to_string(), persistent_id(song.id().as_str()).into());
+    entries.insert("Name".to_string(), song.title().into());
+    entries.insert("Artist".to_string(), song.artist().into());
+    entries.insert("Album".to_string(), song.album().into());
+    if let Some(genre) = song.genre() {
+        entries.insert("Genre".to_string(), genre.into());
+    }
+    entries.insert("Total Time".to_string(), (song.duration().total_seconds() as i64 * 1000).into());
+    entries.insert("Date Added".to_string(), itunes_date(stats.added_at));
+    if stats.play_count > 0 {
+        entries.insert("Play Count".to_string(), i64::from(stats.play_count).into());
+    }
+    if let Some(played) = stats.last_played_at {
+        entries.insert("Play Date UTC".to_string(), itunes_date(played));
+    }
+    if let Some(stars) = stats.rating {
+        entries.insert("Rating".to_string(), (i64::from(stars) * 20).into());
+    }
+    entries.insert("Location".to_string(), file_url::from_path(Path::new(song.file_path().as_str())).into());
+    Value::Dictionary(entries)
+}
+
+/// The 16 hex digits iTunes identifies tracks and playlists by, kept the
+/// same across exports
+fn persistent_id(id: &str) -> String {
+    format!("{:X}", md5::compute(id))[..16].to_string()
+}
+
+/// A plist date, which keeps whole seconds
+fn itunes_date(time: DateTime<Utc>) -> Value {
+    let whole = DateTime::from_timestamp(time.timestamp(), 0).unwrap_or(time);
+    Value::Date(Date::from(SystemTime::from(whole)))
+}
+
+/// Write the playlists as M3U files into `dir`, with paths relative to
+/// `music_dir` as MPD's `music_directory`, and the play counts, last plays
+/// and ratings as stickers in `dir/sticker.sql`
+///
+/// Stickers already in `sticker.sql` are kept unless the export replaces
+/// them, so it can be written into the database of a stopped MPD.
+pub fn write_mpd(songs: &[SongStats], playlists: &[Playlist], music_dir: &Path, dir: &Path) -> Result<MpdExport> {
+    fs::create_dir_all(dir)?;
+    let uris: HashMap<&str, String> = songs
+        .iter()
+        .map(|stats| (stats.song.id().as_str(), mpd_uri(stats.song.file_path().as_str(), music_dir)))
+        .collect();
+
+    // Names that only differ in characters files cannot have, or in case
+    // on some file systems, would overwrite each other
+    let mut taken = HashSet::new();
+    for playlist in playlists {
+        let mut m3u = String::new();
+        for uri in playlist.song_ids.iter().filter_map(|id| uris.get(id.as_str())) {
+            m3u.push_str(uri);
+            m3u.push('\n');
+        }
+        let base = tag_pattern::sanitize(&playlist.name);
+        let name = (1..)
+            .map(|n| if n == 1 { base.clone() } else { format!("{} ({})", base, n) })
+            .find(|name| taken.insert(name.to_lowercase()))
+            .unwrap_or(base);
+        fs::write(dir.join(format!("{}.m3u", name)), m3u)?;
+    }
+
+    let mut conn = Connection::open(dir.join("sticker.sql"))?;
+    // The table and index as MPD creates them
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sticker(
+            type VARCHAR(256) NOT NULL,
+            uri VARCHAR(256) NOT NULL,
+            name VARCHAR(256) NOT NULL,
+            value VARCHAR(256) NOT NULL);
+         CREATE UNIQUE INDEX IF NOT EXISTS sticker_value ON sticker(type, uri, name);",
+    )?;
+
+    let transaction = conn.transaction()?;
+    let mut stickered = 0;
+    {
+        let mut insert =
+            transaction.prepare("INSERT OR REPLACE INTO sticker (type, uri, name, value) VALUES ('song', ?1, ?2, ?3)")?;
+        for stats in songs {
+            // MPD only knows songs in its music directory
+            let Ok(uri) = Path::new(stats.song.file_path().as_str()).strip_prefix(music_dir) else {
+                continue;
+            };
+            let uri = uri.to_string_lossy().replace('\\', "/");
+            let stickers = [
+                ("playCount", (stats.play_count > 0).then(|| stats.play_count.to_string())),
+                ("lastPlayed", stats.last_played_at.map(|played| played.timestamp().to_string())),
+                // Ratings go from 0 to 10, two a star
+                ("rating", stats.rating.map(|stars| (stars * 2).to_string())),
+            ];
+            let mut any = false;
+            for (name, value) in stickers {
+                if let Some(value) = value {
+                    insert.execute((&uri, name, value))?;
+                    any = true;
+                }
+            }
+            stickered += usize::from(any);
+        }
+    }
+    transaction.commit()?;
+
+    Ok(MpdExport { playlists: playlists.len(), songs: stickered })
+}
+
+/// Path of a song as MPD refers to it: relative to its music directory,
+/// or absolute for songs outside it
+fn mpd_uri(path: &str, music_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(music_dir) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::Song;
+    use crate::domain::value_objects::{Duration, FilePath};
+    use crate::services::stats_import;
+
+    fn stats(path: &str, title: &str, play_count: u32, rating: Option<u8>) -> SongStats {
+        let song = Song::new(
+            FilePath::new(path).unwrap(),
+            title.to_string(),
+            "Nujabes".to_string(),
+            "Modal Soul".to_string(),
+            Duration::from_seconds(175),
+        )
+        .unwrap();
+        SongStats {
+            song,
+            play_count,
+            last_played_at: DateTime::from_timestamp(1_700_000_000, 0),
+            added_at: DateTime::from_timestamp(1_600_000_000, 0).unwrap(),
+            rating,
+        }
+    }
+
+    fn playlist(songs: &[&SongStats]) -> Playlist {
+        named_playlist("Late / night", songs)
+    }
+
+    fn named_playlist(name: &str, songs: &[&SongStats]) -> Playlist {
+        Playlist {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            song_ids: songs.iter().map(|stats| stats.song.id().as_str().to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            total_duration: 0,
+        }
+    }
+
+    #[test]
+    fn test_itunes_xml_reads_back() {
+        let songs = [stats("/music/feather.mp3", "Feather", 42, Some(4)), stats("/music/lady.mp3", "Lady Brown", 0, None)];
+        let playlists = [playlist(&[&songs[1], &songs[0]])];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Library.xml");
+        write_itunes_xml(&songs, &playlists, Path::new("/music"), fs::File::create(&path).unwrap()).unwrap();
+
+        let library = Value::from_file(&path).unwrap();
+        let items = library
+            .as_dictionary()
+            .and_then(|library| library.get("Playlists"))
+            .and_then(Value::as_array)
+            .and_then(|playlists| playlists[0].as_dictionary())
+            .and_then(|playlist| playlist.get("Playlist Items"))
+            .cloned();
+        assert_eq!(items, Some(Value::Array(vec![
+            Value::Dictionary(Dictionary::from_iter([("Track ID", 2)])),
+            Value::Dictionary(Dictionary::from_iter([("Track ID", 1)])),
+        ])));
+
+        // Both songs are played or rated, so the importer finds both
+        let tracks = stats_import::read_itunes(&path).unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].path, Some("/music/feather.mp3".into()));
+        assert_eq!(tracks[0].stats.play_count, 42);
+        assert_eq!(tracks[0].stats.rating, Some(4));
+    }
+
+    #[test]
+    fn test_mpd_playlists_and_stickers() {
+        let songs = [stats("/music/a/feather.mp3", "Feather", 3, Some(5)), stats("/elsewhere/lady.mp3", "Lady Brown", 1, None)];
+        let playlists = [playlist(&[&songs[0], &songs[1]])];
+        let dir = tempfile::tempdir().unwrap();
+
+        let export = write_mpd(&songs, &playlists, Path::new("/music"), dir.path()).unwrap();
+        assert_eq!(export, MpdExport { playlists: 1, songs: 1 });
+        assert_eq!(fs::read_to_string(dir.path().join("Late _ night.m3u")).unwrap(), "a/feather.mp3\n/elsewhere/lady.mp3\n");
+
+        // Exporting again replaces the stickers instead of adding to them
+        write_mpd(&songs, &playlists, Path::new("/music"), dir.path()).unwrap();
+        let tracks = stats_import::read_mpd_stickers(&dir.path().join("sticker.sql"), Path::new("/music")).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].stats.play_count, 3);
+        assert_eq!(tracks[0].stats.rating, Some(5));
+        assert_eq!(tracks[0].stats.last_played_at, DateTime::from_timestamp(1_700_000_000, 0));
+    }
+
+    #[test]
+    fn test_mpd_playlist_names_stay_apart() {
+        let songs = [stats("/music/a.mp3", "A", 0, None), stats("/music/b.mp3", "B", 0, None), stats("/music/c.mp3", "C", 0, None)];
+        let playlists = [
+            named_playlist("Late / night", &[&songs[0]]),
+            named_playlist("Late ? night", &[&songs[1]]),
+            named_playlist("late _ NIGHT", &[&songs[2]]),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+
+        let export = write_mpd(&songs, &playlists, Path::new("/music"), dir.path()).unwrap();
+        assert_eq!(export.playlists, 3);
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("Late _ night.m3u"), "a.mp3\n");
+        assert_eq!(read("Late _ night (2).m3u"), "b.mp3\n");
+        assert_eq!(read("late _ NIGHT (3).m3u"), "c.mp3\n");
+    }
+}
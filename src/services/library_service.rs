@@ -0,0 +1,906 @@
+use crate::application::services::{BookmarkService, ListenHistoryService, MusicLibraryService, SongData};
+use crate::domain::entities as domain;
+use crate::audio::Trim;
+use crate::domain::value_objects::{self, Duration, FilePath, PlaylistId, SongId};
+use crate::error::{LofiTurtleError, Result};
+use crate::infrastructure::factories::RepositoryFactory;
+use crate::library::ScanIssue;
+use crate::models::{Playlist, Song};
+use crate::shared::errors::ApplicationError;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::oneshot;
+
+/// Songs per page of a [`SongWindow`], more than a screen holds
+const WINDOW_PAGE_SIZE: usize = 500;
+
+/// Pages a [`SongWindow`] holds on either side of the selected song's page
+const WINDOW_MARGIN: usize = 1;
+
+/// Blocking adapter over [`MusicLibraryService`] for the TUI and CLI commands
+///
+/// The presentation layer runs on plain threads, so this adapter owns a small
+/// Tokio runtime, blocks on each service call and converts domain entities into
+/// the UI models. Loads that may be slow on large libraries can instead run in
+/// the background and be collected later through a [`Pending`], and the whole
+/// library is read a few pages at a time through a [`SongWindow`].
+pub struct LibraryService {
+    runtime: Runtime,
+    service: Arc<MusicLibraryService>,
+    history: ListenHistoryService,
+    bookmarks: BookmarkService,
+}
+
+/// What adding a batch of scanned songs did to the library
+#[derive(Debug, Default)]
+pub struct StoredSongs {
+    /// Songs that were new to the library
+    pub added: usize,
+    /// Songs already in the library whose details were refreshed
+    pub updated: usize,
+    /// Songs that could not be saved
+    pub failed: Vec<ScanIssue>,
+}
+
+/// Which songs to load for a view of the library other than the whole
+/// library, which a [`SongWindow`] shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SongQuery {
+    /// The songs of the playlist with this name, in order
+    Playlist(String),
+    /// Songs added within the last `days` days, newest first
+    RecentlyAdded { days: u32 },
+    /// The `limit` most played songs, most played first
+    MostPlayed { limit: usize },
+    /// Songs that have never been played
+    NeverPlayed,
+    /// The Daily Mix of `day`
+    DailyMix { day: NaiveDate, size: usize },
+}
+
+/// Result of a library call running in the background
+pub struct Pending<T> {
+    receiver: oneshot::Receiver<Result<T>>,
+}
+
+impl<T> Pending<T> {
+    /// A result that is already at hand
+    pub fn ready(result: Result<T>) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let _ = sender.send(result);
+        Self { receiver }
+    }
+
+    /// Take the result if the call has finished
+    pub fn poll(&mut self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => Some(Err(LofiTurtleError::ChannelError(
+                "Background library task stopped without a result".to_string(),
+            ))),
+        }
+    }
+}
+
+/// The library, or its songs matching a search, held a few pages at a time
+///
+/// Holding every song of a large library would take memory in proportion to
+/// it, so the window holds only the pages around the selected song. Pages
+/// load in the background as the selection moves, and the ones it moved
+/// away from are let go. Songs whose page is not held yet read as `None`.
+pub struct SongWindow {
+    /// Lowered search the songs match, or empty for every song
+    query: String,
+    len: usize,
+    pages: BTreeMap<usize, Vec<Song>>,
+    loading: HashMap<usize, Pending<Vec<Song>>>,
+}
+
+impl SongWindow {
+    /// Number of songs in the window, held or not
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the window has no songs at all
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Song at `index`, if its page is held
+    pub fn get(&self, index: usize) -> Option<&Song> {
+        self.pages.get(&(index / WINDOW_PAGE_SIZE))?.get(index % WINDOW_PAGE_SIZE)
+    }
+
+    /// The songs held, with their positions, in order
+    pub fn held(&self) -> impl Iterator<Item = (usize, &Song)> + '_ {
+        self.pages.iter().flat_map(|(&page, songs)| {
+            songs.iter().enumerate().map(move |(i, song)| (page * WINDOW_PAGE_SIZE + i, song))
+        })
+    }
+
+    /// The songs held, to update in place
+    pub fn held_mut(&mut self) -> impl Iterator<Item = &mut Song> + '_ {
+        self.pages.values_mut().flatten()
+    }
+
+    /// Whether pages are still loading
+    pub fn is_loading(&self) -> bool {
+        !self.loading.is_empty()
+    }
+
+    /// Hold the pages around the song at `index`, loading the missing ones
+    /// and letting go of the others
+    pub fn show(&mut self, index: usize, library: &LibraryService) {
+        if self.is_empty() {
+            return;
+        }
+        let wanted = window_pages(index, self.len);
+        self.pages.retain(|page, _| wanted.contains(page));
+        self.loading.retain(|page, _| wanted.contains(page));
+        for page in wanted {
+            if !self.pages.contains_key(&page) && !self.loading.contains_key(&page) {
+                let service = Arc::clone(&library.service);
+                let query = self.query.clone();
+                let load = library.spawn(async move { fetch_page(&service, query, page).await });
+                self.loading.insert(page, load);
+            }
+        }
+    }
+
+    /// Take the pages that have loaded
+    pub fn poll(&mut self) -> Result<()> {
+        let mut loaded = Vec::new();
+        for (&page, load) in &mut self.loading {
+            if let Some(result) = load.poll() {
+                loaded.push((page, result));
+            }
+        }
+        for (page, result) in loaded {
+            self.loading.remove(&page);
+            self.pages.insert(page, result?);
+        }
+        Ok(())
+    }
+}
+
+impl LibraryService {
+    /// Open the library stored in the database at `db_path`, sorting
+    /// artists and albums without the leading `sort_articles`
+    pub fn new<P: AsRef<Path>>(db_path: P, sort_articles: &[String]) -> Result<Self> {
+        Self::from_factory(RepositoryFactory::new(db_path, sort_articles)?)
+    }
+
+    /// Create a library backed by an in-memory database for testing
+    #[cfg(test)]
+    pub fn new_in_memory() -> Result<Self> {
+        Self::from_factory(RepositoryFactory::new_in_memory()?)
+    }
+
+    fn from_factory(factory: RepositoryFactory) -> Result<Self> {
+        // A worker thread lets background loads progress between blocking calls
+        let runtime = Builder::new_multi_thread().worker_threads(1).enable_all().build()?;
+        let bundle = factory.create_all_repositories();
+        let service = MusicLibraryService::new(
+            bundle.song_repository,
+            bundle.playlist_repository,
+            bundle.playlist_song_repository,
+        );
+        let history = ListenHistoryService::new(bundle.listen_repository);
+        let bookmarks = BookmarkService::new(bundle.bookmark_repository);
+
+        Ok(Self { runtime, service: Arc::new(service), history, bookmarks })
+    }
+
+    /// Run a service call to completion on the adapter's runtime
+    fn block_on<T, F>(&self, future: F) -> Result<T>
+    where
+        F: Future<Output = crate::shared::errors::Result<T>>,
+    {
+        Ok(self.runtime.block_on(future)?)
+    }
+
+    /// Run a service call on the adapter's runtime without waiting for it
+    fn spawn<T, F>(&self, future: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.runtime.spawn(async move {
+            // Nobody is waiting any more if the caller dropped its `Pending`
+            let _ = sender.send(future.await);
+        });
+        Pending { receiver }
+    }
+
+    /// Start loading the songs for a view in the background
+    pub fn load_songs(&self, query: SongQuery) -> Pending<Vec<Song>> {
+        let service = Arc::clone(&self.service);
+        self.spawn(async move {
+            let songs = fetch_songs(&service, query).await?;
+            Ok(songs.into_iter().map(to_song).collect())
+        })
+    }
+
+    /// Start opening a window on the songs matching `search`, or the whole
+    /// library if it is empty, in the background
+    ///
+    /// The window opens holding the pages around the song at `select`, if
+    /// it is among them, or else around position `around`.
+    pub fn open_song_window(&self, search: &str, select: Option<&str>, around: usize) -> Pending<SongWindow> {
+        let service = Arc::clone(&self.service);
+        let query = search.to_lowercase();
+        let select = select.and_then(|path| FilePath::new(path).ok());
+        self.spawn(async move {
+            let len = service.count_songs(&query).await?;
+            let position = match select {
+                Some(path) => service.get_song_position(&query, &path).await?,
+                None => None,
+            };
+            let index = position.unwrap_or(around);
+
+            let mut pages = BTreeMap::new();
+            for page in window_pages(index, len) {
+                pages.insert(page, fetch_page(&service, query.clone(), page).await?);
+            }
+            Ok(SongWindow { query, len, pages, loading: HashMap::new() })
+        })
+    }
+
+    /// Start loading all playlists with their total durations in the background
+    pub fn load_playlists(&self) -> Pending<Vec<Playlist>> {
+        let service = Arc::clone(&self.service);
+        self.spawn(async move { Ok(fetch_playlists(&service).await?) })
+    }
+
+    /// Get all songs in the library
+    pub fn get_all_songs(&self) -> Result<Vec<Song>> {
+        let songs = self.block_on(self.service.get_all_songs())?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Get the songs matching `search`, or the whole library if it is
+    /// empty, in library order
+    pub fn get_matching_songs(&self, search: &str) -> Result<Vec<Song>> {
+        let query = search.to_lowercase();
+        let songs = self.block_on(async {
+            let len = self.service.count_songs(&query).await?;
+            self.service.get_song_page(query, 0, len).await
+        })?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Get the songs of the library among `song_ids`, leaving out the rest
+    pub fn get_songs(&self, song_ids: &[String]) -> Result<Vec<Song>> {
+        let song_ids: Vec<SongId> = song_ids.iter().cloned().map(SongId::from_string).collect();
+        let songs = self.block_on(self.service.get_songs(&song_ids))?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Whether the library has no songs at all
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.block_on(self.service.count_songs(""))? == 0)
+    }
+
+    /// Search songs by title, artist or album
+    pub fn search_songs(&self, query: &str) -> Result<Vec<Song>> {
+        let songs = self.block_on(self.service.search_songs(query.to_string()))?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Remove a song from the library and all playlists
+    pub fn remove_song(&self, song_id: &str) -> Result<()> {
+        let song_id = SongId::from_string(song_id.to_string());
+        self.block_on(self.service.remove_song(song_id))
+    }
+
+    /// Point a song at its new file path after the file was moved
+    ///
+    /// Returns the relocated song, whose ID changes along with its path.
+    pub fn relocate_song(&self, song_id: &str, new_path: &str) -> Result<Song> {
+        let song_id = SongId::from_string(song_id.to_string());
+        let new_path = FilePath::new(new_path).map_err(ApplicationError::from)?;
+        let song = self.block_on(self.service.relocate_song(song_id, new_path))?;
+        Ok(to_song(song))
+    }
+
+    /// Count a play of the song stored at `path`
+    pub fn record_play(&self, path: &str) -> Result<()> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let song_id = SongId::from_path(&file_path);
+        self.block_on(self.service.record_play(song_id))
+    }
+
+    /// Mark the song with ID `song_id` as loved, or no longer loved
+    pub fn set_loved(&self, song_id: &str, loved: bool) -> Result<()> {
+        let song_id = SongId::from_string(song_id.to_string());
+        self.block_on(self.service.set_loved(song_id, loved))
+    }
+
+    /// Cut `trim` off the start and end of the song stored at `path` when
+    /// it plays
+    pub fn set_trim(&self, path: &str, trim: Trim) -> Result<()> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let song_id = SongId::from_path(&file_path);
+        let trim = value_objects::Trim::new(
+            Duration::from_seconds(trim.start.as_secs()),
+            Duration::from_seconds(trim.end.as_secs()),
+        );
+        self.block_on(self.service.set_trim(song_id, trim))
+    }
+
+    /// Get how much is cut off the song stored at `path` when it plays
+    pub fn get_trim(&self, path: &str) -> Result<Trim> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let trim = self.block_on(self.service.get_trim(&SongId::from_path(&file_path)))?;
+        Ok(Trim {
+            start: std::time::Duration::from_secs(trim.start().total_seconds()),
+            end: std::time::Duration::from_secs(trim.end().total_seconds()),
+        })
+    }
+
+    /// Get the loved songs
+    pub fn get_loved_songs(&self) -> Result<Vec<Song>> {
+        let songs = self.block_on(self.service.get_loved_songs())?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Merge listening stats brought over from another player into the song
+    /// with ID `song_id`
+    pub fn import_stats(&self, song_id: &str, stats: domain::ImportedStats) -> Result<()> {
+        let song_id = SongId::from_string(song_id.to_string());
+        self.block_on(self.service.import_stats(song_id, stats))
+    }
+
+    /// Get every song with its play count, last play and rating
+    pub fn get_song_stats(&self) -> Result<Vec<domain::SongStats>> {
+        self.block_on(self.service.get_song_stats())
+    }
+
+    /// Get what every song's artist and album sort by, keyed by song ID
+    pub fn get_sort_keys(&self) -> Result<HashMap<String, domain::SortKeys>> {
+        let keys = self.block_on(self.service.get_sort_keys())?;
+        Ok(keys.into_iter().map(|(id, keys)| (id.as_str().to_string(), keys)).collect())
+    }
+
+    /// Get the songs of an album, ignoring case, in track order
+    pub fn get_album(&self, artist: &str, album: &str) -> Result<Vec<Song>> {
+        let songs = self.block_on(self.service.get_album(artist, album))?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Get the songs of the album after the song's album, in track order;
+    /// empty if there is no other album
+    pub fn get_next_album(&self, song_id: &str) -> Result<Vec<Song>> {
+        let song_id = SongId::from_string(song_id.to_string());
+        let songs = self.block_on(self.service.get_next_album(&song_id))?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Add a listen to the history, queueing it for scrobbling if requested
+    pub fn record_listen(&self, listen: domain::Listen, scrobble: bool) -> Result<()> {
+        self.block_on(self.history.record_listen(listen, scrobble))?;
+        Ok(())
+    }
+
+    /// Get up to `limit` scrobbles that are ready to be submitted
+    pub fn get_due_scrobbles(&self, limit: usize) -> Result<Vec<domain::PendingScrobble>> {
+        self.block_on(self.history.get_due_scrobbles(chrono::Utc::now(), limit))
+    }
+
+    /// Remove successfully submitted scrobbles from the queue
+    pub fn complete_scrobbles(&self, scrobbles: Vec<domain::PendingScrobble>) -> Result<()> {
+        self.block_on(self.history.complete_scrobbles(scrobbles))
+    }
+
+    /// Schedule failed scrobbles for another attempt with backoff
+    pub fn retry_scrobbles(&self, scrobbles: Vec<domain::PendingScrobble>) -> Result<()> {
+        self.block_on(self.history.retry_scrobbles(scrobbles, chrono::Utc::now()))
+    }
+
+    /// Count listens waiting to be scrobbled
+    pub fn count_pending_scrobbles(&self) -> Result<usize> {
+        self.block_on(self.history.count_pending_scrobbles())
+    }
+
+    /// Get the listens that started between `from` and `to`, oldest first
+    pub fn get_listens(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<domain::Listen>> {
+        self.block_on(self.history.get_listens(from, to))
+    }
+
+    /// Get the whole listening history, oldest first
+    pub fn get_all_listens(&self) -> Result<Vec<domain::Listen>> {
+        self.get_listens(chrono::DateTime::UNIX_EPOCH, chrono::Utc::now())
+    }
+
+    /// Get the last `limit` listens, newest first
+    pub fn recent_listens(&self, limit: usize) -> Result<Vec<domain::Listen>> {
+        self.block_on(self.history.recent_listens(limit))
+    }
+
+    /// Total up the listens that started since local midnight
+    pub fn listening_today(&self) -> Result<domain::ListeningSummary> {
+        let now = chrono::Local::now();
+        let midnight = start_of_day(now.date_naive());
+
+        self.block_on(self.history.summarize_listens(midnight, now.to_utc()))
+    }
+
+    /// Get every artist listened to before `before`
+    pub fn artists_heard_before(&self, before: chrono::DateTime<chrono::Utc>) -> Result<Vec<String>> {
+        self.block_on(self.history.artists_heard_before(before))
+    }
+
+    /// Bookmark `position` in the song stored at `path` as `name`
+    pub fn add_bookmark(&self, path: &str, position: std::time::Duration, name: &str) -> Result<()> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let song_id = SongId::from_path(&file_path);
+        let position = Duration::from_seconds(position.as_secs());
+        self.block_on(self.bookmarks.add_bookmark(song_id, position, name.to_string()))
+    }
+
+    /// Get the bookmarks in the song stored at `path`, in order of position
+    pub fn get_bookmarks(&self, path: &str) -> Result<Vec<domain::Bookmark>> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let song_id = SongId::from_path(&file_path);
+        self.block_on(self.bookmarks.get_bookmarks(&song_id))
+    }
+
+    /// Remove a bookmark
+    pub fn remove_bookmark(&self, bookmark: &domain::Bookmark) -> Result<()> {
+        self.block_on(self.bookmarks.remove_bookmark(bookmark))
+    }
+
+    /// Add scanned songs to the library, returning how many were new
+    pub fn add_songs(&self, songs: &[Song]) -> Result<usize> {
+        Ok(self.store_songs(songs)?.added)
+    }
+
+    /// Add scanned songs to the library, noting which were new, which were
+    /// refreshed and which could not be saved
+    pub fn store_songs(&self, songs: &[Song]) -> Result<StoredSongs> {
+        let mut stored = StoredSongs::default();
+        let songs_data = songs
+            .iter()
+            .filter_map(|song| match FilePath::new(&song.path) {
+                Ok(file_path) => Some(SongData {
+                    file_path,
+                    title: song.title.clone(),
+                    artist: song.artist.clone(),
+                    album: song.album.clone(),
+                    duration: Duration::from_seconds(song.duration),
+                    genre: song.genre.clone(),
+                }),
+                Err(e) => {
+                    log::warn!("Skipping {}: {}", song.path, e);
+                    stored.failed.push(ScanIssue { path: song.path.clone().into(), reason: e.to_string() });
+                    None
+                }
+            })
+            .collect();
+
+        let result = self.block_on(self.service.batch_add_songs(songs_data))?;
+        for error in result.errors {
+            log::warn!("Failed to add {}: {}", error.file_path.as_str(), error.error);
+            stored.failed.push(ScanIssue { path: error.file_path.as_str().into(), reason: error.error });
+        }
+
+        stored.added = result.added_count;
+        stored.updated = result.updated_count;
+        Ok(stored)
+    }
+
+    /// Remove every song from the library
+    pub fn clear_songs(&self) -> Result<()> {
+        self.block_on(self.service.clear_library())
+    }
+
+    /// Get all playlists with their total durations, ordered by name
+    pub fn get_all_playlists(&self) -> Result<Vec<Playlist>> {
+        self.block_on(fetch_playlists(&self.service))
+    }
+
+    /// Get a playlist by name, without its total duration
+    pub fn get_playlist_by_name(&self, name: &str) -> Result<Option<Playlist>> {
+        let playlist = self.block_on(self.service.find_playlist_by_name(name.to_string()))?;
+        Ok(playlist.map(to_playlist))
+    }
+
+    /// Get the playlists that contain the song stored at `path`, ordered by name
+    pub fn get_playlists_with_song(&self, path: &str) -> Result<Vec<Playlist>> {
+        let file_path = FilePath::new(path).map_err(ApplicationError::from)?;
+        let song_id = SongId::from_path(&file_path);
+        let playlists = self.block_on(self.service.find_playlists_with_song(song_id))?;
+        Ok(playlists.into_iter().map(to_playlist).collect())
+    }
+
+    /// Get the songs of a playlist in order
+    pub fn get_playlist_songs(&self, playlist_id: &str) -> Result<Vec<Song>> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        let (_, songs) = self.block_on(self.service.get_playlist_with_songs(playlist_id))?;
+        Ok(songs.into_iter().map(to_song).collect())
+    }
+
+    /// Create a new playlist, returning its ID
+    pub fn create_playlist(&self, name: &str, description: Option<String>) -> Result<String> {
+        let playlist_id = self.block_on(self.service.create_playlist(name.to_string(), description))?;
+        Ok(playlist_id.as_str().to_string())
+    }
+
+    /// Delete a playlist
+    pub fn delete_playlist(&self, playlist_id: &str) -> Result<()> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        self.block_on(self.service.delete_playlist(playlist_id))
+    }
+
+    /// Append a song to the end of a playlist
+    pub fn add_song_to_playlist(&self, playlist_id: &str, song_id: &str) -> Result<()> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        let song_id = SongId::from_string(song_id.to_string());
+        self.block_on(self.service.add_song_to_playlist(playlist_id, song_id))
+    }
+
+    /// Append songs to the end of a playlist in one transaction, returning
+    /// the IDs of those that were not in it yet
+    pub fn add_songs_to_playlist(&self, playlist_id: &str, song_ids: &[String]) -> Result<Vec<String>> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        let song_ids = song_ids.iter().map(|id| SongId::from_string(id.clone())).collect();
+        let added = self.block_on(self.service.add_songs_to_playlist(playlist_id, song_ids))?;
+        Ok(added.iter().map(|id| id.as_str().to_string()).collect())
+    }
+
+    /// Remove a song from a playlist
+    pub fn remove_song_from_playlist(&self, playlist_id: &str, song_id: &str) -> Result<()> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        let song_id = SongId::from_string(song_id.to_string());
+        self.block_on(self.service.remove_song_from_playlist(playlist_id, song_id))
+    }
+
+    /// Put the songs of a playlist in the order of `song_ids`
+    pub fn reorder_playlist(&self, playlist_id: &str, song_ids: &[String]) -> Result<()> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        let song_ids = song_ids.iter().map(|id| SongId::from_string(id.clone())).collect();
+        self.block_on(self.service.reorder_playlist(playlist_id, song_ids))
+    }
+
+    /// Replace the description of a playlist
+    pub fn describe_playlist(&self, playlist_id: &str, description: Option<String>) -> Result<()> {
+        let playlist_id = PlaylistId::from_string(playlist_id.to_string());
+        self.block_on(self.service.describe_playlist(playlist_id, description))
+    }
+
+    /// Combine two playlists into a new playlist `into`, returning how many
+    /// songs it got
+    pub fn combine_playlists(
+        &self,
+        operation: domain::PlaylistSetOperation,
+        first: &str,
+        second: &str,
+        into: &str,
+    ) -> Result<usize> {
+        self.block_on(self.service.combine_playlists(
+            operation,
+            first.to_string(),
+            second.to_string(),
+            into.to_string(),
+        ))
+    }
+}
+
+/// When `day` starts in the local time zone
+///
+/// Falls back to midnight UTC on the rare days whose local midnight is
+/// skipped by a daylight saving change.
+pub fn start_of_day(day: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    let midnight = day.and_time(chrono::NaiveTime::MIN);
+    midnight
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map_or(midnight.and_utc(), |midnight| midnight.to_utc())
+}
+
+impl std::fmt::Debug for SongWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SongWindow")
+            .field("query", &self.query)
+            .field("len", &self.len)
+            .field("pages", &self.pages.keys().collect::<Vec<_>>())
+            .field("loading", &self.loading.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Pages of a [`SongWindow`] of `len` songs to hold while the song at
+/// `index` is selected
+fn window_pages(index: usize, len: usize) -> std::ops::RangeInclusive<usize> {
+    let last = len.saturating_sub(1) / WINDOW_PAGE_SIZE;
+    let page = index.min(len.saturating_sub(1)) / WINDOW_PAGE_SIZE;
+    page.saturating_sub(WINDOW_MARGIN)..=(page + WINDOW_MARGIN).min(last)
+}
+
+/// Get page `page` of a [`SongWindow`] on the songs matching the lowered
+/// search `query`
+async fn fetch_page(service: &MusicLibraryService, query: String, page: usize) -> Result<Vec<Song>> {
+    let songs = service.get_song_page(query, page * WINDOW_PAGE_SIZE, WINDOW_PAGE_SIZE).await?;
+    Ok(songs.into_iter().map(to_song).collect())
+}
+
+/// Get the songs of a view other than the whole library
+async fn fetch_songs(service: &MusicLibraryService, query: SongQuery) -> crate::shared::errors::Result<Vec<domain::Song>> {
+    match query {
+        SongQuery::Playlist(name) => match service.find_playlist_by_name(name).await? {
+            Some(playlist) => Ok(service.get_playlist_with_songs(playlist.id().clone()).await?.1),
+            None => Ok(Vec::new()),
+        },
+        SongQuery::RecentlyAdded { days } => {
+            let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+            service.get_recently_added_songs(since).await
+        }
+        SongQuery::MostPlayed { limit } => service.get_most_played_songs(limit).await,
+        SongQuery::NeverPlayed => service.get_never_played_songs().await,
+        SongQuery::DailyMix { day, size } => service.get_daily_mix(day, size).await,
+    }
+}
+
+/// Get all playlists with their total durations, ordered by name
+async fn fetch_playlists(service: &MusicLibraryService) -> crate::shared::errors::Result<Vec<Playlist>> {
+    let playlists = service.get_all_playlists().await?;
+    let durations = service.get_playlist_durations().await?;
+    Ok(playlists
+        .into_iter()
+        .map(|playlist| {
+            let total_duration = durations.get(playlist.id()).map_or(0, |d| d.total_seconds());
+            Playlist { total_duration, ..to_playlist(playlist) }
+        })
+        .collect())
+}
+
+/// Convert a domain song into the UI model
+fn to_song(song: domain::Song) -> Song {
+    Song::new(
+        song.file_path().as_str().to_string(),
+        song.title().to_string(),
+        song.artist().to_string(),
+        song.album().to_string(),
+        song.duration().total_seconds(),
+    )
+    .with_genre(song.genre().map(str::to_string))
+}
+
+/// Convert a domain playlist into the UI model
+fn to_playlist(playlist: domain::Playlist) -> Playlist {
+    Playlist {
+        id: playlist.id().as_str().to_string(),
+        name: playlist.name().to_string(),
+        description: playlist.description().map(|d| d.to_string()),
+        song_ids: playlist.song_ids().iter().map(|id| id.as_str().to_string()).collect(),
+        created_at: playlist.created_at(),
+        updated_at: playlist.updated_at(),
+        total_duration: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_song(path: &str, title: &str) -> Song {
+        Song::new(
+            path.to_string(),
+            title.to_string(),
+            "Test Artist".to_string(),
+            "Test Album".to_string(),
+            180,
+        )
+    }
+
+    #[test]
+    fn test_song_operations() {
+        let library = LibraryService::new_in_memory().unwrap();
+
+        let song = test_song("/test/song.mp3", "Test Song");
+        assert_eq!(library.add_songs(std::slice::from_ref(&song)).unwrap(), 1);
+
+        // Adding the same file again is not counted as new
+        let stored = library.store_songs(std::slice::from_ref(&song)).unwrap();
+        assert_eq!((stored.added, stored.updated), (0, 1));
+        assert!(stored.failed.is_empty());
+
+        let songs = library.get_all_songs().unwrap();
+        assert_eq!(songs.len(), 1);
+        assert_eq!(songs[0].id, song.id);
+        assert_eq!(songs[0].title, "Test Song");
+
+        assert_eq!(library.search_songs("test").unwrap().len(), 1);
+        assert!(library.search_songs("missing").unwrap().is_empty());
+
+        library.clear_songs().unwrap();
+        assert!(library.get_all_songs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let library = LibraryService::new_in_memory().unwrap();
+        let mix = test_song("/test/mix.mp3", "Mix");
+        library.add_songs(std::slice::from_ref(&mix)).unwrap();
+
+        library.add_bookmark(&mix.path, std::time::Duration::from_millis(3_723_400), "Peak").unwrap();
+        let bookmarks = library.get_bookmarks(&mix.path).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].song_id().as_str(), mix.id);
+        assert_eq!(bookmarks[0].position().format_h_mm_ss(), "1:02:03");
+
+        // Bookmarks need a name
+        assert!(library.add_bookmark(&mix.path, std::time::Duration::ZERO, " ").is_err());
+
+        library.remove_bookmark(&bookmarks[0]).unwrap();
+        assert!(library.get_bookmarks(&mix.path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_playlist_operations() {
+        let library = LibraryService::new_in_memory().unwrap();
+
+        let first = test_song("/test/first.mp3", "First");
+        let second = test_song("/test/second.mp3", "Second");
+        library.add_songs(&[first.clone(), second.clone()]).unwrap();
+
+        let playlist_id = library.create_playlist("Chill", Some("Evening mix".to_string())).unwrap();
+        library.add_song_to_playlist(&playlist_id, &second.id).unwrap();
+        library.add_song_to_playlist(&playlist_id, &first.id).unwrap();
+
+        let playlist = library.get_playlist_by_name("Chill").unwrap().unwrap();
+        assert_eq!(playlist.id, playlist_id);
+        let containing = library.get_playlists_with_song(&first.path).unwrap();
+        assert_eq!(containing.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Chill"]);
+        assert_eq!(playlist.description.as_deref(), Some("Evening mix"));
+        assert_eq!(playlist.song_ids, vec![second.id.clone(), first.id.clone()]);
+
+        let songs = library.get_playlist_songs(&playlist_id).unwrap();
+        assert_eq!(songs.iter().map(|s| s.title.as_str()).collect::<Vec<_>>(), ["Second", "First"]);
+
+        // Duplicate entries are rejected
+        assert!(library.add_song_to_playlist(&playlist_id, &first.id).is_err());
+
+        library.reorder_playlist(&playlist_id, &[first.id.clone(), second.id.clone()]).unwrap();
+        let playlist = library.get_playlist_by_name("Chill").unwrap().unwrap();
+        assert_eq!(playlist.song_ids, vec![first.id.clone(), second.id.clone()]);
+        // A new order must not add or drop songs
+        assert!(library.reorder_playlist(&playlist_id, std::slice::from_ref(&first.id)).is_err());
+
+        library.remove_song_from_playlist(&playlist_id, &second.id).unwrap();
+        assert_eq!(library.get_playlist_songs(&playlist_id).unwrap().len(), 1);
+
+        let playlists = library.get_all_playlists().unwrap();
+        assert_eq!(playlists[0].total_duration, 180);
+
+        library.delete_playlist(&playlist_id).unwrap();
+        assert!(library.get_playlist_by_name("Chill").unwrap().is_none());
+        assert!(library.get_all_playlists().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_songs_to_playlist() {
+        let library = LibraryService::new_in_memory().unwrap();
+        let songs: Vec<Song> = ["a", "b", "c"].iter().map(|name| test_song(&format!("/test/{}.mp3", name), name)).collect();
+        library.add_songs(&songs).unwrap();
+        let ids: Vec<String> = songs.iter().map(|song| song.id.clone()).collect();
+
+        let playlist_id = library.create_playlist("Chill", None).unwrap();
+        library.add_song_to_playlist(&playlist_id, &ids[1]).unwrap();
+
+        // Songs already in the playlist are skipped, the rest appended in order
+        let added = library.add_songs_to_playlist(&playlist_id, &ids).unwrap();
+        assert_eq!(added, [ids[0].clone(), ids[2].clone()]);
+        let playlist = library.get_playlist_by_name("Chill").unwrap().unwrap();
+        assert_eq!(playlist.song_ids, [ids[1].clone(), ids[0].clone(), ids[2].clone()]);
+
+        assert!(library.add_songs_to_playlist(&playlist_id, &ids).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove_song_from_library_and_playlists() {
+        let library = LibraryService::new_in_memory().unwrap();
+
+        let kept = test_song("/test/kept.mp3", "Kept");
+        let removed = test_song("/test/removed.mp3", "Removed");
+        library.add_songs(&[kept.clone(), removed.clone()]).unwrap();
+
+        let first = library.create_playlist("First", None).unwrap();
+        let second = library.create_playlist("Second", None).unwrap();
+        library.add_song_to_playlist(&first, &removed.id).unwrap();
+        library.add_song_to_playlist(&first, &kept.id).unwrap();
+        library.add_song_to_playlist(&second, &removed.id).unwrap();
+
+        library.remove_song(&removed.id).unwrap();
+
+        let songs = library.get_all_songs().unwrap();
+        assert_eq!(songs.iter().map(|s| s.title.as_str()).collect::<Vec<_>>(), ["Kept"]);
+        assert_eq!(library.get_playlist_by_name("First").unwrap().unwrap().song_ids, vec![kept.id]);
+        assert!(library.get_playlist_songs(&second).unwrap().is_empty());
+    }
+
+    /// Poll a background load until it finishes
+    fn wait<T>(mut pending: Pending<T>) -> Result<T> {
+        loop {
+            if let Some(result) = pending.poll() {
+                return result;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
+
+    #[test]
+    fn test_background_loads() {
+        let library = LibraryService::new_in_memory().unwrap();
+
+        let song = test_song("/test/song.mp3", "Song");
+        library.add_songs(std::slice::from_ref(&song)).unwrap();
+        let playlist_id = library.create_playlist("Chill", None).unwrap();
+        library.add_song_to_playlist(&playlist_id, &song.id).unwrap();
+
+        let songs = wait(library.load_songs(SongQuery::Playlist("Chill".to_string()))).unwrap();
+        assert_eq!(songs.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), [song.id.as_str()]);
+        assert!(wait(library.load_songs(SongQuery::Playlist("Missing".to_string()))).unwrap().is_empty());
+        assert_eq!(wait(library.load_songs(SongQuery::NeverPlayed)).unwrap().len(), 1);
+
+        let playlists = wait(library.load_playlists()).unwrap();
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].total_duration, 180);
+    }
+
+    #[test]
+    fn test_bulk_add() {
+        let library = LibraryService::new_in_memory().unwrap();
+
+        let songs: Vec<Song> = (0..100)
+            .map(|i| test_song(&format!("/test/song{}.mp3", i), &format!("Song {}", i)))
+            .collect();
+
+        assert_eq!(library.add_songs(&songs).unwrap(), 100);
+        assert_eq!(library.get_all_songs().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn test_song_window() {
+        let library = LibraryService::new_in_memory().unwrap();
+        let songs: Vec<Song> = (0..1200)
+            .map(|i| test_song(&format!("/test/song{}.mp3", i), &format!("Song {}", i)))
+            .collect();
+        library.add_songs(&songs).unwrap();
+        let all: Vec<String> = library.get_all_songs().unwrap().into_iter().map(|song| song.id).collect();
+
+        // The window opens on just the pages around the song asked for
+        let mut window = wait(library.open_song_window("", None, 1199)).unwrap();
+        assert_eq!(window.len(), 1200);
+        assert!(window.get(0).is_none());
+        assert_eq!(window.get(1199).unwrap().id, all[1199]);
+
+        // Moving away loads the pages there and lets go of the others
+        window.show(0, &library);
+        while window.is_loading() {
+            window.poll().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(window.get(1199).is_none());
+        assert!(window.held().all(|(index, song)| song.id == all[index]));
+        assert_eq!(window.held().count(), 1000);
+
+        // A search counts its matches alone, in library order
+        let window = wait(library.open_song_window("SONG 11", Some("/test/song1150.mp3"), 0)).unwrap();
+        assert_eq!(window.len(), 111);
+        let position = window.held().find(|(_, song)| song.path == "/test/song1150.mp3").map(|(index, _)| index);
+        assert_eq!(position, Some(61));
+    }
+}
@@ -0,0 +1,73 @@
+//! Matching the songs loved in the library with the tracks loved on Last.fm
+//!
+//! Last.fm knows tracks by artist and title only, so songs are matched on
+//! those, ignoring case and surrounding spaces. Syncing only ever adds
+//! loves: a song unloved on one side since the last sync cannot be told
+//! from one that was never loved there.
+
+use crate::models::Song;
+use crate::scrobbling::LovedTrack;
+use std::collections::HashSet;
+
+/// What a sync changes on each side
+#[derive(Debug, Default)]
+pub struct LovedSync {
+    /// Library songs loved on Last.fm that are not loved here yet
+    pub to_mark: Vec<Song>,
+    /// Songs loved here that are not loved on Last.fm yet
+    pub to_love: Vec<Song>,
+}
+
+/// Work out the sync between the library's `songs`, of which those with an
+/// ID in `loved` are loved, and the tracks loved on Last.fm
+pub fn plan(songs: &[Song], loved: &HashSet<String>, remote: &[LovedTrack]) -> LovedSync {
+    let remote: HashSet<(String, String)> = remote.iter().map(|track| key(&track.artist, &track.title)).collect();
+    let mut sync = LovedSync::default();
+    let mut pushed = HashSet::new();
+
+    for song in songs {
+        let song_key = key(&song.artist, &song.title);
+        match (loved.contains(&song.id), remote.contains(&song_key)) {
+            (false, true) => sync.to_mark.push(song.clone()),
+            // Copies of a song in several files are loved on Last.fm once
+            (true, false) if pushed.insert(song_key) => sync.to_love.push(song.clone()),
+            _ => {}
+        }
+    }
+
+    sync
+}
+
+fn key(artist: &str, title: &str) -> (String, String) {
+    (artist.trim().to_lowercase(), title.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str, title: &str) -> Song {
+        Song::new(path.to_string(), title.to_string(), "Nujabes".to_string(), "Modal Soul".to_string(), 180)
+    }
+
+    fn track(artist: &str, title: &str) -> LovedTrack {
+        LovedTrack { artist: artist.to_string(), title: title.to_string() }
+    }
+
+    #[test]
+    fn test_plan_adds_loves_on_both_sides() {
+        let songs = vec![
+            song("feather.mp3", "Feather"),
+            song("reflection.mp3", "Reflection Eternal"),
+            song("reflection.flac", "Reflection Eternal"),
+            song("luv.mp3", "Luv(sic.) pt3"),
+        ];
+        let loved: HashSet<String> = [&songs[1], &songs[2], &songs[3]].iter().map(|song| song.id.clone()).collect();
+        let remote = vec![track("NUJABES", " feather "), track("Nujabes", "Luv(sic.) pt3"), track("Other", "Song")];
+
+        let sync = plan(&songs, &loved, &remote);
+        let paths = |songs: &[Song]| songs.iter().map(|song| song.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&sync.to_mark), vec!["feather.mp3"]);
+        assert_eq!(paths(&sync.to_love), vec!["reflection.mp3"]);
+    }
+}
@@ -1,3 +1,17 @@
+pub mod acoustid;
+pub mod cover_art;
+pub mod desktop;
+pub mod history_export;
+pub mod library_export;
+pub mod library_service;
+pub mod loved_sync;
+pub mod scan_job;
+pub mod stats_import;
+pub mod sync_service;
 pub mod tui_service;
+pub mod wrapped;
 
+pub use library_service::{LibraryService, Pending, SongQuery, SongWindow, StoredSongs};
+pub use scan_job::{ScanJob, ScanSettings};
+pub use sync_service::{SyncFile, SyncService};
 pub use tui_service::TuiService;
@@ -0,0 +1,299 @@
+//! Library scans that can be watched and cancelled
+//!
+//! A [`ScanJob`] walks the music directory on its own thread and adds what it
+//! finds to the library, so the TUI stays usable and can show its progress.
+//! Cancelling stops the scan after the file being read. The songs read before
+//! then are still added, each one whole, and nothing is removed from the
+//! library, so a cancelled scan leaves it as a smaller version of a full one.
+//!
+//! Until a scan gets through every file, a [`ScanCheckpoint`] records what it
+//! added, so the next scan resumes from there instead of reading it all again.
+//!
+//! The same job reads folders and files pasted into the queue, which may be
+//! outside the library and are not added to it.
+
+use crate::config::Config;
+use crate::domain::value_objects::Duration as TrackDuration;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::{CancelToken, MusicScanner, ScanCheckpoint, ScanIssue, ScanOptions, ScanProgress, ScanReport};
+use crate::services::{LibraryService, StoredSongs};
+use crate::t;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Where to scan and which library to add the songs to
+#[derive(Debug, Clone)]
+pub struct ScanSettings {
+    pub music_dir: PathBuf,
+    pub database_path: PathBuf,
+    pub sort_articles: Vec<String>,
+    pub options: ScanOptions,
+}
+
+impl ScanSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            music_dir: config.music_dir.clone(),
+            database_path: config.database_path.clone(),
+            sort_articles: config.sort_articles.clone(),
+            options: config.scan_options.clone(),
+        }
+    }
+}
+
+/// What a finished scan did
+#[derive(Debug, Clone, Default)]
+pub struct ScanOutcome {
+    /// Songs that were new to the library
+    pub added: usize,
+    /// The music directory could not be read, so the library was left alone
+    pub offline: bool,
+    /// The scan was cancelled before it got through every file
+    pub cancelled: bool,
+    /// Files skipped because an interrupted scan had already added them
+    pub resumed: usize,
+    /// Songs already in the library whose details were refreshed
+    pub updated: usize,
+    /// Directories and entries that could not be listed
+    pub skipped: Vec<ScanIssue>,
+    /// Files that could not be read or saved
+    pub failed: Vec<ScanIssue>,
+}
+
+impl ScanOutcome {
+    /// Count a batch of songs written to the library
+    pub fn record(&mut self, stored: StoredSongs) {
+        self.added += stored.added;
+        self.updated += stored.updated;
+        self.failed.extend(stored.failed);
+    }
+
+    /// Some files or directories were left out of the library
+    pub fn has_issues(&self) -> bool {
+        !self.skipped.is_empty() || !self.failed.is_empty()
+    }
+
+    /// Summary of the scan: the counts, then each path that was skipped or
+    /// failed with the reason, listing at most `limit` of each if given
+    pub fn report_lines(&self, limit: Option<usize>) -> Vec<String> {
+        let mut lines = vec![
+            t!("scan-report-added", count = self.added),
+            t!("scan-report-updated", count = self.updated),
+            t!("scan-report-skipped", count = self.skipped.len()),
+            t!("scan-report-failed", count = self.failed.len()),
+        ];
+
+        for (heading, issues) in [("scan-report-skipped-heading", &self.skipped), ("scan-report-failed-heading", &self.failed)] {
+            if issues.is_empty() {
+                continue;
+            }
+            let shown = limit.unwrap_or(issues.len()).min(issues.len());
+            lines.push(String::new());
+            lines.push(t!(heading));
+            lines.extend(issues[..shown].iter().map(|issue| format!("  {}: {}", issue.path.display(), issue.reason)));
+            if shown < issues.len() {
+                lines.push(format!("  {}", t!("scan-report-more", count = issues.len() - shown)));
+            }
+        }
+
+        lines
+    }
+}
+
+/// A scan running on a background thread, ending in a `T`
+///
+/// Dropping the job cancels the scan.
+pub struct ScanJob<T = ScanOutcome> {
+    cancel: CancelToken,
+    progress: Arc<Mutex<ScanProgress>>,
+    receiver: Receiver<Result<T>>,
+}
+
+impl ScanJob {
+    /// Start scanning in the background
+    pub fn start(settings: ScanSettings) -> Result<Self> {
+        Self::spawn("library-scan", move |cancel, on_progress| scan(&settings, cancel, on_progress))
+    }
+}
+
+impl ScanJob<ScanReport> {
+    /// Read the songs in `folder` in the background without adding them to
+    /// the library
+    pub fn read_folder(folder: PathBuf, options: ScanOptions) -> Result<Self> {
+        Self::spawn("folder-read", move |cancel, on_progress| {
+            Ok(MusicScanner::with_options(options).scan_roots_with(&[&folder], cancel, on_progress))
+        })
+    }
+
+    /// Read the tags of audio files in the background without adding them
+    /// to the library
+    pub fn read_files(files: Vec<PathBuf>, options: ScanOptions) -> Result<Self> {
+        Self::spawn("file-read", move |cancel, on_progress| {
+            Ok(MusicScanner::with_options(options).scan_files_with(files, cancel, on_progress))
+        })
+    }
+}
+
+impl<T: Send + 'static> ScanJob<T> {
+    /// Run `work` on its own thread, keeping the progress it reports
+    fn spawn<W>(name: &str, work: W) -> Result<Self>
+    where
+        W: FnOnce(&CancelToken, &mut dyn FnMut(&ScanProgress)) -> Result<T> + Send + 'static,
+    {
+        let cancel = CancelToken::new();
+        let progress = Arc::new(Mutex::new(ScanProgress::default()));
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_cancel = cancel.clone();
+        let worker_progress = Arc::clone(&progress);
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let result = work(&worker_cancel, &mut |update| {
+                    if let Ok(mut progress) = worker_progress.lock() {
+                        *progress = update.clone();
+                    }
+                });
+                let _ = sender.send(result);
+            })?;
+
+        Ok(Self { cancel, progress, receiver })
+    }
+
+    /// How far the scan has got
+    pub fn progress(&self) -> ScanProgress {
+        self.progress.lock().map(|progress| progress.clone()).unwrap_or_default()
+    }
+
+    /// Ask the scan to stop, keeping what it has read so far
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Take the outcome if the scan has finished
+    pub fn poll(&mut self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(LofiTurtleError::ChannelError(
+                "Library scan stopped without a result".to_string(),
+            ))),
+        }
+    }
+}
+
+impl<T> Drop for ScanJob<T> {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Scan the music directory into the library, reporting to `on_progress`
+pub fn scan<F: FnMut(&ScanProgress)>(settings: &ScanSettings, cancel: &CancelToken, on_progress: F) -> Result<ScanOutcome> {
+    let library = LibraryService::new(&settings.database_path, &settings.sort_articles)?;
+
+    // Pick up after a scan that was interrupted, skipping what it added
+    let mut checkpoint = ScanCheckpoint::open(&ScanCheckpoint::path_for(&settings.database_path), &settings.music_dir)?;
+    let scanner = MusicScanner::with_options(settings.options.clone()).skipping(checkpoint.take_done());
+
+    // Songs are added batch by batch while the next ones are being read
+    let mut outcome = ScanOutcome::default();
+    let report = scanner.scan_roots_into(&[&settings.music_dir], cancel, on_progress, |batch| {
+        outcome.record(library.store_songs(batch)?);
+        checkpoint.record(batch)?;
+        Ok(())
+    })?;
+    if report.resumed > 0 {
+        log::info!("Resumed an interrupted scan, skipping {} files it had added", report.resumed);
+    }
+
+    outcome.offline = report.is_offline(&settings.music_dir);
+    outcome.cancelled = report.cancelled;
+    outcome.resumed = report.resumed;
+    outcome.skipped = report.skipped;
+    outcome.failed.extend(report.failed);
+    if !outcome.cancelled && !outcome.offline {
+        checkpoint.finish()?;
+    }
+
+    Ok(outcome)
+}
+
+/// Rate and time left of a scan, once it has read a file
+pub fn rate_label(progress: &ScanProgress) -> Option<String> {
+    let eta = progress.eta()?;
+    Some(t!(
+        "scan-rate",
+        rate = format!("{:.1}", progress.rate()),
+        eta = TrackDuration::from_seconds(eta.as_secs()).format_h_mm_ss()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn settings(music: &tempfile::TempDir, db: &tempfile::TempDir) -> ScanSettings {
+        ScanSettings {
+            music_dir: music.path().to_path_buf(),
+            database_path: db.path().join("library.db"),
+            sort_articles: Vec::new(),
+            options: ScanOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_job_reports_outcome() {
+        let (music, db) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut job = ScanJob::start(settings(&music, &db)).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let outcome = loop {
+            if let Some(outcome) = job.poll() {
+                break outcome.unwrap();
+            }
+            assert!(Instant::now() < deadline, "scan did not finish");
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(outcome.added, 0);
+        assert!(!outcome.offline);
+    }
+
+    #[test]
+    fn test_report_lists_issues_up_to_limit() {
+        let mut outcome = ScanOutcome::default();
+        outcome.record(StoredSongs {
+            added: 2,
+            updated: 1,
+            failed: ["/music/a.mp3", "/music/b.mp3", "/music/c.mp3"]
+                .map(|path| ScanIssue { path: PathBuf::from(path), reason: "locked".to_string() })
+                .into(),
+        });
+        assert_eq!((outcome.added, outcome.updated), (2, 1));
+        assert!(outcome.has_issues());
+
+        // Four counts, a gap, the heading, the files shown and how many more
+        let lines = outcome.report_lines(Some(2));
+        assert_eq!(lines.len(), 9);
+        assert!(lines[6].contains("/music/a.mp3") && lines[6].contains("locked"));
+        assert!(!lines.iter().any(|line| line.contains("/music/c.mp3")));
+
+        assert_eq!(outcome.report_lines(None).len(), 9);
+        assert_eq!(ScanOutcome::default().report_lines(None).len(), 4);
+    }
+
+    #[test]
+    fn test_cancelled_scan_adds_nothing_more() {
+        let (music, db) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        std::fs::write(music.path().join("song.wav"), b"not read").unwrap();
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let outcome = scan(&settings(&music, &db), &cancel, |_| {}).unwrap();
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.added, 0);
+    }
+}
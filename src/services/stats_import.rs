@@ -0,0 +1,262 @@
+//! Play counts and ratings brought over from other players
+//!
+//! iTunes and Music write their library to `Library.xml` (File > Library >
+//! Export Library); MPD keeps play counts and ratings that clients like
+//! myMPD and Cantata set as stickers in its `sticker.sql` database. Tracks
+//! are matched to library songs by path, then by artist, album and title,
+//! then by artist and title alone.
+
+use crate::domain::entities::ImportedStats;
+use crate::error::{LofiTurtleError, Result};
+use crate::library::file_url;
+use crate::models::Song;
+use chrono::DateTime;
+use plist::{Dictionary, Value};
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A track of another player with how it was listened to
+#[derive(Debug, Clone, Default)]
+pub struct ForeignTrack {
+    /// Where the other player found the file
+    pub path: Option<PathBuf>,
+    pub artist: String,
+    pub album: String,
+    pub title: String,
+    pub stats: ImportedStats,
+}
+
+impl ForeignTrack {
+    /// Name for messages: the title if known, otherwise the path
+    pub fn name(&self) -> String {
+        match (&self.path, self.title.is_empty()) {
+            (_, false) if !self.artist.is_empty() => format!("{} - {}", self.title, self.artist),
+            (_, false) => self.title.clone(),
+            (Some(path), true) => path.display().to_string(),
+            (None, true) => String::new(),
+        }
+    }
+}
+
+/// Tracks matched to library songs, and those that were not
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    pub matched: Vec<(Song, ImportedStats)>,
+    pub unmatched: Vec<ForeignTrack>,
+}
+
+/// Read the played or rated tracks of an iTunes or Music `Library.xml`
+pub fn read_itunes(path: &Path) -> Result<Vec<ForeignTrack>> {
+    let library = Value::from_file(path).map_err(|e| {
+        LofiTurtleError::MusicLibrary(format!("{} is not a property list: {}", path.display(), e))
+    })?;
+    let tracks = library
+        .as_dictionary()
+        .and_then(|library| library.get("Tracks"))
+        .and_then(Value::as_dictionary)
+        .ok_or_else(|| LofiTurtleError::MusicLibrary(format!("{} has no tracks", path.display())))?;
+
+    Ok(tracks.values().filter_map(Value::as_dictionary).filter_map(itunes_track).collect())
+}
+
+fn itunes_track(track: &Dictionary) -> Option<ForeignTrack> {
+    let text = |key: &str| track.get(key).and_then(Value::as_string).unwrap_or_default().to_string();
+    let play_count = track.get("Play Count").and_then(Value::as_signed_integer).unwrap_or(0);
+    // A rating the album passes down to its tracks was not given to the track
+    let computed = track.get("Rating Computed").and_then(Value::as_boolean).unwrap_or(false);
+    let rating = track.get("Rating").and_then(Value::as_signed_integer).filter(|_| !computed);
+
+    let stats = ImportedStats {
+        play_count: u32::try_from(play_count).unwrap_or(0),
+        last_played_at: track.get("Play Date UTC").and_then(Value::as_date).map(|date| SystemTime::from(date).into()),
+        // Ratings go from 0 to 100, 20 a star
+        rating: rating.and_then(|rating| stars(rating, 100)),
+    };
+    if stats == ImportedStats::default() {
+        return None;
+    }
+
+    Some(ForeignTrack {
+        path: track.get("Location").and_then(Value::as_string).and_then(file_url::to_path),
+        artist: text("Artist"),
+        album: text("Album"),
+        title: text("Name"),
+        stats,
+    })
+}
+
+/// Read the played or rated songs of MPD's sticker database, whose paths are
+/// relative to MPD's `music_dir`
+pub fn read_mpd_stickers(database: &Path, music_dir: &Path) -> Result<Vec<ForeignTrack>> {
+    let conn = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare("SELECT uri, name, value FROM sticker WHERE type = 'song'")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?;
+
+    // Stickers of a song are stored one per row
+    let mut tracks: HashMap<String, ForeignTrack> = HashMap::new();
+    for row in rows {
+        let (uri, name, value) = row?;
+        let track = tracks.entry(uri.clone()).or_insert_with(|| ForeignTrack {
+            path: Some(music_dir.join(&uri)),
+            ..ForeignTrack::default()
+        });
+        let value = value.trim();
+        match name.as_str() {
+            "playCount" => track.stats.play_count = value.parse().unwrap_or(0),
+            "lastPlayed" => {
+                track.stats.last_played_at = value.parse().ok().and_then(|secs| DateTime::from_timestamp(secs, 0))
+            }
+            // Ratings go from 0 to 10, two a star
+            "rating" => track.stats.rating = value.parse().ok().and_then(|rating| stars(rating, 10)),
+            _ => {}
+        }
+    }
+
+    let mut tracks: Vec<ForeignTrack> =
+        tracks.into_values().filter(|track| track.stats != ImportedStats::default()).collect();
+    tracks.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(tracks)
+}
+
+/// Match `tracks` to the library's `songs`
+pub fn plan(songs: &[Song], tracks: Vec<ForeignTrack>) -> ImportPlan {
+    let by_path: HashMap<&str, &Song> = songs.iter().map(|song| (song.path.as_str(), song)).collect();
+    let mut by_album: HashMap<(String, String, String), &Song> = HashMap::new();
+    let mut by_title: HashMap<(String, String), &Song> = HashMap::new();
+    for song in songs {
+        by_album.entry((fold(&song.artist), fold(&song.album), fold(&song.title))).or_insert(song);
+        by_title.entry((fold(&song.artist), fold(&song.title))).or_insert(song);
+    }
+
+    let mut plan = ImportPlan::default();
+    for track in tracks {
+        let found = track
+            .path
+            .as_ref()
+            .and_then(|path| by_path.get(path.to_string_lossy().as_ref()))
+            .or_else(|| {
+                if track.title.is_empty() {
+                    return None;
+                }
+                by_album
+                    .get(&(fold(&track.artist), fold(&track.album), fold(&track.title)))
+                    .or_else(|| by_title.get(&(fold(&track.artist), fold(&track.title))))
+            });
+        match found {
+            Some(song) => plan.matched.push(((*song).clone(), track.stats)),
+            None => plan.unmatched.push(track),
+        }
+    }
+    plan
+}
+
+fn fold(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+/// Stars from one to five for a rating out of `scale`, none for no rating
+///
+/// Ratings are kept within the scale first, so a corrupt one cannot
+/// overflow.
+fn stars(rating: i64, scale: i64) -> Option<u8> {
+    let rating = rating.clamp(0, scale);
+    let stars = (rating * 5 + scale / 2) / scale;
+    (rating > 0).then(|| stars.clamp(1, 5) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str, artist: &str, album: &str, title: &str) -> Song {
+        Song::new(path.to_string(), title.to_string(), artist.to_string(), album.to_string(), 180)
+    }
+
+    #[test]
+    fn test_read_itunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Library.xml");
+        std::fs::write(&path, r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict><key>Tracks</key><dict>
+    <key>1</key><dict>
+        <key>Name</key><string>Feather</string>
+        <key>Artist</key><string>Nujabes</string>
+        <key>Album</key><string>Modal Soul</string>
+        <key>Play Count</key><integer>42</integer>
+        <key>Play Date UTC</key><date>2019-03-01T20:15:00Z</date>
+        <key>Rating</key><integer>80</integer>
+        <key>Location</key><string>file:///Users/me/Music/Nujabes/01%20Feather.mp3</string>
+    </dict>
+    <key>2</key><dict>
+        <key>Name</key><string>Rated by its album</string>
+        <key>Rating</key><integer>60</integer>
+        <key>Rating Computed</key><true/>
+    </dict>
+</dict></dict></plist>"#).unwrap();
+
+        let tracks = read_itunes(&path).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].path, Some(PathBuf::from("/Users/me/Music/Nujabes/01 Feather.mp3")));
+        assert_eq!(tracks[0].stats.play_count, 42);
+        assert_eq!(tracks[0].stats.rating, Some(4));
+        assert_eq!(tracks[0].stats.last_played_at, DateTime::from_timestamp(1_551_471_300, 0));
+    }
+
+    #[test]
+    fn test_read_mpd_stickers() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("sticker.sql");
+        let conn = Connection::open(&database).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE sticker (type VARCHAR, uri VARCHAR, name VARCHAR, value VARCHAR);
+             INSERT INTO sticker VALUES ('song', 'Nujabes/feather.flac', 'playCount', '12');
+             INSERT INTO sticker VALUES ('song', 'Nujabes/feather.flac', 'rating', '7');
+             INSERT INTO sticker VALUES ('song', 'Nujabes/feather.flac', 'lastPlayed', '1700000000');
+             INSERT INTO sticker VALUES ('song', 'other.mp3', 'like', '1');",
+        ).unwrap();
+
+        let tracks = read_mpd_stickers(&database, Path::new("/srv/music")).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].path, Some(PathBuf::from("/srv/music/Nujabes/feather.flac")));
+        assert_eq!(tracks[0].stats.play_count, 12);
+        assert_eq!(tracks[0].stats.rating, Some(4));
+        assert_eq!(tracks[0].stats.last_played_at, DateTime::from_timestamp(1_700_000_000, 0));
+    }
+
+    #[test]
+    fn test_plan_matches_by_path_then_tags() {
+        let songs = vec![
+            song("/music/a.mp3", "Nujabes", "Modal Soul", "Feather"),
+            song("/music/b.mp3", "Nujabes", "Metaphorical Music", "Lady Brown"),
+        ];
+        let track = |path: Option<&str>, album: &str, title: &str| ForeignTrack {
+            path: path.map(PathBuf::from),
+            artist: "nujabes".to_string(),
+            album: album.to_string(),
+            title: title.to_string(),
+            stats: ImportedStats { play_count: 1, ..ImportedStats::default() },
+        };
+
+        let plan = plan(&songs, vec![
+            track(Some("/music/a.mp3"), "", ""),
+            track(Some("/elsewhere/b.mp3"), "Other album", "LADY BROWN "),
+            track(None, "", "Unknown"),
+        ]);
+        let matched: Vec<&str> = plan.matched.iter().map(|(song, _)| song.path.as_str()).collect();
+        assert_eq!(matched, ["/music/a.mp3", "/music/b.mp3"]);
+        assert_eq!(plan.unmatched.len(), 1);
+    }
+
+    #[test]
+    fn test_stars() {
+        assert_eq!(stars(0, 100), None);
+        assert_eq!(stars(20, 100), Some(1));
+        assert_eq!(stars(100, 100), Some(5));
+        assert_eq!(stars(1, 10), Some(1));
+        assert_eq!(stars(10, 10), Some(5));
+        assert_eq!(stars(i64::MAX, 100), Some(5));
+        assert_eq!(stars(i64::MIN, 100), None);
+    }
+}
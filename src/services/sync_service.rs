@@ -0,0 +1,452 @@
+use crate::domain::entities as domain;
+use crate::error::{LofiTurtleError, Result};
+use crate::models::Song;
+use crate::services::LibraryService;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Version of the sync file format written by this build
+const SYNC_FORMAT_VERSION: u32 = 1;
+
+/// Library changes exported from one machine for import on another
+///
+/// Songs are identified by a hash of their file contents rather than by path,
+/// so the same music stored under different directories still matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub playlists: Vec<SyncedPlaylist>,
+    /// Play counts and ratings, missing from files written by older builds
+    #[serde(default)]
+    pub stats: Vec<SyncedSongStats>,
+}
+
+/// A playlist as stored in a sync file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedPlaylist {
+    pub name: String,
+    pub description: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub songs: Vec<SyncedSong>,
+}
+
+/// A song reference as stored in a sync file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedSong {
+    pub content_hash: String,
+    pub title: String,
+    pub artist: String,
+    pub duration: u64,
+}
+
+/// Listening stats of a song as stored in a sync file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedSongStats {
+    pub song: SyncedSong,
+    pub play_count: u32,
+    pub last_played_at: Option<DateTime<Utc>>,
+    pub rating: Option<u8>,
+}
+
+/// Outcome of importing a sync file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub missing_songs: usize,
+    pub stats_imported: usize,
+}
+
+impl SyncFile {
+    /// Read a sync file from disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let file: SyncFile = serde_json::from_str(&content)
+            .map_err(|e| LofiTurtleError::MusicLibrary(format!("Invalid sync file: {}", e)))?;
+
+        if file.version > SYNC_FORMAT_VERSION {
+            return Err(LofiTurtleError::MusicLibrary(format!(
+                "Sync file version {} is newer than supported version {}",
+                file.version, SYNC_FORMAT_VERSION
+            )));
+        }
+
+        Ok(file)
+    }
+
+    /// Write the sync file to disk
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| LofiTurtleError::MusicLibrary(format!("Failed to serialize sync file: {}", e)))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Service for exchanging library changes between machines through a file
+pub struct SyncService {
+    library: LibraryService,
+}
+
+impl SyncService {
+    /// Create a sync service for the given library
+    pub fn new(library: LibraryService) -> Self {
+        Self { library }
+    }
+
+    /// Export playlists changed after `since`, or all playlists when `None`
+    ///
+    /// Stats are exported for songs played after `since` and for every rated
+    /// song, since ratings carry no timestamp.
+    pub fn export(&self, since: Option<DateTime<Utc>>) -> Result<SyncFile> {
+        let mut playlists = Vec::new();
+
+        for playlist in self.library.get_all_playlists()? {
+            if since.is_some_and(|since| playlist.updated_at <= since) {
+                continue;
+            }
+
+            let songs = self
+                .library
+                .get_playlist_songs(&playlist.id)?
+                .iter()
+                .filter_map(|song| synced_song(&song.path, &song.title, &song.artist, song.duration))
+                .collect();
+
+            playlists.push(SyncedPlaylist {
+                name: playlist.name,
+                description: playlist.description,
+                updated_at: playlist.updated_at,
+                songs,
+            });
+        }
+
+        let mut stats = Vec::new();
+        for song_stats in self.library.get_song_stats()? {
+            let played = song_stats.play_count > 0
+                && song_stats.last_played_at.is_some_and(|at| since.is_none_or(|since| at > since));
+            if !played && song_stats.rating.is_none() {
+                continue;
+            }
+
+            let song = &song_stats.song;
+            let synced = synced_song(
+                song.file_path().as_str(),
+                song.title(),
+                song.artist(),
+                song.duration().total_seconds(),
+            );
+            if let Some(synced) = synced {
+                stats.push(SyncedSongStats {
+                    song: synced,
+                    play_count: song_stats.play_count,
+                    last_played_at: song_stats.last_played_at,
+                    rating: song_stats.rating,
+                });
+            }
+        }
+
+        Ok(SyncFile {
+            version: SYNC_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            playlists,
+            stats,
+        })
+    }
+
+    /// Merge a sync file into the library
+    ///
+    /// Playlists missing locally are created. Existing playlists are replaced
+    /// only when the imported copy is newer, so the last edit wins: the newer
+    /// copy's songs, order and description all replace the local ones.
+    ///
+    /// Stats are merged per song: the higher play count and the later last
+    /// play are kept, and a local rating wins over the imported one.
+    pub fn import(&self, file: &SyncFile) -> Result<ImportSummary> {
+        let mut summary = ImportSummary::default();
+        let mut resolver = SongResolver::new(self.library.get_all_songs()?);
+
+        for remote in &file.playlists {
+            let local = self.library.get_playlist_by_name(&remote.name)?;
+            if local.as_ref().is_some_and(|local| local.updated_at >= remote.updated_at) {
+                summary.unchanged += 1;
+                continue;
+            }
+
+            let mut song_ids = Vec::new();
+            for song in &remote.songs {
+                match resolver.resolve(song) {
+                    Some(id) if !song_ids.contains(&id) => song_ids.push(id),
+                    Some(_) => {}
+                    None => summary.missing_songs += 1,
+                }
+            }
+
+            match local {
+                Some(local) => {
+                    for song_id in local.song_ids.iter().filter(|id| !song_ids.contains(id)) {
+                        self.library.remove_song_from_playlist(&local.id, song_id)?;
+                    }
+                    self.library.add_songs_to_playlist(&local.id, &song_ids)?;
+                    self.library.reorder_playlist(&local.id, &song_ids)?;
+                    if local.description != remote.description {
+                        self.library.describe_playlist(&local.id, remote.description.clone())?;
+                    }
+                    summary.updated += 1;
+                }
+                None => {
+                    let playlist_id = self.library.create_playlist(&remote.name, remote.description.clone())?;
+                    self.library.add_songs_to_playlist(&playlist_id, &song_ids)?;
+                    summary.created += 1;
+                }
+            }
+        }
+
+        for remote in &file.stats {
+            let Some(song_id) = resolver.resolve(&remote.song) else {
+                summary.missing_songs += 1;
+                continue;
+            };
+
+            let stats = domain::ImportedStats {
+                play_count: remote.play_count,
+                last_played_at: remote.last_played_at,
+                rating: remote.rating,
+            };
+            self.library.import_stats(&song_id, stats)?;
+            summary.stats_imported += 1;
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Describe a local song for a sync file, or `None` if it cannot be read
+fn synced_song(path: &str, title: &str, artist: &str, duration: u64) -> Option<SyncedSong> {
+    match content_hash(path) {
+        Ok(content_hash) => Some(SyncedSong {
+            content_hash,
+            title: title.to_string(),
+            artist: artist.to_string(),
+            duration,
+        }),
+        Err(e) => {
+            log::warn!("Skipping unreadable song {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Matches synced songs to local songs by content hash
+///
+/// Hashing the whole library would be slow, so only local songs with the same
+/// duration are hashed, and each hash is computed at most once.
+struct SongResolver {
+    songs_by_duration: HashMap<u64, Vec<Song>>,
+    hashes: HashMap<String, Option<String>>,
+}
+
+impl SongResolver {
+    fn new(songs: Vec<Song>) -> Self {
+        let mut songs_by_duration: HashMap<u64, Vec<Song>> = HashMap::new();
+        for song in songs {
+            songs_by_duration.entry(song.duration).or_default().push(song);
+        }
+
+        Self {
+            songs_by_duration,
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// Find the ID of the local song with the same contents
+    fn resolve(&mut self, synced: &SyncedSong) -> Option<String> {
+        let candidates = self.songs_by_duration.get(&synced.duration)?;
+
+        for song in candidates {
+            let hash = self
+                .hashes
+                .entry(song.path.clone())
+                .or_insert_with(|| content_hash(&song.path).ok());
+
+            if hash.as_deref() == Some(synced.content_hash.as_str()) {
+                return Some(song.id.clone());
+            }
+        }
+
+        None
+    }
+}
+
+/// Compute the MD5 hash of a file's contents
+pub fn content_hash<P: AsRef<Path>>(path: P) -> std::io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut context = md5::Context::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.consume(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", context.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_song(dir: &Path, name: &str, contents: &str) -> Song {
+        let path: PathBuf = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        Song::new(
+            path.to_string_lossy().into_owned(),
+            name.to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            120,
+        )
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let laptop_dir = tempfile::tempdir().unwrap();
+        let desktop_dir = tempfile::tempdir().unwrap();
+
+        // The same music lives under different paths on each machine
+        let laptop = LibraryService::new_in_memory().unwrap();
+        let first = write_song(laptop_dir.path(), "first.mp3", "first song");
+        let second = write_song(laptop_dir.path(), "second.mp3", "second song");
+        laptop.add_songs(&[first.clone(), second.clone()]).unwrap();
+
+        let desktop = LibraryService::new_in_memory().unwrap();
+        let desktop_first = write_song(desktop_dir.path(), "a.mp3", "first song");
+        let desktop_second = write_song(desktop_dir.path(), "b.mp3", "second song");
+        desktop.add_songs(&[desktop_first.clone(), desktop_second.clone()]).unwrap();
+
+        let playlist_id = laptop.create_playlist("Focus", Some("Deep work".to_string())).unwrap();
+        laptop.add_song_to_playlist(&playlist_id, &second.id).unwrap();
+        laptop.add_song_to_playlist(&playlist_id, &first.id).unwrap();
+
+        let export = SyncService::new(laptop).export(None).unwrap();
+        let sync_path = laptop_dir.path().join("sync.json");
+        export.save(&sync_path).unwrap();
+
+        let desktop = SyncService::new(desktop);
+        let summary = desktop.import(&SyncFile::load(&sync_path).unwrap()).unwrap();
+        assert_eq!(summary, ImportSummary { created: 1, ..Default::default() });
+
+        let playlist = desktop.library.get_playlist_by_name("Focus").unwrap().unwrap();
+        assert_eq!(playlist.description.as_deref(), Some("Deep work"));
+        assert_eq!(playlist.song_ids, vec![desktop_second.id, desktop_first.id]);
+
+        // Importing the same file again does not overwrite the newer local copy
+        let summary = desktop.import(&export).unwrap();
+        assert_eq!(summary, ImportSummary { unchanged: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn test_import_updates_older_playlist() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = LibraryService::new_in_memory().unwrap();
+        let kept = write_song(dir.path(), "kept.mp3", "kept");
+        let dropped = write_song(dir.path(), "dropped.mp3", "dropped");
+        let first = write_song(dir.path(), "first.mp3", "first");
+        library.add_songs(&[kept.clone(), dropped.clone(), first.clone()]).unwrap();
+
+        let playlist_id = library.create_playlist("Mix", Some("Old notes".to_string())).unwrap();
+        library.add_song_to_playlist(&playlist_id, &dropped.id).unwrap();
+        library.add_song_to_playlist(&playlist_id, &kept.id).unwrap();
+
+        let file = SyncFile {
+            version: SYNC_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            playlists: vec![SyncedPlaylist {
+                name: "Mix".to_string(),
+                description: Some("New notes".to_string()),
+                updated_at: Utc::now() + chrono::Duration::minutes(1),
+                songs: vec![
+                    SyncedSong {
+                        content_hash: content_hash(&first.path).unwrap(),
+                        title: "first".to_string(),
+                        artist: "Artist".to_string(),
+                        duration: 120,
+                    },
+                    SyncedSong {
+                        content_hash: content_hash(&kept.path).unwrap(),
+                        title: "kept".to_string(),
+                        artist: "Artist".to_string(),
+                        duration: 120,
+                    },
+                    SyncedSong {
+                        content_hash: "0".repeat(32),
+                        title: "elsewhere".to_string(),
+                        artist: "Artist".to_string(),
+                        duration: 120,
+                    },
+                ],
+            }],
+            stats: Vec::new(),
+        };
+
+        let service = SyncService::new(library);
+        let summary = service.import(&file).unwrap();
+        assert_eq!(summary, ImportSummary { updated: 1, missing_songs: 1, ..Default::default() });
+
+        let playlist = service.library.get_playlist_by_name("Mix").unwrap().unwrap();
+        assert_eq!(playlist.song_ids, vec![first.id, kept.id]);
+        assert_eq!(playlist.description.as_deref(), Some("New notes"));
+    }
+
+    #[test]
+    fn test_stats_round_trip() {
+        let laptop_dir = tempfile::tempdir().unwrap();
+        let desktop_dir = tempfile::tempdir().unwrap();
+
+        let laptop = LibraryService::new_in_memory().unwrap();
+        let played = write_song(laptop_dir.path(), "played.mp3", "played");
+        let unplayed = write_song(laptop_dir.path(), "unplayed.mp3", "unplayed");
+        laptop.add_songs(&[played.clone(), unplayed.clone()]).unwrap();
+        let last_played_at = Utc::now() - chrono::Duration::hours(1);
+        laptop
+            .import_stats(&played.id, domain::ImportedStats { play_count: 7, last_played_at: Some(last_played_at), rating: Some(4) })
+            .unwrap();
+
+        let desktop = LibraryService::new_in_memory().unwrap();
+        let desktop_played = write_song(desktop_dir.path(), "p.mp3", "played");
+        desktop.add_songs(std::slice::from_ref(&desktop_played)).unwrap();
+
+        let export = SyncService::new(laptop).export(None).unwrap();
+        assert_eq!(export.stats.len(), 1);
+
+        let desktop = SyncService::new(desktop);
+        let summary = desktop.import(&export).unwrap();
+        assert_eq!(summary, ImportSummary { stats_imported: 1, ..Default::default() });
+
+        let stats = desktop.library.get_song_stats().unwrap();
+        let stats = stats.iter().find(|s| s.song.id().as_str() == desktop_played.id).unwrap();
+        assert_eq!(stats.play_count, 7);
+        assert_eq!(stats.rating, Some(4));
+        assert_eq!(stats.last_played_at.map(|at| at.timestamp()), Some(last_played_at.timestamp()));
+    }
+
+    #[test]
+    fn test_export_since_skips_unchanged_playlists() {
+        let library = LibraryService::new_in_memory().unwrap();
+        library.create_playlist("Old", None).unwrap();
+
+        let service = SyncService::new(library);
+        assert_eq!(service.export(None).unwrap().playlists.len(), 1);
+        assert!(service.export(Some(Utc::now())).unwrap().playlists.is_empty());
+    }
+}
@@ -1,19 +1,86 @@
 use crate::config::{Config, LayoutConfig};
 use crate::error::{LofiTurtleError, Result};
-use crate::library::{Database, MusicScanner};
+use crate::ui::keymap::{self, ChordMatch};
+use crate::t;
+use crate::ui::dir_browser::DirBrowser;
 use crate::ui::{draw_ui, App};
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
-        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+        cursor::Show,
+        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind},
         execute,
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     },
     Terminal,
 };
+use std::any::Any;
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, Once};
+use std::thread::{self, ThreadId};
 use std::time::{Duration, Instant};
 
+/// Log records the log viewer scrolls by a page
+const LOG_PAGE: usize = 10;
+
+/// Directories a directory browser moves by a page
+const DIR_PAGE: isize = 10;
+
+/// How often the screen is redrawn while something on it animates
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Thread drawing the interface while the terminal is in raw mode
+static TUI_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Put the terminal back in its normal mode, whatever state the interface left it in
+fn reset_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+}
+
+/// Set the thread whose panics must restore the terminal, if any
+fn set_tui_thread(thread: Option<ThreadId>) {
+    if let Ok(mut tui_thread) = TUI_THREAD.lock() {
+        *tui_thread = thread;
+    }
+}
+
+/// Restore the terminal before a panic on the interface thread is printed,
+/// so the message is readable and the shell usable afterwards
+///
+/// Panics on other threads are only logged while the interface is up, as
+/// printing them would garble the screen.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            log::error!("{}", info);
+            let tui_thread = TUI_THREAD.lock().ok().and_then(|thread| *thread);
+            match tui_thread {
+                Some(thread) if thread != thread::current().id() => {}
+                Some(_) => {
+                    set_tui_thread(None);
+                    reset_terminal();
+                    default_hook(info);
+                }
+                None => default_hook(info),
+            }
+        }));
+    });
+}
+
+/// Text of a panic payload, which is a `&str` or a `String` for `panic!` messages
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
 /// Service responsible for managing the terminal user interface
 pub struct TuiService {
     config: Config,
@@ -33,6 +100,9 @@ impl TuiService {
 
     /// Initialize the terminal interface
     fn initialize_terminal(&mut self) -> Result<()> {
+        install_panic_hook();
+        set_tui_thread(Some(thread::current().id()));
+
         enable_raw_mode().map_err(|e| {
             LofiTurtleError::Terminal(format!("Failed to enable raw mode: {}", e))
         })?;
@@ -53,7 +123,8 @@ impl TuiService {
 
     /// Restore the terminal to its original state
     fn restore_terminal(&mut self) -> Result<()> {
-        if let Some(ref mut terminal) = self.terminal {
+        set_tui_thread(None);
+        if let Some(mut terminal) = self.terminal.take() {
             disable_raw_mode().map_err(|e| {
                 LofiTurtleError::Terminal(format!("Failed to disable raw mode: {}", e))
             })?;
@@ -73,48 +144,22 @@ impl TuiService {
         Ok(())
     }
 
-    /// Initialize the music library if scanning is enabled
-    fn initialize_library(&self) -> Result<()> {
-        if self.config.no_scan {
-            log::info!("Skipping library scan as requested");
-            return Ok(());
-        }
-
-        log::info!("Initializing music library...");
-        let mut database = Database::new(&self.config.database_path)?;
-        
-        println!("Scanning music directory: {}", self.config.music_dir.display());
-        let scanner = MusicScanner::new();
-        let songs = scanner.scan_directory(&self.config.music_dir)?;
-        
-        println!("Found {} songs. Adding to database...", songs.len());
-
-        // Use bulk insert for better performance
-        match database.insert_songs_bulk(&songs) {
-            Ok(count) => println!("Successfully added {} songs to database", count),
-            Err(e) => log::warn!("Failed to bulk insert songs: {}", e),
-        }
-        
-        println!("Music library initialized successfully!");
-        Ok(())
-    }
-
     /// Run the main TUI application loop
     pub fn run(&mut self) -> Result<()> {
-        // Initialize library first
-        self.initialize_library()?;
-        
         // Setup terminal
         self.initialize_terminal()?;
         
-        // Ensure terminal is restored even if an error occurs
-        let result = self.run_app_loop();
-        
+        // Ensure terminal is restored even if an error occurs or the loop
+        // panics; the panic hook has already printed the message by then
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.run_app_loop())).unwrap_or_else(|payload| {
+            Err(LofiTurtleError::Terminal(t!("interface-crashed", message = panic_message(payload.as_ref()))))
+        });
+
         // Always try to restore terminal
         if let Err(restore_err) = self.restore_terminal() {
             log::error!("Failed to restore terminal: {}", restore_err);
         }
-        
+
         result
     }
 
@@ -126,6 +171,16 @@ impl TuiService {
 
         // Create app instance with layout config
         let mut app = App::new(&self.config, &self.layout_config)?;
+
+        // Without a music directory chosen, an empty library is a first run:
+        // ask where the music is rather than scanning a default folder
+        if self.config.setup && app.library.is_empty()? {
+            app.open_setup();
+        } else if self.config.no_scan {
+            log::info!("Skipping library scan as requested");
+        } else {
+            app.start_scan();
+        }
         let mut last_tick = Instant::now();
         let tick_rate = Duration::from_millis(self.config.tick_rate_ms);
 
@@ -136,24 +191,41 @@ impl TuiService {
             })?;
 
             // Handle events
-            let timeout = tick_rate
+            let mut timeout = tick_rate
                 .checked_sub(last_tick.elapsed())
                 .unwrap_or_else(|| Duration::from_secs(0));
+            if app.is_animating() {
+                timeout = timeout.min(FRAME_INTERVAL);
+            }
 
-            if event::poll(timeout).map_err(|e| {
+            let has_event = event::poll(timeout).map_err(|e| {
                 LofiTurtleError::Terminal(format!("Failed to poll events: {}", e))
-            })? {
+            })?;
+
+            // A chord left unfinished runs what was typed of it, if bound
+            let chord_timeout = app.chord_timeout();
+            if let Some(action) = app.chords.expire(&app.layout_config.keybindings, Instant::now(), chord_timeout) {
+                let count = app.chords.take_count();
+                if Self::perform_counted(&mut app, &action, count)? {
+                    break;
+                }
+            }
+
+            if has_event {
                 if let Event::Key(key) = event::read().map_err(|e| {
                     LofiTurtleError::Terminal(format!("Failed to read event: {}", e))
                 })? {
-                    if key.kind == KeyEventKind::Press {
-                        if Self::handle_key_event(&mut app, key.code)? {
-                            break; // User requested quit
-                        }
+                    if key.kind == KeyEventKind::Press && Self::handle_key_event(&mut app, key)? {
+                        break; // User requested quit
                     }
                 }
             }
 
+            app.handle_media_keys()?;
+            app.poll_loads()?;
+            app.poll_scan();
+            app.poll_pasted_reads();
+
             // Update app state on tick
             if last_tick.elapsed() >= tick_rate {
                 app.update_playback_status();
@@ -163,6 +235,7 @@ impl TuiService {
             }
 
             if app.should_quit() {
+                log::info!("Album art cache: {}", app.album_art_renderer.cache_stats());
                 break;
             }
         }
@@ -171,197 +244,22 @@ impl TuiService {
     }
 
     /// Handle keyboard input events
-    fn handle_key_event(app: &mut App, key_code: KeyCode) -> Result<bool> {
-        use crate::ui::{InputMode, ActivePanel};
-        
+    fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool> {
+        use crate::ui::InputMode;
+
+        let key_code = key_event.code;
         match app.get_input_mode() {
+            // Esc closes the scan popup by cancelling the scan
+            InputMode::Normal if key_code == KeyCode::Esc && app.is_scanning() => app.cancel_scan(),
             InputMode::Normal => {
-                // Check for configured keybindings first
-                let key_str = match key_code {
-                    KeyCode::Char(' ') => "space".to_string(),
-                    KeyCode::Char(c) => c.to_string(),
-                    KeyCode::Enter => "enter".to_string(),
-                    KeyCode::Tab => "tab".to_string(),
-                    KeyCode::BackTab => "backtab".to_string(),
-                    KeyCode::Esc => "esc".to_string(),
-                    KeyCode::Backspace => "backspace".to_string(),
-                    KeyCode::Up => "up".to_string(),
-                    KeyCode::Down => "down".to_string(),
-                    KeyCode::Left => "left".to_string(),
-                    KeyCode::Right => "right".to_string(),
-                    KeyCode::F(n) => format!("f{}", n),
-                    KeyCode::Delete => "delete".to_string(),
-                    KeyCode::Insert => "insert".to_string(),
-                    KeyCode::Home => "home".to_string(),
-                    KeyCode::End => "end".to_string(),
-                    KeyCode::PageUp => "pageup".to_string(),
-                    KeyCode::PageDown => "pagedown".to_string(),
-                    _ => "".to_string(),
+                let Some(key) = keymap::key_name(key_event) else {
+                    return Ok(false);
                 };
-
-                if !key_str.is_empty() {
-                    if let Some(action) = app.layout_config.keybindings.get(&key_str) {
-                        match action.as_str() {
-                            "quit" => {
-                                app.quit()?;
-                                return Ok(true);
-                            },
-                            "toggle_play" => app.toggle_playback()?,
-                            "next_track" => {
-                                // Logic for next track
-                                app.advance_to_next_song()?;
-                            },
-                            "previous_track" => {
-                                // Logic for previous track
-                                // For now just stop or restart current
-                                app.stop_playback()?;
-                            },
-                            "move_up" => app.move_selection_up(),
-                            "move_down" => app.move_selection_down(),
-                            "select" => {
-                                match app.state.active_panel {
-                                    ActivePanel::Songs => {
-                                        app.play_selected_song()?;
-                                    }
-                                    ActivePanel::Playlists => {
-                                        app.play_selected_playlist()?;
-                                    }
-                                    _ => {}
-                                }
-                            },
-                            "volume_up" => app.increase_volume()?,
-                            "volume_down" => app.decrease_volume()?,
-                            "switch_layout" => {
-                                // Cycle layout logic could go here
-                            },
-                            "switch_theme" => app.cycle_theme(),
-                            "reload_layout" => {
-                                // Reload layout logic
-                            },
-                            "search" => app.enter_search_mode(),
-                            "toggle_art" => app.toggle_album_art(),
-                            _ => {}
-                        }
-                        return Ok(false);
-                    }
-                }
-
-                // Fallback to hardcoded bindings if not found in config
-                match key_code {
-                    // Global controls
-                    KeyCode::Char('q') => {
-                        app.quit()?;
-                        return Ok(true);
-                    }
-                    KeyCode::Tab => app.switch_to_next_panel(),
-                    KeyCode::BackTab => app.switch_to_previous_panel(),
-                    
-                    // Navigation (Arrow keys)
-                    KeyCode::Up => app.move_selection_up(),
-                    KeyCode::Down => app.move_selection_down(),
-                    KeyCode::Left => app.switch_to_previous_panel(),
-                    KeyCode::Right => app.switch_to_next_panel(),
-                    
-                    // Vim-style navigation (hjkl)
-                    KeyCode::Char('h') => app.switch_to_previous_panel(),
-                    KeyCode::Char('j') => app.move_selection_down(),
-                    KeyCode::Char('k') => app.move_selection_up(),
-                    KeyCode::Char('l') => app.switch_to_library()?,
-                    
-                    // Navigation back
-                    KeyCode::Backspace => app.switch_to_library()?,
-                    KeyCode::Enter => {
-                        match app.state.active_panel {
-                            ActivePanel::Songs => {
-                                app.play_selected_song()?;
-                            }
-                            ActivePanel::Playlists => {
-                                // Play selected playlist (switch to it and start playing first song)
-                                app.play_selected_playlist()?;
-                            }
-                            _ => {}
-                        }
-                    }
-                    
-                    // Playback controls
-                    KeyCode::Char(' ') => app.toggle_playback()?,
-                    KeyCode::Char('S') => app.toggle_shuffle()?,
-                    KeyCode::Char('R') => app.cycle_repeat_mode()?,
-                    KeyCode::Char('s') => app.stop_playback()?,
-                    
-                    // Volume controls
-                    KeyCode::Char(']') => app.increase_volume()?,
-                    KeyCode::Char('[') => app.decrease_volume()?,
-                    
-                    // Search and UI controls
-                    KeyCode::Char('/') => app.enter_search_mode(),
-                    KeyCode::Char('c') => app.clear_search(),
-                    KeyCode::Char('a') => app.toggle_album_art(),
-                    KeyCode::F(3) => app.cycle_theme(),
-
-                    // Panel-specific controls
-                    KeyCode::Char('n') => {
-                        if matches!(app.state.active_panel, ActivePanel::Playlists) {
-                            app.enter_playlist_create_mode();
-                        }
-                    }
-                    KeyCode::Char('d') => {
-                        if matches!(app.state.active_panel, ActivePanel::Playlists) {
-                            app.delete_selected_playlist()?;
-                        }
-                    }
-                    KeyCode::Char('e') => {
-                        if matches!(app.state.active_panel, ActivePanel::Playlists) {
-                            app.enter_playlist_edit_mode();
-                        }
-                    }
-                    KeyCode::Char('+') => {
-                        // Add selected song to selected playlist
-                        if matches!(app.state.active_panel, ActivePanel::Songs) {
-                            if let Some(song) = app.get_selected_song() {
-                                let song_id = song.id.clone();
-                                let _song_title = song.title.clone();
-                                
-                                // Get the currently selected playlist from the playlists panel
-                                if !app.state.playlists.is_empty() {
-                                    if let Some(playlist) = app.state.playlists.get(app.state.selected_playlist_index) {
-                                        let playlist_name = playlist.name.clone();
-                                        match app.add_song_to_playlist(&playlist_name, &song_id) {
-                                            Ok(_) => {
-                                                // Success - song added to playlist
-                                                // The add_song_to_playlist method already handles reloading
-                                            }
-                                            Err(_e) => {
-                                                // TODO: Add proper error display in UI
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    KeyCode::Char('-') => {
-                        // Remove selected song from current playlist or selected playlist
-                        if matches!(app.state.active_panel, ActivePanel::Songs) {
-                            if let Some(song) = app.get_selected_song() {
-                                let song_id = song.id.clone();
-                                
-                                // First try to remove from current playlist if we're viewing one
-                                if let Some(playlist_name) = app.get_current_playlist_name() {
-                                    let playlist_name = playlist_name.to_string();
-                                    let _ = app.remove_song_from_playlist(&playlist_name, &song_id);
-                                    let _ = app.load_songs(); // Reload to reflect changes
-                                } else if !app.state.playlists.is_empty() {
-                                    // If not viewing a playlist, remove from the selected playlist
-                                    if let Some(playlist) = app.state.playlists.get(app.state.selected_playlist_index) {
-                                        let playlist_name = playlist.name.clone();
-                                        let _ = app.remove_song_from_playlist(&playlist_name, &song_id);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
+                let timeout = app.chord_timeout();
+                let typed = app.chords.feed(&app.layout_config.keybindings, &key, Instant::now(), timeout);
+                if let ChordMatch::Action(action) = typed {
+                    let count = app.chords.take_count();
+                    return Self::perform_counted(app, &action, count);
                 }
             }
             InputMode::Search => {
@@ -379,7 +277,59 @@ impl TuiService {
                     }
                 }
             }
-            InputMode::PlaylistCreate | InputMode::PlaylistEdit => {
+            InputMode::ConfirmDelete | InputMode::ConfirmDeleteFinal => {
+                match key_code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_delete()?,
+                    _ => app.exit_input_mode(),
+                }
+            }
+            InputMode::ConfirmBatchAdd => match key_code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_batch_add()?,
+                _ => app.exit_input_mode(),
+            },
+            InputMode::Stats => app.close_stats(),
+            InputMode::Help => app.exit_input_mode(),
+            InputMode::SongInfo => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => app.select_bookmark(-1),
+                KeyCode::Down | KeyCode::Char('j') => app.select_bookmark(1),
+                KeyCode::Enter => app.jump_to_bookmark()?,
+                KeyCode::Char('d') | KeyCode::Delete => app.remove_selected_bookmark(),
+                _ => app.close_song_info(),
+            },
+            InputMode::ScanReport => app.close_scan_report(),
+            InputMode::Logs => {
+                match key_code {
+                    KeyCode::Up | KeyCode::Char('k') => app.scroll_logs_back(1),
+                    KeyCode::Down | KeyCode::Char('j') => app.scroll_logs_forward(1),
+                    KeyCode::PageUp => app.scroll_logs_back(LOG_PAGE),
+                    KeyCode::PageDown => app.scroll_logs_forward(LOG_PAGE),
+                    _ => app.exit_input_mode(),
+                }
+            }
+            InputMode::Setup => Self::handle_setup_key(app, key_code)?,
+            InputMode::MusicDir => match key_code {
+                KeyCode::Tab => app.choose_music_dir()?,
+                KeyCode::Esc => app.close_music_dir_picker(),
+                _ => {
+                    if let Some(browser) = app.state.dir_picker.as_mut() {
+                        Self::handle_browser_key(browser, key_code);
+                    }
+                }
+            },
+            InputMode::Output => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => app.move_output_selection(-1),
+                KeyCode::Down | KeyCode::Char('j') => app.move_output_selection(1),
+                KeyCode::Enter => app.choose_output()?,
+                _ => app.close_output_picker(),
+            },
+            InputMode::PlaylistCreate
+            | InputMode::PlaylistEdit
+            | InputMode::QueueSave
+            | InputMode::PartyLock
+            | InputMode::PartyConfirm
+            | InputMode::PartyUnlock
+            | InputMode::BookmarkName
+            | InputMode::SongTitle => {
                 match key_code {
                     KeyCode::Esc => app.exit_input_mode(),
                     KeyCode::Enter => {
@@ -397,6 +347,235 @@ impl TuiService {
         
         Ok(false)
     }
+
+    /// Keys of the setup wizard, which depend on the step it is on
+    fn handle_setup_key(app: &mut App, key_code: KeyCode) -> Result<()> {
+        use crate::ui::setup::SetupStep;
+
+        let scanning = app.is_scanning();
+        let Some(setup) = app.state.setup.as_mut() else {
+            app.exit_input_mode();
+            return Ok(());
+        };
+        match setup.step {
+            SetupStep::Directory => match key_code {
+                KeyCode::Tab => app.choose_setup_directory(),
+                KeyCode::Esc => app.close_setup(),
+                _ => Self::handle_browser_key(&mut setup.browser, key_code),
+            },
+            SetupStep::Theme => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => app.move_setup_theme(-1),
+                KeyCode::Down | KeyCode::Char('j') => app.move_setup_theme(1),
+                KeyCode::Enter => app.finish_setup_choices()?,
+                KeyCode::Esc | KeyCode::Backspace => app.setup_back(),
+                _ => {}
+            },
+            SetupStep::Scan if scanning => {
+                if key_code == KeyCode::Esc {
+                    app.cancel_scan();
+                }
+            }
+            SetupStep::Scan => app.close_setup(),
+        }
+        Ok(())
+    }
+
+    /// Keys moving through a directory browser
+    fn handle_browser_key(browser: &mut DirBrowser, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Up | KeyCode::Char('k') => browser.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => browser.move_by(1),
+            KeyCode::PageUp => browser.move_by(-DIR_PAGE),
+            KeyCode::PageDown => browser.move_by(DIR_PAGE),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => browser.open_selected(),
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => browser.open_parent(),
+            _ => {}
+        }
+    }
+
+    /// Run an action with the count typed before it, e.g. `5j`
+    fn perform_counted(app: &mut App, action: &str, count: Option<usize>) -> Result<bool> {
+        // Checked before the count, so a locked action like `3p` is refused
+        // too and the toast shows once
+        if app.is_party_locked() && !keymap::allowed_in_party(action) {
+            app.show_toast(t!("party-locked"));
+            return Ok(false);
+        }
+
+        let Some(count) = count else {
+            return Self::perform_action(app, action);
+        };
+
+        match action {
+            // Counted moves stop at the ends of the list instead of wrapping
+            "move_up" => app.move_selection_by(-(count as isize)),
+            "move_down" => app.move_selection_by(count as isize),
+            // Like Vim's 5G, a count picks the entry
+            "go_top" | "go_bottom" => app.move_selection_to(count - 1),
+            // `3p` plays the third most recent track again
+            "replay_recent" => app.replay_recent(count - 1)?,
+            _ if keymap::is_repeatable(action) => {
+                for _ in 0..count {
+                    if Self::perform_action(app, action)? {
+                        return Ok(true);
+                    }
+                }
+            }
+            _ => return Self::perform_action(app, action),
+        }
+        Ok(false)
+    }
+
+    /// Run a keymap action, returning whether the user asked to quit
+    fn perform_action(app: &mut App, action: &str) -> Result<bool> {
+        use crate::ui::ActivePanel;
+
+        match action {
+            "quit" => {
+                app.quit()?;
+                return Ok(true);
+            }
+            "toggle_play" => app.toggle_playback()?,
+            "stop" => app.stop_playback()?,
+            "stop_after_current" => app.toggle_stop_after_current(),
+            "next_track" => app.advance_to_next_song()?,
+            "previous_track" => app.go_to_previous_song()?,
+            "next_chapter" => app.next_chapter()?,
+            "previous_chapter" => app.previous_chapter()?,
+            "replay_recent" => app.replay_recent(0)?,
+            "toggle_shuffle" => app.toggle_shuffle()?,
+            "cycle_repeat" => app.cycle_repeat_mode()?,
+            "volume_up" => app.increase_volume()?,
+            "volume_down" => app.decrease_volume()?,
+
+            // Navigation
+            "move_up" => app.move_selection_up(),
+            "move_down" => app.move_selection_down(),
+            "go_top" => app.move_selection_to_top(),
+            "go_bottom" => app.move_selection_to_bottom(),
+            "next_panel" => app.switch_to_next_panel(),
+            "previous_panel" => app.switch_to_previous_panel(),
+            "library" => app.switch_to_library(),
+            "jump_to_playing" => app.jump_to_playing_song(),
+            "toggle_follow" => app.toggle_follow_playback()?,
+            "select" => {
+                match app.state.active_panel {
+                    ActivePanel::Songs => {
+                        app.play_selected_song()?;
+                    }
+                    ActivePanel::Playlists => {
+                        // Play selected playlist (switch to it and start playing first song)
+                        app.play_selected_playlist();
+                    }
+                    _ => {}
+                }
+            }
+
+            // Search and UI controls
+            "search" => app.enter_search_mode(),
+            "clear_search" => app.clear_search(),
+            "toggle_art" => app.toggle_album_art(),
+            "toggle_visualizer" => app.toggle_visualizer(),
+            "toggle_remaining" => app.toggle_remaining_time()?,
+            "reveal_file" => app.reveal_selected_song(),
+            "copy_path" => app.copy_selected_song_path(),
+            "copy_title" => app.copy_selected_song_title(),
+            "enqueue_clipboard" => app.enqueue_clipboard()?,
+            "toggle_love" => app.toggle_selected_song_loved(),
+            "do_not_disturb" => app.toggle_do_not_disturb()?,
+            "show_stats" => app.open_stats(),
+            "help" => app.open_help(),
+            "show_logs" => app.open_logs(),
+            "song_playlists" => app.open_song_info(),
+            "add_bookmark" => app.enter_bookmark_mode(),
+            "rename_song" => app.enter_rename_mode(),
+            "trim_start" => app.trim_start_here(),
+            "trim_end" => app.trim_end_here(),
+            "clear_trim" => app.clear_trim(),
+            "switch_theme" => app.cycle_theme()?,
+            "switch_layout" => {
+                // Cycle layout logic could go here
+            }
+            "reload_layout" => {
+                // Reload layout logic
+            }
+            "undo" => app.undo_playlist_edit(),
+            "redo" => app.redo_playlist_edit(),
+            "delete_file" => {
+                if matches!(app.state.active_panel, ActivePanel::Songs) {
+                    app.request_delete_selected_song();
+                }
+            }
+
+            // Panel-specific controls
+            "save_queue" => app.enter_queue_save_mode(),
+            "party_mode" => app.enter_party_mode(),
+            "append_to_queue" => app.append_selection_to_queue()?,
+            "shuffle_queue" => app.toggle_queue_shuffle(),
+            "remove_from_queue" => app.remove_selected_from_queue(),
+            "remove_queue_above" => app.remove_queue_above(),
+            "remove_queue_below" => app.remove_queue_below(),
+            "clear_queue_after_current" => app.clear_queue_after_current(),
+            "create_playlist" => {
+                if matches!(app.state.active_panel, ActivePanel::Playlists) {
+                    app.enter_playlist_create_mode();
+                }
+            }
+            "sort_playlists" => app.cycle_playlist_sort()?,
+            "rescan" => app.start_scan(),
+            "change_music_dir" => app.open_music_dir_picker(),
+            "choose_output" => app.open_output_picker(),
+            "delete_playlist" => {
+                if matches!(app.state.active_panel, ActivePanel::Playlists) {
+                    app.delete_selected_playlist()?;
+                }
+            }
+            "edit_playlist" => {
+                if matches!(app.state.active_panel, ActivePanel::Playlists) {
+                    app.enter_playlist_edit_mode();
+                }
+            }
+            "add_results_to_playlist" => app.request_batch_add(),
+            "add_to_playlist" => {
+                // Add selected song to selected playlist
+                if matches!(app.state.active_panel, ActivePanel::Songs) {
+                    if let Some(song) = app.get_selected_song() {
+                        let song_id = song.id.clone();
+
+                        // Get the currently selected playlist from the playlists panel
+                        if let Some(playlist) = app.selected_playlist() {
+                            let playlist_name = playlist.name.clone();
+                            if let Err(_e) = app.add_song_to_playlist(&playlist_name, &song_id) {
+                                // TODO: Add proper error display in UI
+                            }
+                        }
+                    }
+                }
+            }
+            "remove_from_playlist" => {
+                // Remove selected song from current playlist or selected playlist
+                if matches!(app.state.active_panel, ActivePanel::Songs) {
+                    if let Some(song) = app.get_selected_song() {
+                        let song_id = song.id.clone();
+
+                        // First try to remove from current playlist if we're viewing one
+                        if let Some(playlist_name) = app.get_current_playlist_name() {
+                            let playlist_name = playlist_name.to_string();
+                            let _ = app.remove_song_from_playlist(&playlist_name, &song_id);
+                            app.load_songs(); // Reload to reflect changes
+                        } else if let Some(playlist) = app.selected_playlist() {
+                            // If not viewing a playlist, remove from the selected playlist
+                            let playlist_name = playlist.name.clone();
+                            let _ = app.remove_song_from_playlist(&playlist_name, &song_id);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
 }
 
 impl Drop for TuiService {
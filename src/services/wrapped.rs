@@ -0,0 +1,343 @@
+//! Listening summaries for the `wrapped` command
+
+use crate::domain::entities::Listen;
+use chrono::{Days, Months, NaiveDate, TimeZone};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Artists listed in the top artists and discoveries sections
+const TOP_ARTISTS: usize = 5;
+
+/// How far back a summary looks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrappedPeriod {
+    Week,
+    Month,
+    Year,
+}
+
+impl WrappedPeriod {
+    /// First day of the period ending on `today`, inclusive
+    pub fn first_day(self, today: NaiveDate) -> NaiveDate {
+        let start = match self {
+            WrappedPeriod::Week => today.checked_sub_days(Days::new(7)),
+            WrappedPeriod::Month => today.checked_sub_months(Months::new(1)),
+            WrappedPeriod::Year => today.checked_sub_months(Months::new(12)),
+        };
+        start.map_or(today, |start| start + Days::new(1))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            WrappedPeriod::Week => "week",
+            WrappedPeriod::Month => "month",
+            WrappedPeriod::Year => "year",
+        }
+    }
+}
+
+/// Listening for one artist within the period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtistPlays {
+    pub artist: String,
+    pub listens: usize,
+    pub played_seconds: u64,
+}
+
+/// Highlights of the listening in a period
+#[derive(Debug, Clone)]
+pub struct Wrapped {
+    pub period: WrappedPeriod,
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub listens: usize,
+    pub played_seconds: u64,
+    pub tracks: usize,
+    pub artists: usize,
+    pub active_days: usize,
+    pub top_artists: Vec<ArtistPlays>,
+    /// Artists first heard during the period, most played first
+    pub discoveries: Vec<ArtistPlays>,
+}
+
+impl Wrapped {
+    /// Summarize `listens` from `first_day` through `last_day`
+    ///
+    /// `known_artists` are the artists heard before the period; any other
+    /// artist counts as a discovery. Days are taken in `timezone`.
+    pub fn new<Tz: TimeZone>(
+        period: WrappedPeriod,
+        first_day: NaiveDate,
+        last_day: NaiveDate,
+        listens: &[Listen],
+        known_artists: &HashSet<String>,
+        timezone: &Tz,
+    ) -> Self {
+        let mut by_artist: HashMap<&str, ArtistPlays> = HashMap::new();
+        let mut tracks = HashSet::new();
+        let mut days = HashSet::new();
+
+        for listen in listens {
+            tracks.insert(listen.track_path());
+            days.insert(listen.listened_at().with_timezone(timezone).date_naive());
+
+            let plays = by_artist.entry(listen.artist()).or_insert_with(|| ArtistPlays {
+                artist: listen.artist().to_string(),
+                listens: 0,
+                played_seconds: 0,
+            });
+            plays.listens += 1;
+            plays.played_seconds += listen.played().total_seconds();
+        }
+
+        let mut ranked: Vec<ArtistPlays> = by_artist
+            .into_values()
+            .filter(|plays| !plays.artist.trim().is_empty())
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.listens
+                .cmp(&a.listens)
+                .then(b.played_seconds.cmp(&a.played_seconds))
+                .then_with(|| a.artist.cmp(&b.artist))
+        });
+
+        let artists = ranked.len();
+        let discoveries = ranked
+            .iter()
+            .filter(|plays| !known_artists.contains(&plays.artist))
+            .take(TOP_ARTISTS)
+            .cloned()
+            .collect();
+        ranked.truncate(TOP_ARTISTS);
+
+        Self {
+            period,
+            first_day,
+            last_day,
+            listens: listens.len(),
+            played_seconds: listens.iter().map(|listen| listen.played().total_seconds()).sum(),
+            tracks: tracks.len(),
+            artists,
+            active_days: days.len(),
+            top_artists: ranked,
+            discoveries,
+        }
+    }
+
+    fn days(&self) -> i64 {
+        (self.last_day - self.first_day).num_days() + 1
+    }
+
+    fn date_range(&self) -> String {
+        format!("{} – {}", self.first_day.format("%b %-d, %Y"), self.last_day.format("%b %-d, %Y"))
+    }
+
+    /// Plain text summary for the terminal
+    pub fn to_text(&self) -> String {
+        let mut text = format!("🐢 Your {} in music\n{}\n\n", self.period.name(), self.date_range());
+
+        if self.listens == 0 {
+            text.push_str(&format!("No listens recorded in the past {}.\n", self.period.name()));
+            return text;
+        }
+
+        let _ = writeln!(
+            text,
+            "{} of music · {} listens · {} tracks · {} artists",
+            format_hours(self.played_seconds),
+            self.listens,
+            self.tracks,
+            self.artists
+        );
+        let _ = writeln!(text, "Listened on {} of {} days", self.active_days, self.days());
+
+        text.push_str("\nTop artists\n");
+        for (rank, plays) in self.top_artists.iter().enumerate() {
+            let _ = writeln!(
+                text,
+                "  {}. {} — {}, {}",
+                rank + 1,
+                plays.artist,
+                plural(plays.listens, "listen"),
+                format_hours(plays.played_seconds)
+            );
+        }
+
+        if !self.discoveries.is_empty() {
+            text.push_str("\nNew discoveries\n");
+            for plays in &self.discoveries {
+                let _ = writeln!(text, "  • {} ({})", plays.artist, plural(plays.listens, "listen"));
+            }
+        }
+
+        text
+    }
+
+    /// Markdown report to keep or share
+    pub fn to_markdown(&self) -> String {
+        let mut report = format!("# My {} in music\n\n*{}*\n\n", self.period.name(), self.date_range());
+
+        if self.listens == 0 {
+            report.push_str(&format!("No listens recorded in the past {}.\n", self.period.name()));
+            return report;
+        }
+
+        let _ = writeln!(report, "- **{}** of music", format_hours(self.played_seconds));
+        let _ = writeln!(
+            report,
+            "- **{}** listens of **{}** tracks by **{}** artists",
+            self.listens, self.tracks, self.artists
+        );
+        let _ = writeln!(report, "- Listened on **{} of {}** days", self.active_days, self.days());
+
+        report.push_str("\n## Top artists\n\n");
+        for (rank, plays) in self.top_artists.iter().enumerate() {
+            let _ = writeln!(
+                report,
+                "{}. **{}** — {} ({})",
+                rank + 1,
+                escape_markdown(&plays.artist),
+                plural(plays.listens, "listen"),
+                format_hours(plays.played_seconds)
+            );
+        }
+
+        if !self.discoveries.is_empty() {
+            report.push_str("\n## New discoveries\n\n");
+            for plays in &self.discoveries {
+                let _ = writeln!(report, "- **{}** — {}", escape_markdown(&plays.artist), plural(plays.listens, "listen"));
+            }
+        }
+
+        report
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+fn format_hours(seconds: u64) -> String {
+    match seconds {
+        0..3600 => plural(seconds as usize / 60, "minute"),
+        3600..3780 => "1 hour".to_string(),
+        _ => format!("{:.1} hours", seconds as f64 / 3600.0),
+    }
+}
+
+/// Keep artist names from being read as Markdown formatting
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '#' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::Duration;
+    use chrono::Utc;
+
+    fn listen(artist: &str, title: &str, at: &str) -> Listen {
+        Listen::new(
+            format!("/music/{}/{}.mp3", artist, title),
+            title.to_string(),
+            artist.to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(240),
+            Duration::from_seconds(180),
+            at.parse().unwrap(),
+        )
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_period_first_day() {
+        let today = date("2026-03-31");
+        assert_eq!(WrappedPeriod::Week.first_day(today), date("2026-03-25"));
+        // February has no 31st, so the month starts on March 1st
+        assert_eq!(WrappedPeriod::Month.first_day(today), date("2026-03-01"));
+        assert_eq!(WrappedPeriod::Year.first_day(today), date("2025-04-01"));
+    }
+
+    #[test]
+    fn test_wrapped_ranks_artists_and_finds_discoveries() {
+        let listens = [
+            listen("Nujabes", "Aruarian Dance", "2026-03-09T10:00:00Z"),
+            listen("Nujabes", "Feather", "2026-03-09T10:05:00Z"),
+            listen("Nujabes", "Feather", "2026-03-10T10:00:00Z"),
+            listen("J Dilla", "Time", "2026-03-10T11:00:00Z"),
+            listen("Shing02", "Luv(sic.)", "2026-03-11T11:00:00Z"),
+            listen("Shing02", "Luv(sic.) pt2", "2026-03-11T11:05:00Z"),
+        ];
+        let known: HashSet<String> = ["Nujabes".to_string()].into();
+
+        let wrapped = Wrapped::new(
+            WrappedPeriod::Week,
+            date("2026-03-05"),
+            date("2026-03-11"),
+            &listens,
+            &known,
+            &Utc,
+        );
+
+        assert_eq!(wrapped.listens, 6);
+        assert_eq!(wrapped.played_seconds, 6 * 180);
+        assert_eq!(wrapped.tracks, 5);
+        assert_eq!(wrapped.artists, 3);
+        assert_eq!(wrapped.active_days, 3);
+
+        let top: Vec<&str> = wrapped.top_artists.iter().map(|plays| plays.artist.as_str()).collect();
+        assert_eq!(top, ["Nujabes", "Shing02", "J Dilla"]);
+        let discoveries: Vec<&str> = wrapped.discoveries.iter().map(|plays| plays.artist.as_str()).collect();
+        assert_eq!(discoveries, ["Shing02", "J Dilla"]);
+
+        let text = wrapped.to_text();
+        assert!(text.contains("18 minutes of music · 6 listens · 5 tracks · 3 artists"));
+        assert!(text.contains("Listened on 3 of 7 days"));
+        assert!(text.contains("  1. Nujabes — 3 listens"));
+        assert!(text.contains("  • J Dilla (1 listen)"));
+
+        let report = wrapped.to_markdown();
+        assert!(report.starts_with("# My week in music\n"));
+        assert!(report.contains("- **Shing02** — 2 listens"));
+    }
+
+    #[test]
+    fn test_wrapped_without_listens() {
+        let wrapped = Wrapped::new(
+            WrappedPeriod::Month,
+            date("2026-02-12"),
+            date("2026-03-11"),
+            &[],
+            &HashSet::new(),
+            &Utc,
+        );
+        assert!(wrapped.to_text().contains("No listens recorded in the past month."));
+    }
+
+    #[test]
+    fn test_format_hours() {
+        assert_eq!(format_hours(59), "0 minutes");
+        assert_eq!(format_hours(60), "1 minute");
+        assert_eq!(format_hours(3600), "1 hour");
+        assert_eq!(format_hours(5 * 3600 + 1800), "5.5 hours");
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("*NSYNC_"), "\\*NSYNC\\_");
+    }
+}
@@ -15,6 +15,9 @@ pub enum DomainError {
     #[error("Invalid playlist name: {0}")]
     InvalidPlaylistName(String),
     
+    #[error("Invalid bookmark name: {0}")]
+    InvalidBookmarkName(String),
+    
     
     #[error("Song not found: {0}")]
     SongNotFound(String),
@@ -44,6 +47,7 @@ pub enum ApplicationError {
 }
 
 /// Infrastructure layer errors
+#[allow(dead_code)] // Reserved for future infrastructure errors
 #[derive(Error, Debug)]
 pub enum InfrastructureError {
     #[error("Infrastructure error: {0}")]
@@ -52,6 +56,7 @@ pub enum InfrastructureError {
 }
 
 /// Presentation layer errors
+#[allow(dead_code)] // Reserved for future presentation errors
 #[derive(Error, Debug)]
 pub enum PresentationError {
     #[error("Application error: {0}")]
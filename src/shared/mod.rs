@@ -0,0 +1,8 @@
+//! Shared kernel
+//!
+//! Contains types used across all architectural layers.
+
+pub mod errors;
+pub mod natural_sort;
+pub mod search;
+pub mod sort_key;
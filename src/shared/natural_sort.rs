@@ -0,0 +1,118 @@
+//! Natural ordering of names
+//!
+//! Runs of digits compare by their value, so "Track 2" sorts before
+//! "Track 10", and letters compare without case. The database sorts with
+//! the same order through its `NATURAL_SORT` collation, or through keys from
+//! [`natural_key`] stored next to the names it sorts most.
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Compare `a` and `b` in natural order
+///
+/// Names that differ only in case or in leading zeros are told apart by
+/// their bytes, so no two different names compare equal.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let order = match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                compare_numbers(&number(&mut a_chars), &number(&mut b_chars))
+            }
+            (Some(&x), Some(&y)) => {
+                a_chars.next();
+                b_chars.next();
+                x.to_lowercase().cmp(y.to_lowercase())
+            }
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+/// Key of `name` whose byte order is the natural order of names
+///
+/// A plain index on stored keys sorts the library without the collation,
+/// which only the app's own connections have. Letters are lowered, and runs
+/// of digits sort among other characters as a digit does, then by their
+/// length without leading zeros and their digits. The name itself follows,
+/// to tell apart names that differ only in case or leading zeros.
+pub fn natural_key(name: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(name.len() * 2 + 1);
+    let mut chars = name.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let digits = number(&mut chars);
+            let value = digits.trim_start_matches('0');
+            key.push(b'0');
+            key.extend_from_slice(&(value.len() as u32).to_be_bytes());
+            key.extend_from_slice(value.as_bytes());
+        } else {
+            chars.next();
+            let mut buffer = [0; 4];
+            for lower in c.to_lowercase() {
+                key.extend_from_slice(lower.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+
+    // Nothing a name continues with sorts before the end of one
+    key.push(0);
+    key.extend_from_slice(name.as_bytes());
+    key
+}
+
+/// The run of digits `chars` starts with
+fn number(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        digits.push(digit);
+    }
+    digits
+}
+
+/// Compare runs of digits by value, however long they are
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_compare_by_value() {
+        let mut titles = vec!["Track 10", "track 2", "Track 1", "Track 02b", "Track", "Side B", "side a"];
+        titles.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(titles, ["side a", "Side B", "Track", "Track 1", "track 2", "Track 02b", "Track 10"]);
+
+        assert_eq!(natural_cmp("9 Lives", "10 Lives"), Ordering::Less);
+        assert_eq!(natural_cmp("99999999999999999999999", "100000000000000000000000"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_keys_sort_in_natural_order() {
+        let mut names = vec![
+            "Track 10", "track 2", "Track 1", "Track 02b", "Track 2", "Track", "Side B", "side a", "Lofi", "lofi",
+            "9 Lives", "10 Lives", "Étude", "etude 3", "Zebra", "", "Track 2 (Remix)", "a100", "a0100", "a-1",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+
+        let mut by_key = names.clone();
+        by_key.sort_by_key(|name| natural_key(name));
+        assert_eq!(by_key, names);
+    }
+
+    #[test]
+    fn test_different_names_never_tie() {
+        assert_eq!(natural_cmp("Lofi", "lofi"), Ordering::Less);
+        assert_ne!(natural_cmp("Track 2", "Track 02"), Ordering::Equal);
+        assert_eq!(natural_cmp("Track 2", "Track 2"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,11 @@
+//! Song search
+//!
+//! A song matches a search when the lowered text of its title, artist and
+//! album contains the lowered query. Songs held by the UI and the database's
+//! `MATCHES_SEARCH` function both go through [`search_text`], so they always
+//! agree on what matches.
+
+/// The lowered text of a song that a search looks through
+pub fn search_text(title: &str, artist: &str, album: &str) -> String {
+    format!("{} {} {}", title, artist, album).to_lowercase()
+}
@@ -0,0 +1,68 @@
+//! Sort keys of artist and album names
+//!
+//! With `ignore_articles` on, artists and albums sort without their leading
+//! article, so "The Roots" files under R next to "Rjd2". The library stores
+//! the keys in the `artist_sort` and `album_sort` columns when songs are
+//! saved, and stores them afresh when the articles in the settings change.
+//! Articles ending in an apostrophe, like the French "L'", join the word
+//! after them without a space.
+
+/// Articles ignored when `ignore_articles` is on and none are configured
+pub const DEFAULT_ARTICLES: &[&str] = &["The", "A", "An"];
+
+/// `name` without the first of `articles` it starts with, ignoring case
+///
+/// A name that is only an article is kept whole.
+pub fn strip_article<'a>(name: &'a str, articles: &[String]) -> &'a str {
+    let name = name.trim_start();
+    'articles: for article in articles {
+        let mut chars = name.char_indices();
+        for expected in article.chars() {
+            match chars.next() {
+                Some((_, c)) if c.to_lowercase().eq(expected.to_lowercase()) => {}
+                _ => continue 'articles,
+            }
+        }
+
+        let rest = &name[chars.offset()..];
+        let joined = article.ends_with(['\'', '’']);
+        if !joined && !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = rest.trim_start();
+        if !rest.is_empty() {
+            return rest;
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn articles(articles: &[&str]) -> Vec<String> {
+        articles.iter().map(|article| article.to_string()).collect()
+    }
+
+    #[test]
+    fn test_strip_article_needs_a_whole_word() {
+        let english = articles(DEFAULT_ARTICLES);
+        assert_eq!(strip_article("The Roots", &english), "Roots");
+        assert_eq!(strip_article("the  black keys", &english), "black keys");
+        assert_eq!(strip_article("A Tribe Called Quest", &english), "Tribe Called Quest");
+        assert_eq!(strip_article("Theory of a Deadman", &english), "Theory of a Deadman");
+        assert_eq!(strip_article("Animal Collective", &english), "Animal Collective");
+        assert_eq!(strip_article("The", &english), "The");
+        assert_eq!(strip_article("The The", &english), "The");
+        assert_eq!(strip_article("The Roots", &[]), "The Roots");
+    }
+
+    #[test]
+    fn test_strip_article_with_apostrophe() {
+        let french = articles(&["Les", "L'", "Le", "La"]);
+        assert_eq!(strip_article("L'Impératrice", &french), "Impératrice");
+        assert_eq!(strip_article("Les Négresses Vertes", &french), "Négresses Vertes");
+        assert_eq!(strip_article("Lemon Demon", &french), "Lemon Demon");
+    }
+}
@@ -1,40 +1,264 @@
-use crate::audio::{AudioPlayer, PlayerCommand, PlayerState, PlaybackStatus};
+use crate::audio::meter::{self, MeterReading, VuMeter};
+use crate::audio::spectrum::Analyzer;
+use crate::audio::stream;
+use crate::audio::{output, AudioOptions, AudioPlayer, PlayerCommand, PlayerError, PlayerState, PlaybackStatus, Trim};
+use crate::domain::entities::{Bookmark, Listen, ListeningSummary};
+use crate::domain::value_objects;
 use crate::config::{Config, PersistentSettings, LayoutConfig};
 use crate::error::{Result, LofiTurtleError};
-use crate::library::Database;
-use crate::models::{Song, Playlist, PlaybackState};
+use crate::media_controls::{MediaControls, MediaKey};
+use crate::notifications::Notifier;
+use crate::services::scan_job::ScanOutcome;
+use crate::services::desktop::Pasted;
+use crate::services::{desktop, library_service, LibraryService, Pending, ScanJob, ScanSettings, SongQuery, SongWindow};
+use crate::models::queue::{self, PlayQueue};
+use crate::models::smart_shuffle::{self, ShuffleTrack};
+use crate::models::{AutoDjStrategy, Song, Playlist, PlaylistSort, PlaybackState, RepeatMode};
 use crate::art::AlbumArtRenderer;
-use crate::ui::theme::Themes;
-use crate::ui::layout::LayoutEngine;
+use crate::library::chapters::{self, Chapter};
+use crate::library::tags::TrackTags;
+use crate::library::{MusicScanner, ScanProgress, ScanReport};
+use crate::logging;
+use crate::plugins::{PlayerEvent, PluginManager, TrackInfo};
+use crate::scrobbling::{LastFmClient, ListenTracker, Scrobbler};
+use crate::shared::natural_sort::natural_cmp;
+use crate::t;
+use crate::ui::theme::{ThemeManager, Themes};
+use crate::ui::heatmap::ListeningHeatmap;
+use crate::ui::keymap::Chords;
+use crate::ui::layout::{LayoutEngine, ThemeConfig, WidgetType};
+use crate::ui::oscilloscope;
+use crate::ui::progress;
+use crate::ui::session::Session;
+use crate::ui::text;
+use crate::ui::thumbnail;
+use crate::ui::dir_browser::DirBrowser;
+use crate::ui::setup::{SetupStep, SetupWizard};
+use crate::ui::undo::{EditHistory, PlaylistEdit};
+use crate::ui::visualizer::VisualizerMode;
 use ratatui::crossterm::event::Event;
-use std::time::Instant;
+use rodio::Sample;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tui_textarea::TextArea;
 
+/// Number of tracks the auto-DJ appends each time the queue runs out
+const AUTO_DJ_BATCH_SIZE: usize = 5;
+
+/// Number of songs shown in the "Most Played" view
+const MOST_PLAYED_LIMIT: usize = 50;
+
+/// Number of songs in the Daily Mix
+const DAILY_MIX_SIZE: usize = 50;
+
+/// How long a toast notification stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// Seconds into a chapter before going back restarts it rather than going
+/// to the chapter before
+const CHAPTER_RESTART_SECS: u64 = 3;
+
+/// How long after the last move through the song list following playback
+/// takes over again
+const FOLLOW_RESUME: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputMode {
     Normal,
     Search,
     PlaylistCreate,
     PlaylistEdit,
+    /// Name of the playlist to save the queue as
+    QueueSave,
+    /// Passphrase that party mode will be unlocked with
+    PartyLock,
+    /// The same passphrase again, so a typo cannot lock the player for good
+    PartyConfirm,
+    /// Passphrase to leave party mode
+    PartyUnlock,
+    /// Name of a bookmark at the playing position
+    BookmarkName,
+    /// Title of the selected song, edited in place in the song list
+    SongTitle,
+    /// First confirmation before deleting a song file
+    ConfirmDelete,
+    /// Second and last confirmation before deleting a song file
+    ConfirmDeleteFinal,
+    /// Confirmation before adding every song shown to a playlist
+    ConfirmBatchAdd,
+    /// Listening stats screen
+    Stats,
+    /// Keybinding cheatsheet
+    Help,
+    /// Popup with the playlists a song is in and its bookmarks
+    SongInfo,
+    /// Summary of a scan that left files out
+    ScanReport,
+    /// Recent log records
+    Logs,
+    /// First-run setup wizard
+    Setup,
+    /// Directory browser for picking the music directory
+    MusicDir,
+    /// List of audio outputs to play through
+    Output,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ActivePanel {
     Playlists,
     Songs,
     AlbumArt,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ViewMode {
     Library,
     Playlist(String),
+    Smart(SmartView),
+}
+
+/// Built-in views listed above user playlists in the Playlists panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartView {
+    RecentlyAdded,
+    MostPlayed,
+    NeverPlayed,
+    /// Songs picked by rating and recency, changing every day
+    DailyMix,
+}
+
+impl SmartView {
+    /// All built-in views in display order
+    pub const ALL: [SmartView; 4] = [
+        SmartView::RecentlyAdded,
+        SmartView::MostPlayed,
+        SmartView::NeverPlayed,
+        SmartView::DailyMix,
+    ];
+
+    /// Name shown in the Playlists panel
+    pub fn title(&self) -> String {
+        match self {
+            SmartView::RecentlyAdded => t!("smart-recently-added"),
+            SmartView::MostPlayed => t!("smart-most-played"),
+            SmartView::NeverPlayed => t!("smart-never-played"),
+            SmartView::DailyMix => t!("smart-daily-mix"),
+        }
+    }
+}
+
+/// What to do with a view's songs once they have loaded
+#[derive(Debug, Clone, PartialEq)]
+enum AfterLoad {
+    /// Just show them
+    Show,
+    /// Start playing the first song
+    PlayFirst,
+    /// Select the song stored at this path
+    Select(String),
+    /// Rebuild the shuffle order, whose song indices went stale
+    Reshuffle,
+}
+
+/// Songs being loaded in the background for the current view
+struct SongsLoad {
+    query: SongQuery,
+    songs: Pending<Vec<Song>>,
+    then: AfterLoad,
+}
+
+/// Window on the library being opened for the library view
+struct WindowLoad {
+    window: Pending<SongWindow>,
+    then: AfterLoad,
+}
+
+/// Paths pasted into the queue whose songs are read in the background
+enum PastedRead {
+    /// A folder, by name, whose songs are queued in path order
+    Folder(String),
+    /// Files, queued in the order they were pasted
+    Files(Vec<PathBuf>),
+}
+
+/// The song lined up to follow the playing one for gapless playback
+///
+/// Shuffling past the end of a shuffled round picks at random, so the pick
+/// is kept, along with the playback state moving on to it leaves, until the
+/// queue changes.
+struct LinedUp {
+    /// Queue, position and modes the pick was made for
+    filtered_indices: Vec<usize>,
+    current_song_index: usize,
+    shuffle: bool,
+    shuffle_queue: VecDeque<usize>,
+    repeat_mode: RepeatMode,
+    /// Playback state once it has moved on to `index`
+    advanced: PlaybackState,
+    index: Option<usize>,
+}
+
+impl LinedUp {
+    /// Whether the pick still stands for the queue in `state`
+    fn is_for(&self, state: &AppState) -> bool {
+        self.current_song_index == state.playback_state.current_song_index
+            && self.shuffle == state.playback_state.shuffle
+            && self.repeat_mode == state.playback_state.repeat_mode
+            && self.shuffle_queue == state.playback_state.shuffle_queue
+            && self.filtered_indices == state.filtered_indices
+    }
+}
+
+/// Short-lived message shown over the UI
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub shown_at: Instant,
+}
+
+impl Toast {
+    /// Check if the toast should still be shown
+    pub fn is_visible(&self) -> bool {
+        self.shown_at.elapsed() < TOAST_DURATION
+    }
+}
+
+/// Song shown in the song info popup
+#[derive(Debug, Clone)]
+pub struct SongInfo {
+    pub song: Song,
+    /// Names of the playlists the song is in
+    pub playlists: Vec<String>,
+    pub bookmarks: Vec<Bookmark>,
+    /// Index of the selected bookmark
+    pub selected: usize,
+}
+
+/// Audio outputs to pick from in the output picker
+#[derive(Debug, Clone)]
+pub struct OutputPicker {
+    /// Host and device name of each output
+    pub outputs: Vec<(String, String)>,
+    /// Index of the highlighted output
+    pub selected: usize,
 }
 
 #[derive(Debug)]
 pub struct AppState {
     pub songs: Vec<Song>,
-    pub filtered_songs: Vec<Song>,
+    /// Indices into `songs` of the songs matching the search, in display order
+    pub filtered_indices: Vec<usize>,
+    /// The library view's songs matching the search, in place of `songs`,
+    /// as the library is too large to hold whole
+    pub library_window: Option<SongWindow>,
+    /// The playing song, which the view may not hold
+    pub playing_song: Option<Song>,
     pub playlists: Vec<Playlist>,
     pub selected_song_index: usize,
     pub selected_playlist_index: usize,
@@ -47,12 +271,116 @@ pub struct AppState {
     pub playback_status: PlaybackStatus,
     pub playback_state: PlaybackState,
     pub show_album_art: bool,
+    pub visualizer_mode: VisualizerMode,
     pub current_album_art: Option<String>,
+    /// Cover of the playing song shrunk for the control panel
+    pub playing_thumbnail: Option<image::RgbImage>,
     pub should_quit: bool,
     pub last_update: Instant,
     // New fields for scanning status
-    pub is_scanning: bool,
-    pub scan_progress: (usize, usize),
+    /// Progress of the running library scan, if any
+    pub scan_progress: Option<ScanProgress>,
+    /// The running scan was asked to stop and is finishing up
+    pub scan_cancelling: bool,
+    /// Finished scan shown in the scan summary popup
+    pub scan_report: Option<ScanOutcome>,
+    /// Log records the log viewer is scrolled back from the newest
+    pub log_scroll: usize,
+    /// How far back the "Recently Added" view looks
+    pub recently_added_days: u32,
+    /// Song waiting for delete confirmation
+    pub pending_delete: Option<Song>,
+    /// Move deleted song files to the trash instead of removing them
+    pub delete_to_trash: bool,
+    /// Most recent toast notification
+    pub toast: Option<Toast>,
+    /// Heatmap shown on the stats screen, loaded when it is opened
+    pub heatmap: Option<ListeningHeatmap>,
+    /// Order of the Playlists panel
+    pub playlist_sort: PlaylistSort,
+    /// Names of the playlists the playing song is in
+    pub playing_song_playlists: Vec<String>,
+    /// Order of the queue before it was shuffled with `shuffle_queue`, to
+    /// put it back
+    pub unshuffled_queue: Option<Vec<usize>>,
+    /// Chapters of the playing song, from a CUE sheet or its tags
+    pub chapters: Vec<Chapter>,
+    /// Song shown in the song info popup
+    pub song_info: Option<SongInfo>,
+    /// Path of the song to bookmark and the position, waiting for a name
+    pub pending_bookmark: Option<(String, Duration)>,
+    /// Path of the song whose title is being edited
+    pub pending_rename: Option<String>,
+    /// Playlist and songs waiting for confirmation to be added to it, and
+    /// how many songs shown were left out as outside the library
+    pub pending_batch_add: Option<(String, Vec<String>, usize)>,
+    /// Today's Daily Mix, kept so it does not shift as songs are played
+    pub daily_mix: Option<(chrono::NaiveDate, Vec<Song>)>,
+    /// When the songs or playlists still loading started to load, for the spinner
+    pub loading_since: Option<Instant>,
+    /// Keep the playing song selected and centred in the song list
+    pub follow_playback: bool,
+    /// When the song selection was last moved by hand, which holds off
+    /// following playback for a while
+    pub navigated_at: Option<Instant>,
+    /// Stop once the playing song ends instead of going on with the queue
+    pub stop_after_current: bool,
+    /// Playback was stopped after a song on purpose, so the queue does not
+    /// move on until a song is played again
+    pub hold_playback: bool,
+    /// Passphrase of party mode while it is on, when guests can only
+    /// browse, search and add to the queue
+    pub party_passphrase: Option<String>,
+    /// Passphrase typed the first time, waiting to be typed again
+    pub pending_party_passphrase: Option<String>,
+    /// First-run setup wizard, while it is open
+    pub setup: Option<SetupWizard>,
+    /// Browser the music directory is being picked in
+    pub dir_picker: Option<DirBrowser>,
+    /// Audio outputs being picked from
+    pub output_picker: Option<OutputPicker>,
+    /// IDs of the songs marked as loved
+    pub loved: HashSet<String>,
+}
+
+impl AppState {
+    /// Number of songs matching the search
+    pub fn filtered_len(&self) -> usize {
+        match &self.library_window {
+            Some(window) => window.len(),
+            None => self.filtered_indices.len(),
+        }
+    }
+
+    /// Song at `index` among the songs matching the search, unless the
+    /// library window does not hold it yet
+    pub fn filtered_song(&self, index: usize) -> Option<&Song> {
+        match &self.library_window {
+            Some(window) => window.get(index),
+            None => self.filtered_indices.get(index).and_then(|&i| self.songs.get(i)),
+        }
+    }
+
+    /// Position of the song at `path` among the songs matching the search,
+    /// if it is held
+    pub fn filtered_index_of(&self, path: &str) -> Option<usize> {
+        match &self.library_window {
+            Some(window) => window.held().find(|(_, song)| song.path == path).map(|(index, _)| index),
+            None => self.filtered_songs().position(|song| song.path == path),
+        }
+    }
+
+    /// Songs matching the search that are held, in display order
+    pub fn filtered_songs(&self) -> impl Iterator<Item = &Song> + '_ {
+        let window = self.library_window.iter().flat_map(|window| window.held().map(|(_, song)| song));
+        self.filtered_indices.iter().filter_map(|&i| self.songs.get(i)).chain(window)
+    }
+
+    /// Every song of the view that is held, matching the search or not
+    fn held_songs(&self) -> impl Iterator<Item = &Song> + '_ {
+        let window = self.library_window.iter().flat_map(|window| window.held().map(|(_, song)| song));
+        self.songs.iter().chain(window)
+    }
 }
 
 impl Default for AppState {
@@ -65,7 +393,9 @@ impl Default for AppState {
         
         Self {
             songs: Vec::new(),
-            filtered_songs: Vec::new(),
+            filtered_indices: Vec::new(),
+            library_window: None,
+            playing_song: None,
             playlists: Vec::new(),
             selected_song_index: 0,
             selected_playlist_index: 0,
@@ -78,112 +408,760 @@ impl Default for AppState {
             playback_status: PlaybackStatus::default(),
             playback_state: PlaybackState::default(),
             show_album_art: true,
+            visualizer_mode: VisualizerMode::default(),
             current_album_art: None,
+            playing_thumbnail: None,
             should_quit: false,
             last_update: Instant::now(),
-            is_scanning: false,
-            scan_progress: (0, 0),
+            scan_progress: None,
+            scan_cancelling: false,
+            scan_report: None,
+            log_scroll: 0,
+            recently_added_days: 30,
+            pending_delete: None,
+            delete_to_trash: true,
+            toast: None,
+            heatmap: None,
+            playlist_sort: PlaylistSort::default(),
+            playing_song_playlists: Vec::new(),
+            unshuffled_queue: None,
+            chapters: Vec::new(),
+            song_info: None,
+            pending_bookmark: None,
+            pending_rename: None,
+            pending_batch_add: None,
+            daily_mix: None,
+            loading_since: None,
+            follow_playback: false,
+            navigated_at: None,
+            stop_after_current: false,
+            hold_playback: false,
+            party_passphrase: None,
+            pending_party_passphrase: None,
+            setup: None,
+            dir_picker: None,
+            output_picker: None,
+            loved: HashSet::new(),
         }
     }
 }
 
+/// Name of a playlist order, as shown in the panel title
+pub fn playlist_sort_label(sort: PlaylistSort) -> String {
+    match sort {
+        PlaylistSort::Name => t!("playlist-sort-name"),
+        PlaylistSort::Created => t!("playlist-sort-created"),
+        PlaylistSort::Updated => t!("playlist-sort-updated"),
+        PlaylistSort::SongCount => t!("playlist-sort-song-count"),
+    }
+}
+
+/// What the player is doing, in words
+pub fn player_state_label(state: &PlayerState) -> String {
+    match state {
+        PlayerState::Playing => t!("state-playing"),
+        PlayerState::Paused => t!("state-paused"),
+        PlayerState::Stopped => t!("state-stopped"),
+    }
+}
+
+/// The shuffle mode, in words
+pub fn shuffle_label(state: &PlaybackState) -> String {
+    t!(match (state.shuffle, state.smart_shuffle) {
+        (false, _) => "state-shuffle-off",
+        (true, false) => "state-shuffle-on",
+        (true, true) => "state-shuffle-smart",
+    })
+}
+
+/// The repeat mode, in words
+pub fn repeat_label(mode: RepeatMode) -> String {
+    match mode {
+        RepeatMode::None => t!("state-repeat-off"),
+        RepeatMode::Single => t!("state-repeat-one"),
+        RepeatMode::Playlist => t!("state-repeat-all"),
+    }
+}
+
+/// The volume, in words
+pub fn volume_label(volume: f32) -> String {
+    t!("state-volume", volume = (volume * 100.0).round() as u32)
+}
+
 pub struct App {
     pub state: AppState,
-    pub database: Database,
+    pub library: LibraryService,
     pub audio_player: AudioPlayer,
+    /// Options the audio player was opened with
+    audio_options: AudioOptions,
+    /// Set while a newly picked output has yet to start the playing song
+    /// again, which until then looks stopped as if the song had ended
+    switching_output: bool,
     pub album_art_renderer: AlbumArtRenderer,
+    /// Spectrum analyser behind the visualizer
+    analyzer: Analyzer,
+    /// Ballistics of the VU meter
+    vu_meter: VuMeter,
     pub persistent_settings: PersistentSettings,
     pub layout_config: LayoutConfig,
     pub layout_engine: LayoutEngine,
+    /// Styles built from `layout_config.theme`
+    pub theme: ThemeManager,
+    /// Whether the terminal can show Unicode, checked once at startup
+    pub unicode: bool,
+    /// Plain text for screen readers, with changes announced on the
+    /// status line instead of in popups
+    pub screen_reader: bool,
+    /// Keys typed so far towards a multi-key binding
+    pub chords: Chords,
+    /// Playlist edits that can be undone this session
+    edits: EditHistory,
+    /// Songs for the current view that are still loading
+    songs_load: Option<SongsLoad>,
+    /// Library window for the library view that is still opening
+    window_load: Option<WindowLoad>,
+    /// Song picked to follow the playing one, for gapless playback
+    lined_up: Option<LinedUp>,
+    /// Playlists that are still loading
+    playlists_load: Option<Pending<Vec<Playlist>>>,
+    /// Library scan running in the background
+    scan: Option<ScanJob>,
+    /// Folders and files pasted into the queue that are still being read
+    pasted_reads: Vec<(PastedRead, ScanJob<ScanReport>)>,
+    /// What a library scan scans
+    scan_settings: ScanSettings,
+    pub plugins: PluginManager,
+    /// Declared after `plugins` so the listen plugin is dropped first and the
+    /// scrobbler can finish storing listens
+    scrobbler: Option<Scrobbler>,
+    /// Counts a play once a track played long enough for a listen has ended
+    plays: ListenTracker,
+    /// Last.fm account loved songs are pushed to, when one is configured
+    lastfm: Option<LastFmClient>,
+    media_controls: Option<MediaControls>,
+    /// Track change notifications, if turned on
+    notifier: Option<Notifier>,
+    clipboard: desktop::Clipboard,
 }
 
 impl App {
     pub fn new(config: &Config, layout_config: &LayoutConfig) -> Result<Self> {
-        let database = Database::new(&config.database_path)?;
-        let audio_player = AudioPlayer::new()?;
+        let library = LibraryService::new(&config.database_path, &config.sort_articles)?;
         let album_art_renderer = AlbumArtRenderer::new(config.album_art_config.clone());
         
         // Load persistent settings and set initial volume
         let persistent_settings = PersistentSettings::load();
         let initial_volume = persistent_settings.volume;
+
+        // An output picked in an earlier session may since have been
+        // unplugged, which is no reason not to start
+        let mut audio_options = config.audio_options.clone();
+        let saved_output = audio_options.host.is_some() || audio_options.device.is_some();
+        let saved_output = saved_output
+            && audio_options.host == persistent_settings.audio_host
+            && audio_options.device == persistent_settings.audio_device;
+        let mut output_missing = None;
+        let audio_player = match AudioPlayer::new(audio_options.clone()) {
+            Err(e) if saved_output => {
+                log::warn!("Falling back to the default audio output: {}", e);
+                output_missing = Some(e);
+                audio_options = AudioOptions { host: None, device: None, ..audio_options };
+                AudioPlayer::new(audio_options.clone())?
+            }
+            result => result?,
+        };
         
         let layout_engine = LayoutEngine::new(layout_config.clone());
+        let mut plugins = PluginManager::from_config(config);
+        let scrobbler = match Scrobbler::start(config) {
+            Ok(scrobbler) => Some(scrobbler),
+            Err(e) => {
+                log::warn!("Listening history disabled: {}", e);
+                None
+            }
+        };
+        if let Some(plugin) = scrobbler.as_ref().and_then(Scrobbler::listen_plugin) {
+            plugins.register(plugin);
+        }
+        let media_controls = if config.media_keys { MediaControls::start() } else { None };
+        if let Some(media_controls) = &media_controls {
+            plugins.register(media_controls.now_playing_plugin());
+        }
+        let notifier = persistent_settings.notifications.then(|| {
+            Notifier::start(
+                AlbumArtRenderer::new(config.album_art_config.clone()),
+                persistent_settings.notification_timeout_ms.map(Duration::from_millis),
+                persistent_settings.do_not_disturb,
+            )
+        });
+        if let Some(notifier) = &notifier {
+            plugins.register(notifier.plugin());
+        }
+        plugins.dispatch(&PlayerEvent::AppStarted);
 
         let mut app = Self {
-            state: AppState::default(),
-            database,
+            state: AppState {
+                playlist_sort: persistent_settings.playlist_sort,
+                follow_playback: persistent_settings.follow_playback,
+                ..AppState::default()
+            },
+            library,
             audio_player,
+            switching_output: false,
+            audio_options,
             album_art_renderer,
+            lined_up: None,
+            analyzer: Analyzer::default(),
+            vu_meter: VuMeter::default(),
             persistent_settings,
             layout_config: layout_config.clone(),
             layout_engine,
+            theme: ThemeManager::new(layout_config.theme.clone()),
+            // Block characters and spinners read as noise
+            unicode: progress::unicode_supported() && !config.screen_reader,
+            screen_reader: config.screen_reader,
+            chords: Chords::default(),
+            edits: EditHistory::default(),
+            songs_load: None,
+            window_load: None,
+            playlists_load: None,
+            scan: None,
+            pasted_reads: Vec::new(),
+            scan_settings: ScanSettings::from_config(config),
+            plugins,
+            scrobbler,
+            plays: ListenTracker::default(),
+            lastfm: LastFmClient::from_config(config).ok().filter(LastFmClient::has_session),
+            media_controls,
+            notifier,
+            clipboard: desktop::Clipboard::default(),
         };
         
         // Set initial volume from persistent settings
         app.set_volume(initial_volume)?;
+        if let Some(e) = output_missing {
+            app.show_toast(t!("output-missing", error = e.to_string()));
+        }
 
-        // Load songs and playlists from database
-        app.load_songs()?;
-        app.load_playlists()?;
+        match app.library.get_loved_songs() {
+            Ok(songs) => app.state.loved = songs.into_iter().map(|song| song.id).collect(),
+            Err(e) => log::warn!("Failed to load loved songs: {}", e),
+        }
         
         // Apply config settings
         app.state.show_album_art = config.show_art;
+        app.state.visualizer_mode = app.layout_config.settings.visualizer.mode;
         app.state.playback_state.shuffle = config.shuffle;
-        app.state.playback_state.repeat_mode = config.repeat_mode.clone();
+        app.state.playback_state.smart_shuffle = app.persistent_settings.smart_shuffle;
+        app.state.playback_state.repeat_mode = config.repeat_mode;
+        app.state.playback_state.auto_dj = config.auto_dj;
+        app.state.playback_state.continue_albums = config.continue_albums;
+        app.state.recently_added_days = config.recently_added_days;
+        app.state.delete_to_trash = config.delete_to_trash;
+
+        // Load songs and playlists from database while the UI comes up,
+        // reopened where the last run was left
+        let selected_song = Session::load().and_then(|session| app.restore_session(session));
+        app.load_songs_then(selected_song.map_or(AfterLoad::Show, AfterLoad::Select));
+        app.load_playlists();
+
+        // A theme picked in the player wins over the layout's
+        if let Some(theme) = app.persistent_settings.theme.as_deref().and_then(Themes::get_by_name) {
+            app.apply_theme(theme);
+        }
         
         Ok(app)
     }
 
-    pub fn load_songs(&mut self) -> Result<()> {
-        match &self.state.view_mode {
+    /// Start loading the songs of the current view in the background
+    ///
+    /// The songs on screen stay until the new ones arrive in `poll_loads`.
+    pub fn load_songs(&mut self) {
+        self.load_songs_then(AfterLoad::Show);
+    }
+
+    fn load_songs_then(&mut self, then: AfterLoad) {
+        self.state.loading_since.get_or_insert_with(Instant::now);
+        let today = chrono::Local::now().date_naive();
+        // Replacing an older load drops it, so its songs are never shown
+        let query = match &self.state.view_mode {
             ViewMode::Library => {
-                self.state.songs = self.database.get_all_songs()?;
+                self.songs_load = None;
+                self.open_library_window(then);
+                return;
             }
-            ViewMode::Playlist(playlist_name) => {
-                // Get playlist by name to get its ID, then get songs
-                if let Some(playlist) = self.database.get_playlist_by_name(playlist_name)? {
-                    self.state.songs = self.database.get_playlist_songs(&playlist.id)?;
-                } else {
-                    self.state.songs = Vec::new();
+            ViewMode::Playlist(playlist_name) => SongQuery::Playlist(playlist_name.clone()),
+            ViewMode::Smart(SmartView::RecentlyAdded) => SongQuery::RecentlyAdded { days: self.state.recently_added_days },
+            ViewMode::Smart(SmartView::MostPlayed) => SongQuery::MostPlayed { limit: MOST_PLAYED_LIMIT },
+            ViewMode::Smart(SmartView::NeverPlayed) => SongQuery::NeverPlayed,
+            ViewMode::Smart(SmartView::DailyMix) => SongQuery::DailyMix { day: today, size: DAILY_MIX_SIZE },
+        };
+
+        self.window_load = None;
+        let songs = match &self.state.daily_mix {
+            Some((day, songs)) if query == (SongQuery::DailyMix { day: *day, size: DAILY_MIX_SIZE }) => {
+                Pending::ready(Ok(songs.clone()))
+            }
+            _ => self.library.load_songs(query.clone()),
+        };
+        self.songs_load = Some(SongsLoad { query, songs, then });
+    }
+
+    /// Start opening a window on the library songs matching the search,
+    /// around the selected song or the one `then` selects
+    fn open_library_window(&mut self, then: AfterLoad) {
+        let select = match &then {
+            AfterLoad::Select(path) => Some(path.as_str()),
+            _ => None,
+        };
+        let around = if then == AfterLoad::PlayFirst { 0 } else { self.state.selected_song_index };
+        let window = self.library.open_song_window(&self.state.search_query, select, around);
+        self.window_load = Some(WindowLoad { window, then });
+    }
+
+    /// Start loading the playlists in the background
+    pub fn load_playlists(&mut self) {
+        self.playlists_load = Some(self.library.load_playlists());
+        self.state.loading_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Whether the songs of the current view are still loading
+    pub fn songs_loading(&self) -> bool {
+        self.songs_load.is_some()
+            || self.window_load.is_some()
+            || self.state.library_window.as_ref().is_some_and(SongWindow::is_loading)
+    }
+
+    /// Whether the playlists are still loading
+    pub fn playlists_loading(&self) -> bool {
+        self.playlists_load.is_some()
+    }
+
+    /// Show the songs and playlists that have loaded so far
+    pub fn poll_loads(&mut self) -> Result<()> {
+        if let Some(mut load) = self.songs_load.take() {
+            match load.songs.poll() {
+                Some(songs) => {
+                    self.show_songs(songs?);
+                    if let SongQuery::DailyMix { day, .. } = load.query {
+                        self.state.daily_mix = Some((day, self.state.songs.clone()));
+                    }
+                    self.finish_songs(load.then)?;
                 }
+                None => self.songs_load = Some(load),
             }
         }
-        self.update_filtered_songs();
+
+        if let Some(mut load) = self.window_load.take() {
+            match load.window.poll() {
+                Some(window) => {
+                    self.state.songs.clear();
+                    self.state.filtered_indices.clear();
+                    self.state.library_window = Some(window?);
+                    self.finish_songs(load.then)?;
+                }
+                None => self.window_load = Some(load),
+            }
+        }
+
+        // The library window follows the selection
+        if let Some(window) = &mut self.state.library_window {
+            window.poll()?;
+            window.show(self.state.selected_song_index, &self.library);
+        }
+
+        if let Some(result) = self.playlists_load.as_mut().and_then(Pending::poll) {
+            self.playlists_load = None;
+            self.show_playlists(result?);
+        }
+
+        if self.songs_loading() || self.playlists_loading() {
+            self.state.loading_since.get_or_insert_with(Instant::now);
+        } else {
+            self.state.loading_since = None;
+        }
         Ok(())
     }
-    
-    pub fn load_playlists(&mut self) -> Result<()> {
-        self.state.playlists = self.database.get_all_playlists()?;
+
+    /// Show the songs of a view in place of the old songs
+    fn show_songs(&mut self, songs: Vec<Song>) {
+        let query_lower = self.state.search_query.to_lowercase();
+        self.state.filtered_indices = songs
+            .iter()
+            .enumerate()
+            .filter(|(_, song)| query_lower.is_empty() || song.matches(&query_lower))
+            .map(|(i, _)| i)
+            .collect();
+        self.state.songs = songs;
+        self.state.library_window = None;
+    }
+
+    fn finish_songs(&mut self, then: AfterLoad) -> Result<()> {
+        self.clamp_selection();
+
+        match then {
+            AfterLoad::Show => {}
+            AfterLoad::PlayFirst => {
+                if self.state.filtered_len() > 0 {
+                    self.state.selected_song_index = 0;
+                    self.play_selected_song()?;
+                }
+            }
+            AfterLoad::Select(path) => {
+                if let Some(index) = self.state.filtered_index_of(&path) {
+                    self.state.selected_song_index = index;
+                }
+            }
+            AfterLoad::Reshuffle => {
+                if self.state.playback_state.shuffle {
+                    self.reshuffle();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep the selection in the list; a list that lost its last songs
+    /// selects the one now last, not the top
+    fn clamp_selection(&mut self) {
+        let last = self.state.filtered_len().saturating_sub(1);
+        self.state.selected_song_index = self.state.selected_song_index.min(last);
+    }
+
+    /// Scan the music directory in the background, unless a scan is running
+    pub fn start_scan(&mut self) {
+        if self.scan.is_some() {
+            self.show_toast(t!("scan-running"));
+            return;
+        }
+
+        match ScanJob::start(self.scan_settings.clone()) {
+            Ok(job) => {
+                self.state.scan_progress = Some(job.progress());
+                self.scan = Some(job);
+            }
+            Err(e) => self.show_toast(t!("scan-failed", error = e.to_string())),
+        }
+    }
+
+    /// Open the first-run setup wizard in place of the startup scan
+    pub fn open_setup(&mut self) {
+        let theme = Themes::all()
+            .iter()
+            .position(|theme| theme.name == self.layout_config.theme.name)
+            .unwrap_or(0);
+        self.state.setup = Some(SetupWizard::new(&self.scan_settings.music_dir, theme));
+        self.state.input_mode = InputMode::Setup;
+    }
+
+    /// Take the directory being browsed as the music directory and go on to
+    /// picking a theme
+    pub fn choose_setup_directory(&mut self) {
+        if let Some(setup) = self.state.setup.as_mut() {
+            setup.step = SetupStep::Theme;
+        }
+    }
+
+    /// Highlight another theme in the wizard, previewing it
+    pub fn move_setup_theme(&mut self, delta: isize) {
+        let themes = Themes::all();
+        let Some(setup) = self.state.setup.as_mut() else {
+            return;
+        };
+        setup.theme = setup.theme.saturating_add_signed(delta).min(themes.len() - 1);
+        let theme = themes[setup.theme].clone();
+        self.apply_theme(theme);
+    }
+
+    /// Go back from picking a theme to picking the directory
+    pub fn setup_back(&mut self) {
+        if let Some(setup) = self.state.setup.as_mut() {
+            setup.step = SetupStep::Directory;
+        }
+    }
+
+    /// Save the directory and theme picked, then start the first scan
+    pub fn finish_setup_choices(&mut self) -> Result<()> {
+        let Some(setup) = self.state.setup.as_mut() else {
+            return Ok(());
+        };
+        setup.step = SetupStep::Scan;
+        let music_dir = setup.browser.dir().to_path_buf();
+
+        self.scan_settings.music_dir = music_dir.clone();
+        self.persistent_settings.music_dir = Some(music_dir.clone());
+        self.persistent_settings.theme = Some(self.layout_config.theme.name.clone());
+        let settings = PersistentSettings {
+            music_dir: Some(music_dir),
+            theme: Some(self.layout_config.theme.name.clone()),
+            ..PersistentSettings::load()
+        };
+        self.start_scan();
+        settings.save()
+    }
+
+    /// Close the setup wizard; if it is left before a directory was picked,
+    /// it comes back on the next start
+    pub fn close_setup(&mut self) {
+        self.state.setup = None;
+        self.state.input_mode = InputMode::Normal;
+    }
+
+    /// Open the directory browser to pick another music directory
+    pub fn open_music_dir_picker(&mut self) {
+        if self.scan.is_some() {
+            self.show_toast(t!("scan-running"));
+            return;
+        }
+        self.state.dir_picker = Some(DirBrowser::new(&self.scan_settings.music_dir));
+        self.state.input_mode = InputMode::MusicDir;
+    }
+
+    /// Make the directory being browsed the music directory, remembered
+    /// for next time, and scan it
+    pub fn choose_music_dir(&mut self) -> Result<()> {
+        let Some(browser) = self.state.dir_picker.take() else {
+            return Ok(());
+        };
+        self.state.input_mode = InputMode::Normal;
+        let music_dir = browser.dir().to_path_buf();
+
+        self.scan_settings.music_dir = music_dir.clone();
+        self.persistent_settings.music_dir = Some(music_dir.clone());
+        let settings = PersistentSettings {
+            music_dir: Some(music_dir.clone()),
+            ..PersistentSettings::load()
+        };
+        self.show_toast(t!("music-dir-changed", dir = music_dir.display().to_string()));
+        self.start_scan();
+        settings.save()
+    }
+
+    /// Open the list of audio outputs, highlighting the one playing
+    pub fn open_output_picker(&mut self) {
+        let outputs = output::outputs();
+        if outputs.is_empty() {
+            self.show_toast(t!("output-none"));
+            return;
+        }
+
+        let host = output::host(self.audio_options.host.as_deref()).ok();
+        let playing = host.and_then(|host| {
+            let device = self.audio_options.device.clone().or_else(|| output::default_device_name(&host))?;
+            Some((host.id().name().to_string(), device))
+        });
+        let selected = playing
+            .and_then(|(host, device)| {
+                outputs.iter().position(|(h, d)| h.eq_ignore_ascii_case(&host) && d.eq_ignore_ascii_case(&device))
+            })
+            .unwrap_or(0);
+
+        self.state.output_picker = Some(OutputPicker { outputs, selected });
+        self.state.input_mode = InputMode::Output;
+    }
+
+    /// Move the highlight in the output picker, stopping at the ends
+    pub fn move_output_selection(&mut self, delta: isize) {
+        if let Some(picker) = self.state.output_picker.as_mut() {
+            let last = picker.outputs.len().saturating_sub(1);
+            picker.selected = picker.selected.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    pub fn close_output_picker(&mut self) {
+        self.state.output_picker = None;
+        self.state.input_mode = InputMode::Normal;
+    }
+
+    /// Play through the output highlighted in the picker, remembered for
+    /// next time; the playing song carries on from where it was
+    pub fn choose_output(&mut self) -> Result<()> {
+        let Some(picker) = self.state.output_picker.take() else {
+            return Ok(());
+        };
+        self.state.input_mode = InputMode::Normal;
+        let Some((host, device)) = picker.outputs.get(picker.selected).cloned() else {
+            return Ok(());
+        };
+
+        let options = AudioOptions {
+            host: Some(host.clone()),
+            device: Some(device.clone()),
+            ..self.audio_options.clone()
+        };
+        let player = match AudioPlayer::new(options.clone()) {
+            Ok(player) => player,
+            Err(e) => {
+                self.show_toast(t!("output-failed", device = device.as_str(), error = e.to_string()));
+                return Ok(());
+            }
+        };
+
+        let status = self.audio_player.get_status();
+        let old_player = std::mem::replace(&mut self.audio_player, player);
+        let _ = old_player.send_command(PlayerCommand::Quit);
+        self.audio_options = options;
+        self.set_volume(status.volume)?;
+
+        if let (Some(path), false) = (status.current_song, status.state == PlayerState::Stopped) {
+            self.audio_player.send_command(PlayerCommand::Play(path, status.trim))?;
+            self.audio_player.send_command(PlayerCommand::Seek(Duration::from_secs(status.current_position)))?;
+            if status.state == PlayerState::Paused {
+                self.audio_player.send_command(PlayerCommand::Pause)?;
+            }
+            self.switching_output = true;
+        }
+
+        self.persistent_settings.audio_host = Some(host.clone());
+        self.persistent_settings.audio_device = Some(device.clone());
+        let settings = PersistentSettings {
+            audio_host: Some(host),
+            audio_device: Some(device.clone()),
+            ..PersistentSettings::load()
+        };
+        self.show_toast(t!("output-changed", device = device.as_str()));
+        settings.save()
+    }
+
+    pub fn close_music_dir_picker(&mut self) {
+        self.state.dir_picker = None;
+        self.state.input_mode = InputMode::Normal;
+    }
+
+    /// Stop the running scan, keeping the songs it has read
+    pub fn cancel_scan(&mut self) {
+        if let Some(job) = &self.scan {
+            job.cancel();
+            self.state.scan_cancelling = true;
+        }
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// Update the scan progress, showing the new songs once the scan is done
+    pub fn poll_scan(&mut self) {
+        let Some(job) = self.scan.as_mut() else {
+            return;
+        };
+        let Some(outcome) = job.poll() else {
+            self.state.scan_progress = Some(job.progress());
+            return;
+        };
+
+        self.scan = None;
+        self.state.scan_progress = None;
+        self.state.scan_cancelling = false;
+
+        // The setup wizard shows how its scan went itself
+        if let (Some(setup), Ok(outcome)) = (self.state.setup.as_mut(), &outcome) {
+            setup.outcome = Some(outcome.clone());
+            self.load_songs();
+            return;
+        }
+
+        match outcome {
+            Ok(outcome) if outcome.offline => self.show_toast(t!("scan-library-offline")),
+            Ok(outcome) => {
+                // Files left out get a popup with the reasons, which would not
+                // fit in a toast, unless the user is busy typing
+                if outcome.has_issues() && self.state.input_mode == InputMode::Normal {
+                    self.state.scan_report = Some(outcome);
+                    self.state.input_mode = InputMode::ScanReport;
+                } else if outcome.cancelled {
+                    self.show_toast(t!("scan-cancelled", count = outcome.added));
+                } else if outcome.resumed > 0 {
+                    self.show_toast(t!("scan-finished-resumed", count = outcome.added, resumed = outcome.resumed));
+                } else {
+                    self.show_toast(t!("scan-finished", count = outcome.added));
+                }
+                let selected = self.get_selected_song().map(|song| song.path.clone());
+                self.load_songs_then(selected.map_or(AfterLoad::Show, AfterLoad::Select));
+            }
+            Err(e) => {
+                log::warn!("Library scan failed: {}", e);
+                self.show_toast(t!("scan-failed", error = e.to_string()));
+            }
+        }
+    }
+
+    fn show_playlists(&mut self, playlists: Vec<Playlist>) {
+        self.state.playlists = playlists;
+        self.state.playlist_sort.sort(&mut self.state.playlists);
+        self.refresh_playing_song_playlists();
         
         // Ensure selected index is valid after loading
-        if self.state.selected_playlist_index >= self.state.playlists.len() && !self.state.playlists.is_empty() {
-            self.state.selected_playlist_index = self.state.playlists.len() - 1;
-        } else if self.state.playlists.is_empty() {
-            self.state.selected_playlist_index = 0;
+        let entry_count = self.playlist_entry_count();
+        if self.state.selected_playlist_index >= entry_count {
+            self.state.selected_playlist_index = entry_count - 1;
         }
-        
-        Ok(())
+    }
+
+    /// Sort the Playlists panel by the next order, keeping the selection
+    pub fn cycle_playlist_sort(&mut self) -> Result<()> {
+        let selected = self.selected_playlist().map(|playlist| playlist.id.clone());
+        let sort = self.state.playlist_sort.next();
+        self.state.playlist_sort = sort;
+        sort.sort(&mut self.state.playlists);
+
+        if let Some(index) = selected.and_then(|id| self.state.playlists.iter().position(|p| p.id == id)) {
+            self.state.selected_playlist_index = SmartView::ALL.len() + index;
+        }
+        self.show_toast(t!("playlist-sorted", order = playlist_sort_label(sort)));
+
+        self.persistent_settings.playlist_sort = sort;
+        let settings = PersistentSettings {
+            playlist_sort: sort,
+            ..PersistentSettings::load()
+        };
+        settings.save()
+    }
+
+    /// Number of selectable entries in the Playlists panel, built-in views first
+    pub fn playlist_entry_count(&self) -> usize {
+        SmartView::ALL.len() + self.state.playlists.len()
+    }
+
+    /// Built-in view under the playlist selection, if any
+    pub fn selected_smart_view(&self) -> Option<SmartView> {
+        SmartView::ALL.get(self.state.selected_playlist_index).copied()
+    }
+
+    /// User playlist under the playlist selection, if any
+    pub fn selected_playlist(&self) -> Option<&Playlist> {
+        self.state.selected_playlist_index
+            .checked_sub(SmartView::ALL.len())
+            .and_then(|index| self.state.playlists.get(index))
     }
 
     pub fn update_filtered_songs(&mut self) {
+        self.state.unshuffled_queue = None;
+        // The library is searched in the database, as it is not held whole.
+        // A window still opening is reopened for the new search, without
+        // dropping what was to happen once it opened.
+        if self.state.view_mode == ViewMode::Library {
+            let then = self.window_load.take().map_or(AfterLoad::Show, |load| load.then);
+            self.open_library_window(then);
+            return;
+        }
+
+        // Only indices are kept, so no song is cloned however large the library
         if self.state.search_query.is_empty() {
-            // Optimization: Avoid cloning - just reference all songs
-            self.state.filtered_songs = self.state.songs.clone();
+            self.state.filtered_indices = (0..self.state.songs.len()).collect();
         } else {
             // Optimization: Pre-lowercase query once to avoid repeated allocations
             let query_lower = self.state.search_query.to_lowercase();
             
-            self.state.filtered_songs = self.state.songs
+            self.state.filtered_indices = self.state.songs
                 .iter()
-                .filter(|song| song.matches(&query_lower))
-                .cloned()
+                .enumerate()
+                .filter(|(_, song)| song.matches(&query_lower))
+                .map(|(i, _)| i)
                 .collect();
         }
 
-        // Reset selection if it's out of bounds
-        if self.state.selected_song_index >= self.state.filtered_songs.len() {
-            self.state.selected_song_index = 0;
-        }
+        self.clamp_selection();
     }
 
     // Panel navigation methods
@@ -219,21 +1197,20 @@ impl App {
     pub fn move_selection_up(&mut self) {
         match self.state.active_panel {
             ActivePanel::Songs => {
-                if !self.state.filtered_songs.is_empty() {
+                self.state.navigated_at = Some(Instant::now());
+                if self.state.filtered_len() > 0 {
                     if self.state.selected_song_index > 0 {
                         self.state.selected_song_index -= 1;
                     } else {
-                        self.state.selected_song_index = self.state.filtered_songs.len() - 1;
+                        self.state.selected_song_index = self.state.filtered_len() - 1;
                     }
                 }
             }
             ActivePanel::Playlists => {
-                if !self.state.playlists.is_empty() {
-                    if self.state.selected_playlist_index > 0 {
-                        self.state.selected_playlist_index -= 1;
-                    } else {
-                        self.state.selected_playlist_index = self.state.playlists.len() - 1;
-                    }
+                if self.state.selected_playlist_index > 0 {
+                    self.state.selected_playlist_index -= 1;
+                } else {
+                    self.state.selected_playlist_index = self.playlist_entry_count() - 1;
                 }
             }
             ActivePanel::AlbumArt => {
@@ -242,27 +1219,66 @@ impl App {
         }
     }
 
-    pub fn move_selection_down(&mut self) {
+    /// Select the first entry of the focused list
+    pub fn move_selection_to_top(&mut self) {
+        self.move_selection_to(0);
+    }
+
+    /// Select the last entry of the focused list
+    pub fn move_selection_to_bottom(&mut self) {
+        self.move_selection_to(usize::MAX);
+    }
+
+    /// Select an entry of the focused list by index, or the last one if the
+    /// list is shorter
+    pub fn move_selection_to(&mut self, index: usize) {
         match self.state.active_panel {
             ActivePanel::Songs => {
-                if !self.state.filtered_songs.is_empty() {
-                    if self.state.selected_song_index < self.state.filtered_songs.len() - 1 {
-                        self.state.selected_song_index += 1;
-                    } else {
-                        self.state.selected_song_index = 0;
-                    }
-                }
+                self.state.navigated_at = Some(Instant::now());
+                self.state.selected_song_index = index.min(self.state.filtered_len().saturating_sub(1));
             }
             ActivePanel::Playlists => {
-                if !self.state.playlists.is_empty() {
-                    if self.state.selected_playlist_index < self.state.playlists.len() - 1 {
-                        self.state.selected_playlist_index += 1;
-                    } else {
-                        self.state.selected_playlist_index = 0;
-                    }
-                }
+                self.state.selected_playlist_index = index.min(self.playlist_entry_count() - 1);
             }
-            ActivePanel::AlbumArt => {
+            ActivePanel::AlbumArt => {}
+        }
+    }
+
+    /// Move the selection by `offset` entries, stopping at either end
+    pub fn move_selection_by(&mut self, offset: isize) {
+        let current = match self.state.active_panel {
+            ActivePanel::Songs => self.state.selected_song_index,
+            ActivePanel::Playlists => self.state.selected_playlist_index,
+            ActivePanel::AlbumArt => return,
+        };
+        self.move_selection_to(current.saturating_add_signed(offset));
+    }
+
+    /// How long the keys of a chord may be apart
+    pub fn chord_timeout(&self) -> Duration {
+        Duration::from_millis(self.layout_config.settings.chord_timeout_ms)
+    }
+
+    pub fn move_selection_down(&mut self) {
+        match self.state.active_panel {
+            ActivePanel::Songs => {
+                self.state.navigated_at = Some(Instant::now());
+                if self.state.filtered_len() > 0 {
+                    if self.state.selected_song_index < self.state.filtered_len() - 1 {
+                        self.state.selected_song_index += 1;
+                    } else {
+                        self.state.selected_song_index = 0;
+                    }
+                }
+            }
+            ActivePanel::Playlists => {
+                if self.state.selected_playlist_index < self.playlist_entry_count() - 1 {
+                    self.state.selected_playlist_index += 1;
+                } else {
+                    self.state.selected_playlist_index = 0;
+                }
+            }
+            ActivePanel::AlbumArt => {
                 // Album art panel doesn't have selectable items
             }
         }
@@ -271,14 +1287,17 @@ impl App {
     pub fn play_selected_song(&mut self) -> Result<()> {
         match self.state.active_panel {
             ActivePanel::Songs => {
-                if let Some(song) = self.state.filtered_songs.get(self.state.selected_song_index).cloned() {
-                    self.audio_player.send_command(PlayerCommand::Play(song.path.clone()))?;
+                if let Some(song) = self.state.filtered_song(self.state.selected_song_index).cloned() {
+                    let trim = self.trim_of(&song.path);
+                    self.audio_player.send_command(PlayerCommand::Play(song.path.clone(), trim))?;
                     self.update_album_art(&song)?;
                 }
             }
             ActivePanel::Playlists => {
-                if let Some(playlist) = self.state.playlists.get(self.state.selected_playlist_index).cloned() {
-                    self.switch_to_playlist(&playlist.name)?;
+                if let Some(view) = self.selected_smart_view() {
+                    self.switch_to_smart_view(view);
+                } else if let Some(playlist) = self.selected_playlist().cloned() {
+                    self.switch_to_playlist(&playlist.name);
                 }
             }
             ActivePanel::AlbumArt => {
@@ -288,80 +1307,1306 @@ impl App {
         Ok(())
     }
 
-    pub fn toggle_playback(&mut self) -> Result<()> {
-        match self.state.playback_status.state {
-            PlayerState::Playing => {
-                self.audio_player.send_command(PlayerCommand::Pause)?;
-            }
-            PlayerState::Paused => {
-                self.audio_player.send_command(PlayerCommand::Resume)?;
+    pub fn toggle_playback(&mut self) -> Result<()> {
+        match self.state.playback_status.state {
+            PlayerState::Playing => {
+                self.audio_player.send_command(PlayerCommand::Pause)?;
+            }
+            PlayerState::Paused => {
+                self.audio_player.send_command(PlayerCommand::Resume)?;
+            }
+            PlayerState::Stopped => {
+                // If stopped, play the selected song
+                self.play_selected_song()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn stop_playback(&mut self) -> Result<()> {
+        self.audio_player.send_command(PlayerCommand::Stop)?;
+        Ok(())
+    }
+
+    /// Arm or disarm stopping once the playing song ends
+    pub fn toggle_stop_after_current(&mut self) {
+        if self.state.playback_status.current_song.is_none() {
+            self.show_toast(t!("nothing-playing"));
+            return;
+        }
+        self.state.stop_after_current = !self.state.stop_after_current;
+        self.show_toast(t!(if self.state.stop_after_current { "stop-after-on" } else { "stop-after-off" }));
+    }
+
+    /// The song the stop was armed for has ended: hold the queue where it is
+    fn finish_stop_after_current(&mut self) {
+        self.state.stop_after_current = false;
+        self.state.hold_playback = true;
+        self.show_toast(t!("stop-after-done"));
+    }
+
+    /// Handle media keys pressed since the last call
+    pub fn handle_media_keys(&mut self) -> Result<()> {
+        let keys: Vec<MediaKey> = match &self.media_controls {
+            Some(media_controls) => media_controls.pending().collect(),
+            None => return Ok(()),
+        };
+
+        for key in keys {
+            self.handle_media_key(key)?;
+        }
+        Ok(())
+    }
+
+    /// React to a keyboard media key, mirroring the matching keybindings
+    fn handle_media_key(&mut self, key: MediaKey) -> Result<()> {
+        let state = &self.state.playback_status.state;
+        match key {
+            MediaKey::PlayPause => self.toggle_playback()?,
+            MediaKey::Play if *state != PlayerState::Playing => self.toggle_playback()?,
+            MediaKey::Pause if *state == PlayerState::Playing => self.toggle_playback()?,
+            MediaKey::Play | MediaKey::Pause => {}
+            MediaKey::Stop => self.stop_playback()?,
+            MediaKey::Previous => self.go_to_previous_song()?,
+            MediaKey::Next => self.advance_to_next_song()?,
+        }
+        Ok(())
+    }
+
+    pub fn enter_search_mode(&mut self) {
+        self.state.input_mode = InputMode::Search;
+        self.state.search_textarea.move_cursor(tui_textarea::CursorMove::End);
+    }
+
+    pub fn exit_search_mode(&mut self) {
+        self.state.input_mode = InputMode::Normal;
+    }
+
+    pub fn update_search_query(&mut self) {
+        let new_query = self.state.search_textarea.lines().join("");
+        if new_query != self.state.search_query {
+            self.state.search_query = new_query;
+            self.update_filtered_songs();
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.state.search_textarea = TextArea::default();
+        self.state.search_textarea.set_placeholder_text("Search songs...");
+        self.state.search_query.clear();
+        self.update_filtered_songs();
+    }
+
+    pub fn update_playback_status(&mut self) {
+        while let Some(error) = self.audio_player.take_error() {
+            self.report_player_error(error);
+        }
+        if self.switching_output {
+            if self.audio_player.get_status().current_song.is_none() {
+                return;
+            }
+            self.switching_output = false;
+        }
+        let previous = std::mem::replace(&mut self.state.playback_status, self.audio_player.get_status());
+        self.state.last_update = Instant::now();
+        let song_changed = previous.current_song != self.state.playback_status.current_song;
+        if song_changed {
+            // Scrolling the library window away may let go of it later
+            self.state.playing_song = self.get_current_song().cloned();
+        }
+        if song_changed && self.state.playback_status.current_song.is_some() {
+            self.state.hold_playback = false;
+        }
+        if self.state.playback_status.gapless && song_changed {
+            if self.state.stop_after_current {
+                // The next song was lined up before the stop was asked for
+                self.finish_stop_after_current();
+                let _ = self.audio_player.send_command(PlayerCommand::Stop);
+            } else {
+                self.follow_gapless_advance();
+            }
+        }
+        self.notify_playback_changes(&previous);
+        if previous.current_song != self.state.playback_status.current_song {
+            self.refresh_playing_song_playlists();
+            self.refresh_playing_thumbnail();
+            self.save_queue();
+            self.state.chapters = match &self.state.playback_status.current_song {
+                Some(path) => chapters::read(Path::new(path)),
+                None => Vec::new(),
+            };
+        }
+        
+        // Update album art if song changed
+        if let Some(current_song) = self.get_current_song().cloned() {
+            if self.state.show_album_art {
+                let _ = self.update_album_art(&current_song);
+            }
+        }
+
+        let _ = self.queue_next_song();
+        self.follow_playing_song();
+    }
+
+    /// Show what the audio thread failed at
+    fn report_player_error(&mut self, error: PlayerError) {
+        match error {
+            PlayerError::Load { path, error } => {
+                // The song is not coming back on the new output either
+                self.switching_output = false;
+                let name = Path::new(&path).file_name().map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+                self.show_toast(t!("play-failed", song = name.as_str(), error = error.as_str()));
+            }
+            PlayerError::Stopped(error) => self.show_toast(t!("audio-stopped", error = error.as_str())),
+        }
+    }
+
+    /// Whether the song list is following playback right now, i.e. the
+    /// mode is on and the list has not just been moved by hand
+    pub fn is_following(&self) -> bool {
+        self.state.follow_playback
+            && self.state.navigated_at.is_none_or(|at| at.elapsed() >= FOLLOW_RESUME)
+    }
+
+    /// Select the playing song when following playback
+    fn follow_playing_song(&mut self) {
+        if !self.is_following() {
+            return;
+        }
+        if let Some(index) = self.playing_queue_index() {
+            self.state.selected_song_index = index;
+        }
+    }
+
+    /// Hold track change notifications back, or let them through again
+    pub fn toggle_do_not_disturb(&mut self) -> Result<()> {
+        let Some(notifier) = &self.notifier else {
+            self.show_toast(t!("notifications-disabled"));
+            return Ok(());
+        };
+        let quiet = !self.persistent_settings.do_not_disturb;
+        notifier.set_do_not_disturb(quiet);
+        self.show_toast(t!(if quiet { "do-not-disturb-on" } else { "do-not-disturb-off" }));
+
+        self.persistent_settings.do_not_disturb = quiet;
+        let settings = PersistentSettings {
+            do_not_disturb: quiet,
+            ..PersistentSettings::load()
+        };
+        settings.save()
+    }
+
+    /// Switch the position label between the song's length and the time
+    /// left in it
+    pub fn toggle_remaining_time(&mut self) -> Result<()> {
+        let remaining = !self.persistent_settings.show_remaining;
+        self.persistent_settings.show_remaining = remaining;
+        let settings = PersistentSettings {
+            show_remaining: remaining,
+            ..PersistentSettings::load()
+        };
+        settings.save()
+    }
+
+    /// Turn following playback in the song list on or off
+    pub fn toggle_follow_playback(&mut self) -> Result<()> {
+        let follow = !self.state.follow_playback;
+        self.state.follow_playback = follow;
+        // Jump to the playing song right away rather than after the pause
+        self.state.navigated_at = None;
+        self.follow_playing_song();
+        self.show_toast(t!(if follow { "follow-on" } else { "follow-off" }));
+
+        self.persistent_settings.follow_playback = follow;
+        let settings = PersistentSettings {
+            follow_playback: follow,
+            ..PersistentSettings::load()
+        };
+        settings.save()
+    }
+
+    /// The songs playback moves through, with the one playing
+    pub fn queue(&self) -> PlayQueue {
+        let playing = self.state.playback_status.current_song.as_deref();
+        if matches!(self.state.view_mode, ViewMode::Library) {
+            // The library does not play on, so only the playing song is queued
+            let songs: Vec<Song> = self.get_current_song().cloned().into_iter().collect();
+            let current = (!songs.is_empty()).then_some(0);
+            return PlayQueue { songs, current };
+        }
+
+        let songs: Vec<Song> = self.state.filtered_songs().cloned().collect();
+        let current = playing.and_then(|path| songs.iter().position(|song| song.path == path));
+        PlayQueue { songs, current }
+    }
+
+    /// Write the queue where commands run alongside the player find it
+    fn save_queue(&self) {
+        if let Err(e) = self.queue().save() {
+            log::warn!("Failed to save the queue: {}", e);
+        }
+    }
+
+    /// Append the selected playlist, or the album of the selected song, to
+    /// the end of the queue, leaving the view and the playing song alone
+    pub fn append_selection_to_queue(&mut self) -> Result<()> {
+        if matches!(self.state.view_mode, ViewMode::Library) {
+            self.show_toast(t!("queue-library"));
+            return Ok(());
+        }
+
+        let (songs, name) = match self.state.active_panel {
+            ActivePanel::Playlists => {
+                let Some(playlist) = self.selected_playlist().cloned() else {
+                    return Ok(());
+                };
+                (self.library.get_playlist_songs(&playlist.id)?, playlist.name)
+            }
+            _ => {
+                let Some(song) = self.get_selected_song().cloned() else {
+                    return Ok(());
+                };
+                (self.library.get_album(&song.artist, &song.album)?, song.album)
+            }
+        };
+
+        self.show_toast(t!("queue-appended", count = songs.len(), name = name.as_str()));
+        self.append_to_queue(songs);
+        self.save_queue();
+        Ok(())
+    }
+
+    /// Append the files, folders and stream URLs on the clipboard to the
+    /// end of the queue
+    ///
+    /// Streams are queued right away. Files and folders are read in the
+    /// background and queued by `poll_pasted_reads` when they are done, so
+    /// many files or a slow folder do not hold up the interface.
+    pub fn enqueue_clipboard(&mut self) -> Result<()> {
+        if matches!(self.state.view_mode, ViewMode::Library) {
+            self.show_toast(t!("queue-library"));
+            return Ok(());
+        }
+        let text = match self.clipboard.get_text() {
+            Ok(text) => text,
+            Err(e) => {
+                log::warn!("Failed to read the clipboard: {}", e);
+                self.show_toast(t!("clipboard-unavailable"));
+                return Ok(());
+            }
+        };
+
+        let mut songs = Vec::new();
+        let mut names = Vec::new();
+        let mut files = Vec::new();
+        let mut reads = 0;
+        let mut failed = 0;
+        for pasted in desktop::parse_pasted(&text) {
+            match pasted {
+                Pasted::Stream(url) => {
+                    names.push(url.clone());
+                    songs.push(Song::new(url.clone(), url, String::new(), String::new(), 0));
+                }
+                Pasted::Path(path) if path.is_dir() => {
+                    let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+                    match ScanJob::read_folder(path.clone(), self.scan_settings.options.clone()) {
+                        Ok(job) => {
+                            self.pasted_reads.push((PastedRead::Folder(name), job));
+                            reads += 1;
+                        }
+                        Err(e) => {
+                            log::warn!("Not queueing {}: {}", path.display(), e);
+                            failed += 1;
+                        }
+                    }
+                }
+                Pasted::Path(path) if path.is_file() => files.push(path),
+                Pasted::Path(path) => {
+                    log::warn!("Not queueing {}: no such file or folder", path.display());
+                    failed += 1;
+                }
+            }
+        }
+
+        if !files.is_empty() {
+            let count = files.len();
+            match ScanJob::read_files(files.clone(), self.scan_settings.options.clone()) {
+                Ok(job) => {
+                    self.pasted_reads.push((PastedRead::Files(files), job));
+                    reads += count;
+                }
+                Err(e) => {
+                    log::warn!("Not queueing the pasted files: {}", e);
+                    failed += count;
+                }
+            }
+        }
+
+        if failed > 0 {
+            self.show_toast(t!("clipboard-unreadable", count = failed));
+        } else if songs.is_empty() && reads == 0 {
+            self.show_toast(t!("clipboard-nothing"));
+        } else if songs.is_empty() {
+            self.show_toast(t!("clipboard-reading", count = reads));
+        }
+        if songs.is_empty() {
+            return Ok(());
+        }
+        if failed == 0 {
+            match names.as_slice() {
+                [name] => self.show_toast(t!("queue-appended", count = songs.len(), name = name.as_str())),
+                _ => self.show_toast(t!("clipboard-queued", count = songs.len())),
+            }
+        }
+        self.append_to_queue(songs);
+        self.save_queue();
+        Ok(())
+    }
+
+    /// Queue the songs of pasted folders and files that have been read
+    pub fn poll_pasted_reads(&mut self) {
+        let mut index = 0;
+        while index < self.pasted_reads.len() {
+            let Some(result) = self.pasted_reads[index].1.poll() else {
+                index += 1;
+                continue;
+            };
+            let (read, _) = self.pasted_reads.remove(index);
+            let name = match read {
+                PastedRead::Folder(name) => name,
+                PastedRead::Files(files) => {
+                    self.queue_pasted_files(&files, result);
+                    continue;
+                }
+            };
+            match result {
+                Ok(report) if !report.offline_roots.is_empty() => {
+                    log::warn!("Not queueing folder {}: it could not be read", name);
+                    self.show_toast(t!("clipboard-folder-failed", name = name.as_str()));
+                }
+                Ok(mut report) if !report.songs.is_empty() => {
+                    // The queue may have been left for the library meanwhile
+                    if matches!(self.state.view_mode, ViewMode::Library) {
+                        self.show_toast(t!("queue-library"));
+                        continue;
+                    }
+                    report.songs.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+                    self.show_toast(t!("queue-appended", count = report.songs.len(), name = name.as_str()));
+                    self.append_to_queue(report.songs);
+                    self.save_queue();
+                }
+                Ok(_) => self.show_toast(t!("clipboard-folder-empty", name = name.as_str())),
+                Err(e) => {
+                    log::warn!("Not queueing folder {}: {}", name, e);
+                    self.show_toast(t!("clipboard-folder-failed", name = name.as_str()));
+                }
+            }
+        }
+    }
+
+    /// Queue the songs read from pasted files, in the order they were pasted
+    fn queue_pasted_files(&mut self, files: &[PathBuf], result: Result<ScanReport>) {
+        let mut report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                log::warn!("Not queueing the pasted files: {}", e);
+                self.show_toast(t!("clipboard-unreadable", count = files.len()));
+                return;
+            }
+        };
+        if !report.failed.is_empty() {
+            self.show_toast(t!("clipboard-unreadable", count = report.failed.len()));
+        }
+        if report.songs.is_empty() {
+            return;
+        }
+        // The queue may have been left for the library meanwhile
+        if matches!(self.state.view_mode, ViewMode::Library) {
+            self.show_toast(t!("queue-library"));
+            return;
+        }
+
+        let pasted_at = |song: &Song| files.iter().position(|file| Path::new(&song.path) == file);
+        report.songs.sort_by_key(pasted_at);
+        if report.failed.is_empty() {
+            match report.songs.as_slice() {
+                [song] => self.show_toast(t!("queue-appended", count = 1, name = song.display_name())),
+                songs => self.show_toast(t!("clipboard-queued", count = songs.len())),
+            }
+        }
+        self.append_to_queue(report.songs);
+        self.save_queue();
+    }
+
+    /// Shuffle the songs of the queue after the playing one, once, or put
+    /// them back in the order they were in before
+    ///
+    /// Unlike shuffle mode this reorders the list itself, so the shuffled
+    /// order shows and plays on as any other.
+    pub fn toggle_queue_shuffle(&mut self) {
+        let playing = self.state.playback_status.current_song.clone();
+        let selected = self.get_selected_song().map(|song| song.path.clone());
+        let playing_index = playing.as_deref().and_then(|path| self.state.filtered_index_of(path));
+
+        match self.state.unshuffled_queue.take() {
+            Some(original) => {
+                self.state.filtered_indices = queue::restore_order(&self.state.filtered_indices, &original);
+                self.show_toast(t!("queue-unshuffled"));
+            }
+            None => {
+                self.state.unshuffled_queue = Some(self.state.filtered_indices.clone());
+                let from = playing_index.map_or(0, |index| index + 1);
+                queue::shuffle_after(&mut self.state.filtered_indices, from);
+                self.show_toast(t!("queue-shuffled"));
+            }
+        }
+
+        // Keep the playing and selected songs, which may have moved
+        if let Some(index) = playing.as_deref().and_then(|path| self.state.filtered_index_of(path)) {
+            self.state.playback_state.current_song_index = index;
+        }
+        if let Some(index) = selected.as_deref().and_then(|path| self.state.filtered_index_of(path)) {
+            self.state.selected_song_index = index;
+        }
+        // Shuffle mode holds positions in the list, which have changed
+        if self.state.playback_state.shuffle {
+            self.reshuffle();
+        }
+        self.save_queue();
+    }
+
+    /// Take the selected song out of the queue, leaving its playlist alone
+    pub fn remove_selected_from_queue(&mut self) {
+        let selected = self.state.selected_song_index;
+        self.remove_from_queue(|position| position == selected);
+    }
+
+    /// Take the songs above the selected one out of the queue
+    pub fn remove_queue_above(&mut self) {
+        let selected = self.state.selected_song_index;
+        self.remove_from_queue(|position| position < selected);
+    }
+
+    /// Take the songs below the selected one out of the queue
+    pub fn remove_queue_below(&mut self) {
+        let selected = self.state.selected_song_index;
+        self.remove_from_queue(|position| position > selected);
+    }
+
+    /// Take every song after the playing one out of the queue
+    pub fn clear_queue_after_current(&mut self) {
+        let Some(playing) = self.playing_queue_index() else {
+            self.show_toast(t!("nothing-playing"));
+            return;
+        };
+        self.remove_from_queue(|position| position > playing);
+    }
+
+    /// Position in the queue of the playing song
+    fn playing_queue_index(&self) -> Option<usize> {
+        let path = self.state.playback_status.current_song.as_deref()?;
+        self.state.filtered_index_of(path)
+    }
+
+    /// Take the songs at the positions `remove` picks out of the queue; the
+    /// playing song stays, so playback goes on from where it is
+    fn remove_from_queue(&mut self, remove: impl Fn(usize) -> bool) {
+        if matches!(self.state.view_mode, ViewMode::Library) {
+            self.show_toast(t!("queue-library"));
+            return;
+        }
+
+        let playing = self.playing_queue_index();
+        let selected = self.get_selected_song().map(|song| song.path.clone());
+        let before = self.state.filtered_len();
+        let mut position = 0;
+        self.state.filtered_indices.retain(|_| {
+            let keep = Some(position) == playing || !remove(position);
+            position += 1;
+            keep
+        });
+
+        let removed = before - self.state.filtered_len();
+        if removed == 0 {
+            if playing.is_some_and(&remove) {
+                self.show_toast(t!("queue-playing-stays"));
+            }
+            return;
+        }
+
+        if let Some(original) = &mut self.state.unshuffled_queue {
+            let kept: HashSet<usize> = self.state.filtered_indices.iter().copied().collect();
+            original.retain(|song| kept.contains(song));
+        }
+        let playlist_size = self.state.filtered_len();
+        if let Some(index) = self.playing_queue_index() {
+            self.state.playback_state.current_song_index = index;
+        }
+        // The selection stays on its song, or where the removed song was
+        self.state.selected_song_index = selected
+            .as_deref()
+            .and_then(|path| self.state.filtered_index_of(path))
+            .unwrap_or(self.state.selected_song_index)
+            .min(playlist_size.saturating_sub(1));
+        if self.state.playback_state.shuffle {
+            self.reshuffle();
+        }
+
+        self.show_toast(t!("queue-removed", count = removed));
+        self.save_queue();
+    }
+
+    /// Add songs to the end of the queue, and to the shuffle if it is on
+    fn append_to_queue(&mut self, songs: Vec<Song>) {
+        let (queued, start) = (self.state.filtered_len(), self.state.songs.len());
+        self.state.filtered_indices.extend(start..start + songs.len());
+        self.state.songs.extend(songs);
+        self.state.playback_state.extend_shuffle(queued..self.state.filtered_len());
+        if self.state.playback_state.smart_shuffle {
+            self.state.playback_state.shuffle_tracks = self.shuffle_tracks();
+        }
+    }
+
+    /// Draw a new shuffle order for the list as it is now
+    fn reshuffle(&mut self) {
+        self.state.playback_state.shuffle_tracks =
+            if self.state.playback_state.smart_shuffle { self.shuffle_tracks() } else { Vec::new() };
+        self.state.playback_state.enable_shuffle(self.state.filtered_len());
+    }
+
+    /// What smart shuffle needs to know of each song in the list: its
+    /// artist and, from the listening history, when it was last played
+    fn shuffle_tracks(&self) -> Vec<ShuffleTrack> {
+        let now = chrono::Utc::now();
+        let since = now - chrono::Duration::days(smart_shuffle::RECOVERY_DAYS as i64);
+        let mut last_played = HashMap::new();
+        match self.library.get_listens(since, now) {
+            // Oldest first, so the latest listen of each song wins
+            Ok(listens) => last_played.extend(
+                listens.iter().map(|listen| (listen.track_path().to_string(), listen.listened_at())),
+            ),
+            Err(e) => log::warn!("Failed to load the listening history for smart shuffle: {}", e),
+        }
+
+        self.state
+            .filtered_songs()
+            .map(|song| ShuffleTrack {
+                artist: song.artist.clone(),
+                days_since_played: last_played
+                    .get(&song.path)
+                    .map(|played| (now - *played).num_seconds() as f64 / 86_400.0),
+            })
+            .collect()
+    }
+
+    /// Save the queue as a new playlist, in queue order
+    pub fn save_queue_as_playlist(&mut self, name: String) -> Result<()> {
+        let song_ids = self.queue().song_ids();
+        if song_ids.is_empty() {
+            self.show_toast(t!("queue-empty"));
+            return Ok(());
+        }
+        if self.library.get_playlist_by_name(&name)?.is_some() {
+            self.show_toast(t!("playlist-exists", name = name.as_str()));
+            return Ok(());
+        }
+
+        // Streams and files queued from the clipboard have no library entry
+        // a playlist could point to
+        let in_library: HashSet<String> = self.library.get_songs(&song_ids)?.into_iter().map(|song| song.id).collect();
+        let song_ids: Vec<String> = song_ids.into_iter().filter(|id| in_library.contains(id)).collect();
+
+        let playlist_id = self.library.create_playlist(&name, None)?;
+        let song_ids = self.library.add_songs_to_playlist(&playlist_id, &song_ids)?;
+        self.show_toast(t!("queue-saved", name = name.as_str(), count = song_ids.len()));
+        self.edits.record(PlaylistEdit::SaveQueue { name, song_ids });
+        self.load_playlists();
+        Ok(())
+    }
+
+    /// Chapter starts as fractions of the playing song, for the progress bar
+    pub fn chapter_marks(&self) -> Vec<f64> {
+        let total = self.state.playback_status.total_duration;
+        if total == 0 {
+            return Vec::new();
+        }
+        self.state.chapters.iter().map(|chapter| chapter.start.as_secs_f64() / total as f64).collect()
+    }
+
+    /// Jump to the start of the next chapter of the playing song
+    pub fn next_chapter(&mut self) -> Result<()> {
+        let position = self.state.playback_status.current_position;
+        let next = chapters::current(&self.state.chapters, position).map_or(0, |index| index + 1);
+        match self.state.chapters.get(next) {
+            Some(chapter) => self.seek(chapter.start),
+            None if self.state.chapters.is_empty() => {
+                self.show_toast(t!("no-chapters"));
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Jump back to the start of the chapter playing, or to the one before
+    /// it if the chapter has only just started
+    pub fn previous_chapter(&mut self) -> Result<()> {
+        let position = self.state.playback_status.current_position;
+        let Some(index) = chapters::current(&self.state.chapters, position) else {
+            if self.state.chapters.is_empty() {
+                self.show_toast(t!("no-chapters"));
+            }
+            return Ok(());
+        };
+        let start = self.state.chapters[index].start;
+        let target = if position.saturating_sub(start.as_secs()) < CHAPTER_RESTART_SECS && index > 0 {
+            self.state.chapters[index - 1].start
+        } else {
+            start
+        };
+        self.seek(target)
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<()> {
+        if self.state.playback_status.current_song.is_none() {
+            return Ok(());
+        }
+        self.audio_player.send_command(PlayerCommand::Seek(position))?;
+        // Shown at once rather than on the next status update
+        self.state.playback_status.current_position = position.as_secs();
+        Ok(())
+    }
+
+    /// Spectrum of what is playing now, for the visualizer
+    pub fn spectrum(&mut self) -> Vec<f32> {
+        let params = self.layout_config.settings.visualizer.params();
+        let (samples, sample_rate) = self.audio_player.recent_samples(params.fft_size);
+        self.analyzer.update(&samples, sample_rate, &params).to_vec()
+    }
+
+    /// Waveform of what is playing now, for the oscilloscope
+    pub fn waveform(&self) -> Vec<Sample> {
+        self.audio_player.recent_waveform(oscilloscope::WINDOW)
+    }
+
+    /// What the VU meter shows now, silence unless playing
+    pub fn vu_readings(&mut self) -> [MeterReading; 2] {
+        let levels = if self.state.playback_status.state == PlayerState::Playing {
+            self.audio_player.recent_levels(meter::WINDOW)
+        } else {
+            self.vu_meter.reset();
+            Default::default()
+        };
+        self.vu_meter.update(levels, Instant::now())
+    }
+
+    /// Whether the screen moves by itself, so it needs redrawing more often
+    /// than the tick
+    pub fn is_animating(&self) -> bool {
+        !self.screen_reader
+            && self.state.playback_status.state == PlayerState::Playing
+            && self.layout_config.widgets.iter().any(|widget| {
+                widget.visible && matches!(widget.widget_type, WidgetType::AlbumArt | WidgetType::VuMeter)
+            })
+    }
+
+    /// Whether the layout shows the big album art panel
+    fn art_panel_shown(&self) -> bool {
+        self.layout_config.widgets.iter().any(|widget| widget.visible && widget.widget_type == WidgetType::AlbumArt)
+    }
+
+    /// Cover to draw next to the playing song in the control panel, when
+    /// no art panel shows it already
+    pub fn control_panel_thumbnail(&self) -> Option<&image::RgbImage> {
+        if !self.state.show_album_art || !self.unicode || self.art_panel_shown() {
+            return None;
+        }
+        self.state.playing_thumbnail.as_ref()
+    }
+
+    /// Shrink the cover of the playing song for the control panel, once
+    /// per song rather than on every frame
+    fn refresh_playing_thumbnail(&mut self) {
+        let path = self.state.playback_status.current_song.as_deref().filter(|path| !stream::is_stream(path));
+        self.state.playing_thumbnail = match path {
+            Some(path) if !self.screen_reader => self
+                .album_art_renderer
+                .thumbnail_from_file(path, thumbnail::WIDTH as u32, thumbnail::ROWS as u32 * 2)
+                .unwrap_or_else(|e| {
+                    log::debug!("No thumbnail for {}: {}", path, e);
+                    None
+                }),
+            _ => None,
+        };
+    }
+
+    /// Catch the queue up with the player, which moved on to the song queued
+    /// next without being told to
+    fn follow_gapless_advance(&mut self) {
+        let Some(path) = self.state.playback_status.current_song.clone() else {
+            return;
+        };
+
+        let playlist_size = self.state.filtered_len();
+        let lined_up = self.lined_up.take().filter(|lined_up| lined_up.is_for(&self.state));
+        let index = match lined_up {
+            Some(LinedUp { advanced, index: Some(index), .. })
+                if self.state.filtered_song(index).is_some_and(|song| song.path == path) =>
+            {
+                self.state.playback_state = advanced;
+                Some(index)
+            }
+            // The queue changed after the song was lined up
+            _ => self.state.filtered_index_of(&path),
+        };
+
+        if let Some(index) = index {
+            self.state.selected_song_index = index;
+            self.state.playback_state.set_current_song_index(index, playlist_size);
+        }
+    }
+
+    /// Tell the player which song follows the current one, so it can decode
+    /// it ahead and start it without a gap
+    fn queue_next_song(&mut self) -> Result<()> {
+        if self.state.playback_status.current_song.is_none() {
+            return Ok(());
+        }
+
+        let next = self.peek_next_song().map(|song| song.path.clone());
+        if next != self.state.playback_status.next_song {
+            let next = next.map(|path| {
+                let trim = self.trim_of(&path);
+                (path, trim)
+            });
+            self.audio_player.send_command(PlayerCommand::Queue(next))?;
+        }
+        Ok(())
+    }
+
+    /// How much is trimmed off the song at `path`; streams and songs whose
+    /// trim cannot be looked up play whole
+    fn trim_of(&self, path: &str) -> Trim {
+        if stream::is_stream(path) {
+            return Trim::default();
+        }
+        self.library.get_trim(path).unwrap_or_else(|e| {
+            log::warn!("Failed to look up the trim of {}: {}", path, e);
+            Trim::default()
+        })
+    }
+
+    /// Skip the start of the playing song up to where it is now, from the
+    /// next time it plays
+    pub fn trim_start_here(&mut self) {
+        let position = Duration::from_secs(self.state.playback_status.current_position);
+        self.update_playing_trim(|trim| trim.start = position);
+    }
+
+    /// Cut the end of the playing song off from where it is now, from the
+    /// next time it plays
+    pub fn trim_end_here(&mut self) {
+        let status = &self.state.playback_status;
+        // The song playing already stops short by the trim it started with
+        let length = Duration::from_secs(status.total_duration) + status.trim.end;
+        let position = Duration::from_secs(status.current_position);
+        self.update_playing_trim(|trim| trim.end = length.saturating_sub(position));
+    }
+
+    /// Play the playing song whole again from the next time it plays
+    pub fn clear_trim(&mut self) {
+        self.update_playing_trim(|trim| *trim = Trim::default());
+    }
+
+    fn update_playing_trim(&mut self, change: impl FnOnce(&mut Trim)) {
+        let Some(path) = self.state.playback_status.current_song.clone() else {
+            return;
+        };
+        if stream::is_stream(&path) {
+            self.show_toast(t!("trim-stream"));
+            return;
+        }
+
+        let mut trim = self.trim_of(&path);
+        change(&mut trim);
+        let message = match self.library.set_trim(&path, trim) {
+            Ok(()) if trim == Trim::default() => t!("trim-cleared"),
+            Ok(()) => t!(
+                "trim-saved",
+                start = value_objects::Duration::from_seconds(trim.start.as_secs()).format_h_mm_ss(),
+                end = value_objects::Duration::from_seconds(trim.end.as_secs()).format_h_mm_ss()
+            ),
+            Err(e) => t!("trim-failed", error = e.to_string()),
+        };
+        self.show_toast(message);
+    }
+
+    /// The song `advance_to_next_song` would play, without advancing
+    fn peek_next_song(&mut self) -> Option<&Song> {
+        // Songs only follow each other outside the library
+        if matches!(self.state.view_mode, ViewMode::Library) || self.state.stop_after_current {
+            return None;
+        }
+
+        if !self.lined_up.as_ref().is_some_and(|lined_up| lined_up.is_for(&self.state)) {
+            let playback_state = &self.state.playback_state;
+            let mut advanced = playback_state.clone();
+            let index = advanced.next_song_index(self.state.filtered_len());
+            self.lined_up = Some(LinedUp {
+                filtered_indices: self.state.filtered_indices.clone(),
+                current_song_index: playback_state.current_song_index,
+                shuffle: playback_state.shuffle,
+                shuffle_queue: playback_state.shuffle_queue.clone(),
+                repeat_mode: playback_state.repeat_mode,
+                advanced,
+                index,
+            });
+        }
+
+        let index = self.lined_up.as_ref()?.index?;
+        // Repeating one song restarts it as before
+        if index == self.state.playback_state.current_song_index {
+            return None;
+        }
+        self.state.filtered_song(index)
+    }
+
+    /// Tell plugins about track and play-state changes since the last status update
+    fn notify_playback_changes(&mut self, previous: &PlaybackStatus) {
+        let status = &self.state.playback_status;
+
+        let event = match (&status.current_song, &previous.current_song) {
+            (Some(path), previous_song) if previous_song.as_ref() != Some(path) => {
+                let track = self.get_current_song().map(TrackInfo::from).unwrap_or_else(|| TrackInfo {
+                    path: path.clone(),
+                    title: String::new(),
+                    artist: String::new(),
+                    album: String::new(),
+                    duration: status.total_duration,
+                });
+                Some(PlayerEvent::TrackChanged { track })
+            }
+            _ => match (&previous.state, &status.state) {
+                (PlayerState::Playing, PlayerState::Paused) => Some(PlayerEvent::PlaybackPaused),
+                (PlayerState::Paused, PlayerState::Playing) => Some(PlayerEvent::PlaybackResumed),
+                (PlayerState::Playing | PlayerState::Paused, PlayerState::Stopped) => Some(PlayerEvent::PlaybackStopped),
+                _ => None,
+            },
+        };
+
+        if let Some(event) = event {
+            match &event {
+                PlayerEvent::TrackChanged { track } => self.announce(t!(
+                    "announce-playing",
+                    title = track.title.as_str(),
+                    artist = track.artist.as_str()
+                )),
+                PlayerEvent::PlaybackPaused | PlayerEvent::PlaybackResumed | PlayerEvent::PlaybackStopped => {
+                    self.announce(player_state_label(&self.state.playback_status.state))
+                }
+                _ => {}
+            }
+            self.count_play(&event);
+            self.plugins.dispatch(&event);
+        }
+    }
+
+    /// Record a play of the track that just ended, if it played long enough
+    fn count_play(&mut self, event: &PlayerEvent) {
+        if let Some(listen) = self.plays.handle(event, Instant::now()) {
+            if let Err(e) = self.library.record_play(listen.track_path()) {
+                log::warn!("Failed to record play of {}: {}", listen.track_path(), e);
+            }
+        }
+    }
+
+    /// Tell a screen reader about a change the screen shows without words
+    fn announce(&mut self, message: String) {
+        if self.screen_reader {
+            self.show_toast(message);
+        }
+    }
+
+    /// Number of listens waiting to be scrobbled
+    pub fn pending_scrobbles(&self) -> usize {
+        self.scrobbler.as_ref().map_or(0, Scrobbler::pending)
+    }
+
+    /// Open the stats screen with a heatmap of the past year
+    pub fn open_stats(&mut self) {
+        let now = chrono::Local::now();
+        let today = now.date_naive();
+        let from = library_service::start_of_day(ListeningHeatmap::first_day(today));
+
+        match self.library.get_listens(from, now.to_utc()) {
+            Ok(listens) => {
+                self.state.heatmap = Some(ListeningHeatmap::from_listens(&listens, today, &chrono::Local));
+                self.state.input_mode = InputMode::Stats;
+            }
+            Err(e) => self.show_toast(t!("history-load-failed", error = e.to_string())),
+        }
+    }
+
+    /// Names of the playlists that contain the song stored at `path`
+    fn playlists_with_song(&self, path: &str) -> Result<Vec<String>> {
+        let playlists = self.library.get_playlists_with_song(path)?;
+        Ok(playlists.into_iter().map(|playlist| playlist.name).collect())
+    }
+
+    /// Look up again which playlists the playing song is in
+    fn refresh_playing_song_playlists(&mut self) {
+        let playlists = match &self.state.playback_status.current_song {
+            Some(path) => self.playlists_with_song(path).unwrap_or_else(|e| {
+                log::warn!("Failed to look up the playlists of {}: {}", path, e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        self.state.playing_song_playlists = playlists;
+    }
+
+    /// Open a popup with the playlists and bookmarks of the selected song,
+    /// or of the playing one when the song list is not focused
+    pub fn open_song_info(&mut self) {
+        let selected = match self.state.active_panel {
+            ActivePanel::Songs => self.get_selected_song(),
+            _ => None,
+        };
+        let Some(song) = selected.or_else(|| self.get_current_song()).cloned() else {
+            return;
+        };
+
+        let info = self.playlists_with_song(&song.path).and_then(|playlists| {
+            let bookmarks = self.library.get_bookmarks(&song.path)?;
+            Ok(SongInfo { song, playlists, bookmarks, selected: 0 })
+        });
+        match info {
+            Ok(info) => {
+                self.state.song_info = Some(info);
+                self.state.input_mode = InputMode::SongInfo;
+            }
+            Err(e) => self.show_toast(t!("song-info-failed", error = e.to_string())),
+        }
+    }
+
+    /// Close the song info popup
+    pub fn close_song_info(&mut self) {
+        self.state.song_info = None;
+        self.state.input_mode = InputMode::Normal;
+    }
+
+    /// Move the bookmark selection in the song info popup by `offset`
+    pub fn select_bookmark(&mut self, offset: isize) {
+        if let Some(info) = self.state.song_info.as_mut() {
+            let last = info.bookmarks.len().saturating_sub(1);
+            info.selected = info.selected.saturating_add_signed(offset).min(last);
+        }
+    }
+
+    /// Play the song in the song info popup from its selected bookmark
+    pub fn jump_to_bookmark(&mut self) -> Result<()> {
+        let Some(info) = self.state.song_info.take() else {
+            return Ok(());
+        };
+        self.state.input_mode = InputMode::Normal;
+        let Some(bookmark) = info.bookmarks.get(info.selected) else {
+            return Ok(());
+        };
+
+        let position = Duration::from_secs(bookmark.position().total_seconds());
+        if self.state.playback_status.current_song.as_deref() != Some(info.song.path.as_str()) {
+            // The player takes commands in order, so the seek lands in the
+            // song just started
+            let trim = self.trim_of(&info.song.path);
+            self.audio_player.send_command(PlayerCommand::Play(info.song.path.clone(), trim))?;
+            self.update_album_art(&info.song)?;
+        }
+        self.audio_player.send_command(PlayerCommand::Seek(position))?;
+        self.state.playback_status.current_position = position.as_secs();
+        Ok(())
+    }
+
+    /// Delete the selected bookmark in the song info popup
+    pub fn remove_selected_bookmark(&mut self) {
+        let Some(info) = self.state.song_info.as_mut() else {
+            return;
+        };
+        if info.selected >= info.bookmarks.len() {
+            return;
+        }
+
+        match self.library.remove_bookmark(&info.bookmarks[info.selected]) {
+            Ok(()) => {
+                let bookmark = info.bookmarks.remove(info.selected);
+                info.selected = info.selected.min(info.bookmarks.len().saturating_sub(1));
+                self.show_toast(t!("bookmark-removed", name = bookmark.name()));
+            }
+            Err(e) => self.show_toast(t!("bookmark-failed", error = e.to_string())),
+        }
+    }
+
+    /// Ask for a name to bookmark the playing song where it is now,
+    /// suggesting the position itself
+    pub fn enter_bookmark_mode(&mut self) {
+        let Some(path) = self.state.playback_status.current_song.clone() else {
+            return;
+        };
+        if stream::is_stream(&path) {
+            self.show_toast(t!("bookmark-stream"));
+            return;
+        }
+
+        let position = Duration::from_secs(self.state.playback_status.current_position);
+        self.state.pending_bookmark = Some((path, position));
+        self.state.input_mode = InputMode::BookmarkName;
+        self.state.playlist_name_textarea = TextArea::new(vec![value_objects::Duration::from_seconds(position.as_secs()).format_h_mm_ss()]);
+        self.state.playlist_name_textarea.move_cursor(tui_textarea::CursorMove::End);
+    }
+
+    fn add_bookmark(&mut self, name: &str) {
+        let Some((path, position)) = self.state.pending_bookmark.take() else {
+            return;
+        };
+        match self.library.add_bookmark(&path, position, name) {
+            Ok(()) => self.show_toast(t!("bookmark-added", name = name)),
+            Err(e) => self.show_toast(t!("bookmark-failed", error = e.to_string())),
+        }
+    }
+
+    /// Edit the title of the selected song in place, to fix a typo without
+    /// going through the tags of the whole library
+    pub fn enter_rename_mode(&mut self) {
+        let Some((path, title)) = self.get_selected_song().map(|song| (song.path.clone(), song.title.clone())) else {
+            return;
+        };
+        if stream::is_stream(&path) {
+            self.show_toast(t!("rename-stream"));
+            return;
+        }
+
+        self.state.pending_rename = Some(path);
+        self.state.playlist_name_textarea = TextArea::new(vec![title]);
+        self.state.playlist_name_textarea.move_cursor(tui_textarea::CursorMove::End);
+        self.state.active_panel = ActivePanel::Songs;
+        self.state.input_mode = InputMode::SongTitle;
+    }
+
+    /// Write `title` to the tags of the song being renamed, then read the
+    /// song back into the library
+    fn rename_song(&mut self, title: &str) {
+        let Some(path) = self.state.pending_rename.take() else {
+            return;
+        };
+        let tags = TrackTags { title: Some(title.to_string()), ..TrackTags::default() };
+        let renamed = tags.write(Path::new(&path)).and_then(|()| {
+            let song = MusicScanner::with_options(self.scan_settings.options.clone()).extract_metadata(Path::new(&path))?;
+            let stored = self.library.store_songs(std::slice::from_ref(&song))?;
+            match stored.failed.into_iter().next() {
+                Some(issue) => Err(LofiTurtleError::MusicLibrary(issue.reason)),
+                None => Ok(song),
             }
-            PlayerState::Stopped => {
-                // If stopped, play the selected song
-                self.play_selected_song()?;
+        });
+
+        match renamed {
+            Ok(song) => {
+                let window = self.state.library_window.iter_mut().flat_map(SongWindow::held_mut);
+                if let Some(entry) = self.state.songs.iter_mut().chain(window).find(|entry| entry.path == path) {
+                    *entry = song;
+                }
+                self.show_toast(t!("song-renamed", title = title));
             }
+            Err(e) => self.show_toast(t!("rename-failed", error = e.to_string())),
         }
-        Ok(())
     }
 
-    pub fn stop_playback(&mut self) -> Result<()> {
-        self.audio_player.send_command(PlayerCommand::Stop)?;
-        Ok(())
+    /// Close the scan summary popup
+    pub fn close_scan_report(&mut self) {
+        self.state.scan_report = None;
+        self.state.input_mode = InputMode::Normal;
     }
 
-    pub fn enter_search_mode(&mut self) {
-        self.state.input_mode = InputMode::Search;
-        self.state.search_textarea.move_cursor(tui_textarea::CursorMove::End);
+    /// Open the keybinding cheatsheet
+    pub fn open_help(&mut self) {
+        self.state.input_mode = InputMode::Help;
     }
 
-    pub fn exit_search_mode(&mut self) {
+    /// Open the log viewer at the newest records
+    pub fn open_logs(&mut self) {
+        self.state.log_scroll = 0;
+        self.state.input_mode = InputMode::Logs;
+    }
+
+    /// Scroll the log viewer back to older records
+    pub fn scroll_logs_back(&mut self, records: usize) {
+        let kept = logging::recent().len();
+        self.state.log_scroll = (self.state.log_scroll + records).min(kept.saturating_sub(1));
+    }
+
+    /// Scroll the log viewer forward to newer records
+    pub fn scroll_logs_forward(&mut self, records: usize) {
+        self.state.log_scroll = self.state.log_scroll.saturating_sub(records);
+    }
+
+    /// Leave the stats screen
+    pub fn close_stats(&mut self) {
+        self.state.heatmap = None;
         self.state.input_mode = InputMode::Normal;
     }
 
-    pub fn update_search_query(&mut self) {
-        let new_query = self.state.search_textarea.lines().join("");
-        if new_query != self.state.search_query {
-            self.state.search_query = new_query;
-            self.update_filtered_songs();
+    /// Listening totals for today, empty when history is disabled
+    pub fn listening_today(&self) -> ListeningSummary {
+        self.scrobbler.as_ref().map(Scrobbler::today).unwrap_or_default()
+    }
+
+    /// The last few tracks played, newest first, empty when history is
+    /// disabled
+    pub fn recently_played(&self) -> Vec<Listen> {
+        self.scrobbler.as_ref().map(Scrobbler::recent).unwrap_or_default()
+    }
+
+    /// Play the track played `index` tracks before the last one again
+    pub fn replay_recent(&mut self, index: usize) -> Result<()> {
+        let Some(listen) = self.recently_played().into_iter().nth(index) else {
+            self.show_toast(t!("recent-none"));
+            return Ok(());
+        };
+
+        let path = listen.track_path().to_string();
+        let trim = self.trim_of(&path);
+        self.audio_player.send_command(PlayerCommand::Play(path.clone(), trim))?;
+        let song = self.state.held_songs().find(|song| song.path == path).cloned();
+        if let Some(song) = song {
+            self.update_album_art(&song)?;
         }
+        Ok(())
     }
 
-    pub fn clear_search(&mut self) {
-        self.state.search_textarea = TextArea::default();
-        self.state.search_textarea.set_placeholder_text("Search songs...");
-        self.state.search_query.clear();
-        self.update_filtered_songs();
+    /// Where the player is, to reopen there next time
+    fn session(&self) -> Session {
+        Session {
+            view: self.state.view_mode.clone(),
+            active_panel: self.state.active_panel.clone(),
+            selected_playlist_index: self.state.selected_playlist_index,
+            selected_song: self.get_selected_song().map(|song| song.path.clone()),
+            selected_song_index: self.state.selected_song_index,
+            search_query: self.state.search_query.clone(),
+        }
     }
 
-    pub fn update_playback_status(&mut self) {
-        self.state.playback_status = self.audio_player.get_status();
-        self.state.last_update = Instant::now();
-        
-        // Update album art if song changed
-        if let Some(current_song) = self.get_current_song().cloned() {
-            if self.state.show_album_art {
-                let _ = self.update_album_art(&current_song);
-            }
+    /// Put the player back where `session` left it, before the songs load,
+    /// returning the song to select once they have
+    fn restore_session(&mut self, session: Session) -> Option<String> {
+        self.state.view_mode = session.restored_view(&self.library);
+        // The album art panel may have been turned off since
+        if session.active_panel != ActivePanel::AlbumArt || self.state.show_album_art {
+            self.state.active_panel = session.active_panel;
+        }
+        // Both are checked against the songs and playlists once they load
+        self.state.selected_playlist_index = session.selected_playlist_index;
+        self.state.selected_song_index = session.selected_song_index;
+        if !session.search_query.is_empty() {
+            self.state.search_textarea.insert_str(&session.search_query);
+            self.state.search_query = session.search_query;
         }
+        session.selected_song
     }
 
     pub fn quit(&mut self) -> Result<()> {
+        if let Err(e) = self.session().save() {
+            log::warn!("{}", e);
+        }
+        self.count_play(&PlayerEvent::AppQuit);
+        self.plugins.dispatch(&PlayerEvent::AppQuit);
         self.state.should_quit = true;
         self.audio_player.send_command(PlayerCommand::Quit)?;
         Ok(())
     }
 
     pub fn get_current_song(&self) -> Option<&Song> {
-        if let Some(ref current_path) = self.state.playback_status.current_song {
-            self.state.songs.iter().find(|song| &song.path == current_path)
+        let current_path = self.state.playback_status.current_song.as_ref()?;
+        self.state
+            .held_songs()
+            .find(|song| &song.path == current_path)
+            .or_else(|| self.state.playing_song.as_ref().filter(|song| &song.path == current_path))
+    }
+
+    pub fn get_selected_song(&self) -> Option<&Song> {
+        self.state.filtered_song(self.state.selected_song_index)
+    }
+
+    /// Open the selected song's folder in the system file manager
+    pub fn reveal_selected_song(&self) {
+        if let Some(song) = self.get_selected_song() {
+            if let Err(e) = desktop::reveal_in_file_manager(std::path::Path::new(&song.path)) {
+                log::warn!("Failed to reveal {}: {}", song.path, e);
+            }
+        }
+    }
+
+    /// Show a short message over the UI
+    pub fn show_toast<S: Into<String>>(&mut self, message: S) {
+        let message = message.into();
+        self.state.toast = Some(Toast {
+            message: if self.screen_reader { text::plain(&message) } else { message },
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Copy the selected song's file path to the clipboard
+    pub fn copy_selected_song_path(&mut self) {
+        if let Some(path) = self.get_selected_song().map(|song| song.path.clone()) {
+            let message = t!("copied-path", text = path.as_str());
+            self.copy_to_clipboard(&path, message);
+        }
+    }
+
+    /// Copy the selected song as "Artist - Title" to the clipboard
+    pub fn copy_selected_song_title(&mut self) {
+        if let Some(song) = self.get_selected_song() {
+            let text = format!("{} - {}", song.artist, song.title);
+            let message = t!("copied-title", text = text.as_str());
+            self.copy_to_clipboard(&text, message);
+        }
+    }
+
+    /// Love the selected song, or unlove it, on Last.fm too when an
+    /// account is configured
+    pub fn toggle_selected_song_loved(&mut self) {
+        let Some(song) = self.get_selected_song().cloned() else {
+            return;
+        };
+        let loved = !self.state.loved.contains(&song.id);
+        if let Err(e) = self.library.set_loved(&song.id, loved) {
+            self.show_toast(t!("loved-failed", error = e.to_string()));
+            return;
+        }
+
+        if loved {
+            self.state.loved.insert(song.id.clone());
+            self.show_toast(t!("loved-on", song = song.display_name()));
         } else {
-            None
+            self.state.loved.remove(&song.id);
+            self.show_toast(t!("loved-off", song = song.display_name()));
+        }
+
+        // Last.fm can be slow or unreachable; `lofiturtle loved sync`
+        // catches up on loves that did not get through
+        if let Some(client) = self.lastfm.clone() {
+            std::thread::spawn(move || {
+                if let Err(e) = client.set_loved(&song.artist, &song.title, loved) {
+                    log::warn!("Failed to update {} on Last.fm: {}", song.display_name(), e);
+                }
+            });
         }
     }
 
-    #[allow(dead_code)] // Future feature: song selection info
-    pub fn get_selected_song(&self) -> Option<&Song> {
-        self.state.filtered_songs.get(self.state.selected_song_index)
+    fn copy_to_clipboard(&mut self, text: &str, message: String) {
+        match self.clipboard.set_text(text) {
+            Ok(()) => self.show_toast(message),
+            Err(e) => {
+                log::warn!("Failed to copy to clipboard: {}", e);
+                self.show_toast(t!("clipboard-unavailable"));
+            }
+        }
     }
 
     /// Get the current input mode
@@ -381,7 +2626,12 @@ impl App {
                 self.state.search_textarea.input(event);
                 self.update_search_query();
             }
-            InputMode::PlaylistCreate | InputMode::PlaylistEdit => {
+            InputMode::PlaylistCreate
+            | InputMode::PlaylistEdit
+            | InputMode::QueueSave
+            | InputMode::PartyLock
+            | InputMode::PartyConfirm
+            | InputMode::PartyUnlock => {
                 self.state.playlist_name_textarea.input(event);
             }
             _ => {}
@@ -390,39 +2640,56 @@ impl App {
     }
     
     // Playlist management methods
-    pub fn switch_to_playlist(&mut self, playlist_name: &str) -> Result<()> {
+    pub fn switch_to_playlist(&mut self, playlist_name: &str) {
         self.state.view_mode = ViewMode::Playlist(playlist_name.to_string());
         self.state.active_panel = ActivePanel::Songs;
-        self.load_songs()?;
-        Ok(())
+        self.load_songs();
     }
     
-    pub fn play_selected_playlist(&mut self) -> Result<()> {
-        if let Some(playlist) = self.state.playlists.get(self.state.selected_playlist_index) {
-            let playlist_name = playlist.name.clone();
-            
-            // Switch to playlist view
-            self.switch_to_playlist(&playlist_name)?;
-            
-            // If playlist has songs, play the first one
-            if !self.state.songs.is_empty() {
-                self.state.selected_song_index = 0;
-                self.play_selected_song()?;
-            } else {
-            }
+    /// Switch to a built-in view such as "Recently Added"
+    pub fn switch_to_smart_view(&mut self, view: SmartView) {
+        self.state.view_mode = ViewMode::Smart(view);
+        self.state.active_panel = ActivePanel::Songs;
+        self.load_songs();
+    }
+    
+    pub fn play_selected_playlist(&mut self) {
+        if let Some(view) = self.selected_smart_view() {
+            self.state.view_mode = ViewMode::Smart(view);
+        } else if let Some(playlist) = self.selected_playlist() {
+            self.state.view_mode = ViewMode::Playlist(playlist.name.clone());
+        } else {
+            return;
         }
-        Ok(())
+        
+        // If playlist has songs, play the first one once they have loaded
+        self.state.active_panel = ActivePanel::Songs;
+        self.load_songs_then(AfterLoad::PlayFirst);
     }
     
     pub fn advance_to_next_song(&mut self) -> Result<()> {
         // Use enhanced PlaybackState for next song logic
-        if !self.state.filtered_songs.is_empty() {
-            let playlist_size = self.state.filtered_songs.len();
+        if self.state.filtered_len() > 0 {
+            let playlist_size = self.state.filtered_len();
+            // Play the song lined up for gapless playback, if one was picked
+            let next_index = match self.lined_up.take().filter(|lined_up| lined_up.is_for(&self.state)) {
+                Some(lined_up) => {
+                    self.state.playback_state = lined_up.advanced;
+                    lined_up.index
+                }
+                None => self.state.playback_state.next_song_index(playlist_size),
+            };
             
-            if let Some(next_index) = self.state.playback_state.next_song_index(playlist_size) {
+            if let Some(next_index) = next_index {
                 self.state.selected_song_index = next_index;
                 self.state.playback_state.set_current_song_index(next_index, playlist_size);
                 self.play_selected_song()?;
+            } else if self.extend_queue_with_next_album()? || self.extend_queue_with_auto_dj()? {
+                // Continue with the track picked from those appended. The
+                // shuffle already holds the rest, so it must not be redrawn
+                // over the songs heard before.
+                self.state.playback_state.current_song_index = self.state.selected_song_index;
+                self.play_selected_song()?;
             } else {
                 // End of playlist with no repeat
                 self.audio_player.send_command(PlayerCommand::Stop)?;
@@ -431,13 +2698,110 @@ impl App {
         Ok(())
     }
     
+    /// Play the song before the playing one in the queue
+    pub fn go_to_previous_song(&mut self) -> Result<()> {
+        if self.state.filtered_len() > 0 {
+            let playlist_size = self.state.filtered_len();
+
+            if let Some(previous_index) = self.state.playback_state.previous_song_index(playlist_size) {
+                self.state.selected_song_index = previous_index;
+                self.state.playback_state.set_current_song_index(previous_index, playlist_size);
+                self.play_selected_song()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the next album once an album shown on its own has played to
+    /// its final track
+    ///
+    /// The player has no album view, so a playlist, search or queue holding
+    /// songs of a single album stands for one; a longer list that only
+    /// happens to end with an album's last track stops as usual. Selects the
+    /// first appended track and returns whether anything was added.
+    fn extend_queue_with_next_album(&mut self) -> Result<bool> {
+        if !self.state.playback_state.continue_albums {
+            return Ok(false);
+        }
+        let Some(last) = self.state.filtered_song(self.state.selected_song_index).cloned() else {
+            return Ok(false);
+        };
+        let same_album = |song: &Song| {
+            song.artist.eq_ignore_ascii_case(&last.artist) && song.album.eq_ignore_ascii_case(&last.album)
+        };
+        if !self.state.filtered_songs().all(same_album) {
+            return Ok(false);
+        }
+        // Only part of the album may have been shown
+        let album = self.library.get_album(&last.artist, &last.album)?;
+        if album.last().is_none_or(|song| song.id != last.id) {
+            return Ok(false);
+        }
+
+        let album = self.library.get_next_album(&last.id)?;
+        Ok(self.extend_queue(album))
+    }
+
+    /// Append tracks picked by the auto-DJ once the queue has run out
+    ///
+    /// Selects the first appended track and returns whether anything was added.
+    fn extend_queue_with_auto_dj(&mut self) -> Result<bool> {
+        let Some(strategy) = self.state.playback_state.auto_dj else {
+            return Ok(false);
+        };
+        let Some(last) = self.state.filtered_song(self.state.selected_song_index).cloned() else {
+            return Ok(false);
+        };
+
+        let library = self.library.get_all_songs()?;
+        let last_played = match strategy {
+            AutoDjStrategy::LeastRecentlyPlayed => self
+                .library
+                .get_song_stats()?
+                .into_iter()
+                .filter_map(|stats| Some((stats.song.id().as_str().to_string(), stats.last_played_at?)))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        let picked = strategy.pick_tracks(&last, &library, self.state.filtered_songs(), &last_played, AUTO_DJ_BATCH_SIZE);
+        Ok(self.extend_queue(picked))
+    }
+
+    /// Add `picked` to the end of the queue and select the one to play
+    /// next, returning whether there were any
+    ///
+    /// With shuffle on they are shuffled in with the songs still to play, and
+    /// the next one is taken from the shuffle rather than the first appended.
+    fn extend_queue(&mut self, picked: Vec<Song>) -> bool {
+        if picked.is_empty() {
+            return false;
+        }
+
+        let first = self.state.filtered_len();
+        self.append_to_queue(picked);
+        let playback_state = &mut self.state.playback_state;
+        let shuffled = if playback_state.shuffle { playback_state.shuffle_queue.pop_front() } else { None };
+        self.state.selected_song_index = shuffled.unwrap_or(first);
+        true
+    }
+    
     pub fn check_and_handle_song_completion(&mut self) -> Result<()> {
+        if self.switching_output {
+            return Ok(());
+        }
         let status = self.audio_player.get_status();
         
         // Check if song just finished (state is Stopped and we were previously playing)
         if status.state == PlayerState::Stopped && status.current_song.is_none() {
-            // Only auto-advance if we're in a playlist
-            if matches!(self.state.view_mode, ViewMode::Playlist(_)) {
+            if self.state.stop_after_current {
+                self.finish_stop_after_current();
+            }
+            if self.state.hold_playback {
+                return Ok(());
+            }
+
+            // Only auto-advance if we're in a playlist or built-in view
+            if !matches!(self.state.view_mode, ViewMode::Library) {
                 self.advance_to_next_song()?;
             }
         }
@@ -445,93 +2809,280 @@ impl App {
         Ok(())
     }
     
-    pub fn switch_to_library(&mut self) -> Result<()> {
+    pub fn switch_to_library(&mut self) {
         self.state.view_mode = ViewMode::Library;
         self.state.active_panel = ActivePanel::Songs;
-        self.load_songs()?;
-        Ok(())
+        self.load_songs();
     }
     
+    /// Show the playing song in the library, clearing any search that hides it
+    pub fn jump_to_playing_song(&mut self) {
+        let Some(path) = self.state.playback_status.current_song.clone() else {
+            self.show_toast(t!("nothing-playing"));
+            return;
+        };
+
+        if !self.state.search_query.is_empty() {
+            self.clear_search();
+        }
+
+        self.state.active_panel = ActivePanel::Songs;
+        match self.state.filtered_index_of(&path) {
+            Some(index) if self.state.view_mode == ViewMode::Library && !self.songs_loading() => {
+                self.state.selected_song_index = index;
+            }
+            // Select the song once the library has loaded around it
+            _ => {
+                self.state.view_mode = ViewMode::Library;
+                self.load_songs_then(AfterLoad::Select(path));
+            }
+        }
+    }
+
     pub fn create_playlist(&mut self, name: String, description: Option<String>) -> Result<()> {
-        let playlist = Playlist::new(name, description);
-        self.database.create_playlist(&playlist)?;
-        self.load_playlists()?;
+        self.library.create_playlist(&name, description.clone())?;
+        self.edits.record(PlaylistEdit::Create { name, description });
+        self.load_playlists();
         Ok(())
     }
     
     pub fn delete_selected_playlist(&mut self) -> Result<()> {
-        if let Some(playlist) = self.state.playlists.get(self.state.selected_playlist_index) {
-            let _playlist_name = playlist.name.clone();
-            let playlist_id = playlist.id.clone(); // Use the playlist ID, not the name
-            
-            match self.database.delete_playlist(&playlist_id) { // Pass ID instead of name
-                Ok(deleted) => {
-                    if deleted {
-                        self.load_playlists()?;
-                        
-                        // Reset selection if out of bounds
-                        if self.state.selected_playlist_index >= self.state.playlists.len() {
-                            self.state.selected_playlist_index = if self.state.playlists.is_empty() { 0 } else { self.state.playlists.len() - 1 };
-                        }
-                    } else {
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            }
-        } else {
+        if let Some(playlist) = self.selected_playlist().cloned() {
+            // Use the playlist ID, not the name
+            self.library.delete_playlist(&playlist.id)?;
+            self.edits.record(PlaylistEdit::Delete {
+                name: playlist.name,
+                description: playlist.description,
+                song_ids: playlist.song_ids,
+            });
+            // Reloading also clamps the selection
+            self.load_playlists();
         }
         Ok(())
     }
     
     pub fn add_song_to_playlist(&mut self, playlist_name: &str, song_id: &str) -> Result<()> {
         // First get the playlist by name to get its ID
-        if let Some(playlist) = self.database.get_playlist_by_name(playlist_name)? {
-            // Debug: Print what we're trying to add
-            
-            self.database.add_song_to_playlist(&playlist.id, song_id, 0)?;
-            
-            // Debug: Verify the song was added
-            if let Ok(_playlist_songs) = self.database.get_playlist_songs(&playlist.id) {
+        if let Some(playlist) = self.library.get_playlist_by_name(playlist_name)? {
+            // Adding a song that is already in the playlist is a no-op
+            if playlist.song_ids.iter().any(|id| id == song_id) {
+                return Ok(());
             }
             
-            // Reload playlists to update song counts in the UI
-            self.load_playlists()?;
-            
-            // If we're currently viewing this playlist, reload the songs
-            if let ViewMode::Playlist(current_playlist) = &self.state.view_mode {
-                if current_playlist == playlist_name {
-                    self.load_songs()?;
+            self.library.add_song_to_playlist(&playlist.id, song_id)?;
+            self.edits.record(PlaylistEdit::AddSong {
+                playlist: playlist_name.to_string(),
+                song_id: song_id.to_string(),
+            });
+            self.reload_playlist(playlist_name);
+        }
+        Ok(())
+    }
+    
+    /// Ask to add every song shown, all the search results in the current
+    /// view, to the playlist selected in the Playlists panel
+    pub fn request_batch_add(&mut self) {
+        let Some(playlist) = self.selected_playlist().map(|playlist| playlist.name.clone()) else {
+            self.show_toast(t!("batch-add-no-playlist"));
+            return;
+        };
+        // The results shown may be about to change
+        if self.songs_load.is_some() || self.window_load.is_some() {
+            self.show_toast(t!("batch-add-loading"));
+            return;
+        }
+
+        let (song_ids, outside) = if self.state.library_window.is_some() {
+            // The library window holds only some of the results, all of
+            // them in the library
+            match self.library.get_matching_songs(&self.state.search_query) {
+                Ok(songs) => (songs.into_iter().map(|song| song.id).collect(), Vec::new()),
+                Err(e) => {
+                    self.show_toast(t!("batch-add-failed", error = e.to_string()));
+                    return;
                 }
             }
         } else {
+            let shown: Vec<String> = self.state.filtered_songs().map(|song| song.id.clone()).collect();
+
+            // Streams and files queued from the clipboard have no library
+            // entry a playlist could point to
+            let in_library: HashSet<String> = match self.library.get_songs(&shown) {
+                Ok(songs) => songs.into_iter().map(|song| song.id).collect(),
+                Err(e) => {
+                    self.show_toast(t!("batch-add-failed", error = e.to_string()));
+                    return;
+                }
+            };
+            shown.into_iter().partition(|id| in_library.contains(id))
+        };
+        if song_ids.is_empty() {
+            self.show_toast(t!("batch-add-nothing"));
+            return;
+        }
+
+        self.state.pending_batch_add = Some((playlist, song_ids, outside.len()));
+        self.state.input_mode = InputMode::ConfirmBatchAdd;
+    }
+
+    /// Add the songs waiting for confirmation to their playlist, in one go
+    pub fn confirm_batch_add(&mut self) -> Result<()> {
+        let Some((playlist_name, song_ids, _)) = self.state.pending_batch_add.take() else {
+            return Ok(());
+        };
+        self.exit_input_mode();
+
+        let playlist = self.find_playlist(&playlist_name)?;
+        match self.library.add_songs_to_playlist(&playlist.id, &song_ids) {
+            Ok(added) => {
+                self.show_toast(t!(
+                    "batch-added",
+                    count = added.len(),
+                    skipped = song_ids.len() - added.len(),
+                    name = playlist_name.as_str()
+                ));
+                if !added.is_empty() {
+                    self.edits.record(PlaylistEdit::AddSongs { playlist: playlist_name.clone(), song_ids: added });
+                    self.reload_playlist(&playlist_name);
+                }
+            }
+            Err(e) => self.show_toast(t!("batch-add-failed", error = e.to_string())),
         }
         Ok(())
     }
     
     pub fn remove_song_from_playlist(&mut self, playlist_name: &str, song_id: &str) -> Result<()> {
         // First get the playlist by name to get its ID
-        if let Some(playlist) = self.database.get_playlist_by_name(playlist_name)? {
-            self.database.remove_song_from_playlist(&playlist.id, song_id)?;
-            
-            // Reload playlists to update song counts in the UI
-            self.load_playlists()?;
+        if let Some(playlist) = self.library.get_playlist_by_name(playlist_name)? {
+            let Some(position) = playlist.song_ids.iter().position(|id| id == song_id) else {
+                return Ok(());
+            };
             
-            // If we're currently viewing this playlist, reload the songs
-            if let ViewMode::Playlist(current_playlist) = &self.state.view_mode {
-                if current_playlist == playlist_name {
-                    self.load_songs()?;
+            self.library.remove_song_from_playlist(&playlist.id, song_id)?;
+            self.edits.record(PlaylistEdit::RemoveSong {
+                playlist: playlist_name.to_string(),
+                song_id: song_id.to_string(),
+                position,
+            });
+            self.reload_playlist(playlist_name);
+        }
+        Ok(())
+    }
+
+    /// Refresh the UI after the songs of a playlist changed
+    fn reload_playlist(&mut self, playlist_name: &str) {
+        // Reload playlists to update song counts in the UI
+        self.load_playlists();
+        
+        // If we're currently viewing this playlist, reload the songs
+        if self.get_current_playlist_name() == Some(playlist_name) {
+            self.load_songs();
+        }
+    }
+
+    /// Undo the latest playlist edit
+    pub fn undo_playlist_edit(&mut self) {
+        match self.edits.undo() {
+            Some(edit) => self.replay_edit(&edit, true, "undo-done", "undo-failed"),
+            None => self.show_toast(t!("undo-nothing")),
+        }
+    }
+
+    /// Redo the latest undone playlist edit
+    pub fn redo_playlist_edit(&mut self) {
+        match self.edits.redo() {
+            Some(edit) => self.replay_edit(&edit, false, "redo-done", "redo-failed"),
+            None => self.show_toast(t!("redo-nothing")),
+        }
+    }
+
+    /// Apply an edit, or its reverse, and report how it went
+    fn replay_edit(&mut self, edit: &PlaylistEdit, reverse: bool, done: &str, failed: &str) {
+        let result = self.apply_edit(edit, reverse);
+        // Reload even after an error, since part of the edit may have applied
+        self.load_playlists();
+        self.load_songs();
+
+        match result {
+            Ok(()) => self.show_toast(t!(done, edit = edit.describe())),
+            Err(e) => {
+                // The library no longer matches the history, e.g. because a
+                // song file was deleted, so the older edits cannot be trusted
+                self.edits.clear();
+                self.show_toast(t!(failed, error = e.to_string()));
+            }
+        }
+    }
+
+    /// Make `edit` again, or take it back when `reverse` is set
+    ///
+    /// Goes to the library directly so the history is left alone.
+    fn apply_edit(&mut self, edit: &PlaylistEdit, reverse: bool) -> Result<()> {
+        match (edit, reverse) {
+            (PlaylistEdit::Create { name, description }, false) => {
+                self.library.create_playlist(name, description.clone())?;
+            }
+            (PlaylistEdit::Create { name, .. }, true)
+            | (PlaylistEdit::Delete { name, .. }, false)
+            | (PlaylistEdit::SaveQueue { name, .. }, true) => {
+                let playlist = self.find_playlist(name)?;
+                self.library.delete_playlist(&playlist.id)?;
+            }
+            (PlaylistEdit::AddSong { playlist, song_id }, false)
+            | (PlaylistEdit::RemoveSong { playlist, song_id, .. }, true) => {
+                let playlist = self.find_playlist(playlist)?;
+                self.library.add_song_to_playlist(&playlist.id, song_id)?;
+
+                // Put a removed song back where it was
+                if let PlaylistEdit::RemoveSong { position, .. } = edit {
+                    let mut song_ids = playlist.song_ids;
+                    song_ids.insert((*position).min(song_ids.len()), song_id.clone());
+                    self.library.reorder_playlist(&playlist.id, &song_ids)?;
+                }
+            }
+            (PlaylistEdit::AddSong { playlist, song_id }, true)
+            | (PlaylistEdit::RemoveSong { playlist, song_id, .. }, false) => {
+                let playlist = self.find_playlist(playlist)?;
+                self.library.remove_song_from_playlist(&playlist.id, song_id)?;
+            }
+            (PlaylistEdit::AddSongs { playlist, song_ids }, false) => {
+                let playlist = self.find_playlist(playlist)?;
+                self.library.add_songs_to_playlist(&playlist.id, song_ids)?;
+            }
+            (PlaylistEdit::AddSongs { playlist, song_ids }, true) => {
+                let playlist = self.find_playlist(playlist)?;
+                for song_id in song_ids {
+                    self.library.remove_song_from_playlist(&playlist.id, song_id)?;
                 }
             }
+            (PlaylistEdit::Delete { name, description, song_ids }, true) => {
+                // The songs are kept in playlist order, so appending them in
+                // one go puts each back at its position
+                let playlist_id = self.library.create_playlist(name, description.clone())?;
+                self.library.add_songs_to_playlist(&playlist_id, song_ids)?;
+            }
+            (PlaylistEdit::SaveQueue { name, song_ids }, false) => {
+                let playlist_id = self.library.create_playlist(name, None)?;
+                self.library.add_songs_to_playlist(&playlist_id, song_ids)?;
+            }
         }
         Ok(())
     }
+
+    fn find_playlist(&self, name: &str) -> Result<Playlist> {
+        self.library
+            .get_playlist_by_name(name)?
+            .ok_or_else(|| LofiTurtleError::MusicLibrary(format!("Playlist not found: {}", name)))
+    }
     
     // Enhanced playback mode controls with fair randomization
     pub fn toggle_shuffle(&mut self) -> Result<()> {
-        let playlist_size = self.state.filtered_songs.len();
-        self.state.playback_state.toggle_shuffle(playlist_size);
+        let playlist_size = self.state.filtered_len();
+        self.state.playback_state.cycle_shuffle(playlist_size);
+        if self.state.playback_state.smart_shuffle {
+            self.reshuffle();
+        }
+        self.announce(shuffle_label(&self.state.playback_state));
         
         // Save to persistent settings
         self.save_playback_settings()?;
@@ -540,6 +3091,7 @@ impl App {
     
     pub fn cycle_repeat_mode(&mut self) -> Result<()> {
         self.state.playback_state.cycle_repeat_mode();
+        self.announce(repeat_label(self.state.playback_state.repeat_mode));
         
         // Save to persistent settings
         self.save_playback_settings()?;
@@ -551,12 +3103,19 @@ impl App {
         let settings = PersistentSettings {
             volume: self.state.playback_status.volume,
             shuffle: self.state.playback_state.shuffle,
+            smart_shuffle: self.state.playback_state.smart_shuffle,
             repeat_mode: self.state.playback_state.repeat_mode,
+            ..PersistentSettings::load()
         };
         settings.save()
     }
 
     
+    /// Switch the visualizer between the spectrum and the oscilloscope
+    pub fn toggle_visualizer(&mut self) {
+        self.state.visualizer_mode = self.state.visualizer_mode.toggled();
+    }
+
     // Album art methods
     pub fn toggle_album_art(&mut self) {
         self.state.show_album_art = !self.state.show_album_art;
@@ -598,7 +3157,7 @@ impl App {
                 // Render with dynamic dimensions
                 let art = match self.album_art_renderer.render_album_art_for_panel(&image_data, panel_width, panel_height) {
                     Ok(art) => art,
-                    Err(e) => return Err(LofiTurtleError::from(std::io::Error::new(std::io::ErrorKind::Other, format!("Album art rendering error: {}", e)))),
+                    Err(e) => return Err(LofiTurtleError::from(std::io::Error::other(format!("Album art rendering error: {}", e)))),
                 };
                 self.state.current_album_art = Some(art.clone());
                 Ok(Some(art))
@@ -629,25 +3188,154 @@ impl App {
         self.state.playlist_name_textarea.set_placeholder_text("Enter playlist name...");
     }
     
+    /// Ask for the name of a playlist to save the queue as
+    pub fn enter_queue_save_mode(&mut self) {
+        self.state.input_mode = InputMode::QueueSave;
+        self.state.playlist_name_textarea = TextArea::default();
+        self.state.playlist_name_textarea.set_placeholder_text("Enter playlist name...");
+    }
+
+    /// Ask for a passphrase to turn party mode on, or for the one it was
+    /// turned on with to turn it off
+    pub fn enter_party_mode(&mut self) {
+        self.state.input_mode = if self.is_party_locked() { InputMode::PartyUnlock } else { InputMode::PartyLock };
+        self.state.playlist_name_textarea = TextArea::default();
+        self.state.playlist_name_textarea.set_mask_char('•');
+    }
+
+    /// Whether party mode is on
+    pub fn is_party_locked(&self) -> bool {
+        self.state.party_passphrase.is_some()
+    }
+
+    /// Ask for the passphrase party mode is turned on with a second time
+    fn ask_to_repeat_passphrase(&mut self, passphrase: String) {
+        self.state.pending_party_passphrase = Some(passphrase);
+        self.state.input_mode = InputMode::PartyConfirm;
+        self.state.playlist_name_textarea = TextArea::default();
+        self.state.playlist_name_textarea.set_mask_char('•');
+    }
+
+    fn confirm_party_passphrase(&mut self, passphrase: String) {
+        match &self.state.party_passphrase {
+            None => match self.state.pending_party_passphrase.take() {
+                Some(first) if first == passphrase => {
+                    self.state.party_passphrase = Some(passphrase);
+                    self.show_toast(t!("party-on"));
+                }
+                _ => self.show_toast(t!("party-passphrase-mismatch")),
+            },
+            Some(expected) if *expected == passphrase => {
+                self.state.party_passphrase = None;
+                self.show_toast(t!("party-off"));
+            }
+            Some(_) => self.show_toast(t!("party-wrong-passphrase")),
+        }
+    }
+
     pub fn enter_playlist_edit_mode(&mut self) {
-        if let Some(playlist) = self.state.playlists.get(self.state.selected_playlist_index) {
+        if let Some(name) = self.selected_playlist().map(|p| p.name.clone()) {
             self.state.input_mode = InputMode::PlaylistEdit;
             self.state.playlist_name_textarea = TextArea::default();
-            self.state.playlist_name_textarea.insert_str(&playlist.name);
+            self.state.playlist_name_textarea.insert_str(&name);
         }
     }
     
     pub fn exit_input_mode(&mut self) {
         self.state.input_mode = InputMode::Normal;
+        self.state.pending_delete = None;
+        self.state.pending_bookmark = None;
+        self.state.pending_rename = None;
+        self.state.pending_batch_add = None;
+        self.state.pending_party_passphrase = None;
+    }
+
+    /// Ask for confirmation before deleting the selected song's file
+    pub fn request_delete_selected_song(&mut self) {
+        if let Some(song) = self.get_selected_song().cloned() {
+            self.state.pending_delete = Some(song);
+            self.state.input_mode = InputMode::ConfirmDelete;
+        }
+    }
+
+    /// Accept the current delete confirmation
+    ///
+    /// Deleting takes two confirmations; the file is only touched after the
+    /// second one.
+    pub fn confirm_delete(&mut self) -> Result<()> {
+        match self.state.input_mode {
+            InputMode::ConfirmDelete => {
+                self.state.input_mode = InputMode::ConfirmDeleteFinal;
+            }
+            InputMode::ConfirmDeleteFinal => {
+                let song = self.state.pending_delete.take();
+                self.exit_input_mode();
+                if let Some(song) = song {
+                    // The list is only reloaded once the song is gone
+                    if let Err(e) = self.delete_song_file(&song) {
+                        log::warn!("Failed to delete {}: {}", song.path, e);
+                        self.show_toast(t!("delete-failed", error = e.to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Delete a song's file and remove it from the library and all playlists
+    fn delete_song_file(&mut self, song: &Song) -> Result<()> {
+        if self.state.playback_status.current_song.as_deref() == Some(song.path.as_str()) {
+            self.stop_playback()?;
+        }
+
+        // A missing file still needs its stale library entry removed
+        let path = std::path::Path::new(&song.path);
+        if path.exists() {
+            desktop::delete_file(path, self.state.delete_to_trash)?;
+        }
+
+        self.library.remove_song(&song.id)?;
+        // Shuffle order holds song indices, which shift with the removal
+        self.load_songs_then(AfterLoad::Reshuffle);
+        self.load_playlists();
+        Ok(())
     }
     
     pub fn confirm_playlist_action(&mut self) -> Result<()> {
         let playlist_name = self.state.playlist_name_textarea.lines().join("");
+        if playlist_name.trim().is_empty() && matches!(self.state.input_mode, InputMode::PartyLock | InputMode::PartyConfirm) {
+            // Stay in the prompt: a blank passphrase is too easy to hit by
+            // accident to lock the player with
+            self.show_toast(t!("party-blank-passphrase"));
+            return Ok(());
+        }
+        if playlist_name.trim().is_empty() && self.state.input_mode == InputMode::SongTitle {
+            // Tags with an empty title would leave the song nameless
+            self.show_toast(t!("rename-blank-title"));
+            return Ok(());
+        }
         if !playlist_name.trim().is_empty() {
             match self.state.input_mode {
                 InputMode::PlaylistCreate => {
                     self.create_playlist(playlist_name.trim().to_string(), None)?;
                 }
+                InputMode::QueueSave => {
+                    self.save_queue_as_playlist(playlist_name.trim().to_string())?;
+                }
+                InputMode::PartyLock => {
+                    self.ask_to_repeat_passphrase(playlist_name);
+                    return Ok(());
+                }
+                InputMode::PartyConfirm | InputMode::PartyUnlock => {
+                    self.confirm_party_passphrase(playlist_name);
+                }
+                InputMode::BookmarkName => {
+                    self.add_bookmark(playlist_name.trim());
+                }
+                InputMode::SongTitle => {
+                    self.rename_song(playlist_name.trim());
+                }
                 InputMode::PlaylistEdit => {
                     // For now, we'll implement rename functionality later
                     // This would require database schema changes
@@ -663,7 +3351,7 @@ impl App {
     pub fn get_current_playlist_name(&self) -> Option<&str> {
         match &self.state.view_mode {
             ViewMode::Playlist(name) => Some(name),
-            ViewMode::Library => None,
+            ViewMode::Library | ViewMode::Smart(_) => None,
         }
     }
     
@@ -684,6 +3372,10 @@ impl App {
     pub fn set_volume(&mut self, volume: f32) -> Result<()> {
         let clamped_volume = volume.clamp(0.0, 1.0);
         self.audio_player.send_command(PlayerCommand::SetVolume(clamped_volume))?;
+        if clamped_volume != self.state.playback_status.volume {
+            self.plugins.dispatch(&PlayerEvent::VolumeChanged { volume: clamped_volume });
+            self.announce(volume_label(clamped_volume));
+        }
         self.state.playback_status.volume = clamped_volume;
         
         // Save volume to persistent settings
@@ -692,8 +3384,8 @@ impl App {
         Ok(())
     }
 
-    /// Cycle through available themes
-    pub fn cycle_theme(&mut self) {
+    /// Cycle through available themes, keeping the one picked for next time
+    pub fn cycle_theme(&mut self) -> Result<()> {
         let themes = Themes::all();
         let current_theme_name = &self.layout_config.theme.name;
 
@@ -705,8 +3397,20 @@ impl App {
         // Calculate next index
         let next_index = (current_index + 1) % themes.len();
 
-        // Update theme
-        self.layout_config.theme = themes[next_index].clone();
+        let theme = themes[next_index].clone();
+        self.persistent_settings.theme = Some(theme.name.clone());
+        let settings = PersistentSettings {
+            theme: Some(theme.name.clone()),
+            ..PersistentSettings::load()
+        };
+        self.apply_theme(theme);
+        settings.save()
+    }
+
+    /// Draw with `theme` from now on
+    fn apply_theme(&mut self, theme: ThemeConfig) {
+        self.layout_config.theme = theme;
+        self.theme.update_theme(self.layout_config.theme.clone());
 
         // Update layout engine config
         self.layout_engine.update_config(self.layout_config.clone());
@@ -0,0 +1,190 @@
+//! Contrast between the text and background colors of a theme
+//!
+//! Ratios are computed as WCAG defines them, from 1:1 for the same color to
+//! 21:1 for black on white. Named and indexed colors are taken at their
+//! usual xterm values, so for themes using them the ratios are estimates of
+//! what the terminal shows.
+
+use crate::ui::layout::ThemeConfig;
+use crate::ui::theme::ThemeManager;
+use ratatui::style::{Color, Style};
+
+/// Lowest ratio at which text reads comfortably (WCAG AA)
+pub const TEXT_MINIMUM: f64 = 4.5;
+
+/// Lowest ratio at which borders and bars stand out (WCAG AA)
+pub const GRAPHICS_MINIMUM: f64 = 3.0;
+
+/// A style of the theme drawn against its background
+#[derive(Debug, Clone)]
+pub struct ColorPair {
+    /// Name of the style, as in the theme's `styles` table
+    pub name: &'static str,
+    pub foreground: Color,
+    pub background: Color,
+    pub ratio: f64,
+    pub minimum: f64,
+}
+
+impl ColorPair {
+    pub fn is_readable(&self) -> bool {
+        self.ratio >= self.minimum
+    }
+}
+
+/// Contrast of each text, border and bar style of `theme`
+///
+/// Styles without a background of their own are drawn on the theme's
+/// `background` color, black if it has none. Pairs with a color the
+/// terminal chooses, like `reset`, are left out.
+pub fn check(theme: &ThemeConfig) -> Vec<ColorPair> {
+    let manager = ThemeManager::new(theme.clone());
+    let background = manager.color("background", Color::Black);
+
+    let styles: [(&'static str, Style, f64); 16] = [
+        ("normal", manager.normal_style(), TEXT_MINIMUM),
+        ("text", manager.text_style(), TEXT_MINIMUM),
+        ("muted", manager.muted_style(), TEXT_MINIMUM),
+        ("hint", manager.hint_style(), TEXT_MINIMUM),
+        ("title", manager.title_style(), TEXT_MINIMUM),
+        ("selected", manager.selected_style(), TEXT_MINIMUM),
+        ("highlight", manager.highlight_style(), TEXT_MINIMUM),
+        ("playing", manager.playing_style(), TEXT_MINIMUM),
+        ("paused", manager.paused_style(), TEXT_MINIMUM),
+        ("secondary", manager.secondary_style(), TEXT_MINIMUM),
+        ("warning", manager.warning_style(), TEXT_MINIMUM),
+        ("error", manager.error_style(), TEXT_MINIMUM),
+        ("success", manager.success_style(), TEXT_MINIMUM),
+        ("border", manager.border_style(), GRAPHICS_MINIMUM),
+        ("active_border", manager.active_border_style(), GRAPHICS_MINIMUM),
+        ("progress", manager.progress_style(), GRAPHICS_MINIMUM),
+    ];
+
+    styles
+        .into_iter()
+        .filter_map(|(name, style, minimum)| {
+            let foreground = style.fg?;
+            let background = style.bg.unwrap_or(background);
+            let ratio = contrast_ratio(foreground, background)?;
+            Some(ColorPair { name, foreground, background, ratio, minimum })
+        })
+        .collect()
+}
+
+/// Contrast ratio of two colors, `None` if either is up to the terminal
+pub fn contrast_ratio(a: Color, b: Color) -> Option<f64> {
+    let (a, b) = (luminance(rgb(a)?), luminance(rgb(b)?));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    Some((lighter + 0.05) / (darker + 0.05))
+}
+
+/// Relative luminance of an sRGB color
+fn luminance([r, g, b]: [u8; 3]) -> f64 {
+    let linear = |channel: u8| {
+        let c = channel as f64 / 255.0;
+        if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// The 16 ANSI colors as xterm shows them
+const ANSI: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+fn rgb(color: Color) -> Option<[u8; 3]> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some([r, g, b]),
+        Color::Indexed(index) => return Some(indexed(index)),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+    Some(ANSI[index])
+}
+
+/// A color of the 256-color palette: the ANSI colors, a 6×6×6 cube and a
+/// ramp of grays
+fn indexed(index: u8) -> [u8; 3] {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => ANSI[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            [LEVELS[(cube / 36) as usize], LEVELS[(cube / 6 % 6) as usize], LEVELS[(cube % 6) as usize]]
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            [gray, gray, gray]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::theme::Themes;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert_eq!(contrast_ratio(Color::Black, Color::White), Some(21.0));
+        assert_eq!(contrast_ratio(Color::Rgb(10, 20, 30), Color::Rgb(10, 20, 30)), Some(1.0));
+        // The 256-color palette: bright white cube corner and the gray ramp
+        assert_eq!(contrast_ratio(Color::Indexed(231), Color::Black), Some(21.0));
+        let gray = contrast_ratio(Color::Indexed(244), Color::Black).unwrap();
+        assert!((gray - 5.3).abs() < 0.1, "{}", gray);
+        assert_eq!(contrast_ratio(Color::Reset, Color::Black), None);
+    }
+
+    #[test]
+    fn test_accessible_themes_are_readable() {
+        for theme in [Themes::high_contrast(), Themes::deuteranopia(), Themes::protanopia(), Themes::tritanopia()] {
+            let pairs = check(&theme);
+            assert_eq!(pairs.len(), 16);
+            assert!(pairs.iter().all(ColorPair::is_readable), "{}: {:?}", theme.name, pairs);
+        }
+    }
+
+    #[test]
+    fn test_unreadable_pairs_are_found() {
+        let mut theme = Themes::dark();
+        let colors = theme.colors.get_or_insert_with(HashMap::new);
+        colors.insert("background".to_string(), "#303030".to_string());
+        colors.insert("primary".to_string(), "#404040".to_string());
+
+        let low: Vec<&str> = check(&theme).iter().filter(|pair| !pair.is_readable()).map(|pair| pair.name).collect();
+        assert!(low.contains(&"title"));
+        assert!(low.contains(&"muted"));
+        assert!(!low.contains(&"normal"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Directory browser for picking a folder from inside the interface
+//!
+//! Only directories are listed, hidden ones left out, so the list stays
+//! short enough to walk with the arrow keys.
+
+use crate::shared::natural_sort::natural_cmp;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory being browsed, with the subdirectory highlighted in it
+#[derive(Debug, Clone)]
+pub struct DirBrowser {
+    dir: PathBuf,
+    entries: Vec<PathBuf>,
+    selected: usize,
+}
+
+impl DirBrowser {
+    /// Browse `dir`, or the nearest ancestor of it that can be listed
+    pub fn new(dir: &Path) -> Self {
+        let mut browser = Self { dir: PathBuf::new(), entries: Vec::new(), selected: 0 };
+        let start = dir.ancestors().find(|dir| dir.is_dir()).unwrap_or(Path::new("/"));
+        browser.enter(start.to_path_buf());
+        browser
+    }
+
+    /// Directory being browsed
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Subdirectories of the directory, sorted by name without case
+    pub fn entries(&self) -> &[PathBuf] {
+        &self.entries
+    }
+
+    /// Index in `entries` of the highlighted subdirectory
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the highlight by `delta` entries, stopping at the ends
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.entries.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Go into the highlighted subdirectory
+    pub fn open_selected(&mut self) {
+        if let Some(dir) = self.entries.get(self.selected).cloned() {
+            self.enter(dir);
+        }
+    }
+
+    /// Go up to the parent directory, highlighting the one just left
+    pub fn open_parent(&mut self) {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let left = self.dir.clone();
+        self.enter(parent);
+        self.selected = self.entries.iter().position(|entry| *entry == left).unwrap_or(0);
+    }
+
+    fn enter(&mut self, dir: PathBuf) {
+        self.entries = list_dirs(&dir);
+        self.dir = dir;
+        self.selected = 0;
+    }
+}
+
+/// Visible subdirectories of `dir`; none if it cannot be read
+fn list_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort_by(|a, b| {
+        let name = |path: &PathBuf| path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        natural_cmp(&name(a), &name(b))
+    });
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_down_and_back_up() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["b", "A", ".hidden", "b/inner"] {
+            fs::create_dir(root.path().join(dir)).unwrap();
+        }
+        fs::write(root.path().join("song.mp3"), b"").unwrap();
+
+        let mut browser = DirBrowser::new(root.path());
+        assert_eq!(browser.entries(), &[root.path().join("A"), root.path().join("b")]);
+
+        browser.move_by(5);
+        assert_eq!(browser.selected(), 1);
+        browser.open_selected();
+        assert_eq!(browser.dir(), root.path().join("b"));
+        assert_eq!(browser.entries(), &[root.path().join("b/inner")]);
+
+        // Back up with the directory just left highlighted
+        browser.open_parent();
+        assert_eq!(browser.dir(), root.path());
+        assert_eq!(browser.selected(), 1);
+    }
+
+    #[test]
+    fn test_missing_directory_starts_at_an_ancestor() {
+        let root = tempfile::tempdir().unwrap();
+        let browser = DirBrowser::new(&root.path().join("gone/too"));
+        assert_eq!(browser.dir(), root.path());
+    }
+}
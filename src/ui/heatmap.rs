@@ -0,0 +1,157 @@
+//! Calendar heatmap of listening time, shown on the stats screen
+
+use crate::domain::entities::Listen;
+use chrono::{Datelike, Days, NaiveDate, TimeZone};
+use std::collections::HashMap;
+
+/// Number of intensity levels above "not listened at all"
+pub const LEVELS: u8 = 4;
+
+/// Minutes listened per day over the last year, laid out in weeks
+///
+/// Columns are weeks starting on Monday, like GitHub's contribution graph,
+/// with the current week last.
+#[derive(Debug, Clone)]
+pub struct ListeningHeatmap {
+    today: NaiveDate,
+    seconds: HashMap<NaiveDate, u64>,
+    max_minutes: u64,
+}
+
+impl ListeningHeatmap {
+    /// Most weeks the heatmap covers
+    pub const WEEKS: u64 = 53;
+
+    /// First day shown when the heatmap ends on `today`
+    pub fn first_day(today: NaiveDate) -> NaiveDate {
+        let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+        monday - Days::new((Self::WEEKS - 1) * 7)
+    }
+
+    /// Add up the listens per day in `timezone`
+    pub fn from_listens<Tz: TimeZone>(listens: &[Listen], today: NaiveDate, timezone: &Tz) -> Self {
+        let mut seconds: HashMap<NaiveDate, u64> = HashMap::new();
+        for listen in listens {
+            let day = listen.listened_at().with_timezone(timezone).date_naive();
+            *seconds.entry(day).or_default() += listen.played().total_seconds();
+        }
+
+        let max_minutes = seconds.values().map(|seconds| seconds / 60).max().unwrap_or(0);
+        Self { today, seconds, max_minutes }
+    }
+
+    /// Last day shown
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    /// Minutes listened on `day`
+    pub fn minutes(&self, day: NaiveDate) -> u64 {
+        self.seconds.get(&day).copied().unwrap_or(0) / 60
+    }
+
+    /// Intensity of `day` from 0 (nothing) to `LEVELS`, relative to the busiest day
+    pub fn level(&self, day: NaiveDate) -> u8 {
+        let minutes = self.minutes(day);
+        if minutes == 0 {
+            return 0;
+        }
+        (minutes * LEVELS as u64).div_ceil(self.max_minutes).clamp(1, LEVELS as u64) as u8
+    }
+
+    /// Mondays of the last `weeks` weeks, oldest first
+    pub fn week_starts(&self, weeks: u64) -> Vec<NaiveDate> {
+        let last = Self::first_day(self.today) + Days::new((Self::WEEKS - 1) * 7);
+        let weeks = weeks.clamp(1, Self::WEEKS);
+        (0..weeks).rev().map(|week| last - Days::new(week * 7)).collect()
+    }
+
+    /// Total minutes listened from `from` through today
+    pub fn total_minutes_since(&self, from: NaiveDate) -> u64 {
+        let seconds: u64 = self
+            .seconds
+            .iter()
+            .filter(|(day, _)| **day >= from && **day <= self.today)
+            .map(|(_, seconds)| seconds)
+            .sum();
+        seconds / 60
+    }
+
+    /// Days with any listening from `from` through today
+    pub fn active_days_since(&self, from: NaiveDate) -> usize {
+        self.seconds
+            .iter()
+            .filter(|(day, seconds)| **day >= from && **day <= self.today && **seconds >= 60)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::Duration;
+    use chrono::{FixedOffset, Utc};
+
+    fn listen(at: &str, minutes: u64) -> Listen {
+        Listen::new(
+            "/music/song.mp3".to_string(),
+            "Title".to_string(),
+            "Artist".to_string(),
+            "Album".to_string(),
+            Duration::from_seconds(600),
+            Duration::from_seconds(minutes * 60),
+            at.parse::<chrono::DateTime<Utc>>().unwrap(),
+        )
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_listens_are_grouped_by_local_day() {
+        let listens = [
+            listen("2026-03-10T08:00:00Z", 10),
+            listen("2026-03-10T20:00:00Z", 30),
+            listen("2026-03-11T01:00:00Z", 20),
+        ];
+
+        let utc = ListeningHeatmap::from_listens(&listens, date("2026-03-11"), &Utc);
+        assert_eq!(utc.minutes(date("2026-03-10")), 40);
+        assert_eq!(utc.minutes(date("2026-03-11")), 20);
+
+        // The evening listen falls on the next day further east
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let local = ListeningHeatmap::from_listens(&listens, date("2026-03-11"), &tokyo);
+        assert_eq!(local.minutes(date("2026-03-10")), 10);
+        assert_eq!(local.minutes(date("2026-03-11")), 50);
+    }
+
+    #[test]
+    fn test_levels_are_relative_to_busiest_day() {
+        let listens = [
+            listen("2026-03-09T12:00:00Z", 100),
+            listen("2026-03-10T12:00:00Z", 50),
+            listen("2026-03-11T12:00:00Z", 1),
+        ];
+        let heatmap = ListeningHeatmap::from_listens(&listens, date("2026-03-11"), &Utc);
+
+        assert_eq!(heatmap.level(date("2026-03-09")), LEVELS);
+        assert_eq!(heatmap.level(date("2026-03-10")), 2);
+        assert_eq!(heatmap.level(date("2026-03-11")), 1);
+        assert_eq!(heatmap.level(date("2026-03-12")), 0);
+        assert_eq!(heatmap.total_minutes_since(date("2026-03-10")), 51);
+        assert_eq!(heatmap.active_days_since(date("2026-03-01")), 3);
+    }
+
+    #[test]
+    fn test_weeks_start_on_monday_and_end_with_today() {
+        // A Wednesday
+        let heatmap = ListeningHeatmap::from_listens(&[], date("2026-03-11"), &Utc);
+
+        let weeks = heatmap.week_starts(3);
+        assert_eq!(weeks, [date("2026-02-23"), date("2026-03-02"), date("2026-03-09")]);
+        assert_eq!(heatmap.week_starts(100).len() as u64, ListeningHeatmap::WEEKS);
+        assert_eq!(ListeningHeatmap::first_day(date("2026-03-11")), date("2025-03-10"));
+    }
+}
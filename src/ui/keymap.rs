@@ -0,0 +1,595 @@
+//! Key bindings
+//!
+//! Keys are looked up in the layout's `[keybindings]` first, then in the
+//! built-in [`DEFAULT_BINDINGS`]. The help overlay and the control hints are
+//! generated from the same lookup, so they always show the keys that work.
+//!
+//! A binding can be a chord of several keys separated by spaces, like
+//! `"g g"`. Its keys must be typed within the chord timeout of each other.
+//! Digits typed before a binding are a count, as in Vim's `5j`. Keys held
+//! with Ctrl are written with a `ctrl+` prefix, like `"ctrl+r"`.
+
+use crate::t;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Keys bound when the keymap does not say otherwise
+pub const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("q", "quit"),
+    ("tab", "next_panel"),
+    ("backtab", "previous_panel"),
+    ("up", "move_up"),
+    ("down", "move_down"),
+    ("left", "previous_panel"),
+    ("right", "next_panel"),
+    ("h", "previous_panel"),
+    ("j", "move_down"),
+    ("k", "move_up"),
+    ("l", "library"),
+    ("backspace", "library"),
+    ("enter", "select"),
+    ("space", "toggle_play"),
+    ("S", "toggle_shuffle"),
+    ("R", "cycle_repeat"),
+    ("s", "stop"),
+    ("T", "stop_after_current"),
+    ("]", "volume_up"),
+    ("[", "volume_down"),
+    ("}", "next_chapter"),
+    ("{", "previous_chapter"),
+    ("p", "replay_recent"),
+    ("/", "search"),
+    ("c", "clear_search"),
+    ("a", "toggle_art"),
+    ("v", "toggle_visualizer"),
+    ("o", "reveal_file"),
+    ("y", "copy_path"),
+    ("Y", "copy_title"),
+    ("r", "rename_song"),
+    ("*", "toggle_love"),
+    ("i", "show_stats"),
+    ("X", "delete_file"),
+    ("f3", "switch_theme"),
+    ("n", "create_playlist"),
+    ("Q", "save_queue"),
+    ("A", "append_to_queue"),
+    ("ctrl+v", "enqueue_clipboard"),
+    ("Z", "shuffle_queue"),
+    ("x", "remove_from_queue"),
+    ("d k", "remove_queue_above"),
+    ("d j", "remove_queue_below"),
+    ("d c", "clear_queue_after_current"),
+    ("d", "delete_playlist"),
+    ("e", "edit_playlist"),
+    ("+", "add_to_playlist"),
+    ("ctrl+a", "add_results_to_playlist"),
+    ("-", "remove_from_playlist"),
+    ("?", "help"),
+    ("g g", "go_top"),
+    ("G", "go_bottom"),
+    ("d d", "remove_from_playlist"),
+    ("u", "undo"),
+    ("ctrl+r", "redo"),
+    ("P", "song_playlists"),
+    ("b", "add_bookmark"),
+    ("t s", "trim_start"),
+    ("t e", "trim_end"),
+    ("t c", "clear_trim"),
+    (".", "jump_to_playing"),
+    ("F", "toggle_follow"),
+    ("=", "toggle_remaining"),
+    ("N", "do_not_disturb"),
+    ("O", "sort_playlists"),
+    ("U", "rescan"),
+    ("M", "change_music_dir"),
+    ("D", "choose_output"),
+    ("L", "show_logs"),
+    ("ctrl+p", "party_mode"),
+];
+
+/// Actions listed in the help overlay, in order
+pub const HELP_ACTIONS: &[&str] = &[
+    "toggle_play",
+    "stop",
+    "stop_after_current",
+    "next_track",
+    "previous_track",
+    "next_chapter",
+    "previous_chapter",
+    "replay_recent",
+    "volume_up",
+    "volume_down",
+    "toggle_shuffle",
+    "cycle_repeat",
+    "move_up",
+    "move_down",
+    "go_top",
+    "go_bottom",
+    "select",
+    "next_panel",
+    "previous_panel",
+    "library",
+    "jump_to_playing",
+    "toggle_follow",
+    "search",
+    "clear_search",
+    "create_playlist",
+    "save_queue",
+    "append_to_queue",
+    "enqueue_clipboard",
+    "shuffle_queue",
+    "remove_from_queue",
+    "remove_queue_above",
+    "remove_queue_below",
+    "clear_queue_after_current",
+    "edit_playlist",
+    "delete_playlist",
+    "sort_playlists",
+    "add_to_playlist",
+    "add_results_to_playlist",
+    "remove_from_playlist",
+    "undo",
+    "redo",
+    "song_playlists",
+    "add_bookmark",
+    "trim_start",
+    "trim_end",
+    "clear_trim",
+    "toggle_art",
+    "toggle_visualizer",
+    "toggle_remaining",
+    "reveal_file",
+    "copy_path",
+    "copy_title",
+    "rename_song",
+    "toggle_love",
+    "do_not_disturb",
+    "delete_file",
+    "show_stats",
+    "rescan",
+    "change_music_dir",
+    "choose_output",
+    "show_logs",
+    "switch_theme",
+    "party_mode",
+    "help",
+    "quit",
+];
+
+/// Actions that can be bound but are left out of the help overlay
+const UNLISTED_ACTIONS: &[&str] = &["switch_layout", "reload_layout"];
+
+/// Keys written by name rather than as the character they type
+const NAMED_KEYS: &[&str] = &[
+    "space", "enter", "tab", "backtab", "esc", "backspace", "up", "down", "left", "right",
+    "delete", "insert", "home", "end", "pageup", "pagedown",
+];
+
+/// Actions guests may use while party mode is on: finding songs and adding
+/// them to the queue, but nothing that deletes, edits or quits
+const PARTY_ACTIONS: &[&str] = &[
+    "move_up",
+    "move_down",
+    "go_top",
+    "go_bottom",
+    "next_panel",
+    "previous_panel",
+    "library",
+    "select",
+    "search",
+    "clear_search",
+    "jump_to_playing",
+    "append_to_queue",
+    "help",
+    "party_mode",
+];
+
+/// Actions a count repeats
+const REPEATABLE_ACTIONS: &[&str] = &[
+    "move_up",
+    "move_down",
+    "volume_up",
+    "volume_down",
+    "next_track",
+    "next_panel",
+    "previous_panel",
+];
+
+/// Largest count, so a slip of the finger cannot start a long loop
+const MAX_COUNT: usize = 9999;
+
+/// Actions in the control hint line, with the message of their short label
+const HINT_ACTIONS: &[(&str, &str)] = &[
+    ("toggle_play", "hint-play"),
+    ("next_panel", "hint-switch"),
+    ("search", "hint-search"),
+    ("help", "hint-help"),
+    ("quit", "hint-quit"),
+];
+
+/// Name of a key as written in the keymap, e.g. "space", "f1" or "ctrl+r"
+pub fn key_name(key: KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            format!("ctrl+{}", c.to_ascii_lowercase())
+        }
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::BackTab => "backtab".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::F(n) => format!("f{}", n),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Insert => "insert".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Whether `action` is one a key can be bound to
+pub fn is_action(action: &str) -> bool {
+    HELP_ACTIONS.contains(&action) || UNLISTED_ACTIONS.contains(&action)
+}
+
+/// Whether `key` is written the way [`key_name`] names keys, each key of a
+/// chord included
+pub fn is_key(key: &str) -> bool {
+    fn is_single(key: &str) -> bool {
+        if let Some(letter) = key.strip_prefix("ctrl+") {
+            return letter.chars().count() == 1 && letter == letter.to_ascii_lowercase();
+        }
+        key.chars().count() == 1
+            || NAMED_KEYS.contains(&key)
+            || key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()).is_some_and(|n| (1..=24).contains(&n))
+    }
+    !key.is_empty() && key.split(' ').all(is_single)
+}
+
+/// Configured bindings, then the defaults they leave in place
+fn bindings(keybindings: &HashMap<String, String>) -> impl Iterator<Item = (&str, &str)> {
+    let defaults = DEFAULT_BINDINGS
+        .iter()
+        .filter(|(key, _)| !keybindings.contains_key(*key))
+        .copied();
+    keybindings
+        .iter()
+        .map(|(key, action)| (key.as_str(), action.as_str()))
+        .chain(defaults)
+}
+
+/// Action bound to `key`, preferring the configured keybindings
+pub fn action<'a>(keybindings: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+    bindings(keybindings).find(|(bound, _)| *bound == key).map(|(_, action)| action)
+}
+
+/// Keys that trigger `action`, configured ones first
+pub fn keys_for(keybindings: &HashMap<String, String>, action: &str) -> Vec<String> {
+    let mut configured: Vec<&String> = keybindings
+        .iter()
+        .filter(|(_, bound)| *bound == action)
+        .map(|(key, _)| key)
+        .collect();
+    // Single characters read best, so they go first
+    configured.sort_by_key(|key| (key.chars().count(), key.as_str()));
+
+    let defaults = DEFAULT_BINDINGS
+        .iter()
+        .filter(|(key, bound)| *bound == action && !keybindings.contains_key(*key))
+        .map(|(key, _)| key.to_string());
+
+    configured.into_iter().cloned().chain(defaults).collect()
+}
+
+/// Result of typing a key
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChordMatch {
+    /// The keys typed so far complete a binding
+    Action(String),
+    /// The keys typed so far start a longer chord
+    Pending,
+    /// Nothing is bound to the keys
+    Unbound,
+}
+
+/// Whether `action` may be used while party mode is on
+pub fn allowed_in_party(action: &str) -> bool {
+    PARTY_ACTIONS.contains(&action)
+}
+
+/// Whether a count typed before `action` repeats it
+pub fn is_repeatable(action: &str) -> bool {
+    REPEATABLE_ACTIONS.contains(&action)
+}
+
+/// Keys typed so far towards a chord, and the count before it
+#[derive(Debug, Default)]
+pub struct Chords {
+    pending: Vec<String>,
+    last_key: Option<Instant>,
+    count: Option<usize>,
+}
+
+impl Chords {
+    /// Add a typed key to the chord in progress
+    pub fn feed(&mut self, keybindings: &HashMap<String, String>, key: &str, now: Instant, timeout: Duration) -> ChordMatch {
+        if self.expired(now, timeout) {
+            self.clear();
+        }
+
+        // Digits start or extend a count, unless the keymap binds them.
+        // A leading zero is not a count.
+        let digit = key.parse::<usize>().ok().filter(|_| key.len() == 1);
+        if let Some(digit) = digit {
+            if self.pending.is_empty() && !keybindings.contains_key(key) && (digit > 0 || self.count.is_some()) {
+                self.count = Some((self.count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                return ChordMatch::Pending;
+            }
+        }
+
+        let mut keys = self.pending.clone();
+        keys.push(key.to_string());
+        let typed = keys.join(" ");
+        let prefix = format!("{} ", typed);
+
+        if bindings(keybindings).any(|(bound, _)| bound.starts_with(&prefix)) {
+            self.pending = keys;
+            self.last_key = Some(now);
+            return ChordMatch::Pending;
+        }
+
+        let had_pending = !self.pending.is_empty();
+        self.clear();
+        match action(keybindings, &typed) {
+            Some(action) => ChordMatch::Action(action.to_string()),
+            // A key that breaks a chord starts over on its own
+            None if had_pending => self.feed(keybindings, key, now, timeout),
+            None => {
+                self.count = None;
+                ChordMatch::Unbound
+            }
+        }
+    }
+
+    /// Take the count typed before the last action
+    pub fn take_count(&mut self) -> Option<usize> {
+        self.count.take()
+    }
+
+    /// End a chord nobody finished in time
+    ///
+    /// Returns the action bound to the keys typed so far, if any, as with
+    /// `d` when both `d` and `d d` are bound.
+    pub fn expire(&mut self, keybindings: &HashMap<String, String>, now: Instant, timeout: Duration) -> Option<String> {
+        if !self.expired(now, timeout) {
+            return None;
+        }
+        let typed = self.pending.join(" ");
+        self.clear();
+        action(keybindings, &typed).map(str::to_string)
+    }
+
+    /// Count and keys typed towards the next action, e.g. "5" or "g"
+    pub fn typed(&self) -> String {
+        let count = self.count.map(|count| count.to_string()).unwrap_or_default();
+        format!("{}{}", count, self.pending.join(" "))
+    }
+
+    fn expired(&self, now: Instant, timeout: Duration) -> bool {
+        !self.pending.is_empty() && self.last_key.is_some_and(|last| now.duration_since(last) >= timeout)
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.last_key = None;
+    }
+}
+
+/// Key as shown to the user, e.g. "Space", "F1" or "g g"
+pub fn display_key(key: &str) -> String {
+    if key.contains(' ') {
+        return key.split(' ').map(display_key).collect::<Vec<_>>().join(" ");
+    }
+
+    if let Some(key) = key.strip_prefix("ctrl+") {
+        return format!("Ctrl+{}", display_key(key).to_uppercase());
+    }
+
+    match key {
+        "backtab" => "Shift+Tab".to_string(),
+        "pageup" => "PgUp".to_string(),
+        "pagedown" => "PgDn".to_string(),
+        _ if key.chars().count() == 1 => key.to_string(),
+        _ => {
+            let mut chars = key.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Description of an action in the help overlay
+pub fn describe(action: &str) -> String {
+    t!(&format!("action-{}", action.replace('_', "-")))
+}
+
+/// Short hints for the main controls, e.g. "Space:Play/Pause | q:Quit"
+pub fn control_hints(keybindings: &HashMap<String, String>) -> String {
+    HINT_ACTIONS
+        .iter()
+        .filter_map(|(action, label)| {
+            let key = keys_for(keybindings, action).into_iter().next()?;
+            Some(format!("{}:{}", display_key(&key), t!(label)))
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(bindings: &[(&str, &str)]) -> HashMap<String, String> {
+        bindings.iter().map(|(key, action)| (key.to_string(), action.to_string())).collect()
+    }
+
+    #[test]
+    fn test_configured_keys_override_defaults() {
+        let keybindings = keymap(&[("n", "next_track"), ("esc", "quit")]);
+        assert_eq!(action(&keybindings, "n"), Some("next_track"));
+        assert_eq!(action(&keybindings, "S"), Some("toggle_shuffle"));
+        assert_eq!(action(&keybindings, "z"), None);
+
+        assert_eq!(keys_for(&keybindings, "quit"), ["esc", "q"]);
+        // "n" is rebound, so creating a playlist has no key left
+        assert!(keys_for(&keybindings, "create_playlist").is_empty());
+        assert_eq!(keys_for(&keybindings, "next_panel"), ["tab", "right"]);
+    }
+
+    #[test]
+    fn test_chords() {
+        let keybindings = keymap(&[("z z", "go_top"), ("z x c", "quit")]);
+        let timeout = Duration::from_millis(500);
+        let start = Instant::now();
+        let mut chords = Chords::default();
+
+        assert_eq!(chords.feed(&keybindings, "z", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.typed(), "z");
+        assert_eq!(chords.feed(&keybindings, "z", start, timeout), ChordMatch::Action("go_top".into()));
+        assert!(chords.typed().is_empty());
+
+        assert_eq!(chords.feed(&keybindings, "z", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "x", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "c", start, timeout), ChordMatch::Action("quit".into()));
+
+        // A key that does not continue the chord counts on its own
+        assert_eq!(chords.feed(&keybindings, "z", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "j", start, timeout), ChordMatch::Action("move_down".into()));
+        assert_eq!(chords.feed(&keybindings, "#", start, timeout), ChordMatch::Unbound);
+
+        // Too slow: the chord starts over
+        let late = start + timeout;
+        assert_eq!(chords.feed(&keybindings, "z", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "z", late, timeout), ChordMatch::Pending);
+    }
+
+    #[test]
+    fn test_expired_chord_runs_its_prefix() {
+        let keybindings = HashMap::new();
+        let timeout = Duration::from_millis(500);
+        let start = Instant::now();
+        let mut chords = Chords::default();
+
+        // "d" deletes a playlist and "d d" removes a song
+        assert_eq!(chords.feed(&keybindings, "d", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.expire(&keybindings, start, timeout), None);
+        assert_eq!(chords.expire(&keybindings, start + timeout, timeout), Some("delete_playlist".into()));
+        assert!(chords.typed().is_empty());
+
+        assert_eq!(chords.feed(&keybindings, "d", start, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "d", start, timeout), ChordMatch::Action("remove_from_playlist".into()));
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let timeout = Duration::from_millis(500);
+        let now = Instant::now();
+        let mut chords = Chords::default();
+
+        let keybindings = HashMap::new();
+        assert_eq!(chords.feed(&keybindings, "1", now, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "0", now, timeout), ChordMatch::Pending);
+        assert_eq!(chords.typed(), "10");
+        assert_eq!(chords.feed(&keybindings, "j", now, timeout), ChordMatch::Action("move_down".into()));
+        assert_eq!(chords.take_count(), Some(10));
+        assert_eq!(chords.take_count(), None);
+
+        // A count carries over to a chord
+        assert_eq!(chords.feed(&keybindings, "3", now, timeout), ChordMatch::Pending);
+        assert_eq!(chords.feed(&keybindings, "g", now, timeout), ChordMatch::Pending);
+        assert_eq!(chords.typed(), "3g");
+        assert_eq!(chords.feed(&keybindings, "g", now, timeout), ChordMatch::Action("go_top".into()));
+        assert_eq!(chords.take_count(), Some(3));
+
+        // Zero alone, and digits the keymap binds, are plain keys
+        assert_eq!(chords.feed(&keybindings, "0", now, timeout), ChordMatch::Unbound);
+        let keybindings = keymap(&[("1", "toggle_play")]);
+        assert_eq!(chords.feed(&keybindings, "1", now, timeout), ChordMatch::Action("toggle_play".into()));
+        assert_eq!(chords.take_count(), None);
+    }
+
+    #[test]
+    fn test_display_key() {
+        assert_eq!(display_key("g g"), "g g");
+        assert_eq!(display_key("space f1"), "Space F1");
+        assert_eq!(display_key("space"), "Space");
+        assert_eq!(display_key("f1"), "F1");
+        assert_eq!(display_key("backtab"), "Shift+Tab");
+        assert_eq!(display_key("S"), "S");
+        assert_eq!(display_key("ctrl+r"), "Ctrl+R");
+    }
+
+    #[test]
+    fn test_key_name_with_ctrl() {
+        let key = |code, modifiers| key_name(KeyEvent::new(code, modifiers));
+        assert_eq!(key(KeyCode::Char('r'), KeyModifiers::CONTROL).as_deref(), Some("ctrl+r"));
+        assert_eq!(key(KeyCode::Char('R'), KeyModifiers::CONTROL | KeyModifiers::SHIFT).as_deref(), Some("ctrl+r"));
+        assert_eq!(key(KeyCode::Char('R'), KeyModifiers::SHIFT).as_deref(), Some("R"));
+        assert_eq!(key(KeyCode::F(1), KeyModifiers::CONTROL).as_deref(), Some("f1"));
+    }
+
+    #[test]
+    fn test_valid_keys_and_actions() {
+        for key in ["q", "S", "space", "pagedown", "f12", "ctrl+r", "g g", "space f1"] {
+            assert!(is_key(key), "{}", key);
+        }
+        for key in ["", "page_up", "ctrl+R", "ctrl+up", "shift+tab", "f25", "g  g"] {
+            assert!(!is_key(key), "{}", key);
+        }
+
+        assert!(DEFAULT_BINDINGS.iter().all(|(key, action)| is_key(key) && is_action(action)));
+        assert!(is_action("reload_layout"));
+        assert!(!is_action("stop_playback"));
+    }
+
+    #[test]
+    fn test_control_hints_follow_the_keymap() {
+        let keybindings = keymap(&[("p", "toggle_play"), ("x", "quit")]);
+        let hints = control_hints(&keybindings);
+        assert!(hints.starts_with("p:"), "{}", hints);
+        assert!(hints.contains(" | x:"), "{}", hints);
+        assert!(!hints.contains("Space"), "{}", hints);
+    }
+
+    #[test]
+    fn test_every_action_is_described() {
+        for action in HELP_ACTIONS {
+            let id = format!("action-{}", action.replace('_', "-"));
+            assert_ne!(describe(action), id, "{} has no description", action);
+        }
+    }
+
+    #[test]
+    fn test_party_mode_keeps_guests_to_finding_and_queueing() {
+        assert!(allowed_in_party("search"));
+        assert!(allowed_in_party("append_to_queue"));
+        // The only way out is the passphrase
+        assert!(allowed_in_party("party_mode"));
+        for action in ["quit", "delete_playlist", "remove_from_queue", "delete_file", "undo"] {
+            assert!(!allowed_in_party(action), "{} is allowed", action);
+        }
+        assert!(PARTY_ACTIONS.iter().all(|action| HELP_ACTIONS.contains(action)));
+    }
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::widgets::BorderType;
 use std::collections::HashMap;
 use crate::config::layout_config::LayoutConfig;
 use crate::error::Result;
+use crate::ui::progress::ProgressBarStyle;
+use crate::ui::visualizer::VisualizerConfig;
 
 /// Position of a component in the layout
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -56,6 +59,10 @@ pub struct WidgetConfig {
     #[serde(default)]
     pub border: bool,
 
+    /// Line style of the border, when `border` is set
+    #[serde(default)]
+    pub border_style: BorderStyle,
+
     #[serde(default)]
     pub title: Option<String>,
 
@@ -67,6 +74,42 @@ fn default_true() -> bool {
     true
 }
 
+impl WidgetConfig {
+    /// Border to draw around the widget, if any
+    pub fn border_type(&self) -> Option<BorderType> {
+        if self.border {
+            self.border_style.border_type()
+        } else {
+            None
+        }
+    }
+}
+
+/// Line style of a widget border
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    #[default]
+    Rounded,
+    Plain,
+    Double,
+    Thick,
+    None,
+}
+
+impl BorderStyle {
+    /// The ratatui border type, or `None` for no border
+    pub fn border_type(self) -> Option<BorderType> {
+        match self {
+            BorderStyle::Rounded => Some(BorderType::Rounded),
+            BorderStyle::Plain => Some(BorderType::Plain),
+            BorderStyle::Double => Some(BorderType::Double),
+            BorderStyle::Thick => Some(BorderType::Thick),
+            BorderStyle::None => None,
+        }
+    }
+}
+
 /// Available widget types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -80,10 +123,19 @@ pub enum WidgetType {
     AlbumArt,
     VolumeControl,
     SearchBox,
+    /// Today's listening time and track count
+    Stats,
+    /// Peak and RMS level of the left and right channels
+    VuMeter,
+    /// The last few tracks played, numbered for replaying
+    RecentlyPlayed,
 }
 
 /// Widget styling configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// Colors override the theme for a single panel and, like theme styles, may
+/// name a palette color or give a color directly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct WidgetStyle {
     pub fg_color: Option<String>,
     pub bg_color: Option<String>,
@@ -92,18 +144,6 @@ pub struct WidgetStyle {
     pub selected_color: Option<String>,
 }
 
-impl Default for WidgetStyle {
-    fn default() -> Self {
-        Self {
-            fg_color: None,
-            bg_color: None,
-            border_color: None,
-            highlight_color: None,
-            selected_color: None,
-        }
-    }
-}
-
 /// Theme configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeConfig {
@@ -113,7 +153,7 @@ pub struct ThemeConfig {
 }
 
 /// Style configuration for theme elements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StyleConfig {
     pub fg: Option<String>,
     pub bg: Option<String>,
@@ -148,12 +188,33 @@ pub struct LayoutSettings {
 
     #[serde(default)]
     pub responsive: ResponsiveBreakpoints,
+
+    /// How progress bars are drawn
+    #[serde(default)]
+    pub progress_bar: ProgressBarStyle,
+
+    /// Template for the status bar, e.g. "{state} {song} | {volume}"; key
+    /// hints if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_bar: Option<String>,
+
+    /// Milliseconds allowed between the keys of a chord such as "g g"
+    #[serde(default = "default_chord_timeout")]
+    pub chord_timeout_ms: u64,
+
+    /// How the spectrum under the album art is analysed and drawn
+    #[serde(default)]
+    pub visualizer: VisualizerConfig,
 }
 
 fn default_debounce() -> u64 {
     300
 }
 
+fn default_chord_timeout() -> u64 {
+    1000
+}
+
 /// Responsive design breakpoints
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponsiveBreakpoints {
@@ -178,6 +239,10 @@ impl Default for LayoutSettings {
             auto_save: true,
             debounce_ms: 300,
             responsive: ResponsiveBreakpoints::default(),
+            progress_bar: ProgressBarStyle::default(),
+            status_bar: None,
+            chord_timeout_ms: default_chord_timeout(),
+            visualizer: VisualizerConfig::default(),
         }
     }
 }
@@ -236,7 +301,7 @@ impl LayoutEngine {
         let top_widgets = self.get_visible_widgets_by_pos(Position::Top);
         if !top_widgets.is_empty() {
             let (areas, remaining) = self.split_vertical(current_area, &top_widgets, true);
-            for (widget, rect) in top_widgets.iter().zip(areas.into_iter()) {
+            for (widget, rect) in top_widgets.iter().zip(areas) {
                 result.insert(widget.name.clone(), rect);
             }
             current_area = remaining;
@@ -246,7 +311,7 @@ impl LayoutEngine {
         let bottom_widgets = self.get_visible_widgets_by_pos(Position::Bottom);
         if !bottom_widgets.is_empty() {
             let (areas, remaining) = self.split_vertical(current_area, &bottom_widgets, false);
-            for (widget, rect) in bottom_widgets.iter().zip(areas.into_iter()) {
+            for (widget, rect) in bottom_widgets.iter().zip(areas) {
                 result.insert(widget.name.clone(), rect);
             }
             current_area = remaining;
@@ -335,6 +400,7 @@ impl LayoutEngine {
         }
     }
 
+    #[allow(dead_code)]
     pub fn config(&self) -> &LayoutConfig {
         &self.config
     }
@@ -346,9 +412,10 @@ mod tests {
     use crate::config::layout_config::LayoutConfig;
 
     fn create_test_config(widgets: Vec<WidgetConfig>) -> LayoutConfig {
-        let mut config = LayoutConfig::default();
-        config.widgets = widgets;
-        config
+        LayoutConfig {
+            widgets,
+            ..LayoutConfig::default()
+        }
     }
 
     fn create_widget(name: &str, pos: Position, size: SizeConstraint) -> WidgetConfig {
@@ -359,6 +426,7 @@ mod tests {
             size,
             visible: true,
             border: false,
+            border_style: BorderStyle::default(),
             title: None,
             style: WidgetStyle::default(),
         }
@@ -484,4 +552,29 @@ mod tests {
         let result = engine.calculate_layout(area).unwrap();
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_widget_border_type() {
+        let mut w: WidgetConfig = toml::from_str(
+            r#"
+            name = "sidebar"
+            type = "sidebar"
+            position = "left"
+            size = "fill"
+            border = true
+            border_style = "double"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(w.border_type(), Some(BorderType::Double));
+
+        w.border_style = BorderStyle::None;
+        assert_eq!(w.border_type(), None);
+
+        // Rounded unless set, and only when the border is enabled
+        let mut w = create_widget("plain", Position::Left, SizeConstraint::Fill);
+        assert_eq!(w.border_type(), None);
+        w.border = true;
+        assert_eq!(w.border_type(), Some(BorderType::Rounded));
+    }
 }
@@ -1,9 +1,23 @@
 pub mod app;
+pub mod contrast;
+pub mod dir_browser;
 pub mod widgets;
 pub mod layout;
+pub mod oscilloscope;
 pub mod theme;
+pub mod heatmap;
+pub mod keymap;
+pub mod progress;
+pub mod session;
+pub mod setup;
+pub mod status;
+pub mod text;
+pub mod thumbnail;
+pub mod undo;
+pub mod visualizer;
+pub mod vu_meter;
 
-pub use app::{App, InputMode, ActivePanel, ViewMode};
+pub use app::{App, InputMode, ActivePanel, ViewMode, SmartView};
 pub use widgets::draw_ui;
 // Re-exporting these for convenience, even if not all are used in every module
 #[allow(unused_imports)]
@@ -0,0 +1,100 @@
+//! Oscilloscope drawn in braille, the visualizer's other mode
+
+use rodio::Sample;
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+use std::time::Duration;
+
+/// Audio shown across the width of the oscilloscope
+pub const WINDOW: Duration = Duration::from_millis(40);
+
+/// Bit of each dot in a braille cell, by row and then column
+const DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Waveform of the latest samples, oldest on the left, with two columns
+/// and four rows of dots to a cell
+#[derive(Debug, Clone)]
+pub struct Oscilloscope<'a> {
+    samples: &'a [Sample],
+    unicode: bool,
+    style: Style,
+}
+
+impl<'a> Oscilloscope<'a> {
+    /// Draw `samples`, full scale filling the height; without `unicode`
+    /// every cell with a dot in it becomes a `*`
+    pub fn new(samples: &'a [Sample], unicode: bool) -> Self {
+        Self { samples, unicode, style: Style::default() }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Highest and lowest dot row lit in dot column `column` of `columns`,
+    /// out of `rows`; the sample before the column is included so that
+    /// neighbouring columns join up
+    fn span(&self, column: usize, columns: usize, rows: usize) -> (usize, usize) {
+        let len = self.samples.len();
+        let start = column * len / columns;
+        let end = ((column + 1) * len / columns).max(start + 1).min(len);
+        let (low, high) = self.samples[start.saturating_sub(1)..end]
+            .iter()
+            .filter(|sample| !sample.is_nan())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), &sample| (low.min(sample), high.max(sample)));
+        let (low, high) = if low <= high { (low, high) } else { (0.0, 0.0) };
+        let row = |sample: f32| ((1.0 - sample.clamp(-1.0, 1.0)) / 2.0 * (rows - 1) as f32).round() as usize;
+        (row(high), row(low))
+    }
+}
+
+impl Widget for Oscilloscope<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.samples.is_empty() {
+            return;
+        }
+
+        let (columns, rows) = (area.width as usize * 2, area.height as usize * 4);
+        let mut cells = vec![0u8; area.width as usize * area.height as usize];
+        for column in 0..columns {
+            let (top, bottom) = self.span(column, columns, rows);
+            for row in top..=bottom {
+                cells[row / 4 * area.width as usize + column / 2] |= DOTS[row % 4][column % 2];
+            }
+        }
+
+        for (i, &dots) in cells.iter().enumerate().filter(|(_, &dots)| dots != 0) {
+            let symbol = if self.unicode { char::from_u32(0x2800 + dots as u32).unwrap_or('*') } else { '*' };
+            let (x, y) = (area.x + (i % area.width as usize) as u16, area.y + (i / area.width as usize) as u16);
+            buf[(x, y)].set_char(symbol).set_style(self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(samples: &[Sample], unicode: bool, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        Oscilloscope::new(samples, unicode).render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol().to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_is_a_flat_line() {
+        assert_eq!(rows(&[0.0; 100], true, 3, 1), vec!["⠤⠤⠤"]);
+        assert_eq!(rows(&[0.0; 100], true, 2, 2), vec!["  ", "⠉⠉"]);
+        assert_eq!(rows(&[], true, 2, 1), vec!["  "]);
+    }
+
+    #[test]
+    fn test_swing_joins_top_and_bottom() {
+        // Full scale at the top, then a drop to the bottom drawn as a line
+        assert_eq!(rows(&[1.0, -1.0], true, 2, 1), vec!["⠉⣿"]);
+        assert_eq!(rows(&[1.0, -1.0], false, 2, 2), vec!["**", " *"]);
+    }
+}
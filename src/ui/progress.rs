@@ -0,0 +1,224 @@
+//! Text progress bars for playback and scanning, and a spinner for loads
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::Widget,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Shades for the leading cell of a gradient bar, emptiest first
+const GRADIENT_HEAD: [char; 4] = [' ', '░', '▒', '▓'];
+
+/// Frames of the loading spinner
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const ASCII_SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long each spinner frame is shown
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// How progress bars are drawn
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressBarStyle {
+    /// Solid blocks that shade off at the leading edge
+    #[default]
+    Gradient,
+    /// Braille dots on a dotted track, with half-cell precision
+    Braille,
+    /// `=`, `>` and `-`, for terminals without Unicode
+    Ascii,
+}
+
+impl ProgressBarStyle {
+    /// The style to use on a terminal that can or cannot show Unicode
+    pub fn for_terminal(self, unicode: bool) -> Self {
+        if unicode {
+            self
+        } else {
+            ProgressBarStyle::Ascii
+        }
+    }
+
+    /// Text of a bar `width` cells wide filled to `ratio`
+    pub fn bar(self, ratio: f64, width: usize) -> String {
+        let ratio = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+
+        match self {
+            ProgressBarStyle::Gradient => {
+                let steps = GRADIENT_HEAD.len();
+                let filled = (ratio * (width * steps) as f64).round() as usize;
+                let (full, head) = (filled / steps, filled % steps);
+
+                let mut bar = "█".repeat(full);
+                if full < width {
+                    bar.push(GRADIENT_HEAD[head]);
+                    bar.push_str(&" ".repeat(width - full - 1));
+                }
+                bar
+            }
+            ProgressBarStyle::Braille => {
+                let halves = (ratio * (width * 2) as f64).round() as usize;
+                let (full, half) = (halves / 2, halves % 2 == 1);
+
+                let mut bar = "⣿".repeat(full);
+                if full < width {
+                    bar.push(if half { '⡇' } else { '⣀' });
+                    bar.push_str(&"⣀".repeat(width - full - 1));
+                }
+                bar
+            }
+            ProgressBarStyle::Ascii => {
+                let filled = (ratio * width as f64).round() as usize;
+                if filled == 0 || filled == width {
+                    format!("{}{}", "=".repeat(filled), "-".repeat(width - filled))
+                } else {
+                    format!("{}>{}", "=".repeat(filled - 1), "-".repeat(width - filled))
+                }
+            }
+        }
+    }
+}
+
+/// Whether the terminal's locale can show Unicode
+///
+/// Follows the usual precedence of `LC_ALL`, `LC_CTYPE` and `LANG`. Without
+/// any of them Unicode is assumed, as most terminals support it nowadays.
+pub fn unicode_supported() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+/// Spinner frame to show `elapsed` after a load started
+pub fn spinner(elapsed: Duration, unicode: bool) -> char {
+    let frames: &[char] = if unicode { &SPINNER } else { &ASCII_SPINNER };
+    let frame = (elapsed.as_millis() / SPINNER_FRAME.as_millis()) as usize;
+    frames[frame % frames.len()]
+}
+
+/// Progress bar widget drawn on the middle row of its area
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    ratio: f64,
+    bar_style: ProgressBarStyle,
+    style: Style,
+    label: Option<String>,
+    marks: Vec<f64>,
+}
+
+impl ProgressBar {
+    pub fn new(ratio: f64, bar_style: ProgressBarStyle) -> Self {
+        Self {
+            ratio,
+            bar_style,
+            style: Style::default(),
+            label: None,
+            marks: Vec::new(),
+        }
+    }
+
+    /// Colors of the bar, the filled part in the foreground color
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Text shown in the middle of the bar
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Points along the bar to mark with a tick, such as chapter starts,
+    /// from 0 to 1; ticks at either end are left out
+    pub fn marks(mut self, marks: Vec<f64>) -> Self {
+        self.marks = marks;
+        self
+    }
+}
+
+impl Widget for ProgressBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let row = Rect { y: area.y + area.height / 2, height: 1, ..area };
+        let bar = self.bar_style.bar(self.ratio, row.width as usize);
+        Line::styled(bar, self.style).render(row, buf);
+
+        let tick = if self.bar_style == ProgressBarStyle::Ascii { '|' } else { '│' };
+        for mark in self.marks.iter().filter(|mark| **mark > 0.0 && **mark < 1.0) {
+            let x = (mark * row.width as f64).round() as u16;
+            if x > 0 && x < row.width {
+                buf[(row.x + x, row.y)].set_char(tick);
+            }
+        }
+
+        if let Some(label) = self.label {
+            let width = label.chars().count() as u16;
+            let x = row.x + row.width.saturating_sub(width) / 2;
+            buf.set_stringn(x, row.y, &label, row.width as usize, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bars_fill_their_width() {
+        for style in [ProgressBarStyle::Gradient, ProgressBarStyle::Braille, ProgressBarStyle::Ascii] {
+            for ratio in [0.0, 0.33, 0.5, 1.0, 2.0, f64::NAN] {
+                assert_eq!(style.bar(ratio, 10).chars().count(), 10, "{:?} at {}", style, ratio);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bar_text() {
+        assert_eq!(ProgressBarStyle::Gradient.bar(0.5, 4), "██  ");
+        assert_eq!(ProgressBarStyle::Gradient.bar(0.375, 4), "█▒  ");
+        assert_eq!(ProgressBarStyle::Braille.bar(0.375, 4), "⣿⡇⣀⣀");
+        assert_eq!(ProgressBarStyle::Ascii.bar(0.5, 6), "==>---");
+        assert_eq!(ProgressBarStyle::Ascii.bar(0.0, 3), "---");
+        assert_eq!(ProgressBarStyle::Ascii.bar(1.0, 3), "===");
+    }
+
+    #[test]
+    fn test_marks_tick_the_bar() {
+        let area = Rect::new(0, 0, 8, 1);
+        let mut buf = Buffer::empty(area);
+        ProgressBar::new(0.5, ProgressBarStyle::Ascii).marks(vec![0.0, 0.25, 0.75, 1.0]).render(area, &mut buf);
+        let row: String = (0..8).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert_eq!(row, "==|>--|-");
+    }
+
+    #[test]
+    fn test_ascii_without_unicode() {
+        assert_eq!(ProgressBarStyle::Braille.for_terminal(false), ProgressBarStyle::Ascii);
+        assert_eq!(ProgressBarStyle::Braille.for_terminal(true), ProgressBarStyle::Braille);
+    }
+
+    #[test]
+    fn test_spinner_turns() {
+        assert_eq!(spinner(Duration::ZERO, true), '⠋');
+        assert_eq!(spinner(SPINNER_FRAME, true), '⠙');
+        assert_eq!(spinner(SPINNER_FRAME * 10, true), '⠋');
+        assert_eq!(spinner(SPINNER_FRAME * 5, false), '/');
+    }
+}
@@ -0,0 +1,134 @@
+//! Session restore
+//!
+//! Where the player was left, its view, panel, selections and search, is
+//! saved on quit and put back at the next start. The song list scrolls just
+//! far enough to keep the selection in view, so putting the selection back
+//! puts the scroll back too. The session is disposable: one that cannot be
+//! read starts the player fresh.
+
+use crate::config::paths::{self, Location};
+use crate::error::{LofiTurtleError, Result};
+use crate::services::LibraryService;
+use crate::ui::app::{ActivePanel, ViewMode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the player was left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub view: ViewMode,
+    pub active_panel: ActivePanel,
+    pub selected_playlist_index: usize,
+    /// Path of the selected song, which is found again even if songs were
+    /// added before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_song: Option<String>,
+    /// Position of the selected song, for when it is gone
+    #[serde(default)]
+    pub selected_song_index: usize,
+    #[serde(default)]
+    pub search_query: String,
+}
+
+impl Session {
+    /// Get the path to the session file
+    pub fn session_path() -> PathBuf {
+        paths::default_path(Location::State, "session.json")
+    }
+
+    /// Load the session left by the last run, if there is one
+    pub fn load() -> Option<Self> {
+        Self::load_from(&Self::session_path())
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content)
+            .inspect_err(|e| log::warn!("Failed to parse session file, starting fresh: {}", e))
+            .ok()
+    }
+
+    /// The view to reopen, which is the library if the playlist it showed
+    /// has been deleted since, from the command line or by a sync
+    pub fn restored_view(&self, library: &LibraryService) -> ViewMode {
+        match &self.view {
+            ViewMode::Playlist(name) if !matches!(library.get_playlist_by_name(name), Ok(Some(_))) => {
+                ViewMode::Library
+            }
+            view => view.clone(),
+        }
+    }
+
+    /// Save the session for the next run
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::session_path())
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| LofiTurtleError::Configuration(format!("Failed to serialize session: {}", e)))?;
+
+        // The state directory is not created at startup
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| LofiTurtleError::Configuration(format!("Failed to save session: {}", e)))?;
+        }
+        fs::write(path, content)
+            .map_err(|e| LofiTurtleError::Configuration(format!("Failed to save session: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::app::SmartView;
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("session.json");
+        assert_eq!(Session::load_from(&path), None);
+
+        let session = Session {
+            view: ViewMode::Playlist("Late Night".to_string()),
+            active_panel: ActivePanel::Playlists,
+            selected_playlist_index: 6,
+            selected_song: Some("/music/feather.mp3".to_string()),
+            selected_song_index: 12,
+            search_query: "nujabes".to_string(),
+        };
+        session.save_to(&path).unwrap();
+        assert_eq!(Session::load_from(&path), Some(session));
+
+        let smart = Session { view: ViewMode::Smart(SmartView::DailyMix), ..Session::load_from(&path).unwrap() };
+        smart.save_to(&path).unwrap();
+        assert_eq!(Session::load_from(&path).unwrap().view, ViewMode::Smart(SmartView::DailyMix));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(Session::load_from(&path), None);
+    }
+
+    #[test]
+    fn test_restored_view() {
+        let library = LibraryService::new_in_memory().unwrap();
+        library.create_playlist("Late Night", None).unwrap();
+
+        let session = Session {
+            view: ViewMode::Playlist("Late Night".to_string()),
+            active_panel: ActivePanel::Songs,
+            selected_playlist_index: 0,
+            selected_song: None,
+            selected_song_index: 0,
+            search_query: String::new(),
+        };
+        assert_eq!(session.restored_view(&library), ViewMode::Playlist("Late Night".to_string()));
+
+        // A playlist deleted since reopens the library
+        let deleted = Session { view: ViewMode::Playlist("Gone".to_string()), ..session.clone() };
+        assert_eq!(deleted.restored_view(&library), ViewMode::Library);
+
+        let smart = Session { view: ViewMode::Smart(SmartView::DailyMix), ..session };
+        assert_eq!(smart.restored_view(&library), ViewMode::Smart(SmartView::DailyMix));
+    }
+}
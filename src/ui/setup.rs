@@ -0,0 +1,65 @@
+//! First-run setup, shown when no music directory has been chosen and the
+//! library is empty, instead of scanning a default folder nobody picked
+//!
+//! The wizard asks for the music directory, then a theme, which is
+//! previewed as it is highlighted, and finally runs the first scan.
+
+use crate::services::scan_job::ScanOutcome;
+use crate::ui::dir_browser::DirBrowser;
+use std::path::{Path, PathBuf};
+
+/// Step the setup wizard is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+    Directory,
+    Theme,
+    Scan,
+}
+
+impl SetupStep {
+    /// Position of the step, counting from one
+    pub fn number(self) -> usize {
+        match self {
+            SetupStep::Directory => 1,
+            SetupStep::Theme => 2,
+            SetupStep::Scan => 3,
+        }
+    }
+}
+
+/// Number of steps of the wizard
+pub const SETUP_STEPS: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct SetupWizard {
+    pub step: SetupStep,
+    pub browser: DirBrowser,
+    /// Index in `Themes::all()` of the highlighted theme
+    pub theme: usize,
+    /// How the first scan went, once it is over
+    pub outcome: Option<ScanOutcome>,
+}
+
+impl SetupWizard {
+    pub fn new(music_dir: &Path, theme: usize) -> Self {
+        Self {
+            step: SetupStep::Directory,
+            browser: DirBrowser::new(&start_dir(music_dir)),
+            theme,
+            outcome: None,
+        }
+    }
+}
+
+/// Where browsing starts: the default music directory if there is one,
+/// otherwise the home directory
+fn start_dir(music_dir: &Path) -> PathBuf {
+    if music_dir.is_dir() {
+        return music_dir.to_path_buf();
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("/"))
+}
@@ -0,0 +1,123 @@
+//! Status bar format templates
+//!
+//! A template is text with `{placeholder}` fields, e.g.
+//! `"{state} {song}  {position}  {volume}"`. Placeholders the player does not
+//! know are shown as written.
+
+/// Value a placeholder stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusField {
+    /// Playing, paused or stopped icon
+    State,
+    /// "Artist - Title" of the current song
+    Song,
+    /// Elapsed and total time
+    Position,
+    /// Volume with an icon
+    Volume,
+    /// Shuffle icon
+    Shuffle,
+    /// Repeat mode icon
+    Repeat,
+    /// Files scanned so far, while a scan runs
+    Scan,
+    /// Key hints
+    Hints,
+    /// Listens waiting to be scrobbled, if any
+    Scrobbles,
+    /// Playlists the current song is in, if any
+    Playlists,
+    /// Notice that playback stops after the current song, when armed
+    StopAfter,
+    /// Notice that party mode is on
+    Party,
+}
+
+impl StatusField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "state" => Some(StatusField::State),
+            "song" => Some(StatusField::Song),
+            "position" => Some(StatusField::Position),
+            "volume" => Some(StatusField::Volume),
+            "shuffle" => Some(StatusField::Shuffle),
+            "repeat" => Some(StatusField::Repeat),
+            "scan" => Some(StatusField::Scan),
+            "hints" => Some(StatusField::Hints),
+            "scrobbles" => Some(StatusField::Scrobbles),
+            "playlists" => Some(StatusField::Playlists),
+            "stop_after" => Some(StatusField::StopAfter),
+            "party" => Some(StatusField::Party),
+            _ => None,
+        }
+    }
+}
+
+/// Piece of a parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Field(StatusField),
+}
+
+/// Split a template into text and placeholders
+pub fn parse(template: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let end = start + length;
+
+        match StatusField::from_name(&rest[start + 1..end]) {
+            Some(field) => {
+                if start > 0 {
+                    segments.push(Segment::Text(&rest[..start]));
+                }
+                segments.push(Segment::Field(field));
+            }
+            None => segments.push(Segment::Text(&rest[..=end])),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse("{state} {song} | {volume}"),
+            [
+                Segment::Field(StatusField::State),
+                Segment::Text(" "),
+                Segment::Field(StatusField::Song),
+                Segment::Text(" | "),
+                Segment::Field(StatusField::Volume),
+            ]
+        );
+        assert_eq!(parse(""), []);
+    }
+
+    #[test]
+    fn test_unknown_placeholders_stay_text() {
+        assert_eq!(
+            parse("{bpm} {scan} {oops"),
+            [
+                Segment::Text("{bpm}"),
+                Segment::Text(" "),
+                Segment::Field(StatusField::Scan),
+                Segment::Text(" {oops"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Fitting text into terminal cells
+//!
+//! CJK characters and most emoji take two cells, so lengths are measured in
+//! display width rather than chars or bytes.
+
+use ratatui::text::Span;
+use std::borrow::Cow;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Marks text that was cut short
+const ELLIPSIS: char = '…';
+
+/// Number of cells `text` takes up
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Cut `text` to at most `max_width` cells, ending in an ellipsis if shortened
+pub fn truncate(text: &str, max_width: usize) -> Cow<'_, str> {
+    if text.width() <= max_width {
+        return Cow::Borrowed(text);
+    }
+    if max_width == 0 {
+        return Cow::Borrowed("");
+    }
+
+    // Leave a cell for the ellipsis
+    let mut width = 0;
+    let mut end = 0;
+    for (index, c) in text.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > max_width - 1 {
+            break;
+        }
+        width += char_width;
+        end = index + c.len_utf8();
+    }
+
+    Cow::Owned(format!("{}{}", &text[..end], ELLIPSIS))
+}
+
+/// Truncate or pad `text` with spaces to exactly `width` cells
+pub fn fit(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+/// Cut a line of spans to at most `max_width` cells
+///
+/// Spans past the limit are dropped and the one crossing it is truncated.
+pub fn truncate_spans(spans: Vec<Span<'_>>, max_width: usize) -> Vec<Span<'_>> {
+    let mut remaining = max_width;
+    let mut fitted = Vec::with_capacity(spans.len());
+
+    for span in spans {
+        let width = span.content.width();
+        if width <= remaining {
+            remaining -= width;
+            fitted.push(span);
+        } else {
+            let content = truncate(&span.content, remaining).into_owned();
+            if !content.is_empty() {
+                fitted.push(Span::styled(content, span.style));
+            }
+            break;
+        }
+    }
+
+    fitted
+}
+
+/// `text` without emoji, arrows and other symbols, for screen readers that
+/// would spell them out
+///
+/// Letters of any script, digits and punctuation are kept.
+pub fn plain(text: &str) -> String {
+    let kept: String = text
+        .chars()
+        .map(|c| {
+            let readable = c.is_alphanumeric()
+                || c.is_ascii_punctuation()
+                || matches!(c, '…' | '·' | '–' | '—' | '‘' | '’' | '“' | '”');
+            if readable { c } else { ' ' }
+        })
+        .collect();
+    kept.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_counts_wide_characters() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("夜に駆ける"), 10);
+        assert_eq!(display_width("🎵 Lofi"), 7);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Feather", 10), "Feather");
+        assert_eq!(truncate("Feather", 7), "Feather");
+        assert_eq!(truncate("Feather", 5), "Feat…");
+        assert_eq!(truncate("Feather", 0), "");
+        // A wide character that does not fit whole is left out
+        assert_eq!(truncate("夜に駆ける", 6), "夜に…");
+        assert_eq!(truncate("夜に駆ける", 5), "夜に…");
+        assert_eq!(truncate("夜に駆ける", 1), "…");
+    }
+
+    #[test]
+    fn test_fit_pads_to_width() {
+        assert_eq!(fit("夜に", 6), "夜に  ");
+        assert_eq!(fit("夜に駆ける", 6), "夜に… ");
+        assert_eq!(display_width(&fit("夜に駆ける", 6)), 6);
+    }
+
+    #[test]
+    fn test_truncate_spans() {
+        let spans = vec![Span::raw("🎵 "), Span::raw("Aruarian Dance"), Span::raw(" - Nujabes")];
+        let fitted = truncate_spans(spans, 12);
+        let text: Vec<&str> = fitted.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, ["🎵 ", "Aruarian…"]);
+
+        let text: String = truncate_spans(vec![Span::raw("ab"), Span::raw("cd")], 3)
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "ab…");
+    }
+
+    #[test]
+    fn test_plain_drops_symbols() {
+        assert_eq!(plain("🐢 Lofi Turtle Library"), "Lofi Turtle Library");
+        assert_eq!(plain("⚠️ Không thể tạo thư mục: /nhạc"), "Không thể tạo thư mục: /nhạc");
+        assert_eq!(plain(" Playlists ↓ Newest ⠋"), "Playlists Newest");
+        assert_eq!(plain("夜に駆ける (3:41) · 2…"), "夜に駆ける (3:41) · 2…");
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::style::{Color, Style, Modifier};
 use std::collections::HashMap;
-use crate::ui::layout::ThemeConfig;
+use crate::ui::layout::{StyleConfig, ThemeConfig, WidgetStyle};
 
 /// Color palette for the application
 #[derive(Debug, Clone)]
@@ -59,9 +59,9 @@ impl ColorPalette {
             "reset" => Some(Color::Reset),
             _ => {
                 // Try to parse as RGB hex color
-                if color_str.starts_with('#') {
+                if let Some(stripped) = color_str.strip_prefix('#') {
                     let hex = if color_str.len() == 7 {
-                        &color_str[1..]
+                        stripped
                     } else if color_str.len() == 4 {
                         // Handle short hex #RGB -> #RRGGBB (not implemented here for simplicity, but good to know)
                         // For now just standard 6-digit hex
@@ -87,6 +87,7 @@ impl ColorPalette {
     }
 
     /// Helper to get color from Option<String> or fallback
+    #[allow(dead_code)]
     pub fn get_color_from_option(color_str: Option<&String>, fallback: Color) -> Color {
         if let Some(s) = color_str {
             Self::parse_color(s).unwrap_or(fallback)
@@ -97,16 +98,26 @@ impl ColorPalette {
 }
 
 /// Theme manager for styling UI components
+///
+/// Every style is built from the theme's colors and can then be adjusted by
+/// an entry of the same name in the theme's `styles` table, e.g.
+///
+/// ```toml
+/// [theme.styles.playing]
+/// fg = "success"
+/// bold = true
+/// italic = true
+/// ```
+///
+/// Style colors may name a palette color or give a color directly.
 #[derive(Debug, Clone)]
 pub struct ThemeManager {
     palette: ColorPalette,
-    #[allow(dead_code)]
     theme_config: ThemeConfig,
 }
 
 impl ThemeManager {
     /// Create a new theme manager
-    #[allow(dead_code)]
     pub fn new(theme_config: ThemeConfig) -> Self {
         let palette = ColorPalette::from_theme(&theme_config);
         Self {
@@ -127,91 +138,191 @@ impl ThemeManager {
         &self.theme_config
     }
 
+    /// Get a palette color with fallback
+    pub fn color(&self, name: &str, fallback: Color) -> Color {
+        self.palette.get_or(name, fallback)
+    }
+
+    /// Apply the theme's `styles` entry called `name` on top of `base`
+    ///
+    /// Settings left out of the entry keep their value from `base`.
+    pub fn style(&self, name: &str, base: Style) -> Style {
+        let Some(config) = self.theme_config.styles.as_ref().and_then(|styles| styles.get(name)) else {
+            return base;
+        };
+
+        let mut style = base;
+        if let Some(fg) = config.fg.as_deref().and_then(|fg| self.resolve_color(name, fg)) {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = config.bg.as_deref().and_then(|bg| self.resolve_color(name, bg)) {
+            style = style.bg(bg);
+        }
+
+        for (enabled, modifier) in [
+            (config.bold, Modifier::BOLD),
+            (config.italic, Modifier::ITALIC),
+            (config.underline, Modifier::UNDERLINED),
+        ] {
+            style = match enabled {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+
+        style
+    }
+
+    /// Theme for a single panel, with its widget style layered on top
+    ///
+    /// `fg_color` sets the text, `bg_color` the panel background,
+    /// `border_color` the border when the panel is not focused,
+    /// `highlight_color` the playing track and `selected_color` the
+    /// selection.
+    pub fn for_widget(&self, widget: &WidgetStyle) -> ThemeManager {
+        let mut theme = self.clone();
+        let styles = theme.theme_config.styles.get_or_insert_with(HashMap::new);
+        let overrides = [
+            ("text", &widget.fg_color, false),
+            ("panel", &widget.bg_color, true),
+            ("border", &widget.border_color, false),
+            ("playing", &widget.highlight_color, false),
+            ("selected", &widget.selected_color, false),
+        ];
+
+        for (name, color, background) in overrides {
+            let Some(color) = color else { continue };
+            let style = styles.entry(name.to_string()).or_default();
+            if background {
+                style.bg = Some(color.clone());
+            } else {
+                style.fg = Some(color.clone());
+            }
+        }
+
+        theme
+    }
+
+    /// Look a style color up in the palette, or parse it as a color
+    fn resolve_color(&self, style: &str, color: &str) -> Option<Color> {
+        let resolved = self.palette.get(color).or_else(|| ColorPalette::parse_color(color));
+        if resolved.is_none() {
+            log::warn!("Invalid color '{}' for theme style '{}'", color, style);
+        }
+        resolved
+    }
+
     /// Create a style for normal text
-    #[allow(dead_code)]
     pub fn normal_style(&self) -> Style {
-        Style::default()
+        self.style("normal", Style::default()
             .fg(self.palette.get_or("foreground", Color::White))
-            .bg(self.palette.get_or("background", Color::Black))
+            .bg(self.palette.get_or("background", Color::Black)))
     }
 
     /// Create a style for highlighted text
-    #[allow(dead_code)]
     pub fn highlight_style(&self) -> Style {
-        Style::default()
+        self.style("highlight", Style::default()
             .fg(self.palette.get_or("highlight", Color::Cyan))
             .bg(self.palette.get_or("background", Color::Black))
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::BOLD))
     }
 
-    /// Create a style for selected items
-    #[allow(dead_code)]
+    /// Create a style for the selected item of a list
     pub fn selected_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("background", Color::Black))
-            .bg(self.palette.get_or("primary", Color::Cyan))
-            .add_modifier(Modifier::BOLD)
+        self.style("selected", Style::default()
+            .fg(self.palette.get_or("primary", Color::Cyan))
+            .add_modifier(Modifier::BOLD))
+    }
+
+    /// Create a style for the background of a panel
+    pub fn panel_style(&self) -> Style {
+        self.style("panel", Style::default())
+    }
+
+    /// Create a style for notices that need attention, like pending scrobbles
+    pub fn warning_style(&self) -> Style {
+        self.style("warning", Style::default()
+            .fg(self.palette.get_or("warning", Color::Yellow)))
+    }
+
+    /// Create a style for list entries and other plain text
+    pub fn text_style(&self) -> Style {
+        self.style("text", Style::default().fg(Color::Gray))
+    }
+
+    /// Create a style for secondary details such as durations and placeholders
+    pub fn muted_style(&self) -> Style {
+        self.style("muted", Style::default().fg(Color::DarkGray))
+    }
+
+    /// Create a style for key hints
+    pub fn hint_style(&self) -> Style {
+        self.style("hint", Style::default()
+            .fg(self.palette.get_or("border", Color::DarkGray))
+            .add_modifier(Modifier::ITALIC))
     }
 
     /// Create a style for borders
-    #[allow(dead_code)]
     pub fn border_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("border", Color::Gray))
+        self.style("border", Style::default()
+            .fg(self.palette.get_or("border", Color::DarkGray)))
+    }
+
+    /// Create a style for the border of the focused panel
+    pub fn active_border_style(&self) -> Style {
+        self.style("active_border", Style::default()
+            .fg(self.palette.get_or("primary", Color::Cyan))
+            .add_modifier(Modifier::BOLD))
     }
 
     /// Create a style for titles
-    #[allow(dead_code)]
     pub fn title_style(&self) -> Style {
-        Style::default()
+        self.style("title", Style::default()
             .fg(self.palette.get_or("primary", Color::Cyan))
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::BOLD))
     }
 
     /// Create a style for playing track
-    #[allow(dead_code)]
     pub fn playing_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("playing", Color::Green))
-            .add_modifier(Modifier::BOLD)
+        let highlight = self.palette.get_or("highlight", Color::Yellow);
+        self.style("playing", Style::default()
+            .fg(self.palette.get_or("playing", highlight))
+            .add_modifier(Modifier::BOLD))
     }
 
     /// Create a style for paused track
-    #[allow(dead_code)]
     pub fn paused_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("paused", Color::Yellow))
+        self.style("paused", Style::default()
+            .fg(self.palette.get_or("paused", Color::Yellow)))
     }
 
     /// Create a style for progress bar
-    #[allow(dead_code)]
     pub fn progress_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("progress", Color::Blue))
-            .bg(self.palette.get_or("background", Color::Black))
+        let primary = self.palette.get_or("primary", Color::Cyan);
+        self.style("progress", Style::default()
+            .fg(self.palette.get_or("progress", primary))
+            .bg(Color::DarkGray))
     }
 
     /// Create a style for error messages
-    #[allow(dead_code)]
     pub fn error_style(&self) -> Style {
-        Style::default()
+        self.style("error", Style::default()
             .fg(self.palette.get_or("error", Color::Red))
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::BOLD))
     }
 
     /// Create a style for success messages
-    #[allow(dead_code)]
     pub fn success_style(&self) -> Style {
-        Style::default()
+        self.style("success", Style::default()
             .fg(self.palette.get_or("success", Color::Green))
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::BOLD))
     }
 
-    /// Create a style for secondary text
-    #[allow(dead_code)]
+    /// Create a style for secondary text and icons
     pub fn secondary_style(&self) -> Style {
-        Style::default()
-            .fg(self.palette.get_or("secondary", Color::Yellow))
+        self.style("secondary", Style::default()
+            .fg(self.palette.get_or("secondary", Color::Magenta)))
     }
 
     /// Create a custom style with specific colors
@@ -235,7 +346,6 @@ impl ThemeManager {
     }
 
     /// Update theme configuration
-    #[allow(dead_code)]
     pub fn update_theme(&mut self, theme_config: ThemeConfig) {
         self.theme_config = theme_config;
         self.palette = ColorPalette::from_theme(&self.theme_config);
@@ -378,6 +488,109 @@ impl Themes {
         }
     }
 
+    /// High-contrast theme: bright colors on black, with plain text and
+    /// details in white and light gray instead of the usual grays
+    pub fn high_contrast() -> ThemeConfig {
+        let mut colors = HashMap::new();
+        colors.insert("primary".to_string(), "bright_yellow".to_string());
+        colors.insert("secondary".to_string(), "bright_cyan".to_string());
+        colors.insert("background".to_string(), "black".to_string());
+        colors.insert("foreground".to_string(), "white".to_string());
+        colors.insert("border".to_string(), "white".to_string());
+        colors.insert("highlight".to_string(), "bright_yellow".to_string());
+        colors.insert("warning".to_string(), "bright_yellow".to_string());
+        colors.insert("error".to_string(), "bright_red".to_string());
+        colors.insert("success".to_string(), "bright_green".to_string());
+        colors.insert("playing".to_string(), "bright_green".to_string());
+        colors.insert("paused".to_string(), "bright_yellow".to_string());
+        colors.insert("progress".to_string(), "white".to_string());
+
+        let fg = |color: &str| StyleConfig { fg: Some(color.to_string()), ..StyleConfig::default() };
+        let styles = HashMap::from([
+            ("text".to_string(), fg("foreground")),
+            ("muted".to_string(), fg("gray")),
+            progress_on_black(),
+        ]);
+
+        ThemeConfig {
+            name: "high_contrast".to_string(),
+            colors: Some(colors),
+            styles: Some(styles),
+        }
+    }
+
+    /// Theme for red-green color blindness with weak green (deuteranopia):
+    /// blues and oranges instead of greens and reds
+    pub fn deuteranopia() -> ThemeConfig {
+        let mut colors = HashMap::new();
+        colors.insert("primary".to_string(), "#56b4e9".to_string()); // Sky blue
+        colors.insert("secondary".to_string(), "#e69f00".to_string()); // Orange
+        colors.insert("background".to_string(), "black".to_string());
+        colors.insert("foreground".to_string(), "#f0f0f0".to_string());
+        colors.insert("border".to_string(), "#8a8a8a".to_string());
+        colors.insert("highlight".to_string(), "#f0e442".to_string()); // Yellow
+        colors.insert("warning".to_string(), "#e69f00".to_string());
+        colors.insert("error".to_string(), "#d55e00".to_string()); // Vermillion
+        colors.insert("success".to_string(), "#56b4e9".to_string());
+        colors.insert("playing".to_string(), "#56b4e9".to_string());
+        colors.insert("paused".to_string(), "#f0e442".to_string());
+        colors.insert("progress".to_string(), "#56b4e9".to_string());
+
+        ThemeConfig {
+            name: "deuteranopia".to_string(),
+            colors: Some(colors),
+            styles: Some(HashMap::from([progress_on_black()])),
+        }
+    }
+
+    /// Theme for red-green color blindness with weak red (protanopia), to
+    /// whom reds look dark: blues, bluish green, orange and yellow
+    pub fn protanopia() -> ThemeConfig {
+        let mut colors = HashMap::new();
+        colors.insert("primary".to_string(), "#56b4e9".to_string()); // Sky blue
+        colors.insert("secondary".to_string(), "#f0e442".to_string()); // Yellow
+        colors.insert("background".to_string(), "black".to_string());
+        colors.insert("foreground".to_string(), "#f0f0f0".to_string());
+        colors.insert("border".to_string(), "#8a8a8a".to_string());
+        colors.insert("highlight".to_string(), "#ffffff".to_string());
+        colors.insert("warning".to_string(), "#f0e442".to_string());
+        colors.insert("error".to_string(), "#e69f00".to_string()); // Orange
+        colors.insert("success".to_string(), "#009e73".to_string()); // Bluish green
+        colors.insert("playing".to_string(), "#56b4e9".to_string());
+        colors.insert("paused".to_string(), "#f0e442".to_string());
+        colors.insert("progress".to_string(), "#56b4e9".to_string());
+
+        ThemeConfig {
+            name: "protanopia".to_string(),
+            colors: Some(colors),
+            styles: Some(HashMap::from([progress_on_black()])),
+        }
+    }
+
+    /// Theme for blue-yellow color blindness (tritanopia): reds, pinks and
+    /// teal instead of blues and yellows
+    pub fn tritanopia() -> ThemeConfig {
+        let mut colors = HashMap::new();
+        colors.insert("primary".to_string(), "#ff7f7f".to_string()); // Light red
+        colors.insert("secondary".to_string(), "#00c0c0".to_string()); // Teal
+        colors.insert("background".to_string(), "black".to_string());
+        colors.insert("foreground".to_string(), "#f0f0f0".to_string());
+        colors.insert("border".to_string(), "#8a8a8a".to_string());
+        colors.insert("highlight".to_string(), "#ffffff".to_string());
+        colors.insert("warning".to_string(), "#cc79a7".to_string()); // Pink
+        colors.insert("error".to_string(), "#ff4040".to_string()); // Red
+        colors.insert("success".to_string(), "#00c0c0".to_string());
+        colors.insert("playing".to_string(), "#00c0c0".to_string());
+        colors.insert("paused".to_string(), "#cc79a7".to_string());
+        colors.insert("progress".to_string(), "#00c0c0".to_string());
+
+        ThemeConfig {
+            name: "tritanopia".to_string(),
+            colors: Some(colors),
+            styles: Some(HashMap::from([progress_on_black()])),
+        }
+    }
+
     /// Get all available themes
     pub fn all() -> Vec<ThemeConfig> {
         vec![
@@ -387,11 +600,14 @@ impl Themes {
             Self::forest(),
             Self::dracula(),
             Self::gruvbox(),
+            Self::high_contrast(),
+            Self::deuteranopia(),
+            Self::protanopia(),
+            Self::tritanopia(),
         ]
     }
 
     /// Get theme by name
-    #[allow(dead_code)]
     pub fn get_by_name(name: &str) -> Option<ThemeConfig> {
         match name.to_lowercase().as_str() {
             "dark" => Some(Self::dark()),
@@ -400,11 +616,26 @@ impl Themes {
             "forest" => Some(Self::forest()),
             "dracula" => Some(Self::dracula()),
             "gruvbox" => Some(Self::gruvbox()),
+            "high_contrast" | "high-contrast" => Some(Self::high_contrast()),
+            "deuteranopia" => Some(Self::deuteranopia()),
+            "protanopia" => Some(Self::protanopia()),
+            "tritanopia" => Some(Self::tritanopia()),
             _ => None,
         }
     }
 }
 
+/// Progress bar drawn on black rather than dark gray, against which light
+/// accents hardly stand out
+fn progress_on_black() -> (String, StyleConfig) {
+    let style = StyleConfig {
+        fg: Some("progress".to_string()),
+        bg: Some("black".to_string()),
+        ..StyleConfig::default()
+    };
+    ("progress".to_string(), style)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,10 +663,77 @@ mod tests {
         assert!(highlight.add_modifier.contains(Modifier::BOLD));
     }
 
+    #[test]
+    fn test_theme_styles() {
+        let mut theme = Themes::dark();
+        let mut styles = HashMap::new();
+        styles.insert("title".to_string(), StyleConfig {
+            fg: Some("success".to_string()),
+            bg: Some("#102030".to_string()),
+            bold: Some(false),
+            italic: Some(true),
+            underline: Some(true),
+        });
+        styles.insert("muted".to_string(), StyleConfig {
+            fg: Some("not-a-color".to_string()),
+            bg: None,
+            bold: Some(true),
+            italic: None,
+            underline: None,
+        });
+        theme.styles = Some(styles);
+        let manager = ThemeManager::new(theme);
+
+        // Palette names and literal colors both work, and modifiers can be removed
+        let title = manager.title_style();
+        assert_eq!(title.fg, Some(Color::Green));
+        assert_eq!(title.bg, Some(Color::Rgb(0x10, 0x20, 0x30)));
+        assert!(!title.add_modifier.contains(Modifier::BOLD));
+        assert!(title.sub_modifier.contains(Modifier::BOLD));
+        assert!(title.add_modifier.contains(Modifier::ITALIC | Modifier::UNDERLINED));
+
+        // Invalid colors keep the default
+        let muted = manager.muted_style();
+        assert_eq!(muted.fg, Some(Color::DarkGray));
+        assert!(muted.add_modifier.contains(Modifier::BOLD));
+
+        // Styles without an entry are unchanged
+        assert_eq!(manager.border_style(), Style::default().fg(Color::Gray));
+    }
+
+    #[test]
+    fn test_widget_style_overrides_theme() {
+        let mut config = Themes::dark();
+        config.styles = Some(HashMap::from([(
+            "selected".to_string(),
+            StyleConfig { underline: Some(true), ..StyleConfig::default() },
+        )]));
+        let manager = ThemeManager::new(config);
+
+        let widget = WidgetStyle {
+            bg_color: Some("#101010".to_string()),
+            border_color: Some("secondary".to_string()),
+            selected_color: Some("green".to_string()),
+            ..WidgetStyle::default()
+        };
+        let panel = manager.for_widget(&widget);
+
+        assert_eq!(panel.panel_style(), Style::default().bg(Color::Rgb(16, 16, 16)));
+        assert_eq!(panel.border_style(), Style::default().fg(Color::Yellow));
+        // The theme's modifiers are kept
+        assert_eq!(
+            panel.selected_style(),
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        );
+        // Settings the widget leaves out come from the theme
+        assert_eq!(panel.playing_style(), manager.playing_style());
+        assert_eq!(manager.border_style(), Style::default().fg(Color::Gray));
+    }
+
     #[test]
     fn test_predefined_themes() {
         let themes = Themes::all();
-        assert_eq!(themes.len(), 6);
+        assert_eq!(themes.len(), 10);
         
         let dark = Themes::get_by_name("dark").unwrap();
         assert_eq!(dark.name, "dark");
@@ -445,5 +743,11 @@ mod tests {
 
         let dracula = Themes::get_by_name("dracula").unwrap();
         assert_eq!(dracula.name, "dracula");
+
+        let high_contrast = Themes::get_by_name("high-contrast").unwrap();
+        assert_eq!(high_contrast.name, "high_contrast");
+        let manager = ThemeManager::new(high_contrast);
+        assert_eq!(manager.text_style().fg, Some(Color::White));
+        assert_eq!(manager.progress_style().bg, Some(Color::Black));
     }
 }
@@ -0,0 +1,81 @@
+//! Album art thumbnail drawn in half blocks
+//!
+//! Each cell shows two pixels, the top one as the color of `▀` and the
+//! bottom one as the background behind it, so a cover 8 pixels square fits
+//! in 8 columns and 4 rows.
+
+use image::RgbImage;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+/// Columns taken by the thumbnail
+pub const WIDTH: u16 = 8;
+
+/// Rows taken by the thumbnail when there is room for all of them
+pub const ROWS: u16 = 4;
+
+/// Cover shrunk to a few cells, squeezed to fewer rows when the area is
+/// shorter than the image
+#[derive(Debug, Clone)]
+pub struct Thumbnail<'a> {
+    image: &'a RgbImage,
+}
+
+impl<'a> Thumbnail<'a> {
+    pub fn new(image: &'a RgbImage) -> Self {
+        Self { image }
+    }
+
+    fn color(&self, x: u32, y: u32) -> Color {
+        let [r, g, b] = self.image.get_pixel(x, y).0;
+        Color::Rgb(r, g, b)
+    }
+}
+
+impl Widget for Thumbnail<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (width, height) = self.image.dimensions();
+        if area.is_empty() || width == 0 || height == 0 {
+            return;
+        }
+
+        let pixel_rows = area.height as u32 * 2;
+        for row in 0..area.height {
+            let top = (row as u32 * 2) * height / pixel_rows;
+            let bottom = (row as u32 * 2 + 1) * height / pixel_rows;
+            for column in 0..area.width.min(width as u16) {
+                let x = column as u32;
+                buf[(area.x + column, area.y + row)]
+                    .set_char('▀')
+                    .set_fg(self.color(x, top))
+                    .set_bg(self.color(x, bottom));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn test_two_pixels_to_a_cell() {
+        // Each pixel row its own shade of red
+        let image = RgbImage::from_fn(2, 4, |_, y| Rgb([y as u8 * 10, 0, 0]));
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        Thumbnail::new(&image).render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].symbol(), "▀");
+        assert_eq!((buf[(1, 0)].fg, buf[(1, 0)].bg), (Color::Rgb(0, 0, 0), Color::Rgb(10, 0, 0)));
+        assert_eq!((buf[(0, 1)].fg, buf[(0, 1)].bg), (Color::Rgb(20, 0, 0), Color::Rgb(30, 0, 0)));
+        // Nothing is drawn past the image
+        assert_eq!(buf[(2, 0)].symbol(), " ");
+
+        // A single row keeps the top and bottom halves of the image
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        Thumbnail::new(&image).render(area, &mut buf);
+        assert_eq!((buf[(0, 0)].fg, buf[(0, 0)].bg), (Color::Rgb(0, 0, 0), Color::Rgb(20, 0, 0)));
+    }
+}
@@ -0,0 +1,131 @@
+//! Undo history for playlist edits
+//!
+//! The history lasts for the session. Edits name their playlist rather than
+//! holding its ID, because undoing a delete recreates the playlist with a new
+//! ID.
+
+use crate::t;
+
+/// Most edits kept for undoing
+const MAX_EDITS: usize = 100;
+
+/// A change to the playlists that can be undone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistEdit {
+    /// A playlist was created
+    Create { name: String, description: Option<String> },
+    /// A song was appended to a playlist
+    AddSong { playlist: String, song_id: String },
+    /// Songs were appended to a playlist together
+    AddSongs { playlist: String, song_ids: Vec<String> },
+    /// A song was taken out of a playlist at `position`
+    RemoveSong { playlist: String, song_id: String, position: usize },
+    /// A playlist was deleted, with the songs it held in order
+    Delete { name: String, description: Option<String>, song_ids: Vec<String> },
+    /// The queue was saved as a new playlist of these songs
+    SaveQueue { name: String, song_ids: Vec<String> },
+}
+
+impl PlaylistEdit {
+    /// Short description for the undo and redo notifications
+    pub fn describe(&self) -> String {
+        match self {
+            PlaylistEdit::Create { name, .. } => t!("edit-create", name = name.as_str()),
+            PlaylistEdit::AddSong { playlist, .. } => t!("edit-add-song", name = playlist.as_str()),
+            PlaylistEdit::AddSongs { playlist, song_ids } => {
+                t!("edit-add-songs", count = song_ids.len(), name = playlist.as_str())
+            }
+            PlaylistEdit::RemoveSong { playlist, .. } => t!("edit-remove-song", name = playlist.as_str()),
+            PlaylistEdit::Delete { name, .. } => t!("edit-delete", name = name.as_str()),
+            PlaylistEdit::SaveQueue { name, .. } => t!("edit-save-queue", name = name.as_str()),
+        }
+    }
+}
+
+/// Edits that can be undone, and undone edits that can be redone
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo: Vec<PlaylistEdit>,
+    redo: Vec<PlaylistEdit>,
+}
+
+impl EditHistory {
+    /// Remember an edit the user just made
+    ///
+    /// A new edit starts a new branch, so it drops whatever could be redone.
+    pub fn record(&mut self, edit: PlaylistEdit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Take the latest edit to undo, keeping it for redo
+    pub fn undo(&mut self) -> Option<PlaylistEdit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Take the latest undone edit to redo, keeping it for undo
+    pub fn redo(&mut self) -> Option<PlaylistEdit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Forget every edit, e.g. after one could not be undone
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(song_id: &str) -> PlaylistEdit {
+        PlaylistEdit::AddSong { playlist: "Chill".to_string(), song_id: song_id.to_string() }
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut history = EditHistory::default();
+        assert_eq!(history.undo(), None);
+
+        history.record(add("a"));
+        history.record(add("b"));
+        assert_eq!(history.undo(), Some(add("b")));
+        assert_eq!(history.undo(), Some(add("a")));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo(), Some(add("a")));
+        assert_eq!(history.undo(), Some(add("a")));
+        assert_eq!(history.redo(), Some(add("a")));
+        assert_eq!(history.redo(), Some(add("b")));
+        assert_eq!(history.redo(), None);
+
+        // A new edit after an undo cannot be followed by a redo
+        history.undo();
+        history.record(add("c"));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo(), Some(add("c")));
+        assert_eq!(history.undo(), Some(add("a")));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = EditHistory::default();
+        for i in 0..MAX_EDITS + 5 {
+            history.record(add(&i.to_string()));
+        }
+
+        let mut undone = 0;
+        while history.undo().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_EDITS);
+    }
+}
@@ -0,0 +1,306 @@
+//! Spectrum visualizer drawn under the album art
+//!
+//! The panel shows either the spectrum or an
+//! [oscilloscope](crate::ui::oscilloscope), switched with the visualizer key.
+
+use crate::audio::spectrum::{SpectrumParams, MAX_BARS, MAX_FFT_SIZE, MIN_FFT_SIZE};
+use crate::config::check::FieldPath;
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+use serde::{Deserialize, Serialize};
+
+/// Blocks filling a cell from the bottom in eighths, emptiest first
+const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// What the visualizer shows
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualizerMode {
+    /// Levels of the frequencies, drawn by the preset
+    #[default]
+    Spectrum,
+    /// The waveform itself, scrolling past
+    Oscilloscope,
+}
+
+impl VisualizerMode {
+    /// The other mode
+    pub fn toggled(self) -> Self {
+        match self {
+            VisualizerMode::Spectrum => VisualizerMode::Oscilloscope,
+            VisualizerMode::Oscilloscope => VisualizerMode::Spectrum,
+        }
+    }
+}
+
+/// How the spectrum is drawn, each with analysis settings that suit it
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualizerPreset {
+    /// Bars rising from the bottom
+    #[default]
+    Bars,
+    /// Bars growing up and down from the middle
+    Mirror,
+    /// A line tracing the top of the spectrum
+    Wave,
+}
+
+impl VisualizerPreset {
+    /// Analysis settings used unless overridden
+    pub fn params(self) -> SpectrumParams {
+        match self {
+            VisualizerPreset::Bars => SpectrumParams {
+                fft_size: 2048,
+                bars: 32,
+                smoothing: 0.6,
+                min_freq: 40.0,
+                max_freq: 16000.0,
+            },
+            VisualizerPreset::Mirror => SpectrumParams {
+                fft_size: 2048,
+                bars: 24,
+                smoothing: 0.7,
+                min_freq: 40.0,
+                max_freq: 12000.0,
+            },
+            VisualizerPreset::Wave => SpectrumParams {
+                fft_size: 4096,
+                bars: 64,
+                smoothing: 0.8,
+                min_freq: 30.0,
+                max_freq: 18000.0,
+            },
+        }
+    }
+}
+
+/// The `[settings.visualizer]` section: the mode shown at start, and a
+/// preset for the spectrum with any of its analysis settings overridden
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VisualizerConfig {
+    pub mode: VisualizerMode,
+    pub preset: VisualizerPreset,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fft_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bars: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smoothing: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_freq: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_freq: Option<f32>,
+}
+
+impl VisualizerConfig {
+    /// Analysis settings of the preset with the overrides applied, brought
+    /// into range
+    pub fn params(&self) -> SpectrumParams {
+        let preset = self.preset.params();
+        SpectrumParams {
+            fft_size: self.fft_size.unwrap_or(preset.fft_size),
+            bars: self.bars.unwrap_or(preset.bars),
+            smoothing: self.smoothing.unwrap_or(preset.smoothing),
+            min_freq: self.min_freq.unwrap_or(preset.min_freq),
+            max_freq: self.max_freq.unwrap_or(preset.max_freq),
+        }
+        .clamped()
+    }
+
+    /// Overrides out of range, each with the field under `field` it is in
+    pub fn problems(&self, field: &FieldPath) -> Vec<(FieldPath, String)> {
+        let mut problems = Vec::new();
+        let mut problem = |key: &str, message: String| problems.push((field.clone().then_key(key), message));
+
+        if let Some(size) = self.fft_size {
+            if !size.is_power_of_two() || !(MIN_FFT_SIZE..=MAX_FFT_SIZE).contains(&size) {
+                problem(
+                    "fft_size",
+                    format!("FFT size must be a power of two from {} to {}, got {}", MIN_FFT_SIZE, MAX_FFT_SIZE, size),
+                );
+            }
+        }
+        if let Some(bars) = self.bars.filter(|bars| !(1..=MAX_BARS).contains(bars)) {
+            problem("bars", format!("Bar count must be from 1 to {}, got {}", MAX_BARS, bars));
+        }
+        if let Some(smoothing) = self.smoothing.filter(|smoothing| !(0.0..1.0).contains(smoothing)) {
+            problem("smoothing", format!("Smoothing must be at least 0 and below 1, got {}", smoothing));
+        }
+
+        let preset = self.preset.params();
+        let (min_freq, max_freq) = (self.min_freq.unwrap_or(preset.min_freq), self.max_freq.unwrap_or(preset.max_freq));
+        if min_freq <= 0.0 {
+            problem("min_freq", format!("Lowest frequency must be above 0 Hz, got {}", min_freq));
+        } else if max_freq <= min_freq {
+            let key = if self.max_freq.is_some() { "max_freq" } else { "min_freq" };
+            problem(key, format!("Frequency range {} to {} Hz is empty", min_freq, max_freq));
+        }
+
+        problems
+    }
+}
+
+/// Spectrum widget filling its area with one of the presets
+#[derive(Debug, Clone)]
+pub struct Visualizer<'a> {
+    levels: &'a [f32],
+    preset: VisualizerPreset,
+    style: Style,
+}
+
+impl<'a> Visualizer<'a> {
+    /// Draw `levels`, from 0 to 1 and lowest frequency first
+    pub fn new(levels: &'a [f32], preset: VisualizerPreset) -> Self {
+        Self { levels, preset, style: Style::default() }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Level of column `x` of `width`: the bars side by side with a gap
+    /// where there is room, or interpolated between them for the wave
+    fn column_level(&self, x: u16, width: u16) -> Option<f32> {
+        let count = self.levels.len();
+        if count == 0 {
+            return None;
+        }
+
+        if self.preset == VisualizerPreset::Wave {
+            let position = if width > 1 { x as f32 * (count - 1) as f32 / (width - 1) as f32 } else { 0.0 };
+            let (low, high) = (position.floor() as usize, (position.ceil() as usize).min(count - 1));
+            let fraction = position - low as f32;
+            return Some(self.levels[low] * (1.0 - fraction) + self.levels[high] * fraction);
+        }
+
+        let bar_width = (width as usize / count).max(1);
+        let gap = usize::from(bar_width >= 3);
+        let bar = x as usize / bar_width;
+        (bar < count && x as usize % bar_width < bar_width - gap).then(|| self.levels[bar])
+    }
+}
+
+impl Widget for Visualizer<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        for x in 0..area.width {
+            let Some(level) = self.column_level(x, area.width) else {
+                continue;
+            };
+            let level = if level.is_nan() { 0.0 } else { level.clamp(0.0, 1.0) };
+            let column = area.x + x;
+            let mut set = |y: u16, symbol: char| {
+                buf[(column, y)].set_char(symbol).set_style(self.style);
+            };
+
+            match self.preset {
+                VisualizerPreset::Bars | VisualizerPreset::Wave => {
+                    let (full, partial) = filled(level, area.height);
+                    // The wave keeps only the topmost cell
+                    let first = match self.preset {
+                        VisualizerPreset::Wave if partial > 0 => full,
+                        VisualizerPreset::Wave => full.saturating_sub(1),
+                        _ => 0,
+                    };
+                    for row in first..full {
+                        set(area.bottom() - 1 - row, EIGHTHS[8]);
+                    }
+                    if partial > 0 {
+                        set(area.bottom() - 1 - full, EIGHTHS[partial]);
+                    }
+                }
+                VisualizerPreset::Mirror => {
+                    // An odd middle row is shared by both halves
+                    let half = area.height.div_ceil(2);
+                    let upper = |row: u16| area.y + (area.height - 1) / 2 - row;
+                    let lower = |row: u16| area.y + area.height / 2 + row;
+                    let (full, partial) = filled(level, half);
+                    for row in 0..full {
+                        set(lower(row), EIGHTHS[8]);
+                        set(upper(row), EIGHTHS[8]);
+                    }
+                    if partial > 0 {
+                        set(lower(full), if partial >= 4 { '▀' } else { '▔' });
+                        set(upper(full), EIGHTHS[partial]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whole cells and eighths of a further one that `level` fills of `rows`
+fn filled(level: f32, rows: u16) -> (u16, usize) {
+    let eighths = (level * (rows as usize * 8) as f32).round() as usize;
+    ((eighths / 8) as u16, eighths % 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(levels: &[f32], preset: VisualizerPreset, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        Visualizer::new(levels, preset).render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol().to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_bars_rise_from_the_bottom() {
+        assert_eq!(
+            rows(&[1.0, 0.5, 0.0], VisualizerPreset::Bars, 9, 2),
+            vec!["██       ", "██ ██    "]
+        );
+        // Eighths at the top of a bar
+        assert_eq!(rows(&[0.25], VisualizerPreset::Bars, 1, 2), vec![" ", "▄"]);
+    }
+
+    #[test]
+    fn test_mirror_grows_from_the_middle() {
+        assert_eq!(rows(&[1.0, 0.5], VisualizerPreset::Mirror, 2, 4), vec!["█ ", "██", "██", "█ "]);
+    }
+
+    #[test]
+    fn test_wave_traces_the_top() {
+        assert_eq!(rows(&[1.0, 0.0], VisualizerPreset::Wave, 3, 2), vec!["█  ", " █ "]);
+        assert_eq!(rows(&[0.0, 0.0], VisualizerPreset::Wave, 2, 2), vec!["  ", "  "]);
+    }
+
+    #[test]
+    fn test_config_overrides_preset() {
+        let config: VisualizerConfig = toml::from_str("preset = \"wave\"\nbars = 10").unwrap();
+        assert_eq!(config.mode, VisualizerMode::Spectrum);
+        let params = config.params();
+        assert_eq!(params.bars, 10);
+        assert_eq!(params.fft_size, VisualizerPreset::Wave.params().fft_size);
+        assert!(config.problems(&FieldPath::key("visualizer")).is_empty());
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let config: VisualizerConfig =
+            toml::from_str("fft_size = 1000\nbars = 0\nsmoothing = 1.0\nmax_freq = 20.0").unwrap();
+        let fields: Vec<String> = config
+            .problems(&FieldPath::key("settings").then_key("visualizer"))
+            .into_iter()
+            .map(|(field, _)| field.to_string())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "settings.visualizer.fft_size",
+                "settings.visualizer.bars",
+                "settings.visualizer.smoothing",
+                "settings.visualizer.max_freq",
+            ]
+        );
+    }
+}
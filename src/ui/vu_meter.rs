@@ -0,0 +1,135 @@
+//! Stereo VU meter widget
+
+use crate::audio::meter::{ChannelLevel, MeterReading};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+/// Blocks filling a cell from the left in eighths, emptiest first
+const EIGHTHS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Level from which the meter is drawn in the hot style, in dB
+const HOT_DB: f32 = -6.0;
+
+/// Meter showing the RMS level of each channel as a bar and its held peak
+/// as a mark, one row per channel, or both halves of a single row
+#[derive(Debug, Clone)]
+pub struct VuMeter {
+    readings: [MeterReading; 2],
+    unicode: bool,
+    style: Style,
+    hot_style: Style,
+    peak_style: Style,
+}
+
+impl VuMeter {
+    pub fn new(readings: [MeterReading; 2], unicode: bool) -> Self {
+        Self {
+            readings,
+            unicode,
+            style: Style::default(),
+            hot_style: Style::default(),
+            peak_style: Style::default(),
+        }
+    }
+
+    /// Colors of the bar, of its part near full scale, and of the peak mark
+    pub fn styles(mut self, style: Style, hot_style: Style, peak_style: Style) -> Self {
+        self.style = style;
+        self.hot_style = hot_style;
+        self.peak_style = peak_style;
+        self
+    }
+
+    /// Cells of a bar `width` wide for `reading`, filled from the left, each
+    /// with its style; without `eighths` the last cell is whole or empty
+    fn bar(&self, reading: &MeterReading, width: usize, eighths: bool) -> Vec<(char, Style)> {
+        let level = (ChannelLevel::fraction(reading.level.rms) * (width * 8) as f32).round() as usize;
+        let hot_from = (ChannelLevel::fraction(HOT_DB) * width as f32) as usize;
+        let peak = (ChannelLevel::fraction(reading.held_peak) * width as f32).ceil() as usize;
+
+        (0..width)
+            .map(|cell| {
+                let filled = level.saturating_sub(cell * 8).min(8);
+                let symbol = match (self.unicode, filled) {
+                    (true, filled) if eighths => EIGHTHS[filled],
+                    (true, 4..) => EIGHTHS[8],
+                    (false, 4..) => '#',
+                    _ => ' ',
+                };
+                let style = if cell >= hot_from { self.hot_style } else { self.style };
+                if filled == 0 && peak > 0 && cell == peak - 1 {
+                    (if self.unicode { '│' } else { '|' }, self.peak_style)
+                } else {
+                    (symbol, style)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Widget for VuMeter {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        if area.height == 1 {
+            // Left fills from the left edge and right from the right edge,
+            // towards each other, in whole cells so both look alike
+            let width = (area.width / 2).saturating_sub(1) as usize;
+            buf.set_string(area.x, area.y, "L", self.style);
+            for (i, (symbol, style)) in self.bar(&self.readings[0], width, false).into_iter().enumerate() {
+                buf[(area.x + 1 + i as u16, area.y)].set_char(symbol).set_style(style);
+            }
+            buf.set_string(area.right() - 1, area.y, "R", self.style);
+            for (i, (symbol, style)) in self.bar(&self.readings[1], width, false).into_iter().enumerate() {
+                buf[(area.right() - 2 - i as u16, area.y)].set_char(symbol).set_style(style);
+            }
+            return;
+        }
+
+        let top = area.y + (area.height - 2) / 2;
+        for (row, (label, reading)) in ["L", "R"].iter().zip(&self.readings).enumerate() {
+            let y = top + row as u16;
+            buf.set_string(area.x, y, label, self.style);
+            let width = area.width.saturating_sub(2) as usize;
+            for (i, (symbol, style)) in self.bar(reading, width, true).into_iter().enumerate() {
+                buf[(area.x + 2 + i as u16, y)].set_char(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::meter::FLOOR_DB;
+
+    fn reading(rms: f32, held_peak: f32) -> MeterReading {
+        MeterReading { level: ChannelLevel { peak: held_peak, rms }, held_peak }
+    }
+
+    fn rows(meter: VuMeter, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        meter.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol().to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_meter_rows() {
+        // Half scale fills half the bar, the peak marked further along
+        let meter = VuMeter::new([reading(FLOOR_DB / 2.0, FLOOR_DB / 4.0), reading(FLOOR_DB, FLOOR_DB)], true);
+        assert_eq!(rows(meter, 10, 2), vec!["L ████ │  ", "R         "]);
+
+        let meter = VuMeter::new([reading(FLOOR_DB * 0.75 - 0.1, FLOOR_DB), reading(0.0, 0.0)], false);
+        assert_eq!(rows(meter, 6, 3), vec!["L #   ", "R ####", "      "]);
+    }
+
+    #[test]
+    fn test_single_row_meets_in_the_middle() {
+        let meter = VuMeter::new([reading(0.0, 0.0), reading(FLOOR_DB / 2.0, FLOOR_DB / 2.0)], true);
+        assert_eq!(rows(meter, 10, 1), vec!["L████  ██R"]);
+    }
+}
@@ -1,53 +1,42 @@
-use crate::ui::{App, InputMode, ActivePanel, ViewMode};
+use crate::logging;
+use crate::models::PlaylistSort;
+use crate::services::scan_job;
+use crate::ui::app::{self, playlist_sort_label};
+use crate::ui::heatmap::{self, ListeningHeatmap};
+use crate::ui::keymap;
+use crate::ui::dir_browser::DirBrowser;
+use crate::ui::progress::{self, ProgressBar};
+use crate::ui::setup::{SetupStep, SetupWizard, SETUP_STEPS};
+use crate::ui::status::{self, Segment, StatusField};
+use crate::ui::text;
+use crate::ui::oscilloscope::Oscilloscope;
+use crate::ui::visualizer::{Visualizer, VisualizerMode};
+use crate::ui::thumbnail::{self, Thumbnail};
+use crate::ui::vu_meter::VuMeter;
+use crate::ui::theme::{ThemeManager, Themes};
+use crate::ui::{App, InputMode, ActivePanel, ViewMode, SmartView};
+use crate::t;
+use chrono::Datelike;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, Gauge, List, ListItem, Paragraph, Clear,
+        Block, BorderType, Borders, List, ListItem, Paragraph, Clear, Wrap,
     },
     Frame,
 };
 use std::collections::HashMap;
 
-/// Helper to get color from hex string or name, defaulting to a fallback
-fn get_color(color_str: Option<&String>, fallback: Color) -> Color {
-    if let Some(c) = color_str {
-        match c.to_lowercase().as_str() {
-            "black" => Color::Black,
-            "red" => Color::Red,
-            "green" => Color::Green,
-            "yellow" => Color::Yellow,
-            "blue" => Color::Blue,
-            "magenta" => Color::Magenta,
-            "cyan" => Color::Cyan,
-            "gray" | "grey" => Color::Gray,
-            "dark_gray" => Color::DarkGray,
-            "white" => Color::White,
-            s if s.starts_with('#') => {
-                if let Ok(rgb) = u32::from_str_radix(&s[1..], 16) {
-                    Color::Rgb(((rgb >> 16) & 0xFF) as u8, ((rgb >> 8) & 0xFF) as u8, (rgb & 0xFF) as u8)
-                } else {
-                    fallback
-                }
-            }
-            _ => fallback,
-        }
-    } else {
-        fallback
-    }
-}
+/// Marks put before errors, warnings and successes, which are told apart by
+/// symbol as well as by color
+const ERROR_MARK: &str = "✗";
+const WARNING_MARK: &str = "!";
+const SUCCESS_MARK: &str = "✓";
 
 pub fn draw_ui(f: &mut Frame, app: &mut App) {
-    // Extract theme colors for easy access
-    let theme = &app.layout_config.theme;
-    let colors = theme.colors.as_ref();
-    
-    let primary_color = get_color(colors.and_then(|c| c.get("primary")), Color::Cyan);
-    let secondary_color = get_color(colors.and_then(|c| c.get("secondary")), Color::Magenta);
-    let _bg_color = get_color(colors.and_then(|c| c.get("background")), Color::Reset);
-    let border_color = get_color(colors.and_then(|c| c.get("border")), Color::DarkGray);
-    let highlight_color = get_color(colors.and_then(|c| c.get("highlight")), Color::Yellow);
+    // Cloned so draw functions can borrow the app mutably
+    let theme = app.theme.clone();
 
     // Calculate layout using the layout engine
     let area = f.area();
@@ -67,33 +56,47 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
     // First, collect the widgets we need to draw to avoid holding the borrow
     let widgets_to_draw: Vec<_> = app.layout_config.widgets.iter()
         .filter(|w| w.visible)
-        .map(|w| (w.name.clone(), w.widget_type.clone()))
+        .map(|w| (w.name.clone(), w.widget_type.clone(), w.border_type(), theme.for_widget(&w.style)))
         .collect();
 
-    for (name, widget_type) in widgets_to_draw {
+    for (name, widget_type, border, widget_theme) in widgets_to_draw {
+        // Pictures mean nothing to a screen reader
+        if app.screen_reader && matches!(widget_type, crate::ui::layout::WidgetType::AlbumArt | crate::ui::layout::WidgetType::VuMeter) {
+            continue;
+        }
+        let theme = &widget_theme;
         if let Some(area) = layout_areas.get(&name) {
             match widget_type {
                 crate::ui::layout::WidgetType::Sidebar => {
-                    draw_playlist_panel(f, app, *area, primary_color, secondary_color, border_color);
+                    draw_playlist_panel(f, app, *area, theme, border);
                 },
                 crate::ui::layout::WidgetType::PlaylistView => {
-                    draw_song_list_panel(f, app, *area, primary_color, highlight_color, border_color);
+                    draw_song_list_panel(f, app, *area, theme, border);
                 },
                 crate::ui::layout::WidgetType::NowPlaying => {
-                    draw_player_controls(f, app, *area, primary_color, secondary_color, border_color);
+                    draw_player_controls(f, app, *area, theme, border);
                 },
                 crate::ui::layout::WidgetType::AlbumArt => {
-                    draw_visual_panel(f, app, *area, secondary_color, border_color);
+                    draw_visual_panel(f, app, *area, theme, border);
                 },
                 crate::ui::layout::WidgetType::ProgressBar => {
                     // Progress bar is usually part of NowPlaying, but if separate:
-                    draw_progress_bar(f, app, *area, primary_color);
+                    draw_progress_bar(f, app, *area, theme);
                 },
                 crate::ui::layout::WidgetType::StatusBar => {
-                    draw_status_bar(f, app, *area, border_color);
+                    draw_status_bar(f, app, *area, theme);
+                },
+                crate::ui::layout::WidgetType::Stats => {
+                    draw_stats(f, app, *area, theme, border);
                 },
                 crate::ui::layout::WidgetType::SearchBox => {
-                    draw_header(f, app, *area, primary_color, border_color);
+                    draw_header(f, app, *area, theme, border);
+                },
+                crate::ui::layout::WidgetType::VuMeter => {
+                    draw_vu_meter(f, app, *area, theme, border);
+                },
+                crate::ui::layout::WidgetType::RecentlyPlayed => {
+                    draw_recently_played(f, app, *area, theme, border);
                 },
                 _ => {}
             }
@@ -102,21 +105,77 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
 
     // If no layout areas (fallback or empty config), use default hardcoded layout
     if layout_areas.is_empty() {
-        draw_default_layout(f, app, primary_color, secondary_color, highlight_color, border_color);
+        draw_default_layout(f, app, &theme);
     }
 
     // --- Modals ---
-    if matches!(app.state.input_mode, InputMode::PlaylistCreate | InputMode::PlaylistEdit) {
-        draw_input_modal(f, app, highlight_color);
+    if matches!(
+        app.state.input_mode,
+        InputMode::PlaylistCreate
+            | InputMode::PlaylistEdit
+            | InputMode::QueueSave
+            | InputMode::PartyLock
+            | InputMode::PartyConfirm
+            | InputMode::PartyUnlock
+            | InputMode::BookmarkName
+    ) {
+        draw_input_modal(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::ConfirmDelete | InputMode::ConfirmDeleteFinal) {
+        draw_delete_modal(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::Stats) {
+        draw_stats_screen(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::Help) {
+        draw_help_overlay(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::SongInfo) {
+        draw_song_info(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::ConfirmBatchAdd) {
+        draw_batch_add_confirm(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::ScanReport) {
+        draw_scan_report(f, app, &theme);
     }
 
-    // Draw scanning modal on top if scanning is in progress
-    if app.state.is_scanning {
-        draw_scanning_modal(f, app);
+    if matches!(app.state.input_mode, InputMode::Logs) {
+        draw_logs(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::Setup) {
+        draw_setup(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::MusicDir) {
+        draw_music_dir_picker(f, app, &theme);
+    }
+
+    if matches!(app.state.input_mode, InputMode::Output) {
+        draw_output_picker(f, app, &theme);
+    }
+
+    // Draw scanning modal on top if scanning is in progress, unless the
+    // setup wizard shows the scan itself
+    if app.state.scan_progress.is_some() && !matches!(app.state.input_mode, InputMode::Setup) {
+        draw_scanning_modal(f, app, &theme);
+    }
+
+    // Screen readers get toasts on the status line instead
+    if !app.screen_reader && app.state.toast.as_ref().is_some_and(|toast| toast.is_visible()) {
+        draw_toast(f, app, &theme);
     }
 }
 
-fn draw_default_layout(f: &mut Frame, app: &mut App, primary: Color, secondary: Color, highlight: Color, border: Color) {
+fn draw_default_layout(f: &mut Frame, app: &mut App, theme: &ThemeManager) {
+    let border = Some(BorderType::Rounded);
     // Main layout
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -128,7 +187,7 @@ fn draw_default_layout(f: &mut Frame, app: &mut App, primary: Color, secondary:
         .split(f.area());
 
     // --- Header / Search Bar ---
-    draw_header(f, app, main_chunks[0], primary, border);
+    draw_header(f, app, main_chunks[0], theme, border);
 
     // --- Main Content Area ---
     // Check visible widgets to decide layout
@@ -136,7 +195,7 @@ fn draw_default_layout(f: &mut Frame, app: &mut App, primary: Color, secondary:
         .filter(|w| w.visible)
         .collect();
     
-    let show_album_art = visible_widgets.iter().any(|w| w.name.contains("art"));
+    let show_album_art = !app.screen_reader && visible_widgets.iter().any(|w| w.name.contains("art"));
 
     let content_chunks = if show_album_art {
         Layout::default()
@@ -158,27 +217,30 @@ fn draw_default_layout(f: &mut Frame, app: &mut App, primary: Color, secondary:
     };
 
     // Draw Panels
-    draw_playlist_panel(f, app, content_chunks[0], primary, secondary, border);
+    draw_playlist_panel(f, app, content_chunks[0], theme, border);
     
     if content_chunks.len() > 1 {
-        draw_song_list_panel(f, app, content_chunks[1], primary, highlight, border);
+        draw_song_list_panel(f, app, content_chunks[1], theme, border);
     }
     
     if content_chunks.len() > 2 {
-        draw_visual_panel(f, app, content_chunks[2], secondary, border);
+        draw_visual_panel(f, app, content_chunks[2], theme, border);
     }
 
     // --- Player Controls ---
-    draw_player_controls(f, app, main_chunks[2], primary, secondary, border);
+    draw_player_controls(f, app, main_chunks[2], theme, border);
 }
 
-fn draw_scanning_modal(f: &mut Frame, app: &App) {
+fn draw_scanning_modal(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(scan) = &app.state.scan_progress else {
+        return;
+    };
     let area = f.area();
-    let popup_area = centered_rect(60, 25, area);
+    let popup_area = centered_rect(60, 35, area);
 
     f.render_widget(Clear, popup_area);
 
-    let (processed, total) = app.state.scan_progress;
+    let (processed, total) = (scan.processed, scan.total);
     let percentage = if total > 0 {
         (processed as f64 / total as f64) * 100.0
     } else {
@@ -186,192 +248,281 @@ fn draw_scanning_modal(f: &mut Frame, app: &App) {
     };
 
     let block = Block::default()
-        .title("📀 Scanning Music Library...")
+        .title(shown(app, t!("scan-modal-title")))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(theme.color("highlight", Color::Yellow)));
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
-        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+        .constraints([Constraint::Length(1); 6])
         .split(popup_area);
 
     f.render_widget(block, popup_area);
 
-    let text = Paragraph::new(format!("Processing file {} of {}...", processed, total))
-        .alignment(Alignment::Center);
-    f.render_widget(text, layout[0]);
+    // Files are counted once the directories have been walked
+    let status = if total == 0 {
+        t!("scan-modal-walking")
+    } else {
+        t!("scan-modal-progress", processed = processed, total = total)
+    };
+    f.render_widget(Paragraph::new(status).alignment(Alignment::Center), layout[0]);
+
+    if let Some(name) = scan.current_file.as_deref().and_then(|path| path.file_name()) {
+        let name = text::fit(&name.to_string_lossy(), layout[1].width as usize);
+        f.render_widget(Paragraph::new(name).alignment(Alignment::Center).style(theme.muted_style()), layout[1]);
+    }
+
+    if let Some(rate) = scan_job::rate_label(scan) {
+        f.render_widget(Paragraph::new(rate).alignment(Alignment::Center), layout[2]);
+    }
 
-    let progress_bar = Gauge::default()
-        .gauge_style(Style::default().fg(Color::Cyan))
-        .percent(percentage as u16)
-        .label(format!("{:.0}%", percentage));
-    f.render_widget(progress_bar, layout[2]);
+    let progress_bar = progress_bar(app, percentage / 100.0, theme).label(format!("{:.0}%", percentage));
+    f.render_widget(progress_bar, layout[3]);
+
+    let hint = if app.state.scan_cancelling {
+        t!("scan-modal-cancelling")
+    } else {
+        t!("scan-modal-cancel")
+    };
+    f.render_widget(Paragraph::new(hint).alignment(Alignment::Center).style(theme.hint_style()), layout[5]);
 }
 
-fn draw_header(f: &mut Frame, app: &App, area: Rect, primary: Color, border: Color) {
+fn draw_header(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
     let title = match &app.state.view_mode {
-        ViewMode::Library => " 🐢 Lofi Turtle Library ",
-        ViewMode::Playlist(_name) => " 🐢 Playlist View ",
+        ViewMode::Library => t!("header-library"),
+        ViewMode::Playlist(_name) => t!("header-playlist"),
+        ViewMode::Smart(_view) => t!("header-smart"),
     };
 
     let border_style = if matches!(app.state.input_mode, InputMode::Search) {
-        Style::default().fg(primary).add_modifier(Modifier::BOLD)
+        theme.active_border_style()
     } else {
-        Style::default().fg(border)
+        theme.border_style()
     };
 
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(border_style)
-        .title(Span::styled(title, Style::default().fg(primary).add_modifier(Modifier::BOLD)));
+    let block = panel_block(border, border_style, theme).title(Span::styled(format!(" {} ", shown(app, title)), theme.title_style()));
 
     if matches!(app.state.input_mode, InputMode::Search) {
         let mut textarea = app.state.search_textarea.clone();
         textarea.set_block(block);
         textarea.set_style(Style::default().fg(Color::White));
-        textarea.set_cursor_style(Style::default().bg(primary));
+        textarea.set_cursor_style(Style::default().bg(theme.color("primary", Color::Cyan)));
         f.render_widget(&textarea, area);
     } else {
         // Just show the title or a hint when not searching
         let hint = if !app.state.search_query.is_empty() {
-            format!("🔍 Filter: {}", app.state.search_query)
+            t!("search-filter", query = app.state.search_query.as_str())
         } else {
-            "Press '/' to search".to_string()
+            t!("search-hint")
         };
 
-        let p = Paragraph::new(hint)
+        let p = Paragraph::new(shown(app, hint))
             .block(block)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.muted_style())
             .alignment(Alignment::Left);
         f.render_widget(p, area);
     }
 }
 
-fn draw_playlist_panel(f: &mut Frame, app: &App, area: Rect, primary: Color, secondary: Color, border: Color) {
+fn draw_playlist_panel(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
     let is_active = app.state.active_panel == ActivePanel::Playlists;
     let border_style = if is_active {
-        Style::default().fg(primary).add_modifier(Modifier::BOLD)
+        theme.active_border_style()
     } else {
-        Style::default().fg(border)
+        theme.border_style()
+    };
+    let current_style = theme.secondary_style().add_modifier(Modifier::BOLD);
+    // The default order goes without saying
+    let mut title = match app.state.playlist_sort {
+        PlaylistSort::Name => format!(" {} ", t!("playlists-title")),
+        sort => format!(" {} ↓ {} ", t!("playlists-title"), playlist_sort_label(sort)),
     };
+    if app.playlists_loading() {
+        title.push_str(&loading_spinner(app));
+    }
+    let block = panel_block(border, border_style, theme).title(shown(app, title));
+    let width = block.inner(area).width as usize;
 
-    let items: Vec<ListItem> = app.state.playlists.iter().enumerate().map(|(i, p)| {
+    // Built-in views come first, then user playlists
+    let smart_items = SmartView::ALL.iter().enumerate().map(|(i, view)| {
         let is_selected = i == app.state.selected_playlist_index && is_active;
-        let icon = if is_selected { "📂" } else { "📁" };
+        let is_current = app.state.view_mode == ViewMode::Smart(*view);
+
+        let style = if is_selected || is_current {
+            current_style
+        } else {
+            theme.text_style()
+        };
+
+        let icon = if app.screen_reader { "" } else { "✨ " };
+        ListItem::new(Line::from(vec![
+            Span::styled(icon, theme.secondary_style()),
+            Span::styled(view.title(), style),
+        ]))
+    });
+
+    let items: Vec<ListItem> = app.state.playlists.iter().enumerate().map(|(i, p)| {
+        let is_selected = i + SmartView::ALL.len() == app.state.selected_playlist_index && is_active;
+        let icon = match (app.screen_reader, is_selected) {
+            (true, true) => ">",
+            (true, false) => " ",
+            (false, true) => "📂",
+            (false, false) => "📁",
+        };
 
         let style = if is_selected {
-            Style::default().fg(primary).add_modifier(Modifier::BOLD)
+            theme.selected_style()
+        } else {
+            theme.text_style()
+        };
+
+        // Long names are cut short so the song count and length stay visible
+        let count = if p.total_duration > 0 {
+            format!(" ({} · {})", p.song_count(), p.duration_formatted())
         } else {
-            Style::default().fg(Color::Gray)
+            format!(" ({})", p.song_count())
         };
+        let name_width = width.saturating_sub(text::display_width(icon) + 1 + text::display_width(&count));
 
         ListItem::new(Line::from(vec![
             Span::styled(format!("{} ", icon), style),
-            Span::styled(p.name.clone(), style),
-            Span::styled(format!(" ({})", p.song_count()), Style::default().fg(Color::DarkGray)),
+            Span::styled(text::truncate(&p.name, name_width).into_owned(), style),
+            Span::styled(count, theme.muted_style()),
         ]))
     }).collect();
 
     // Add "Library" at top
     let mut all_items = vec![ListItem::new(Line::from(vec![
-        Span::styled("📚 ", Style::default().fg(secondary)),
-        Span::styled("All Music", if matches!(app.state.view_mode, ViewMode::Library) {
-            Style::default().fg(secondary).add_modifier(Modifier::BOLD)
+        Span::styled(if app.screen_reader { "" } else { "📚 " }, theme.secondary_style()),
+        Span::styled(t!("all-music"), if matches!(app.state.view_mode, ViewMode::Library) {
+            current_style
         } else {
-            Style::default().fg(Color::Gray)
+            theme.text_style()
         }),
     ]))];
+    all_items.extend(smart_items);
     all_items.extend(items);
 
     let list = List::new(all_items)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .border_style(border_style)
-            .title(" Playlists "))
+        .block(block)
         .highlight_style(Style::default().bg(Color::DarkGray)); // Fallback highlight if needed
 
     f.render_widget(list, area);
 }
 
-fn draw_song_list_panel(f: &mut Frame, app: &App, area: Rect, primary: Color, highlight: Color, border: Color) {
+fn draw_song_list_panel(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
     let is_active = app.state.active_panel == ActivePanel::Songs;
     let border_style = if is_active {
-        Style::default().fg(primary).add_modifier(Modifier::BOLD)
+        theme.active_border_style()
     } else {
-        Style::default().fg(border)
+        theme.border_style()
     };
 
-    let songs: Vec<ListItem> = app.state.filtered_songs.iter().enumerate().map(|(i, song)| {
-        let is_selected = i == app.state.selected_song_index && is_active;
-        let is_playing = app.get_current_song().map(|s| s.path == song.path).unwrap_or(false);
+    let count = app.state.filtered_len();
+    let mut title = match &app.state.view_mode {
+        ViewMode::Library => format!(" {} ({}) ", t!("songs-title"), count),
+        ViewMode::Playlist(n) => format!(" {} ({}) ", n, count),
+        ViewMode::Smart(view) => format!(" {} ({}) ", view.title(), count),
+    };
+    if app.songs_loading() {
+        title.push_str(&loading_spinner(app));
+    }
+    let block = panel_block(border, border_style, theme).title(title);
+    let inner = block.inner(area);
+    let width = inner.width as usize;
+
+    // Only the rows that fit are built, scrolled to keep the selection in view,
+    // so drawing costs the same however large the library is; when following
+    // playback the playing song sits in the middle
+    let height = (inner.height as usize).max(1);
+    let selected = app.state.selected_song_index;
+    let offset = if app.is_following() {
+        selected.saturating_sub(height / 2).min(app.state.filtered_len().saturating_sub(height))
+    } else {
+        (selected + 1).saturating_sub(height)
+    };
+    let playing_path = app.state.playback_status.current_song.as_deref();
+    // The playing song is marked by symbol as well as color, so paused
+    // reads differently from playing without telling the colors apart
+    let paused = app.state.playback_status.state == crate::audio::PlayerState::Paused;
+    let playing_mark = match (app.screen_reader, paused) {
+        (true, false) => ("* ", theme.playing_style()),
+        (true, true) => ("= ", theme.paused_style()),
+        (false, false) => ("▶ ", theme.playing_style()),
+        (false, true) => ("⏸ ", theme.paused_style()),
+    };
+    let loved_mark = match (app.screen_reader, app.unicode) {
+        (true, _) => format!("{} ", t!("state-loved")),
+        (false, true) => "♥ ".to_string(),
+        (false, false) => "<3 ".to_string(),
+    };
+
+    let end = app.state.filtered_len().min(offset + height);
+    let songs: Vec<ListItem> = (offset..end).map(|i| {
+        // The library window loads the songs scrolled to in the background
+        let Some(song) = app.state.filtered_song(i) else {
+            return ListItem::new(Span::styled(format!("  {}", loading_spinner(app)), theme.muted_style()));
+        };
+        let is_selected = i == selected && is_active;
+        let is_playing = playing_path == Some(song.path.as_str());
 
         let (icon, style) = if is_playing {
-            ("▶ ", Style::default().fg(highlight).add_modifier(Modifier::BOLD))
+            playing_mark
         } else if is_selected {
-            ("● ", Style::default().fg(primary).add_modifier(Modifier::BOLD))
+            (if app.screen_reader { "> " } else { "● " }, theme.selected_style())
         } else {
-            ("  ", Style::default().fg(Color::Gray))
+            ("  ", theme.text_style())
         };
 
-        let title_width = (area.width as usize).saturating_sub(25); // Reserve space for duration/icon
-        let title = format!("{:<width$}", song.display_name(), width = title_width);
+        // Durations line up on the right, with the title cut to fit before
+        // them and the loved mark
+        let duration = song.duration_formatted();
+        let loved = if app.state.loved.contains(&song.id) { loved_mark.as_str() } else { "" };
+        let title_width = width.saturating_sub(
+            text::display_width(icon) + text::display_width(loved) + text::display_width(duration) + 1,
+        );
 
         ListItem::new(Line::from(vec![
             Span::styled(icon, style),
-            Span::styled(title, style),
-            Span::styled(song.duration_formatted(), Style::default().fg(Color::DarkGray)),
+            Span::styled(text::fit(song.display_name(), title_width), style),
+            Span::raw(" "),
+            Span::styled(loved, theme.highlight_style()),
+            Span::styled(duration, theme.muted_style()),
         ]))
     }).collect();
 
-    let title = match &app.state.view_mode {
-        ViewMode::Library => format!(" Songs ({}) ", app.state.filtered_songs.len()),
-        ViewMode::Playlist(n) => format!(" {} ({}) ", n, app.state.filtered_songs.len()),
-    };
-
-    let list = List::new(songs)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .border_style(border_style)
-            .title(title));
-
-    // We handle selection rendering manually above for better control,
-    // but we need to pass a state if we want scrolling to work automatically.
-    // Since AppState handles index, we just render the list centered around selection if possible
-    // For now, simple rendering:
-
-    // Calculate scroll offset to keep selection visible
-    let height = area.height as usize - 2; // borders
-    let _offset = if app.state.selected_song_index >= height {
-        app.state.selected_song_index - height + 1
-    } else {
-        0
-    };
-
-    // Create a new list with offset (manual scrolling implementation for stateless widget)
-    // In a real app we'd use ListState, but here we just slice for simplicity or rely on ratatui's state
-    // Let's use the proper way:
+    let list = List::new(songs).block(block);
     let mut state = ratatui::widgets::ListState::default();
-    state.select(Some(app.state.selected_song_index));
+    state.select(Some(selected - offset));
     f.render_stateful_widget(list, area, &mut state);
+
+    // A title being renamed is edited right where it is shown, after the
+    // selection mark
+    if app.state.input_mode == InputMode::SongTitle && selected - offset < inner.height as usize {
+        let row = Rect {
+            x: inner.x + 2,
+            y: inner.y + (selected - offset) as u16,
+            width: inner.width.saturating_sub(2),
+            height: 1,
+        };
+        let mut textarea = app.state.playlist_name_textarea.clone();
+        textarea.set_style(theme.selected_style());
+        textarea.set_cursor_line_style(Style::default());
+        f.render_widget(Clear, row);
+        f.render_widget(&textarea, row);
+    }
 }
 
-fn draw_visual_panel(f: &mut Frame, app: &mut App, area: Rect, color: Color, border: Color) {
+fn draw_visual_panel(f: &mut Frame, app: &mut App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
     let is_active = app.state.active_panel == ActivePanel::AlbumArt;
     let border_style = if is_active {
-        Style::default().fg(color).add_modifier(Modifier::BOLD)
+        theme.secondary_style().add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(border)
+        theme.border_style()
     };
 
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(border_style)
-        .title(" Visuals ");
+    let block = panel_block(border, border_style, theme).title(format!(" {} ", t!("visuals-title")));
 
     let inner_area = block.inner(area);
     f.render_widget(block, area);
@@ -388,38 +539,75 @@ fn draw_visual_panel(f: &mut Frame, app: &mut App, area: Rect, color: Color, bor
                  f.render_widget(p, art_area);
              } else {
                  // Placeholder
-                 let p = Paragraph::new("No Art").alignment(Alignment::Center).style(Style::default().fg(Color::DarkGray));
+                 let p = Paragraph::new(t!("no-art")).alignment(Alignment::Center).style(theme.muted_style());
                  f.render_widget(p, art_area);
              }
         }
     } else {
-        let p = Paragraph::new("Art Disabled\n(Press 'a')").alignment(Alignment::Center).style(Style::default().fg(Color::DarkGray));
+        let p = Paragraph::new(t!("art-disabled")).alignment(Alignment::Center).style(theme.muted_style());
         f.render_widget(p, centered_rect(80, 20, art_area));
     }
 
-    // 2. Fake Visualizer (Bottom 30%)
+    // 2. Visualizer (Bottom 30%)
     let viz_area = Rect { y: art_area.y + art_area.height, height: inner_area.height - art_area.height, ..inner_area };
     if app.state.playback_status.state == crate::audio::PlayerState::Playing {
-        // Simple animated bars
-        let bars = " ▂▃▅▆▇█▇▆▅▃▂ ";
-        let repeated = bars.repeat(5);
-        let p = Paragraph::new(repeated)
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(color));
-        f.render_widget(p, centered_rect(90, 50, viz_area));
+        let viz_area = centered_rect(90, 100, viz_area);
+        match app.state.visualizer_mode {
+            VisualizerMode::Spectrum => {
+                let levels = app.spectrum();
+                let visualizer =
+                    Visualizer::new(&levels, app.layout_config.settings.visualizer.preset).style(theme.secondary_style());
+                f.render_widget(visualizer, viz_area);
+            }
+            VisualizerMode::Oscilloscope => {
+                let samples = app.waveform();
+                f.render_widget(Oscilloscope::new(&samples, app.unicode).style(theme.secondary_style()), viz_area);
+            }
+        }
     }
 }
 
-fn draw_player_controls(f: &mut Frame, app: &App, area: Rect, primary: Color, secondary: Color, border: Color) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(border))
-        .title(" Now Playing ");
+fn draw_vu_meter(f: &mut Frame, app: &mut App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
+    // Framed when there is room, bare rows otherwise
+    let area = match border {
+        Some(border_type) if area.height >= 3 => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(theme.border_style())
+                .title(format!(" {} ", t!("vu-meter-title")));
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            inner
+        }
+        _ => area,
+    };
 
-    let inner = block.inner(area);
+    let meter = VuMeter::new(app.vu_readings(), app.unicode)
+        .styles(theme.success_style(), theme.warning_style(), theme.secondary_style());
+    f.render_widget(meter, area);
+}
+
+fn draw_player_controls(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
+    let block = panel_block(border, theme.border_style(), theme).title(format!(" {} ", t!("now-playing-title")));
+
+    let mut inner = block.inner(area);
     f.render_widget(block, area);
 
+    // A small cover beside the song when the layout has no art panel, if
+    // it leaves the rest enough room
+    if let Some(cover) = app.control_panel_thumbnail().filter(|_| inner.width >= thumbnail::WIDTH * 5) {
+        let cover_area = Rect {
+            x: inner.x + 1,
+            y: inner.y + 1,
+            width: thumbnail::WIDTH,
+            height: inner.height.saturating_sub(2).min(thumbnail::ROWS),
+        };
+        f.render_widget(Thumbnail::new(cover), cover_area);
+        inner.x += thumbnail::WIDTH + 1;
+        inner.width -= thumbnail::WIDTH + 1;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -432,16 +620,25 @@ fn draw_player_controls(f: &mut Frame, app: &App, area: Rect, primary: Color, se
         .split(inner);
 
     // 1. Song Info
-    if let Some(song) = app.get_current_song() {
+    if let Some(song) = app.get_current_song().filter(|_| app.screen_reader) {
+        let info = t!("now-playing-plain", title = song.title.as_str(), artist = song.artist.as_str());
+        f.render_widget(Paragraph::new(text::truncate(&info, chunks[0].width as usize).into_owned()).style(theme.text_style()), chunks[0]);
+    } else if let Some(song) = app.get_current_song() {
+        // The artist keeps at least a third of the room when both are long
+        let room = (chunks[0].width as usize).saturating_sub(text::display_width("🎵   👤 "));
+        let artist_width = (room / 3).max(room.saturating_sub(text::display_width(&song.title)));
+        let artist = text::truncate(&song.artist, artist_width);
+        let title = text::truncate(&song.title, room.saturating_sub(text::display_width(&artist)));
+
         let info = Line::from(vec![
-            Span::styled("🎵 ", Style::default().fg(secondary)),
-            Span::styled(&song.title, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-            Span::styled("  👤 ", Style::default().fg(secondary)),
-            Span::styled(&song.artist, Style::default().fg(Color::Gray)),
+            Span::styled("🎵 ", theme.secondary_style()),
+            Span::styled(title, theme.style("song_title", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))),
+            Span::styled("  👤 ", theme.secondary_style()),
+            Span::styled(artist, theme.text_style()),
         ]);
         f.render_widget(Paragraph::new(info).alignment(Alignment::Center), chunks[0]);
     } else {
-        f.render_widget(Paragraph::new("Nothing Playing").alignment(Alignment::Center).style(Style::default().fg(Color::DarkGray)), chunks[0]);
+        f.render_widget(Paragraph::new(t!("nothing-playing")).alignment(Alignment::Center).style(theme.muted_style()), chunks[0]);
     }
 
     // 2. Progress Bar
@@ -451,89 +648,289 @@ fn draw_player_controls(f: &mut Frame, app: &App, area: Rect, primary: Color, se
         0.0
     };
 
-    let gauge = Gauge::default()
-        .gauge_style(Style::default().fg(primary).bg(Color::DarkGray))
-        .ratio(progress)
-        .use_unicode(true); // Uses smooth blocks
-    f.render_widget(gauge, chunks[1]);
+    f.render_widget(progress_bar(app, progress, theme).marks(app.chapter_marks()), chunks[1]);
 
     // 3. Time & Status Icons
-    let time_str = format!("{} / {}",
-        format_duration(app.state.playback_status.current_position),
-        format_duration(app.state.playback_status.total_duration)
+    let mut status_spans = vec![
+        Span::styled(format!("{}  ", position_label(app)), theme.text_style()),
+        Span::styled(format!("{} ", state_icon(app)), theme.title_style()),
+        Span::raw("   "),
+        Span::styled(format!("{} ", shuffle_icon(app)), shuffle_style(app, theme)),
+        Span::styled(format!("{} ", repeat_icon(app)), repeat_style(app, theme)),
+        Span::raw("   "),
+        Span::styled(volume_label(app), theme.text_style()),
+    ];
+    // How many playlists the song is in; `P` lists them
+    let playlist_count = app.state.playing_song_playlists.len();
+    if playlist_count > 0 {
+        status_spans.push(Span::raw("   "));
+        let count = if app.screen_reader {
+            t!("in-playlists", count = playlist_count)
+        } else {
+            format!("📂 {}", playlist_count)
+        };
+        status_spans.push(Span::styled(count, theme.secondary_style()));
+    }
+    let status_line = Line::from(text::truncate_spans(status_spans, chunks[2].width as usize));
+    f.render_widget(Paragraph::new(status_line).alignment(Alignment::Center), chunks[2]);
+
+    // 4. Quick Help
+    let help = Span::styled(
+        text::truncate(&keymap::control_hints(&app.layout_config.keybindings), chunks[3].width as usize).into_owned(),
+        theme.hint_style()
     );
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[3]);
+}
+
+fn draw_progress_bar(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager) {
+    let progress = if app.state.playback_status.total_duration > 0 {
+        (app.state.playback_status.current_position as f64 / app.state.playback_status.total_duration as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
 
-    let status_icon = match app.state.playback_status.state {
+    f.render_widget(progress_bar(app, progress, theme).marks(app.chapter_marks()), area);
+}
+
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager) {
+    let mut spans: Vec<Span> = match &app.layout_config.settings.status_bar {
+        Some(template) => status::parse(template)
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(Span::styled(text.to_string(), theme.muted_style())),
+                Segment::Field(field) => status_field(app, field, theme),
+            })
+            .collect(),
+        None => {
+            let mut spans = vec![Span::styled(keymap::control_hints(&app.layout_config.keybindings), theme.hint_style())];
+
+            // Listens that could not be scrobbled yet, e.g. while offline,
+            // and a stop waiting for the song to end
+            for field in [StatusField::Scrobbles, StatusField::StopAfter, StatusField::Party] {
+                if let Some(notice) = status_field(app, field, theme) {
+                    spans.push(Span::styled(" | ", theme.warning_style()));
+                    spans.push(notice);
+                }
+            }
+            spans
+        }
+    };
+
+    // The latest announcement stays until the next one, so a screen reader
+    // finds it again on the line
+    if app.screen_reader {
+        if let Some(toast) = &app.state.toast {
+            spans = vec![Span::styled(toast.message.clone(), theme.text_style())];
+        }
+    }
+
+    // Count and keys typed towards the next action, like Vim's showcmd
+    let typed = app.chords.typed();
+    if !typed.is_empty() {
+        spans.insert(0, Span::styled(format!("{}  ", typed), theme.secondary_style()));
+    }
+
+    let line = Line::from(text::truncate_spans(spans, area.width as usize));
+    f.render_widget(Paragraph::new(line).style(theme.panel_style()).alignment(Alignment::Center), area);
+}
+
+/// Text of a status bar placeholder, or `None` when there is nothing to show
+fn status_field(app: &App, field: StatusField, theme: &ThemeManager) -> Option<Span<'static>> {
+    let span = match field {
+        StatusField::State => Span::styled(state_icon(app), theme.title_style()),
+        StatusField::Song => {
+            let song = app.get_current_song()?;
+            Span::styled(format!("{} - {}", song.artist, song.title), theme.text_style())
+        }
+        StatusField::Position => Span::styled(position_label(app), theme.text_style()),
+        StatusField::Volume => Span::styled(volume_label(app), theme.text_style()),
+        StatusField::Shuffle => Span::styled(shuffle_icon(app), shuffle_style(app, theme)),
+        StatusField::Repeat => Span::styled(repeat_icon(app), repeat_style(app, theme)),
+        StatusField::Scan => {
+            let scan = app.state.scan_progress.as_ref()?;
+            Span::styled(t!("status-scanning", processed = scan.processed, total = scan.total), theme.secondary_style())
+        }
+        StatusField::Hints => Span::styled(keymap::control_hints(&app.layout_config.keybindings), theme.hint_style()),
+        StatusField::Scrobbles => {
+            let pending = app.pending_scrobbles();
+            if pending == 0 {
+                return None;
+            }
+            Span::styled(format!("{} {}", WARNING_MARK, t!("scrobbles-pending", count = pending)), theme.warning_style())
+        }
+        StatusField::Playlists => {
+            let playlists = &app.state.playing_song_playlists;
+            if playlists.is_empty() {
+                return None;
+            }
+            Span::styled(shown(app, format!("📂 {}", playlists.join(", "))), theme.secondary_style())
+        }
+        StatusField::Party => {
+            if !app.is_party_locked() {
+                return None;
+            }
+            Span::styled(shown(app, t!("status-party")), theme.secondary_style())
+        }
+        StatusField::StopAfter => {
+            if !app.state.stop_after_current {
+                return None;
+            }
+            Span::styled(shown(app, t!("status-stop-after")), theme.warning_style())
+        }
+    };
+    Some(span)
+}
+
+fn state_icon(app: &App) -> String {
+    if app.screen_reader {
+        return app::player_state_label(&app.state.playback_status.state);
+    }
+    match app.state.playback_status.state {
         crate::audio::PlayerState::Playing => "▶",
         crate::audio::PlayerState::Paused => "⏸",
         crate::audio::PlayerState::Stopped => "⏹",
-    };
+    }
+    .to_string()
+}
+
+fn shuffle_icon(app: &App) -> String {
+    if app.screen_reader {
+        return app::shuffle_label(&app.state.playback_state);
+    }
+    match (app.state.playback_state.shuffle, app.state.playback_state.smart_shuffle) {
+        (false, _) => "➡",
+        (true, false) => "🔀",
+        (true, true) => "🔀✨",
+    }
+    .to_string()
+}
+
+fn shuffle_style(app: &App, theme: &ThemeManager) -> Style {
+    if app.state.playback_state.shuffle { theme.secondary_style() } else { theme.muted_style() }
+}
 
-    let shuffle_icon = if app.state.playback_state.shuffle { "🔀" } else { "➡" };
-    let repeat_icon = match app.state.playback_state.repeat_mode {
+fn repeat_icon(app: &App) -> String {
+    if app.screen_reader {
+        return app::repeat_label(app.state.playback_state.repeat_mode);
+    }
+    match app.state.playback_state.repeat_mode {
         crate::models::RepeatMode::None => "➡",
         crate::models::RepeatMode::Single => "🔂",
         crate::models::RepeatMode::Playlist => "🔁",
-    };
+    }
+    .to_string()
+}
+
+fn repeat_style(app: &App, theme: &ThemeManager) -> Style {
+    if app.state.playback_state.repeat_mode != crate::models::RepeatMode::None {
+        theme.secondary_style()
+    } else {
+        theme.muted_style()
+    }
+}
+
+fn position_label(app: &App) -> String {
+    let status = &app.state.playback_status;
+    if app.persistent_settings.show_remaining {
+        let remaining = status.total_duration.saturating_sub(status.current_position);
+        return format!("{} / -{}", format_duration(status.current_position), format_duration(remaining));
+    }
+    format!("{} / {}",
+        format_duration(status.current_position),
+        format_duration(status.total_duration)
+    )
+}
+
+fn volume_label(app: &App) -> String {
+    if app.screen_reader {
+        return app::volume_label(app.state.playback_status.volume);
+    }
     let vol = (app.state.playback_status.volume * 100.0) as u8;
     let vol_icon = if vol == 0 { "🔇" } else if vol < 50 { "🔉" } else { "🔊" };
+    format!("{} {}%", vol_icon, vol)
+}
 
-    let status_line = Line::from(vec![
-        Span::styled(format!("{}  ", time_str), Style::default().fg(Color::Gray)),
-        Span::styled(format!("{} ", status_icon), Style::default().fg(primary).add_modifier(Modifier::BOLD)),
-        Span::raw("   "),
-        Span::styled(format!("{} ", shuffle_icon), if app.state.playback_state.shuffle { Style::default().fg(secondary) } else { Style::default().fg(Color::DarkGray) }),
-        Span::styled(format!("{} ", repeat_icon), if app.state.playback_state.repeat_mode != crate::models::RepeatMode::None { Style::default().fg(secondary) } else { Style::default().fg(Color::DarkGray) }),
-        Span::raw("   "),
-        Span::styled(format!("{} {}%", vol_icon, vol), Style::default().fg(Color::Gray)),
-    ]);
-    f.render_widget(Paragraph::new(status_line).alignment(Alignment::Center), chunks[2]);
-
-    // 4. Quick Help
-    let help = Span::styled(
-        "Space:Play/Pause | Tab:Switch | /:Search | q:Quit",
-        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
-    );
-    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[3]);
+fn draw_stats(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
+    let line = listening_today_line(app, theme);
+
+    // Framed when there is room, a bare line otherwise
+    let paragraph = Paragraph::new(line).style(theme.panel_style()).alignment(Alignment::Center);
+    match border {
+        Some(border_type) if area.height >= 3 => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(theme.border_style())
+                .title(format!(" {} ", shown(app, t!("stats-title"))));
+            f.render_widget(paragraph.block(block), area);
+        }
+        _ => f.render_widget(paragraph, area),
+    }
 }
 
-fn draw_progress_bar(f: &mut Frame, app: &App, area: Rect, primary: Color) {
-    let progress = if app.state.playback_status.total_duration > 0 {
-        (app.state.playback_status.current_position as f64 / app.state.playback_status.total_duration as f64).clamp(0.0, 1.0)
+fn draw_recently_played(f: &mut Frame, app: &App, area: Rect, theme: &ThemeManager, border: Option<BorderType>) {
+    let recent = app.recently_played();
+    let lines: Vec<Line> = if recent.is_empty() {
+        vec![Line::from(Span::styled(t!("recent-empty"), theme.muted_style()))]
     } else {
-        0.0
+        // Numbered as the count that replays them, e.g. `3p`
+        recent
+            .iter()
+            .enumerate()
+            .map(|(index, listen)| {
+                Line::from(vec![
+                    Span::styled(format!("{} ", index + 1), theme.border_style()),
+                    Span::styled(listen.title().to_string(), theme.title_style()),
+                    Span::styled(format!(" · {}", listen.artist()), theme.muted_style()),
+                ])
+            })
+            .collect()
     };
 
-    let gauge = Gauge::default()
-        .gauge_style(Style::default().fg(primary).bg(Color::DarkGray))
-        .ratio(progress)
-        .use_unicode(true);
-    f.render_widget(gauge, area);
+    let paragraph = Paragraph::new(lines).style(theme.panel_style());
+    match border {
+        Some(border_type) if area.height >= 3 => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(theme.border_style())
+                .title(format!(" {} ", shown(app, t!("recent-title"))));
+            f.render_widget(paragraph.block(block), area);
+        }
+        _ => f.render_widget(paragraph, area),
+    }
 }
 
-fn draw_status_bar(f: &mut Frame, _app: &App, area: Rect, border: Color) {
-    let help = Span::styled(
-        "Space:Play/Pause | Tab:Switch | /:Search | q:Quit",
-        Style::default().fg(border).add_modifier(Modifier::ITALIC)
-    );
-    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), area);
+fn listening_today_line(app: &App, theme: &ThemeManager) -> Line<'static> {
+    let today = app.listening_today();
+
+    Line::from(vec![
+        Span::styled(format!("{} ", t!("today")), theme.border_style()),
+        Span::styled(format_listening_time(today.played.total_seconds()), theme.title_style()),
+        Span::styled(format!(" · {}", t!("tracks", count = today.listens)), theme.border_style()),
+    ])
 }
 
-fn draw_input_modal(f: &mut Frame, app: &App, highlight: Color) {
+fn draw_input_modal(f: &mut Frame, app: &App, theme: &ThemeManager) {
     let area = centered_rect(50, 20, f.area());
     f.render_widget(Clear, area);
     
     let title = match app.state.input_mode {
-        InputMode::PlaylistCreate => " Create Playlist ",
-        InputMode::PlaylistEdit => " Edit Playlist ",
-        _ => " Input ",
+        InputMode::PlaylistCreate => t!("playlist-create-title"),
+        InputMode::PlaylistEdit => t!("playlist-edit-title"),
+        InputMode::QueueSave => t!("queue-save-title"),
+        InputMode::PartyLock => t!("party-lock-title"),
+        InputMode::PartyConfirm => t!("party-confirm-title"),
+        InputMode::PartyUnlock => t!("party-unlock-title"),
+        InputMode::BookmarkName => t!("bookmark-name-title"),
+        _ => t!("input-title"),
     };
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(highlight))
-        .title(title);
+        .border_style(Style::default().fg(theme.color("highlight", Color::Yellow)))
+        .title(format!(" {} ", title));
 
     let mut textarea = app.state.playlist_name_textarea.clone();
     textarea.set_block(block);
@@ -541,6 +938,594 @@ fn draw_input_modal(f: &mut Frame, app: &App, highlight: Color) {
     f.render_widget(&textarea, area);
 }
 
+fn draw_toast(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(toast) = &app.state.toast else {
+        return;
+    };
+
+    // Bottom-right corner, clear of the status bar
+    let screen = f.area();
+    let width = (toast.message.chars().count() as u16 + 4).min(screen.width);
+    let height = 3.min(screen.height);
+    let area = Rect {
+        x: screen.x + screen.width - width,
+        y: screen.y + screen.height.saturating_sub(height + 1),
+        width,
+        height,
+    };
+
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.color("highlight", Color::Yellow)));
+    f.render_widget(
+        Paragraph::new(toast.message.clone()).block(block).alignment(Alignment::Center),
+        area,
+    );
+}
+
+fn draw_delete_modal(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(song) = &app.state.pending_delete else {
+        return;
+    };
+
+    let area = centered_rect(60, 25, f.area());
+    f.render_widget(Clear, area);
+
+    let action = if app.state.delete_to_trash { "delete-to-trash" } else { "delete-permanently" };
+    let question = match app.state.input_mode {
+        InputMode::ConfirmDeleteFinal if !app.state.delete_to_trash => t!("delete-confirm-permanent"),
+        InputMode::ConfirmDeleteFinal => t!("delete-confirm"),
+        _ => t!("delete-explanation"),
+    };
+
+    let text = vec![
+        Line::from(Span::styled(
+            t!(action, artist = song.artist.as_str(), title = song.title.as_str()),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(song.path.clone(), theme.muted_style())),
+        Line::from(""),
+        Line::from(question),
+        Line::from(""),
+        Line::from(Span::styled(t!("delete-keys"), theme.text_style())),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.color("error", Color::Red)))
+        .title(format!(" {} ", t!("delete-title")));
+
+    f.render_widget(
+        Paragraph::new(text).block(block).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+fn draw_stats_screen(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(heatmap) = &app.state.heatmap else {
+        return;
+    };
+
+    // Weekday labels, then two columns per week
+    const LABEL_WIDTH: usize = 4;
+    let screen = f.area();
+    let width = screen.width.saturating_sub(4).min((LABEL_WIDTH as u16 + 2 * ListeningHeatmap::WEEKS as u16) + 4);
+    let height = screen.height.min(17);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+
+    let weeks = heatmap.week_starts((width.saturating_sub(4) as usize).saturating_sub(LABEL_WIDTH) as u64 / 2);
+    let first_day = weeks[0];
+    let dim = theme.muted_style();
+    let heat = Style::default().fg(theme.color("primary", Color::Cyan));
+
+    // Month names above the first week of each month, where they fit
+    let line_width = LABEL_WIDTH + weeks.len() * 2;
+    let mut months = String::new();
+    let mut months_width = 0;
+    for (column, monday) in weeks.iter().enumerate() {
+        let position = LABEL_WIDTH + column * 2;
+        let new_month = column == 0 || monday.month() != weeks[column - 1].month();
+        let name = t!("month-short", month = monday.month());
+        let name_width = text::display_width(&name);
+        // Leave a space after the previous name
+        if new_month && position > months_width && position + name_width <= line_width {
+            months.push_str(&" ".repeat(position - months_width));
+            months.push_str(&name);
+            months_width = position + name_width;
+        }
+    }
+    months.push_str(&" ".repeat(line_width.saturating_sub(months_width)));
+
+    let mut text = vec![
+        listening_today_line(app, theme),
+        Line::from(""),
+        Line::from(Span::styled(months, dim)),
+    ];
+
+    for weekday in 0..7u64 {
+        let label = match weekday {
+            0 => t!("weekday-monday"),
+            2 => t!("weekday-wednesday"),
+            4 => t!("weekday-friday"),
+            _ => String::new(),
+        };
+        let label = text::fit(&label, LABEL_WIDTH - 1) + " ";
+        let mut spans = vec![Span::styled(label, dim)];
+        for monday in &weeks {
+            let day = *monday + chrono::Days::new(weekday);
+            if day > heatmap.today() {
+                spans.push(Span::raw("  "));
+                continue;
+            }
+            spans.push(match heatmap.level(day) {
+                0 => Span::styled("· ", dim),
+                level => Span::styled(format!("{} ", HEAT_CELLS[level as usize - 1]), heat),
+            });
+        }
+        text.push(Line::from(spans));
+    }
+
+    let mut legend = vec![Span::styled(format!("{} ", t!("heatmap-less")), dim), Span::styled("· ", dim)];
+    legend.extend(HEAT_CELLS.iter().map(|cell| Span::styled(format!("{} ", cell), heat)));
+    legend.push(Span::styled(t!("heatmap-more"), dim));
+    legend.push(Span::styled(
+        format!(
+            "    {}",
+            t!(
+                "heatmap-summary",
+                time = format_listening_time(heatmap.total_minutes_since(first_day) * 60),
+                days = heatmap.active_days_since(first_day),
+                month = t!("month-short", month = first_day.month()),
+                month_number = first_day.month(),
+                day = first_day.day(),
+                year = first_day.year().to_string(),
+            )
+        ),
+        theme.border_style(),
+    ));
+
+    text.push(Line::from(""));
+    text.push(Line::from(legend));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(t!("press-any-key"), theme.text_style())));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .title(format!(" {} ", shown(app, t!("listening-stats-title"))));
+
+    f.render_widget(Paragraph::new(text).block(block).alignment(Alignment::Center), area);
+}
+
+fn draw_help_overlay(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let bindings: Vec<(String, String)> = keymap::HELP_ACTIONS
+        .iter()
+        .filter_map(|action| {
+            let keys = keymap::keys_for(&app.layout_config.keybindings, action);
+            if keys.is_empty() {
+                return None;
+            }
+            let keys: Vec<String> = keys.iter().map(|key| keymap::display_key(key)).collect();
+            Some((keys.join(", "), keymap::describe(action)))
+        })
+        .collect();
+
+    let keys_width = bindings.iter().map(|(keys, _)| text::display_width(keys)).max().unwrap_or(0);
+    let row_width = bindings
+        .iter()
+        .map(|(_, description)| keys_width + 2 + text::display_width(description))
+        .max()
+        .unwrap_or(0);
+
+    // Two columns when one does not fit on the screen
+    let screen = f.area();
+    let columns = if bindings.len() + 4 > screen.height as usize { 2 } else { 1 };
+    let rows = bindings.len().div_ceil(columns);
+    let width = ((row_width * columns + 3 * (columns - 1) + 4) as u16).min(screen.width);
+    let height = (rows as u16 + 4).min(screen.height);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .title(format!(" {} ", shown(app, t!("help-title"))))
+        .title_bottom(Line::from(format!(" {} ", t!("press-any-key"))).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 1));
+    f.render_widget(block, area);
+
+    let column_areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .spacing(3)
+        .split(inner);
+
+    for (chunk, column_area) in bindings.chunks(rows.max(1)).zip(column_areas.iter()) {
+        let lines: Vec<Line> = chunk
+            .iter()
+            .map(|(keys, description)| {
+                Line::from(vec![
+                    Span::styled(text::fit(keys, keys_width), theme.title_style()),
+                    Span::raw("  "),
+                    Span::styled(description.clone(), theme.text_style()),
+                ])
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), *column_area);
+    }
+}
+
+/// Popup with the playlists a song is in and its bookmarks
+fn draw_song_info(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(info) = &app.state.song_info else {
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(format!("{} - {}", info.song.artist, info.song.title), theme.title_style())),
+        Line::default(),
+        Line::from(Span::styled(t!("song-info-playlists"), theme.title_style())),
+    ];
+    if info.playlists.is_empty() {
+        lines.push(Line::from(Span::styled(t!("song-playlists-none"), theme.muted_style())));
+    } else {
+        lines.extend(info.playlists.iter().map(|name| Line::from(Span::styled(format!("• {}", name), theme.text_style()))));
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(t!("song-info-bookmarks"), theme.title_style())));
+    if info.bookmarks.is_empty() {
+        lines.push(Line::from(Span::styled(t!("song-info-no-bookmarks"), theme.muted_style())));
+    }
+    for (index, bookmark) in info.bookmarks.iter().enumerate() {
+        let (marker, style) = if index == info.selected {
+            ("▶ ", theme.selected_style())
+        } else {
+            ("  ", theme.text_style())
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{}{}  {}", marker, bookmark.position().format_h_mm_ss(), bookmark.name()),
+            style,
+        )));
+    }
+
+    let hint = if info.bookmarks.is_empty() { t!("press-any-key") } else { t!("song-info-hint") };
+    draw_popup(f, &shown(app, t!("song-info-title")), lines, &hint, theme);
+}
+
+fn draw_batch_add_confirm(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some((playlist, song_ids, outside)) = &app.state.pending_batch_add else {
+        return;
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        t!("batch-add-confirm", count = song_ids.len(), name = playlist.as_str()),
+        theme.text_style(),
+    ))];
+    if *outside > 0 {
+        lines.push(Line::from(Span::styled(t!("batch-add-outside", count = *outside), theme.text_style())));
+    }
+    draw_popup(f, &t!("batch-add-title"), lines, &t!("delete-keys"), theme);
+}
+
+/// First-run setup wizard, filling most of the screen
+fn draw_setup(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(setup) = &app.state.setup else {
+        return;
+    };
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let hint = match setup.step {
+        SetupStep::Directory => t!("setup-directory-hint"),
+        SetupStep::Theme => t!("setup-theme-hint"),
+        SetupStep::Scan if app.is_scanning() => t!("scan-modal-cancel"),
+        SetupStep::Scan => t!("press-any-key"),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .style(theme.panel_style())
+        .title(format!(" {} ", shown(app, t!("setup-title", step = setup.step.number(), steps = SETUP_STEPS))))
+        .title_bottom(Line::from(Span::styled(format!(" {} ", hint), theme.hint_style())).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 1));
+    f.render_widget(block, area);
+
+    let [heading, body] = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    let title = match setup.step {
+        SetupStep::Directory => t!("setup-directory-title"),
+        SetupStep::Theme => t!("setup-theme-title"),
+        SetupStep::Scan => t!("setup-scan-title"),
+    };
+    f.render_widget(Paragraph::new(Span::styled(title, theme.title_style())), heading);
+
+    match setup.step {
+        SetupStep::Directory => draw_dir_browser(f, &setup.browser, body, theme),
+        SetupStep::Theme => {
+            let items: Vec<ListItem> = Themes::all()
+                .into_iter()
+                .map(|theme_config| ListItem::new(Span::styled(theme_config.name, theme.text_style())))
+                .collect();
+            let list = List::new(items).highlight_style(theme.selected_style()).highlight_symbol("▶ ");
+            let mut state = ratatui::widgets::ListState::default().with_selected(Some(setup.theme));
+            f.render_stateful_widget(list, body, &mut state);
+        }
+        SetupStep::Scan => draw_setup_scan(f, app, setup, body, theme),
+    }
+}
+
+/// Popup browsing for another music directory
+fn draw_music_dir_picker(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(browser) = &app.state.dir_picker else {
+        return;
+    };
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .style(theme.panel_style())
+        .title(format!(" {} ", shown(app, t!("music-dir-title"))))
+        .title_bottom(Line::from(Span::styled(format!(" {} ", t!("music-dir-hint")), theme.hint_style())).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 1));
+    f.render_widget(block, area);
+    draw_dir_browser(f, browser, inner, theme);
+}
+
+/// Popup listing the audio outputs of every host
+fn draw_output_picker(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(picker) = &app.state.output_picker else {
+        return;
+    };
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .style(theme.panel_style())
+        .title(format!(" {} ", shown(app, t!("output-title"))))
+        .title_bottom(Line::from(Span::styled(format!(" {} ", t!("output-hint")), theme.hint_style())).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 1));
+    f.render_widget(block, area);
+
+    let items: Vec<ListItem> = picker
+        .outputs
+        .iter()
+        .map(|(host, device)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}  ", host), theme.muted_style()),
+                Span::styled(device.clone(), theme.text_style()),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(theme.selected_style()).highlight_symbol("▶ ");
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(picker.selected));
+    f.render_stateful_widget(list, inner, &mut state);
+}
+
+/// The directory being browsed and its subdirectories
+fn draw_dir_browser(f: &mut Frame, browser: &DirBrowser, area: Rect, theme: &ThemeManager) {
+    let [path, list_area] = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(area);
+    let dir = browser.dir().display().to_string();
+    f.render_widget(
+        Paragraph::new(Span::styled(text::fit(&dir, path.width as usize), theme.secondary_style())),
+        path,
+    );
+
+    if browser.entries().is_empty() {
+        f.render_widget(Paragraph::new(Span::styled(t!("dir-browser-empty"), theme.muted_style())), list_area);
+        return;
+    }
+    let items: Vec<ListItem> = browser
+        .entries()
+        .iter()
+        .map(|entry| {
+            let name = entry.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            ListItem::new(Span::styled(format!("{}/", name), theme.text_style()))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(theme.selected_style()).highlight_symbol("▶ ");
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(browser.selected()));
+    f.render_stateful_widget(list, list_area, &mut state);
+}
+
+/// Progress of the first scan, then what it found
+fn draw_setup_scan(f: &mut Frame, app: &App, setup: &SetupWizard, area: Rect, theme: &ThemeManager) {
+    let rows = Layout::vertical([Constraint::Length(1); 4]).spacing(1).split(area);
+    let dir = setup.browser.dir().display().to_string();
+    f.render_widget(Paragraph::new(Span::styled(text::fit(&dir, rows[0].width as usize), theme.muted_style())), rows[0]);
+
+    if let Some(scan) = &app.state.scan_progress {
+        let status = if scan.total == 0 {
+            t!("scan-modal-walking")
+        } else {
+            t!("scan-modal-progress", processed = scan.processed, total = scan.total)
+        };
+        f.render_widget(Paragraph::new(status), rows[1]);
+        let ratio = if scan.total > 0 { scan.processed as f64 / scan.total as f64 } else { 0.0 };
+        f.render_widget(progress_bar(app, ratio, theme).label(format!("{:.0}%", ratio * 100.0)), rows[2]);
+        return;
+    }
+
+    let Some(outcome) = &setup.outcome else {
+        return;
+    };
+    let (mark, message, style) = if outcome.offline {
+        (ERROR_MARK, t!("setup-scan-unreadable"), theme.error_style())
+    } else if outcome.added == 0 {
+        (WARNING_MARK, t!("setup-scan-none"), theme.warning_style())
+    } else {
+        (SUCCESS_MARK, t!("setup-scan-done", count = outcome.added), theme.success_style())
+    };
+    let message = shown(app, format!("{} {}", mark, message));
+    f.render_widget(Paragraph::new(Span::styled(message, style)).wrap(Wrap { trim: true }), rows[1]);
+}
+
+/// Files shown per list in the scan summary popup, the rest in a count
+const SCAN_REPORT_LIMIT: usize = 8;
+
+/// Popup summarising a finished scan, with the files it left out
+fn draw_scan_report(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let Some(outcome) = &app.state.scan_report else {
+        return;
+    };
+
+    let lines = outcome
+        .report_lines(Some(SCAN_REPORT_LIMIT))
+        .into_iter()
+        .map(|line| {
+            let style = if line.starts_with(' ') { theme.muted_style() } else { theme.text_style() };
+            Line::from(Span::styled(line, style))
+        })
+        .collect();
+    draw_text_popup(f, &t!("scan-report-title"), lines, theme);
+}
+
+/// Log viewer showing the newest records that fit, or older ones when scrolled back
+fn draw_logs(f: &mut Frame, app: &App, theme: &ThemeManager) {
+    let screen = f.area();
+    let width = (screen.width * 9 / 10).max(screen.width.min(20));
+    let height = (screen.height * 4 / 5).max(screen.height.min(6));
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .title(format!(" {} ", shown(app, t!("logs-title"))))
+        .title_bottom(Line::from(format!(" {} ", t!("logs-hint"))).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 0));
+    f.render_widget(block, area);
+
+    let records = logging::recent();
+    if records.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled(t!("logs-empty"), theme.muted_style())), inner);
+        return;
+    }
+
+    let end = records.len() - app.state.log_scroll.min(records.len() - 1);
+    let start = end.saturating_sub(inner.height as usize);
+    let lines: Vec<Line> = records[start..end]
+        .iter()
+        .map(|record| {
+            let (mark, level_style) = match record.level {
+                log::Level::Error => (ERROR_MARK, theme.error_style()),
+                log::Level::Warn => (WARNING_MARK, theme.warning_style()),
+                _ => (" ", theme.muted_style()),
+            };
+            let spans = vec![
+                Span::styled(record.time.format("%H:%M:%S ").to_string(), theme.muted_style()),
+                Span::styled(format!("{} {:<5} ", mark, record.level), level_style),
+                Span::styled(record.message.clone(), theme.text_style()),
+            ];
+            Line::from(text::truncate_spans(spans, inner.width as usize))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Centered popup sized to its lines, closed by any key
+fn draw_text_popup(f: &mut Frame, title: &str, lines: Vec<Line>, theme: &ThemeManager) {
+    draw_popup(f, title, lines, &t!("press-any-key"), theme);
+}
+
+/// Centered popup sized to its lines, with the keys it takes at the bottom
+fn draw_popup(f: &mut Frame, title: &str, lines: Vec<Line>, hint: &str, theme: &ThemeManager) {
+    let title = format!(" {} ", title);
+    let footer = format!(" {} ", hint);
+    let content_width = lines
+        .iter()
+        .map(Line::width)
+        .chain([title.as_str(), footer.as_str()].map(text::display_width))
+        .max()
+        .unwrap_or(0);
+
+    let screen = f.area();
+    let width = (content_width as u16 + 4).min(screen.width);
+    let height = (lines.len() as u16 + 4).min(screen.height);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .title(title)
+        .title_bottom(Line::from(footer).centered());
+    let inner = block.inner(area).inner(ratatui::layout::Margin::new(1, 1));
+    f.render_widget(block, area);
+
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .map(|line| Line::from(text::truncate_spans(line.spans, inner.width as usize)))
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Heatmap cells from the least to the most listening
+const HEAT_CELLS: [char; heatmap::LEVELS as usize] = ['░', '▒', '▓', '█'];
+
+/// Progress bar in the configured style, or ASCII if the terminal lacks Unicode
+fn progress_bar(app: &App, ratio: f64, theme: &ThemeManager) -> ProgressBar {
+    let style = app.layout_config.settings.progress_bar.for_terminal(app.unicode);
+    ProgressBar::new(ratio, style).style(theme.progress_style())
+}
+
+/// Spinner for a panel title while its contents are loading
+fn loading_spinner(app: &App) -> String {
+    if app.screen_reader {
+        return format!("{} ", t!("state-loading"));
+    }
+    let elapsed = app.state.loading_since.map(|since| since.elapsed()).unwrap_or_default();
+    format!("{} ", progress::spinner(elapsed, app.unicode))
+}
+
+/// `text` as shown on screen: without symbols for screen readers
+fn shown(app: &App, text: String) -> String {
+    if app.screen_reader { text::plain(&text) } else { text }
+}
+
+/// Block around a panel, drawn with the widget's border if it has one
+fn panel_block<'a>(border: Option<BorderType>, border_style: Style, theme: &ThemeManager) -> Block<'a> {
+    let block = Block::default().style(theme.panel_style());
+    match border {
+        Some(border_type) => block.borders(Borders::ALL).border_type(border_type).border_style(border_style),
+        None => block,
+    }
+}
+
 // Utils
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -567,3 +1552,13 @@ fn format_duration(seconds: u64) -> String {
     let seconds = seconds % 60;
     format!("{:02}:{:02}", minutes, seconds)
 }
+
+fn format_listening_time(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = seconds % 3600 / 60;
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}